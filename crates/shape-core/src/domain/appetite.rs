@@ -0,0 +1,142 @@
+//! Appetite budgets for briefs in progress
+//!
+//! A brief's appetite (`meta.appetite`, e.g. `6-weeks` from the betting
+//! table) is how much time the work is worth, not an estimate of how long it
+//! takes. Once a brief is in progress, the time elapsed since it started is
+//! measured against that budget; running out of it is the cue for the
+//! circuit breaker: cut scope or stop, rather than extend.
+
+use chrono::{DateTime, Duration, Utc};
+
+use super::brief::{Brief, BriefStatus};
+
+/// Metadata key holding a brief's appetite
+pub const APPETITE_META_KEY: &str = "appetite";
+
+/// Parses an appetite such as `6-weeks`, `2w`, `10 days` or `3d`
+pub fn appetite_duration(s: &str) -> Option<Duration> {
+    let normalized = s.trim().to_lowercase().replace([' ', '-'], "");
+    let (number, days_per_unit) = if let Some(n) = ["weeks", "week", "w"]
+        .iter()
+        .find_map(|unit| normalized.strip_suffix(unit))
+    {
+        (n, 7)
+    } else if let Some(n) = ["days", "day", "d"]
+        .iter()
+        .find_map(|unit| normalized.strip_suffix(unit))
+    {
+        (n, 1)
+    } else {
+        return None;
+    };
+    let count: i64 = number.parse().ok().filter(|n| *n > 0)?;
+    Some(Duration::days(count * days_per_unit))
+}
+
+/// How much of an in-progress brief's appetite is used up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppetiteBudget {
+    /// The appetite as written, e.g. `6-weeks`
+    pub appetite: String,
+
+    /// When the brief moved to in progress
+    pub started_at: DateTime<Utc>,
+
+    pub budget: Duration,
+
+    pub elapsed: Duration,
+}
+
+impl AppetiteBudget {
+    /// The budget of an in-progress brief with a readable appetite, as of
+    /// `now`
+    pub fn of(brief: &Brief, now: DateTime<Utc>) -> Option<Self> {
+        if brief.status != BriefStatus::InProgress {
+            return None;
+        }
+        let appetite = brief.get_meta(APPETITE_META_KEY)?.as_str()?;
+        let started_at = brief.started_at?;
+        Some(Self {
+            appetite: appetite.to_string(),
+            started_at,
+            budget: appetite_duration(appetite)?,
+            elapsed: (now - started_at).max(Duration::zero()),
+        })
+    }
+
+    /// Time left, negative once the appetite is exceeded
+    pub fn remaining(&self) -> Duration {
+        self.budget - self.elapsed
+    }
+
+    /// Share of the budget used, in percent (above 100 when over)
+    pub fn percent_used(&self) -> u32 {
+        let used = self.elapsed.num_seconds() * 100 / self.budget.num_seconds().max(1);
+        used.clamp(0, u32::MAX as i64) as u32
+    }
+
+    /// Returns true once more time has passed than the appetite allows
+    pub fn is_exceeded(&self) -> bool {
+        self.elapsed > self.budget
+    }
+
+    /// Short description, e.g. `9 of 14 days into its 2-weeks appetite, 5 left`
+    /// or `3 days over its 2-weeks appetite`
+    pub fn describe(&self) -> String {
+        let days = |d: Duration| d.num_days();
+        if self.is_exceeded() {
+            let over = days(-self.remaining()).max(1);
+            format!(
+                "{} day{} over its {} appetite",
+                over,
+                if over == 1 { "" } else { "s" },
+                self.appetite
+            )
+        } else {
+            format!(
+                "{} of {} days into its {} appetite, {} left",
+                days(self.elapsed),
+                days(self.budget),
+                self.appetite,
+                days(self.remaining())
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_weeks_and_days() {
+        assert_eq!(appetite_duration("6-weeks"), Some(Duration::days(42)));
+        assert_eq!(appetite_duration("1 week"), Some(Duration::days(7)));
+        assert_eq!(appetite_duration("2w"), Some(Duration::days(14)));
+        assert_eq!(appetite_duration("10 Days"), Some(Duration::days(10)));
+        assert_eq!(appetite_duration("big batch"), None);
+        assert_eq!(appetite_duration("0w"), None);
+    }
+
+    #[test]
+    fn budget_counts_from_start_of_work() {
+        let mut brief = Brief::new("Pitch", "shapeup");
+        brief.set_meta(APPETITE_META_KEY, "2-weeks");
+        assert!(AppetiteBudget::of(&brief, Utc::now()).is_none());
+
+        brief.set_status(BriefStatus::InProgress);
+        let started = brief.started_at.unwrap();
+        let budget = AppetiteBudget::of(&brief, started + Duration::days(7)).unwrap();
+        assert_eq!(budget.percent_used(), 50);
+        assert_eq!(budget.remaining(), Duration::days(7));
+        assert!(!budget.is_exceeded());
+        assert_eq!(
+            budget.describe(),
+            "7 of 14 days into its 2-weeks appetite, 7 left"
+        );
+
+        let budget = AppetiteBudget::of(&brief, started + Duration::days(17)).unwrap();
+        assert!(budget.is_exceeded());
+        assert_eq!(budget.describe(), "3 days over its 2-weeks appetite");
+    }
+}
@@ -0,0 +1,209 @@
+//! Betting table domain model
+//!
+//! ShapeUp's betting table: before a cycle starts, stakeholders bet on the
+//! briefs they want built and the appetite they would give each. Closing the
+//! table tallies the bets into decisions: briefs with enough backers are bet
+//! on at their most-backed appetite, the rest are passed.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::BriefId;
+
+/// Appetites a bet can carry, smallest first
+pub const APPETITES: &[&str] = &["1-week", "2-weeks", "6-weeks"];
+
+/// Normalizes an appetite such as `2w`, `2 weeks` or `2-weeks` to its
+/// canonical form
+pub fn parse_appetite(s: &str) -> Option<&'static str> {
+    let normalized = s.trim().to_lowercase().replace([' ', '-'], "");
+    let weeks = normalized
+        .strip_suffix("weeks")
+        .or_else(|| normalized.strip_suffix("week"))
+        .or_else(|| normalized.strip_suffix('w'))
+        .unwrap_or(&normalized);
+    match weeks {
+        "1" => Some("1-week"),
+        "2" => Some("2-weeks"),
+        "6" => Some("6-weeks"),
+        _ => None,
+    }
+}
+
+/// One stakeholder's bet on a brief
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bet {
+    pub brief: BriefId,
+
+    /// Who placed the bet
+    pub by: String,
+
+    /// Appetite the bettor would give the brief
+    pub appetite: String,
+
+    pub at: DateTime<Utc>,
+}
+
+/// What closing the table decided for a brief
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// Picked for the cycle
+    Bet,
+    /// Not enough backers
+    Passed,
+}
+
+/// The decision for one brief when the table closed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    pub brief: BriefId,
+
+    pub outcome: Outcome,
+
+    /// Most-backed appetite (the smaller one on a tie)
+    pub appetite: String,
+
+    /// Who bet on the brief
+    pub backers: Vec<String>,
+}
+
+/// A betting table for one cycle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BettingTable {
+    /// Cycle the table decides, e.g. `c-1`
+    pub cycle: String,
+
+    pub opened_at: DateTime<Utc>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<DateTime<Utc>>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bets: Vec<Bet>,
+
+    /// Filled in when the table closes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub decisions: Vec<Decision>,
+}
+
+impl BettingTable {
+    /// Opens a table for a cycle
+    pub fn open(cycle: impl Into<String>) -> Self {
+        Self {
+            cycle: cycle.into(),
+            opened_at: Utc::now(),
+            closed_at: None,
+            bets: Vec::new(),
+            decisions: Vec::new(),
+        }
+    }
+
+    /// Whether bets are still accepted
+    pub fn is_open(&self) -> bool {
+        self.closed_at.is_none()
+    }
+
+    /// Places a bet, replacing the bettor's earlier bet on the same brief
+    pub fn cast(&mut self, bet: Bet) {
+        self.bets
+            .retain(|b| !(b.brief == bet.brief && b.by == bet.by));
+        self.bets.push(bet);
+    }
+
+    /// Decisions for every brief bet on: those with at least `min_backers`
+    /// backers are bet on. Ordered with bets first, then by backers, then ID.
+    pub fn tally(&self, min_backers: usize) -> Vec<Decision> {
+        let mut by_brief: BTreeMap<&BriefId, Vec<&Bet>> = BTreeMap::new();
+        for bet in &self.bets {
+            by_brief.entry(&bet.brief).or_default().push(bet);
+        }
+
+        let mut decisions: Vec<Decision> = by_brief
+            .into_iter()
+            .map(|(brief, bets)| {
+                let count = |appetite: &str| bets.iter().filter(|b| b.appetite == appetite).count();
+                // max_by_key keeps the last maximum, so walk largest first
+                let appetite = APPETITES
+                    .iter()
+                    .rev()
+                    .max_by_key(|a| count(a))
+                    .copied()
+                    .unwrap_or(APPETITES[0]);
+                let mut backers: Vec<String> = bets.iter().map(|b| b.by.clone()).collect();
+                backers.sort();
+                Decision {
+                    brief: brief.clone(),
+                    outcome: if backers.len() >= min_backers {
+                        Outcome::Bet
+                    } else {
+                        Outcome::Passed
+                    },
+                    appetite: appetite.to_string(),
+                    backers,
+                }
+            })
+            .collect();
+
+        decisions.sort_by(|a, b| {
+            (a.outcome != Outcome::Bet)
+                .cmp(&(b.outcome != Outcome::Bet))
+                .then(b.backers.len().cmp(&a.backers.len()))
+                .then(a.brief.cmp(&b.brief))
+        });
+        decisions
+    }
+
+    /// Records the decisions and closes the table
+    pub fn close(&mut self, min_backers: usize) -> &[Decision] {
+        self.decisions = self.tally(min_backers);
+        self.closed_at = Some(Utc::now());
+        &self.decisions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bet(brief: &BriefId, by: &str, appetite: &str) -> Bet {
+        Bet {
+            brief: brief.clone(),
+            by: by.to_string(),
+            appetite: parse_appetite(appetite).unwrap().to_string(),
+            at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn parses_appetites() {
+        assert_eq!(parse_appetite("2w"), Some("2-weeks"));
+        assert_eq!(parse_appetite("6 Weeks"), Some("6-weeks"));
+        assert_eq!(parse_appetite("1-week"), Some("1-week"));
+        assert_eq!(parse_appetite("3w"), None);
+    }
+
+    #[test]
+    fn tally_picks_backed_briefs_and_smaller_appetite_on_ties() {
+        let search: BriefId = "b-1111111".parse().unwrap();
+        let billing: BriefId = "b-2222222".parse().unwrap();
+        let mut table = BettingTable::open("c-1");
+
+        table.cast(bet(&search, "alice", "6w"));
+        table.cast(bet(&search, "alice", "2w")); // replaces alice's first bet
+        table.cast(bet(&search, "bob", "6w"));
+        table.cast(bet(&billing, "carol", "1w"));
+        assert_eq!(table.bets.len(), 3);
+
+        let decisions = table.close(2).to_vec();
+        assert!(!table.is_open());
+        assert_eq!(decisions[0].brief, search);
+        assert_eq!(decisions[0].outcome, Outcome::Bet);
+        assert_eq!(decisions[0].appetite, "2-weeks");
+        assert_eq!(decisions[0].backers, ["alice", "bob"]);
+        assert_eq!(decisions[1].brief, billing);
+        assert_eq!(decisions[1].outcome, Outcome::Passed);
+    }
+}
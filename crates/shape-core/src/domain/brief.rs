@@ -0,0 +1,817 @@
+//! Brief domain model
+//!
+//! Briefs are the high-level documents (pitches, RFCs, PRDs, etc.)
+//! that organize related tasks. They are stored as markdown files
+//! with YAML frontmatter.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::external::ExternalIds;
+use super::freeze::FreezeInfo;
+use super::id::{BriefId, IdScheme};
+
+/// Status of a brief
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BriefStatus {
+    /// Initial state - not yet started
+    #[default]
+    Proposed,
+
+    /// In the betting process (for ShapeUp)
+    Betting,
+
+    /// Actively being worked on
+    InProgress,
+
+    /// Successfully completed
+    Shipped,
+
+    /// No longer being pursued
+    Archived,
+}
+
+impl BriefStatus {
+    /// Returns true if this status represents completion
+    pub fn is_complete(&self) -> bool {
+        matches!(self, BriefStatus::Shipped | BriefStatus::Archived)
+    }
+
+    /// Returns true if this brief is actively being worked on
+    pub fn is_active(&self) -> bool {
+        matches!(self, BriefStatus::InProgress)
+    }
+
+    /// Returns all valid status values
+    pub fn all() -> &'static [BriefStatus] {
+        &[
+            BriefStatus::Proposed,
+            BriefStatus::Betting,
+            BriefStatus::InProgress,
+            BriefStatus::Shipped,
+            BriefStatus::Archived,
+        ]
+    }
+}
+
+impl std::fmt::Display for BriefStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BriefStatus::Proposed => write!(f, "proposed"),
+            BriefStatus::Betting => write!(f, "betting"),
+            BriefStatus::InProgress => write!(f, "in_progress"),
+            BriefStatus::Shipped => write!(f, "shipped"),
+            BriefStatus::Archived => write!(f, "archived"),
+        }
+    }
+}
+
+impl std::str::FromStr for BriefStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "proposed" => Ok(BriefStatus::Proposed),
+            "betting" => Ok(BriefStatus::Betting),
+            "in_progress" | "in-progress" | "inprogress" => Ok(BriefStatus::InProgress),
+            "shipped" | "done" | "complete" | "completed" => Ok(BriefStatus::Shipped),
+            "archived" | "cancelled" | "canceled" => Ok(BriefStatus::Archived),
+            _ => Err(format!("Unknown brief status: {}", s)),
+        }
+    }
+}
+
+/// Metadata for a brief - extensible key-value pairs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BriefMeta(BTreeMap<String, serde_json::Value>);
+
+impl BriefMeta {
+    /// Creates empty metadata
+    pub fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    /// Gets a value by key
+    pub fn get(&self, key: &str) -> Option<&serde_json::Value> {
+        self.0.get(key)
+    }
+
+    /// Sets a value
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) {
+        self.0.insert(key.into(), value.into());
+    }
+
+    /// Removes a value
+    pub fn remove(&mut self, key: &str) -> Option<serde_json::Value> {
+        self.0.remove(key)
+    }
+
+    /// Returns true if empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over all key-value pairs
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &serde_json::Value)> {
+        self.0.iter()
+    }
+
+    /// Returns the inner map
+    pub fn inner(&self) -> &BTreeMap<String, serde_json::Value> {
+        &self.0
+    }
+}
+
+/// Types of brief history events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BriefEventType {
+    /// Won a bet at the betting table
+    Bet,
+    /// Was bet on but not picked when the table closed
+    Passed,
+    /// Used up enough of its appetite to warn about
+    AppetiteWarning,
+}
+
+impl std::fmt::Display for BriefEventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BriefEventType::Bet => write!(f, "bet"),
+            BriefEventType::Passed => write!(f, "passed"),
+            BriefEventType::AppetiteWarning => write!(f, "appetite_warning"),
+        }
+    }
+}
+
+/// An event in a brief's history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BriefEvent {
+    /// When the event occurred
+    pub at: DateTime<Utc>,
+    /// Type of event
+    pub event: BriefEventType,
+    /// Who caused the event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by: Option<String>,
+    /// Additional event data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+/// A brief document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Brief {
+    /// Unique identifier
+    pub id: BriefId,
+
+    /// Human-readable title
+    pub title: String,
+
+    /// Brief type (e.g., "minimal", "shapeup", "rfc")
+    #[serde(rename = "type")]
+    pub brief_type: String,
+
+    /// Current status
+    pub status: BriefStatus,
+
+    /// When the brief was created
+    pub created_at: DateTime<Utc>,
+
+    /// When the brief was last updated
+    pub updated_at: DateTime<Utc>,
+
+    /// When the brief first moved to in progress
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+
+    /// Person accountable for the brief
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+
+    /// Additional owners sharing responsibility
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub co_owners: Vec<String>,
+
+    /// IDs of this brief in external tools (e.g. `github: 456`)
+    #[serde(default, skip_serializing_if = "ExternalIds::is_empty")]
+    pub external_ids: ExternalIds,
+
+    /// Freeze preventing changes to the brief and its tasks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frozen: Option<FreezeInfo>,
+
+    /// Epic (umbrella brief) this brief belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<BriefId>,
+
+    /// Decisions about the brief, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<BriefEvent>,
+
+    /// Markdown body content (excluding frontmatter)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub body: String,
+
+    /// Short summary of the body (auto-generated or plugin-provided)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// Hash of the body the summary was generated from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary_hash: Option<String>,
+
+    /// Extensible metadata from frontmatter
+    #[serde(default, skip_serializing_if = "BriefMeta::is_empty")]
+    pub meta: BriefMeta,
+}
+
+/// Maximum number of sentences in a generated summary
+const SUMMARY_SENTENCES: usize = 3;
+
+/// Maximum length of a generated summary in characters
+const SUMMARY_MAX_CHARS: usize = 400;
+
+/// Reading speed used for reading-time estimates
+const WORDS_PER_MINUTE: usize = 200;
+
+impl Brief {
+    /// Creates a new brief with the given title and type
+    pub fn new(title: impl Into<String>, brief_type: impl Into<String>) -> Self {
+        Self::with_scheme(title, brief_type, &IdScheme::default())
+    }
+
+    /// Creates a new brief whose ID follows the given scheme
+    pub fn with_scheme(
+        title: impl Into<String>,
+        brief_type: impl Into<String>,
+        scheme: &IdScheme,
+    ) -> Self {
+        let title = title.into();
+        let brief_type = brief_type.into();
+        let now = Utc::now();
+        let id = scheme.brief_id(&title, &brief_type, now);
+
+        Self {
+            id,
+            title,
+            brief_type,
+            status: BriefStatus::Proposed,
+            created_at: now,
+            updated_at: now,
+            started_at: None,
+            owner: None,
+            co_owners: Vec::new(),
+            external_ids: ExternalIds::new(),
+            frozen: None,
+            parent: None,
+            history: Vec::new(),
+            body: String::new(),
+            summary: None,
+            summary_hash: None,
+            meta: BriefMeta::new(),
+        }
+    }
+
+    /// Creates a new brief with a specific ID (for deserialization)
+    pub fn with_id(id: BriefId, title: impl Into<String>, brief_type: impl Into<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id,
+            title: title.into(),
+            brief_type: brief_type.into(),
+            status: BriefStatus::Proposed,
+            created_at: now,
+            updated_at: now,
+            started_at: None,
+            owner: None,
+            co_owners: Vec::new(),
+            external_ids: ExternalIds::new(),
+            frozen: None,
+            parent: None,
+            history: Vec::new(),
+            body: String::new(),
+            summary: None,
+            summary_hash: None,
+            meta: BriefMeta::new(),
+        }
+    }
+
+    /// Sets the owner and co-owners (`None` clears ownership)
+    pub fn set_owner(&mut self, owner: Option<String>, co_owners: Vec<String>) {
+        self.owner = owner;
+        self.co_owners = co_owners;
+        self.updated_at = Utc::now();
+    }
+
+    /// Records this brief's ID in an external system
+    pub fn set_external_id(&mut self, system: impl Into<String>, key: impl Into<String>) {
+        self.external_ids.insert(system.into(), key.into());
+        self.updated_at = Utc::now();
+    }
+
+    /// Forgets this brief's ID in an external system
+    pub fn remove_external_id(&mut self, system: &str) -> bool {
+        let removed = self.external_ids.remove(system).is_some();
+        if removed {
+            self.updated_at = Utc::now();
+        }
+        removed
+    }
+
+    /// Freezes the brief (and so its tasks)
+    pub fn freeze(&mut self, reason: impl Into<String>, by: impl Into<String>) {
+        let info = FreezeInfo::new(reason, by);
+        self.updated_at = info.at;
+        self.frozen = Some(info);
+    }
+
+    /// Lifts a freeze
+    pub fn unfreeze(&mut self) -> bool {
+        let was_frozen = self.frozen.take().is_some();
+        if was_frozen {
+            self.updated_at = Utc::now();
+        }
+        was_frozen
+    }
+
+    /// Appends an event to the brief's history
+    pub fn record(
+        &mut self,
+        event: BriefEventType,
+        by: Option<String>,
+        data: Option<serde_json::Value>,
+    ) {
+        let at = Utc::now();
+        self.history.push(BriefEvent {
+            at,
+            event,
+            by,
+            data,
+        });
+        self.updated_at = at;
+    }
+
+    /// Cycle of the latest bet the brief won at the betting table
+    pub fn cycle(&self) -> Option<&str> {
+        self.history
+            .iter()
+            .rev()
+            .find(|e| e.event == BriefEventType::Bet)
+            .and_then(|e| e.data.as_ref()?.get("cycle")?.as_str())
+    }
+
+    /// Places the brief under an epic (`None` makes it top-level)
+    pub fn set_parent(&mut self, parent: Option<BriefId>) {
+        if self.parent != parent {
+            self.parent = parent;
+            self.updated_at = Utc::now();
+        }
+    }
+
+    /// Returns true if `who` is the owner or a co-owner
+    pub fn is_owned_by(&self, who: &str) -> bool {
+        self.owner.as_deref() == Some(who) || self.co_owners.iter().any(|c| c == who)
+    }
+
+    /// Transitions to a new status. The first move to in progress starts
+    /// the clock on the brief's appetite.
+    pub fn set_status(&mut self, status: BriefStatus) {
+        if self.status != status {
+            self.status = status;
+            self.updated_at = Utc::now();
+            if status == BriefStatus::InProgress && self.started_at.is_none() {
+                self.started_at = Some(self.updated_at);
+            }
+        }
+    }
+
+    /// Sets the body content and refreshes the summary
+    pub fn set_body(&mut self, body: impl Into<String>) {
+        self.body = body.into();
+        self.updated_at = Utc::now();
+        self.refresh_summary();
+    }
+
+    /// Returns a short hash of the body, used to detect stale summaries
+    pub fn body_hash(&self) -> String {
+        blake3::hash(self.body.as_bytes()).to_hex()[..16].to_string()
+    }
+
+    /// Returns true if the stored summary was generated from the current body
+    pub fn summary_is_current(&self) -> bool {
+        self.summary_hash.as_deref() == Some(self.body_hash().as_str())
+    }
+
+    /// Regenerates the summary if the body changed since it was last generated.
+    ///
+    /// A summary whose hash matches the body is kept as-is, so summaries
+    /// written by plugins survive until the body is edited.
+    /// Returns true if the summary was updated.
+    pub fn refresh_summary(&mut self) -> bool {
+        if self.summary_is_current() {
+            return false;
+        }
+        self.summary = summarize_body(&self.body);
+        self.summary_hash = Some(self.body_hash());
+        true
+    }
+
+    /// Sets a summary provided by a plugin or user for the current body
+    pub fn set_summary(&mut self, summary: impl Into<String>) {
+        self.summary = Some(summary.into());
+        self.summary_hash = Some(self.body_hash());
+        self.updated_at = Utc::now();
+    }
+
+    /// Returns the stored summary if current, otherwise a freshly generated one
+    pub fn current_summary(&self) -> Option<String> {
+        if self.summary_is_current() {
+            self.summary.clone()
+        } else {
+            summarize_body(&self.body)
+        }
+    }
+
+    /// Estimated reading time of the body in minutes (0 for an empty body)
+    pub fn reading_minutes(&self) -> usize {
+        let words = self.body.split_whitespace().count();
+        words.div_ceil(WORDS_PER_MINUTE)
+    }
+
+    /// Sets a metadata value
+    pub fn set_meta(&mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) {
+        self.meta.set(key, value);
+        self.updated_at = Utc::now();
+    }
+
+    /// Gets a metadata value
+    pub fn get_meta(&self, key: &str) -> Option<&serde_json::Value> {
+        self.meta.get(key)
+    }
+
+    /// Removes a metadata value
+    pub fn remove_meta(&mut self, key: &str) -> Option<serde_json::Value> {
+        let result = self.meta.remove(key);
+        if result.is_some() {
+            self.updated_at = Utc::now();
+        }
+        result
+    }
+
+    /// Returns true if this brief is complete (shipped or archived)
+    pub fn is_complete(&self) -> bool {
+        self.status.is_complete()
+    }
+
+    /// Returns true if this brief is actively being worked on
+    pub fn is_active(&self) -> bool {
+        self.status.is_active()
+    }
+}
+
+/// Represents the frontmatter section of a brief file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BriefFrontmatter {
+    pub id: BriefId,
+    pub title: String,
+    #[serde(rename = "type")]
+    pub brief_type: String,
+    pub status: BriefStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub co_owners: Vec<String>,
+    #[serde(default, skip_serializing_if = "ExternalIds::is_empty")]
+    pub external_ids: ExternalIds,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frozen: Option<FreezeInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<BriefId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<BriefEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary_hash: Option<String>,
+    #[serde(flatten)]
+    pub meta: BTreeMap<String, serde_json::Value>,
+}
+
+impl From<&Brief> for BriefFrontmatter {
+    fn from(brief: &Brief) -> Self {
+        Self {
+            id: brief.id.clone(),
+            title: brief.title.clone(),
+            brief_type: brief.brief_type.clone(),
+            status: brief.status,
+            created_at: brief.created_at,
+            updated_at: brief.updated_at,
+            started_at: brief.started_at,
+            owner: brief.owner.clone(),
+            co_owners: brief.co_owners.clone(),
+            external_ids: brief.external_ids.clone(),
+            frozen: brief.frozen.clone(),
+            parent: brief.parent.clone(),
+            history: brief.history.clone(),
+            summary: brief.summary.clone(),
+            summary_hash: brief.summary_hash.clone(),
+            meta: brief.meta.inner().clone(),
+        }
+    }
+}
+
+impl BriefFrontmatter {
+    /// Converts to a Brief with the given body
+    pub fn into_brief(self, body: String) -> Brief {
+        Brief {
+            id: self.id,
+            title: self.title,
+            brief_type: self.brief_type,
+            status: self.status,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            started_at: self.started_at,
+            owner: self.owner,
+            co_owners: self.co_owners,
+            external_ids: self.external_ids,
+            frozen: self.frozen,
+            parent: self.parent,
+            history: self.history,
+            body,
+            summary: self.summary,
+            summary_hash: self.summary_hash,
+            meta: BriefMeta(self.meta),
+        }
+    }
+}
+
+/// Generates a 2-3 sentence summary from a markdown body.
+///
+/// Headings, HTML comments, code blocks and tables are skipped; the first
+/// sentences of the remaining prose are used. Returns None if the body has
+/// no prose.
+pub fn summarize_body(body: &str) -> Option<String> {
+    let mut prose = Vec::new();
+    let mut in_code = false;
+    let mut in_comment = false;
+
+    for line in body.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        if in_comment {
+            if trimmed.contains("-->") {
+                in_comment = false;
+            }
+            continue;
+        }
+        if trimmed.starts_with("<!--") {
+            in_comment = !trimmed.contains("-->");
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('|') {
+            continue;
+        }
+
+        let text = trimmed
+            .trim_start_matches(['-', '*', '+', '>'])
+            .trim_start();
+        if !text.is_empty() {
+            prose.push(text);
+        }
+    }
+
+    let text = prose.join(" ");
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut summary = String::new();
+    let mut sentences = 0;
+    let mut chars = text.char_indices().peekable();
+    let mut start = 0;
+
+    while let Some((i, c)) = chars.next() {
+        let at_end = chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+        if matches!(c, '.' | '!' | '?') && at_end {
+            let end = i + c.len_utf8();
+            summary.push_str(text[start..end].trim());
+            summary.push(' ');
+            start = end;
+            sentences += 1;
+            if sentences == SUMMARY_SENTENCES {
+                break;
+            }
+        }
+    }
+    if sentences == 0 {
+        summary = text;
+    }
+
+    let summary = summary.trim();
+    if summary.chars().count() > SUMMARY_MAX_CHARS {
+        let truncated: String = summary.chars().take(SUMMARY_MAX_CHARS).collect();
+        Some(format!("{}...", truncated.trim_end()))
+    } else {
+        Some(summary.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_brief_has_proposed_status() {
+        let brief = Brief::new("Test Pitch", "minimal");
+        assert_eq!(brief.status, BriefStatus::Proposed);
+    }
+
+    #[test]
+    fn brief_id_is_generated_from_title() {
+        let brief = Brief::new("My Feature", "minimal");
+        assert!(brief.id.to_string().starts_with("b-"));
+    }
+
+    #[test]
+    fn brief_status_transitions() {
+        let mut brief = Brief::new("Test", "minimal");
+
+        brief.set_status(BriefStatus::InProgress);
+        assert!(brief.is_active());
+
+        brief.set_status(BriefStatus::Shipped);
+        assert!(brief.is_complete());
+    }
+
+    #[test]
+    fn brief_meta_operations() {
+        let mut brief = Brief::new("Test", "minimal");
+
+        brief.set_meta("appetite", "6 weeks");
+        brief.set_meta("priority", 1);
+
+        assert_eq!(
+            brief.get_meta("appetite"),
+            Some(&serde_json::json!("6 weeks"))
+        );
+        assert_eq!(brief.get_meta("priority"), Some(&serde_json::json!(1)));
+
+        brief.remove_meta("appetite");
+        assert!(brief.get_meta("appetite").is_none());
+    }
+
+    #[test]
+    fn brief_ownership() {
+        let mut brief = Brief::new("Test", "minimal");
+        assert!(!brief.is_owned_by("alice"));
+
+        brief.set_owner(Some("alice".to_string()), vec!["bob".to_string()]);
+        assert!(brief.is_owned_by("alice"));
+        assert!(brief.is_owned_by("bob"));
+        assert!(!brief.is_owned_by("carol"));
+
+        let frontmatter = BriefFrontmatter::from(&brief);
+        let yaml = serde_yaml::to_string(&frontmatter).unwrap();
+        assert!(yaml.contains("owner: alice"));
+        let parsed: BriefFrontmatter = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed.into_brief(String::new()).co_owners, vec!["bob"]);
+    }
+
+    #[test]
+    fn summarize_body_skips_structure() {
+        let body = "# Title\n\n<!-- hint\nmore -->\n\n## Problem\n\nLogins are slow. Users give up! Sessions time out? The fourth sentence is dropped.\n\n```\ncode.\n```";
+        assert_eq!(
+            summarize_body(body).unwrap(),
+            "Logins are slow. Users give up! Sessions time out?"
+        );
+
+        assert_eq!(summarize_body("# Only\n\n## Headings\n"), None);
+        assert_eq!(
+            summarize_body("- No trailing period").unwrap(),
+            "No trailing period"
+        );
+    }
+
+    #[test]
+    fn summary_refreshes_when_body_changes() {
+        let mut brief = Brief::new("Test", "minimal");
+        brief.set_body("First version.");
+        assert_eq!(brief.summary.as_deref(), Some("First version."));
+        assert!(brief.summary_is_current());
+
+        // Plugin-provided summaries are kept while the body is unchanged
+        brief.set_summary("Custom summary.");
+        assert!(!brief.refresh_summary());
+        assert_eq!(brief.current_summary().as_deref(), Some("Custom summary."));
+
+        // Editing the body outside set_body makes the summary stale
+        brief.body = "Second version.".to_string();
+        assert!(!brief.summary_is_current());
+        assert_eq!(brief.current_summary().as_deref(), Some("Second version."));
+        assert!(brief.refresh_summary());
+        assert_eq!(brief.summary.as_deref(), Some("Second version."));
+    }
+
+    #[test]
+    fn reading_minutes_rounds_up() {
+        let mut brief = Brief::new("Test", "minimal");
+        assert_eq!(brief.reading_minutes(), 0);
+        brief.body = "word ".repeat(201);
+        assert_eq!(brief.reading_minutes(), 2);
+    }
+
+    #[test]
+    fn brief_body() {
+        let mut brief = Brief::new("Test", "minimal");
+        brief.set_body("# Problem\n\nThis is the problem statement.");
+
+        assert_eq!(brief.body, "# Problem\n\nThis is the problem statement.");
+    }
+
+    #[test]
+    fn brief_status_from_string() {
+        assert_eq!(
+            "proposed".parse::<BriefStatus>().unwrap(),
+            BriefStatus::Proposed
+        );
+        assert_eq!(
+            "in_progress".parse::<BriefStatus>().unwrap(),
+            BriefStatus::InProgress
+        );
+        assert_eq!(
+            "in-progress".parse::<BriefStatus>().unwrap(),
+            BriefStatus::InProgress
+        );
+        assert_eq!(
+            "shipped".parse::<BriefStatus>().unwrap(),
+            BriefStatus::Shipped
+        );
+        assert_eq!("done".parse::<BriefStatus>().unwrap(), BriefStatus::Shipped);
+        assert_eq!(
+            "archived".parse::<BriefStatus>().unwrap(),
+            BriefStatus::Archived
+        );
+    }
+
+    #[test]
+    fn brief_status_display() {
+        assert_eq!(BriefStatus::Proposed.to_string(), "proposed");
+        assert_eq!(BriefStatus::InProgress.to_string(), "in_progress");
+    }
+
+    #[test]
+    fn serde_roundtrip() {
+        let mut brief = Brief::new("Test", "shapeup");
+        brief.set_body("Some content");
+        brief.set_meta("appetite", "2 weeks");
+
+        let json = serde_json::to_string(&brief).unwrap();
+        let parsed: Brief = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(brief.id, parsed.id);
+        assert_eq!(brief.title, parsed.title);
+        assert_eq!(brief.brief_type, parsed.brief_type);
+        assert_eq!(brief.body, parsed.body);
+    }
+
+    #[test]
+    fn frontmatter_conversion() {
+        let mut brief = Brief::new("Test", "minimal");
+        brief.set_meta("custom", "value");
+        brief.set_parent(Some(Brief::new("Epic", "minimal").id));
+
+        let frontmatter = BriefFrontmatter::from(&brief);
+        let restored = frontmatter.into_brief(brief.body.clone());
+
+        assert_eq!(brief.id, restored.id);
+        assert_eq!(brief.title, restored.title);
+        assert_eq!(brief.get_meta("custom"), restored.get_meta("custom"));
+        assert_eq!(brief.parent, restored.parent);
+        assert!(restored.get_meta("parent").is_none());
+    }
+
+    #[test]
+    fn updated_at_changes_on_modifications() {
+        let mut brief = Brief::new("Test", "minimal");
+        let created = brief.updated_at;
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        brief.set_status(BriefStatus::InProgress);
+
+        assert!(brief.updated_at > created);
+    }
+}
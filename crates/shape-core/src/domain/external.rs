@@ -0,0 +1,56 @@
+//! External IDs
+//!
+//! Briefs and tasks remember the IDs they have in other tools
+//! (`jira: ABC-123`, `github: 456`) so they can be addressed as
+//! `jira:ABC-123` anywhere a Shape ID is accepted.
+
+use std::collections::BTreeMap;
+
+/// System name (`jira`, `github`) → ID in that system
+pub type ExternalIds = BTreeMap<String, String>;
+
+/// Splits an external reference like `jira:ABC-123` into system and key
+///
+/// System names are lowercase letters, digits, `-` and `_`.
+pub fn parse_external_ref(s: &str) -> Option<(&str, &str)> {
+    let (system, key) = s.split_once(':')?;
+    let valid_system = !system.is_empty()
+        && system
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    let key = key.trim();
+    (valid_system && !key.is_empty()).then_some((system, key))
+}
+
+/// Formats external IDs as `github:456, jira:ABC-123`
+pub fn format_external_ids(ids: &ExternalIds) -> String {
+    ids.iter()
+        .map(|(system, key)| format!("{}:{}", system, key))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_external_refs() {
+        assert_eq!(
+            parse_external_ref("jira:ABC-123"),
+            Some(("jira", "ABC-123"))
+        );
+        assert_eq!(parse_external_ref("github:456"), Some(("github", "456")));
+        assert_eq!(parse_external_ref("Jira:ABC-123"), None);
+        assert_eq!(parse_external_ref("jira:"), None);
+        assert_eq!(parse_external_ref("b-1234567"), None);
+    }
+
+    #[test]
+    fn formats_sorted_by_system() {
+        let mut ids = ExternalIds::new();
+        ids.insert("jira".to_string(), "ABC-123".to_string());
+        ids.insert("github".to_string(), "456".to_string());
+        assert_eq!(format_external_ids(&ids), "github:456, jira:ABC-123");
+    }
+}
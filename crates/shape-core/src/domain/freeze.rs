@@ -0,0 +1,30 @@
+//! Frozen entities
+//!
+//! A brief or task can be frozen (e.g. "security sign-off pending") so that
+//! commands refuse to change it until it is unfrozen. Freezing a brief
+//! freezes all of its tasks.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Why and by whom a brief or task was frozen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FreezeInfo {
+    /// Reason for the freeze
+    pub reason: String,
+    /// Who froze it
+    pub by: String,
+    /// When it was frozen
+    pub at: DateTime<Utc>,
+}
+
+impl FreezeInfo {
+    /// Creates a freeze stamped with the current time
+    pub fn new(reason: impl Into<String>, by: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+            by: by.into(),
+            at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! Completion gates
+//!
+//! A gate is a check on data already recorded on a task, such as a linked PR
+//! or an approving review. `[gates]` in the config attaches gates to every
+//! task or to tasks with a label, and `task done` refuses to complete a task
+//! while any of its gates is unmet.
+//!
+//! A brief can also declare a definition of done in its frontmatter, which
+//! applies to all of its tasks. Entries that aren't gate names are checklist
+//! items, ticked off per task.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::brief::Brief;
+use super::task::{LinkType, Task};
+
+/// Brief metadata key holding the definition of done for its tasks
+pub const DOD_META_KEY: &str = "definition_of_done";
+
+/// Task metadata key opting out of its brief's definition of done: `true`
+/// for all of it, or a list of the entries to skip
+pub const SKIP_DOD_META_KEY: &str = "skip_dod";
+
+/// Task metadata key listing the checklist items ticked off
+pub const CHECKED_META_KEY: &str = "checked";
+
+/// A check a task must pass before it can be completed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Gate {
+    /// A PR link
+    PrLinked,
+    /// A commit link
+    CommitLinked,
+    /// A file or URL link that looks like a test (`test` or `spec` in it)
+    TestsLinked,
+    /// An approving review and no rejection
+    ReviewApproved,
+    /// At least one note
+    Note,
+    /// A value under this metadata key
+    Meta(String),
+    /// A checklist item ticked off with `task check`
+    Checked(String),
+}
+
+impl Gate {
+    /// Returns true if the task passes the gate
+    pub fn check(&self, task: &Task) -> bool {
+        let has_link = |link_type: LinkType| task.links.iter().any(|l| l.link_type == link_type);
+        match self {
+            Gate::PrLinked => has_link(LinkType::Pr),
+            Gate::CommitLinked => has_link(LinkType::Commit),
+            Gate::TestsLinked => task.links.iter().any(|l| {
+                let reference = l.reference.to_lowercase();
+                matches!(l.link_type, LinkType::File | LinkType::Url)
+                    && (reference.contains("test") || reference.contains("spec"))
+            }),
+            Gate::ReviewApproved => task.is_approved(),
+            Gate::Note => !task.notes.is_empty(),
+            Gate::Meta(key) => task.get_meta(key).is_some_and(|v| !v.is_null()),
+            Gate::Checked(item) => checked_items(task).contains(&item.as_str()),
+        }
+    }
+
+    /// Command that satisfies the gate, for `id`
+    pub fn hint(&self, id: &str) -> String {
+        match self {
+            Gate::PrLinked => format!("shape link {} --pr <NUMBER>", id),
+            Gate::CommitLinked => format!("shape link {} --commit <SHA>", id),
+            Gate::TestsLinked => format!("shape link {} --file <TEST_FILE>", id),
+            Gate::ReviewApproved => format!("shape review request {} --from <NAME>", id),
+            Gate::Note => format!("shape note {} <TEXT>", id),
+            Gate::Meta(key) => format!("shape task meta {} {} <VALUE>", id, key),
+            Gate::Checked(item) => format!("shape task check {} {:?}", id, item),
+        }
+    }
+}
+
+impl fmt::Display for Gate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gate::PrLinked => write!(f, "pr_linked"),
+            Gate::CommitLinked => write!(f, "commit_linked"),
+            Gate::TestsLinked => write!(f, "tests_linked"),
+            Gate::ReviewApproved => write!(f, "review_approved"),
+            Gate::Note => write!(f, "note"),
+            Gate::Meta(key) => write!(f, "meta:{}", key),
+            Gate::Checked(item) => write!(f, "check:{}", item),
+        }
+    }
+}
+
+impl FromStr for Gate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "pr_linked" => Ok(Gate::PrLinked),
+            "commit_linked" => Ok(Gate::CommitLinked),
+            "tests_linked" => Ok(Gate::TestsLinked),
+            "review_approved" => Ok(Gate::ReviewApproved),
+            "note" => Ok(Gate::Note),
+            other => {
+                if let Some(key) = other.strip_prefix("meta:").filter(|k| !k.is_empty()) {
+                    return Ok(Gate::Meta(key.to_string()));
+                }
+                if let Some(item) = other.strip_prefix("check:").map(str::trim) {
+                    if !item.is_empty() {
+                        return Ok(Gate::Checked(item.to_string()));
+                    }
+                }
+                Err(format!(
+                    "unknown gate '{}' (expected pr_linked, commit_linked, tests_linked, \
+                     review_approved, note, meta:<key> or check:<item>)",
+                    other
+                ))
+            }
+        }
+    }
+}
+
+/// Checklist items ticked off on the task
+pub fn checked_items(task: &Task) -> Vec<&str> {
+    task.get_meta(CHECKED_META_KEY)
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|i| i.as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// The brief's definition of done as it applies to `task`, without the
+/// entries the task opts out of
+///
+/// Entries that name a gate are that gate; anything else is a checklist item.
+pub fn definition_of_done(brief: &Brief, task: &Task) -> Vec<Gate> {
+    let skip = task.get_meta(SKIP_DOD_META_KEY);
+    if skip.and_then(|v| v.as_bool()) == Some(true) {
+        return Vec::new();
+    }
+    let skipped: Vec<&str> = skip
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|i| i.as_str()).collect())
+        .unwrap_or_default();
+
+    brief
+        .get_meta(DOD_META_KEY)
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.as_str())
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty() && !skipped.contains(entry))
+        .map(|entry| {
+            entry
+                .parse()
+                .unwrap_or_else(|_| Gate::Checked(entry.to_string()))
+        })
+        .collect()
+}
+
+impl Serialize for Gate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Gate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{BriefId, TaskId};
+    use chrono::Utc;
+
+    #[test]
+    fn gates_check_task_data() {
+        let brief = BriefId::new("Test", Utc::now());
+        let mut task = Task::new(TaskId::new(&brief, 1), "Fix login");
+        let gates: Vec<Gate> = ["pr_linked", "tests_linked", "meta:risk", "note"]
+            .iter()
+            .map(|g| g.parse().unwrap())
+            .collect();
+        assert!(gates.iter().all(|g| !g.check(&task)));
+
+        task.add_link(LinkType::Pr, "42", None);
+        task.add_link(LinkType::File, "tests/login_test.rs", None);
+        task.set_meta("risk", "low");
+        task.add_note("agent", "Covered the edge case");
+        assert!(gates.iter().all(|g| g.check(&task)));
+        assert_eq!(gates[2].to_string(), "meta:risk");
+    }
+
+    #[test]
+    fn briefs_definition_of_done_applies_to_tasks() {
+        let mut brief = Brief::new("Search", "minimal");
+        brief.set_meta(
+            DOD_META_KEY,
+            serde_json::json!(["pr_linked", "Docs updated", "check:Changelog entry"]),
+        );
+        let mut task = Task::new(TaskId::new(&brief.id, 1), "Index");
+        let dod = definition_of_done(&brief, &task);
+        assert_eq!(
+            dod,
+            vec![
+                Gate::PrLinked,
+                Gate::Checked("Docs updated".to_string()),
+                Gate::Checked("Changelog entry".to_string()),
+            ]
+        );
+
+        task.set_meta(CHECKED_META_KEY, serde_json::json!(["Docs updated"]));
+        assert!(dod[1].check(&task));
+        assert!(!dod[2].check(&task));
+
+        task.set_meta(SKIP_DOD_META_KEY, serde_json::json!(["pr_linked"]));
+        assert_eq!(definition_of_done(&brief, &task).len(), 2);
+        task.set_meta(SKIP_DOD_META_KEY, true);
+        assert!(definition_of_done(&brief, &task).is_empty());
+    }
+
+    #[test]
+    fn unknown_gates_are_rejected() {
+        assert!("ci_green".parse::<Gate>().is_err());
+        assert!("meta:".parse::<Gate>().is_err());
+        assert!("check: ".parse::<Gate>().is_err());
+    }
+}
@@ -3,8 +3,10 @@
 //! Manages task dependencies with cycle detection and topological ordering.
 //! Uses petgraph for graph operations.
 
+use chrono::{DateTime, Utc};
 use petgraph::algo::{is_cyclic_directed, toposort};
 use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
 use std::collections::HashMap;
 use thiserror::Error;
 
@@ -14,7 +16,7 @@ use super::task::{Task, TaskStatus};
 #[derive(Debug, Error, PartialEq)]
 pub enum GraphError {
     #[error("Adding dependency would create a cycle: {0} -> {1}")]
-    CycleDetected(TaskId, TaskId),
+    CycleDetected(Box<TaskId>, Box<TaskId>),
 
     #[error("Task not found: {0}")]
     TaskNotFound(TaskId),
@@ -26,11 +28,14 @@ pub enum GraphError {
 /// A dependency graph for tasks
 #[derive(Debug, Default)]
 pub struct DependencyGraph {
-    /// The underlying directed graph
-    graph: DiGraph<TaskId, ()>,
+    /// The underlying directed graph; edges carry the dependency's lag in days
+    graph: DiGraph<TaskId, i64>,
 
     /// Map from TaskId to node index
     node_map: HashMap<TaskId, NodeIndex>,
+
+    /// When completed tasks finished, so lags after them count from then
+    completed_at: HashMap<TaskId, DateTime<Utc>>,
 }
 
 impl DependencyGraph {
@@ -39,6 +44,7 @@ impl DependencyGraph {
         Self {
             graph: DiGraph::new(),
             node_map: HashMap::new(),
+            completed_at: HashMap::new(),
         }
     }
 
@@ -46,6 +52,7 @@ impl DependencyGraph {
     ///
     /// Only blocking dependencies are added to the graph for cycle detection.
     /// Informational dependencies (provenance, related, duplicates) don't affect the graph.
+    #[tracing::instrument(name = "graph.build", level = "debug", skip_all)]
     pub fn from_tasks<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> Result<Self, GraphError> {
         let mut graph = Self::new();
 
@@ -53,12 +60,15 @@ impl DependencyGraph {
         let tasks: Vec<_> = tasks.into_iter().collect();
         for task in &tasks {
             graph.add_task(task.id.clone());
+            if let (true, Some(at)) = (task.status.is_complete(), task.completed_at) {
+                graph.completed_at.insert(task.id.clone(), at);
+            }
         }
 
         // Second pass: add all blocking dependency edges
         for task in &tasks {
-            for dep_id in task.depends_on.blocking_task_ids() {
-                graph.add_dependency(&task.id, dep_id)?;
+            for dep in task.depends_on.blocking() {
+                graph.add_dependency_with_lag(&task.id, &dep.task, dep.lag_days)?;
             }
         }
 
@@ -100,6 +110,17 @@ impl DependencyGraph {
     /// The edge direction is: depends_on -> task
     /// This means "depends_on must be completed before task"
     pub fn add_dependency(&mut self, task: &TaskId, depends_on: &TaskId) -> Result<(), GraphError> {
+        self.add_dependency_with_lag(task, depends_on, 0)
+    }
+
+    /// Adds a dependency edge whose dependent starts `lag_days` after the
+    /// dependency finishes in schedule projections (before, when negative)
+    pub fn add_dependency_with_lag(
+        &mut self,
+        task: &TaskId,
+        depends_on: &TaskId,
+        lag_days: i64,
+    ) -> Result<(), GraphError> {
         if task == depends_on {
             return Err(GraphError::SelfDependency(task.clone()));
         }
@@ -115,7 +136,7 @@ impl DependencyGraph {
             .ok_or_else(|| GraphError::TaskNotFound(depends_on.clone()))?;
 
         // Add edge: depends_on -> task
-        self.graph.add_edge(*dep_idx, *task_idx, ());
+        self.graph.add_edge(*dep_idx, *task_idx, lag_days);
 
         // Check for cycles
         if is_cyclic_directed(&self.graph) {
@@ -123,7 +144,10 @@ impl DependencyGraph {
             if let Some(edge) = self.graph.find_edge(*dep_idx, *task_idx) {
                 self.graph.remove_edge(edge);
             }
-            return Err(GraphError::CycleDetected(task.clone(), depends_on.clone()));
+            return Err(GraphError::CycleDetected(
+                Box::new(task.clone()),
+                Box::new(depends_on.clone()),
+            ));
         }
 
         Ok(())
@@ -149,9 +173,11 @@ impl DependencyGraph {
         }
     }
 
-    /// Returns tasks that are ready (no incomplete dependencies)
+    /// Returns tasks that are ready (no incomplete dependencies), in ID order
+    #[tracing::instrument(name = "graph.ready", level = "debug", skip_all)]
     pub fn ready_tasks(&self, statuses: &HashMap<TaskId, TaskStatus>) -> Vec<TaskId> {
-        self.node_map
+        let mut ready: Vec<_> = self
+            .node_map
             .keys()
             .filter(|task_id| {
                 // Task must not be complete
@@ -169,12 +195,16 @@ impl DependencyGraph {
                 })
             })
             .cloned()
-            .collect()
+            .collect();
+        ready.sort();
+        ready
     }
 
-    /// Returns tasks that are blocked (have incomplete dependencies)
+    /// Returns tasks that are blocked (have incomplete dependencies), in ID order
+    #[tracing::instrument(name = "graph.blocked", level = "debug", skip_all)]
     pub fn blocked_tasks(&self, statuses: &HashMap<TaskId, TaskStatus>) -> Vec<TaskId> {
-        self.node_map
+        let mut blocked: Vec<_> = self
+            .node_map
             .keys()
             .filter(|task_id| {
                 // Task must not be complete
@@ -192,7 +222,9 @@ impl DependencyGraph {
                 })
             })
             .cloned()
-            .collect()
+            .collect();
+        blocked.sort();
+        blocked
     }
 
     /// Returns the direct dependencies of a task
@@ -208,6 +240,23 @@ impl DependencyGraph {
             .collect()
     }
 
+    /// Returns the direct dependencies of a task with their lags in days
+    pub fn dependency_lags(&self, task_id: &TaskId) -> Vec<(TaskId, i64)> {
+        let Some(task_idx) = self.node_map.get(task_id) else {
+            return vec![];
+        };
+
+        self.graph
+            .edges_directed(*task_idx, petgraph::Direction::Incoming)
+            .filter_map(|edge| {
+                Some((
+                    self.graph.node_weight(edge.source())?.clone(),
+                    *edge.weight(),
+                ))
+            })
+            .collect()
+    }
+
     /// Returns the direct dependents of a task (tasks that depend on it)
     pub fn dependents(&self, task_id: &TaskId) -> Vec<TaskId> {
         let task_idx = match self.node_map.get(task_id) {
@@ -231,8 +280,14 @@ impl DependencyGraph {
             Err(_) => {
                 // This shouldn't happen if we maintain acyclicity
                 Err(GraphError::CycleDetected(
-                    TaskId::new(&super::id::BriefId::new("cycle", chrono::Utc::now()), 0),
-                    TaskId::new(&super::id::BriefId::new("cycle", chrono::Utc::now()), 0),
+                    Box::new(TaskId::new(
+                        &super::id::BriefId::new("cycle", chrono::Utc::now()),
+                        0,
+                    )),
+                    Box::new(TaskId::new(
+                        &super::id::BriefId::new("cycle", chrono::Utc::now()),
+                        0,
+                    )),
                 ))
             }
         }
@@ -257,6 +312,119 @@ impl DependencyGraph {
     pub fn task_ids(&self) -> impl Iterator<Item = &TaskId> {
         self.node_map.keys()
     }
+
+    /// Returns the shortest chain of blocking dependencies leading from `from`
+    /// to `to`: `from` depends on the second entry, which depends on the
+    /// third, and so on until `to`. `None` when `from` doesn't depend on `to`,
+    /// even transitively.
+    ///
+    /// When [`add_dependency`](Self::add_dependency) reports a cycle for
+    /// `task -> depends_on`, `dependency_path(depends_on, task)` is the
+    /// existing half of that cycle.
+    pub fn dependency_path(&self, from: &TaskId, to: &TaskId) -> Option<Vec<TaskId>> {
+        let mut previous: HashMap<TaskId, TaskId> = HashMap::new();
+        let mut queue = std::collections::VecDeque::from([from.clone()]);
+        while let Some(current) = queue.pop_front() {
+            if &current == to {
+                let mut path = vec![current];
+                while let Some(prev) = previous.get(path.last().expect("non-empty")) {
+                    path.push(prev.clone());
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let mut deps = self.dependencies(&current);
+            deps.sort();
+            for dep in deps {
+                if &dep != from && !previous.contains_key(&dep) {
+                    previous.insert(dep.clone(), current.clone());
+                    queue.push_back(dep);
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns when each incomplete task would finish, as an offset from now
+    /// in days, if every task starts as soon as its blocking dependencies
+    /// (plus their lags) allow.
+    ///
+    /// `duration` gives each task's length in days, the unit of dependency
+    /// lags. A lag after a completed dependency counts from its completion;
+    /// a lead (negative lag) never moves a start before now. Work is assumed
+    /// to run in parallel without limit.
+    pub fn earliest_finish(
+        &self,
+        statuses: &HashMap<TaskId, TaskStatus>,
+        duration: impl Fn(&TaskId) -> i64,
+    ) -> HashMap<TaskId, i64> {
+        let is_complete = |id: &TaskId| statuses.get(id).copied().unwrap_or_default().is_complete();
+        let now = Utc::now();
+
+        let mut finish: HashMap<TaskId, i64> = HashMap::new();
+        for task_id in self.topological_order().unwrap_or_default() {
+            if is_complete(&task_id) {
+                continue;
+            }
+            let start = self
+                .dependency_lags(&task_id)
+                .into_iter()
+                .map(|(dep, lag)| {
+                    let dep_finish = match finish.get(&dep) {
+                        Some(at) => *at,
+                        // Complete: finished now, or days ago
+                        None => self
+                            .completed_at
+                            .get(&dep)
+                            .map_or(0, |at| -(now - *at).num_days()),
+                    };
+                    dep_finish + lag
+                })
+                .max()
+                .unwrap_or(0)
+                .max(0);
+            finish.insert(task_id.clone(), start + duration(&task_id));
+        }
+        finish
+    }
+
+    /// Returns the longest chain of incomplete tasks (dependencies first) and
+    /// its total duration. Ties are broken by task ID.
+    #[tracing::instrument(name = "graph.critical_path", level = "debug", skip_all)]
+    pub fn critical_path(
+        &self,
+        statuses: &HashMap<TaskId, TaskStatus>,
+        duration: impl Fn(&TaskId) -> i64,
+    ) -> (Vec<TaskId>, i64) {
+        let finish = self.earliest_finish(statuses, &duration);
+        let latest = |ids: Vec<&TaskId>| {
+            ids.into_iter()
+                .max_by(|a, b| finish[*a].cmp(&finish[*b]).then_with(|| b.cmp(a)))
+                .cloned()
+        };
+
+        let Some(mut current) = latest(finish.keys().collect()) else {
+            return (Vec::new(), 0);
+        };
+        let total = finish[&current];
+        let mut path = vec![current.clone()];
+        loop {
+            let start = finish[&current] - duration(&current);
+            let deps = self.dependency_lags(&current);
+            let Some(prev) = latest(
+                deps.iter()
+                    .filter(|(d, lag)| finish.get(d).map(|f| f + lag) == Some(start))
+                    .map(|(d, _)| d)
+                    .collect(),
+            ) else {
+                break;
+            };
+            path.push(prev.clone());
+            current = prev;
+        }
+        path.reverse();
+        (path, total)
+    }
 }
 
 #[cfg(test)]
@@ -327,6 +495,26 @@ mod tests {
         assert!(matches!(result, Err(GraphError::CycleDetected(_, _))));
     }
 
+    #[test]
+    fn dependency_path_is_shortest_chain() {
+        let mut graph = DependencyGraph::new();
+        let ids: Vec<TaskId> = (1..=4).map(make_task_id).collect();
+        for id in &ids {
+            graph.add_task(id.clone());
+        }
+        // 4 -> 3 -> 2 -> 1, plus a shortcut 4 -> 2
+        graph.add_dependency(&ids[1], &ids[0]).unwrap();
+        graph.add_dependency(&ids[2], &ids[1]).unwrap();
+        graph.add_dependency(&ids[3], &ids[2]).unwrap();
+        graph.add_dependency(&ids[3], &ids[1]).unwrap();
+
+        assert_eq!(
+            graph.dependency_path(&ids[3], &ids[0]),
+            Some(vec![ids[3].clone(), ids[1].clone(), ids[0].clone()])
+        );
+        assert_eq!(graph.dependency_path(&ids[0], &ids[3]), None);
+    }
+
     #[test]
     fn self_dependency_rejected() {
         let mut graph = DependencyGraph::new();
@@ -507,4 +695,63 @@ mod tests {
 
         assert!(duration.as_millis() < 10, "Ready query took {:?}", duration);
     }
+
+    #[test]
+    fn critical_path_follows_longest_incomplete_chain() {
+        let brief = super::super::id::BriefId::new("Test", Utc::now());
+        let id = |seq| TaskId::new(&brief, seq);
+        let mut graph = DependencyGraph::new();
+        for seq in 1..=4 {
+            graph.add_task(id(seq));
+        }
+        // 1 -> 2 -> 4 and 3 -> 4; task 3 is long
+        graph.add_dependency(&id(2), &id(1)).unwrap();
+        graph.add_dependency(&id(4), &id(2)).unwrap();
+        graph.add_dependency(&id(4), &id(3)).unwrap();
+
+        let duration = |t: &TaskId| if *t == id(3) { 5 } else { 1 };
+        let mut statuses: HashMap<TaskId, TaskStatus> =
+            (1..=4).map(|seq| (id(seq), TaskStatus::Todo)).collect();
+
+        assert_eq!(
+            graph.critical_path(&statuses, duration),
+            (vec![id(3), id(4)], 6)
+        );
+
+        statuses.insert(id(3), TaskStatus::Done);
+        assert_eq!(
+            graph.critical_path(&statuses, duration),
+            (vec![id(1), id(2), id(4)], 3)
+        );
+        assert_eq!(graph.earliest_finish(&statuses, duration)[&id(2)], 2);
+    }
+
+    #[test]
+    fn lags_delay_and_leads_overlap_dependents() {
+        let brief = super::super::id::BriefId::new("Test", Utc::now());
+        let id = |seq| TaskId::new(&brief, seq);
+        let mut graph = DependencyGraph::new();
+        for seq in 1..=3 {
+            graph.add_task(id(seq));
+        }
+        // 2 starts two days after 1 finishes; 3 starts a day before 2 does
+        graph.add_dependency_with_lag(&id(2), &id(1), 2).unwrap();
+        graph.add_dependency_with_lag(&id(3), &id(2), -1).unwrap();
+        assert_eq!(graph.dependency_lags(&id(2)), vec![(id(1), 2)]);
+
+        let duration = |_: &TaskId| 3;
+        let mut statuses: HashMap<TaskId, TaskStatus> =
+            (1..=3).map(|seq| (id(seq), TaskStatus::Todo)).collect();
+        let finish = graph.earliest_finish(&statuses, duration);
+        assert_eq!((finish[&id(1)], finish[&id(2)], finish[&id(3)]), (3, 8, 10));
+        assert_eq!(
+            graph.critical_path(&statuses, duration),
+            (vec![id(1), id(2), id(3)], 10)
+        );
+
+        // A lead never moves a start before now
+        statuses.insert(id(1), TaskStatus::Done);
+        statuses.insert(id(2), TaskStatus::Done);
+        assert_eq!(graph.earliest_finish(&statuses, duration)[&id(3)], 3);
+    }
 }
@@ -0,0 +1,1060 @@
+//! Hierarchical ID system for briefs and tasks
+//!
+//! ID Format:
+//! - Brief IDs: `b-{7-char-hash}` (e.g., `b-7f2b4c1`)
+//! - Task IDs (under brief): `{brief-id}.{sequence}` (e.g., `b-7f2b4c1.1`)
+//! - Task IDs (standalone): `t-{7-char-hash}` (e.g., `t-9d3e5f2`)
+//! - Subtask IDs: `{task-id}.{sequence}` (e.g., `b-7f2b4c1.1.1` or `t-9d3e5f2.1`)
+//!
+//! Hash is derived from title + creation timestamp, ensuring uniqueness.
+//! Same title at different times produces different IDs (by design).
+//!
+//! The [`IdScheme`] can change how new IDs look: a longer hash
+//! (`b-7f2b4c1a9e`), a prefix per brief type (`rfc-7f2b4c1`), and a slug of
+//! the title (`b-7f2b4c1-auth`). The parser accepts all of these forms
+//! regardless of configuration, so IDs from any scheme stay readable.
+//!
+//! Note: Old `a-` prefixed IDs are still accepted for backward compatibility
+//! and are automatically treated as brief IDs.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum IdError {
+    #[error("Invalid brief ID format: expected 'b-{{7-char-hash}}', got '{0}'")]
+    InvalidBriefId(String),
+
+    #[error("Invalid task ID format: expected '{{brief-id}}.{{sequence}}' or 't-{{7-char-hash}}', got '{0}'")]
+    InvalidTaskId(String),
+
+    #[error("Invalid sequence number: {0}")]
+    InvalidSequence(String),
+
+    #[error("Invalid ID scheme: {0}")]
+    InvalidScheme(String),
+}
+
+/// Default hash length in characters
+pub const DEFAULT_HASH_LENGTH: usize = 7;
+
+/// Shortest and longest hash the parser accepts
+const MIN_HASH_LENGTH: usize = 7;
+const MAX_HASH_LENGTH: usize = 12;
+
+/// Longest accepted prefix (e.g. `pitch`)
+const MAX_PREFIX_LENGTH: usize = 10;
+
+/// Longest generated slug
+const MAX_SLUG_LENGTH: usize = 24;
+
+/// Prefix for briefs and their tasks
+const BRIEF_PREFIX: &str = "b";
+
+/// Prefix for standalone tasks
+const STANDALONE_PREFIX: &str = "t";
+
+/// Generates a 7-character hash from title and timestamp
+pub(crate) fn generate_hash(title: &str, timestamp: DateTime<Utc>) -> String {
+    generate_hash_of_length(title, timestamp, DEFAULT_HASH_LENGTH)
+}
+
+fn generate_hash_of_length(title: &str, timestamp: DateTime<Utc>, length: usize) -> String {
+    let input = format!("{}{}", title, timestamp.timestamp_nanos_opt().unwrap_or(0));
+    let hash = blake3::hash(input.as_bytes());
+    let hex = hash.to_hex();
+    hex[..length].to_string()
+}
+
+/// Resizes an existing hash, keeping the old hash as a prefix when growing
+fn resize_hash(hash: &str, length: usize) -> String {
+    if hash.len() >= length {
+        return hash[..length].to_string();
+    }
+    let extension = blake3::hash(hash.as_bytes()).to_hex();
+    format!("{}{}", hash, &extension[..length - hash.len()])
+}
+
+/// New hashes for IDs that share `hash`, one per seed
+///
+/// Seeds tell the IDs apart (their full IDs will do). Every new hash keeps
+/// the shared one as its prefix and is only as long as it takes for the group
+/// to differ and to avoid `taken`. None if 12 characters are not enough.
+pub fn widen_colliding(hash: &str, seeds: &[&str], taken: &HashSet<String>) -> Option<Vec<String>> {
+    let extensions: Vec<_> = seeds
+        .iter()
+        .map(|seed| blake3::hash(format!("{}{}", hash, seed).as_bytes()).to_hex())
+        .collect();
+    (hash.len() + 1..=MAX_HASH_LENGTH).find_map(|length| {
+        let widened: Vec<String> = extensions
+            .iter()
+            .map(|ext| format!("{}{}", hash, &ext[..length - hash.len()]))
+            .collect();
+        let distinct: HashSet<&String> = widened.iter().collect();
+        (distinct.len() == widened.len() && !widened.iter().any(|h| taken.contains(h)))
+            .then_some(widened)
+    })
+}
+
+/// Builds a short slug from a title: `"Add OAuth login"` → `add-oauth-login`
+pub fn slugify(title: &str) -> Option<String> {
+    let mut slug = String::new();
+    for word in title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        let word = word.to_ascii_lowercase();
+        let needed = if slug.is_empty() {
+            word.len()
+        } else {
+            word.len() + 1
+        };
+        if slug.len() + needed > MAX_SLUG_LENGTH {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word);
+    }
+    (!slug.is_empty()).then_some(slug)
+}
+
+fn is_hash(s: &str) -> bool {
+    (MIN_HASH_LENGTH..=MAX_HASH_LENGTH).contains(&s.len())
+        && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn is_prefix(s: &str) -> bool {
+    !s.is_empty() && s.len() <= MAX_PREFIX_LENGTH && s.chars().all(|c| c.is_ascii_lowercase())
+}
+
+fn is_slug(s: &str) -> bool {
+    !s.is_empty()
+        && !s.starts_with('-')
+        && !s.ends_with('-')
+        && s.chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Splits `{prefix}-{hash}[-{slug}]` into its parts
+fn parse_root(s: &str) -> Option<(String, String, Option<String>)> {
+    let (prefix, rest) = s.split_once('-')?;
+    if !is_prefix(prefix) {
+        return None;
+    }
+
+    let (hash, slug) = match rest.split_once('-') {
+        Some((hash, slug)) => (hash, Some(slug)),
+        None => (rest, None),
+    };
+    if !is_hash(hash) || !slug.is_none_or(is_slug) {
+        return None;
+    }
+
+    // Legacy a- IDs are brief IDs
+    let prefix = if prefix == "a" { BRIEF_PREFIX } else { prefix };
+    Some((
+        prefix.to_string(),
+        hash.to_string(),
+        slug.map(str::to_string),
+    ))
+}
+
+fn write_root(
+    f: &mut fmt::Formatter<'_>,
+    prefix: &str,
+    hash: &str,
+    slug: Option<&str>,
+) -> fmt::Result {
+    write!(f, "{}-{}", prefix, hash)?;
+    if let Some(slug) = slug {
+        write!(f, "-{}", slug)?;
+    }
+    Ok(())
+}
+
+/// How new IDs are generated (`[ids]` in config)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdScheme {
+    /// Hash length in characters (7-12)
+    pub hash_length: usize,
+
+    /// Prefix per brief type (e.g. `rfc = "rfc"`, `shapeup = "pitch"`)
+    pub prefixes: BTreeMap<String, String>,
+
+    /// Append a slug of the title (e.g. `b-7f2b4c1-auth`)
+    pub slug: bool,
+}
+
+impl Default for IdScheme {
+    fn default() -> Self {
+        Self {
+            hash_length: DEFAULT_HASH_LENGTH,
+            prefixes: BTreeMap::new(),
+            slug: false,
+        }
+    }
+}
+
+impl IdScheme {
+    /// Checks that the scheme produces IDs the parser accepts
+    pub fn validate(&self) -> Result<(), IdError> {
+        if !(MIN_HASH_LENGTH..=MAX_HASH_LENGTH).contains(&self.hash_length) {
+            return Err(IdError::InvalidScheme(format!(
+                "hash_length must be between {} and {}, got {}",
+                MIN_HASH_LENGTH, MAX_HASH_LENGTH, self.hash_length
+            )));
+        }
+        for (brief_type, prefix) in &self.prefixes {
+            if !is_prefix(prefix) || prefix == STANDALONE_PREFIX || prefix == "a" {
+                return Err(IdError::InvalidScheme(format!(
+                    "prefix '{}' for type '{}' must be 1-{} lowercase letters and not 't' or 'a'",
+                    prefix, brief_type, MAX_PREFIX_LENGTH
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Prefix used for briefs of the given type
+    pub fn prefix_for(&self, brief_type: &str) -> &str {
+        self.prefixes
+            .get(brief_type)
+            .map(String::as_str)
+            .unwrap_or(BRIEF_PREFIX)
+    }
+
+    fn slug_for(&self, title: &str) -> Option<String> {
+        if self.slug {
+            slugify(title)
+        } else {
+            None
+        }
+    }
+
+    /// Generates a new brief ID
+    pub fn brief_id(&self, title: &str, brief_type: &str, timestamp: DateTime<Utc>) -> BriefId {
+        BriefId {
+            prefix: self.prefix_for(brief_type).to_string(),
+            hash: generate_hash_of_length(title, timestamp, self.hash_length),
+            slug: self.slug_for(title),
+        }
+    }
+
+    /// Generates a new standalone task ID
+    pub fn standalone_task_id(&self, title: &str, timestamp: DateTime<Utc>) -> TaskId {
+        TaskId {
+            prefix: STANDALONE_PREFIX.to_string(),
+            hash: generate_hash_of_length(title, timestamp, self.hash_length),
+            slug: self.slug_for(title),
+            segments: vec![],
+        }
+    }
+
+    /// Generates a new brief ID whose hash is not among `taken`
+    ///
+    /// On a collision the hash is widened a character at a time, up to 12.
+    pub fn unique_brief_id(
+        &self,
+        title: &str,
+        brief_type: &str,
+        timestamp: DateTime<Utc>,
+        taken: &HashSet<String>,
+    ) -> BriefId {
+        BriefId {
+            prefix: self.prefix_for(brief_type).to_string(),
+            hash: self.unique_hash(title, timestamp, taken),
+            slug: self.slug_for(title),
+        }
+    }
+
+    /// Generates a new standalone task ID whose hash is not among `taken`
+    pub fn unique_standalone_task_id(
+        &self,
+        title: &str,
+        timestamp: DateTime<Utc>,
+        taken: &HashSet<String>,
+    ) -> TaskId {
+        TaskId {
+            prefix: STANDALONE_PREFIX.to_string(),
+            hash: self.unique_hash(title, timestamp, taken),
+            slug: self.slug_for(title),
+            segments: vec![],
+        }
+    }
+
+    fn unique_hash(
+        &self,
+        title: &str,
+        mut timestamp: DateTime<Utc>,
+        taken: &HashSet<String>,
+    ) -> String {
+        loop {
+            for length in self.hash_length..=MAX_HASH_LENGTH {
+                let hash = generate_hash_of_length(title, timestamp, length);
+                if !taken.contains(&hash) {
+                    return hash;
+                }
+            }
+            // Every width is taken; try the next instant
+            timestamp += Duration::nanoseconds(1);
+        }
+    }
+
+    /// Rewrites an existing brief ID to this scheme, keeping its identity
+    ///
+    /// A longer hash keeps the old hash as its prefix; a shorter one is
+    /// truncated.
+    pub fn migrate_brief_id(&self, id: &BriefId, title: &str, brief_type: &str) -> BriefId {
+        BriefId {
+            prefix: self.prefix_for(brief_type).to_string(),
+            hash: resize_hash(&id.hash, self.hash_length),
+            slug: self.slug_for(title),
+        }
+    }
+
+    /// Rewrites a top-level standalone task ID to this scheme
+    pub fn migrate_standalone_id(&self, id: &TaskId, title: &str) -> TaskId {
+        TaskId {
+            prefix: STANDALONE_PREFIX.to_string(),
+            hash: resize_hash(&id.hash, self.hash_length),
+            slug: self.slug_for(title),
+            segments: id.segments.clone(),
+        }
+    }
+}
+
+/// Brief ID in the format `b-{7-char-hash}`
+///
+/// Configured schemes may use another prefix, a longer hash or a slug
+/// suffix: `rfc-7f2b4c1a9e-auth`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct BriefId {
+    prefix: String,
+    hash: String,
+    slug: Option<String>,
+}
+
+impl BriefId {
+    /// Creates a new brief ID from title and timestamp
+    pub fn new(title: &str, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            prefix: BRIEF_PREFIX.to_string(),
+            hash: generate_hash(title, timestamp),
+            slug: None,
+        }
+    }
+
+    /// Returns the hash portion of the ID
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    /// Returns the prefix (e.g. `b`, `rfc`)
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns the slug suffix, if any
+    pub fn slug(&self) -> Option<&str> {
+        self.slug.as_deref()
+    }
+
+    /// Returns this ID with another hash, keeping prefix and slug
+    pub fn with_hash(&self, hash: &str) -> BriefId {
+        BriefId {
+            prefix: self.prefix.clone(),
+            hash: hash.to_string(),
+            slug: self.slug.clone(),
+        }
+    }
+
+    /// Creates a task ID for this brief with the given sequence number
+    pub fn task_id(&self, sequence: u32) -> TaskId {
+        TaskId::new(self, sequence)
+    }
+}
+
+/// IDs order by their string form: the canonical order of all listings,
+/// matching `tasks.jsonl`. Compared part by part, which gives the same
+/// order without formatting either side.
+impl Ord for BriefId {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.prefix, &self.hash, &self.slug).cmp(&(&other.prefix, &other.hash, &other.slug))
+    }
+}
+
+impl PartialOrd for BriefId {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BriefId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_root(f, &self.prefix, &self.hash, self.slug.as_deref())
+    }
+}
+
+impl FromStr for BriefId {
+    type Err = IdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        match parse_root(s) {
+            Some((prefix, hash, slug)) if prefix != STANDALONE_PREFIX => {
+                Ok(Self { prefix, hash, slug })
+            }
+            _ => Err(IdError::InvalidBriefId(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for BriefId {
+    type Error = IdError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<BriefId> for String {
+    fn from(id: BriefId) -> Self {
+        id.to_string()
+    }
+}
+
+/// Task ID - can be under a brief (`b-{hash}.{seq}`) or standalone (`t-{hash}`)
+///
+/// Tasks under briefs: `b-7f2b4c1.1`
+/// Standalone tasks exist independently: `t-9d3e5f2`
+/// Both support subtasks: `b-7f2b4c1.1.1` or `t-9d3e5f2.1`
+///
+/// Note: Legacy `a-` prefixed IDs are still accepted for backward compatibility.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TaskId {
+    /// The brief's prefix, or `t` for standalone tasks
+    prefix: String,
+    /// The hash portion of the ID (from brief or standalone)
+    hash: String,
+    /// Optional slug suffix of the brief or standalone task
+    slug: Option<String>,
+    /// Sequence segments (empty for top-level standalone, non-empty for brief tasks or subtasks)
+    segments: Vec<u32>,
+}
+
+impl TaskId {
+    /// Creates a new task ID for a given brief with a sequence number
+    pub fn new(brief_id: &BriefId, sequence: u32) -> Self {
+        Self {
+            prefix: brief_id.prefix.clone(),
+            hash: brief_id.hash.clone(),
+            slug: brief_id.slug.clone(),
+            segments: vec![sequence],
+        }
+    }
+
+    /// Creates a new standalone task ID from title and timestamp
+    pub fn new_standalone(title: &str, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            prefix: STANDALONE_PREFIX.to_string(),
+            hash: generate_hash(title, timestamp),
+            slug: None,
+            segments: vec![],
+        }
+    }
+
+    /// Returns true if this is a standalone task (t- prefix)
+    pub fn is_standalone(&self) -> bool {
+        self.prefix == STANDALONE_PREFIX
+    }
+
+    /// Returns the brief ID this task belongs to, or None if standalone
+    pub fn brief_id(&self) -> Option<BriefId> {
+        if self.is_standalone() {
+            None
+        } else {
+            Some(BriefId {
+                prefix: self.prefix.clone(),
+                hash: self.hash.clone(),
+                slug: self.slug.clone(),
+            })
+        }
+    }
+
+    /// Returns the hash portion of the ID
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    /// Returns the root's prefix (`t` for standalone tasks)
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns the slug suffix of the root, if any
+    pub fn slug(&self) -> Option<&str> {
+        self.slug.as_deref()
+    }
+
+    /// Returns the top-level standalone task for a standalone (sub)task
+    pub fn standalone_root(&self) -> Option<TaskId> {
+        self.is_standalone().then(|| TaskId {
+            segments: vec![],
+            ..self.clone()
+        })
+    }
+
+    /// Returns this ID moved under another root (a brief or top-level
+    /// standalone task), keeping the sequence segments
+    pub fn with_root(&self, prefix: &str, hash: &str, slug: Option<&str>) -> TaskId {
+        TaskId {
+            prefix: prefix.to_string(),
+            hash: hash.to_string(),
+            slug: slug.map(str::to_string),
+            segments: self.segments.clone(),
+        }
+    }
+
+    /// Returns the sequence segments (e.g., `[1]` for brief task, `[1, 2]` for subtask)
+    /// Empty for top-level standalone tasks
+    pub fn segments(&self) -> &[u32] {
+        &self.segments
+    }
+
+    /// Returns the depth of this task
+    /// - Brief top-level: 1
+    /// - Standalone top-level: 0
+    /// - Subtasks: segments.len()
+    pub fn depth(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns true if this is a subtask (has parent task)
+    /// For brief tasks: depth > 1
+    /// For standalone tasks: depth > 0 (has any segments)
+    pub fn is_subtask(&self) -> bool {
+        if self.is_standalone() {
+            !self.segments.is_empty()
+        } else {
+            self.segments.len() > 1
+        }
+    }
+
+    /// Returns the parent task ID, or None if this is a top-level task
+    pub fn parent(&self) -> Option<TaskId> {
+        // Standalone tasks have a parent once they have any segments; brief
+        // tasks need at least two (the first segment is the brief's task)
+        let top_level = if self.is_standalone() { 0 } else { 1 };
+        if self.segments.len() <= top_level {
+            return None;
+        }
+        Some(TaskId {
+            segments: self.segments[..self.segments.len() - 1].to_vec(),
+            ..self.clone()
+        })
+    }
+
+    /// A string whose byte order matches [`Ord`], for stores that can only
+    /// sort text (the cache's `ORDER BY sort_key`)
+    pub fn sort_key(&self) -> String {
+        // A space sorts below every character an ID can contain, and
+        // zero-padding keeps segments in numeric order
+        let mut key = format!("{}-{}", self.prefix, self.hash);
+        if let Some(slug) = &self.slug {
+            key.push('-');
+            key.push_str(slug);
+        }
+        for seg in &self.segments {
+            key.push_str(&format!(" {:010}", seg));
+        }
+        key
+    }
+
+    /// Creates a subtask ID under this task
+    pub fn subtask(&self, sequence: u32) -> TaskId {
+        let mut segments = self.segments.clone();
+        segments.push(sequence);
+        TaskId {
+            segments,
+            ..self.clone()
+        }
+    }
+}
+
+/// Root first, in [`BriefId`] order, then each segment as a number, so
+/// `b-7f2b4c1.2` sorts before `b-7f2b4c1.10` and a task before its subtasks
+impl Ord for TaskId {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.prefix, &self.hash, &self.slug)
+            .cmp(&(&other.prefix, &other.hash, &other.slug))
+            .then_with(|| self.segments.cmp(&other.segments))
+    }
+}
+
+impl PartialOrd for TaskId {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for TaskId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_root(f, &self.prefix, &self.hash, self.slug.as_deref())?;
+        for seg in &self.segments {
+            write!(f, ".{}", seg)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TaskId {
+    type Err = IdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let mut parts = s.split('.');
+        let root = parts.next().unwrap_or_default();
+        let (prefix, hash, slug) =
+            parse_root(root).ok_or_else(|| IdError::InvalidTaskId(s.to_string()))?;
+
+        let segments = parts
+            .map(|p| {
+                p.parse::<u32>()
+                    .map_err(|_| IdError::InvalidSequence(p.to_string()))
+            })
+            .collect::<Result<Vec<u32>, _>>()?;
+
+        // Brief tasks must have at least one segment; standalone tasks may
+        // have none (top-level) or several (subtasks)
+        if prefix != STANDALONE_PREFIX && segments.is_empty() {
+            return Err(IdError::InvalidTaskId(s.to_string()));
+        }
+
+        Ok(Self {
+            prefix,
+            hash,
+            slug,
+            segments,
+        })
+    }
+}
+
+impl TryFrom<String> for TaskId {
+    type Error = IdError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<TaskId> for String {
+    fn from(id: TaskId) -> Self {
+        id.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brief_id_generation_is_unique_for_different_timestamps() {
+        let title = "Same Title";
+        let ts1 = Utc::now();
+        let ts2 = ts1 + chrono::Duration::nanoseconds(1);
+
+        let id1 = BriefId::new(title, ts1);
+        let id2 = BriefId::new(title, ts2);
+
+        assert_ne!(id1, id2);
+    }
+
+    #[test]
+    fn brief_id_format_is_correct() {
+        let id = BriefId::new("Test", Utc::now());
+        let s = id.to_string();
+
+        assert!(s.starts_with("b-"));
+        assert_eq!(s.len(), 9); // "b-" + 7 chars
+    }
+
+    #[test]
+    fn brief_id_parses_correctly() {
+        let original = BriefId::new("Test", Utc::now());
+        let s = original.to_string();
+        let parsed: BriefId = s.parse().unwrap();
+
+        assert_eq!(original, parsed);
+    }
+
+    #[test]
+    fn brief_id_parses_legacy_format() {
+        // Legacy a- prefix should still work
+        let parsed: BriefId = "a-1234567".parse().unwrap();
+        assert_eq!(parsed.hash(), "1234567");
+        // But when displayed, uses new b- format
+        assert!(parsed.to_string().starts_with("b-"));
+    }
+
+    #[test]
+    fn brief_id_rejects_invalid_format() {
+        assert!("invalid".parse::<BriefId>().is_err());
+        assert!("b-short".parse::<BriefId>().is_err());
+        assert!("b-toolonggg".parse::<BriefId>().is_err());
+        assert!("b-gggggg1".parse::<BriefId>().is_err()); // 'g' is not hex
+    }
+
+    #[test]
+    fn task_id_format_is_correct() {
+        let brief = BriefId::new("Test", Utc::now());
+        let task = TaskId::new(&brief, 1);
+        let s = task.to_string();
+
+        assert!(s.starts_with("b-"));
+        assert!(s.ends_with(".1"));
+    }
+
+    #[test]
+    fn task_id_parses_correctly() {
+        let brief = BriefId::new("Test", Utc::now());
+        let original = TaskId::new(&brief, 42);
+        let s = original.to_string();
+        let parsed: TaskId = s.parse().unwrap();
+
+        assert_eq!(original, parsed);
+    }
+
+    #[test]
+    fn task_id_parses_legacy_format() {
+        // Legacy a- prefix should still work
+        let parsed: TaskId = "a-1234567.1".parse().unwrap();
+        assert_eq!(parsed.hash(), "1234567");
+        assert!(!parsed.is_standalone());
+        // But when displayed, uses new b- format
+        assert!(parsed.to_string().starts_with("b-"));
+    }
+
+    #[test]
+    fn subtask_id_works() {
+        let brief = BriefId::new("Test", Utc::now());
+        let task = TaskId::new(&brief, 1);
+        let subtask = task.subtask(2);
+
+        assert_eq!(subtask.depth(), 2);
+        assert!(subtask.is_subtask());
+        assert_eq!(subtask.parent(), Some(task.clone()));
+        assert!(subtask.to_string().ends_with(".1.2"));
+    }
+
+    #[test]
+    fn task_id_parses_subtasks() {
+        let s = "b-1234567.1.2.3";
+        let task: TaskId = s.parse().unwrap();
+
+        assert_eq!(task.segments(), &[1, 2, 3]);
+        assert_eq!(task.depth(), 3);
+    }
+
+    #[test]
+    fn task_id_rejects_invalid_format() {
+        assert!("invalid".parse::<TaskId>().is_err());
+        assert!("b-1234567".parse::<TaskId>().is_err()); // no sequence
+        assert!("b-123456.1".parse::<TaskId>().is_err()); // hash too short
+        assert!("b-1234567.abc".parse::<TaskId>().is_err()); // non-numeric sequence
+    }
+
+    #[test]
+    fn brief_id_creates_task_id() {
+        let brief = BriefId::new("Test", Utc::now());
+        let task = brief.task_id(5);
+
+        assert_eq!(task.brief_id(), Some(brief));
+        assert_eq!(task.segments(), &[5]);
+    }
+
+    #[test]
+    fn serde_roundtrip_brief_id() {
+        let original = BriefId::new("Test", Utc::now());
+        let json = serde_json::to_string(&original).unwrap();
+        let parsed: BriefId = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(original, parsed);
+    }
+
+    #[test]
+    fn serde_roundtrip_task_id() {
+        let brief = BriefId::new("Test", Utc::now());
+        let original = TaskId::new(&brief, 1).subtask(2);
+        let json = serde_json::to_string(&original).unwrap();
+        let parsed: TaskId = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(original, parsed);
+    }
+
+    // =========================================================================
+    // Standalone Task ID Tests
+    // =========================================================================
+
+    #[test]
+    fn standalone_task_id_generation() {
+        let ts = Utc::now();
+        let id = TaskId::new_standalone("Fix typo", ts);
+
+        assert!(id.is_standalone());
+        assert!(id.brief_id().is_none());
+        assert!(id.segments().is_empty());
+        assert_eq!(id.depth(), 0);
+        assert!(!id.is_subtask());
+    }
+
+    #[test]
+    fn standalone_task_id_format() {
+        let ts = Utc::now();
+        let id = TaskId::new_standalone("Fix typo", ts);
+        let s = id.to_string();
+
+        assert!(s.starts_with("t-"));
+        assert_eq!(s.len(), 9); // "t-" + 7 chars
+    }
+
+    #[test]
+    fn standalone_task_id_parses_correctly() {
+        let s = "t-1234567";
+        let task: TaskId = s.parse().unwrap();
+
+        assert!(task.is_standalone());
+        assert!(task.brief_id().is_none());
+        assert!(task.segments().is_empty());
+    }
+
+    #[test]
+    fn standalone_task_id_roundtrip() {
+        let ts = Utc::now();
+        let original = TaskId::new_standalone("Fix bug", ts);
+        let s = original.to_string();
+        let parsed: TaskId = s.parse().unwrap();
+
+        assert_eq!(original, parsed);
+    }
+
+    #[test]
+    fn standalone_task_subtask() {
+        let ts = Utc::now();
+        let task = TaskId::new_standalone("Main task", ts);
+        let subtask = task.subtask(1);
+
+        assert!(subtask.is_standalone());
+        assert!(subtask.is_subtask());
+        assert_eq!(subtask.depth(), 1);
+        assert_eq!(subtask.parent(), Some(task.clone()));
+
+        let s = subtask.to_string();
+        assert!(s.starts_with("t-"));
+        assert!(s.ends_with(".1"));
+    }
+
+    #[test]
+    fn standalone_subtask_parses() {
+        let s = "t-1234567.1.2";
+        let task: TaskId = s.parse().unwrap();
+
+        assert!(task.is_standalone());
+        assert!(task.is_subtask());
+        assert_eq!(task.segments(), &[1, 2]);
+        assert_eq!(task.depth(), 2);
+    }
+
+    #[test]
+    fn standalone_subtask_parent_chain() {
+        let s = "t-1234567.1.2";
+        let subtask: TaskId = s.parse().unwrap();
+
+        let parent = subtask.parent().unwrap();
+        assert_eq!(parent.to_string(), "t-1234567.1");
+        assert!(parent.is_subtask());
+
+        let grandparent = parent.parent().unwrap();
+        assert_eq!(grandparent.to_string(), "t-1234567");
+        assert!(!grandparent.is_subtask());
+
+        assert!(grandparent.parent().is_none());
+    }
+
+    #[test]
+    fn serde_roundtrip_standalone_task_id() {
+        let ts = Utc::now();
+        let original = TaskId::new_standalone("Test", ts);
+        let json = serde_json::to_string(&original).unwrap();
+        let parsed: TaskId = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(original, parsed);
+        assert!(parsed.is_standalone());
+    }
+
+    #[test]
+    fn serde_roundtrip_standalone_subtask_id() {
+        let ts = Utc::now();
+        let original = TaskId::new_standalone("Test", ts).subtask(1).subtask(2);
+        let json = serde_json::to_string(&original).unwrap();
+        let parsed: TaskId = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(original, parsed);
+    }
+
+    #[test]
+    fn standalone_id_unique_per_timestamp() {
+        let title = "Same Title";
+        let ts1 = Utc::now();
+        let ts2 = ts1 + chrono::Duration::nanoseconds(1);
+
+        let id1 = TaskId::new_standalone(title, ts1);
+        let id2 = TaskId::new_standalone(title, ts2);
+
+        assert_ne!(id1, id2);
+    }
+
+    #[test]
+    fn brief_vs_standalone_are_different() {
+        let brief = BriefId::new("Test", Utc::now());
+        let brief_task = TaskId::new(&brief, 1);
+
+        let ts = Utc::now();
+        let standalone = TaskId::new_standalone("Test", ts);
+
+        assert!(!brief_task.is_standalone());
+        assert!(standalone.is_standalone());
+        assert!(brief_task.brief_id().is_some());
+        assert!(standalone.brief_id().is_none());
+    }
+
+    fn custom_scheme() -> IdScheme {
+        IdScheme {
+            hash_length: 10,
+            prefixes: BTreeMap::from([("rfc".to_string(), "rfc".to_string())]),
+            slug: true,
+        }
+    }
+
+    #[test]
+    fn parse_configured_forms() {
+        let id: BriefId = "rfc-7f2b4c1a9e-auth-flow".parse().unwrap();
+        assert_eq!(id.prefix(), "rfc");
+        assert_eq!(id.hash(), "7f2b4c1a9e");
+        assert_eq!(id.slug(), Some("auth-flow"));
+
+        let task: TaskId = "rfc-7f2b4c1a9e-auth-flow.2.1".parse().unwrap();
+        assert_eq!(task.brief_id(), Some(id));
+        assert_eq!(task.segments(), &[2, 1]);
+
+        let standalone: TaskId = "t-7f2b4c1a9e-fix-login".parse().unwrap();
+        assert!(standalone.is_standalone());
+        assert_eq!(standalone.slug(), Some("fix-login"));
+        assert_eq!(standalone.to_string(), "t-7f2b4c1a9e-fix-login");
+    }
+
+    #[test]
+    fn scheme_generates_configured_ids() {
+        let scheme = custom_scheme();
+        let id = scheme.brief_id("Auth Flow", "rfc", Utc::now());
+        assert_eq!(id.prefix(), "rfc");
+        assert_eq!(id.hash().len(), 10);
+        assert_eq!(id.slug(), Some("auth-flow"));
+        assert_eq!(id, id.to_string().parse().unwrap());
+
+        let other = scheme.brief_id("Auth Flow", "minimal", Utc::now());
+        assert_eq!(other.prefix(), "b");
+    }
+
+    #[test]
+    fn scheme_validation() {
+        assert!(IdScheme::default().validate().is_ok());
+        assert!(custom_scheme().validate().is_ok());
+
+        let too_short = IdScheme {
+            hash_length: 5,
+            ..IdScheme::default()
+        };
+        assert!(too_short.validate().is_err());
+
+        let reserved = IdScheme {
+            prefixes: BTreeMap::from([("rfc".to_string(), "t".to_string())]),
+            ..IdScheme::default()
+        };
+        assert!(reserved.validate().is_err());
+    }
+
+    #[test]
+    fn migrate_keeps_hash_prefix() {
+        let old = BriefId::new("Auth Flow", Utc::now());
+        let new = custom_scheme().migrate_brief_id(&old, "Auth Flow", "rfc");
+        assert!(new.hash().starts_with(old.hash()));
+        assert_eq!(new.hash().len(), 10);
+
+        let back = IdScheme::default().migrate_brief_id(&new, "Auth Flow", "rfc");
+        assert_eq!(back, old);
+    }
+
+    #[test]
+    fn unique_ids_widen_past_taken_hashes() {
+        let scheme = IdScheme::default();
+        let at = Utc::now();
+        let first = scheme.brief_id("Auth Flow", "minimal", at);
+        let taken = HashSet::from([first.hash().to_string()]);
+
+        let second = scheme.unique_brief_id("Auth Flow", "minimal", at, &taken);
+        assert_eq!(second.hash().len(), 8);
+        assert!(second.hash().starts_with(first.hash()));
+
+        let free = scheme.unique_standalone_task_id("Other", at, &taken);
+        assert_eq!(free.hash().len(), 7);
+    }
+
+    #[test]
+    fn widen_colliding_tells_ids_apart() {
+        let taken = HashSet::new();
+        let widened = widen_colliding("abc1234", &["b-abc1234", "t-abc1234"], &taken).unwrap();
+        assert_ne!(widened[0], widened[1]);
+        assert!(widened.iter().all(|h| h.starts_with("abc1234")));
+        assert!(widened.iter().all(|h| h.len() < 12));
+
+        assert!(widen_colliding("abc1234def01", &["b-1", "b-2"], &taken).is_none());
+    }
+
+    #[test]
+    fn task_ids_order_segments_numerically() {
+        let brief: BriefId = "b-7f2b4c1".parse().unwrap();
+        let mut ids = [
+            brief.task_id(10),
+            brief.task_id(2).subtask(1),
+            brief.task_id(2),
+            brief.task_id(1),
+        ];
+        ids.sort();
+        let printed: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        assert_eq!(
+            printed,
+            [
+                "b-7f2b4c1.1",
+                "b-7f2b4c1.2",
+                "b-7f2b4c1.2.1",
+                "b-7f2b4c1.10"
+            ]
+        );
+
+        let mut keys: Vec<String> = ids.iter().map(|id| id.sort_key()).collect();
+        keys.sort();
+        assert_eq!(keys, ids.iter().map(|id| id.sort_key()).collect::<Vec<_>>());
+    }
+}
@@ -89,6 +89,7 @@ pub fn merge_tasks(base: &Task, ours: &Task, theirs: &Task) -> MergeResult {
     merge_field!(status, "status", touch_status);
     merge_field!(description, "description", touch_description);
     merge_field!(completed_at, "completed_at", touch_completed_at);
+    merge_field!(due_at, "due_at", touch_due_at);
 
     // Merge dependencies (set union - additions win)
     use super::task::{Dependencies, Dependency};
@@ -112,9 +113,13 @@ pub fn merge_tasks(base: &Task, ours: &Task, theirs: &Task) -> MergeResult {
         merged_deps.remove(dep);
     }
 
-    // Convert HashSet back to Dependencies
+    // Convert HashSet back to Dependencies. A dependency whose lag changed
+    // on one side appears twice; adding the changed one last keeps it.
+    let (kept, mut changed): (Vec<_>, Vec<_>) =
+        merged_deps.into_iter().partition(|d| base_deps.contains(d));
+    changed.sort_by_key(|d| d.lag_days);
     let mut final_deps = Dependencies::new();
-    for dep in merged_deps {
+    for dep in kept.into_iter().chain(changed) {
         final_deps.add(dep);
     }
     merged.depends_on = final_deps;
@@ -136,7 +141,7 @@ pub fn merge_tasks(base: &Task, ours: &Task, theirs: &Task) -> MergeResult {
         .collect();
 
     let mut merged_meta = TaskMeta::new();
-    let mut merged_versions_meta = std::collections::HashMap::new();
+    let mut merged_versions_meta = std::collections::BTreeMap::new();
 
     for key in all_meta_keys {
         let base_v = base.versions.meta_version(&key);
@@ -186,6 +191,55 @@ pub fn merge_tasks(base: &Task, ours: &Task, theirs: &Task) -> MergeResult {
     merged.meta = merged_meta;
     merged.versions.meta = merged_versions_meta;
 
+    // Merge external IDs per system; a side that changed a system wins
+    // over one that left it alone, ours first
+    let systems: HashSet<_> = base
+        .external_ids
+        .keys()
+        .chain(ours.external_ids.keys())
+        .chain(theirs.external_ids.keys())
+        .cloned()
+        .collect();
+    for system in systems {
+        let base_v = base.external_ids.get(&system);
+        let ours_v = ours.external_ids.get(&system);
+        let theirs_v = theirs.external_ids.get(&system);
+        let value = if ours_v != base_v { ours_v } else { theirs_v };
+        match value {
+            Some(key) => merged.external_ids.insert(system, key.clone()),
+            None => merged.external_ids.remove(&system),
+        };
+    }
+
+    // A freeze or unfreeze on either side wins over no change, ours first
+    merged.frozen = if ours.frozen != base.frozen {
+        ours.frozen.clone()
+    } else {
+        theirs.frozen.clone()
+    };
+
+    merged.rank = if ours.rank != base.rank {
+        ours.rank
+    } else {
+        theirs.rank
+    };
+
+    // Union reviews from both sides; a decided review beats the same
+    // review still pending on the other side
+    let mut reviews = ours.reviews.clone();
+    for theirs_review in &theirs.reviews {
+        let same = reviews.iter_mut().find(|r| {
+            r.reviewer == theirs_review.reviewer && r.requested_at == theirs_review.requested_at
+        });
+        match same {
+            Some(r) if r.is_pending() && !theirs_review.is_pending() => *r = theirs_review.clone(),
+            Some(_) => {}
+            None => reviews.push(theirs_review.clone()),
+        }
+    }
+    reviews.sort_by_key(|r| r.requested_at);
+    merged.reviews = reviews;
+
     // Update the overall updated_at to the max of both
     merged.updated_at = std::cmp::max(ours.updated_at, theirs.updated_at);
 
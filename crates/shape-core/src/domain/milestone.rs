@@ -0,0 +1,104 @@
+//! Milestone domain model
+//!
+//! A milestone is an external, date-anchored target such as
+//! "Conference demo — March 3". Briefs and tasks target a milestone through
+//! the `milestone` metadata key; tasks without their own target inherit their
+//! brief's.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::id::generate_hash;
+use super::{Brief, Task};
+
+/// Metadata key briefs and tasks use to target a milestone
+pub const MILESTONE_META_KEY: &str = "milestone";
+
+/// A date-anchored target that briefs and tasks can work toward
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Milestone {
+    /// Milestone ID in the format `m-{7-char-hash}`
+    pub id: String,
+
+    pub title: String,
+
+    /// The date the milestone falls on
+    pub date: NaiveDate,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl Milestone {
+    /// Creates a new milestone on the given date
+    pub fn new(title: impl Into<String>, date: NaiveDate) -> Self {
+        let title = title.into();
+        let now = Utc::now();
+        Self {
+            id: format!("m-{}", generate_hash(&title, now)),
+            title,
+            date,
+            description: None,
+            created_at: now,
+        }
+    }
+
+    /// Days from `today` until the milestone (negative once it has passed)
+    pub fn days_until(&self, today: NaiveDate) -> i64 {
+        (self.date - today).num_days()
+    }
+
+    /// Whether the milestone date is before `today`
+    pub fn is_past(&self, today: NaiveDate) -> bool {
+        self.date < today
+    }
+}
+
+/// The milestone a brief targets, if any
+pub fn brief_milestone(brief: &Brief) -> Option<&str> {
+    brief.get_meta(MILESTONE_META_KEY).and_then(|v| v.as_str())
+}
+
+/// The milestone a task targets: its own, or else its brief's
+pub fn task_milestone<'a>(task: &'a Task, brief: Option<&'a Brief>) -> Option<&'a str> {
+    task.get_meta(MILESTONE_META_KEY)
+        .and_then(|v| v.as_str())
+        .or_else(|| brief.and_then(brief_milestone))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::TaskId;
+
+    #[test]
+    fn milestone_dates() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 3).unwrap();
+        let milestone = Milestone::new("Conference demo", date);
+
+        assert!(milestone.id.starts_with("m-"));
+        assert_eq!(milestone.id.len(), 9);
+
+        let before = NaiveDate::from_ymd_opt(2026, 2, 24).unwrap();
+        assert_eq!(milestone.days_until(before), 7);
+        assert!(!milestone.is_past(before));
+        assert!(!milestone.is_past(date));
+        assert!(milestone.is_past(date.succ_opt().unwrap()));
+    }
+
+    #[test]
+    fn tasks_inherit_brief_milestone() {
+        let mut brief = Brief::new("Pitch", "minimal");
+        let mut task = Task::new(TaskId::new(&brief.id, 1), "Task");
+
+        assert_eq!(task_milestone(&task, Some(&brief)), None);
+
+        brief.set_meta(MILESTONE_META_KEY, "m-1111111");
+        assert_eq!(task_milestone(&task, Some(&brief)), Some("m-1111111"));
+
+        task.set_meta(MILESTONE_META_KEY, "m-2222222");
+        assert_eq!(task_milestone(&task, Some(&brief)), Some("m-2222222"));
+    }
+}
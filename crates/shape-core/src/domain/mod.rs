@@ -7,7 +7,12 @@
 //! - [`Brief`] - A document (pitch, RFC, PRD) that spawns tasks
 //! - [`Task`] - An executable unit of work belonging to a brief
 //! - [`BriefId`] / [`TaskId`] - Unique identifiers with format `b-{hash}` and `b-{hash}.{seq}`
+//! - [`Milestone`] - A date-anchored target briefs and tasks can work toward
+//! - [`BettingTable`] - Bets on briefs for a cycle and the decisions they led to
+//! - [`ResourceLock`] - A path or subsystem an agent is working on
+//! - [`Team`] - The humans and agents `@handle` mentions refer to
 //! - [`DependencyGraph`] - DAG of task dependencies with cycle detection
+//! - [`TaskTree`] - Subtask hierarchy with leaf-weighted progress rollups
 //!
 //! ## Status Lifecycles
 //!
@@ -24,7 +29,7 @@
 //! ## Example
 //!
 //! ```
-//! use shape_cli::domain::{Brief, Task, TaskId, DependencyGraph, TaskStatus};
+//! use shape_core::domain::{Brief, Task, TaskId, DependencyGraph, TaskStatus};
 //! use std::collections::HashMap;
 //!
 //! // Create a brief and tasks
@@ -48,17 +53,44 @@
 //! assert!(!ready.contains(&task2.id)); // task2 is blocked
 //! ```
 
+mod appetite;
+mod bet;
 mod brief;
+mod external;
+mod freeze;
+mod gate;
 mod graph;
 mod id;
 mod merge;
+mod milestone;
+mod order;
+mod priority;
+mod resource_lock;
+mod review;
 mod task;
+mod team;
+mod tree;
 
-pub use brief::{Brief, BriefFrontmatter, BriefMeta, BriefStatus};
+pub use appetite::{appetite_duration, AppetiteBudget, APPETITE_META_KEY};
+pub use bet::{parse_appetite, Bet, BettingTable, Decision, Outcome, APPETITES};
+pub use brief::{Brief, BriefEvent, BriefEventType, BriefFrontmatter, BriefMeta, BriefStatus};
+pub use external::{format_external_ids, parse_external_ref, ExternalIds};
+pub use freeze::FreezeInfo;
+pub use gate::{
+    checked_items, definition_of_done, Gate, CHECKED_META_KEY, DOD_META_KEY, SKIP_DOD_META_KEY,
+};
 pub use graph::{DependencyGraph, GraphError};
-pub use id::{BriefId, IdError, TaskId};
+pub use id::{slugify, widen_colliding, BriefId, IdError, IdScheme, TaskId};
 pub use merge::{merge_tasks, MergeResult};
+pub use milestone::{brief_milestone, task_milestone, Milestone, MILESTONE_META_KEY};
+pub use order::{by_id, by_rank, place, rank_cmp, ById, RANK_STEP};
+pub use priority::{effective_priorities, EffectivePriority, Priority, PRIORITY_META_KEY};
+pub use resource_lock::{task_scope, ResourceLock, SCOPE_META_KEY};
+pub use review::{Review, ReviewVerdict};
 pub use task::{
     current_timestamp, BlockInfo, Dependencies, Dependency, DependencyType, FieldVersions,
     HistoryEvent, HistoryEventType, Link, LinkType, Note, Task, TaskMeta, TaskStatus,
+    CONTAINER_META_KEY, REQUIRES_HUMAN_META_KEY,
 };
+pub use team::{is_valid_handle, mentions, Interests, MemberKind, Team, TeamMember};
+pub use tree::{Progress, TaskTree};
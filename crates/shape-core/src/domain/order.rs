@@ -0,0 +1,180 @@
+//! Canonical output ordering
+//!
+//! Stores hand back `HashMap`s, whose iteration order changes from run to
+//! run. Anything a command prints as a list goes through [`by_id`] (or is
+//! sorted by ID directly) so identical project state always produces
+//! identical output. Commands with their own ranking (`next` by score,
+//! `history` by time) break ties by ID.
+//!
+//! Within a brief, tasks can also be ranked by hand ([`by_rank`], [`place`]):
+//! ranked tasks come first, in rank order, followed by the rest by ID.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use super::{Brief, BriefId, Milestone, Task, TaskId};
+
+/// Gap left between ranks, so most moves change a single task
+pub const RANK_STEP: i64 = 1024;
+
+/// Items listed in command output, in canonical (ID) order
+pub trait ById {
+    type Id: Ord + ?Sized;
+
+    fn sort_id(&self) -> &Self::Id;
+}
+
+impl ById for Task {
+    type Id = TaskId;
+
+    fn sort_id(&self) -> &TaskId {
+        &self.id
+    }
+}
+
+impl ById for Brief {
+    type Id = BriefId;
+
+    fn sort_id(&self) -> &BriefId {
+        &self.id
+    }
+}
+
+impl ById for Milestone {
+    type Id = str;
+
+    fn sort_id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// Collects items in canonical order
+pub fn by_id<'a, T: ById + 'a>(items: impl IntoIterator<Item = &'a T>) -> Vec<&'a T> {
+    let mut sorted: Vec<&T> = items.into_iter().collect();
+    sorted.sort_by(|a, b| a.sort_id().cmp(b.sort_id()));
+    sorted
+}
+
+/// Backlog order: tasks of a brief (or a standalone task) stay together,
+/// ranked tasks first by rank, then unranked ones by ID
+pub fn rank_cmp(a: &Task, b: &Task) -> Ordering {
+    let group = |t: &Task| {
+        t.brief_id()
+            .map_or_else(|| t.id.to_string(), |b| b.to_string())
+    };
+    group(a)
+        .cmp(&group(b))
+        .then_with(|| match (a.rank, b.rank) {
+            (Some(ra), Some(rb)) => ra.cmp(&rb),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        })
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+/// Collects tasks in backlog order (see [`rank_cmp`])
+pub fn by_rank<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> Vec<&'a Task> {
+    let mut sorted: Vec<&Task> = tasks.into_iter().collect();
+    sorted.sort_by(|a, b| rank_cmp(a, b));
+    sorted
+}
+
+/// Moves `id` directly before `anchor` (or after it) in their brief's
+/// backlog and returns the IDs of tasks whose rank changed.
+///
+/// Only `id` is re-ranked when there is room between its new neighbours;
+/// otherwise the whole brief is renumbered. Both tasks must exist and
+/// belong to the same brief.
+pub fn place(
+    tasks: &mut HashMap<TaskId, Task>,
+    id: &TaskId,
+    anchor: &TaskId,
+    after: bool,
+) -> Vec<TaskId> {
+    let brief = tasks[id].brief_id();
+    let mut order: Vec<TaskId> = by_rank(tasks.values().filter(|t| t.brief_id() == brief))
+        .into_iter()
+        .map(|t| t.id.clone())
+        .filter(|t| t != id)
+        .collect();
+    let pos = order
+        .iter()
+        .position(|t| t == anchor)
+        .unwrap_or(order.len())
+        + after as usize;
+
+    let rank_at = |i: usize| order.get(i).and_then(|t| tasks[t].rank);
+    let prev = pos.checked_sub(1).map(rank_at);
+    let next = rank_at(pos);
+    let fits = match (prev, next) {
+        (None, Some(n)) => Some(n - RANK_STEP),
+        (Some(Some(p)), None) if pos == order.len() => Some(p + RANK_STEP),
+        (Some(Some(p)), Some(n)) if n - p >= 2 => Some(p + (n - p) / 2),
+        _ => None,
+    };
+
+    if let Some(rank) = fits {
+        tasks.get_mut(id).unwrap().rank = Some(rank);
+        return vec![id.clone()];
+    }
+
+    order.insert(pos, id.clone());
+    let mut changed = Vec::new();
+    for (i, task_id) in order.iter().enumerate() {
+        let rank = Some((i as i64 + 1) * RANK_STEP);
+        let task = tasks.get_mut(task_id).unwrap();
+        if task.rank != rank {
+            task.rank = rank;
+            changed.push(task_id.clone());
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn tasks_sort_by_numeric_id() {
+        let brief = BriefId::new("Ordering", Utc::now());
+        let tasks: Vec<Task> = [10, 2, 1]
+            .into_iter()
+            .map(|seq| Task::new(brief.task_id(seq), "Task"))
+            .collect();
+
+        let ids: Vec<String> = by_id(&tasks).iter().map(|t| t.id.to_string()).collect();
+        assert!(ids[0].ends_with(".1") && ids[1].ends_with(".2") && ids[2].ends_with(".10"));
+    }
+
+    #[test]
+    fn place_reranks_one_task_when_there_is_room() {
+        let brief = BriefId::new("Ranking", Utc::now());
+        let mut tasks: HashMap<TaskId, Task> = (1..=4)
+            .map(|seq| {
+                let task = Task::new(brief.task_id(seq), "Task");
+                (task.id.clone(), task)
+            })
+            .collect();
+        let id = |seq| brief.task_id(seq);
+        let order = |tasks: &HashMap<TaskId, Task>| -> Vec<TaskId> {
+            by_rank(tasks.values())
+                .iter()
+                .map(|t| t.id.clone())
+                .collect()
+        };
+
+        // Unranked: the first move numbers the whole brief
+        let changed = place(&mut tasks, &id(4), &id(1), false);
+        assert_eq!(changed.len(), 4);
+        assert_eq!(order(&tasks), vec![id(4), id(1), id(2), id(3)]);
+
+        // Afterwards only the moved task changes
+        assert_eq!(place(&mut tasks, &id(3), &id(1), false), vec![id(3)]);
+        assert_eq!(order(&tasks), vec![id(4), id(3), id(1), id(2)]);
+        assert_eq!(place(&mut tasks, &id(4), &id(2), true), vec![id(4)]);
+        assert_eq!(order(&tasks), vec![id(3), id(1), id(2), id(4)]);
+    }
+}
@@ -0,0 +1,163 @@
+//! Task priority and inheritance along blocking chains
+//!
+//! A task's priority is `meta.priority`: `high`, `medium` or `low`, with
+//! anything else counting as low. When a high-priority task is blocked by a
+//! low-priority one, the blocker is what stands in the way, so it inherits
+//! the higher priority: a task's effective priority is the highest among
+//! itself and the unfinished tasks waiting on it, directly or through other
+//! blockers.
+
+use std::collections::{HashMap, HashSet};
+
+use super::id::TaskId;
+use super::task::Task;
+
+/// Task metadata key holding the task's own priority
+pub const PRIORITY_META_KEY: &str = "priority";
+
+/// Priority of a task, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    /// The task's own priority from `meta.priority`
+    pub fn of(task: &Task) -> Self {
+        match task.get_meta(PRIORITY_META_KEY).and_then(|v| v.as_str()) {
+            Some("high") => Priority::High,
+            Some("medium") => Priority::Medium,
+            _ => Priority::Low,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Medium => "medium",
+            Priority::High => "high",
+        }
+    }
+
+    /// Weight used when scoring tasks (low=1, medium=2, high=3)
+    pub fn score(self) -> f64 {
+        match self {
+            Priority::Low => 1.0,
+            Priority::Medium => 2.0,
+            Priority::High => 3.0,
+        }
+    }
+}
+
+/// A task's priority once inheritance is applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectivePriority {
+    pub priority: Priority,
+
+    /// Waiting task the priority was inherited from, if above the task's own
+    pub inherited_from: Option<TaskId>,
+}
+
+/// Computes every task's effective priority. Finished tasks keep their own
+/// priority and pass nothing on.
+pub fn effective_priorities(tasks: &HashMap<TaskId, Task>) -> HashMap<TaskId, EffectivePriority> {
+    // Blocker -> unfinished tasks waiting on it, in ID order so ties resolve
+    // the same way every run
+    let mut waiting: HashMap<&TaskId, Vec<&TaskId>> = HashMap::new();
+    for task in tasks.values().filter(|t| !t.status.is_complete()) {
+        for dep in task.depends_on.blocking_task_ids() {
+            waiting.entry(dep).or_default().push(&task.id);
+        }
+    }
+    for ids in waiting.values_mut() {
+        ids.sort();
+    }
+
+    let mut resolved = HashMap::new();
+    for id in tasks.keys() {
+        resolve(id, tasks, &waiting, &mut resolved, &mut HashSet::new());
+    }
+    resolved
+}
+
+fn resolve(
+    id: &TaskId,
+    tasks: &HashMap<TaskId, Task>,
+    waiting: &HashMap<&TaskId, Vec<&TaskId>>,
+    resolved: &mut HashMap<TaskId, EffectivePriority>,
+    visiting: &mut HashSet<TaskId>,
+) -> EffectivePriority {
+    if let Some(done) = resolved.get(id) {
+        return done.clone();
+    }
+    let mut best = EffectivePriority {
+        priority: tasks.get(id).map(Priority::of).unwrap_or_default(),
+        inherited_from: None,
+    };
+    let unfinished = tasks.get(id).is_some_and(|t| !t.status.is_complete());
+    // A dependency cycle is reported elsewhere; stop walking it here
+    if unfinished && visiting.insert(id.clone()) {
+        for waiter in waiting.get(id).into_iter().flatten() {
+            let theirs = resolve(waiter, tasks, waiting, resolved, visiting);
+            if theirs.priority > best.priority {
+                best = EffectivePriority {
+                    priority: theirs.priority,
+                    inherited_from: Some(theirs.inherited_from.unwrap_or((*waiter).clone())),
+                };
+            }
+        }
+        visiting.remove(id);
+    }
+    resolved.insert(id.clone(), best.clone());
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::BriefId;
+    use chrono::Utc;
+
+    fn task(brief: &BriefId, seq: u32, priority: Option<&str>) -> Task {
+        let mut task = Task::new(TaskId::new(brief, seq), format!("Task {}", seq));
+        if let Some(p) = priority {
+            task.set_meta(PRIORITY_META_KEY, p);
+        }
+        task
+    }
+
+    #[test]
+    fn blockers_inherit_priority_through_chains() {
+        let brief = BriefId::new("Launch", Utc::now());
+        let release = task(&brief, 1, Some("high"));
+        let migrate = task(&brief, 2, Some("low"));
+        let backup = task(&brief, 3, None);
+        let docs = task(&brief, 4, Some("medium"));
+        let mut tasks: HashMap<TaskId, Task> = [release, migrate, backup, docs]
+            .into_iter()
+            .map(|t| (t.id.clone(), t))
+            .collect();
+        let id = |seq| TaskId::new(&brief, seq);
+        // release waits on migrate, which waits on backup
+        tasks.get_mut(&id(1)).unwrap().add_dependency(id(2));
+        tasks.get_mut(&id(2)).unwrap().add_dependency(id(3));
+
+        let effective = effective_priorities(&tasks);
+        assert_eq!(effective[&id(1)].priority, Priority::High);
+        assert_eq!(effective[&id(1)].inherited_from, None);
+        for seq in [2, 3] {
+            assert_eq!(effective[&id(seq)].priority, Priority::High);
+            assert_eq!(effective[&id(seq)].inherited_from, Some(id(1)));
+        }
+        assert_eq!(effective[&id(4)].priority, Priority::Medium);
+
+        // A finished task passes nothing on
+        tasks.get_mut(&id(1)).unwrap().complete();
+        let effective = effective_priorities(&tasks);
+        assert_eq!(effective[&id(2)].priority, Priority::Low);
+        assert_eq!(effective[&id(3)].inherited_from, None);
+    }
+}
@@ -0,0 +1,135 @@
+//! Resource lock domain model
+//!
+//! An agent can lock a path or subsystem it is changing (`src/auth`,
+//! `billing`) so other agents keep off it even when their tasks aren't
+//! linked by dependencies. A lock conflicts with a task whose scope overlaps
+//! it: the task's file links and its `scope` metadata.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::id::TaskId;
+use super::task::{LinkType, Task};
+
+/// Task metadata key naming the paths or subsystems a task touches
+pub const SCOPE_META_KEY: &str = "scope";
+
+/// A path or subsystem held by an agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceLock {
+    /// Locked path or subsystem, e.g. `src/auth`
+    pub resource: String,
+
+    /// Agent holding the lock
+    pub agent: String,
+
+    /// Task the lock was taken for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<TaskId>,
+
+    pub acquired_at: DateTime<Utc>,
+
+    /// When the lock lapses; held until released if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ResourceLock {
+    /// Creates a lock held from now
+    pub fn new(resource: impl Into<String>, agent: impl Into<String>) -> Self {
+        Self {
+            resource: normalize(&resource.into()),
+            agent: agent.into(),
+            task: None,
+            acquired_at: Utc::now(),
+            expires_at: None,
+        }
+    }
+
+    /// Returns true once the lock has lapsed
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+
+    /// Returns true if the lock covers part of `resource`, or `resource`
+    /// covers part of the lock
+    pub fn overlaps(&self, resource: &str) -> bool {
+        let ours: Vec<&str> = self.resource.split('/').collect();
+        let normalized = normalize(resource);
+        let theirs: Vec<&str> = normalized.split('/').collect();
+        if normalized.is_empty() || self.resource.is_empty() {
+            return false;
+        }
+        let shared = ours.len().min(theirs.len());
+        ours[..shared] == theirs[..shared]
+    }
+
+    /// Returns true if the lock overlaps any part of the task's scope
+    pub fn conflicts_with(&self, task: &Task) -> bool {
+        task_scope(task).iter().any(|scope| self.overlaps(scope))
+    }
+}
+
+/// Paths and subsystems a task touches: its file links and `meta.scope`
+/// (a string or a list)
+pub fn task_scope(task: &Task) -> Vec<&str> {
+    let mut scope: Vec<&str> = task
+        .links
+        .iter()
+        .filter(|l| l.link_type == LinkType::File)
+        .map(|l| l.reference.as_str())
+        .collect();
+    match task.get_meta(SCOPE_META_KEY) {
+        Some(serde_json::Value::String(s)) => scope.push(s),
+        Some(serde_json::Value::Array(items)) => {
+            scope.extend(items.iter().filter_map(|v| v.as_str()))
+        }
+        _ => {}
+    }
+    scope
+}
+
+/// `./src/auth/` → `src/auth`
+fn normalize(resource: &str) -> String {
+    let resource = resource.trim();
+    let resource = resource.strip_prefix("./").unwrap_or(resource);
+    resource.trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{BriefId, Link};
+
+    #[test]
+    fn overlap_is_by_path_component() {
+        let lock = ResourceLock::new("./src/auth/", "agent-a");
+        assert_eq!(lock.resource, "src/auth");
+        assert!(lock.overlaps("src/auth/login.rs"));
+        assert!(lock.overlaps("src"));
+        assert!(lock.overlaps("src/auth"));
+        assert!(!lock.overlaps("src/authz"));
+        assert!(!lock.overlaps("docs"));
+        assert!(!lock.overlaps(""));
+    }
+
+    #[test]
+    fn conflicts_through_file_links_and_scope_meta() {
+        let brief = BriefId::new("Auth", Utc::now());
+        let mut task = Task::new(TaskId::new(&brief, 1), "Login");
+        let lock = ResourceLock::new("billing", "agent-a");
+        assert!(!lock.conflicts_with(&task));
+
+        task.set_meta(SCOPE_META_KEY, serde_json::json!(["billing"]));
+        assert!(lock.conflicts_with(&task));
+
+        let mut other = Task::new(TaskId::new(&brief, 2), "Session");
+        other.links.push(Link {
+            link_type: LinkType::File,
+            reference: "src/auth/session.rs".to_string(),
+            at: Utc::now(),
+            by: None,
+        });
+        assert!(ResourceLock::new("src/auth", "agent-a").conflicts_with(&other));
+    }
+}
@@ -0,0 +1,58 @@
+//! Task reviews
+//!
+//! A review is requested from someone and later answered with a verdict.
+//! Each reviewer's latest review counts: a task is approved when at least
+//! one reviewer approved and none currently rejects.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewVerdict {
+    Approved,
+    Rejected,
+}
+
+impl ReviewVerdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewVerdict::Approved => "approved",
+            ReviewVerdict::Rejected => "rejected",
+        }
+    }
+}
+
+/// A review of a task, pending until it has a verdict
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Review {
+    /// Who is asked to review
+    pub reviewer: String,
+
+    /// Who asked for the review (None for unrequested reviews)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested_by: Option<String>,
+
+    /// When the review was requested or given
+    pub requested_at: DateTime<Utc>,
+
+    /// Verdict, once given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<ReviewVerdict>,
+
+    /// Reviewer's comment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+
+    /// When the verdict was given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+impl Review {
+    /// Returns true if no verdict has been given yet
+    pub fn is_pending(&self) -> bool {
+        self.verdict.is_none()
+    }
+}
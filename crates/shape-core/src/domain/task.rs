@@ -5,9 +5,18 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use super::external::ExternalIds;
+use super::freeze::FreezeInfo;
 use super::id::{BriefId, TaskId};
+use super::review::{Review, ReviewVerdict};
+
+/// Task metadata key marking a task split into subtasks
+pub const CONTAINER_META_KEY: &str = "container";
+
+/// Task metadata key marking a task only humans may claim
+pub const REQUIRES_HUMAN_META_KEY: &str = "requires_human";
 
 /// Type of dependency between tasks
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
@@ -49,40 +58,48 @@ pub struct Dependency {
     /// The type of dependency
     #[serde(rename = "type", default)]
     pub dep_type: DependencyType,
+    /// Days between the dependency finishing and this task starting, for
+    /// schedule projections (bake time); negative for a lead, letting this
+    /// task start before the dependency finishes. Doesn't affect readiness.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub lag_days: i64,
 }
 
 impl Dependency {
     /// Creates a new blocking dependency
     pub fn blocks(task: TaskId) -> Self {
-        Self {
-            task,
-            dep_type: DependencyType::Blocks,
-        }
+        Self::of_type(task, DependencyType::Blocks)
     }
 
     /// Creates a new provenance dependency
     pub fn provenance(task: TaskId) -> Self {
-        Self {
-            task,
-            dep_type: DependencyType::Provenance,
-        }
+        Self::of_type(task, DependencyType::Provenance)
     }
 
     /// Creates a new related dependency
     pub fn related(task: TaskId) -> Self {
-        Self {
-            task,
-            dep_type: DependencyType::Related,
-        }
+        Self::of_type(task, DependencyType::Related)
     }
 
     /// Creates a new duplicates dependency
     pub fn duplicates(task: TaskId) -> Self {
+        Self::of_type(task, DependencyType::Duplicates)
+    }
+
+    /// Creates a dependency of the given type, without lag
+    pub fn of_type(task: TaskId, dep_type: DependencyType) -> Self {
         Self {
             task,
-            dep_type: DependencyType::Duplicates,
+            dep_type,
+            lag_days: 0,
         }
     }
+
+    /// Sets the lag (negative for a lead)
+    pub fn with_lag(mut self, days: i64) -> Self {
+        self.lag_days = days;
+        self
+    }
 }
 
 /// Collection of dependencies with backward-compatible serialization
@@ -98,17 +115,23 @@ impl Dependencies {
         Self(Vec::new())
     }
 
-    /// Adds a dependency
+    /// Adds a dependency, or updates the lag of an existing one of the same
+    /// type. Returns false when nothing changed.
     pub fn add(&mut self, dep: Dependency) -> bool {
-        if !self
+        match self
             .0
-            .iter()
-            .any(|d| d.task == dep.task && d.dep_type == dep.dep_type)
+            .iter_mut()
+            .find(|d| d.task == dep.task && d.dep_type == dep.dep_type)
         {
-            self.0.push(dep);
-            true
-        } else {
-            false
+            Some(existing) if existing.lag_days == dep.lag_days => false,
+            Some(existing) => {
+                existing.lag_days = dep.lag_days;
+                true
+            }
+            None => {
+                self.0.push(dep);
+                true
+            }
         }
     }
 
@@ -169,6 +192,15 @@ impl Dependencies {
     pub fn contains_blocking(&self, task_id: &TaskId) -> bool {
         self.blocking().any(|d| &d.task == task_id)
     }
+
+    /// Rewrites dependency targets that `map` returns a new ID for
+    pub fn remap(&mut self, map: impl Fn(&TaskId) -> Option<TaskId>) {
+        for dep in &mut self.0 {
+            if let Some(new_id) = map(&dep.task) {
+                dep.task = new_id;
+            }
+        }
+    }
 }
 
 impl Serialize for Dependencies {
@@ -271,14 +303,24 @@ impl TaskStatus {
     }
 }
 
+impl std::fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskStatus::Todo => write!(f, "todo"),
+            TaskStatus::InProgress => write!(f, "in_progress"),
+            TaskStatus::Done => write!(f, "done"),
+        }
+    }
+}
+
 /// Metadata for a task - extensible key-value pairs
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
-pub struct TaskMeta(HashMap<String, serde_json::Value>);
+pub struct TaskMeta(BTreeMap<String, serde_json::Value>);
 
 impl TaskMeta {
     /// Creates empty metadata
     pub fn new() -> Self {
-        Self(HashMap::new())
+        Self(BTreeMap::new())
     }
 
     /// Gets a value by key
@@ -335,9 +377,13 @@ pub struct FieldVersions {
     #[serde(default, skip_serializing_if = "is_zero")]
     pub completed_at: i64,
 
+    /// Version timestamp for due_at field
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub due_at: i64,
+
     /// Per-key version timestamps for metadata fields
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub meta: HashMap<String, i64>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub meta: BTreeMap<String, i64>,
 }
 
 fn is_zero(val: &i64) -> bool {
@@ -353,7 +399,8 @@ impl FieldVersions {
             status: now,
             description: 0,
             completed_at: 0,
-            meta: HashMap::new(),
+            due_at: 0,
+            meta: BTreeMap::new(),
         }
     }
 
@@ -382,6 +429,11 @@ impl FieldVersions {
         self.completed_at = current_timestamp();
     }
 
+    /// Updates the due_at version to current timestamp
+    pub fn touch_due_at(&mut self) {
+        self.due_at = current_timestamp();
+    }
+
     /// Updates a metadata key's version to current timestamp
     pub fn touch_meta(&mut self, key: &str) {
         self.meta.insert(key.to_string(), current_timestamp());
@@ -398,6 +450,7 @@ impl FieldVersions {
             && self.status == 0
             && self.description == 0
             && self.completed_at == 0
+            && self.due_at == 0
             && self.meta.is_empty()
     }
 }
@@ -433,6 +486,10 @@ pub struct Task {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub completed_at: Option<DateTime<Utc>>,
 
+    /// When the task is due
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<DateTime<Utc>>,
+
     /// Optional description
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
@@ -483,6 +540,19 @@ pub struct Task {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub blocked: Option<BlockInfo>,
 
+    /// Freeze preventing changes until unfrozen
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frozen: Option<FreezeInfo>,
+
+    /// Position in the brief's backlog (lower first); unranked tasks follow
+    /// ranked ones (see [`by_rank`](super::by_rank))
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rank: Option<i64>,
+
+    /// Requested and completed reviews, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reviews: Vec<Review>,
+
     /// Task history/timeline
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub history: Vec<HistoryEvent>,
@@ -490,6 +560,10 @@ pub struct Task {
     /// Agent this task is assigned to (for handoff)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub assigned_to: Option<String>,
+
+    /// IDs of this task in external tools (e.g. `jira: ABC-123`)
+    #[serde(default, skip_serializing_if = "ExternalIds::is_empty")]
+    pub external_ids: ExternalIds,
 }
 
 /// A note added during task work
@@ -586,6 +660,12 @@ pub enum HistoryEventType {
     Unblocked,
     Assigned,
     Handoff,
+    Frozen,
+    Unfrozen,
+    ReviewRequested,
+    Reviewed,
+    Split,
+    BriefArchived,
 }
 
 impl Task {
@@ -600,6 +680,7 @@ impl Task {
             created_at: now,
             updated_at: now,
             completed_at: None,
+            due_at: None,
             description: None,
             meta: TaskMeta::new(),
             versions: FieldVersions::new(),
@@ -611,8 +692,12 @@ impl Task {
             notes: Vec::new(),
             links: Vec::new(),
             blocked: None,
+            frozen: None,
+            rank: None,
+            reviews: Vec::new(),
             history: Vec::new(),
             assigned_to: None,
+            external_ids: ExternalIds::new(),
         };
         task.add_history_event(HistoryEventType::Created, None, None);
         task
@@ -659,11 +744,14 @@ impl Task {
     }
 
     /// Transitions to in_progress status
+    ///
+    /// Status transitions are attributed to the claiming agent, if any.
     pub fn start(&mut self) {
         if self.status == TaskStatus::Todo {
             self.status = TaskStatus::InProgress;
             self.updated_at = Utc::now();
             self.versions.touch_status();
+            self.add_claimant_event(HistoryEventType::Started);
         }
     }
 
@@ -676,20 +764,39 @@ impl Task {
             self.completed_at = Some(now);
             self.versions.touch_status();
             self.versions.touch_completed_at();
+            self.add_claimant_event(HistoryEventType::Completed);
         }
     }
 
     /// Transitions back to todo status
     pub fn reopen(&mut self) {
         if self.status.is_complete() {
-            self.status = TaskStatus::Todo;
-            self.updated_at = Utc::now();
-            self.completed_at = None;
-            self.versions.touch_status();
-            self.versions.touch_completed_at();
+            self.mark_todo();
+            self.add_claimant_event(HistoryEventType::Reopened);
+        }
+    }
+
+    /// Transitions back to todo status, recording why. `cause` is the
+    /// dependency whose reopening invalidated this task's completion.
+    pub fn reopen_because(&mut self, reason: &str, agent: &str, cause: Option<&TaskId>) {
+        if self.status.is_complete() {
+            self.mark_todo();
+            let mut data = serde_json::json!({ "reason": reason });
+            if let Some(cause) = cause {
+                data["cause"] = serde_json::json!(cause.to_string());
+            }
+            self.add_history_event(HistoryEventType::Reopened, Some(agent), Some(data));
         }
     }
 
+    fn mark_todo(&mut self) {
+        self.status = TaskStatus::Todo;
+        self.updated_at = Utc::now();
+        self.completed_at = None;
+        self.versions.touch_status();
+        self.versions.touch_completed_at();
+    }
+
     /// Adds a blocking dependency on another task (default behavior)
     pub fn add_dependency(&mut self, task_id: TaskId) {
         self.add_typed_dependency(Dependency::blocks(task_id));
@@ -746,6 +853,14 @@ impl Task {
         self.versions.touch_description();
     }
 
+    /// Clears the description
+    pub fn clear_description(&mut self) {
+        if self.description.take().is_some() {
+            self.updated_at = Utc::now();
+            self.versions.touch_description();
+        }
+    }
+
     /// Sets the title
     pub fn set_title(&mut self, title: impl Into<String>) {
         self.title = title.into();
@@ -753,6 +868,18 @@ impl Task {
         self.versions.touch_title();
     }
 
+    /// Sets or clears the due date
+    pub fn set_due(&mut self, due_at: Option<DateTime<Utc>>) {
+        self.due_at = due_at;
+        self.updated_at = Utc::now();
+        self.versions.touch_due_at();
+    }
+
+    /// Returns true if the task is still open past its due date
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        !self.status.is_complete() && self.due_at.is_some_and(|due| due < now)
+    }
+
     /// Returns true if this task has been compacted into another task
     pub fn is_compacted(&self) -> bool {
         self.compacted_into.is_some()
@@ -781,6 +908,43 @@ impl Task {
         self.updated_at = Utc::now();
     }
 
+    /// Records this task's ID in an external system
+    pub fn set_external_id(&mut self, system: impl Into<String>, key: impl Into<String>) {
+        self.external_ids.insert(system.into(), key.into());
+        self.updated_at = Utc::now();
+    }
+
+    /// Forgets this task's ID in an external system
+    pub fn remove_external_id(&mut self, system: &str) -> bool {
+        let removed = self.external_ids.remove(system).is_some();
+        if removed {
+            self.updated_at = Utc::now();
+        }
+        removed
+    }
+
+    /// Rewrites every task ID this task holds (its own, dependencies,
+    /// blocker and compaction links) that `map` returns a new ID for
+    pub fn remap_ids(&mut self, map: impl Fn(&TaskId) -> Option<TaskId>) {
+        if let Some(new_id) = map(&self.id) {
+            self.id = new_id;
+        }
+        self.depends_on.remap(&map);
+        if let Some(on_task) = self.blocked.as_mut().and_then(|b| b.on_task.as_mut()) {
+            if let Some(new_id) = map(on_task) {
+                *on_task = new_id;
+            }
+        }
+        for id in self.compacted_tasks.iter_mut().flatten() {
+            if let Some(new_id) = map(id) {
+                *id = new_id;
+            }
+        }
+        if let Some(new_id) = self.compacted_into.as_ref().and_then(&map) {
+            self.compacted_into = Some(new_id);
+        }
+    }
+
     /// Clear compaction data (for undo)
     pub fn clear_compaction(&mut self) {
         self.summary = None;
@@ -821,6 +985,27 @@ impl Task {
         }
     }
 
+    /// When the task last moved to in progress, falling back to its last
+    /// update for tasks started without a recorded event
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.history
+            .iter()
+            .rev()
+            .find(|e| e.event == HistoryEventType::Started)
+            .map_or(self.updated_at, |e| e.at)
+    }
+
+    /// Time of the most recent note, link or history event
+    pub fn last_activity(&self) -> DateTime<Utc> {
+        self.history
+            .iter()
+            .map(|e| e.at)
+            .chain(self.notes.iter().map(|n| n.at))
+            .chain(self.links.iter().map(|l| l.at))
+            .max()
+            .unwrap_or(self.created_at)
+    }
+
     /// Returns true if the task is currently claimed
     pub fn is_claimed(&self) -> bool {
         self.claimed_by.is_some()
@@ -940,6 +1125,23 @@ impl Task {
         self.add_history_event(HistoryEventType::Blocked, Some(&agent), Some(data));
     }
 
+    /// Records that the task's brief was archived and what was decided for
+    /// the task (`cascade`); orphaned tasks also record the ID they had
+    pub fn record_brief_archived(
+        &mut self,
+        brief: &BriefId,
+        cascade: &str,
+        agent: &str,
+        from: Option<&TaskId>,
+    ) {
+        let mut data = serde_json::json!({ "brief": brief.to_string(), "cascade": cascade });
+        if let Some(from) = from {
+            data["from"] = serde_json::json!(from.to_string());
+        }
+        self.updated_at = Utc::now();
+        self.add_history_event(HistoryEventType::BriefArchived, Some(agent), Some(data));
+    }
+
     /// Unblocks the task
     pub fn unblock(&mut self, agent: Option<&str>) {
         if self.blocked.is_some() {
@@ -949,6 +1151,27 @@ impl Task {
         }
     }
 
+    /// Freezes the task so commands refuse to change it
+    pub fn freeze(&mut self, reason: impl Into<String>, agent: impl Into<String>) {
+        let info = FreezeInfo::new(reason, agent);
+        let data = serde_json::json!({ "reason": info.reason });
+        let agent = info.by.clone();
+        self.updated_at = info.at;
+        self.frozen = Some(info);
+        self.add_history_event(HistoryEventType::Frozen, Some(&agent), Some(data));
+    }
+
+    /// Lifts a freeze
+    pub fn unfreeze(&mut self, agent: Option<&str>) -> bool {
+        if self.frozen.take().is_some() {
+            self.updated_at = Utc::now();
+            self.add_history_event(HistoryEventType::Unfrozen, agent, None);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Returns true if the task is explicitly blocked (not just dependency blocked)
     pub fn is_explicitly_blocked(&self) -> bool {
         self.blocked.is_some()
@@ -1013,6 +1236,147 @@ impl Task {
         }
     }
 
+    /// Asks `reviewer` to review the task. Returns false if a review from
+    /// them is already pending.
+    pub fn request_review(&mut self, reviewer: &str, by: &str) -> bool {
+        if self.pending_review_from(reviewer).is_some() {
+            return false;
+        }
+        let now = Utc::now();
+        self.reviews.push(Review {
+            reviewer: reviewer.to_string(),
+            requested_by: Some(by.to_string()),
+            requested_at: now,
+            verdict: None,
+            comment: None,
+            decided_at: None,
+        });
+        self.updated_at = now;
+        let data = serde_json::json!({ "reviewer": reviewer });
+        self.add_history_event(HistoryEventType::ReviewRequested, Some(by), Some(data));
+        true
+    }
+
+    /// Records a verdict, answering the reviewer's pending request if any
+    pub fn record_review(
+        &mut self,
+        reviewer: &str,
+        verdict: ReviewVerdict,
+        comment: Option<String>,
+    ) {
+        let now = Utc::now();
+        let index = match self.pending_review_from(reviewer) {
+            Some(index) => index,
+            None => {
+                self.reviews.push(Review {
+                    reviewer: reviewer.to_string(),
+                    requested_by: None,
+                    requested_at: now,
+                    verdict: None,
+                    comment: None,
+                    decided_at: None,
+                });
+                self.reviews.len() - 1
+            }
+        };
+        let review = &mut self.reviews[index];
+        review.verdict = Some(verdict);
+        review.comment = comment.clone();
+        review.decided_at = Some(now);
+        self.updated_at = now;
+
+        let mut data = serde_json::json!({ "verdict": verdict });
+        if let Some(comment) = comment {
+            data["comment"] = serde_json::json!(comment);
+        }
+        self.add_history_event(HistoryEventType::Reviewed, Some(reviewer), Some(data));
+    }
+
+    /// Index of the reviewer's pending review
+    fn pending_review_from(&self, reviewer: &str) -> Option<usize> {
+        self.reviews
+            .iter()
+            .position(|r| r.reviewer == reviewer && r.is_pending())
+    }
+
+    /// Reviews still waiting for a verdict
+    pub fn pending_reviews(&self) -> impl Iterator<Item = &Review> {
+        self.reviews.iter().filter(|r| r.is_pending())
+    }
+
+    /// Returns true if some reviewer approved and no reviewer's latest
+    /// verdict is a rejection
+    pub fn is_approved(&self) -> bool {
+        let mut latest: HashMap<&str, ReviewVerdict> = HashMap::new();
+        for review in &self.reviews {
+            if let Some(verdict) = review.verdict {
+                latest.insert(&review.reviewer, verdict);
+            }
+        }
+        latest.values().any(|v| *v == ReviewVerdict::Approved)
+            && !latest.values().any(|v| *v == ReviewVerdict::Rejected)
+    }
+
+    /// Returns true if the task was split into subtasks and now tracks them
+    /// (`meta.container`)
+    pub fn is_container(&self) -> bool {
+        self.get_meta(CONTAINER_META_KEY)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Returns true if only humans may claim the task: `meta.requires_human`
+    /// is set or it has one of `human_labels`
+    pub fn requires_human<S: AsRef<str>>(&self, human_labels: &[S]) -> bool {
+        let flagged = self
+            .get_meta(REQUIRES_HUMAN_META_KEY)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        flagged
+            || self
+                .labels()
+                .iter()
+                .any(|l| human_labels.iter().any(|h| h.as_ref() == *l))
+    }
+
+    /// Turns the task into a container for `subtasks`: it depends on each of
+    /// them, so it only becomes ready once they are all done
+    pub fn mark_split(&mut self, subtasks: &[TaskId]) {
+        for id in subtasks {
+            self.depends_on.add(Dependency::blocks(id.clone()));
+        }
+        self.set_meta(CONTAINER_META_KEY, true);
+        self.updated_at = Utc::now();
+        let into: Vec<String> = subtasks.iter().map(|id| id.to_string()).collect();
+        let by = self.claimed_by.clone();
+        self.add_history_event(
+            HistoryEventType::Split,
+            by.as_deref(),
+            Some(serde_json::json!({ "into": into })),
+        );
+    }
+
+    /// Labels in `meta.labels`, in order
+    pub fn labels(&self) -> Vec<&str> {
+        self.get_meta("labels")
+            .and_then(|v| v.as_array())
+            .map(|labels| labels.iter().filter_map(|l| l.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns true if `meta.labels` contains `label`
+    pub fn has_label(&self, label: &str) -> bool {
+        self.get_meta("labels")
+            .and_then(|v| v.as_array())
+            .is_some_and(|labels| labels.iter().any(|l| l.as_str() == Some(label)))
+    }
+
+    /// Adds a history event attributed to the current claimant
+    fn add_claimant_event(&mut self, event: HistoryEventType) {
+        let by = self.claimed_by.clone();
+        self.add_history_event(event, by.as_deref(), None);
+    }
+
     /// Adds a history event
     fn add_history_event(
         &mut self,
@@ -1064,6 +1428,26 @@ mod tests {
         assert!(task.completed_at.is_none());
     }
 
+    #[test]
+    fn reopen_because_records_reason_and_cause() {
+        let mut task = make_task(1);
+        let cause = make_task(2).id;
+
+        // Not done yet: nothing to reopen
+        task.reopen_because("flaky", "alice", None);
+        assert_eq!(task.history.len(), 1);
+
+        task.complete();
+        task.reopen_because("regressed", "alice", Some(&cause));
+        assert_eq!(task.status, TaskStatus::Todo);
+        let event = task.history.last().unwrap();
+        assert_eq!(event.event, HistoryEventType::Reopened);
+        assert_eq!(event.by.as_deref(), Some("alice"));
+        let data = event.data.as_ref().unwrap();
+        assert_eq!(data["reason"], "regressed");
+        assert_eq!(data["cause"], cause.to_string());
+    }
+
     #[test]
     fn task_dependencies() {
         let mut task1 = make_task(1);
@@ -1257,6 +1641,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dependency_lag_serialization() {
+        let task1 = make_task(1);
+        let task2 = make_task(2);
+        let mut task3 = make_task(3);
+
+        task3.add_typed_dependency(Dependency::blocks(task1.id.clone()));
+        task3.add_typed_dependency(Dependency::blocks(task2.id.clone()).with_lag(2));
+
+        // Zero lag is left out, so tasks without lags serialize as before
+        let json = serde_json::to_value(&task3).unwrap();
+        assert!(json["depends_on"][0].get("lag_days").is_none());
+        assert_eq!(json["depends_on"][1]["lag_days"], 2);
+
+        let parsed: Task = serde_json::from_value(json).unwrap();
+        let lags: Vec<i64> = parsed.depends_on.iter().map(|d| d.lag_days).collect();
+        assert_eq!(lags, [0, 2]);
+
+        // Re-adding with a different lag replaces it
+        assert!(task3
+            .depends_on
+            .add(Dependency::blocks(task2.id.clone()).with_lag(-1)));
+        assert!(!task3
+            .depends_on
+            .add(Dependency::blocks(task2.id.clone()).with_lag(-1)));
+        assert_eq!(task3.depends_on.len(), 2);
+        assert_eq!(task3.depends_on.iter().nth(1).unwrap().lag_days, -1);
+    }
+
     #[test]
     fn dependency_type_label() {
         assert_eq!(DependencyType::Blocks.label(), "blocks");
@@ -1596,6 +2009,27 @@ mod tests {
         assert_eq!(task.history[0].event, HistoryEventType::Created);
     }
 
+    #[test]
+    fn status_transitions_record_history() {
+        let mut task = make_task(1);
+        task.claim("agent-1");
+        task.complete();
+        task.reopen();
+
+        let events: Vec<_> = task.history.iter().map(|e| e.event.clone()).collect();
+        assert_eq!(
+            events,
+            vec![
+                HistoryEventType::Created,
+                HistoryEventType::Started,
+                HistoryEventType::Claimed,
+                HistoryEventType::Completed,
+                HistoryEventType::Reopened,
+            ]
+        );
+        assert_eq!(task.history[3].by.as_deref(), Some("agent-1"));
+    }
+
     #[test]
     fn reclaim_does_not_duplicate_start_event() {
         let mut task = make_task(1);
@@ -1647,4 +2081,41 @@ mod tests {
         assert!(parsed.blocked.is_some());
         assert!(!parsed.history.is_empty());
     }
+
+    #[test]
+    fn latest_verdict_per_reviewer_decides_approval() {
+        let mut task = make_task(1);
+        assert!(task.request_review("bob", "alice"));
+        assert!(!task.request_review("bob", "alice"));
+        assert_eq!(task.pending_reviews().count(), 1);
+        assert!(!task.is_approved());
+
+        task.record_review("bob", ReviewVerdict::Rejected, Some("Needs tests".into()));
+        assert_eq!(task.pending_reviews().count(), 0);
+        assert_eq!(task.reviews.len(), 1);
+        assert!(!task.is_approved());
+
+        // A later approval from the same reviewer replaces the rejection
+        task.record_review("bob", ReviewVerdict::Approved, None);
+        assert_eq!(task.reviews.len(), 2);
+        assert!(task.is_approved());
+
+        task.record_review("carol", ReviewVerdict::Rejected, None);
+        assert!(!task.is_approved());
+    }
+
+    #[test]
+    fn open_tasks_past_due_are_overdue() {
+        let now = Utc::now();
+        let mut task = make_task(1);
+        assert!(!task.is_overdue(now));
+
+        task.set_due(Some(now - chrono::Duration::days(1)));
+        assert!(task.is_overdue(now));
+        assert!(task.versions.due_at > 0);
+
+        task.start();
+        task.complete();
+        assert!(!task.is_overdue(now));
+    }
 }
@@ -0,0 +1,220 @@
+//! Team directory and @-mentions
+//!
+//! A project can list the humans and agents working on it, each under a
+//! handle. Notes and brief bodies mention members as `@handle`; a member
+//! also answers to its aliases, e.g. the agent name it claims tasks under.
+//!
+//! Agents can also list [`Interests`]: labels they specialize in, and labels
+//! they won't take, which steer `shape next` toward the right tasks.
+
+use serde::{Deserialize, Serialize};
+
+/// Whether a member is a person or an agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemberKind {
+    #[default]
+    Human,
+    Agent,
+}
+
+impl MemberKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MemberKind::Human => "human",
+            MemberKind::Agent => "agent",
+        }
+    }
+}
+
+/// Someone who can be mentioned
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamMember {
+    /// Name mentions use, without the `@`
+    pub handle: String,
+
+    /// Display name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(default)]
+    pub kind: MemberKind,
+
+    /// Other names the member goes by, such as its agent name or `$USER`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
+    /// Task labels the member prefers; `-label` disclaims one (see
+    /// [`Interests`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interests: Vec<String>,
+}
+
+impl TeamMember {
+    pub fn new(handle: impl Into<String>, kind: MemberKind) -> Self {
+        Self {
+            handle: handle.into(),
+            name: None,
+            kind,
+            aliases: Vec::new(),
+            interests: Vec::new(),
+        }
+    }
+
+    /// Returns true if `name` is the member's handle or one of its aliases,
+    /// ignoring case
+    pub fn answers_to(&self, name: &str) -> bool {
+        let name = name.trim_start_matches('@');
+        self.handle.eq_ignore_ascii_case(name)
+            || self.aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Labels an agent wants to work on and labels it disclaims, written as
+/// `backend`, `-frontend`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Interests {
+    pub wanted: Vec<String>,
+    pub disclaimed: Vec<String>,
+}
+
+impl Interests {
+    /// Parses interest entries; a leading `-` disclaims the label. Blank
+    /// entries are ignored.
+    pub fn parse<S: AsRef<str>>(entries: &[S]) -> Self {
+        let mut interests = Self::default();
+        for entry in entries.iter().map(|e| e.as_ref().trim()) {
+            match entry.strip_prefix('-') {
+                Some(label) if !label.trim().is_empty() => {
+                    interests.disclaimed.push(label.trim().to_string())
+                }
+                Some(_) => {}
+                None if !entry.is_empty() => interests.wanted.push(entry.to_string()),
+                None => {}
+            }
+        }
+        interests
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.wanted.is_empty() && self.disclaimed.is_empty()
+    }
+
+    /// The wanted labels among `labels`
+    pub fn matching<'a>(&self, labels: &[&'a str]) -> Vec<&'a str> {
+        labels
+            .iter()
+            .copied()
+            .filter(|l| self.wanted.iter().any(|w| w == l))
+            .collect()
+    }
+
+    /// Returns true if any of `labels` is disclaimed
+    pub fn disclaims(&self, labels: &[&str]) -> bool {
+        labels
+            .iter()
+            .any(|l| self.disclaimed.iter().any(|d| d == l))
+    }
+}
+
+/// The members of a project, ordered by handle
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Team {
+    #[serde(default, rename = "member")]
+    pub members: Vec<TeamMember>,
+}
+
+impl Team {
+    /// The member answering to `name`, if any
+    pub fn find(&self, name: &str) -> Option<&TeamMember> {
+        self.members.iter().find(|m| m.answers_to(name))
+    }
+
+    /// Mentions in `text` that match no member
+    pub fn unknown_mentions(&self, text: &str) -> Vec<String> {
+        mentions(text)
+            .into_iter()
+            .filter(|m| self.find(m).is_none())
+            .collect()
+    }
+}
+
+/// Returns true if `c` can be part of a handle
+fn is_handle_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+/// Returns true if `handle` is usable as a mention target
+pub fn is_valid_handle(handle: &str) -> bool {
+    !handle.is_empty()
+        && handle.chars().all(is_handle_char)
+        && handle.starts_with(|c: char| c.is_alphanumeric())
+        && !handle.ends_with('.')
+}
+
+/// Handles mentioned in `text` as `@handle`, without the `@`, in order of
+/// first appearance. An `@` inside a word (an email address) or inside
+/// backticks is not a mention.
+pub fn mentions(text: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let mut in_code = false;
+        let mut prev: Option<char> = None;
+        for (i, c) in line.char_indices() {
+            if c == '`' {
+                in_code = !in_code;
+            } else if c == '@' && !in_code && !prev.is_some_and(|p| is_handle_char(p) || p == '@') {
+                let rest = &line[i + 1..];
+                let end = rest
+                    .find(|c: char| !is_handle_char(c))
+                    .unwrap_or(rest.len());
+                // Sentence punctuation after a mention isn't part of it
+                let handle = rest[..end].trim_end_matches('.');
+                if is_valid_handle(handle) && !found.iter().any(|f| f == handle) {
+                    found.push(handle.to_string());
+                }
+            }
+            prev = Some(c);
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_mentions_outside_emails_and_code() {
+        let text = "@alice can you check this with @bob-2?\n\
+                    Mail ops@example.com, not `@carol`. Thanks @dave.\n\
+                    cc @alice";
+        assert_eq!(mentions(text), vec!["alice", "bob-2", "dave"]);
+        assert!(mentions("@ nobody, @.dot").is_empty());
+    }
+
+    #[test]
+    fn interests_split_wanted_and_disclaimed() {
+        let interests = Interests::parse(&["backend", " -frontend", "db", "-", ""]);
+        assert_eq!(interests.wanted, vec!["backend", "db"]);
+        assert_eq!(interests.disclaimed, vec!["frontend"]);
+        assert_eq!(interests.matching(&["db", "ops"]), vec!["db"]);
+        assert!(interests.disclaims(&["ops", "frontend"]));
+        assert!(!interests.disclaims(&["backend"]));
+    }
+
+    #[test]
+    fn team_matches_handles_and_aliases() {
+        let mut bot = TeamMember::new("reviewer", MemberKind::Agent);
+        bot.aliases.push("review-bot".to_string());
+        let team = Team {
+            members: vec![TeamMember::new("alice", MemberKind::Human), bot],
+        };
+        assert_eq!(team.find("@Alice").unwrap().handle, "alice");
+        assert_eq!(team.find("review-bot").unwrap().handle, "reviewer");
+        assert_eq!(
+            team.unknown_mentions("@alice @review-bot @mallory"),
+            vec!["mallory"]
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! Subtask hierarchy
+//!
+//! Task IDs nest: `b-7f2a3b1.1.2` is a subtask of `b-7f2a3b1.1`. [`TaskTree`]
+//! indexes a set of tasks by parent so the hierarchy can be walked and its
+//! status rolled up.
+//!
+//! [`Progress`] counts leaf tasks, so a task split into four subtasks weighs
+//! four rather than one and a parent never counts twice next to its own
+//! subtasks. A done task counts its whole subtree as done.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::{Task, TaskId, TaskStatus};
+
+/// Done and total leaf tasks of a subtree
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Progress {
+    /// Share done, rounded down (0 for an empty subtree)
+    pub fn percent(&self) -> usize {
+        (self.done * 100).checked_div(self.total).unwrap_or(0)
+    }
+
+    fn add(self, other: Progress) -> Progress {
+        Progress {
+            done: self.done + other.done,
+            total: self.total + other.total,
+        }
+    }
+}
+
+/// Tasks arranged by subtask parent
+#[derive(Debug)]
+pub struct TaskTree<'a> {
+    /// Tasks whose parent isn't in the set, in sequence order
+    roots: Vec<&'a Task>,
+    /// Subtasks of each task, in sequence order
+    children: HashMap<&'a TaskId, Vec<&'a Task>>,
+}
+
+impl<'a> TaskTree<'a> {
+    /// Builds the tree of `tasks`. A subtask whose parent is missing from
+    /// the set becomes a root.
+    pub fn new(tasks: impl IntoIterator<Item = &'a Task>) -> Self {
+        let tasks: HashMap<&TaskId, &Task> = tasks.into_iter().map(|t| (&t.id, t)).collect();
+        let mut roots = Vec::new();
+        let mut children: HashMap<&TaskId, Vec<&Task>> = HashMap::new();
+        for task in tasks.values() {
+            match task.id.parent().and_then(|p| tasks.get_key_value(&p)) {
+                Some((parent, _)) => children.entry(*parent).or_default().push(task),
+                None => roots.push(*task),
+            }
+        }
+        roots.sort_by(|a, b| sequence_cmp(&a.id, &b.id));
+        for siblings in children.values_mut() {
+            siblings.sort_by(|a, b| sequence_cmp(&a.id, &b.id));
+        }
+        Self { roots, children }
+    }
+
+    /// Top-level tasks of the set, in sequence order
+    pub fn roots(&self) -> &[&'a Task] {
+        &self.roots
+    }
+
+    /// Direct subtasks of `id`, in sequence order
+    pub fn children(&self, id: &TaskId) -> &[&'a Task] {
+        self.children.get(id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Leaf progress of `task` and its subtasks
+    pub fn progress(&self, task: &Task) -> Progress {
+        let children = self.children(&task.id);
+        if children.is_empty() {
+            let done = task.status.is_complete() as usize;
+            return Progress { done, total: 1 };
+        }
+        let below = children.iter().fold(Progress::default(), |sum, child| {
+            sum.add(self.progress(child))
+        });
+        if task.status.is_complete() {
+            Progress {
+                done: below.total,
+                total: below.total,
+            }
+        } else {
+            below
+        }
+    }
+
+    /// Leaf progress of the whole set
+    pub fn total_progress(&self) -> Progress {
+        self.roots.iter().fold(Progress::default(), |sum, root| {
+            sum.add(self.progress(root))
+        })
+    }
+
+    /// Status of `task` counting its subtasks: done once it or all of its
+    /// subtasks are done, in progress once any work under it started
+    pub fn rollup_status(&self, task: &Task) -> TaskStatus {
+        let children = self.children(&task.id);
+        if task.status.is_complete() || children.is_empty() {
+            return task.status;
+        }
+        let statuses: Vec<TaskStatus> = children.iter().map(|c| self.rollup_status(c)).collect();
+        if statuses.iter().all(|s| s.is_complete()) {
+            TaskStatus::Done
+        } else if task.status == TaskStatus::InProgress
+            || statuses.iter().any(|s| *s != TaskStatus::Todo)
+        {
+            TaskStatus::InProgress
+        } else {
+            TaskStatus::Todo
+        }
+    }
+}
+
+/// ID order with sequence numbers compared as numbers, so `.10` follows `.9`
+fn sequence_cmp(a: &TaskId, b: &TaskId) -> Ordering {
+    let root = |id: &TaskId| {
+        let id = id.to_string();
+        id.split('.').next().unwrap_or_default().to_string()
+    };
+    root(a)
+        .cmp(&root(b))
+        .then_with(|| a.segments().cmp(b.segments()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::BriefId;
+
+    fn task(id: &TaskId, status: TaskStatus) -> Task {
+        let mut task = Task::new(id.clone(), "t");
+        task.status = status;
+        task
+    }
+
+    #[test]
+    fn progress_counts_leaves_and_done_subtrees() {
+        let brief = BriefId::new("Tree", chrono::Utc::now());
+        let one = TaskId::new(&brief, 1);
+        let two = TaskId::new(&brief, 2);
+        let tasks = vec![
+            task(&one, TaskStatus::Todo),
+            task(&one.subtask(1), TaskStatus::Done),
+            task(&one.subtask(2), TaskStatus::Todo),
+            task(&one.subtask(2).subtask(1), TaskStatus::InProgress),
+            task(&one.subtask(2).subtask(2), TaskStatus::Done),
+            task(&two, TaskStatus::Done),
+            task(&two.subtask(1), TaskStatus::Todo),
+            task(&two.subtask(2), TaskStatus::Todo),
+        ];
+        let tree = TaskTree::new(&tasks);
+
+        let roots: Vec<&TaskId> = tree.roots().iter().map(|t| &t.id).collect();
+        assert_eq!(roots, [&one, &two]);
+        assert_eq!(tree.children(&one).len(), 2);
+        assert_eq!(tree.progress(&tasks[0]), Progress { done: 2, total: 3 });
+        assert_eq!(tree.progress(&tasks[5]), Progress { done: 2, total: 2 });
+        assert_eq!(tree.total_progress(), Progress { done: 4, total: 5 });
+        assert_eq!(tree.total_progress().percent(), 80);
+        assert_eq!(tree.rollup_status(&tasks[0]), TaskStatus::InProgress);
+    }
+
+    #[test]
+    fn rollup_is_done_when_all_subtasks_are() {
+        let brief = BriefId::new("Rollup", chrono::Utc::now());
+        let parent = TaskId::new(&brief, 1);
+        let mut tasks = vec![
+            task(&parent, TaskStatus::Todo),
+            task(&parent.subtask(1), TaskStatus::Todo),
+            task(&parent.subtask(10), TaskStatus::Todo),
+        ];
+        let tree = TaskTree::new(&tasks);
+        assert_eq!(tree.rollup_status(&tasks[0]), TaskStatus::Todo);
+        let order: Vec<&TaskId> = tree.children(&parent).iter().map(|t| &t.id).collect();
+        assert_eq!(order, [&parent.subtask(1), &parent.subtask(10)]);
+
+        tasks[1].status = TaskStatus::Done;
+        tasks[2].status = TaskStatus::Done;
+        let tree = TaskTree::new(&tasks);
+        assert_eq!(tree.rollup_status(&tasks[0]), TaskStatus::Done);
+
+        // A subtask whose parent isn't in the set is a root
+        let tree = TaskTree::new(&tasks[1..]);
+        assert_eq!(tree.roots().len(), 2);
+    }
+}
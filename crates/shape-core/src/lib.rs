@@ -0,0 +1,35 @@
+//! # Shape Core
+//!
+//! The data model and storage of [Shape](https://github.com/shape-cli/shape)
+//! projects, without the command-line interface: no clap, ratatui or daemon
+//! dependencies. The `shape-cli` crate builds the `shape` binary on top of
+//! this one and re-exports both modules unchanged.
+//!
+//! - [`domain`] - Briefs, tasks, IDs and the dependency graph
+//! - [`storage`] - The `.shape/` stores: markdown briefs, JSONL tasks, config
+//!
+//! ```no_run
+//! use shape_core::storage::Project;
+//!
+//! let project = Project::open(".")?;
+//! let tasks = project.task_store().read_all()?;
+//! println!("{} tasks", tasks.len());
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! ## Stability
+//!
+//! The crate follows semver. The types re-exported here and the public items
+//! of [`domain`] and [`storage`] are its API; the on-disk format is versioned
+//! separately (see [`storage::FORMAT_VERSION`]) and migrated by `shape migrate`.
+//!
+//! ## Features
+//!
+//! - `cache` (default) - the SQLite cache behind search and fast queries
+//!   ([`storage::Cache`], [`storage::Project::cache`])
+
+pub mod domain;
+pub mod storage;
+
+pub use domain::{Brief, BriefId, BriefStatus, DependencyGraph, Task, TaskId, TaskStatus};
+pub use storage::{BriefStore, Config, Project, TaskStore};
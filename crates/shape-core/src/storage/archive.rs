@@ -0,0 +1,79 @@
+//! Archive of shipped briefs
+//!
+//! `shape maintenance archive-shipped` moves old shipped briefs and their
+//! done tasks out of the live stores into `.shape/archive/`, laid out like
+//! `.shape/` itself (`tasks.jsonl`, `briefs/`). Commands that read the live
+//! stores stay fast as the project ages; list and search commands read the
+//! archive too with `--include-archived`.
+//!
+//! An encrypted project keeps a copy of its `encryption.json` in the archive,
+//! so the stores there are sealed and opened with the same passphrase. The
+//! archived briefs' index holds plaintext frontmatter, so it lives under
+//! `.cache/` rather than next to them, where `.gitignore` files written
+//! before the archive existed would let it be committed.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use super::encryption::ENCRYPTION_FILE;
+
+/// Directory holding the archived stores
+pub fn dir(shape_dir: &Path) -> PathBuf {
+    shape_dir.join("archive")
+}
+
+/// Index of the archived briefs
+pub fn index_path(shape_dir: &Path) -> PathBuf {
+    shape_dir
+        .join(".cache")
+        .join("archive")
+        .join("briefs.index.jsonl")
+}
+
+/// Removes the index older versions kept in `archive/briefs/`
+pub fn remove_legacy_index(shape_dir: &Path) -> Result<()> {
+    let path = dir(shape_dir).join("briefs").join("index.jsonl");
+    match fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Makes the archive's encryption settings match the project's: copies
+/// `encryption.json` in when the project is encrypted, removes it otherwise
+pub fn mirror_encryption(shape_dir: &Path) -> Result<()> {
+    let live = shape_dir.join(ENCRYPTION_FILE);
+    let archived = dir(shape_dir).join(ENCRYPTION_FILE);
+    if live.is_file() {
+        fs::create_dir_all(dir(shape_dir))?;
+        fs::copy(&live, &archived).with_context(|| format!("Failed to copy {}", live.display()))?;
+    } else if archived.is_file() {
+        fs::remove_file(&archived)
+            .with_context(|| format!("Failed to remove {}", archived.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn mirror_encryption_follows_project() {
+        let shape_dir = TempDir::new().unwrap();
+        let archived = dir(shape_dir.path()).join(ENCRYPTION_FILE);
+
+        fs::write(shape_dir.path().join(ENCRYPTION_FILE), "{}").unwrap();
+        mirror_encryption(shape_dir.path()).unwrap();
+        assert!(archived.is_file());
+
+        fs::remove_file(shape_dir.path().join(ENCRYPTION_FILE)).unwrap();
+        mirror_encryption(shape_dir.path()).unwrap();
+        assert!(!archived.exists());
+    }
+}
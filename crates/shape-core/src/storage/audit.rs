@@ -0,0 +1,97 @@
+//! Audit log
+//!
+//! Overrides of guard rails (such as changing a frozen task) are appended to
+//! `.shape/audit.jsonl`. The file is committed with the project so overrides
+//! show up in review.
+
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+
+fn audit_path(shape_dir: &Path) -> PathBuf {
+    shape_dir.join("audit.jsonl")
+}
+
+/// One audited action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the action happened
+    pub at: DateTime<Utc>,
+
+    /// Who performed it
+    pub by: String,
+
+    /// Brief or task the action touched
+    pub id: String,
+
+    /// What was done, e.g. `task done`
+    pub action: String,
+
+    /// Justification given for the override
+    pub reason: String,
+}
+
+/// Appends an entry
+pub(crate) fn append(shape_dir: &Path, entry: &AuditEntry) -> Result<()> {
+    let path = audit_path(shape_dir);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open audit log: {}", path.display()))?;
+    file.lock_exclusive()?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Reads all entries, oldest first
+pub fn read(shape_dir: &Path) -> Result<Vec<AuditEntry>> {
+    let path = audit_path(shape_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file = fs::File::open(&path)
+        .with_context(|| format!("Failed to open audit log: {}", path.display()))?;
+    let mut entries = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line)
+            .with_context(|| format!("Invalid audit entry on line {}", i + 1))?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn entries_round_trip() {
+        let dir = TempDir::new().unwrap();
+        assert!(read(dir.path()).unwrap().is_empty());
+
+        let entry = AuditEntry {
+            at: Utc::now(),
+            by: "alice".to_string(),
+            id: "b-1234567.1".to_string(),
+            action: "task done".to_string(),
+            reason: "hotfix".to_string(),
+        };
+        append(dir.path(), &entry).unwrap();
+        append(dir.path(), &entry).unwrap();
+
+        let entries = read(dir.path()).unwrap();
+        assert_eq!(entries, vec![entry.clone(), entry]);
+    }
+}
@@ -0,0 +1,75 @@
+//! Benchmark baselines
+//!
+//! `shape bench` times key operations on the project and compares them with
+//! the baseline in `.shape/.cache/bench.json`, written by the first run and
+//! by `shape bench --save-baseline`. Like the rest of `.cache/` the file is
+//! local to the machine it was measured on.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+fn bench_path(shape_dir: &Path) -> PathBuf {
+    shape_dir.join(".cache").join("bench.json")
+}
+
+/// Recorded timings to compare later runs against
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchBaseline {
+    pub recorded_at: DateTime<Utc>,
+
+    /// Project size when recorded
+    pub tasks: usize,
+    pub briefs: usize,
+
+    /// Median time of each operation in microseconds
+    pub operations: BTreeMap<String, u64>,
+}
+
+/// Reads the baseline, if one was recorded
+pub fn read(shape_dir: &Path) -> Result<Option<BenchBaseline>> {
+    let path = bench_path(shape_dir);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let baseline = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(baseline))
+}
+
+/// Replaces the baseline
+pub fn write(shape_dir: &Path, baseline: &BenchBaseline) -> Result<()> {
+    let path = bench_path(shape_dir);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(baseline)?;
+    fs::write(&path, content + "\n").with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn baseline_round_trips() {
+        let dir = TempDir::new().unwrap();
+        assert!(read(dir.path()).unwrap().is_none());
+
+        let baseline = BenchBaseline {
+            recorded_at: Utc::now(),
+            tasks: 3,
+            briefs: 1,
+            operations: BTreeMap::from([("read_all".to_string(), 420)]),
+        };
+        write(dir.path(), &baseline).unwrap();
+        assert_eq!(read(dir.path()).unwrap(), Some(baseline));
+    }
+}
@@ -0,0 +1,138 @@
+//! JSONL storage for betting tables
+//!
+//! Betting tables are stored in `.shape/bets.jsonl`, one table per line in
+//! the order they were opened. At most one table is open at a time.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use fs2::FileExt;
+
+use crate::domain::BettingTable;
+
+/// Store for betting tables in JSONL format
+pub struct BetStore {
+    path: PathBuf,
+}
+
+impl BetStore {
+    /// Creates a new betting table store at the given path
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Creates the default store for a project
+    pub fn for_project(project_root: &Path) -> Self {
+        Self::new(project_root.join(".shape").join("bets.jsonl"))
+    }
+
+    /// Reads all tables, oldest first
+    pub fn read_all(&self) -> Result<Vec<BettingTable>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let file = File::open(&self.path)
+            .with_context(|| format!("Failed to open bet store: {}", self.path.display()))?;
+        file.lock_shared()
+            .context("Failed to acquire read lock on bet store")?;
+
+        let mut tables = Vec::new();
+        for (line_num, line) in BufReader::new(&file).lines().enumerate() {
+            let line = line.with_context(|| format!("Failed to read line {}", line_num + 1))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let table: BettingTable = serde_json::from_str(&line).with_context(|| {
+                format!("Failed to parse betting table at line {}", line_num + 1)
+            })?;
+            tables.push(table);
+        }
+        Ok(tables)
+    }
+
+    /// The open table, if any
+    pub fn open_table(&self) -> Result<Option<BettingTable>> {
+        Ok(self.read_all()?.into_iter().find(|t| t.is_open()))
+    }
+
+    /// The table for a cycle
+    pub fn read(&self, cycle: &str) -> Result<Option<BettingTable>> {
+        Ok(self.read_all()?.into_iter().find(|t| t.cycle == cycle))
+    }
+
+    /// Adds a table or replaces the one for the same cycle
+    pub fn upsert(&self, table: &BettingTable) -> Result<()> {
+        let mut tables = self.read_all()?;
+        match tables.iter_mut().find(|t| t.cycle == table.cycle) {
+            Some(existing) => *existing = table.clone(),
+            None => tables.push(table.clone()),
+        }
+        self.write_all(&tables)
+    }
+
+    /// Writes all tables atomically (temp file + rename)
+    fn write_all(&self, tables: &[BettingTable]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        let temp_path = self.path.with_extension("jsonl.tmp");
+        {
+            let file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&temp_path)
+                .with_context(|| format!("Failed to create temp file: {}", temp_path.display()))?;
+            file.lock_exclusive()
+                .context("Failed to acquire write lock on bet store")?;
+
+            let mut writer = BufWriter::new(&file);
+            for table in tables {
+                let line =
+                    serde_json::to_string(table).context("Failed to serialize betting table")?;
+                writeln!(writer, "{}", line).context("Failed to write betting table")?;
+            }
+            writer.flush().context("Failed to flush bet store")?;
+        }
+
+        fs::rename(&temp_path, &self.path).with_context(|| {
+            format!(
+                "Failed to rename {} to {}",
+                temp_path.display(),
+                self.path.display()
+            )
+        })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn tracks_the_open_table() {
+        let dir = TempDir::new().unwrap();
+        let store = BetStore::for_project(dir.path());
+        assert!(store.open_table().unwrap().is_none());
+
+        let mut first = BettingTable::open("c-1");
+        store.upsert(&first).unwrap();
+        assert_eq!(store.open_table().unwrap().unwrap().cycle, "c-1");
+
+        first.close(1);
+        store.upsert(&first).unwrap();
+        store.upsert(&BettingTable::open("c-2")).unwrap();
+
+        assert_eq!(store.read_all().unwrap().len(), 2);
+        assert!(!store.read("c-1").unwrap().unwrap().is_open());
+        assert_eq!(store.open_table().unwrap().unwrap().cycle, "c-2");
+    }
+}
@@ -7,13 +7,13 @@
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use anyhow::{Context, Result};
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
 use thiserror::Error;
 
-use crate::domain::{Brief, BriefId, Task, TaskId, TaskStatus};
+use crate::domain::{Brief, BriefId, ExternalIds, Task, TaskId, TaskStatus};
 
 #[derive(Debug, Error)]
 pub enum CacheError {
@@ -30,6 +30,9 @@ pub enum CacheError {
     Io(#[from] std::io::Error),
 }
 
+/// How long a connection waits on another process's write lock
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// SQLite cache for fast queries
 pub struct Cache {
     /// Path to the SQLite database
@@ -47,11 +50,15 @@ pub struct Cache {
 
 impl Cache {
     /// Schema version - bump when schema changes to force rebuild
-    const SCHEMA_VERSION: i32 = 1;
+    const SCHEMA_VERSION: i32 = 3;
 
     /// Creates or opens the cache for a project
     pub fn open(project_root: &Path) -> Result<Self> {
-        let shape_dir = project_root.join(".shape");
+        Self::open_in(&project_root.join(".shape"))
+    }
+
+    /// Creates or opens the cache for the stores in `shape_dir`
+    pub(crate) fn open_in(shape_dir: &Path) -> Result<Self> {
         let cache_dir = shape_dir.join(".cache");
         let db_path = cache_dir.join("shape.db");
         let tasks_path = shape_dir.join("tasks.jsonl");
@@ -65,6 +72,9 @@ impl Cache {
         let conn = Connection::open(&db_path)
             .with_context(|| format!("Failed to open cache database: {}", db_path.display()))?;
 
+        // Wait for other processes' writes instead of failing with SQLITE_BUSY
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+
         // Enable WAL mode for better concurrent access
         conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;")?;
 
@@ -82,11 +92,21 @@ impl Cache {
 
     /// Ensures the schema is up to date
     fn ensure_schema(&mut self) -> Result<()> {
-        let current_version = self.get_schema_version()?;
+        if self.get_schema_version()? == Self::SCHEMA_VERSION {
+            return Ok(());
+        }
 
-        if current_version != Self::SCHEMA_VERSION {
-            self.create_schema()?;
+        // Several processes may find the cache missing at once. The write
+        // transaction serializes them and the re-check keeps later ones from
+        // recreating the schema the first one just built.
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let version: i32 = tx.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version != Self::SCHEMA_VERSION {
+            Self::create_schema(&tx)?;
         }
+        tx.commit()?;
 
         Ok(())
     }
@@ -103,9 +123,9 @@ impl Cache {
     }
 
     /// Creates the schema from scratch
-    fn create_schema(&mut self) -> Result<()> {
+    fn create_schema(conn: &Connection) -> Result<()> {
         // Drop existing tables
-        self.conn.execute_batch(
+        conn.execute_batch(
             "
             DROP TABLE IF EXISTS dependencies;
             DROP TABLE IF EXISTS tasks;
@@ -117,10 +137,11 @@ impl Cache {
         )?;
 
         // Create tables
-        self.conn.execute_batch(
+        conn.execute_batch(
             "
             CREATE TABLE tasks (
                 id TEXT PRIMARY KEY,
+                sort_key TEXT NOT NULL,
                 brief_id TEXT,
                 title TEXT NOT NULL,
                 status TEXT NOT NULL,
@@ -129,7 +150,8 @@ impl Cache {
                 completed_at TEXT,
                 description TEXT,
                 meta TEXT,
-                depends_on TEXT
+                depends_on TEXT,
+                external_ids TEXT
             );
 
             CREATE TABLE briefs (
@@ -140,7 +162,8 @@ impl Cache {
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL,
                 file_path TEXT NOT NULL,
-                body TEXT
+                body TEXT,
+                external_ids TEXT
             );
 
             CREATE TABLE dependencies (
@@ -156,6 +179,7 @@ impl Cache {
 
             CREATE INDEX idx_tasks_brief ON tasks(brief_id);
             CREATE INDEX idx_tasks_status ON tasks(status);
+            CREATE INDEX idx_tasks_sort ON tasks(sort_key);
             CREATE INDEX idx_deps_blocked ON dependencies(depends_on_id);
             CREATE INDEX idx_briefs_status ON briefs(status);
 
@@ -164,6 +188,7 @@ impl Cache {
                 id,
                 title,
                 description,
+                external_ids,
                 content='tasks',
                 content_rowid='rowid'
             );
@@ -172,49 +197,50 @@ impl Cache {
                 id,
                 title,
                 body,
+                external_ids,
                 content='briefs',
                 content_rowid='rowid'
             );
 
             -- Triggers to keep FTS in sync
             CREATE TRIGGER tasks_ai AFTER INSERT ON tasks BEGIN
-                INSERT INTO tasks_fts(rowid, id, title, description)
-                VALUES (NEW.rowid, NEW.id, NEW.title, NEW.description);
+                INSERT INTO tasks_fts(rowid, id, title, description, external_ids)
+                VALUES (NEW.rowid, NEW.id, NEW.title, NEW.description, NEW.external_ids);
             END;
 
             CREATE TRIGGER tasks_ad AFTER DELETE ON tasks BEGIN
-                INSERT INTO tasks_fts(tasks_fts, rowid, id, title, description)
-                VALUES ('delete', OLD.rowid, OLD.id, OLD.title, OLD.description);
+                INSERT INTO tasks_fts(tasks_fts, rowid, id, title, description, external_ids)
+                VALUES ('delete', OLD.rowid, OLD.id, OLD.title, OLD.description, OLD.external_ids);
             END;
 
             CREATE TRIGGER tasks_au AFTER UPDATE ON tasks BEGIN
-                INSERT INTO tasks_fts(tasks_fts, rowid, id, title, description)
-                VALUES ('delete', OLD.rowid, OLD.id, OLD.title, OLD.description);
-                INSERT INTO tasks_fts(rowid, id, title, description)
-                VALUES (NEW.rowid, NEW.id, NEW.title, NEW.description);
+                INSERT INTO tasks_fts(tasks_fts, rowid, id, title, description, external_ids)
+                VALUES ('delete', OLD.rowid, OLD.id, OLD.title, OLD.description, OLD.external_ids);
+                INSERT INTO tasks_fts(rowid, id, title, description, external_ids)
+                VALUES (NEW.rowid, NEW.id, NEW.title, NEW.description, NEW.external_ids);
             END;
 
             CREATE TRIGGER briefs_ai AFTER INSERT ON briefs BEGIN
-                INSERT INTO briefs_fts(rowid, id, title, body)
-                VALUES (NEW.rowid, NEW.id, NEW.title, NEW.body);
+                INSERT INTO briefs_fts(rowid, id, title, body, external_ids)
+                VALUES (NEW.rowid, NEW.id, NEW.title, NEW.body, NEW.external_ids);
             END;
 
             CREATE TRIGGER briefs_ad AFTER DELETE ON briefs BEGIN
-                INSERT INTO briefs_fts(briefs_fts, rowid, id, title, body)
-                VALUES ('delete', OLD.rowid, OLD.id, OLD.title, OLD.body);
+                INSERT INTO briefs_fts(briefs_fts, rowid, id, title, body, external_ids)
+                VALUES ('delete', OLD.rowid, OLD.id, OLD.title, OLD.body, OLD.external_ids);
             END;
 
             CREATE TRIGGER briefs_au AFTER UPDATE ON briefs BEGIN
-                INSERT INTO briefs_fts(briefs_fts, rowid, id, title, body)
-                VALUES ('delete', OLD.rowid, OLD.id, OLD.title, OLD.body);
-                INSERT INTO briefs_fts(rowid, id, title, body)
-                VALUES (NEW.rowid, NEW.id, NEW.title, NEW.body);
+                INSERT INTO briefs_fts(briefs_fts, rowid, id, title, body, external_ids)
+                VALUES ('delete', OLD.rowid, OLD.id, OLD.title, OLD.body, OLD.external_ids);
+                INSERT INTO briefs_fts(rowid, id, title, body, external_ids)
+                VALUES (NEW.rowid, NEW.id, NEW.title, NEW.body, NEW.external_ids);
             END;
             ",
         )?;
 
         // Set schema version
-        self.conn.execute(
+        conn.execute(
             &format!("PRAGMA user_version = {}", Self::SCHEMA_VERSION),
             [],
         )?;
@@ -290,6 +316,7 @@ impl Cache {
     }
 
     /// Rebuilds the cache from source files
+    #[tracing::instrument(name = "cache.rebuild", level = "debug", skip_all)]
     pub fn rebuild(
         &mut self,
         tasks: &HashMap<TaskId, Task>,
@@ -305,8 +332,8 @@ impl Cache {
         // Insert tasks
         {
             let mut stmt = tx.prepare(
-                "INSERT INTO tasks (id, brief_id, title, status, created_at, updated_at, completed_at, description, meta, depends_on)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                "INSERT INTO tasks (id, brief_id, title, status, created_at, updated_at, completed_at, description, meta, depends_on, external_ids, sort_key)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
             )?;
 
             for task in tasks.values() {
@@ -340,6 +367,8 @@ impl Cache {
                     task.description,
                     meta,
                     depends_on_json,
+                    external_ids_text(&task.external_ids),
+                    task.id.sort_key(),
                 ])?;
             }
         }
@@ -359,8 +388,8 @@ impl Cache {
         // Insert briefs (briefs)
         {
             let mut stmt = tx.prepare(
-                "INSERT INTO briefs (id, title, brief_type, status, created_at, updated_at, file_path, body)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                "INSERT INTO briefs (id, title, brief_type, status, created_at, updated_at, file_path, body, external_ids)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             )?;
 
             for brief in briefs.values() {
@@ -376,6 +405,7 @@ impl Cache {
                     brief.updated_at.to_rfc3339(),
                     file_path,
                     brief.body,
+                    external_ids_text(&brief.external_ids),
                 ])?;
             }
         }
@@ -397,7 +427,7 @@ impl Cache {
 
         let mut stmt = self
             .conn
-            .prepare("SELECT id FROM tasks WHERE status = ?1")?;
+            .prepare("SELECT id FROM tasks WHERE status = ?1 ORDER BY sort_key")?;
         let ids: Vec<String> = stmt
             .query_map(params![status_str], |row| row.get(0))?
             .collect::<Result<Vec<_>, _>>()?;
@@ -409,7 +439,7 @@ impl Cache {
     pub fn tasks_for_brief(&self, brief_id: &str) -> Result<Vec<String>> {
         let mut stmt = self
             .conn
-            .prepare("SELECT id FROM tasks WHERE brief_id = ?1")?;
+            .prepare("SELECT id FROM tasks WHERE brief_id = ?1 ORDER BY sort_key")?;
         let ids: Vec<String> = stmt
             .query_map(params![brief_id], |row| row.get(0))?
             .collect::<Result<Vec<_>, _>>()?;
@@ -421,7 +451,7 @@ impl Cache {
     pub fn standalone_tasks(&self) -> Result<Vec<String>> {
         let mut stmt = self
             .conn
-            .prepare("SELECT id FROM tasks WHERE brief_id IS NULL")?;
+            .prepare("SELECT id FROM tasks WHERE brief_id IS NULL ORDER BY sort_key")?;
         let ids: Vec<String> = stmt
             .query_map([], |row| row.get(0))?
             .collect::<Result<Vec<_>, _>>()?;
@@ -482,7 +512,8 @@ impl Cache {
                  JOIN tasks dep ON d.depends_on_id = dep.id
                  WHERE d.task_id = t.id
                  AND dep.status != 'done'
-             )",
+             )
+             ORDER BY t.sort_key",
         )?;
 
         let ids: Vec<String> = stmt
@@ -499,7 +530,8 @@ impl Cache {
              JOIN dependencies d ON d.task_id = t.id
              JOIN tasks dep ON d.depends_on_id = dep.id
              WHERE t.status != 'done'
-             AND dep.status != 'done'",
+             AND dep.status != 'done'
+             ORDER BY t.sort_key",
         )?;
 
         let ids: Vec<String> = stmt
@@ -509,8 +541,14 @@ impl Cache {
         Ok(ids)
     }
 
-    /// Query: Get ready task IDs with details
-    pub fn ready_tasks_detailed(&self) -> Result<Vec<CachedTask>> {
+    /// Query: Get ready task IDs with details, ordered by ID.
+    ///
+    /// `limit` of `None` returns all rows after `offset`.
+    pub fn ready_tasks_detailed(
+        &self,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<Vec<CachedTask>> {
         let mut stmt = self.conn.prepare(
             "SELECT t.id, t.brief_id, t.title, t.status, t.description
              FROM tasks t
@@ -520,11 +558,13 @@ impl Cache {
                  JOIN tasks dep ON d.depends_on_id = dep.id
                  WHERE d.task_id = t.id
                  AND dep.status != 'done'
-             )",
+             )
+             ORDER BY t.sort_key
+             LIMIT ?1 OFFSET ?2",
         )?;
 
         let tasks = stmt
-            .query_map([], |row| {
+            .query_map(params![sql_limit(limit), offset as i64], |row| {
                 Ok(CachedTask {
                     id: row.get(0)?,
                     brief_id: row.get(1)?,
@@ -538,8 +578,13 @@ impl Cache {
         Ok(tasks)
     }
 
-    /// Query: Get ready tasks filtered by brief
-    pub fn ready_tasks_for_brief(&self, brief_id: &str) -> Result<Vec<CachedTask>> {
+    /// Query: Get ready tasks filtered by brief, ordered by ID
+    pub fn ready_tasks_for_brief(
+        &self,
+        brief_id: &str,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<Vec<CachedTask>> {
         let mut stmt = self.conn.prepare(
             "SELECT t.id, t.brief_id, t.title, t.status, t.description
              FROM tasks t
@@ -550,11 +595,13 @@ impl Cache {
                  JOIN tasks dep ON d.depends_on_id = dep.id
                  WHERE d.task_id = t.id
                  AND dep.status != 'done'
-             )",
+             )
+             ORDER BY t.sort_key
+             LIMIT ?2 OFFSET ?3",
         )?;
 
         let tasks = stmt
-            .query_map(params![brief_id], |row| {
+            .query_map(params![brief_id, sql_limit(limit), offset as i64], |row| {
                 Ok(CachedTask {
                     id: row.get(0)?,
                     brief_id: row.get(1)?,
@@ -576,7 +623,8 @@ impl Cache {
              JOIN dependencies d ON d.task_id = t.id
              JOIN tasks dep ON d.depends_on_id = dep.id
              WHERE t.status != 'done'
-             AND dep.status != 'done'",
+             AND dep.status != 'done'
+             ORDER BY t.sort_key",
         )?;
 
         let tasks: Vec<CachedTask> = stmt
@@ -596,7 +644,8 @@ impl Cache {
             "SELECT dep.id FROM dependencies d
              JOIN tasks dep ON d.depends_on_id = dep.id
              WHERE d.task_id = ?1
-             AND dep.status != 'done'",
+             AND dep.status != 'done'
+             ORDER BY dep.sort_key",
         )?;
 
         let mut result = Vec::new();
@@ -622,7 +671,8 @@ impl Cache {
              JOIN tasks dep ON d.depends_on_id = dep.id
              WHERE t.brief_id = ?1
              AND t.status != 'done'
-             AND dep.status != 'done'",
+             AND dep.status != 'done'
+             ORDER BY t.sort_key",
         )?;
 
         let tasks: Vec<CachedTask> = stmt
@@ -641,7 +691,8 @@ impl Cache {
             "SELECT dep.id FROM dependencies d
              JOIN tasks dep ON d.depends_on_id = dep.id
              WHERE d.task_id = ?1
-             AND dep.status != 'done'",
+             AND dep.status != 'done'
+             ORDER BY dep.sort_key",
         )?;
 
         let mut result = Vec::new();
@@ -700,51 +751,50 @@ impl Cache {
 
     /// Query: Full-text search across tasks and briefs
     pub fn search(&self, query: &str) -> Result<Vec<SearchResult>> {
-        let mut results = Vec::new();
+        self.search_page(query, None, 0)
+    }
 
-        // Search tasks
-        {
-            let mut stmt = self.conn.prepare(
-                "SELECT id, title, snippet(tasks_fts, 2, '<mark>', '</mark>', '...', 32)
+    /// Query: Full-text search with paging.
+    ///
+    /// Task matches come first, then brief matches, each ordered by rank.
+    pub fn search_page(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, snippet, kind FROM (
+                 SELECT id, title,
+                        snippet(tasks_fts, 2, '<mark>', '</mark>', '...', 32) AS snippet,
+                        0 AS kind, rank
                  FROM tasks_fts WHERE tasks_fts MATCH ?1
-                 ORDER BY rank LIMIT 50",
-            )?;
-
-            let rows = stmt.query_map(params![query], |row| {
-                Ok(SearchResult {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    snippet: row.get(2)?,
-                    result_type: SearchResultType::Task,
-                })
-            })?;
-
-            for row in rows {
-                results.push(row?);
-            }
-        }
-
-        // Search briefs
-        {
-            let mut stmt = self.conn.prepare(
-                "SELECT id, title, snippet(briefs_fts, 2, '<mark>', '</mark>', '...', 32)
+                 UNION ALL
+                 SELECT id, title,
+                        snippet(briefs_fts, 2, '<mark>', '</mark>', '...', 32) AS snippet,
+                        1 AS kind, rank
                  FROM briefs_fts WHERE briefs_fts MATCH ?1
-                 ORDER BY rank LIMIT 50",
-            )?;
+             )
+             ORDER BY kind, rank, id
+             LIMIT ?2 OFFSET ?3",
+        )?;
 
-            let rows = stmt.query_map(params![query], |row| {
+        let results = stmt
+            .query_map(params![query, sql_limit(limit), offset as i64], |row| {
+                let kind: i64 = row.get(3)?;
                 Ok(SearchResult {
                     id: row.get(0)?,
                     title: row.get(1)?,
-                    snippet: row.get(2)?,
-                    result_type: SearchResultType::Brief,
+                    // Tasks without a description have no snippet
+                    snippet: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                    result_type: if kind == 0 {
+                        SearchResultType::Task
+                    } else {
+                        SearchResultType::Brief
+                    },
                 })
-            })?;
-
-            for row in rows {
-                results.push(row?);
-            }
-        }
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
 
         Ok(results)
     }
@@ -755,6 +805,21 @@ impl Cache {
     }
 }
 
+/// External IDs as searchable text (`jira:ABC-123 github:456`)
+fn external_ids_text(ids: &ExternalIds) -> Option<String> {
+    (!ids.is_empty()).then(|| {
+        ids.iter()
+            .map(|(system, key)| format!("{}:{}", system, key))
+            .collect::<Vec<_>>()
+            .join(" ")
+    })
+}
+
+/// Converts an optional row limit to SQLite's form (-1 means no limit)
+fn sql_limit(limit: Option<usize>) -> i64 {
+    limit.map(|l| l as i64).unwrap_or(-1)
+}
+
 /// Result from a search query
 #[derive(Debug)]
 pub struct SearchResult {
@@ -886,6 +951,43 @@ mod tests {
         assert!(blocked.contains(&task2.id.to_string()));
     }
 
+    #[test]
+    fn test_ready_tasks_paging() {
+        let (_dir, project_root) = setup_project();
+        let mut cache = Cache::open(&project_root).unwrap();
+
+        let mut tasks = HashMap::new();
+        let brief = BriefId::new("Paging", Utc::now());
+        for seq in [1, 2, 10] {
+            let task = Task::new(brief.task_id(seq), format!("Task {}", seq));
+            tasks.insert(task.id.clone(), task);
+        }
+        cache.rebuild(&tasks, &HashMap::new()).unwrap();
+
+        let all = cache.ready_tasks_detailed(None, 0).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all[1].id.ends_with(".2") && all[2].id.ends_with(".10"));
+
+        let page = cache.ready_tasks_detailed(Some(2), 1).unwrap();
+        let ids: Vec<_> = page.iter().map(|t| t.id.clone()).collect();
+        assert_eq!(ids, vec![all[1].id.clone(), all[2].id.clone()]);
+    }
+
+    #[test]
+    fn test_search_without_description() {
+        let (_dir, project_root) = setup_project();
+        let mut cache = Cache::open(&project_root).unwrap();
+
+        let task = make_task(1, "Plain title");
+        let mut tasks = HashMap::new();
+        tasks.insert(task.id.clone(), task);
+        cache.rebuild(&tasks, &HashMap::new()).unwrap();
+
+        let results = cache.search_page("plain", Some(1), 0).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].snippet, "");
+    }
+
     #[test]
     fn test_search() {
         let (_dir, project_root) = setup_project();
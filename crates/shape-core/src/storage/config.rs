@@ -0,0 +1,967 @@
+//! Configuration handling for Shape CLI
+//!
+//! Configuration is stored in `.shape/config.toml` (project) and
+//! `~/.config/shape/config.toml` (global).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::domain::{Gate, IdScheme, LinkType, Task};
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Invalid configuration: {0}")]
+    Invalid(String),
+
+    #[error("Failed to parse configuration: {0}")]
+    Parse(String),
+}
+
+/// Default brief type for new briefs
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultBriefType {
+    #[default]
+    Minimal,
+    Custom(String),
+}
+
+impl DefaultBriefType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DefaultBriefType::Minimal => "minimal",
+            DefaultBriefType::Custom(s) => s,
+        }
+    }
+}
+
+/// Compaction strategy for summarizing completed tasks
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionStrategy {
+    /// Concatenate task titles
+    #[default]
+    Basic,
+    /// Group related tasks by common words
+    Smart,
+    /// Use LLM to generate summaries
+    Llm,
+}
+
+impl CompactionStrategy {
+    pub fn as_str(&self) -> &str {
+        match self {
+            CompactionStrategy::Basic => "basic",
+            CompactionStrategy::Smart => "smart",
+            CompactionStrategy::Llm => "llm",
+        }
+    }
+}
+
+/// Configuration for memory compaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompactionConfig {
+    /// Days after which completed tasks can be compacted
+    pub auto_compact_days: u32,
+
+    /// Minimum tasks required to trigger compaction
+    pub min_tasks: usize,
+
+    /// Compaction strategy
+    pub strategy: CompactionStrategy,
+}
+
+impl Default for CompactionConfig {
+    fn default() -> Self {
+        Self {
+            auto_compact_days: 14,
+            min_tasks: 3,
+            strategy: CompactionStrategy::Smart,
+        }
+    }
+}
+
+/// Configuration for the background daemon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DaemonConfig {
+    /// Enable daemon functionality
+    pub enabled: bool,
+
+    /// Debounce delay in seconds before committing
+    pub debounce_seconds: u64,
+
+    /// Auto-commit changes to git
+    pub auto_commit: bool,
+
+    /// Commit message format (placeholders: {action}, {id}, {count})
+    pub commit_message_format: String,
+
+    /// Auto-push to remote after commit
+    pub auto_push: bool,
+
+    /// Remote name for auto-push
+    pub push_remote: String,
+
+    /// Branch name for auto-push
+    pub push_branch: String,
+
+    /// Longest the daemon postpones a commit while a CLI write holds the
+    /// task store lock
+    pub commit_backoff_max_seconds: u64,
+}
+
+/// Configuration for agent coordination
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgentConfig {
+    /// Agent name (defaults to $SHAPE_AGENT, then $USER)
+    pub name: Option<String>,
+
+    /// Claim timeout in hours (default: 4)
+    pub claim_timeout_hours: u32,
+
+    /// Auto-unclaim when task is marked done
+    pub auto_unclaim_on_done: bool,
+
+    /// Link tasks created while holding a claim back to the claimed task
+    pub auto_provenance: bool,
+
+    /// Minutes between expired-claim sweeps run by the daemon (0 disables)
+    pub sweep_interval_minutes: u64,
+
+    /// Agent that swept tasks are assigned to (e.g. "human")
+    pub sweep_assign_to: Option<String>,
+
+    /// Identities that are people; only they may claim tasks that require
+    /// a human
+    pub humans: Vec<String>,
+
+    /// Tasks with any of these labels require a human, like
+    /// `meta.requires_human`
+    pub human_labels: Vec<String>,
+
+    /// Identities that may let an agent claim a task requiring a human
+    /// (`claim --allow-agent`)
+    pub operators: Vec<String>,
+
+    /// What `next` does with tasks whose scope another agent has locked
+    pub lock_conflicts: LockConflicts,
+
+    /// Let blockers of higher-priority tasks inherit their priority in
+    /// `ready` and `next` ordering
+    pub priority_inheritance: bool,
+}
+
+/// How `next` treats tasks that touch a resource another agent has locked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LockConflicts {
+    /// Recommend them only after every task without a conflict
+    #[default]
+    Deprioritize,
+    /// Leave them out
+    Filter,
+}
+
+impl Default for AgentConfig {
+    fn default() -> Self {
+        Self {
+            name: None,
+            claim_timeout_hours: 4,
+            auto_unclaim_on_done: true,
+            auto_provenance: true,
+            sweep_interval_minutes: 0,
+            sweep_assign_to: None,
+            humans: Vec::new(),
+            human_labels: Vec::new(),
+            operators: Vec::new(),
+            lock_conflicts: LockConflicts::default(),
+            priority_inheritance: true,
+        }
+    }
+}
+
+impl AgentConfig {
+    /// Gets the effective agent name from config, environment, or defaults
+    pub fn effective_name(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| std::env::var("SHAPE_AGENT").ok())
+            .or_else(|| std::env::var("USER").ok())
+            .unwrap_or_else(|| "anonymous".to_string())
+    }
+
+    /// Returns true if `name` is listed under `humans`
+    pub fn is_human(&self, name: &str) -> bool {
+        self.humans.iter().any(|h| h == name)
+    }
+
+    /// Returns true if `name` is listed under `operators`
+    pub fn is_operator(&self, name: &str) -> bool {
+        self.operators.iter().any(|o| o == name)
+    }
+
+    /// Returns true if `name` may claim `task`: anyone may claim a task
+    /// unless it requires a human
+    pub fn may_claim(&self, task: &Task, name: &str) -> bool {
+        self.is_human(name) || !task.requires_human(&self.human_labels)
+    }
+}
+
+/// Guardrails for commands that touch many tasks at once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuardrailConfig {
+    /// Number of tasks a single command may modify without `--yes` (0 disables)
+    pub bulk_threshold: usize,
+}
+
+impl Default for GuardrailConfig {
+    fn default() -> Self {
+        Self { bulk_threshold: 5 }
+    }
+}
+
+/// Brief lifecycle rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BriefConfig {
+    /// Only an owner or co-owner may mark an owned brief as shipped
+    pub owner_only_ship: bool,
+
+    /// Share of its appetite (in percent) an in-progress brief may use
+    /// before the `appetite_warning` event fires
+    pub appetite_warn_percent: u32,
+
+    /// Days after which `shape maintenance archive-shipped` moves a shipped
+    /// brief to the archive when no `--older-than` is given (0 = no policy)
+    pub archive_shipped_after_days: u32,
+}
+
+impl Default for BriefConfig {
+    fn default() -> Self {
+        Self {
+            owner_only_ship: false,
+            appetite_warn_percent: 100,
+            archive_shipped_after_days: 0,
+        }
+    }
+}
+
+/// Completion gates checked by `task done`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct GateConfig {
+    /// Gates every task must pass
+    pub default: Vec<Gate>,
+
+    /// Extra gates for tasks with a label, keyed by label
+    pub labels: BTreeMap<String, Vec<Gate>>,
+}
+
+impl GateConfig {
+    /// Gates for a task with these labels, without repeats
+    pub fn for_labels<S: AsRef<str>>(&self, labels: &[S]) -> Vec<&Gate> {
+        let mut gates: Vec<&Gate> = Vec::new();
+        let by_label = labels
+            .iter()
+            .filter_map(|label| self.labels.get(label.as_ref()))
+            .flatten();
+        for gate in self.default.iter().chain(by_label) {
+            if !gates.contains(&gate) {
+                gates.push(gate);
+            }
+        }
+        gates
+    }
+}
+
+/// Review requirements
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReviewConfig {
+    /// Refuse to complete tasks carrying `label` until a review approves them
+    pub require_approval: bool,
+
+    /// Label marking tasks that need an approving review
+    pub label: String,
+}
+
+impl Default for ReviewConfig {
+    fn default() -> Self {
+        Self {
+            require_approval: false,
+            label: "needs-review".to_string(),
+        }
+    }
+}
+
+/// Context export tuning
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContextConfig {
+    /// Ready or in-progress tasks a brief needs for `--include-bodies selective`
+    /// to include its body
+    pub selective_min_active: usize,
+
+    /// Longest body `--include-bodies selective` includes before truncating
+    pub selective_max_chars: usize,
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            selective_min_active: 1,
+            selective_max_chars: 8000,
+        }
+    }
+}
+
+/// Reports for humans
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportConfig {
+    /// Hours without activity after which `report wip` flags a task as stale
+    pub stale_wip_hours: u32,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            stale_wip_hours: 72,
+        }
+    }
+}
+
+/// Icon set for statuses in text output and the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IconMode {
+    /// Unicode symbols such as ○ ◐ ✓
+    #[default]
+    Unicode,
+    /// Emoji such as ✅ 🚧 🚀
+    Emoji,
+    /// Plain ASCII such as [ ] [~] [x]
+    Ascii,
+    /// No icons
+    None,
+}
+
+/// How statuses and labels look in text output and the TUI
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct DisplayConfig {
+    /// Icon set (`SHAPE_ICONS` overrides it per user)
+    pub icons: IconMode,
+
+    /// Bright colors and no dimmed text (`SHAPE_HIGH_CONTRAST=1` turns it on
+    /// per user)
+    pub high_contrast: bool,
+
+    /// Icon overrides keyed by task or brief status, e.g. `done = "✔"`
+    pub status_icons: BTreeMap<String, String>,
+
+    /// Colors for labels: red, green, yellow, blue, magenta or cyan
+    pub label_colors: BTreeMap<String, String>,
+}
+
+/// Storage tuning
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// Seconds to wait for another process's lock on `tasks.jsonl` before
+    /// failing (`--wait` waits indefinitely)
+    pub lock_timeout_secs: u64,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            lock_timeout_secs: 10,
+        }
+    }
+}
+
+/// How `shape open` picks a task's target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenConfig {
+    /// Link types tried in order; the first link of the first type found is opened
+    pub prefer: Vec<LinkType>,
+
+    /// Command used to open URLs (default: `$BROWSER`, then the platform opener)
+    pub browser: Option<String>,
+}
+
+impl Default for OpenConfig {
+    fn default() -> Self {
+        Self {
+            prefer: vec![LinkType::Pr, LinkType::Url, LinkType::File],
+            browser: None,
+        }
+    }
+}
+
+/// Local performance metrics (opt-in)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// Record command latencies and store sizes in `.shape/.cache/metrics.jsonl`
+    pub enabled: bool,
+}
+
+/// A reusable chain of tasks instantiated with `shape task pipeline add`
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct PipelineConfig {
+    /// Optional description shown in `shape task pipeline list`
+    pub description: Option<String>,
+
+    /// Steps in order
+    pub steps: Vec<PipelineStep>,
+}
+
+/// A single step of a pipeline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PipelineStep {
+    /// Task title
+    pub title: String,
+
+    /// Titles of steps that must complete first (defaults to the previous step)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<Vec<String>>,
+
+    /// Optional task description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            debounce_seconds: 5,
+            auto_commit: true,
+            commit_message_format: "shape: {action} {id}".to_string(),
+            auto_push: false,
+            push_remote: "origin".to_string(),
+            push_branch: "main".to_string(),
+            commit_backoff_max_seconds: 30,
+        }
+    }
+}
+
+/// Project-level configuration
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ProjectConfig {
+    /// On-disk format version (0 for projects created before versioning)
+    pub format_version: u32,
+
+    /// Default brief type for `shape brief new`
+    pub default_brief_type: DefaultBriefType,
+
+    /// Plugins to load
+    pub plugins: Vec<String>,
+
+    /// Days to include completed tasks in context (default 7)
+    pub context_days: u32,
+
+    /// Context export tuning
+    pub context: ContextConfig,
+
+    /// Compaction settings
+    pub compaction: CompactionConfig,
+
+    /// Daemon settings
+    pub daemon: DaemonConfig,
+
+    /// Agent coordination settings
+    pub agent: AgentConfig,
+
+    /// Bulk modification guardrails
+    pub guardrails: GuardrailConfig,
+
+    /// Brief lifecycle rules
+    pub briefs: BriefConfig,
+
+    /// Review requirements
+    pub reviews: ReviewConfig,
+
+    /// Completion gates
+    pub gates: GateConfig,
+
+    /// Report settings
+    pub reports: ReportConfig,
+
+    /// Status icons, label colors and accessibility modes
+    pub display: DisplayConfig,
+
+    /// Local performance metrics
+    pub metrics: MetricsConfig,
+
+    /// Storage tuning
+    pub storage: StorageConfig,
+
+    /// `shape open` preferences
+    pub open: OpenConfig,
+
+    /// Task pipelines by name
+    pub pipelines: BTreeMap<String, PipelineConfig>,
+
+    /// Description scaffolds for new tasks, keyed by label
+    pub description_templates: BTreeMap<String, String>,
+
+    /// Shell commands run on events (e.g. `digest`), keyed by event
+    pub hooks: BTreeMap<String, String>,
+
+    /// How new brief and task IDs are generated
+    pub ids: IdScheme,
+}
+
+impl ProjectConfig {
+    pub fn default() -> Self {
+        Self {
+            format_version: 0,
+            default_brief_type: DefaultBriefType::Minimal,
+            plugins: vec![],
+            context_days: 7,
+            context: ContextConfig::default(),
+            compaction: CompactionConfig::default(),
+            daemon: DaemonConfig::default(),
+            agent: AgentConfig::default(),
+            guardrails: GuardrailConfig::default(),
+            briefs: BriefConfig::default(),
+            reviews: ReviewConfig::default(),
+            gates: GateConfig::default(),
+            reports: ReportConfig::default(),
+            display: DisplayConfig::default(),
+            metrics: MetricsConfig::default(),
+            storage: StorageConfig::default(),
+            open: OpenConfig::default(),
+            pipelines: BTreeMap::new(),
+            description_templates: BTreeMap::new(),
+            hooks: BTreeMap::new(),
+            ids: IdScheme::default(),
+        }
+    }
+
+    /// Description scaffold for a new task with these labels: the template
+    /// of the first label that has one
+    pub fn description_template<S: AsRef<str>>(&self, labels: &[S]) -> Option<&str> {
+        labels
+            .iter()
+            .find_map(|label| self.description_templates.get(label.as_ref()))
+            .map(String::as_str)
+    }
+}
+
+/// Global user configuration
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct GlobalConfig {
+    /// Default output format (text or json)
+    pub default_format: OutputFormat,
+
+    /// Editor command for editing briefs
+    pub editor: Option<String>,
+
+    /// Release checks for `shape self-update`
+    pub update: UpdateConfig,
+}
+
+/// Where `shape self-update` looks for releases and how often `shape status`
+/// checks on its own
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateConfig {
+    /// Release feed: a GitHub "latest release" API URL, or a file with the
+    /// same JSON (default: the shape-cli releases)
+    pub feed_url: Option<String>,
+
+    /// Hours between the passive checks `shape status` makes (0 = never)
+    pub check_interval_hours: u32,
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            feed_url: None,
+            check_interval_hours: 24,
+        }
+    }
+}
+
+/// Output format for commands
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Combined configuration (global + project)
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub project: ProjectConfig,
+    pub global: GlobalConfig,
+    pub project_root: Option<PathBuf>,
+}
+
+impl Config {
+    /// Loads configuration from default locations
+    pub fn load() -> Result<Self> {
+        let global = Self::load_global()?;
+        let (project, project_root) = Self::load_project()?;
+
+        Ok(Self {
+            project,
+            global,
+            project_root,
+        })
+    }
+
+    /// Loads configuration for a specific project
+    pub fn for_project(project_root: &Path) -> Result<Self> {
+        let global = Self::load_global()?;
+        let project = Self::load_project_config(project_root)?;
+
+        Ok(Self {
+            project,
+            global,
+            project_root: Some(project_root.to_path_buf()),
+        })
+    }
+
+    /// Returns the global config directory
+    pub fn global_config_dir() -> Option<PathBuf> {
+        ProjectDirs::from("dev", "shape", "shape-cli").map(|dirs| dirs.config_dir().to_path_buf())
+    }
+
+    /// Loads global configuration
+    fn load_global() -> Result<GlobalConfig> {
+        let config_dir = match Self::global_config_dir() {
+            Some(dir) => dir,
+            None => return Ok(GlobalConfig::default()),
+        };
+
+        let config_path = config_dir.join("config.toml");
+        if !config_path.exists() {
+            return Ok(GlobalConfig::default());
+        }
+
+        let content = fs::read_to_string(&config_path)
+            .with_context(|| format!("Failed to read global config: {}", config_path.display()))?;
+
+        toml::from_str(&content)
+            .map_err(|e| ConfigError::Parse(e.to_string()))
+            .context("Failed to parse global config")
+    }
+
+    /// Finds and loads project configuration
+    fn load_project() -> Result<(ProjectConfig, Option<PathBuf>)> {
+        let project_root = Self::find_project_root();
+
+        match project_root {
+            Some(root) => {
+                let config = Self::load_project_config(&root)?;
+                Ok((config, Some(root)))
+            }
+            None => Ok((ProjectConfig::default(), None)),
+        }
+    }
+
+    /// Loads project configuration from a specific root
+    fn load_project_config(project_root: &Path) -> Result<ProjectConfig> {
+        let config_path = project_root.join(".shape").join("config.toml");
+
+        if !config_path.exists() {
+            return Ok(ProjectConfig::default());
+        }
+
+        let content = fs::read_to_string(&config_path)
+            .with_context(|| format!("Failed to read project config: {}", config_path.display()))?;
+
+        let config: ProjectConfig = toml::from_str(&content)
+            .map_err(|e| ConfigError::Parse(e.to_string()))
+            .context("Failed to parse project config")?;
+
+        config
+            .ids
+            .validate()
+            .map_err(|e| ConfigError::Invalid(e.to_string()))?;
+
+        Ok(config)
+    }
+
+    /// Finds the project root by looking for `.shape/` directory
+    ///
+    /// Searches upward from `--project`, `SHAPE_PROJECT` or the current
+    /// directory.
+    pub fn find_project_root() -> Option<PathBuf> {
+        let mut current = super::project::search_start()?;
+
+        loop {
+            let shape_dir = current.join(".shape");
+            if shape_dir.is_dir() {
+                return Some(current);
+            }
+
+            if !current.pop() {
+                return None;
+            }
+        }
+    }
+
+    /// Returns true if we're in a shape project
+    pub fn is_in_project(&self) -> bool {
+        self.project_root.is_some()
+    }
+
+    /// Returns the project root, or an error if not in a project
+    pub fn require_project_root(&self) -> Result<&Path> {
+        self.project_root
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Not in a shape project. Run 'shape init' first."))
+    }
+
+    /// Saves the project configuration
+    pub fn save_project(&self) -> Result<()> {
+        let root = self.require_project_root()?;
+        let config_path = root.join(".shape").join("config.toml");
+
+        let content =
+            toml::to_string_pretty(&self.project).context("Failed to serialize project config")?;
+
+        fs::write(&config_path, content)
+            .with_context(|| format!("Failed to write project config: {}", config_path.display()))
+    }
+
+    /// Saves the global configuration
+    pub fn save_global(&self) -> Result<()> {
+        let config_dir = Self::global_config_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+
+        fs::create_dir_all(&config_dir).with_context(|| {
+            format!(
+                "Failed to create config directory: {}",
+                config_dir.display()
+            )
+        })?;
+
+        let config_path = config_dir.join("config.toml");
+        let content =
+            toml::to_string_pretty(&self.global).context("Failed to serialize global config")?;
+
+        fs::write(&config_path, content)
+            .with_context(|| format!("Failed to write global config: {}", config_path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn default_config() {
+        let config = Config {
+            project: ProjectConfig::default(),
+            global: GlobalConfig::default(),
+            project_root: None,
+        };
+
+        assert_eq!(config.project.context_days, 7);
+        assert_eq!(config.global.default_format, OutputFormat::Text);
+    }
+
+    #[test]
+    fn parse_project_config() {
+        let toml = r#"
+default_brief_type = "minimal"
+plugins = ["shape-brief-shapeup"]
+context_days = 14
+"#;
+
+        let config: ProjectConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.context_days, 14);
+        assert_eq!(config.plugins, vec!["shape-brief-shapeup"]);
+    }
+
+    #[test]
+    fn parse_guardrail_config() {
+        let config: ProjectConfig = toml::from_str("").unwrap();
+        assert_eq!(config.guardrails.bulk_threshold, 5);
+
+        let toml = r#"
+[guardrails]
+bulk_threshold = 20
+"#;
+
+        let config: ProjectConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.guardrails.bulk_threshold, 20);
+    }
+
+    #[test]
+    fn parse_pipeline_config() {
+        let toml = r#"
+[pipelines.release-checklist]
+description = "Weekly release"
+steps = [
+    { title = "Design" },
+    { title = "Implement" },
+    { title = "Docs", after = [] },
+    { title = "Review", after = ["Implement", "Docs"] },
+]
+"#;
+
+        let config: ProjectConfig = toml::from_str(toml).unwrap();
+        let pipeline = &config.pipelines["release-checklist"];
+        assert_eq!(pipeline.steps.len(), 4);
+        assert_eq!(pipeline.steps[1].after, None);
+        assert_eq!(pipeline.steps[2].after, Some(vec![]));
+        assert_eq!(
+            pipeline.steps[3].after,
+            Some(vec!["Implement".to_string(), "Docs".to_string()])
+        );
+    }
+
+    #[test]
+    fn description_template_by_label() {
+        let toml = r#"
+[description_templates]
+bug = """
+## Steps to reproduce
+
+## Expected
+
+## Actual
+"""
+"#;
+
+        let config: ProjectConfig = toml::from_str(toml).unwrap();
+        let template = config.description_template(&["ui", "bug"]).unwrap();
+        assert!(template.starts_with("## Steps to reproduce"));
+        assert_eq!(config.description_template(&["ui"]), None);
+        assert_eq!(config.description_template::<&str>(&[]), None);
+    }
+
+    #[test]
+    fn parse_global_config() {
+        let toml = r#"
+default_format = "json"
+editor = "code"
+"#;
+
+        let config: GlobalConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.default_format, OutputFormat::Json);
+        assert_eq!(config.editor, Some("code".to_string()));
+        assert_eq!(config.update.check_interval_hours, 24);
+    }
+
+    #[test]
+    fn find_project_root() {
+        let dir = TempDir::new().unwrap();
+        let shape_dir = dir.path().join(".shape");
+        fs::create_dir_all(&shape_dir).unwrap();
+
+        // Change to a subdirectory
+        let sub_dir = dir.path().join("sub").join("dir");
+        fs::create_dir_all(&sub_dir).unwrap();
+        std::env::set_current_dir(&sub_dir).unwrap();
+
+        let root = Config::find_project_root();
+        // Canonicalize both paths to handle macOS /var -> /private/var symlinks
+        let expected = dir.path().canonicalize().ok();
+        let actual = root.and_then(|p| p.canonicalize().ok());
+        assert_eq!(actual, expected);
+
+        // Reset current dir to avoid affecting other tests
+        std::env::set_current_dir(dir.path()).unwrap();
+    }
+
+    #[test]
+    fn config_not_in_project() {
+        let config = Config {
+            project: ProjectConfig::default(),
+            global: GlobalConfig::default(),
+            project_root: None,
+        };
+
+        assert!(!config.is_in_project());
+        assert!(config.require_project_root().is_err());
+    }
+
+    #[test]
+    fn gates_for_labels() {
+        let config: ProjectConfig = toml::from_str(
+            r#"
+            [gates]
+            default = ["pr_linked"]
+
+            [gates.labels]
+            bug = ["tests_linked", "pr_linked"]
+            risky = ["review_approved"]
+            "#,
+        )
+        .unwrap();
+        let names = |labels: &[&str]| -> Vec<String> {
+            config
+                .gates
+                .for_labels(labels)
+                .iter()
+                .map(|g| g.to_string())
+                .collect()
+        };
+        assert_eq!(names(&[]), vec!["pr_linked"]);
+        assert_eq!(names(&["bug"]), vec!["pr_linked", "tests_linked"]);
+        assert_eq!(
+            names(&["risky", "ui"]),
+            vec!["pr_linked", "review_approved"]
+        );
+        assert!(toml::from_str::<ProjectConfig>("[gates]\ndefault = [\"ci\"]").is_err());
+    }
+
+    #[test]
+    fn only_humans_may_claim_tasks_requiring_one() {
+        let config: ProjectConfig = toml::from_str(
+            r#"
+            [agent]
+            humans = ["alice"]
+            human_labels = ["security"]
+            "#,
+        )
+        .unwrap();
+        let agent = &config.agent;
+        let brief = crate::domain::BriefId::new("Test", chrono::Utc::now());
+        let mut task = Task::new(crate::domain::TaskId::new(&brief, 1), "Rotate keys");
+        assert!(agent.may_claim(&task, "bot"));
+
+        task.set_meta("labels", serde_json::json!(["security"]));
+        assert!(!agent.may_claim(&task, "bot"));
+        assert!(agent.may_claim(&task, "alice"));
+
+        task.set_meta("labels", serde_json::json!([]));
+        task.set_meta(crate::domain::REQUIRES_HUMAN_META_KEY, true);
+        assert!(!agent.may_claim(&task, "bot"));
+    }
+
+    #[test]
+    fn default_brief_type() {
+        let minimal = DefaultBriefType::Minimal;
+        assert_eq!(minimal.as_str(), "minimal");
+
+        let custom = DefaultBriefType::Custom("shapeup".to_string());
+        assert_eq!(custom.as_str(), "shapeup");
+    }
+}
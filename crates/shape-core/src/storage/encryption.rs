@@ -0,0 +1,361 @@
+//! Encryption at rest for synced project data
+//!
+//! `shape lock-store` encrypts the parts of `.shape/` that reach a remote:
+//! each task line in `tasks.jsonl` and each brief file. Everything is sealed
+//! with XChaCha20-Poly1305 under a key derived from a project passphrase
+//! with Argon2id. The KDF parameters, salt and a sealed check value live in
+//! `.shape/encryption.json`, which is committed alongside the data so every
+//! clone knows the store is encrypted.
+//!
+//! A sealed value is written as `shape-enc:v1:<base64 nonce + ciphertext>`.
+//! The `tasks.jsonl` format header stays plaintext so version checks work
+//! before the store is unlocked.
+//!
+//! The passphrase is taken from `SHAPE_PASSPHRASE`, then from the system
+//! keychain (`security` on macOS, `secret-tool` on Linux) if it was stored
+//! there with `shape lock-store --remember`. Without either, reads and
+//! writes fail with [`EncryptionError::Locked`].
+//!
+//! The local SQLite cache and brief index are derived files that never leave
+//! the machine (they are gitignored), so they stay plaintext.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Context, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// File in `.shape/` holding the key derivation settings
+pub const ENCRYPTION_FILE: &str = "encryption.json";
+
+/// Environment variable read for the passphrase
+pub const PASSPHRASE_ENV: &str = "SHAPE_PASSPHRASE";
+
+/// Prefix marking a sealed value
+const SEALED_PREFIX: &str = "shape-enc:v1:";
+
+/// Plaintext sealed into [`EncryptionInfo::check`] to verify a passphrase
+const CHECK_TEXT: &str = "shape-cli store key";
+
+/// Keychain service name entries are stored under
+const KEYCHAIN_SERVICE: &str = "shape-cli";
+
+const NONCE_LEN: usize = 24;
+
+/// Failures reading or writing an encrypted store
+#[derive(Debug, Error)]
+pub enum EncryptionError {
+    #[error(
+        "The store is encrypted and locked. Set {} or run 'shape lock-store --remember' to keep the passphrase in the system keychain.",
+        PASSPHRASE_ENV
+    )]
+    Locked,
+
+    #[error("Wrong passphrase for the encrypted store")]
+    WrongPassphrase,
+
+    #[error("Encrypted data is unreadable: {0}")]
+    Corrupt(String),
+}
+
+/// Contents of `.shape/encryption.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptionInfo {
+    pub version: u32,
+
+    /// Always `argon2id`
+    pub kdf: String,
+
+    /// Argon2 memory cost in KiB
+    pub m_cost: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+
+    /// Base64 salt, also the keychain account name
+    pub salt: String,
+
+    /// [`CHECK_TEXT`] sealed under the key, to tell a wrong passphrase apart
+    /// from damaged data
+    pub check: String,
+
+    pub encrypted_at: DateTime<Utc>,
+}
+
+/// Key for sealing and opening store values
+#[derive(Clone)]
+pub struct StoreCipher {
+    key: [u8; 32],
+}
+
+impl std::fmt::Debug for StoreCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("StoreCipher(..)")
+    }
+}
+
+impl StoreCipher {
+    fn derive(info: &EncryptionInfo, passphrase: &str) -> Result<Self, EncryptionError> {
+        let salt = BASE64
+            .decode(&info.salt)
+            .map_err(|e| EncryptionError::Corrupt(format!("bad salt: {}", e)))?;
+        let params = Params::new(info.m_cost, info.t_cost, info.p_cost, Some(32))
+            .map_err(|e| EncryptionError::Corrupt(format!("bad KDF parameters: {}", e)))?;
+        let mut key = [0u8; 32];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| EncryptionError::Corrupt(format!("key derivation failed: {}", e)))?;
+        Ok(Self { key })
+    }
+
+    fn aead(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new((&self.key).into())
+    }
+
+    /// Encrypts `plaintext` into a sealed value
+    pub fn seal(&self, plaintext: &str) -> String {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .aead()
+            .encrypt(&nonce, plaintext.as_bytes())
+            .expect("XChaCha20-Poly1305 encryption cannot fail for in-memory data");
+        let mut bytes = nonce.to_vec();
+        bytes.extend(ciphertext);
+        format!("{}{}", SEALED_PREFIX, BASE64.encode(bytes))
+    }
+
+    /// Decrypts a sealed value
+    pub fn open(&self, sealed: &str) -> Result<String, EncryptionError> {
+        let encoded = sealed
+            .trim()
+            .strip_prefix(SEALED_PREFIX)
+            .ok_or_else(|| EncryptionError::Corrupt("not a sealed value".to_string()))?;
+        let bytes = BASE64
+            .decode(encoded)
+            .map_err(|e| EncryptionError::Corrupt(e.to_string()))?;
+        if bytes.len() < NONCE_LEN {
+            return Err(EncryptionError::Corrupt("value too short".to_string()));
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let plaintext = self
+            .aead()
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| EncryptionError::WrongPassphrase)?;
+        String::from_utf8(plaintext).map_err(|e| EncryptionError::Corrupt(e.to_string()))
+    }
+}
+
+/// Returns true if `text` is a sealed value
+pub fn is_sealed(text: &str) -> bool {
+    text.trim_start().starts_with(SEALED_PREFIX)
+}
+
+/// Returns true if the store in `shape_dir` is encrypted
+pub fn is_enabled(shape_dir: &Path) -> bool {
+    shape_dir.join(ENCRYPTION_FILE).exists()
+}
+
+/// Reads `encryption.json`, if the store is encrypted
+pub fn info(shape_dir: &Path) -> Result<Option<EncryptionInfo>> {
+    let path = shape_dir.join(ENCRYPTION_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let info = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(info))
+}
+
+/// Keys unlocked in this process, by salt, so the KDF runs once per command
+/// rather than once per file, and a passphrase given to
+/// [`unlock_with`] applies to every store read after it
+fn unlocked() -> &'static Mutex<HashMap<String, StoreCipher>> {
+    static KEYS: OnceLock<Mutex<HashMap<String, StoreCipher>>> = OnceLock::new();
+    KEYS.get_or_init(Default::default)
+}
+
+/// Derives the key for `passphrase` and checks it against `info`. On
+/// success the store stays unlocked for the rest of the process.
+pub fn unlock_with(info: &EncryptionInfo, passphrase: &str) -> Result<StoreCipher> {
+    let cipher = StoreCipher::derive(info, passphrase)?;
+    match cipher.open(&info.check) {
+        Ok(text) if text == CHECK_TEXT => {}
+        _ => return Err(EncryptionError::WrongPassphrase.into()),
+    }
+    unlocked()
+        .lock()
+        .expect("key cache")
+        .insert(info.salt.clone(), cipher.clone());
+    Ok(cipher)
+}
+
+/// Cipher for the store in `shape_dir`: `None` when it isn't encrypted,
+/// [`EncryptionError::Locked`] when it is and no passphrase is available
+pub fn cipher(shape_dir: &Path) -> Result<Option<StoreCipher>> {
+    let Some(info) = info(shape_dir)? else {
+        return Ok(None);
+    };
+    if let Some(cipher) = unlocked().lock().expect("key cache").get(&info.salt) {
+        return Ok(Some(cipher.clone()));
+    }
+    let passphrase = std::env::var(PASSPHRASE_ENV)
+        .ok()
+        .filter(|p| !p.is_empty())
+        .or_else(|| keychain_lookup(&info.salt));
+    match passphrase {
+        Some(passphrase) => unlock_with(&info, &passphrase).map(Some),
+        None => Err(EncryptionError::Locked.into()),
+    }
+}
+
+/// Starts encrypting the store in `shape_dir` with `passphrase`: writes
+/// `encryption.json` and returns the new cipher, unlocked for the rest of
+/// the process. Existing data is left as is for the caller to reseal.
+pub fn enable(shape_dir: &Path, passphrase: &str) -> Result<StoreCipher> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let params = Params::default();
+    let mut info = EncryptionInfo {
+        version: 1,
+        kdf: "argon2id".to_string(),
+        m_cost: params.m_cost(),
+        t_cost: params.t_cost(),
+        p_cost: params.p_cost(),
+        salt: BASE64.encode(salt),
+        check: String::new(),
+        encrypted_at: Utc::now(),
+    };
+    let cipher = StoreCipher::derive(&info, passphrase)?;
+    info.check = cipher.seal(CHECK_TEXT);
+    unlocked()
+        .lock()
+        .expect("key cache")
+        .insert(info.salt.clone(), cipher.clone());
+
+    let path = shape_dir.join(ENCRYPTION_FILE);
+    let content = serde_json::to_string_pretty(&info).context("Failed to serialize settings")?;
+    fs::write(&path, content + "\n")
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(cipher)
+}
+
+/// Stops encrypting the store in `shape_dir` once its data is plaintext again
+pub fn disable(shape_dir: &Path) -> Result<()> {
+    let path = shape_dir.join(ENCRYPTION_FILE);
+    fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))
+}
+
+/// Stores the passphrase in the system keychain. Returns false where no
+/// supported keychain tool is available.
+pub fn remember(info: &EncryptionInfo, passphrase: &str) -> bool {
+    if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args(["add-generic-password", "-U", "-s", KEYCHAIN_SERVICE])
+            .args(["-a", &info.salt, "-w", passphrase])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success())
+    } else {
+        let child = Command::new("secret-tool")
+            .args(["store", "--label", "shape-cli store passphrase"])
+            .args(["service", KEYCHAIN_SERVICE, "account", &info.salt])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let Ok(mut child) = child else {
+            return false;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            use std::io::Write;
+            if stdin.write_all(passphrase.as_bytes()).is_err() {
+                return false;
+            }
+        }
+        child.wait().is_ok_and(|s| s.success())
+    }
+}
+
+/// Removes the passphrase from the system keychain, if it was stored there
+pub fn forget(info: &EncryptionInfo) {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut c = Command::new("security");
+        c.args(["delete-generic-password", "-s", KEYCHAIN_SERVICE])
+            .args(["-a", &info.salt]);
+        c
+    } else {
+        let mut c = Command::new("secret-tool");
+        c.args(["clear", "service", KEYCHAIN_SERVICE, "account", &info.salt]);
+        c
+    };
+    let _ = command.stdout(Stdio::null()).stderr(Stdio::null()).status();
+}
+
+fn keychain_lookup(account: &str) -> Option<String> {
+    let output = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args(["find-generic-password", "-s", KEYCHAIN_SERVICE])
+            .args(["-a", account, "-w"])
+            .stderr(Stdio::null())
+            .output()
+    } else {
+        Command::new("secret-tool")
+            .args(["lookup", "service", KEYCHAIN_SERVICE, "account", account])
+            .stderr(Stdio::null())
+            .output()
+    }
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let passphrase = String::from_utf8(output.stdout).ok()?;
+    let passphrase = passphrase.trim_end_matches(['\r', '\n']);
+    (!passphrase.is_empty()).then(|| passphrase.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn seals_and_opens_with_the_project_passphrase() {
+        let dir = TempDir::new().unwrap();
+        let cipher = enable(dir.path(), "correct horse").unwrap();
+        let info = info(dir.path()).unwrap().unwrap();
+
+        let sealed = cipher.seal("{\"title\":\"Secret plan\"}");
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.contains("Secret"));
+        assert_ne!(sealed, cipher.seal("{\"title\":\"Secret plan\"}"));
+
+        let unlocked = unlock_with(&info, "correct horse").unwrap();
+        assert_eq!(
+            unlocked.open(&sealed).unwrap(),
+            "{\"title\":\"Secret plan\"}"
+        );
+
+        let err = unlock_with(&info, "wrong").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<EncryptionError>(),
+            Some(EncryptionError::WrongPassphrase)
+        ));
+
+        disable(dir.path()).unwrap();
+        assert!(!is_enabled(dir.path()));
+        assert!(super::cipher(dir.path()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,114 @@
+//! Write fences
+//!
+//! A command that writes several times (an import, a split, `shape tx
+//! commit`) raises a fence for its whole run: a record in
+//! `.shape/.cache/fences/<pid>.json` naming the operation. The daemon holds
+//! its commit while any fence is up, so the operation lands as one commit
+//! instead of one per write.
+//!
+//! Each process has one fence; raising it again while it is up (a command
+//! running another) leaves the outer one in charge.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+fn fence_dir(shape_dir: &Path) -> PathBuf {
+    shape_dir.join(".cache").join("fences")
+}
+
+/// An operation in progress, as recorded in its fence
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FenceHolder {
+    pub pid: u32,
+
+    /// Command running, e.g. `task import`
+    pub operation: String,
+
+    pub started_at: DateTime<Utc>,
+}
+
+/// A raised fence, lowered when dropped
+pub struct WriteFence {
+    /// `None` when an outer fence of this process is already up
+    path: Option<PathBuf>,
+}
+
+impl WriteFence {
+    /// Raises this process's fence for `operation`
+    pub fn raise(shape_dir: &Path, operation: &str) -> Result<Self> {
+        let dir = fence_dir(shape_dir);
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        let pid = std::process::id();
+        let path = dir.join(format!("{}.json", pid));
+        if path.exists() {
+            return Ok(Self { path: None });
+        }
+        let holder = FenceHolder {
+            pid,
+            operation: operation.to_string(),
+            started_at: Utc::now(),
+        };
+        fs::write(&path, serde_json::to_string(&holder)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Self { path: Some(path) })
+    }
+
+    /// Fences currently recorded, oldest first. Records of processes that
+    /// died without lowering theirs are included; see [`WriteFence::clear`].
+    pub fn holders(shape_dir: &Path) -> Vec<FenceHolder> {
+        let Ok(entries) = fs::read_dir(fence_dir(shape_dir)) else {
+            return Vec::new();
+        };
+        let mut holders: Vec<FenceHolder> = entries
+            .filter_map(|e| fs::read_to_string(e.ok()?.path()).ok())
+            .filter_map(|content| serde_json::from_str(&content).ok())
+            .collect();
+        holders.sort_by_key(|h| h.started_at);
+        holders
+    }
+
+    /// Removes the record of a process's fence
+    pub fn clear(shape_dir: &Path, pid: u32) {
+        let _ = fs::remove_file(fence_dir(shape_dir).join(format!("{}.json", pid)));
+    }
+}
+
+impl Drop for WriteFence {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn fence_is_up_until_the_outer_guard_drops() {
+        let dir = TempDir::new().unwrap();
+        assert!(WriteFence::holders(dir.path()).is_empty());
+
+        let outer = WriteFence::raise(dir.path(), "task import").unwrap();
+        let inner = WriteFence::raise(dir.path(), "task add").unwrap();
+        drop(inner);
+        let holders = WriteFence::holders(dir.path());
+        assert_eq!(holders.len(), 1);
+        assert_eq!(holders[0].operation, "task import");
+        assert_eq!(holders[0].pid, std::process::id());
+
+        drop(outer);
+        assert!(WriteFence::holders(dir.path()).is_empty());
+
+        let _fence = WriteFence::raise(dir.path(), "tx").unwrap();
+        WriteFence::clear(dir.path(), std::process::id());
+        assert!(WriteFence::holders(dir.path()).is_empty());
+    }
+}
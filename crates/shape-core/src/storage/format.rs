@@ -0,0 +1,193 @@
+//! On-disk format versioning and migrations
+//!
+//! The project format version lives in two places: `format_version` in
+//! `.shape/config.toml` and a header line at the top of `tasks.jsonl`:
+//!
+//! ```text
+//! {"format_version":1}
+//! {"id":"b-7f2a3b1.1",...}
+//! ```
+//!
+//! Projects without either are version 0 (created before versioning) and keep
+//! working. Anything newer than [`FORMAT_VERSION`] is refused so an old
+//! binary never rewrites data it doesn't understand. `shape migrate` applies
+//! the steps in [`MIGRATIONS`] to bring a project up to date.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::TaskStore;
+
+/// Format version written by this build
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum FormatError {
+    #[error("{file} uses format version {found}, but this shape supports up to version {}. Upgrade shape to work with this project.", FORMAT_VERSION)]
+    TooNew { file: String, found: u32 },
+}
+
+/// Fails if `found` is newer than this build understands
+pub fn check_version(file: &str, found: u32) -> Result<(), FormatError> {
+    if found > FORMAT_VERSION {
+        return Err(FormatError::TooNew {
+            file: file.to_string(),
+            found,
+        });
+    }
+    Ok(())
+}
+
+/// First line of `tasks.jsonl`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TasksHeader {
+    pub format_version: u32,
+}
+
+impl TasksHeader {
+    pub fn current() -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+        }
+    }
+
+    /// Parses a line as a header; task lines return `None`
+    pub fn parse(line: &str) -> Option<Self> {
+        serde_json::from_str(line).ok()
+    }
+}
+
+/// One step from `from` to `from + 1`
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    apply: fn(&Path) -> Result<()>,
+}
+
+impl Migration {
+    /// Applies the step to the project at `root`
+    pub fn apply(&self, root: &Path) -> Result<()> {
+        (self.apply)(root)
+    }
+}
+
+/// All migration steps, oldest first
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    from: 0,
+    description: "Add format_version to config.toml and a header line to tasks.jsonl; \
+                  rewrite tasks in the current field layout",
+    apply: migrate_v0_to_v1,
+}];
+
+/// Returns the steps needed to bring a project at `version` up to date
+pub fn pending_migrations(version: u32) -> Vec<&'static Migration> {
+    MIGRATIONS.iter().filter(|m| m.from >= version).collect()
+}
+
+/// Version 1 introduced explicit versioning. Tasks are rewritten through the
+/// current serializer, which normalizes legacy dependency lists and missing
+/// field versions.
+fn migrate_v0_to_v1(root: &Path) -> Result<()> {
+    let store = TaskStore::for_project(root);
+    if store.path().exists() {
+        let tasks: HashMap<_, _> = store.read_all()?;
+        store.write_all_with_header(&tasks, Some(TasksHeader { format_version: 1 }))?;
+    }
+    set_config_version(root, 1)
+}
+
+/// Writes `format_version = N` into `config.toml`, replacing an existing
+/// value or adding it before the first table so comments are preserved
+pub fn set_config_version(root: &Path, version: u32) -> Result<()> {
+    let path = root.join(".shape").join("config.toml");
+    let content = if path.exists() {
+        fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config: {}", path.display()))?
+    } else {
+        String::new()
+    };
+
+    let line = format!("format_version = {}", version);
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let top_level_end = lines
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .unwrap_or(lines.len());
+
+    match lines[..top_level_end]
+        .iter()
+        .position(|l| l.trim_start().starts_with("format_version"))
+    {
+        Some(i) => lines[i] = line,
+        None => {
+            // After the leading comment block
+            let at = lines[..top_level_end]
+                .iter()
+                .position(|l| !l.trim_start().starts_with('#'))
+                .unwrap_or(top_level_end);
+            lines.insert(at, String::new());
+            lines.insert(
+                at + 1,
+                "# On-disk format version (see 'shape migrate')".into(),
+            );
+            lines.insert(at + 2, line);
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    fs::write(&path, updated).with_context(|| format!("Failed to write config: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn rejects_newer_versions() {
+        assert!(check_version("tasks.jsonl", FORMAT_VERSION).is_ok());
+        let err = check_version("tasks.jsonl", FORMAT_VERSION + 1).unwrap_err();
+        assert!(err.to_string().contains("Upgrade shape"));
+    }
+
+    #[test]
+    fn header_is_distinguished_from_tasks() {
+        assert_eq!(
+            TasksHeader::parse(r#"{"format_version":1}"#),
+            Some(TasksHeader { format_version: 1 })
+        );
+        assert_eq!(
+            TasksHeader::parse(r#"{"id":"t-1234567","title":"x"}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn config_version_keeps_comments_and_tables() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join(".shape")).unwrap();
+        let path = dir.path().join(".shape/config.toml");
+        fs::write(
+            &path,
+            "# Shape CLI configuration\n\ncontext_days = 7\n\n[agent]\nclaim_timeout_hours = 2\n",
+        )
+        .unwrap();
+
+        set_config_version(dir.path(), 1).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# Shape CLI configuration\n"));
+        assert!(content.find("format_version = 1").unwrap() < content.find("[agent]").unwrap());
+
+        set_config_version(dir.path(), 2).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content.matches("format_version").count(), 1);
+        assert!(content.contains("format_version = 2"));
+        assert!(content.contains("claim_timeout_hours = 2"));
+    }
+}
@@ -0,0 +1,476 @@
+//! Brief frontmatter validation
+//!
+//! Hand-edited frontmatter is checked key by key before it is deserialized,
+//! so a mistake is reported with its line, the key involved, what was
+//! expected and, for misspelled keys and values, the closest valid spelling.
+//! Unknown keys are legitimate brief metadata; only those that look like a
+//! typo of a known field are flagged, as warnings.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_yaml::Value;
+use thiserror::Error;
+
+use crate::domain::{BriefEvent, BriefFrontmatter, BriefId, ExternalIds, FreezeInfo};
+
+/// Brief statuses as written in frontmatter
+const STATUSES: &[&str] = &["proposed", "betting", "in_progress", "shipped", "archived"];
+
+/// Old or alternative spellings of known fields
+const ALIASES: &[(&str, &str)] = &[
+    ("created", "created_at"),
+    ("updated", "updated_at"),
+    ("brief_type", "type"),
+    ("state", "status"),
+];
+
+/// Expected shape of a frontmatter value
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Text,
+    Id,
+    Status,
+    Timestamp,
+    TextList,
+    External,
+    Freeze,
+    History,
+}
+
+impl Kind {
+    fn expected(self) -> &'static str {
+        match self {
+            Kind::Text => "a string",
+            Kind::Id => "a brief ID like b-7f2a3b1",
+            Kind::Status => "one of proposed, betting, in_progress, shipped, archived",
+            Kind::Timestamp => "an RFC 3339 timestamp like 2025-01-16T10:30:00Z",
+            Kind::TextList => "a list of strings",
+            Kind::External => "a mapping of system to ID, e.g. {github: \"456\"}",
+            Kind::Freeze => "a mapping with reason, by and at",
+            Kind::History => "a list of events with at and event",
+        }
+    }
+}
+
+/// Known frontmatter fields: name, expected kind and whether it is required
+const FIELDS: &[(&str, Kind, bool)] = &[
+    ("id", Kind::Id, true),
+    ("title", Kind::Text, true),
+    ("type", Kind::Text, true),
+    ("status", Kind::Status, true),
+    ("created_at", Kind::Timestamp, true),
+    ("updated_at", Kind::Timestamp, true),
+    ("started_at", Kind::Timestamp, false),
+    ("owner", Kind::Text, false),
+    ("co_owners", Kind::TextList, false),
+    ("external_ids", Kind::External, false),
+    ("frozen", Kind::Freeze, false),
+    ("parent", Kind::Id, false),
+    ("history", Kind::History, false),
+    ("summary", Kind::Text, false),
+    ("summary_hash", Kind::Text, false),
+    ("definition_of_done", Kind::TextList, false),
+];
+
+/// Severity of a frontmatter issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    /// The brief cannot be loaded
+    Error,
+    /// The brief loads, but probably not as intended
+    Warning,
+}
+
+/// A problem found in a brief's frontmatter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FrontmatterIssue {
+    pub severity: IssueSeverity,
+    /// 1-based line in the brief file
+    pub line: usize,
+    /// 1-based column, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    /// The offending key, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub message: String,
+    /// Likely intended key or value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+impl FrontmatterIssue {
+    fn error(line: usize, key: Option<&str>, message: String) -> Self {
+        Self {
+            severity: IssueSeverity::Error,
+            line,
+            column: None,
+            key: key.map(str::to_string),
+            message,
+            suggestion: None,
+        }
+    }
+
+    fn suggest(mut self, suggestion: Option<&str>) -> Self {
+        self.suggestion = suggestion.map(str::to_string);
+        self
+    }
+
+    /// The message with its suggestion, without the location
+    pub fn detail(&self) -> String {
+        match &self.suggestion {
+            Some(suggestion) => format!("{} (did you mean `{}`?)", self.message, suggestion),
+            None => self.message.clone(),
+        }
+    }
+}
+
+impl fmt::Display for FrontmatterIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}", self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ", column {}", column)?;
+        }
+        write!(f, ": {}", self.detail())
+    }
+}
+
+/// Frontmatter that failed validation; holds every error-level issue
+#[derive(Debug, Error)]
+#[error("Invalid frontmatter: {}", join(.0))]
+pub struct FrontmatterError(pub Vec<FrontmatterIssue>);
+
+fn join(issues: &[FrontmatterIssue]) -> String {
+    issues
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Frontmatter and body of a brief file
+struct Sections<'a> {
+    yaml: &'a str,
+    /// Line of the file on which `yaml` starts
+    first_line: usize,
+    body: &'a str,
+}
+
+fn split(content: &str) -> Result<Sections<'_>, FrontmatterIssue> {
+    let trimmed = content.trim();
+    let start_line = line_of(content, content.len() - content.trim_start().len());
+
+    if !trimmed.starts_with("---") {
+        return Err(FrontmatterIssue::error(
+            start_line,
+            None,
+            "Missing frontmatter (must start with ---)".to_string(),
+        ));
+    }
+
+    let rest = &trimmed[3..];
+    let Some(end_pos) = rest.find("---") else {
+        return Err(FrontmatterIssue::error(
+            start_line,
+            None,
+            "Missing frontmatter end delimiter (---)".to_string(),
+        ));
+    };
+
+    let raw = &rest[..end_pos];
+    let yaml = raw.trim();
+    let offset =
+        content.len() - content.trim_start().len() + 3 + (raw.len() - raw.trim_start().len());
+    Ok(Sections {
+        yaml,
+        first_line: line_of(content, offset),
+        body: rest[end_pos + 3..].trim(),
+    })
+}
+
+/// 1-based line of a byte offset
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+/// Checks a brief file's frontmatter, returning errors and warnings in line order
+pub fn validate(content: &str) -> Vec<FrontmatterIssue> {
+    match split(content) {
+        Ok(sections) => check(&sections),
+        Err(issue) => vec![issue],
+    }
+}
+
+/// Parses a brief file into its frontmatter and body, failing with every
+/// error-level issue
+pub(crate) fn parse(content: &str) -> Result<(BriefFrontmatter, String), FrontmatterError> {
+    let sections = split(content).map_err(|issue| FrontmatterError(vec![issue]))?;
+    let errors: Vec<_> = check(&sections)
+        .into_iter()
+        .filter(|i| i.severity == IssueSeverity::Error)
+        .collect();
+    if !errors.is_empty() {
+        return Err(FrontmatterError(errors));
+    }
+
+    // Validation covers every field, so this only fails on shapes it missed
+    let fm: BriefFrontmatter = serde_yaml::from_str(sections.yaml)
+        .map_err(|e| FrontmatterError(vec![yaml_issue(&e, sections.first_line)]))?;
+    Ok((fm, sections.body.to_string()))
+}
+
+fn yaml_issue(err: &serde_yaml::Error, first_line: usize) -> FrontmatterIssue {
+    let message = err.to_string();
+    // serde_yaml appends its own location, relative to the frontmatter
+    let message = message
+        .split(" at line ")
+        .next()
+        .unwrap_or(&message)
+        .to_string();
+    let location = err.location();
+    FrontmatterIssue {
+        column: location.as_ref().map(|l| l.column()),
+        ..FrontmatterIssue::error(
+            first_line + location.map_or(0, |l| l.line().saturating_sub(1)),
+            None,
+            message,
+        )
+    }
+}
+
+fn check(sections: &Sections<'_>) -> Vec<FrontmatterIssue> {
+    let value: Value = match serde_yaml::from_str(sections.yaml) {
+        Ok(value) => value,
+        Err(e) => return vec![yaml_issue(&e, sections.first_line)],
+    };
+    let Value::Mapping(map) = value else {
+        return vec![FrontmatterIssue::error(
+            sections.first_line,
+            None,
+            "Frontmatter must be a mapping of `key: value` lines".to_string(),
+        )];
+    };
+
+    let key_line = |key: &str| {
+        sections
+            .yaml
+            .lines()
+            .position(|line| {
+                line.split_once(':').is_some_and(|(k, _)| {
+                    !k.starts_with(char::is_whitespace) && k.trim_matches(['"', '\'']) == key
+                })
+            })
+            .map_or(sections.first_line, |i| sections.first_line + i)
+    };
+
+    let mut issues = Vec::new();
+    let keys: Vec<&str> = map.keys().filter_map(Value::as_str).collect();
+
+    for (name, kind, required) in FIELDS {
+        match map.get(*name) {
+            Some(value) => {
+                if let Some(issue) = check_value(name, *kind, value, key_line(name)) {
+                    issues.push(issue);
+                }
+            }
+            None if *required => {
+                let typo = keys.iter().find(|k| resembles(k) == Some(name));
+                let line = typo.map_or(sections.first_line, |k| key_line(k));
+                issues.push(
+                    FrontmatterIssue::error(
+                        line,
+                        Some(name),
+                        format!("Missing required key `{}`", name),
+                    )
+                    .suggest(typo.map(|_| *name)),
+                );
+            }
+            None => {}
+        }
+    }
+
+    for key in &keys {
+        let Some(field) = resembles(key) else {
+            continue;
+        };
+        // A missing required field is already reported at the typo
+        let missing_required = !map.contains_key(field)
+            && FIELDS
+                .iter()
+                .any(|(name, _, required)| *name == field && *required);
+        if !missing_required {
+            issues.push(FrontmatterIssue {
+                severity: IssueSeverity::Warning,
+                line: key_line(key),
+                column: None,
+                key: Some(key.to_string()),
+                message: format!("Unknown key `{}` is kept as metadata", key),
+                suggestion: Some(field.to_string()),
+            });
+        }
+    }
+
+    issues.sort_by_key(|i| i.line);
+    issues
+}
+
+/// Known field that an unknown key is probably a misspelling of
+fn resembles(key: &str) -> Option<&'static str> {
+    if FIELDS.iter().any(|(name, _, _)| *name == key) {
+        return None;
+    }
+    if let Some((_, field)) = ALIASES.iter().find(|(alias, _)| *alias == key) {
+        return Some(field);
+    }
+    closest(key, FIELDS.iter().map(|(name, _, _)| *name))
+}
+
+/// Closest candidate within a small edit distance
+fn closest<'a>(word: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let limit = if word.chars().count() <= 4 { 1 } else { 2 };
+    candidates
+        .map(|c| (edit_distance(&word.to_lowercase(), c), c))
+        .filter(|(d, _)| *d <= limit)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+/// Levenshtein distance
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            let next = (row[j + 1] + 1).min(row[j] + 1).min(prev + cost);
+            prev = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    row[b.len()]
+}
+
+fn check_value(key: &str, kind: Kind, value: &Value, line: usize) -> Option<FrontmatterIssue> {
+    let mismatch = |found: String| {
+        FrontmatterIssue::error(
+            line,
+            Some(key),
+            format!("`{}` must be {}, found {}", key, kind.expected(), found),
+        )
+    };
+
+    match kind {
+        Kind::Text => (!value.is_string()).then(|| mismatch(describe(value))),
+        Kind::Id => match value.as_str() {
+            Some(s) if s.parse::<BriefId>().is_ok() => None,
+            _ => Some(mismatch(describe(value))),
+        },
+        Kind::Status => match value.as_str() {
+            Some(s) if STATUSES.contains(&s) => None,
+            Some(s) => Some(mismatch(describe(value)).suggest(
+                closest(s, STATUSES.iter().copied()).or_else(|| {
+                    let normalized = s.to_lowercase().replace([' ', '-'], "_");
+                    STATUSES.iter().copied().find(|st| *st == normalized)
+                }),
+            )),
+            None => Some(mismatch(describe(value))),
+        },
+        Kind::Timestamp => match value.as_str() {
+            Some(s) if s.parse::<DateTime<Utc>>().is_ok() => None,
+            _ => Some(mismatch(describe(value))),
+        },
+        Kind::TextList => match value.as_sequence() {
+            Some(items) if items.iter().all(Value::is_string) => None,
+            _ => Some(mismatch(describe(value))),
+        },
+        Kind::External => serde_yaml::from_value::<ExternalIds>(value.clone())
+            .err()
+            .map(|_| mismatch(describe(value))),
+        Kind::Freeze => serde_yaml::from_value::<FreezeInfo>(value.clone())
+            .err()
+            .map(|_| mismatch(describe(value))),
+        Kind::History => serde_yaml::from_value::<Vec<BriefEvent>>(value.clone())
+            .err()
+            .map(|_| mismatch(describe(value))),
+    }
+}
+
+/// Short description of a value for error messages
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "nothing".to_string(),
+        Value::Bool(b) => format!("`{}`", b),
+        Value::Number(n) => format!("`{}`", n),
+        Value::String(s) => format!("`{}`", s),
+        Value::Sequence(_) => "a list".to_string(),
+        Value::Mapping(_) => "a mapping".to_string(),
+        Value::Tagged(_) => "a tagged value".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID: &str = "---\nid: b-7f2a3b1\ntitle: Auth\ntype: minimal\nstatus: proposed\ncreated_at: 2025-01-16T10:30:00Z\nupdated_at: 2025-01-16T10:30:00Z\n---\n\nBody\n";
+
+    #[test]
+    fn valid_frontmatter_has_no_issues() {
+        assert!(validate(VALID).is_empty());
+        let (fm, body) = parse(VALID).unwrap();
+        assert_eq!(fm.title, "Auth");
+        assert_eq!(body, "Body");
+    }
+
+    #[test]
+    fn reports_line_key_and_suggestion() {
+        let content = VALID.replace("status: proposed", "status: inprogress");
+        let issues = validate(&content);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, 5);
+        assert_eq!(issues[0].key.as_deref(), Some("status"));
+        assert_eq!(issues[0].suggestion.as_deref(), Some("in_progress"));
+        assert!(issues[0].message.contains("must be one of"));
+
+        let err = parse(&content).unwrap_err();
+        assert!(err.to_string().contains("line 5"));
+    }
+
+    #[test]
+    fn misspelled_keys() {
+        // Misspelled required key: an error at the typo's line
+        let content = VALID.replace("title:", "titel:");
+        let issues = validate(&content);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+        assert_eq!(issues[0].line, 3);
+        assert_eq!(issues[0].suggestion.as_deref(), Some("title"));
+
+        // Misspelled optional key and unrelated metadata: only the typo warns
+        let content = VALID.replace("---\n\n", "ownr: alice\nappetite: 2-weeks\n---\n\n");
+        let issues = validate(&content);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Warning);
+        assert_eq!(issues[0].line, 8);
+        assert_eq!(issues[0].suggestion.as_deref(), Some("owner"));
+        assert!(parse(&content).is_ok());
+    }
+
+    #[test]
+    fn syntax_errors_point_into_the_file() {
+        let content = VALID.replace("title: Auth", "title: [unclosed");
+        let issues = validate(&content);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].line >= 3);
+        assert!(issues[0].column.is_some());
+
+        let content = VALID.replace("created_at: 2025-01-16T10:30:00Z", "created_at: yesterday");
+        let issues = validate(&content);
+        assert!(issues[0].message.contains("RFC 3339"));
+    }
+}
@@ -0,0 +1,147 @@
+//! Idempotency keys
+//!
+//! A command run with `--idempotency-key` is recorded in
+//! `.shape/.cache/idempotency.jsonl` together with what it printed. Running
+//! it again with the same key prints the recorded output instead of applying
+//! the change twice, so agents can retry after a timeout. Keys are kept for
+//! [`RETENTION_HOURS`].
+
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+
+/// How long a key is remembered
+pub const RETENTION_HOURS: i64 = 24;
+
+/// A command that ran with an idempotency key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    /// The key supplied by the caller
+    pub key: String,
+
+    /// Arguments of the command, without the key
+    pub command: String,
+
+    /// When the command completed
+    pub at: DateTime<Utc>,
+
+    /// What the command printed on success
+    #[serde(default)]
+    pub output: String,
+}
+
+/// Recently seen idempotency keys
+pub struct IdempotencyLog {
+    path: PathBuf,
+}
+
+/// Held while a keyed command runs, so a retry waits for the original
+pub struct IdempotencyLock {
+    _file: File,
+}
+
+impl IdempotencyLog {
+    /// Creates the log for a project's `.shape` directory
+    pub fn for_project(shape_dir: &Path) -> Self {
+        Self {
+            path: shape_dir.join(".cache").join("idempotency.jsonl"),
+        }
+    }
+
+    /// Blocks until no other keyed command is running
+    pub fn lock(&self) -> Result<IdempotencyLock> {
+        let path = self.path.with_extension("lock");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        file.lock_exclusive()
+            .with_context(|| format!("Failed to lock {}", path.display()))?;
+        Ok(IdempotencyLock { _file: file })
+    }
+
+    /// Returns the unexpired record for a key
+    pub fn find(&self, key: &str) -> Result<Option<IdempotencyRecord>> {
+        Ok(self.read()?.into_iter().find(|r| r.key == key))
+    }
+
+    /// Records a completed command, replacing any record with the same key
+    /// and dropping expired ones
+    pub fn record(&self, record: IdempotencyRecord) -> Result<()> {
+        let mut records: Vec<_> = self
+            .read()?
+            .into_iter()
+            .filter(|r| r.key != record.key)
+            .collect();
+        records.push(record);
+
+        let mut content = String::new();
+        for record in &records {
+            content.push_str(&serde_json::to_string(record)?);
+            content.push('\n');
+        }
+        let tmp = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp, content).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(())
+    }
+
+    /// Unexpired records, oldest first. Unreadable lines are skipped: the
+    /// log is a local cache.
+    fn read(&self) -> Result<Vec<IdempotencyRecord>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        let cutoff = Utc::now() - Duration::hours(RETENTION_HOURS);
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<IdempotencyRecord>(line).ok())
+            .filter(|r| r.at > cutoff)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn record(key: &str, at: DateTime<Utc>) -> IdempotencyRecord {
+        IdempotencyRecord {
+            key: key.to_string(),
+            command: "note b-1.1 done".to_string(),
+            at,
+            output: "{\"success\":true}\n".to_string(),
+        }
+    }
+
+    #[test]
+    fn records_are_found_until_they_expire() {
+        let dir = TempDir::new().unwrap();
+        let log = IdempotencyLog::for_project(dir.path());
+        let _lock = log.lock().unwrap();
+        assert_eq!(log.find("k1").unwrap(), None);
+
+        let old = Utc::now() - Duration::hours(RETENTION_HOURS + 1);
+        log.record(record("old", old)).unwrap();
+        log.record(record("k1", Utc::now())).unwrap();
+
+        assert_eq!(log.find("k1").unwrap().unwrap().command, "note b-1.1 done");
+        assert_eq!(log.find("old").unwrap(), None);
+        let lines = fs::read_to_string(dir.path().join(".cache/idempotency.jsonl")).unwrap();
+        assert_eq!(lines.lines().count(), 1);
+    }
+}
@@ -0,0 +1,239 @@
+//! Operation journal
+//!
+//! Commands that change tasks record what each task looked like before and
+//! after in `.shape/journal.jsonl`, so `shape undo` can put the earlier
+//! versions back and `shape redo` the later ones. The journal is local
+//! history (it is ignored by git) and keeps the last [`MAX_ENTRIES`]
+//! commands.
+//!
+//! In an encrypted store every entry is sealed like the task lines it
+//! copies.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+
+use super::encryption::{self, StoreCipher};
+use crate::domain::{Task, TaskId};
+
+/// How many commands are kept
+pub const MAX_ENTRIES: usize = 100;
+
+/// One command's changes to tasks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Increasing number identifying the entry
+    pub seq: u64,
+
+    /// When the command ran
+    pub at: DateTime<Utc>,
+
+    /// Who ran it
+    pub by: String,
+
+    /// Subcommand that made the changes, e.g. `task done`
+    pub command: String,
+
+    /// Tasks the command changed
+    pub changes: Vec<TaskChange>,
+
+    /// Set while the entry is undone, until it is redone
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub undone: bool,
+}
+
+/// A task before and after a command; `None` when it didn't exist
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskChange {
+    pub id: TaskId,
+    pub before: Option<Task>,
+    pub after: Option<Task>,
+}
+
+impl JournalEntry {
+    /// Changes between two snapshots of the store, in ID order
+    pub fn diff(before: &HashMap<TaskId, Task>, after: &HashMap<TaskId, Task>) -> Vec<TaskChange> {
+        let mut ids: Vec<&TaskId> = before.keys().chain(after.keys()).collect();
+        ids.sort();
+        ids.dedup();
+        ids.into_iter()
+            .filter(|id| before.get(*id) != after.get(*id))
+            .map(|id| TaskChange {
+                id: id.clone(),
+                before: before.get(id).cloned(),
+                after: after.get(id).cloned(),
+            })
+            .collect()
+    }
+}
+
+/// The project's operation journal
+pub struct Journal {
+    path: PathBuf,
+}
+
+/// Held while the journal is read and rewritten
+pub struct JournalLock {
+    _file: File,
+}
+
+impl Journal {
+    /// Creates the journal for a project's `.shape` directory
+    pub fn for_project(shape_dir: &Path) -> Self {
+        Self {
+            path: shape_dir.join("journal.jsonl"),
+        }
+    }
+
+    fn shape_dir(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new("."))
+    }
+
+    /// Blocks until no other process is recording, undoing or redoing
+    pub fn lock(&self) -> Result<JournalLock> {
+        let path = self.shape_dir().join(".cache").join("journal.lock");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        file.lock_exclusive()
+            .with_context(|| format!("Failed to lock {}", path.display()))?;
+        Ok(JournalLock { _file: file })
+    }
+
+    /// Entries, oldest first
+    pub fn entries(&self) -> Result<Vec<JournalEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        let mut cipher: Option<Option<StoreCipher>> = None;
+        let mut entries = Vec::new();
+        for (i, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line = if encryption::is_sealed(line) {
+                if cipher.is_none() {
+                    cipher = Some(encryption::cipher(self.shape_dir())?);
+                }
+                let Some(Some(cipher)) = &cipher else {
+                    anyhow::bail!(
+                        "{} has encrypted entries but the store isn't encrypted",
+                        self.path.display()
+                    );
+                };
+                cipher
+                    .open(line)
+                    .with_context(|| format!("Failed to decrypt journal entry {}", i + 1))?
+            } else {
+                line.to_string()
+            };
+            let entry = serde_json::from_str(&line)
+                .with_context(|| format!("Invalid journal entry on line {}", i + 1))?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    /// Records a command's changes. Undone entries can no longer be redone
+    /// once something else changed, so they are dropped.
+    pub fn record(
+        &self,
+        by: &str,
+        command: &str,
+        changes: Vec<TaskChange>,
+    ) -> Result<JournalEntry> {
+        let mut entries = self.entries()?;
+        let seq = entries.last().map_or(1, |e| e.seq + 1);
+        entries.retain(|e| !e.undone);
+        let entry = JournalEntry {
+            seq,
+            at: Utc::now(),
+            by: by.to_string(),
+            command: command.to_string(),
+            changes,
+            undone: false,
+        };
+        entries.push(entry.clone());
+        let excess = entries.len().saturating_sub(MAX_ENTRIES);
+        entries.drain(..excess);
+        self.write(&entries)?;
+        Ok(entry)
+    }
+
+    /// Replaces every entry
+    pub fn write(&self, entries: &[JournalEntry]) -> Result<()> {
+        let cipher = encryption::cipher(self.shape_dir())?;
+        let mut content = String::new();
+        for entry in entries {
+            let line = serde_json::to_string(entry)?;
+            match &cipher {
+                Some(cipher) => content.push_str(&cipher.seal(&line)),
+                None => content.push_str(&line),
+            }
+            content.push('\n');
+        }
+        let tmp = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp, content).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::BriefId;
+    use tempfile::TempDir;
+
+    #[test]
+    fn record_drops_undone_entries_and_keeps_the_latest() {
+        let dir = TempDir::new().unwrap();
+        let journal = Journal::for_project(dir.path());
+        let _lock = journal.lock().unwrap();
+        assert!(journal.entries().unwrap().is_empty());
+
+        let brief = BriefId::new("Journal", Utc::now());
+        let id = TaskId::new(&brief, 1);
+        let before = HashMap::new();
+        let after = HashMap::from([(id.clone(), Task::new(id.clone(), "Write it down"))]);
+        let changes = JournalEntry::diff(&before, &after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].before, None);
+        assert!(JournalEntry::diff(&after, &after).is_empty());
+
+        journal
+            .record("alice", "task add", changes.clone())
+            .unwrap();
+        let mut entries = journal.entries().unwrap();
+        entries[0].undone = true;
+        journal.write(&entries).unwrap();
+        let entry = journal
+            .record("alice", "task done", changes.clone())
+            .unwrap();
+        assert_eq!(journal.entries().unwrap(), vec![entry]);
+
+        for _ in 0..MAX_ENTRIES {
+            journal
+                .record("alice", "task edit", changes.clone())
+                .unwrap();
+        }
+        let entries = journal.entries().unwrap();
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries.last().unwrap().seq, MAX_ENTRIES as u64 + 2);
+    }
+}
@@ -0,0 +1,829 @@
+//! JSONL storage for tasks
+//!
+//! Tasks are stored in `.shape/tasks.jsonl` with one JSON object per line.
+//! Uses file locking for concurrent access safety; see [`lock`](super::lock)
+//! for timeouts.
+//!
+//! Readers take a shared lock on `tasks.jsonl` itself. Writers (appends and
+//! full rewrites alike) first take the exclusive write lock on
+//! `.cache/tasks.write.lock`, a file that is never renamed, so a rewrite's
+//! read, change and rename can't interleave with any other write.
+//!
+//! Versioned files start with a `{"format_version":N}` header line (see
+//! [`format`](super::format)); files from before versioning have none.
+//!
+//! In an encrypted store every task line is sealed (see
+//! [`encryption`](super::encryption)); the header stays plaintext.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use super::encryption::{self, EncryptionError, StoreCipher};
+use super::format::{check_version, TasksHeader};
+use super::journal::{JournalEntry, TaskChange};
+use super::lock::{self, LockGuard, LockMode};
+use crate::domain::{by_id, Task, TaskId};
+
+/// Writes this process is recording (see [`TaskStore::record_writes`])
+static RECORDING: Mutex<Option<Recording>> = Mutex::new(None);
+
+/// The store being recorded and, per task, its state before the first
+/// recorded write and after the last
+struct Recording {
+    path: PathBuf,
+    changes: HashMap<TaskId, TaskChange>,
+}
+
+/// Records the writes this process makes to one store, for the undo
+/// journal. Recording stops when the recorder is finished or dropped.
+pub struct WriteRecorder {
+    _private: (),
+}
+
+impl WriteRecorder {
+    /// Stops recording and returns every task the writes changed, in ID
+    /// order. Tasks written back unchanged are left out.
+    pub fn finish(self) -> Vec<TaskChange> {
+        let recording = RECORDING.lock().unwrap_or_else(|e| e.into_inner()).take();
+        let mut changes: Vec<TaskChange> = recording
+            .map(|r| r.changes.into_values().collect())
+            .unwrap_or_default();
+        changes.retain(|c| c.before != c.after);
+        changes.sort_by(|a, b| a.id.cmp(&b.id));
+        changes
+    }
+}
+
+impl Drop for WriteRecorder {
+    fn drop(&mut self) {
+        *RECORDING.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+/// Store for task data in JSONL format
+pub struct TaskStore {
+    path: PathBuf,
+    lock_timeout: Option<Duration>,
+}
+
+impl TaskStore {
+    /// Creates a new task store at the given path
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock_timeout: None,
+        }
+    }
+
+    /// Fails with [`LockError::Timeout`](super::LockError::Timeout) instead
+    /// of waiting longer than `timeout` for another process's lock
+    pub fn with_lock_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_timeout = Some(timeout);
+        self
+    }
+
+    /// Creates the default store for a project
+    pub fn for_project(project_root: &Path) -> Self {
+        Self::new(project_root.join(".shape").join("tasks.jsonl"))
+    }
+
+    /// Returns the path to the store file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Directory the store lives in
+    fn shape_dir(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new("."))
+    }
+
+    /// File where the holder of the exclusive lock records its PID
+    pub fn lock_holder_path(&self) -> PathBuf {
+        self.shape_dir().join(".cache").join("tasks.lock")
+    }
+
+    /// File writers lock; it stays in place while `tasks.jsonl` is replaced
+    fn write_lock_path(&self) -> PathBuf {
+        self.shape_dir().join(".cache").join("tasks.write.lock")
+    }
+
+    /// Temp file a full rewrite goes through before it is renamed into place
+    fn temp_path(&self) -> PathBuf {
+        self.path.with_extension("jsonl.tmp")
+    }
+
+    /// Returns true while another process is reading or writing the store
+    pub fn is_locked(&self) -> bool {
+        lock::is_locked(&self.path) || lock::is_locked(&self.write_lock_path())
+    }
+
+    fn lock(&self, file: &File, mode: LockMode) -> Result<LockGuard> {
+        lock::acquire(
+            file,
+            mode,
+            self.lock_timeout,
+            &self.path,
+            &self.lock_holder_path(),
+        )
+    }
+
+    /// Starts recording this process's writes to the store.
+    ///
+    /// Each write notes the tasks it changed while it still holds the write
+    /// lock, so other processes' writes in the meantime are never mistaken
+    /// for this one's.
+    pub fn record_writes(&self) -> WriteRecorder {
+        *RECORDING.lock().unwrap_or_else(|e| e.into_inner()) = Some(Recording {
+            path: self.path.clone(),
+            changes: HashMap::new(),
+        });
+        WriteRecorder { _private: () }
+    }
+
+    fn is_recorded(&self) -> bool {
+        RECORDING
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .is_some_and(|r| r.path == self.path)
+    }
+
+    /// Adds a write's changes to the recording, if this store is recorded
+    fn note_writes(&self, before: &HashMap<TaskId, Task>, after: &HashMap<TaskId, Task>) {
+        let mut recording = RECORDING.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(recording) = recording.as_mut().filter(|r| r.path == self.path) else {
+            return;
+        };
+        for change in JournalEntry::diff(before, after) {
+            recording
+                .changes
+                .entry(change.id.clone())
+                .and_modify(|c| c.after = change.after.clone())
+                .or_insert(change);
+        }
+    }
+
+    /// Takes the store's write lock, held until the returned file and guard
+    /// are dropped. Fails while another session's `shape tx` is open.
+    fn lock_for_write(&self) -> Result<(File, LockGuard)> {
+        super::tx::ensure_writable(self.shape_dir())?;
+        let path = self.write_lock_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Failed to open lock file: {}", path.display()))?;
+        let guard = self.lock(&file, LockMode::Exclusive)?;
+        Ok((file, guard))
+    }
+
+    /// Reads all tasks from the store
+    #[tracing::instrument(name = "tasks.read_all", level = "debug", skip_all)]
+    pub fn read_all(&self) -> Result<HashMap<TaskId, Task>> {
+        if !self.path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let file = File::open(&self.path)
+            .with_context(|| format!("Failed to open task store: {}", self.path.display()))?;
+
+        // Acquire shared lock for reading
+        let _lock = self.lock(&file, LockMode::Shared)?;
+
+        let reader = BufReader::new(&file);
+        let mut tasks = HashMap::new();
+        // Only looked up once a sealed line turns up
+        let mut cipher: Option<Option<StoreCipher>> = None;
+
+        for (line_num, line) in reader.lines().enumerate() {
+            let line = line.with_context(|| format!("Failed to read line {}", line_num + 1))?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(header) = TasksHeader::parse(&line) {
+                check_version("tasks.jsonl", header.format_version)?;
+                continue;
+            }
+
+            let line = if encryption::is_sealed(&line) {
+                if cipher.is_none() {
+                    cipher = Some(encryption::cipher(self.shape_dir())?);
+                }
+                let Some(Some(cipher)) = &cipher else {
+                    return Err(EncryptionError::Corrupt(format!(
+                        "{} has encrypted tasks but {} is missing",
+                        self.path.display(),
+                        encryption::ENCRYPTION_FILE
+                    ))
+                    .into());
+                };
+                cipher
+                    .open(&line)
+                    .with_context(|| format!("Failed to decrypt task at line {}", line_num + 1))?
+            } else {
+                line
+            };
+
+            let task: Task = serde_json::from_str(&line)
+                .with_context(|| format!("Failed to parse task at line {}", line_num + 1))?;
+
+            tasks.insert(task.id.clone(), task);
+        }
+
+        // Lock is released when file is dropped
+        Ok(tasks)
+    }
+
+    /// Reads the format header, if the file has one
+    pub fn header(&self) -> Result<Option<TasksHeader>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let file = File::open(&self.path)
+            .with_context(|| format!("Failed to open task store: {}", self.path.display()))?;
+        let first = BufReader::new(file)
+            .lines()
+            .map_while(|l| l.ok())
+            .find(|l| !l.trim().is_empty());
+        Ok(first.as_deref().and_then(TasksHeader::parse))
+    }
+
+    /// Reads tasks for a specific brief
+    pub fn read_for_brief(
+        &self,
+        brief_id: &crate::domain::BriefId,
+    ) -> Result<HashMap<TaskId, Task>> {
+        let all = self.read_all()?;
+        Ok(all
+            .into_iter()
+            .filter(|(_, task)| task.brief_id().as_ref() == Some(brief_id))
+            .collect())
+    }
+
+    /// Returns the next free top-level task ID under a brief
+    pub fn next_task_id(&self, brief_id: &crate::domain::BriefId) -> Result<TaskId> {
+        let max_seq = self
+            .read_for_brief(brief_id)?
+            .values()
+            .filter(|t| t.id.depth() == 1)
+            .map(|t| *t.id.segments().first().unwrap_or(&0))
+            .max()
+            .unwrap_or(0);
+
+        Ok(TaskId::new(brief_id, max_seq + 1))
+    }
+
+    /// Returns the next free subtask ID under a task
+    pub fn next_subtask_id(&self, parent: &TaskId) -> Result<TaskId> {
+        let max_seq = self
+            .read_all()?
+            .values()
+            .filter(|t| t.id.parent().as_ref() == Some(parent))
+            .map(|t| *t.id.segments().last().unwrap_or(&0))
+            .max()
+            .unwrap_or(0);
+
+        Ok(parent.subtask(max_seq + 1))
+    }
+
+    /// Reads all standalone tasks (tasks not belonging to any brief)
+    pub fn read_standalone(&self) -> Result<HashMap<TaskId, Task>> {
+        let all = self.read_all()?;
+        Ok(all
+            .into_iter()
+            .filter(|(_, task)| task.is_standalone())
+            .collect())
+    }
+
+    /// Writes all tasks to the store (full rewrite)
+    ///
+    /// New files get the current format header; existing files keep theirs
+    /// (or its absence) until `shape migrate` changes it.
+    #[tracing::instrument(name = "tasks.write_all", level = "debug", skip_all, fields(tasks = tasks.len()))]
+    pub fn write_all(&self, tasks: &HashMap<TaskId, Task>) -> Result<()> {
+        let header = if self.path.exists() {
+            self.header()?
+        } else {
+            Some(TasksHeader::current())
+        };
+        self.write_all_with_header(tasks, header)
+    }
+
+    /// Writes all tasks with an explicit header line (or none)
+    pub(crate) fn write_all_with_header(
+        &self,
+        tasks: &HashMap<TaskId, Task>,
+        header: Option<TasksHeader>,
+    ) -> Result<()> {
+        // A locked store refuses writes rather than mixing in plaintext
+        let cipher = encryption::cipher(self.shape_dir())?;
+        self.rewrite(tasks, header, cipher.as_ref())
+    }
+
+    /// Rewrites every task, sealed with `to` or in plaintext, whatever the
+    /// store's encryption setting says. Used to switch encryption on or
+    /// off; returns the number of tasks.
+    pub fn reseal(&self, to: Option<&StoreCipher>) -> Result<usize> {
+        if !self.path.exists() {
+            return Ok(0);
+        }
+        let _lock = self.lock_for_write()?;
+        let tasks = self.read_all()?;
+        self.replace(&tasks, self.header()?, to)?;
+        // Contents are unchanged, so there is nothing to record
+        Ok(tasks.len())
+    }
+
+    fn rewrite(
+        &self,
+        tasks: &HashMap<TaskId, Task>,
+        header: Option<TasksHeader>,
+        cipher: Option<&StoreCipher>,
+    ) -> Result<()> {
+        let _lock = self.lock_for_write()?;
+        let before = if self.is_recorded() {
+            Some(self.read_all()?)
+        } else {
+            None
+        };
+        self.replace(tasks, header, cipher)?;
+        if let Some(before) = before {
+            self.note_writes(&before, tasks);
+        }
+        Ok(())
+    }
+
+    /// Reads every task, lets `f` change them and writes the result back,
+    /// holding the write lock throughout so no other write (rewrite or
+    /// append) lands in between. Nothing is written when `f` fails.
+    pub fn modify<R>(&self, f: impl FnOnce(&mut HashMap<TaskId, Task>) -> Result<R>) -> Result<R> {
+        let cipher = encryption::cipher(self.shape_dir())?;
+        let _lock = self.lock_for_write()?;
+        let header = if self.path.exists() {
+            self.header()?
+        } else {
+            Some(TasksHeader::current())
+        };
+
+        let mut tasks = self.read_all()?;
+        let before = self.is_recorded().then(|| tasks.clone());
+        let result = f(&mut tasks)?;
+        self.replace(&tasks, header, cipher.as_ref())?;
+        if let Some(before) = before {
+            self.note_writes(&before, &tasks);
+        }
+        Ok(result)
+    }
+
+    /// Writes the temp file and renames it over the store. The caller holds
+    /// the write lock.
+    fn replace(
+        &self,
+        tasks: &HashMap<TaskId, Task>,
+        header: Option<TasksHeader>,
+        cipher: Option<&StoreCipher>,
+    ) -> Result<()> {
+        {
+            let file = self.open_temp()?;
+            self.write_temp(&file, tasks, header, cipher)?;
+        }
+        self.swap_in_temp()
+    }
+
+    /// Opens the temp file a rewrite goes through, creating the directory.
+    /// Anything left by a writer that died mid-rewrite is discarded.
+    fn open_temp(&self) -> Result<File> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let temp_path = self.temp_path();
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&temp_path)
+            .with_context(|| format!("Failed to create temp file: {}", temp_path.display()))
+    }
+
+    /// Writes every task to the temp file
+    fn write_temp(
+        &self,
+        file: &File,
+        tasks: &HashMap<TaskId, Task>,
+        header: Option<TasksHeader>,
+        cipher: Option<&StoreCipher>,
+    ) -> Result<()> {
+        let mut writer = BufWriter::new(file);
+
+        if let Some(header) = header {
+            let line = serde_json::to_string(&header).context("Failed to serialize header")?;
+            writeln!(writer, "{}", line).context("Failed to write header")?;
+        }
+
+        // Sort by ID for consistent output
+        let sorted = by_id(tasks.values());
+
+        for task in sorted {
+            let line = encode(task, cipher)?;
+            writeln!(writer, "{}", line).context("Failed to write task")?;
+        }
+
+        writer.flush().context("Failed to flush task store")
+    }
+
+    /// Renames the written temp file over the store
+    fn swap_in_temp(&self) -> Result<()> {
+        let temp_path = self.temp_path();
+        fs::rename(&temp_path, &self.path).with_context(|| {
+            format!(
+                "Failed to rename {} to {}",
+                temp_path.display(),
+                self.path.display()
+            )
+        })?;
+        self.bump_revision();
+
+        Ok(())
+    }
+
+    /// Appends a single task (used for quick adds without full rewrite)
+    pub fn append(&self, task: &Task) -> Result<()> {
+        self.append_batch(std::slice::from_ref(task))
+    }
+
+    /// Appends tasks under a single lock and flush
+    pub fn append_batch(&self, tasks: &[Task]) -> Result<()> {
+        if tasks.is_empty() {
+            return Ok(());
+        }
+        let cipher = encryption::cipher(self.shape_dir())?;
+
+        // Ensure parent directory exists
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        // The write lock keeps rewrites out; the file lock keeps readers
+        // from seeing a half-written line
+        let _write_lock = self.lock_for_write()?;
+        // An appended line replaces any earlier line for the same task
+        let before: Option<HashMap<TaskId, Task>> = if self.is_recorded() {
+            let existing = self.read_all()?;
+            Some(
+                tasks
+                    .iter()
+                    .filter_map(|t| existing.get(&t.id).map(|e| (t.id.clone(), e.clone())))
+                    .collect(),
+            )
+        } else {
+            None
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open task store: {}", self.path.display()))?;
+        let _lock = self.lock(&file, LockMode::Exclusive)?;
+
+        let mut writer = BufWriter::new(&file);
+
+        // A new store starts with the format header
+        if file.metadata().map(|m| m.len() == 0).unwrap_or(false) {
+            let line = serde_json::to_string(&TasksHeader::current())
+                .context("Failed to serialize header")?;
+            writeln!(writer, "{}", line).context("Failed to write header")?;
+        }
+
+        for task in tasks {
+            let line = encode(task, cipher.as_ref())?;
+            writeln!(writer, "{}", line).context("Failed to write task")?;
+        }
+
+        writer.flush().context("Failed to flush task store")?;
+        self.bump_revision();
+
+        if let Some(before) = before {
+            let after = tasks.iter().map(|t| (t.id.clone(), t.clone())).collect();
+            self.note_writes(&before, &after);
+        }
+        Ok(())
+    }
+
+    /// Records the write in the project's change counter
+    fn bump_revision(&self) {
+        if let Some(shape_dir) = self.path.parent() {
+            super::revision::bump(shape_dir);
+        }
+    }
+
+    /// Updates a single task (reads all, updates, writes all)
+    pub fn update(&self, task: &Task) -> Result<()> {
+        self.update_batch(std::slice::from_ref(task))
+    }
+
+    /// Inserts or replaces tasks in one locked read and atomic rewrite
+    pub fn update_batch(&self, tasks: &[Task]) -> Result<()> {
+        self.modify(|all| {
+            for task in tasks {
+                all.insert(task.id.clone(), task.clone());
+            }
+            Ok(())
+        })
+    }
+
+    /// Removes a task by ID
+    pub fn remove(&self, task_id: &TaskId) -> Result<bool> {
+        self.modify(|tasks| Ok(tasks.remove(task_id).is_some()))
+    }
+
+    /// Compacts the store (removes duplicates, rewrites clean)
+    pub fn compact(&self) -> Result<usize> {
+        self.modify(|tasks| Ok(tasks.len()))
+    }
+}
+
+/// A task's line in the file, sealed when the store is encrypted
+fn encode(task: &Task, cipher: Option<&StoreCipher>) -> Result<String> {
+    let line = serde_json::to_string(task).context("Failed to serialize task")?;
+    Ok(match cipher {
+        Some(cipher) => cipher.seal(&line),
+        None => line,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn make_task(seq: u32) -> Task {
+        let brief = crate::domain::BriefId::new("Test", Utc::now());
+        let task_id = crate::domain::TaskId::new(&brief, seq);
+        Task::new(task_id, format!("Task {}", seq))
+    }
+
+    #[test]
+    fn read_empty_store() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskStore::new(dir.path().join("tasks.jsonl"));
+
+        let tasks = store.read_all().unwrap();
+        assert!(tasks.is_empty());
+    }
+
+    #[test]
+    fn write_and_read_tasks() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskStore::new(dir.path().join("tasks.jsonl"));
+
+        let task1 = make_task(1);
+        let task2 = make_task(2);
+
+        let mut tasks = HashMap::new();
+        tasks.insert(task1.id.clone(), task1.clone());
+        tasks.insert(task2.id.clone(), task2.clone());
+
+        store.write_all(&tasks).unwrap();
+
+        let loaded = store.read_all().unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.get(&task1.id).unwrap().title, task1.title);
+        assert_eq!(loaded.get(&task2.id).unwrap().title, task2.title);
+    }
+
+    #[test]
+    fn append_task() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskStore::new(dir.path().join("tasks.jsonl"));
+
+        let task1 = make_task(1);
+        let task2 = make_task(2);
+
+        store.append(&task1).unwrap();
+        store.append(&task2).unwrap();
+
+        let loaded = store.read_all().unwrap();
+        assert_eq!(loaded.len(), 2);
+    }
+
+    #[test]
+    fn batch_append_and_update() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskStore::new(dir.path().join("tasks.jsonl"));
+
+        let mut tasks: Vec<Task> = (1..=50).map(make_task).collect();
+        store.append_batch(&tasks).unwrap();
+        assert!(store.header().unwrap().is_some());
+        assert_eq!(store.read_all().unwrap().len(), 50);
+
+        for task in &mut tasks[..10] {
+            task.start();
+        }
+        store.update_batch(&tasks[..10]).unwrap();
+
+        let loaded = store.read_all().unwrap();
+        assert_eq!(loaded.len(), 50);
+        let started = loaded
+            .values()
+            .filter(|t| t.status == crate::domain::TaskStatus::InProgress)
+            .count();
+        assert_eq!(started, 10);
+        // The rewrite collapses to one line per task plus the header
+        let content = fs::read_to_string(store.path()).unwrap();
+        assert_eq!(content.lines().count(), 51);
+    }
+
+    #[test]
+    fn modify_writes_only_on_success() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskStore::new(dir.path().join("tasks.jsonl"));
+        let task1 = make_task(1);
+        store.append(&task1).unwrap();
+
+        let err = store.modify(|tasks| -> Result<()> {
+            tasks.clear();
+            anyhow::bail!("refused")
+        });
+        assert!(err.is_err());
+        assert_eq!(store.read_all().unwrap().len(), 1);
+
+        let removed = store
+            .modify(|tasks| Ok(tasks.remove(&task1.id).is_some()))
+            .unwrap();
+        assert!(removed);
+        assert!(store.read_all().unwrap().is_empty());
+        assert!(!store.temp_path().exists());
+    }
+
+    #[test]
+    fn concurrent_updates_and_appends_lose_nothing() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("tasks.jsonl");
+        let brief = crate::domain::BriefId::new("Concurrent", Utc::now());
+        let seed = Task::new(brief.task_id(1), "Seed");
+        TaskStore::new(&path).append(&seed).unwrap();
+
+        let writers: Vec<_> = (0..4u32)
+            .map(|writer| {
+                let path = path.clone();
+                let brief = brief.clone();
+                let seed = seed.clone();
+                std::thread::spawn(move || {
+                    let store = TaskStore::new(path);
+                    for i in 0..10 {
+                        let seq = 2 + writer * 10 + i;
+                        if i % 2 == 0 {
+                            store
+                                .append(&Task::new(brief.task_id(seq), "Appended"))
+                                .unwrap();
+                        } else {
+                            let mut updated = Task::new(brief.task_id(seq), "Updated");
+                            updated.start();
+                            store.update_batch(&[updated, seed.clone()]).unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        // Every append and update survives the others' rewrites
+        assert_eq!(TaskStore::new(&path).read_all().unwrap().len(), 41);
+    }
+
+    #[test]
+    fn recorder_keeps_only_this_process_writes() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskStore::new(dir.path().join("tasks.jsonl"));
+        let brief = crate::domain::BriefId::new("Recorded", Utc::now());
+        let mut mine = Task::new(brief.task_id(1), "Mine");
+        store.append(&mine).unwrap();
+
+        let recorder = store.record_writes();
+        // Another process appends a task behind this one's back
+        let theirs = Task::new(brief.task_id(2), "Theirs");
+        let mut file = OpenOptions::new().append(true).open(store.path()).unwrap();
+        writeln!(file, "{}", serde_json::to_string(&theirs).unwrap()).unwrap();
+
+        mine.start();
+        store.update(&mine).unwrap();
+        let added = Task::new(brief.task_id(3), "Added");
+        store.append(&added).unwrap();
+        // Writes to other stores aren't this store's changes
+        TaskStore::new(dir.path().join("archive.jsonl"))
+            .append(&theirs)
+            .unwrap();
+
+        let changes = recorder.finish();
+        let ids: Vec<&TaskId> = changes.iter().map(|c| &c.id).collect();
+        assert_eq!(ids, vec![&mine.id, &added.id]);
+        assert_eq!(
+            changes[0].before.as_ref().unwrap().status,
+            crate::domain::TaskStatus::Todo
+        );
+        assert!(changes[1].before.is_none());
+        assert!(!store.is_recorded());
+    }
+
+    #[test]
+    fn update_task() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskStore::new(dir.path().join("tasks.jsonl"));
+
+        let mut task = make_task(1);
+        store.append(&task).unwrap();
+
+        task.start();
+        store.update(&task).unwrap();
+
+        let loaded = store.read_all().unwrap();
+        let loaded_task = loaded.get(&task.id).unwrap();
+        assert_eq!(loaded_task.status, crate::domain::TaskStatus::InProgress);
+    }
+
+    #[test]
+    fn remove_task() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskStore::new(dir.path().join("tasks.jsonl"));
+
+        let task1 = make_task(1);
+        let task2 = make_task(2);
+
+        let mut tasks = HashMap::new();
+        tasks.insert(task1.id.clone(), task1.clone());
+        tasks.insert(task2.id.clone(), task2.clone());
+        store.write_all(&tasks).unwrap();
+
+        let removed = store.remove(&task1.id).unwrap();
+        assert!(removed);
+
+        let loaded = store.read_all().unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert!(!loaded.contains_key(&task1.id));
+    }
+
+    #[test]
+    fn compact_removes_duplicates() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskStore::new(dir.path().join("tasks.jsonl"));
+
+        let task = make_task(1);
+
+        // Append same task multiple times (simulating updates)
+        store.append(&task).unwrap();
+        store.append(&task).unwrap();
+        store.append(&task).unwrap();
+
+        // Compact should result in one task
+        let count = store.compact().unwrap();
+        assert_eq!(count, 1);
+
+        let loaded = store.read_all().unwrap();
+        assert_eq!(loaded.len(), 1);
+    }
+
+    #[test]
+    fn creates_parent_directories() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskStore::new(dir.path().join("nested").join("dir").join("tasks.jsonl"));
+
+        let task = make_task(1);
+        store.append(&task).unwrap();
+
+        assert!(store.path().exists());
+    }
+
+    #[test]
+    fn atomic_write() {
+        let dir = TempDir::new().unwrap();
+        let store = TaskStore::new(dir.path().join("tasks.jsonl"));
+
+        let task = make_task(1);
+        let mut tasks = HashMap::new();
+        tasks.insert(task.id.clone(), task.clone());
+        store.write_all(&tasks).unwrap();
+
+        // Temp file should not exist after write
+        let temp_path = store.path().with_extension("jsonl.tmp");
+        assert!(!temp_path.exists());
+    }
+}
@@ -0,0 +1,222 @@
+//! File locks with a timeout and holder diagnostics
+//!
+//! [`TaskStore`](super::TaskStore) takes its locks through [`acquire`], which
+//! polls instead of blocking when a timeout is set. Whoever holds the
+//! exclusive lock records itself in `.shape/.cache/tasks.lock`, so a process
+//! that gives up waiting can say which PID is in the way.
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// How often a contended lock is retried
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Lock acquisition errors
+#[derive(Debug, Error)]
+pub enum LockError {
+    #[error(
+        "Timed out after {}s waiting for the lock on {}{}. Retry with --wait to block until it is released.",
+        .waited.as_secs_f64(),
+        .path.display(),
+        holder_hint(.holder)
+    )]
+    Timeout {
+        path: PathBuf,
+        waited: Duration,
+        holder: Option<LockHolder>,
+    },
+}
+
+fn holder_hint(holder: &Option<LockHolder>) -> String {
+    match holder {
+        Some(h) => format!(
+            " (held by PID {} running `{}` since {})",
+            h.pid,
+            h.command,
+            h.acquired_at.format("%Y-%m-%d %H:%M:%S")
+        ),
+        None => String::new(),
+    }
+}
+
+/// Process holding an exclusive lock, as recorded in the lock metadata file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    pub command: String,
+    pub acquired_at: DateTime<Utc>,
+}
+
+impl LockHolder {
+    fn current() -> Self {
+        let command = std::env::args()
+            .map(|arg| {
+                Path::new(&arg)
+                    .file_name()
+                    .map_or(arg.clone(), |n| n.to_string_lossy().into_owned())
+            })
+            .take(3)
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            pid: std::process::id(),
+            command,
+            acquired_at: Utc::now(),
+        }
+    }
+
+    /// Reads the holder recorded in `path`, if any
+    pub fn read(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+}
+
+/// Lock mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    Shared,
+    Exclusive,
+}
+
+/// A held lock. Exclusive locks remove their holder record when dropped; the
+/// lock itself is released when the file is closed.
+pub struct LockGuard {
+    holder_path: Option<PathBuf>,
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        if let Some(path) = &self.holder_path {
+            if LockHolder::read(path).is_some_and(|h| h.pid == std::process::id()) {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+/// Locks `file`, giving up after `timeout` (`None` blocks indefinitely).
+///
+/// `path` names the file in errors and `holder_path` is where exclusive
+/// holders record themselves.
+pub fn acquire(
+    file: &File,
+    mode: LockMode,
+    timeout: Option<Duration>,
+    path: &Path,
+    holder_path: &Path,
+) -> anyhow::Result<LockGuard> {
+    let failed = || format!("Failed to lock {}", path.display());
+    match timeout {
+        None => match mode {
+            LockMode::Shared => FileExt::lock_shared(file).with_context(failed)?,
+            LockMode::Exclusive => FileExt::lock_exclusive(file).with_context(failed)?,
+        },
+        Some(timeout) => {
+            let start = Instant::now();
+            loop {
+                let attempt = match mode {
+                    LockMode::Shared => FileExt::try_lock_shared(file),
+                    LockMode::Exclusive => FileExt::try_lock_exclusive(file),
+                };
+                match attempt {
+                    Ok(()) => break,
+                    Err(e) if e.kind() == fs2::lock_contended_error().kind() => {}
+                    Err(e) => return Err(e).with_context(failed),
+                }
+                if start.elapsed() >= timeout {
+                    return Err(LockError::Timeout {
+                        path: path.to_path_buf(),
+                        waited: timeout,
+                        holder: LockHolder::read(holder_path),
+                    }
+                    .into());
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+
+    if mode == LockMode::Shared {
+        return Ok(LockGuard { holder_path: None });
+    }
+    // The holder record is diagnostic only; failing to write it doesn't fail the lock
+    if let Some(parent) = holder_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let record = serde_json::to_string(&LockHolder::current()).unwrap_or_default();
+    let _ = fs::write(holder_path, record);
+    Ok(LockGuard {
+        holder_path: Some(holder_path.to_path_buf()),
+    })
+}
+
+/// Returns true while another process holds a lock on `path`. Missing files
+/// and files that can't be opened count as unlocked.
+pub fn is_locked(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    match FileExt::try_lock_exclusive(&file) {
+        Ok(()) => false,
+        Err(e) => e.kind() == fs2::lock_contended_error().kind(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn times_out_naming_the_holder() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("tasks.jsonl");
+        let holder_path = dir.path().join("tasks.lock");
+        fs::write(&path, "").unwrap();
+
+        let held = File::open(&path).unwrap();
+        let guard = acquire(&held, LockMode::Exclusive, None, &path, &holder_path).unwrap();
+        assert_eq!(
+            LockHolder::read(&holder_path).unwrap().pid,
+            std::process::id()
+        );
+
+        let waiting = File::open(&path).unwrap();
+        let err = acquire(
+            &waiting,
+            LockMode::Shared,
+            Some(Duration::from_millis(50)),
+            &path,
+            &holder_path,
+        )
+        .err()
+        .unwrap();
+        let message = err.to_string();
+        assert!(err.is::<LockError>());
+        assert!(message.contains(&format!("PID {}", std::process::id())));
+        assert!(message.contains("--wait"));
+
+        assert!(is_locked(&path));
+        drop(guard);
+        drop(held);
+        assert!(!holder_path.exists());
+        assert!(!is_locked(&path));
+        acquire(
+            &waiting,
+            LockMode::Shared,
+            Some(Duration::from_millis(50)),
+            &path,
+            &holder_path,
+        )
+        .unwrap();
+    }
+}
@@ -2,7 +2,12 @@
 //!
 //! Briefs are stored as markdown files in `.shape/briefs/`.
 //! Each file has YAML frontmatter for metadata and markdown body.
-//! An index file (`.shape/briefs/index.jsonl`) caches metadata for fast queries.
+//! An index file (`.shape/briefs/index.jsonl`) caches each brief's frontmatter,
+//! so listings and lookups that don't need bodies skip parsing the markdown.
+//!
+//! In an encrypted store each brief file is sealed as a whole (see
+//! [`encryption`](super::encryption)). The index is gitignored and stays
+//! plaintext.
 
 use std::collections::HashMap;
 use std::fs::{self, File};
@@ -11,27 +16,22 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 
+use super::encryption::{self, EncryptionError, StoreCipher};
+use super::frontmatter::{self, FrontmatterIssue, IssueSeverity};
 use crate::domain::{Brief, BriefFrontmatter, BriefId};
 
-/// Index entry for quick brief lookups
+/// Index entry for quick brief lookups: the full frontmatter, without the body
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 struct IndexEntry {
-    id: BriefId,
-    title: String,
-    brief_type: String,
-    status: crate::domain::BriefStatus,
-    updated_at: chrono::DateTime<chrono::Utc>,
+    #[serde(flatten)]
+    frontmatter: BriefFrontmatter,
     file_name: String,
 }
 
 impl From<&Brief> for IndexEntry {
     fn from(brief: &Brief) -> Self {
         Self {
-            id: brief.id.clone(),
-            title: brief.title.clone(),
-            brief_type: brief.brief_type.clone(),
-            status: brief.status,
-            updated_at: brief.updated_at,
+            frontmatter: BriefFrontmatter::from(brief),
             file_name: format!("{}.md", brief.id),
         }
     }
@@ -54,6 +54,13 @@ impl BriefStore {
         Self { dir, index_path }
     }
 
+    /// Keeps the index at `path` instead of `index.jsonl` in the store's
+    /// directory
+    pub fn with_index_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.index_path = path.into();
+        self
+    }
+
     /// Creates the default store for a project
     pub fn for_project(project_root: &Path) -> Self {
         Self::new(project_root.join(".shape").join("briefs"))
@@ -99,17 +106,14 @@ impl BriefStore {
             }
         }
 
-        // Check if any file was deleted (entry in index but no file)
-        if let Ok(index) = self.read_index() {
-            for entry in index.values() {
-                let path = self.dir.join(&entry.file_name);
-                if !path.exists() {
-                    return true;
-                }
-            }
+        // Check if any file was deleted (entry in index but no file). An index
+        // that doesn't parse, e.g. one written by an older version, is rebuilt.
+        match self.read_index() {
+            Ok(index) => index
+                .values()
+                .any(|entry| !self.dir.join(&entry.file_name).exists()),
+            Err(_) => true,
         }
-
-        false
     }
 
     /// Reads the index file
@@ -135,7 +139,7 @@ impl BriefStore {
             let entry: IndexEntry = serde_json::from_str(&line)
                 .with_context(|| format!("Failed to parse index entry at line {}", line_num + 1))?;
 
-            entries.insert(entry.id.clone(), entry);
+            entries.insert(entry.frontmatter.id.clone(), entry);
         }
 
         Ok(entries)
@@ -143,8 +147,9 @@ impl BriefStore {
 
     /// Writes the index file
     fn write_index(&self, entries: &HashMap<BriefId, IndexEntry>) -> Result<()> {
-        fs::create_dir_all(&self.dir)
-            .with_context(|| format!("Failed to create directory: {}", self.dir.display()))?;
+        let dir = self.index_path.parent().unwrap_or(&self.dir);
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
 
         let file = File::create(&self.index_path)
             .with_context(|| format!("Failed to create index: {}", self.index_path.display()))?;
@@ -152,7 +157,7 @@ impl BriefStore {
         let mut writer = BufWriter::new(file);
 
         let mut sorted: Vec<_> = entries.values().collect();
-        sorted.sort_by(|a, b| a.id.to_string().cmp(&b.id.to_string()));
+        sorted.sort_by(|a, b| a.frontmatter.id.cmp(&b.frontmatter.id));
 
         for entry in sorted {
             let line = serde_json::to_string(entry).context("Failed to serialize index entry")?;
@@ -178,8 +183,13 @@ impl BriefStore {
             let path = entry.path();
 
             if path.extension().is_some_and(|e| e == "md") {
-                if let Ok(brief) = self.read_from_file(&path) {
-                    entries.insert(brief.id.clone(), IndexEntry::from(&brief));
+                match self.read_from_file(&path) {
+                    Ok(brief) => {
+                        entries.insert(brief.id.clone(), IndexEntry::from(&brief));
+                    }
+                    // A locked store must not leave an empty index behind
+                    Err(e) if is_encryption_error(&e) => return Err(e),
+                    Err(_) => {}
                 }
             }
         }
@@ -199,35 +209,35 @@ impl BriefStore {
 
     /// Reads a brief from a file
     fn read_from_file(&self, path: &Path) -> Result<Brief> {
-        let content = fs::read_to_string(path)
-            .with_context(|| format!("Failed to read brief file: {}", path.display()))?;
-
+        let content = self.read_text(path)?;
         self.parse_markdown(&content)
     }
 
-    /// Parses a markdown string into a Brief
-    fn parse_markdown(&self, content: &str) -> Result<Brief> {
-        // Manual frontmatter parsing
-        let content = content.trim();
-
-        if !content.starts_with("---") {
-            anyhow::bail!("Missing frontmatter (must start with ---)");
+    /// Reads a brief file's markdown, decrypting it if it is sealed
+    fn read_text(&self, path: &Path) -> Result<String> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read brief file: {}", path.display()))?;
+        if !encryption::is_sealed(&content) {
+            return Ok(content);
         }
 
-        // Find the end of frontmatter
-        let rest = &content[3..];
-        let end_pos = rest
-            .find("---")
-            .ok_or_else(|| anyhow::anyhow!("Missing frontmatter end delimiter (---)"))?;
-
-        let yaml_content = &rest[..end_pos].trim();
-        let body = rest[end_pos + 3..].trim();
-
-        // Parse YAML frontmatter
-        let fm: BriefFrontmatter =
-            serde_yaml::from_str(yaml_content).context("Failed to parse frontmatter")?;
+        let shape_dir = self.dir.parent().unwrap_or(Path::new("."));
+        let cipher = encryption::cipher(shape_dir)?.ok_or_else(|| {
+            EncryptionError::Corrupt(format!(
+                "{} is encrypted but {} is missing",
+                path.display(),
+                encryption::ENCRYPTION_FILE
+            ))
+        })?;
+        cipher
+            .open(&content)
+            .with_context(|| format!("Failed to decrypt brief file: {}", path.display()))
+    }
 
-        Ok(fm.into_brief(body.to_string()))
+    /// Parses a markdown string into a Brief, validating its frontmatter
+    fn parse_markdown(&self, content: &str) -> Result<Brief> {
+        let (fm, body) = frontmatter::parse(content)?;
+        Ok(fm.into_brief(body))
     }
 
     /// Writes a brief to its file atomically (temp file + rename)
@@ -235,16 +245,31 @@ impl BriefStore {
         fs::create_dir_all(&self.dir)
             .with_context(|| format!("Failed to create directory: {}", self.dir.display()))?;
 
-        let path = self.brief_path(&brief.id);
-        let temp_path = path.with_extension("md.tmp");
         let content = self.render_markdown(brief)?;
+        self.write_text(&self.brief_path(&brief.id), &content)
+    }
+
+    /// Writes a brief file's markdown atomically, sealed when the store is
+    /// encrypted
+    fn write_text(&self, path: &Path, content: &str) -> Result<()> {
+        let shape_dir = self.dir.parent().unwrap_or(Path::new("."));
+        let cipher = encryption::cipher(shape_dir)?;
+        self.write_sealed(path, content, cipher.as_ref())
+    }
+
+    fn write_sealed(&self, path: &Path, content: &str, cipher: Option<&StoreCipher>) -> Result<()> {
+        let content = match cipher {
+            Some(cipher) => cipher.seal(content) + "\n",
+            None => content.to_string(),
+        };
+        let temp_path = path.with_extension("md.tmp");
 
         // Write to temp file first
         fs::write(&temp_path, &content)
             .with_context(|| format!("Failed to write temp file: {}", temp_path.display()))?;
 
         // Atomic rename
-        fs::rename(&temp_path, &path).with_context(|| {
+        fs::rename(&temp_path, path).with_context(|| {
             format!(
                 "Failed to rename {} to {}",
                 temp_path.display(),
@@ -275,6 +300,7 @@ impl BriefStore {
     }
 
     /// Reads all briefs
+    #[tracing::instrument(name = "briefs.read_all", level = "debug", skip_all)]
     pub fn read_all(&self) -> Result<HashMap<BriefId, Brief>> {
         let _ = self.ensure_index()?; // Ensure index is fresh
         let mut briefs = HashMap::new();
@@ -290,8 +316,12 @@ impl BriefStore {
             let path = entry.path();
 
             if path.extension().is_some_and(|e| e == "md") {
-                if let Ok(brief) = self.read_from_file(&path) {
-                    briefs.insert(brief.id.clone(), brief);
+                match self.read_from_file(&path) {
+                    Ok(brief) => {
+                        briefs.insert(brief.id.clone(), brief);
+                    }
+                    Err(e) if is_encryption_error(&e) => return Err(e),
+                    Err(_) => {}
                 }
             }
         }
@@ -299,13 +329,96 @@ impl BriefStore {
         Ok(briefs)
     }
 
+    /// Reads all briefs without their bodies, from the index.
+    ///
+    /// Much cheaper than [`read_all`](Self::read_all) once the index is fresh.
+    /// The returned briefs have an empty `body`, so they are for reading
+    /// metadata only: use [`read`](Self::read) for the body or summary, and
+    /// never write one back.
+    #[tracing::instrument(name = "briefs.read_all_meta", level = "debug", skip_all)]
+    pub fn read_all_meta(&self) -> Result<HashMap<BriefId, Brief>> {
+        Ok(self
+            .ensure_index()?
+            .into_iter()
+            .map(|(id, entry)| (id, entry.frontmatter.into_brief(String::new())))
+            .collect())
+    }
+
+    /// Returns brief files that fail to parse, with the parse error.
+    ///
+    /// `read_all` skips such files silently; this lets diagnostics report them.
+    pub fn invalid_files(&self) -> Result<Vec<(PathBuf, String)>> {
+        let mut invalid = Vec::new();
+
+        if !self.dir.exists() {
+            return Ok(invalid);
+        }
+
+        for entry in fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read directory: {}", self.dir.display()))?
+        {
+            let entry = entry.context("Failed to read directory entry")?;
+            let path = entry.path();
+
+            if path.extension().is_some_and(|e| e == "md") {
+                match self.read_from_file(&path) {
+                    Err(e) if is_encryption_error(&e) => return Err(e),
+                    Err(e) => invalid.push((path, format!("{:#}", e))),
+                    Ok(_) => {}
+                }
+            }
+        }
+
+        invalid.sort();
+        Ok(invalid)
+    }
+
+    /// Returns the frontmatter errors and warnings of every brief file that
+    /// has any, sorted by path
+    pub fn frontmatter_issues(&self) -> Result<Vec<(PathBuf, Vec<FrontmatterIssue>)>> {
+        let mut found = Vec::new();
+
+        if !self.dir.exists() {
+            return Ok(found);
+        }
+
+        for entry in fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read directory: {}", self.dir.display()))?
+        {
+            let entry = entry.context("Failed to read directory entry")?;
+            let path = entry.path();
+
+            if path.extension().is_some_and(|e| e == "md") {
+                let content = self.read_text(&path)?;
+                let issues = frontmatter::validate(&content);
+                if !issues.is_empty() {
+                    found.push((path, issues));
+                }
+            }
+        }
+
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(found)
+    }
+
+    /// Returns the frontmatter warnings of a brief that loads
+    pub fn warnings(&self, id: &BriefId) -> Result<Vec<FrontmatterIssue>> {
+        let content = self.read_text(&self.brief_path(id))?;
+        Ok(frontmatter::validate(&content)
+            .into_iter()
+            .filter(|i| i.severity == IssueSeverity::Warning)
+            .collect())
+    }
+
     /// Lists briefs with basic info (from index, fast)
     pub fn list(&self) -> Result<Vec<(BriefId, String, crate::domain::BriefStatus)>> {
         let index = self.ensure_index()?;
-        Ok(index
-            .values()
-            .map(|e| (e.id.clone(), e.title.clone(), e.status))
-            .collect())
+        let mut list: Vec<_> = index
+            .into_values()
+            .map(|e| (e.frontmatter.id, e.frontmatter.title, e.frontmatter.status))
+            .collect();
+        list.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(list)
     }
 
     /// Lists briefs filtered by status
@@ -314,14 +427,17 @@ impl BriefStore {
         status: crate::domain::BriefStatus,
     ) -> Result<Vec<(BriefId, String)>> {
         let index = self.ensure_index()?;
-        Ok(index
-            .values()
-            .filter(|e| e.status == status)
-            .map(|e| (e.id.clone(), e.title.clone()))
-            .collect())
+        let mut list: Vec<_> = index
+            .into_values()
+            .filter(|e| e.frontmatter.status == status)
+            .map(|e| (e.frontmatter.id, e.frontmatter.title))
+            .collect();
+        list.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(list)
     }
 
     /// Reads a single brief by ID
+    #[tracing::instrument(name = "briefs.read", level = "debug", skip_all, fields(id = %id))]
     pub fn read(&self, id: &BriefId) -> Result<Option<Brief>> {
         let path = self.brief_path(id);
         if !path.exists() {
@@ -331,40 +447,98 @@ impl BriefStore {
         Ok(Some(self.read_from_file(&path)?))
     }
 
-    /// Writes a brief
+    /// Writes a brief, refreshing its summary if the body changed
+    #[tracing::instrument(name = "briefs.write", level = "debug", skip_all, fields(id = %brief.id))]
     pub fn write(&self, brief: &Brief) -> Result<()> {
+        self.ensure_writable()?;
+        let mut brief = brief.clone();
+        brief.refresh_summary();
+        let brief = &brief;
+
+        // Bring the index up to date first: once this file is written the
+        // index would no longer look stale to earlier hand edits
+        let mut index = self.ensure_index()?;
         self.write_to_file(brief)?;
 
         // Update index
-        let mut index = self.read_index().unwrap_or_default();
         index.insert(brief.id.clone(), IndexEntry::from(brief));
         self.write_index(&index)?;
+        self.bump_revision();
 
         Ok(())
     }
 
     /// Removes a brief by ID
     pub fn remove(&self, id: &BriefId) -> Result<bool> {
+        self.ensure_writable()?;
         let path = self.brief_path(id);
         if !path.exists() {
             return Ok(false);
         }
 
+        let mut index = self.ensure_index()?;
         fs::remove_file(&path)
             .with_context(|| format!("Failed to remove brief file: {}", path.display()))?;
 
         // Update index
-        let mut index = self.read_index().unwrap_or_default();
         index.remove(id);
         self.write_index(&index)?;
+        self.bump_revision();
 
         Ok(true)
     }
 
+    /// Refuses writes while another session's `shape tx` is open
+    fn ensure_writable(&self) -> Result<()> {
+        match self.dir.parent() {
+            Some(shape_dir) => super::tx::ensure_writable(shape_dir),
+            None => Ok(()),
+        }
+    }
+
+    /// Records the write in the project's change counter
+    fn bump_revision(&self) {
+        if let Some(shape_dir) = self.dir.parent() {
+            super::revision::bump(shape_dir);
+        }
+    }
+
     /// Checks if a brief exists
     pub fn exists(&self, id: &BriefId) -> bool {
         self.brief_path(id).exists()
     }
+
+    /// Rewrites every brief file unchanged, sealed with `to` or in
+    /// plaintext, whatever the store's encryption setting says. Used to
+    /// switch encryption on or off; returns the number of files.
+    pub fn reseal(&self, to: Option<&StoreCipher>) -> Result<usize> {
+        if !self.dir.exists() {
+            return Ok(0);
+        }
+        self.ensure_writable()?;
+
+        let mut count = 0;
+        for entry in fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read directory: {}", self.dir.display()))?
+        {
+            let path = entry.context("Failed to read directory entry")?.path();
+            if path.extension().is_some_and(|e| e == "md") {
+                let content = self.read_text(&path)?;
+                self.write_sealed(&path, &content, to)?;
+                count += 1;
+            }
+        }
+        if count > 0 {
+            self.bump_revision();
+        }
+        Ok(count)
+    }
+}
+
+/// Returns true if reading failed because the store is locked or can't be
+/// decrypted, rather than because the file is malformed
+fn is_encryption_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|e| e.is::<EncryptionError>())
 }
 
 #[cfg(test)]
@@ -382,6 +556,41 @@ mod tests {
         assert!(briefs.is_empty());
     }
 
+    #[test]
+    fn invalid_files_reports_unparseable_briefs() {
+        let dir = TempDir::new().unwrap();
+        let store = BriefStore::new(dir.path().join("briefs"));
+
+        let brief = Brief::new("Valid", "minimal");
+        store.write(&brief).unwrap();
+        fs::write(dir.path().join("briefs/broken.md"), "no frontmatter here").unwrap();
+
+        let invalid = store.invalid_files().unwrap();
+        assert_eq!(invalid.len(), 1);
+        assert!(invalid[0].0.ends_with("broken.md"));
+        assert!(invalid[0].1.contains("Missing frontmatter"));
+    }
+
+    #[test]
+    fn write_stores_summary_for_current_body() {
+        let dir = TempDir::new().unwrap();
+        let store = BriefStore::new(dir.path().join("briefs"));
+
+        let mut brief = Brief::new("Summarized", "minimal");
+        brief.body = "Hand edited body. Second sentence.".to_string();
+        store.write(&brief).unwrap();
+
+        let loaded = store.read(&brief.id).unwrap().unwrap();
+        assert_eq!(
+            loaded.summary.as_deref(),
+            Some("Hand edited body. Second sentence.")
+        );
+        assert!(loaded.summary_is_current());
+
+        let raw = fs::read_to_string(dir.path().join(format!("briefs/{}.md", brief.id))).unwrap();
+        assert!(raw.contains("summary_hash:"));
+    }
+
     #[test]
     fn write_and_read_brief() {
         let dir = TempDir::new().unwrap();
@@ -469,6 +678,60 @@ mod tests {
         assert_eq!(loaded.title, "Updated Title");
     }
 
+    #[test]
+    fn read_all_meta_comes_from_index() {
+        let dir = TempDir::new().unwrap();
+        let store = BriefStore::new(dir.path().join("briefs"));
+
+        let mut brief = Brief::new("Indexed", "shapeup");
+        brief.set_body("# Problem\n\nLong body.");
+        brief.owner = Some("alice".to_string());
+        brief.set_meta("appetite", "2-weeks");
+        store.write(&brief).unwrap();
+
+        let meta = store.read_all_meta().unwrap();
+        let loaded = &meta[&brief.id];
+        assert_eq!(loaded.title, "Indexed");
+        assert_eq!(loaded.owner.as_deref(), Some("alice"));
+        assert_eq!(loaded.get_meta("appetite"), brief.get_meta("appetite"));
+        assert!(loaded.body.is_empty());
+
+        // Served from the index: the markdown is not parsed again
+        let path = store.brief_path(&brief.id);
+        let raw = fs::read_to_string(&path).unwrap();
+        let index_mtime = fs::metadata(&store.index_path).unwrap().modified().unwrap();
+        fs::write(&path, raw.replace("# Problem", "# Changed")).unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(index_mtime)
+            .unwrap();
+        assert!(store.read_all_meta().unwrap()[&brief.id].body.is_empty());
+    }
+
+    #[test]
+    fn index_in_old_format_is_rebuilt() {
+        let dir = TempDir::new().unwrap();
+        let store = BriefStore::new(dir.path().join("briefs"));
+
+        let first = Brief::new("First", "minimal");
+        store.write(&first).unwrap();
+        fs::write(
+            &store.index_path,
+            format!(
+                "{{\"id\":\"{}\",\"title\":\"First\",\"file_name\":\"{}.md\"}}\n",
+                first.id, first.id
+            ),
+        )
+        .unwrap();
+
+        let second = Brief::new("Second", "minimal");
+        store.write(&second).unwrap();
+        assert_eq!(store.list().unwrap().len(), 2);
+        assert_eq!(store.read_all_meta().unwrap().len(), 2);
+    }
+
     #[test]
     fn index_handles_deleted_files() {
         let dir = TempDir::new().unwrap();
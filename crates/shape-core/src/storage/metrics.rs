@@ -0,0 +1,145 @@
+//! Local command metrics
+//!
+//! When `[metrics] enabled = true`, each command appends one line to
+//! `.shape/.cache/metrics.jsonl` with its latency and the size of the stores
+//! at the time. Nothing leaves the machine; `shape metrics show` summarizes
+//! the file for diagnosing slow projects. The file is trimmed to its newer
+//! half once it passes [`MAX_BYTES`].
+
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+
+/// Size at which the metrics file is trimmed
+const MAX_BYTES: u64 = 1024 * 1024;
+
+fn metrics_path(shape_dir: &Path) -> PathBuf {
+    shape_dir.join(".cache").join("metrics.jsonl")
+}
+
+/// Store sizes at the time of a command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoreSizes {
+    /// Size of `tasks.jsonl` in bytes
+    pub tasks_bytes: u64,
+
+    /// Number of brief files
+    pub briefs: u64,
+
+    /// Size of the SQLite cache in bytes
+    pub cache_bytes: u64,
+}
+
+impl StoreSizes {
+    /// Measures the stores from file metadata (no parsing)
+    pub fn measure(shape_dir: &Path) -> Self {
+        let size = |path: PathBuf| fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        let briefs = fs::read_dir(shape_dir.join("briefs"))
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.path().extension().is_some_and(|ext| ext == "md"))
+                    .count() as u64
+            })
+            .unwrap_or(0);
+
+        Self {
+            tasks_bytes: size(shape_dir.join("tasks.jsonl")),
+            briefs,
+            cache_bytes: size(shape_dir.join(".cache").join("shape.db")),
+        }
+    }
+}
+
+/// One recorded command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricRecord {
+    /// When the command finished
+    pub at: DateTime<Utc>,
+
+    /// Subcommand path, e.g. `task list` (arguments are not recorded)
+    pub command: String,
+
+    /// Wall-clock time in milliseconds
+    pub duration_ms: u64,
+
+    /// Whether the command succeeded
+    pub success: bool,
+
+    /// Store sizes after the command
+    #[serde(flatten)]
+    pub sizes: StoreSizes,
+}
+
+/// Appends a record. Metrics are advisory, so failures are ignored.
+pub(crate) fn record(shape_dir: &Path, record: &MetricRecord) {
+    let _ = try_record(shape_dir, record);
+}
+
+fn try_record(shape_dir: &Path, record: &MetricRecord) -> Result<()> {
+    let path = metrics_path(shape_dir);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    file.lock_exclusive()?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+
+    if file.metadata()?.len() > MAX_BYTES {
+        let content = fs::read_to_string(&path)?;
+        let lines: Vec<&str> = content.lines().collect();
+        let kept = lines[lines.len() / 2..].join("\n");
+        fs::write(&path, kept + "\n")?;
+    }
+    Ok(())
+}
+
+/// Reads all records, oldest first. Unreadable lines are skipped.
+pub fn read(shape_dir: &Path) -> Result<Vec<MetricRecord>> {
+    let path = metrics_path(shape_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file = fs::File::open(&path)
+        .with_context(|| format!("Failed to open metrics: {}", path.display()))?;
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(|l| l.ok())
+        .filter_map(|l| serde_json::from_str(&l).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn records_round_trip() {
+        let dir = TempDir::new().unwrap();
+        assert!(read(dir.path()).unwrap().is_empty());
+
+        fs::write(dir.path().join("tasks.jsonl"), "{}\n").unwrap();
+        let entry = MetricRecord {
+            at: Utc::now(),
+            command: "task list".to_string(),
+            duration_ms: 12,
+            success: true,
+            sizes: StoreSizes::measure(dir.path()),
+        };
+        record(dir.path(), &entry);
+        record(dir.path(), &entry);
+
+        let records = read(dir.path()).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].command, "task list");
+        assert_eq!(records[0].sizes.tasks_bytes, 3);
+    }
+}
@@ -0,0 +1,156 @@
+//! JSONL storage for milestones
+//!
+//! Milestones are stored in `.shape/milestones.jsonl`, one JSON object per
+//! line, ordered by date.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use fs2::FileExt;
+
+use crate::domain::Milestone;
+
+/// Store for milestones in JSONL format
+pub struct MilestoneStore {
+    path: PathBuf,
+}
+
+impl MilestoneStore {
+    /// Creates a new milestone store at the given path
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Creates the default store for a project
+    pub fn for_project(project_root: &Path) -> Self {
+        Self::new(project_root.join(".shape").join("milestones.jsonl"))
+    }
+
+    /// Returns the path to the store file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads all milestones, ordered by date then ID
+    pub fn read_all(&self) -> Result<Vec<Milestone>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let file = File::open(&self.path)
+            .with_context(|| format!("Failed to open milestone store: {}", self.path.display()))?;
+        file.lock_shared()
+            .context("Failed to acquire read lock on milestone store")?;
+
+        let mut milestones = Vec::new();
+        for (line_num, line) in BufReader::new(&file).lines().enumerate() {
+            let line = line.with_context(|| format!("Failed to read line {}", line_num + 1))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let milestone: Milestone = serde_json::from_str(&line)
+                .with_context(|| format!("Failed to parse milestone at line {}", line_num + 1))?;
+            milestones.push(milestone);
+        }
+
+        milestones.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.id.cmp(&b.id)));
+        Ok(milestones)
+    }
+
+    /// Reads a single milestone by ID
+    pub fn read(&self, id: &str) -> Result<Option<Milestone>> {
+        Ok(self.read_all()?.into_iter().find(|m| m.id == id))
+    }
+
+    /// Writes all milestones atomically (temp file + rename)
+    pub fn write_all(&self, milestones: &[Milestone]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        let temp_path = self.path.with_extension("jsonl.tmp");
+
+        {
+            let file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&temp_path)
+                .with_context(|| format!("Failed to create temp file: {}", temp_path.display()))?;
+            file.lock_exclusive()
+                .context("Failed to acquire write lock on milestone store")?;
+
+            let mut sorted: Vec<_> = milestones.iter().collect();
+            sorted.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.id.cmp(&b.id)));
+
+            let mut writer = BufWriter::new(&file);
+            for milestone in sorted {
+                let line =
+                    serde_json::to_string(milestone).context("Failed to serialize milestone")?;
+                writeln!(writer, "{}", line).context("Failed to write milestone")?;
+            }
+            writer.flush().context("Failed to flush milestone store")?;
+        }
+
+        fs::rename(&temp_path, &self.path).with_context(|| {
+            format!(
+                "Failed to rename {} to {}",
+                temp_path.display(),
+                self.path.display()
+            )
+        })?;
+
+        Ok(())
+    }
+
+    /// Adds or replaces a milestone
+    pub fn upsert(&self, milestone: &Milestone) -> Result<()> {
+        let mut all = self.read_all()?;
+        all.retain(|m| m.id != milestone.id);
+        all.push(milestone.clone());
+        self.write_all(&all)
+    }
+
+    /// Removes a milestone, returning whether it existed
+    pub fn remove(&self, id: &str) -> Result<bool> {
+        let mut all = self.read_all()?;
+        let before = all.len();
+        all.retain(|m| m.id != id);
+        if all.len() == before {
+            return Ok(false);
+        }
+        self.write_all(&all)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    #[test]
+    fn round_trips_in_date_order() {
+        let dir = TempDir::new().unwrap();
+        let store = MilestoneStore::for_project(dir.path());
+        assert!(store.read_all().unwrap().is_empty());
+
+        let late = Milestone::new("Launch", NaiveDate::from_ymd_opt(2026, 6, 1).unwrap());
+        let early = Milestone::new("Demo", NaiveDate::from_ymd_opt(2026, 3, 3).unwrap());
+        store.upsert(&late).unwrap();
+        store.upsert(&early).unwrap();
+
+        let all = store.read_all().unwrap();
+        assert_eq!(all, vec![early.clone(), late.clone()]);
+        assert_eq!(store.read(&late.id).unwrap(), Some(late.clone()));
+
+        assert!(store.remove(&early.id).unwrap());
+        assert!(!store.remove(&early.id).unwrap());
+        assert_eq!(store.read_all().unwrap(), vec![late]);
+    }
+}
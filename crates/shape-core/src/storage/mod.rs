@@ -0,0 +1,140 @@
+//! # Storage Layer
+//!
+//! Persistence layer for Shape CLI with git-friendly file formats.
+//!
+//! ## Storage Formats
+//!
+//! | Data | Format | Location |
+//! |------|--------|----------|
+//! | Briefs | Markdown + YAML frontmatter (validated on read) | `.shape/briefs/{id}.md` |
+//! | Brief translations | Markdown + YAML frontmatter | `.shape/briefs/i18n/{id}.{lang}.md` |
+//! | Tasks | JSONL (one JSON per line) | `.shape/tasks.jsonl` |
+//! | Milestones | JSONL | `.shape/milestones.jsonl` |
+//! | Betting tables | JSONL | `.shape/bets.jsonl` |
+//! | Task templates | TOML | `.shape/templates/tasks/{name}.toml` |
+//! | Prompt presets | Markdown | `.shape/prompts/{name}.md` |
+//! | ID redirects | JSONL | `.shape/redirects.jsonl` |
+//! | Resource locks | JSONL | `.shape/locks.jsonl` |
+//! | Operation journal (local, for `shape undo`) | JSONL | `.shape/journal.jsonl` |
+//! | Config | TOML | `.shape/config.toml` |
+//! | Team directory | TOML | `.shape/team.toml` |
+//! | Encryption settings | JSON (only once `shape lock-store` has run) | `.shape/encryption.json` |
+//! | Index | JSONL (auto-regenerated) | `.shape/briefs/index.jsonl` |
+//! | Archived briefs and tasks | Same as the live stores | `.shape/archive/` |
+//!
+//! ## Concurrency Safety
+//!
+//! - [`TaskStore`] uses file locking (`fs2`) for concurrent access, with a
+//!   configurable timeout that reports the PID holding the lock
+//! - [`BriefStore`] uses mtime-based index invalidation
+//! - All writes are atomic (temp file + rename)
+//! - `shape tx` sessions stage task and brief writes and apply them together
+//! - Multi-write commands raise a [`WriteFence`] so the daemon commits them once
+//!
+//! ## Encryption at Rest
+//!
+//! Once a store is encrypted with `shape lock-store`, [`TaskStore`] and
+//! [`BriefStore`] seal what they write and open what they read, so callers
+//! see plaintext either way.
+//!
+//! ## Project Structure
+//!
+//! ```text
+//! .shape/
+//! ├── briefs/
+//! │   ├── b-1234567.md      # Brief markdown files
+//! │   ├── i18n/             # Translated copies (b-1234567.fr.md)
+//! │   └── index.jsonl       # Fast query index (auto-generated)
+//! ├── tasks.jsonl           # All tasks in JSONL format
+//! ├── milestones.jsonl      # Date-anchored milestones
+//! ├── bets.jsonl            # Betting tables, one per cycle
+//! ├── redirects.jsonl       # Old IDs and the IDs they became
+//! ├── locks.jsonl           # Paths and subsystems agents have locked
+//! ├── journal.jsonl         # Recent task changes for undo/redo (not committed)
+//! ├── config.toml           # Project configuration
+//! ├── team.toml             # Humans and agents @mentions refer to
+//! ├── encryption.json       # Key derivation settings for an encrypted store
+//! ├── archive/              # Old shipped briefs and their tasks (briefs/, tasks.jsonl)
+//! ├── templates/tasks/       # Task templates
+//! ├── prompts/              # Agent prompt presets
+//! ├── plugins/              # Local plugins
+//! ├── sync/                 # Sync state for external tools
+//! ├── .cache/               # SQLite cache, change counter, metrics, bench baseline, idempotency keys
+//! │   ├── fences/           # Operations the daemon waits for before committing
+//! │   └── tx/               # Stores staged by an open `shape tx` session
+//! └── .gitignore            # Ignores index and sync state
+//! ```
+//!
+//! ## Key Types
+//!
+//! - [`Project`] - Entry point for accessing a Shape project
+//! - [`BriefStore`] - Read/write briefs as markdown files
+//! - [`TaskStore`] - Read/write tasks as JSONL
+//! - [`MilestoneStore`] - Read/write milestones as JSONL
+//! - [`BetStore`] - Read/write betting tables as JSONL
+//! - [`TemplateStore`] - Read/write task templates as TOML
+//! - [`PromptStore`] - Read agent prompt presets
+//! - [`TranslationStore`] - Read/write brief translations
+//! - [`ResourceLockStore`] - Read/write resource locks as JSONL
+//! - [`Config`] - Project and global configuration
+
+pub mod archive;
+mod audit;
+mod bench;
+mod bets;
+#[cfg(feature = "cache")]
+mod cache;
+mod config;
+pub mod encryption;
+mod fence;
+mod format;
+mod frontmatter;
+mod idempotency;
+mod journal;
+mod jsonl;
+mod lock;
+mod markdown;
+mod metrics;
+mod milestones;
+mod project;
+mod prompts;
+mod redirects;
+mod resource_locks;
+mod revision;
+mod team;
+mod templates;
+mod translations;
+mod tx;
+
+pub use audit::AuditEntry;
+pub use bench::BenchBaseline;
+pub use bets::BetStore;
+#[cfg(feature = "cache")]
+pub use cache::{Cache, CacheError, CachedBrief, CachedTask, SearchResult, SearchResultType};
+pub use config::{
+    BriefConfig, CompactionConfig, CompactionStrategy, Config, ConfigError, ContextConfig,
+    DaemonConfig, DisplayConfig, GateConfig, IconMode, LockConflicts, MetricsConfig, OpenConfig,
+    PipelineConfig, PipelineStep, ReportConfig, ReviewConfig, StorageConfig, UpdateConfig,
+};
+pub use encryption::{EncryptionError, EncryptionInfo, StoreCipher};
+pub use fence::{FenceHolder, WriteFence};
+pub use format::{
+    check_version, pending_migrations, set_config_version, FormatError, Migration, TasksHeader,
+    FORMAT_VERSION,
+};
+pub use frontmatter::{FrontmatterError, FrontmatterIssue, IssueSeverity};
+pub use idempotency::{IdempotencyLock, IdempotencyLog, IdempotencyRecord};
+pub use journal::{Journal, JournalEntry, JournalLock, TaskChange};
+pub use jsonl::{TaskStore, WriteRecorder};
+pub use lock::{LockError, LockHolder};
+pub use markdown::BriefStore;
+pub use metrics::{MetricRecord, StoreSizes};
+pub use milestones::MilestoneStore;
+pub use project::{Project, ProjectError};
+pub use prompts::PromptStore;
+pub use redirects::Redirect;
+pub use resource_locks::ResourceLockStore;
+pub use team::TeamStore;
+pub use templates::{fill_placeholders, placeholders, TaskTemplate, TemplateStore};
+pub use translations::{Translation, TranslationStore};
+pub use tx::{TxCommit, TxError, TxSession};
@@ -0,0 +1,731 @@
+//! Project management
+//!
+//! Handles project initialization and provides access to stores.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use thiserror::Error;
+
+use crate::domain::{parse_external_ref, BriefId, IdScheme, TaskId};
+
+#[cfg(feature = "cache")]
+use super::Cache;
+use super::{
+    check_version, AuditEntry, BenchBaseline, BetStore, BriefStore, Config, IdempotencyLog,
+    Journal, MetricRecord, MilestoneStore, PromptStore, Redirect, ResourceLockStore, StoreSizes,
+    TaskStore, TeamStore, TemplateStore, TranslationStore, TxCommit, TxSession, WriteFence,
+    FORMAT_VERSION,
+};
+
+#[derive(Debug, Error)]
+pub enum ProjectError {
+    #[error("Project already exists at {0}")]
+    AlreadyExists(PathBuf),
+
+    #[error("Not in a shape project. Run 'shape init' first.")]
+    NotInProject,
+
+    #[error("Not in a shape project (searched from {}). Run 'shape init' first or pass --project PATH.{}", .start.display(), nearby_hint(.nearby))]
+    NotFound {
+        start: PathBuf,
+        nearby: Vec<PathBuf>,
+    },
+
+    #[error("Failed to create project: {0}")]
+    CreateFailed(String),
+
+    #[error("No brief or task has external ID {0}")]
+    ExternalIdNotFound(String),
+
+    #[error("External ID {0} matches more than one {1}")]
+    AmbiguousExternalId(String, &'static str),
+}
+
+/// Environment variable naming the project to operate on
+const PROJECT_ENV: &str = "SHAPE_PROJECT";
+
+/// Set once from `--project`; takes precedence over [`PROJECT_ENV`]
+static SEARCH_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Set from `--wait`: block on store locks instead of timing out
+static WAIT_FOR_LOCKS: AtomicBool = AtomicBool::new(false);
+
+/// Directory project discovery starts from: `--project`, then
+/// `SHAPE_PROJECT`, then the current directory
+pub(crate) fn search_start() -> Option<PathBuf> {
+    if let Some(root) = SEARCH_ROOT.get() {
+        return Some(root.clone());
+    }
+    match std::env::var_os(PROJECT_ENV) {
+        Some(path) if !path.is_empty() => Some(PathBuf::from(path)),
+        _ => std::env::current_dir().ok(),
+    }
+}
+
+/// Finds directories with a `.shape/` near `start`: below it (two levels
+/// deep) and beside it
+fn nearby_projects(start: &Path) -> Vec<PathBuf> {
+    const LIMIT: usize = 5;
+
+    fn children(dir: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut dirs: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| {
+                p.is_dir()
+                    && !p
+                        .file_name()
+                        .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+            })
+            .collect();
+        dirs.sort();
+        dirs
+    }
+
+    let mut candidates = Vec::new();
+    for child in children(start) {
+        candidates.extend(children(&child));
+        candidates.push(child);
+    }
+    if let Some(parent) = start.parent() {
+        candidates.extend(children(parent).into_iter().filter(|p| p != start));
+    }
+
+    let mut found: Vec<PathBuf> = candidates
+        .into_iter()
+        .filter(|p| p.join(".shape").is_dir())
+        .collect();
+    found.sort();
+    found.truncate(LIMIT);
+    found
+}
+
+fn nearby_hint(nearby: &[PathBuf]) -> String {
+    if nearby.is_empty() {
+        return String::new();
+    }
+    let paths: Vec<String> = nearby
+        .iter()
+        .map(|p| format!("\n  {}", p.display()))
+        .collect();
+    format!("\nProjects nearby:{}", paths.concat())
+}
+
+/// A Shape project
+pub struct Project {
+    root: PathBuf,
+    config: Config,
+}
+
+impl Project {
+    /// Opens an existing project at the given path
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        let shape_dir = root.join(".shape");
+
+        if !shape_dir.is_dir() {
+            return Err(ProjectError::NotInProject.into());
+        }
+
+        let config = Config::for_project(&root)?;
+        check_version("config.toml", config.project.format_version)?;
+
+        Ok(Self { root, config })
+    }
+
+    /// Opens the project at the current directory or a parent
+    ///
+    /// `--project` or `SHAPE_PROJECT` replace the current directory as the
+    /// starting point.
+    #[tracing::instrument(name = "project.open", level = "debug", skip_all)]
+    pub fn open_current() -> Result<Self> {
+        let Some(root) = Config::find_project_root() else {
+            let start = search_start().unwrap_or_default();
+            let nearby = nearby_projects(&start);
+            return Err(ProjectError::NotFound { start, nearby }.into());
+        };
+
+        Self::open(root)
+    }
+
+    /// Makes [`Project::open_current`] search from `path` instead of the
+    /// current directory (`--project`). Only the first call has an effect.
+    pub fn set_search_root(path: impl Into<PathBuf>) {
+        let _ = SEARCH_ROOT.set(path.into());
+    }
+
+    /// Makes task stores wait indefinitely for locks instead of failing after
+    /// `[storage] lock_timeout_secs` (`--wait`)
+    pub fn wait_for_locks() {
+        WAIT_FOR_LOCKS.store(true, Ordering::Relaxed);
+    }
+
+    /// Initializes a new project at the given path
+    pub fn init(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        let shape_dir = root.join(".shape");
+
+        // Create directory structure
+        fs::create_dir_all(&shape_dir).with_context(|| {
+            format!("Failed to create .shape directory: {}", shape_dir.display())
+        })?;
+
+        let briefs_dir = shape_dir.join("briefs");
+        fs::create_dir_all(&briefs_dir).with_context(|| {
+            format!(
+                "Failed to create briefs directory: {}",
+                briefs_dir.display()
+            )
+        })?;
+
+        let plugins_dir = shape_dir.join("plugins");
+        fs::create_dir_all(&plugins_dir).with_context(|| {
+            format!(
+                "Failed to create plugins directory: {}",
+                plugins_dir.display()
+            )
+        })?;
+
+        let sync_dir = shape_dir.join("sync");
+        fs::create_dir_all(&sync_dir)
+            .with_context(|| format!("Failed to create sync directory: {}", sync_dir.display()))?;
+
+        // Create default config
+        let config_path = shape_dir.join("config.toml");
+        if !config_path.exists() {
+            let default_config = format!(
+                r#"# Shape CLI configuration
+# See https://shape.dev/docs/config for options
+
+# On-disk format version (see 'shape migrate')
+format_version = {}
+
+# Default brief type for 'shape brief new'
+default_brief_type = "minimal"
+
+# Plugins to load
+plugins = []
+
+# Days to include completed tasks in context export
+context_days = 7
+"#,
+                FORMAT_VERSION
+            );
+            fs::write(&config_path, default_config)
+                .with_context(|| format!("Failed to write config: {}", config_path.display()))?;
+        }
+
+        // Create .gitignore for .shape
+        let gitignore_path = shape_dir.join(".gitignore");
+        if !gitignore_path.exists() {
+            let gitignore = r#"# Ignore index files (they're regenerated)
+briefs/index.jsonl
+archive/briefs/index.jsonl
+
+# Ignore SQLite cache (regenerated from source files)
+.cache/
+
+# Ignore sync state (contains remote IDs)
+sync/
+
+# Ignore plugin cache
+plugins/*.cache
+
+# Ignore the undo journal (local to each machine)
+journal.jsonl
+
+# Ignore daemon files (local to each machine)
+daemon.pid
+daemon.log
+daemon.log.*
+"#;
+            fs::write(&gitignore_path, gitignore).with_context(|| {
+                format!("Failed to write .gitignore: {}", gitignore_path.display())
+            })?;
+        }
+
+        Self::open(root)
+    }
+
+    /// Returns the project root path
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the .shape directory path
+    pub fn shape_dir(&self) -> PathBuf {
+        self.root.join(".shape")
+    }
+
+    /// Returns the configuration
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Returns a mutable reference to the configuration
+    pub fn config_mut(&mut self) -> &mut Config {
+        &mut self.config
+    }
+
+    /// Resolves a task reference: a task ID or an external ID such as
+    /// `jira:ABC-123`
+    pub fn resolve_task_id(&self, reference: &str) -> Result<TaskId> {
+        let parsed = reference.parse::<TaskId>();
+        let Some((system, key)) = parsed
+            .is_err()
+            .then(|| parse_external_ref(reference))
+            .flatten()
+        else {
+            return self.redirected(parsed?);
+        };
+
+        let tasks = self.task_store().read_all()?;
+        let mut matches = tasks
+            .values()
+            .filter(|t| t.external_ids.get(system).is_some_and(|k| k == key))
+            .map(|t| t.id.clone());
+        match (matches.next(), matches.next()) {
+            (Some(id), None) => Ok(id),
+            (Some(_), Some(_)) => {
+                Err(ProjectError::AmbiguousExternalId(reference.to_string(), "task").into())
+            }
+            _ => Err(ProjectError::ExternalIdNotFound(reference.to_string()).into()),
+        }
+    }
+
+    /// Resolves a brief reference: a brief ID or an external ID such as
+    /// `github:456`
+    pub fn resolve_brief_id(&self, reference: &str) -> Result<BriefId> {
+        let parsed = reference.parse::<BriefId>();
+        let Some((system, key)) = parsed
+            .is_err()
+            .then(|| parse_external_ref(reference))
+            .flatten()
+        else {
+            return self.redirected(parsed?);
+        };
+
+        let briefs = self.brief_store().read_all_meta()?;
+        let mut matches = briefs
+            .values()
+            .filter(|b| b.external_ids.get(system).is_some_and(|k| k == key))
+            .map(|b| b.id.clone());
+        match (matches.next(), matches.next()) {
+            (Some(id), None) => Ok(id),
+            (Some(_), Some(_)) => {
+                Err(ProjectError::AmbiguousExternalId(reference.to_string(), "brief").into())
+            }
+            _ => Err(ProjectError::ExternalIdNotFound(reference.to_string()).into()),
+        }
+    }
+
+    /// Returns the scheme for new brief and task IDs
+    pub fn id_scheme(&self) -> &IdScheme {
+        &self.config.project.ids
+    }
+
+    /// Generates an ID for a new brief, widening its hash past existing ones
+    pub fn new_brief_id(&self, title: &str, brief_type: &str) -> Result<BriefId> {
+        let taken = self.root_ids()?.into_keys().collect();
+        Ok(self
+            .id_scheme()
+            .unique_brief_id(title, brief_type, chrono::Utc::now(), &taken))
+    }
+
+    /// Generates an ID for a new standalone task, widening its hash past
+    /// existing ones
+    pub fn new_standalone_task_id(&self, title: &str) -> Result<TaskId> {
+        let taken = self.root_ids()?.into_keys().collect();
+        Ok(self
+            .id_scheme()
+            .unique_standalone_task_id(title, chrono::Utc::now(), &taken))
+    }
+
+    /// Briefs and standalone tasks that share a hash, keyed by the hash
+    ///
+    /// IDs that differ only in prefix or slug (`b-7f2b4c1` and
+    /// `rfc-7f2b4c1-auth`) still collide: the hash is what identifies them.
+    pub fn id_collisions(&self) -> Result<BTreeMap<String, Vec<String>>> {
+        let mut roots = self.root_ids()?;
+        roots.retain(|_, ids| ids.len() > 1);
+        Ok(roots)
+    }
+
+    /// IDs of briefs and top-level standalone tasks, keyed by lowercase hash
+    fn root_ids(&self) -> Result<BTreeMap<String, Vec<String>>> {
+        let mut roots: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for id in self.brief_store().read_all_meta()?.into_keys() {
+            roots
+                .entry(id.hash().to_ascii_lowercase())
+                .or_default()
+                .push(id.to_string());
+        }
+        for id in self.task_store().read_all()?.into_keys() {
+            if id.is_standalone() && id.segments().is_empty() {
+                roots
+                    .entry(id.hash().to_ascii_lowercase())
+                    .or_default()
+                    .push(id.to_string());
+            }
+        }
+        for ids in roots.values_mut() {
+            ids.sort();
+        }
+        Ok(roots)
+    }
+
+    /// Directory holding the task and brief stores: the staging copy while
+    /// this process owns the open `shape tx` session, `.shape/` otherwise
+    fn store_dir(&self) -> PathBuf {
+        let shape_dir = self.shape_dir();
+        super::tx::joined(&shape_dir, Self::tx_token().as_deref()).unwrap_or(shape_dir)
+    }
+
+    /// Token of the `shape tx` session this process works in, from
+    /// `SHAPE_TX`
+    pub fn tx_token() -> Option<String> {
+        std::env::var(super::tx::TOKEN_ENV)
+            .ok()
+            .filter(|token| !token.is_empty())
+    }
+
+    /// Returns the task store
+    pub fn task_store(&self) -> TaskStore {
+        let store = TaskStore::new(self.store_dir().join("tasks.jsonl"));
+        if WAIT_FOR_LOCKS.load(Ordering::Relaxed) {
+            return store;
+        }
+        let timeout = self.config.project.storage.lock_timeout_secs;
+        store.with_lock_timeout(Duration::from_secs(timeout))
+    }
+
+    /// Returns the brief store
+    pub fn brief_store(&self) -> BriefStore {
+        BriefStore::new(self.store_dir().join("briefs"))
+    }
+
+    /// Returns the store of archived tasks
+    pub fn archived_task_store(&self) -> TaskStore {
+        TaskStore::new(super::archive::dir(&self.shape_dir()).join("tasks.jsonl"))
+    }
+
+    /// Returns the store of archived briefs
+    pub fn archived_brief_store(&self) -> BriefStore {
+        let shape_dir = self.shape_dir();
+        BriefStore::new(super::archive::dir(&shape_dir).join("briefs"))
+            .with_index_path(super::archive::index_path(&shape_dir))
+    }
+
+    /// Adds `entry` to `.shape/.gitignore` unless it is already listed, for
+    /// projects whose file predates it
+    pub fn ensure_gitignored(&self, entry: &str) -> Result<()> {
+        let path = self.shape_dir().join(".gitignore");
+        let mut content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        if content.lines().any(|line| line.trim() == entry) {
+            return Ok(());
+        }
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(entry);
+        content.push('\n');
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Returns the milestone store
+    pub fn milestone_store(&self) -> MilestoneStore {
+        MilestoneStore::for_project(&self.root)
+    }
+
+    /// Returns the betting table store
+    pub fn bet_store(&self) -> BetStore {
+        BetStore::for_project(&self.root)
+    }
+
+    /// Returns the task template store
+    pub fn template_store(&self) -> TemplateStore {
+        TemplateStore::for_project(&self.root)
+    }
+
+    /// Returns the prompt preset store
+    pub fn prompt_store(&self) -> PromptStore {
+        PromptStore::for_project(&self.root)
+    }
+
+    /// Returns the brief translation store
+    pub fn translation_store(&self) -> TranslationStore {
+        TranslationStore::for_project(&self.root)
+    }
+
+    /// Returns the resource lock store
+    pub fn resource_lock_store(&self) -> ResourceLockStore {
+        ResourceLockStore::for_project(&self.root)
+    }
+
+    /// Returns the team directory store
+    pub fn team_store(&self) -> TeamStore {
+        TeamStore::for_project(&self.root)
+    }
+
+    /// Returns the plugins directory
+    pub fn plugins_dir(&self) -> PathBuf {
+        self.shape_dir().join("plugins")
+    }
+
+    /// Returns the sync directory
+    pub fn sync_dir(&self) -> PathBuf {
+        self.shape_dir().join("sync")
+    }
+
+    /// Returns the attachments directory
+    pub fn attachments_dir(&self) -> PathBuf {
+        self.shape_dir().join("attachments")
+    }
+
+    /// Returns the project's change counter, bumped on every brief or task write
+    pub fn revision(&self) -> u64 {
+        super::revision::current(&self.store_dir())
+    }
+
+    /// Returns the open `shape tx` session, if any
+    pub fn tx_session(&self) -> Result<Option<TxSession>> {
+        super::tx::current(&self.shape_dir())
+    }
+
+    /// Starts staging task and brief writes instead of applying them
+    pub fn begin_tx(&self) -> Result<TxSession> {
+        super::tx::begin(&self.shape_dir())
+    }
+
+    /// Applies the staged writes to the live stores and ends the session
+    pub fn commit_tx(&self) -> Result<TxCommit> {
+        super::tx::commit(&self.shape_dir(), Self::tx_token().as_deref())
+    }
+
+    /// Discards the staged writes and ends the session; `force` discards
+    /// a session this process doesn't own
+    pub fn abort_tx(&self, force: bool) -> Result<()> {
+        super::tx::abort(&self.shape_dir(), Self::tx_token().as_deref(), force)
+    }
+
+    /// Appends a command metric when `[metrics] enabled` is set
+    pub fn record_metric(&self, command: &str, duration: std::time::Duration, success: bool) {
+        if !self.config.project.metrics.enabled {
+            return;
+        }
+        let shape_dir = self.shape_dir();
+        let record = MetricRecord {
+            at: chrono::Utc::now(),
+            command: command.to_string(),
+            duration_ms: duration.as_millis() as u64,
+            success,
+            sizes: StoreSizes::measure(&shape_dir),
+        };
+        super::metrics::record(&shape_dir, &record);
+    }
+
+    /// Returns the recorded command metrics, oldest first
+    pub fn metrics(&self) -> Result<Vec<MetricRecord>> {
+        super::metrics::read(&self.shape_dir())
+    }
+
+    /// Returns the `shape bench` baseline, if one was recorded
+    pub fn bench_baseline(&self) -> Result<Option<BenchBaseline>> {
+        super::bench::read(&self.shape_dir())
+    }
+
+    /// Replaces the `shape bench` baseline
+    pub fn save_bench_baseline(&self, baseline: &BenchBaseline) -> Result<()> {
+        super::bench::write(&self.shape_dir(), baseline)
+    }
+
+    /// Returns the log of recently used idempotency keys
+    pub fn idempotency_log(&self) -> IdempotencyLog {
+        IdempotencyLog::for_project(&self.shape_dir())
+    }
+
+    /// Returns the operation journal behind `shape undo` and `shape redo`
+    pub fn journal(&self) -> Journal {
+        Journal::for_project(&self.shape_dir())
+    }
+
+    /// Raises this process's write fence for `operation` until the guard
+    /// is dropped
+    pub fn write_fence(&self, operation: &str) -> Result<WriteFence> {
+        WriteFence::raise(&self.shape_dir(), operation)
+    }
+
+    /// Follows `.shape/redirects.jsonl` from an old ID to its current one
+    fn redirected<T>(&self, id: T) -> Result<T>
+    where
+        T: std::str::FromStr + std::fmt::Display,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        match super::redirects::resolve(&self.shape_dir(), &id.to_string())? {
+            Some(current) => Ok(current.parse()?),
+            None => Ok(id),
+        }
+    }
+
+    /// Records IDs changed by `reason` so the resolver can follow them
+    pub fn record_redirects(&self, changes: &[(String, String)], reason: &str) -> Result<()> {
+        super::redirects::record(&self.shape_dir(), changes, reason)
+    }
+
+    /// Returns the recorded ID redirects
+    pub fn redirects(&self) -> Result<Vec<Redirect>> {
+        super::redirects::read(&self.shape_dir())
+    }
+
+    /// Appends an entry to the audit log (`.shape/audit.jsonl`)
+    pub fn audit(&self, entry: &AuditEntry) -> Result<()> {
+        super::audit::append(&self.shape_dir(), entry)
+    }
+
+    /// Returns the audit log, oldest first
+    pub fn audit_log(&self) -> Result<Vec<AuditEntry>> {
+        super::audit::read(&self.shape_dir())
+    }
+
+    /// Returns the on-disk format version: the lower of `config.toml` and the
+    /// `tasks.jsonl` header (a task file without a header is version 0)
+    pub fn format_version(&self) -> Result<u32> {
+        let mut version = self.config.project.format_version;
+        let store = self.task_store();
+        if store.path().exists() {
+            let tasks = store.header()?.map_or(0, |h| h.format_version);
+            version = version.min(tasks);
+        }
+        Ok(version)
+    }
+
+    /// Returns the cache directory
+    pub fn cache_dir(&self) -> PathBuf {
+        self.shape_dir().join(".cache")
+    }
+
+    /// Opens the SQLite cache for this project
+    #[cfg(feature = "cache")]
+    pub fn cache(&self) -> Result<Cache> {
+        Cache::open_in(&self.store_dir())
+    }
+
+    /// Rebuilds the cache from source files
+    #[cfg(feature = "cache")]
+    pub fn rebuild_cache(&self) -> Result<()> {
+        let mut cache = self.cache()?;
+        let tasks = self.task_store().read_all()?;
+        let briefs = self.brief_store().read_all()?;
+        cache.rebuild(&tasks, &briefs)?;
+        Ok(())
+    }
+
+    /// Gets the cache if it's fresh, or rebuilds it if stale
+    #[cfg(feature = "cache")]
+    #[tracing::instrument(name = "cache.open", level = "debug", skip_all)]
+    pub fn get_or_rebuild_cache(&self) -> Result<Cache> {
+        // The cache holds plaintext, so a locked store doesn't serve it either
+        super::encryption::cipher(&self.shape_dir())?;
+        let mut cache = self.cache()?;
+
+        if cache.is_stale()? {
+            let tasks = self.task_store().read_all()?;
+            let briefs = self.brief_store().read_all()?;
+            cache.rebuild(&tasks, &briefs)?;
+        }
+
+        Ok(cache)
+    }
+
+    /// Checks if a path is inside this project
+    pub fn contains(&self, path: &Path) -> bool {
+        path.starts_with(&self.root)
+    }
+
+    /// Returns a relative path from the project root
+    pub fn relative_path(&self, path: &Path) -> Option<PathBuf> {
+        path.strip_prefix(&self.root).ok().map(|p| p.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn init_creates_structure() {
+        let dir = TempDir::new().unwrap();
+        let project = Project::init(dir.path()).unwrap();
+
+        assert!(project.shape_dir().is_dir());
+        assert!(project.shape_dir().join("briefs").is_dir());
+        assert!(project.shape_dir().join("plugins").is_dir());
+        assert!(project.shape_dir().join("sync").is_dir());
+        assert!(project.shape_dir().join("config.toml").is_file());
+        assert!(project.shape_dir().join(".gitignore").is_file());
+    }
+
+    #[test]
+    fn init_is_idempotent() {
+        let dir = TempDir::new().unwrap();
+
+        Project::init(dir.path()).unwrap();
+        Project::init(dir.path()).unwrap(); // Should not fail
+
+        assert!(dir.path().join(".shape").is_dir());
+    }
+
+    #[test]
+    fn open_existing_project() {
+        let dir = TempDir::new().unwrap();
+        Project::init(dir.path()).unwrap();
+
+        let project = Project::open(dir.path()).unwrap();
+        assert_eq!(project.root(), dir.path());
+    }
+
+    #[test]
+    fn open_non_project_fails() {
+        let dir = TempDir::new().unwrap();
+        let result = Project::open(dir.path());
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn stores_are_accessible() {
+        let dir = TempDir::new().unwrap();
+        let project = Project::init(dir.path()).unwrap();
+
+        let task_store = project.task_store();
+        let brief_store = project.brief_store();
+
+        assert!(task_store.path().ends_with("tasks.jsonl"));
+        assert!(brief_store.dir().ends_with("briefs"));
+    }
+
+    #[test]
+    fn relative_path() {
+        let dir = TempDir::new().unwrap();
+        let project = Project::init(dir.path()).unwrap();
+
+        let abs_path = dir.path().join("sub").join("file.txt");
+        let rel_path = project.relative_path(&abs_path);
+
+        assert_eq!(rel_path, Some(PathBuf::from("sub/file.txt")));
+    }
+}
@@ -0,0 +1,106 @@
+//! Agent prompt presets
+//!
+//! Presets are markdown files under `.shape/prompts/`, one per preset
+//! (`coder.md`). They may contain `{{name}}` placeholders that `shape prompt`
+//! fills with the selected task, its brief and the context export.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// Store for prompt presets
+pub struct PromptStore {
+    dir: PathBuf,
+}
+
+impl PromptStore {
+    /// Creates a store rooted at the given directory
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Creates the default prompt store for a project
+    pub fn for_project(project_root: &Path) -> Self {
+        Self::new(project_root.join(".shape").join("prompts"))
+    }
+
+    /// Returns the prompt directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            anyhow::bail!(
+                "Invalid prompt name '{}': use letters, digits, '-' and '_'",
+                name
+            );
+        }
+        Ok(self.dir.join(format!("{}.md", name)))
+    }
+
+    /// Reads a preset by name
+    pub fn read(&self, name: &str) -> Result<Option<String>> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read prompt: {}", path.display()))?;
+        Ok(Some(content))
+    }
+
+    /// Names of all presets, sorted
+    pub fn list(&self) -> Result<Vec<String>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read directory: {}", self.dir.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                names.push(name.to_string());
+            }
+        }
+
+        names.sort();
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn read_and_list_presets() {
+        let dir = TempDir::new().unwrap();
+        let store = PromptStore::new(dir.path().join("prompts"));
+        assert!(store.list().unwrap().is_empty());
+        assert_eq!(store.read("coder").unwrap(), None);
+
+        fs::create_dir_all(store.dir()).unwrap();
+        fs::write(store.dir().join("reviewer.md"), "Review {{task.id}}").unwrap();
+        fs::write(store.dir().join("coder.md"), "Implement {{task.id}}").unwrap();
+        fs::write(store.dir().join("notes.txt"), "ignored").unwrap();
+
+        assert_eq!(store.list().unwrap(), vec!["coder", "reviewer"]);
+        assert_eq!(
+            store.read("coder").unwrap().as_deref(),
+            Some("Implement {{task.id}}")
+        );
+        assert!(store.read("../secrets").is_err());
+    }
+}
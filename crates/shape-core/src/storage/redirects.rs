@@ -0,0 +1,162 @@
+//! ID redirects
+//!
+//! When an operation changes brief or task IDs (`ids migrate`), each old ID
+//! is recorded in `.shape/redirects.jsonl` with the ID it became. The ID
+//! resolver follows these redirects, so agents and scripts still holding an
+//! old ID keep working. The file is committed with the project so other
+//! checkouts resolve old IDs too.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+fn redirects_path(shape_dir: &Path) -> PathBuf {
+    shape_dir.join("redirects.jsonl")
+}
+
+/// An old ID and the ID it became
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Redirect {
+    /// The old ID
+    pub from: String,
+
+    /// The current ID
+    pub to: String,
+
+    /// When the ID changed
+    pub at: DateTime<Utc>,
+
+    /// What changed it, e.g. `ids migrate`
+    pub reason: String,
+}
+
+/// Reads all redirects
+pub fn read(shape_dir: &Path) -> Result<Vec<Redirect>> {
+    let path = redirects_path(shape_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read redirects: {}", path.display()))?;
+    let mut redirects = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let redirect = serde_json::from_str(line)
+            .with_context(|| format!("Invalid redirect on line {}", i + 1))?;
+        redirects.push(redirect);
+    }
+    Ok(redirects)
+}
+
+/// Current ID for `id`, if it was redirected
+pub fn resolve(shape_dir: &Path, id: &str) -> Result<Option<String>> {
+    if !redirects_path(shape_dir).exists() {
+        return Ok(None);
+    }
+    let redirects = read(shape_dir)?;
+    Ok(redirects
+        .iter()
+        .find(|r| r.from == id)
+        .map(|r| r.to.clone()))
+}
+
+/// Records that each `(old, new)` ID pair changed.
+///
+/// Existing redirects to an old ID are pointed at its new ID so lookups
+/// never chain, and redirects from an ID that exists again are dropped.
+pub(crate) fn record(shape_dir: &Path, changes: &[(String, String)], reason: &str) -> Result<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let renamed: HashMap<&str, &str> = changes
+        .iter()
+        .map(|(old, new)| (old.as_str(), new.as_str()))
+        .collect();
+    let mut redirects: Vec<Redirect> = read(shape_dir)?
+        .into_iter()
+        .filter(|r| !renamed.values().any(|new| *new == r.from))
+        .map(|mut r| {
+            if let Some(new) = renamed.get(r.to.as_str()) {
+                r.to = new.to_string();
+            }
+            r
+        })
+        .filter(|r| r.from != r.to)
+        .collect();
+
+    let at = Utc::now();
+    redirects.retain(|r| !renamed.contains_key(r.from.as_str()));
+    redirects.extend(changes.iter().map(|(old, new)| Redirect {
+        from: old.clone(),
+        to: new.clone(),
+        at,
+        reason: reason.to_string(),
+    }));
+
+    let path = redirects_path(shape_dir);
+    let mut content = String::new();
+    for redirect in &redirects {
+        content.push_str(&serde_json::to_string(redirect)?);
+        content.push('\n');
+    }
+    let temp_path = path.with_extension("jsonl.tmp");
+    fs::write(&temp_path, content)
+        .with_context(|| format!("Failed to write redirects: {}", temp_path.display()))?;
+    fs::rename(&temp_path, &path)
+        .with_context(|| format!("Failed to write redirects: {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn pair(old: &str, new: &str) -> (String, String) {
+        (old.to_string(), new.to_string())
+    }
+
+    #[test]
+    fn redirects_collapse_chains_and_drop_reused_ids() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(resolve(dir.path(), "b-1234567").unwrap(), None);
+
+        record(
+            dir.path(),
+            &[pair("b-1234567", "b-auth-1234567")],
+            "ids migrate",
+        )
+        .unwrap();
+        record(
+            dir.path(),
+            &[pair("b-auth-1234567", "B-1234567")],
+            "ids migrate",
+        )
+        .unwrap();
+        assert_eq!(
+            resolve(dir.path(), "b-1234567").unwrap().as_deref(),
+            Some("B-1234567")
+        );
+        assert_eq!(
+            resolve(dir.path(), "b-auth-1234567").unwrap().as_deref(),
+            Some("B-1234567")
+        );
+
+        // Migrating back makes the original ID current again
+        record(dir.path(), &[pair("B-1234567", "b-1234567")], "ids migrate").unwrap();
+        assert_eq!(resolve(dir.path(), "b-1234567").unwrap(), None);
+        assert_eq!(
+            resolve(dir.path(), "B-1234567").unwrap().as_deref(),
+            Some("b-1234567")
+        );
+        assert_eq!(read(dir.path()).unwrap().len(), 2);
+    }
+}
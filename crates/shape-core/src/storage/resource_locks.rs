@@ -0,0 +1,118 @@
+//! JSONL storage for resource locks
+//!
+//! Locks agents hold on paths and subsystems are stored in
+//! `.shape/locks.jsonl`, one JSON object per line, ordered by resource. The
+//! file is committed like claims, so agents in other checkouts see them.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use fs2::FileExt;
+
+use crate::domain::ResourceLock;
+
+/// Store for resource locks in JSONL format
+pub struct ResourceLockStore {
+    path: PathBuf,
+}
+
+impl ResourceLockStore {
+    /// Creates a new lock store at the given path
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Creates the default store for a project
+    pub fn for_project(project_root: &Path) -> Self {
+        Self::new(project_root.join(".shape").join("locks.jsonl"))
+    }
+
+    /// Reads all locks, expired ones included, ordered by resource then agent
+    pub fn read_all(&self) -> Result<Vec<ResourceLock>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let file = File::open(&self.path)
+            .with_context(|| format!("Failed to open lock store: {}", self.path.display()))?;
+        file.lock_shared()
+            .context("Failed to acquire read lock on lock store")?;
+
+        let mut locks = Vec::new();
+        for (line_num, line) in BufReader::new(&file).lines().enumerate() {
+            let line = line.with_context(|| format!("Failed to read line {}", line_num + 1))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let lock: ResourceLock = serde_json::from_str(&line)
+                .with_context(|| format!("Failed to parse lock at line {}", line_num + 1))?;
+            locks.push(lock);
+        }
+
+        locks.sort_by(|a, b| a.resource.cmp(&b.resource).then(a.agent.cmp(&b.agent)));
+        Ok(locks)
+    }
+
+    /// Writes all locks atomically (temp file + rename)
+    pub fn write_all(&self, locks: &[ResourceLock]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        let temp_path = self.path.with_extension("jsonl.tmp");
+
+        {
+            let file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&temp_path)
+                .with_context(|| format!("Failed to create temp file: {}", temp_path.display()))?;
+            file.lock_exclusive()
+                .context("Failed to acquire write lock on lock store")?;
+
+            let mut sorted: Vec<_> = locks.iter().collect();
+            sorted.sort_by(|a, b| a.resource.cmp(&b.resource).then(a.agent.cmp(&b.agent)));
+
+            let mut writer = BufWriter::new(&file);
+            for lock in sorted {
+                let line = serde_json::to_string(lock).context("Failed to serialize lock")?;
+                writeln!(writer, "{}", line).context("Failed to write lock")?;
+            }
+            writer.flush().context("Failed to flush lock store")?;
+        }
+
+        fs::rename(&temp_path, &self.path).with_context(|| {
+            format!(
+                "Failed to rename {} to {}",
+                temp_path.display(),
+                self.path.display()
+            )
+        })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn write_and_read_locks() {
+        let dir = TempDir::new().unwrap();
+        let store = ResourceLockStore::new(dir.path().join("locks.jsonl"));
+        assert!(store.read_all().unwrap().is_empty());
+
+        let auth = ResourceLock::new("src/auth", "agent-a");
+        let billing = ResourceLock::new("billing", "agent-b");
+        store.write_all(&[auth.clone(), billing.clone()]).unwrap();
+
+        assert_eq!(store.read_all().unwrap(), vec![billing, auth]);
+    }
+}
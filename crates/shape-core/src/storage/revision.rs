@@ -0,0 +1,71 @@
+//! Project-level change counter
+//!
+//! Every write through [`TaskStore`](super::TaskStore) or
+//! [`BriefStore`](super::BriefStore) bumps a counter in
+//! `.shape/.cache/revision`. It counts local writes only (the cache is
+//! git-ignored), so it tells agents *that* something changed; content hashes
+//! such as the context ETag tell them *whether* the result differs.
+
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use fs2::FileExt;
+
+fn revision_path(shape_dir: &Path) -> PathBuf {
+    shape_dir.join(".cache").join("revision")
+}
+
+/// Returns the current revision (0 before the first write)
+pub fn current(shape_dir: &Path) -> u64 {
+    fs::read_to_string(revision_path(shape_dir))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Increments the revision. The counter is advisory, so failures are ignored
+/// rather than failing the write that triggered them.
+pub(crate) fn bump(shape_dir: &Path) {
+    let _ = try_bump(shape_dir);
+}
+
+fn try_bump(shape_dir: &Path) -> std::io::Result<u64> {
+    let path = revision_path(shape_dir);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)?;
+    file.lock_exclusive()?;
+
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    let next = contents.trim().parse::<u64>().unwrap_or(0) + 1;
+
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    writeln!(file, "{}", next)?;
+    Ok(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn bump_increments_from_zero() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(current(dir.path()), 0);
+
+        bump(dir.path());
+        bump(dir.path());
+        assert_eq!(current(dir.path()), 2);
+    }
+}
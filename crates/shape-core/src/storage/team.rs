@@ -0,0 +1,104 @@
+//! TOML storage for the team directory
+//!
+//! Members are listed in `.shape/team.toml`, one `[[member]]` table each:
+//!
+//! ```toml
+//! [[member]]
+//! handle = "alice"
+//! name = "Alice Chen"
+//! kind = "human"
+//! aliases = ["achen"]
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::domain::Team;
+
+/// Store for the team directory
+pub struct TeamStore {
+    path: PathBuf,
+}
+
+impl TeamStore {
+    /// Creates a store at the given path
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Creates the default store for a project
+    pub fn for_project(project_root: &Path) -> Self {
+        Self::new(project_root.join(".shape").join("team.toml"))
+    }
+
+    /// Returns the path to the team file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns true if the project keeps a team directory
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Reads the team, empty if there is no team file
+    pub fn read(&self) -> Result<Team> {
+        if !self.path.exists() {
+            return Ok(Team::default());
+        }
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read team: {}", self.path.display()))?;
+        let mut team: Team = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse team: {}", self.path.display()))?;
+        team.members.sort_by(|a, b| a.handle.cmp(&b.handle));
+        Ok(team)
+    }
+
+    /// Writes the team atomically (temp file + rename), ordered by handle
+    pub fn write(&self, team: &Team) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        let mut team = team.clone();
+        team.members.sort_by(|a, b| a.handle.cmp(&b.handle));
+        let content = toml::to_string_pretty(&team).context("Failed to serialize team")?;
+        let temp_path = self.path.with_extension("toml.tmp");
+        fs::write(&temp_path, content)
+            .with_context(|| format!("Failed to write team: {}", temp_path.display()))?;
+        fs::rename(&temp_path, &self.path)
+            .with_context(|| format!("Failed to write team: {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{MemberKind, TeamMember};
+    use tempfile::TempDir;
+
+    #[test]
+    fn store_round_trips_sorted_by_handle() {
+        let dir = TempDir::new().unwrap();
+        let store = TeamStore::for_project(dir.path());
+        assert!(store.read().unwrap().members.is_empty());
+
+        let mut bot = TeamMember::new("reviewer", MemberKind::Agent);
+        bot.aliases.push("review-bot".to_string());
+        let team = Team {
+            members: vec![bot, TeamMember::new("alice", MemberKind::Human)],
+        };
+        store.write(&team).unwrap();
+
+        let raw = fs::read_to_string(store.path()).unwrap();
+        assert!(raw.contains("[[member]]"));
+        let read = store.read().unwrap();
+        assert_eq!(read.members[0].handle, "alice");
+        assert_eq!(read.members[1].kind, MemberKind::Agent);
+        assert_eq!(read.members[1].aliases, vec!["review-bot"]);
+    }
+}
@@ -0,0 +1,255 @@
+//! Task templates
+//!
+//! Templates are stored as TOML files under `.shape/templates/tasks/`, one
+//! file per template (`code-review.toml`). Text fields may contain
+//! `{{name}}` placeholders that are filled in when a task is created from
+//! the template.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// A reusable task shape with parameterized fields
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskTemplate {
+    /// Task title, may contain `{{param}}` placeholders
+    pub title: String,
+
+    /// Task description, may contain `{{param}}` placeholders
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Labels applied to created tasks (stored in `meta.labels`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+
+    /// Estimate applied to created tasks (stored in `meta.estimate`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<i64>,
+}
+
+impl TaskTemplate {
+    /// Placeholder names used by the template, sorted
+    pub fn params(&self) -> Vec<String> {
+        let mut names = BTreeSet::new();
+        let fields = std::iter::once(self.title.as_str())
+            .chain(self.description.as_deref())
+            .chain(self.labels.iter().map(String::as_str));
+        for text in fields {
+            names.extend(placeholders(text));
+        }
+        names.into_iter().collect()
+    }
+
+    /// Returns a copy with every placeholder substituted
+    ///
+    /// Fails if a placeholder has no value.
+    pub fn render(&self, values: &HashMap<String, String>) -> Result<TaskTemplate> {
+        let missing: Vec<_> = self
+            .params()
+            .into_iter()
+            .filter(|p| !values.contains_key(p))
+            .collect();
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "Missing template parameter(s): {}. Pass them with --set {}=...",
+                missing.join(", "),
+                missing[0]
+            );
+        }
+
+        let fill = |text: &str| fill_placeholders(text, values);
+        Ok(TaskTemplate {
+            title: fill(&self.title),
+            description: self.description.as_deref().map(fill),
+            labels: self.labels.iter().map(|l| fill(l)).collect(),
+            estimate: self.estimate,
+        })
+    }
+}
+
+/// Names of the `{{name}}` placeholders in `text`, sorted
+pub fn placeholders(text: &str) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else { break };
+        names.insert(after[..end].trim().to_string());
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// Substitutes `{{name}}` placeholders; every name must have a value
+pub fn fill_placeholders(text: &str, values: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else { break };
+        out.push_str(&rest[..start]);
+        out.push_str(&values[after[..end].trim()]);
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Store for task templates
+pub struct TemplateStore {
+    dir: PathBuf,
+}
+
+impl TemplateStore {
+    /// Creates a store rooted at the given directory
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Creates the default task template store for a project
+    pub fn for_project(project_root: &Path) -> Self {
+        Self::new(project_root.join(".shape").join("templates").join("tasks"))
+    }
+
+    /// Returns the template directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            anyhow::bail!(
+                "Invalid template name '{}': use letters, digits, '-' and '_'",
+                name
+            );
+        }
+        Ok(self.dir.join(format!("{}.toml", name)))
+    }
+
+    /// Reads a template by name
+    pub fn read(&self, name: &str) -> Result<Option<TaskTemplate>> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read template: {}", path.display()))?;
+        let template = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse template: {}", path.display()))?;
+        Ok(Some(template))
+    }
+
+    /// Lists all templates, sorted by name
+    pub fn list(&self) -> Result<Vec<(String, TaskTemplate)>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut templates = Vec::new();
+        for entry in fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read directory: {}", self.dir.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if let Some(template) = self.read(name)? {
+                templates.push((name.to_string(), template));
+            }
+        }
+
+        templates.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(templates)
+    }
+
+    /// Writes a template, replacing any existing one with the same name
+    pub fn write(&self, name: &str, template: &TaskTemplate) -> Result<()> {
+        let path = self.path(name)?;
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create directory: {}", self.dir.display()))?;
+
+        let content = toml::to_string_pretty(template).context("Failed to serialize template")?;
+        let temp_path = path.with_extension("toml.tmp");
+        fs::write(&temp_path, content)
+            .with_context(|| format!("Failed to write template: {}", temp_path.display()))?;
+        fs::rename(&temp_path, &path)
+            .with_context(|| format!("Failed to write template: {}", path.display()))?;
+
+        Ok(())
+    }
+
+    /// Removes a template, returning whether it existed
+    pub fn remove(&self, name: &str) -> Result<bool> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            return Ok(false);
+        }
+        fs::remove_file(&path)
+            .with_context(|| format!("Failed to remove template: {}", path.display()))?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn review() -> TaskTemplate {
+        TaskTemplate {
+            title: "Review {{pr}}".to_string(),
+            description: Some("See PR #{{ pr }} by {{author}}".to_string()),
+            labels: vec!["review".to_string()],
+            estimate: Some(1),
+        }
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let template = review();
+        assert_eq!(template.params(), vec!["author", "pr"]);
+
+        let values = HashMap::from([
+            ("pr".to_string(), "412".to_string()),
+            ("author".to_string(), "sam".to_string()),
+        ]);
+        let rendered = template.render(&values).unwrap();
+        assert_eq!(rendered.title, "Review 412");
+        assert_eq!(rendered.description.as_deref(), Some("See PR #412 by sam"));
+        assert_eq!(rendered.labels, vec!["review"]);
+
+        let partial = HashMap::from([("pr".to_string(), "412".to_string())]);
+        let err = template.render(&partial).unwrap_err().to_string();
+        assert!(err.contains("author"));
+    }
+
+    #[test]
+    fn store_round_trips() {
+        let dir = TempDir::new().unwrap();
+        let store = TemplateStore::for_project(dir.path());
+        assert!(store.list().unwrap().is_empty());
+
+        store.write("code-review", &review()).unwrap();
+        assert!(dir
+            .path()
+            .join(".shape/templates/tasks/code-review.toml")
+            .exists());
+        assert_eq!(store.read("code-review").unwrap(), Some(review()));
+        assert_eq!(store.list().unwrap().len(), 1);
+
+        assert!(store.read("../escape").is_err());
+        assert!(store.remove("code-review").unwrap());
+        assert!(!store.remove("code-review").unwrap());
+    }
+}
@@ -0,0 +1,169 @@
+//! Brief translations
+//!
+//! Localized copies of briefs live under `.shape/briefs/i18n/`, one markdown
+//! file per brief and language (`b-7f2a3b1.fr.md`). The canonical brief stays
+//! authoritative: a translation records when its source was last updated, so
+//! it can be flagged as stale once the brief changes.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Brief, BriefId};
+
+/// A brief's title and body in another language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Translation {
+    /// Brief this translates
+    pub brief: BriefId,
+
+    /// Language code, e.g. `fr` or `pt-BR`
+    pub lang: String,
+
+    /// Translated title
+    pub title: String,
+
+    /// Plugin that produced the translation
+    pub via: String,
+
+    /// `updated_at` of the brief when it was translated
+    pub source_updated_at: DateTime<Utc>,
+
+    /// When the translation was made
+    pub translated_at: DateTime<Utc>,
+
+    /// Translated body
+    #[serde(skip)]
+    pub body: String,
+}
+
+impl Translation {
+    /// Returns true if the brief changed after it was translated
+    pub fn is_stale(&self, brief: &Brief) -> bool {
+        brief.updated_at > self.source_updated_at
+    }
+}
+
+/// Store for brief translations
+pub struct TranslationStore {
+    dir: PathBuf,
+}
+
+impl TranslationStore {
+    /// Creates a store rooted at the given directory
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Creates the default translation store for a project
+    pub fn for_project(project_root: &Path) -> Self {
+        Self::new(project_root.join(".shape").join("briefs").join("i18n"))
+    }
+
+    fn path(&self, id: &BriefId, lang: &str) -> Result<PathBuf> {
+        let valid = !lang.is_empty()
+            && lang
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            anyhow::bail!("Invalid language '{}': use a code like fr or pt-BR", lang);
+        }
+        Ok(self.dir.join(format!("{}.{}.md", id, lang)))
+    }
+
+    /// Reads the translation of a brief into `lang`
+    pub fn read(&self, id: &BriefId, lang: &str) -> Result<Option<Translation>> {
+        let path = self.path(id, lang)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read translation: {}", path.display()))?;
+        let parsed = content
+            .strip_prefix("---\n")
+            .and_then(|rest| rest.split_once("\n---\n"));
+        let Some((yaml, body)) = parsed else {
+            anyhow::bail!("Missing frontmatter in translation: {}", path.display());
+        };
+        let mut translation: Translation = serde_yaml::from_str(yaml)
+            .with_context(|| format!("Invalid translation frontmatter: {}", path.display()))?;
+        translation.body = body.strip_prefix('\n').unwrap_or(body).to_string();
+        Ok(Some(translation))
+    }
+
+    /// Writes a translation, replacing any earlier one for its language
+    pub fn write(&self, translation: &Translation) -> Result<PathBuf> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create directory: {}", self.dir.display()))?;
+        let path = self.path(&translation.brief, &translation.lang)?;
+        let yaml = serde_yaml::to_string(translation).context("Failed to serialize translation")?;
+        let mut content = format!("---\n{}---\n\n{}", yaml, translation.body);
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+
+        let temp_path = path.with_extension("md.tmp");
+        fs::write(&temp_path, &content)
+            .with_context(|| format!("Failed to write temp file: {}", temp_path.display()))?;
+        fs::rename(&temp_path, &path)
+            .with_context(|| format!("Failed to write translation: {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Languages a brief has been translated into, sorted
+    pub fn languages(&self, id: &BriefId) -> Result<Vec<String>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let prefix = format!("{}.", id);
+        let mut langs = Vec::new();
+        for entry in fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read directory: {}", self.dir.display()))?
+        {
+            let name = entry?.file_name();
+            let lang = name
+                .to_str()
+                .and_then(|n| n.strip_prefix(&prefix))
+                .and_then(|n| n.strip_suffix(".md"));
+            if let Some(lang) = lang {
+                langs.push(lang.to_string());
+            }
+        }
+        langs.sort();
+        Ok(langs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn write_and_read_translation() {
+        let dir = TempDir::new().unwrap();
+        let store = TranslationStore::new(dir.path());
+        let brief = Brief::new("Search", "minimal");
+        let translation = Translation {
+            brief: brief.id.clone(),
+            lang: "fr".to_string(),
+            title: "Recherche".to_string(),
+            via: "shape-translate-test".to_string(),
+            source_updated_at: brief.updated_at,
+            translated_at: Utc::now(),
+            body: "## Problème\n\nLent.\n".to_string(),
+        };
+        store.write(&translation).unwrap();
+
+        let read = store.read(&brief.id, "fr").unwrap().unwrap();
+        assert_eq!(read, translation);
+        assert!(!read.is_stale(&brief));
+        assert_eq!(store.languages(&brief.id).unwrap(), vec!["fr"]);
+        assert!(store.read(&brief.id, "de").unwrap().is_none());
+        assert!(store.read(&brief.id, "../fr").is_err());
+    }
+}
@@ -0,0 +1,425 @@
+//! Transactional sessions
+//!
+//! `shape tx begin` copies the task and brief stores into
+//! `.shape/.cache/tx/`. While the session is open, [`Project`](super::Project)
+//! hands out stores over those copies, so every command stages its changes
+//! there and the live files stay untouched. `shape tx commit` copies the
+//! changed files next to the live ones and then renames them into place one
+//! after another, quickly enough that the daemon's debounce folds them into a
+//! single change; `shape tx abort` throws them away.
+//!
+//! The session remembers a fingerprint of the live stores from when it
+//! began. If something else wrote to them since (a `git pull`, another
+//! checkout's daemon, a hand edit), commit refuses rather than overwrite it.
+//!
+//! A session belongs to whoever began it: `begin` returns a token, and only
+//! processes with that token in [`TOKEN_ENV`] (see
+//! [`Project::tx_token`](super::Project::tx_token)) stage into it or may commit or
+//! abort it. Everyone else keeps reading the live stores, and their task and
+//! brief writes fail with [`TxError::HeldElsewhere`] until the session closes,
+//! so a claim made outside the session can neither vanish on abort nor race
+//! a claim staged inside it. Milestones, bets and the other small stores are not staged: writes to
+//! them apply immediately.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::encryption::ENCRYPTION_FILE;
+
+const SESSION_FILE: &str = "session.json";
+
+/// Environment variable carrying the token of the session a process works in
+pub(super) const TOKEN_ENV: &str = "SHAPE_TX";
+
+/// Failures opening or closing a session
+#[derive(Debug, Error)]
+pub enum TxError {
+    #[error("A transaction is already open (started {0}). Commit or abort it first.")]
+    AlreadyOpen(DateTime<Utc>),
+
+    #[error("No transaction is open. Start one with 'shape tx begin'.")]
+    NotOpen,
+
+    #[error("Tasks or briefs changed outside the transaction since it began. Run 'shape tx abort' and redo the changes.")]
+    Conflict,
+
+    #[error("Another session's transaction (started {0}) is open; tasks and briefs can't be changed until it is committed or aborted.")]
+    HeldElsewhere(DateTime<Utc>),
+
+    #[error("The open transaction belongs to another session. Set SHAPE_TX to the token 'shape tx begin' printed, or discard it with 'shape tx abort --force'.")]
+    NotOwner,
+}
+
+/// An open session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxSession {
+    pub started_at: DateTime<Utc>,
+
+    /// Secret the owner passes in [`TOKEN_ENV`]: 16 random bytes, hex-encoded
+    #[serde(default)]
+    pub token: String,
+
+    /// Fingerprint of the live stores when the session began
+    pub base: String,
+}
+
+impl TxSession {
+    /// Returns true if `token` is this session's token
+    pub fn is_owned_by(&self, token: Option<&str>) -> bool {
+        !self.token.is_empty() && token == Some(self.token.as_str())
+    }
+}
+
+/// What a commit changed in the live stores
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TxCommit {
+    pub tasks_changed: bool,
+
+    /// Brief files written or changed
+    pub briefs_written: usize,
+
+    /// Brief files removed
+    pub briefs_removed: usize,
+}
+
+impl TxCommit {
+    /// Returns true if the session staged no change
+    pub fn is_empty(&self) -> bool {
+        !self.tasks_changed && self.briefs_written == 0 && self.briefs_removed == 0
+    }
+}
+
+/// Directory a session stages its stores in
+pub fn staging_dir(shape_dir: &Path) -> PathBuf {
+    shape_dir.join(".cache").join("tx")
+}
+
+/// Returns the staging directory if a session is open and `token` is its
+/// token
+pub fn joined(shape_dir: &Path, token: Option<&str>) -> Option<PathBuf> {
+    let session = current(shape_dir).ok().flatten()?;
+    session.is_owned_by(token).then(|| staging_dir(shape_dir))
+}
+
+/// Fails if a session is open over the live stores in `shape_dir`. Stores
+/// call this before writing: the owner's stores point into the staging
+/// directory, so a live write during a session comes from someone else.
+pub fn ensure_writable(shape_dir: &Path) -> Result<()> {
+    match current(shape_dir)? {
+        Some(session) => Err(TxError::HeldElsewhere(session.started_at).into()),
+        None => Ok(()),
+    }
+}
+
+/// Returns the open session, if any
+pub fn current(shape_dir: &Path) -> Result<Option<TxSession>> {
+    let path = staging_dir(shape_dir).join(SESSION_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let session = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(session))
+}
+
+/// Opens a session by copying the live stores into the staging directory
+pub fn begin(shape_dir: &Path) -> Result<TxSession> {
+    if let Some(session) = current(shape_dir)? {
+        return Err(TxError::AlreadyOpen(session.started_at).into());
+    }
+    let staging = staging_dir(shape_dir);
+    if staging.exists() {
+        // Left behind by a begin that didn't finish
+        fs::remove_dir_all(&staging)
+            .with_context(|| format!("Failed to remove {}", staging.display()))?;
+    }
+    fs::create_dir_all(staging.join("briefs"))
+        .with_context(|| format!("Failed to create {}", staging.display()))?;
+
+    copy_if_exists(&shape_dir.join("tasks.jsonl"), &staging.join("tasks.jsonl"))?;
+    copy_if_exists(
+        &shape_dir.join(ENCRYPTION_FILE),
+        &staging.join(ENCRYPTION_FILE),
+    )?;
+    // Staged writes keep counting from the live revision
+    copy_if_exists(
+        &shape_dir.join(".cache").join("revision"),
+        &staging.join(".cache").join("revision"),
+    )?;
+    for path in brief_files(&shape_dir.join("briefs"), true)? {
+        let name = path.file_name().expect("brief file name");
+        copy_if_exists(&path, &staging.join("briefs").join(name))?;
+    }
+
+    let mut token = [0u8; 16];
+    OsRng.fill_bytes(&mut token);
+    let session = TxSession {
+        started_at: Utc::now(),
+        token: token.iter().map(|b| format!("{:02x}", b)).collect(),
+        base: fingerprint(shape_dir)?,
+    };
+    // Written last: a session only exists once everything is staged
+    let path = staging.join(SESSION_FILE);
+    fs::write(&path, serde_json::to_string_pretty(&session)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(session)
+}
+
+/// Moves the staged stores over the live ones and closes the session
+pub fn commit(shape_dir: &Path, token: Option<&str>) -> Result<TxCommit> {
+    let Some(session) = current(shape_dir)? else {
+        return Err(TxError::NotOpen.into());
+    };
+    if !session.is_owned_by(token) {
+        return Err(TxError::NotOwner.into());
+    }
+    if fingerprint(shape_dir)? != session.base {
+        return Err(TxError::Conflict.into());
+    }
+    let staging = staging_dir(shape_dir);
+    let mut result = TxCommit::default();
+
+    // Everything is copied next to its target first and only renamed into
+    // place once all copies succeeded
+    let mut moves = Vec::new();
+    let staged_tasks = staging.join("tasks.jsonl");
+    let live_tasks = shape_dir.join("tasks.jsonl");
+    if staged_tasks.exists() && read_or_empty(&staged_tasks)? != read_or_empty(&live_tasks)? {
+        result.tasks_changed = true;
+        moves.push((staged_tasks, live_tasks));
+    }
+
+    let live_briefs = shape_dir.join("briefs");
+    let staged_files = brief_files(&staging.join("briefs"), true)?;
+    for staged in &staged_files {
+        let live = live_briefs.join(staged.file_name().expect("brief file name"));
+        if fs::read(staged)? == read_or_empty(&live)? {
+            continue;
+        }
+        if live.extension().is_some_and(|e| e == "md") {
+            result.briefs_written += 1;
+        }
+        moves.push((staged.clone(), live));
+    }
+    let removed: Vec<PathBuf> = brief_files(&live_briefs, false)?
+        .into_iter()
+        .filter(|live| {
+            !staged_files
+                .iter()
+                .any(|s| s.file_name() == live.file_name())
+        })
+        .collect();
+    result.briefs_removed = removed.len();
+
+    let mut pending = Vec::new();
+    for (from, to) in &moves {
+        let temp = to.with_extension("tx.tmp");
+        if let Some(dir) = to.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        if let Err(e) = fs::copy(from, &temp) {
+            for temp in &pending {
+                let _ = fs::remove_file(temp);
+            }
+            return Err(e).with_context(|| format!("Failed to stage {}", to.display()));
+        }
+        pending.push(temp);
+    }
+    for ((_, to), temp) in moves.iter().zip(&pending) {
+        fs::rename(temp, to).with_context(|| format!("Failed to write {}", to.display()))?;
+    }
+    for path in &removed {
+        fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    }
+
+    if !result.is_empty() {
+        super::revision::bump(shape_dir);
+    }
+    fs::remove_dir_all(&staging)
+        .with_context(|| format!("Failed to remove {}", staging.display()))?;
+    Ok(result)
+}
+
+/// Discards the staged changes and closes the session. `token` must be the
+/// session's unless `force` is set.
+pub fn abort(shape_dir: &Path, token: Option<&str>, force: bool) -> Result<()> {
+    let Some(session) = current(shape_dir)? else {
+        return Err(TxError::NotOpen.into());
+    };
+    if !force && !session.is_owned_by(token) {
+        return Err(TxError::NotOwner.into());
+    }
+    let staging = staging_dir(shape_dir);
+    fs::remove_dir_all(&staging).with_context(|| format!("Failed to remove {}", staging.display()))
+}
+
+/// Hash of the live task file and brief files
+fn fingerprint(shape_dir: &Path) -> Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&read_or_empty(&shape_dir.join("tasks.jsonl"))?);
+    for path in brief_files(&shape_dir.join("briefs"), false)? {
+        hasher.update(path.file_name().unwrap_or_default().as_encoded_bytes());
+        hasher.update(&[0]);
+        hasher.update(&fs::read(&path)?);
+    }
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// Top-level brief files in `dir`, sorted: the `.md` files and, with
+/// `with_index`, `index.jsonl`. Translations under `i18n/` aren't staged.
+fn brief_files(dir: &Path, with_index: bool) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in
+        fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()))?
+    {
+        let path = entry?.path();
+        let is_brief = path.extension().is_some_and(|e| e == "md");
+        let is_index = path.file_name().is_some_and(|n| n == "index.jsonl");
+        if path.is_file() && (is_brief || (with_index && is_index)) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn read_or_empty(path: &Path) -> Result<Vec<u8>> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(bytes),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+fn copy_if_exists(from: &Path, to: &Path) -> Result<()> {
+    if !from.exists() {
+        return Ok(());
+    }
+    if let Some(dir) = to.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::copy(from, to).with_context(|| format!("Failed to copy {}", from.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn is_open(shape_dir: &Path) -> bool {
+        staging_dir(shape_dir).join(SESSION_FILE).is_file()
+    }
+
+    fn shape_dir() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("briefs")).unwrap();
+        fs::write(dir.path().join("tasks.jsonl"), "{\"a\":1}\n").unwrap();
+        fs::write(dir.path().join("briefs/b-1.md"), "one\n").unwrap();
+        fs::write(dir.path().join("briefs/b-2.md"), "two\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn commit_applies_staged_changes() {
+        let dir = shape_dir();
+        let session = begin(dir.path()).unwrap();
+        assert!(is_open(dir.path()));
+        assert!(begin(dir.path()).is_err());
+        let token = Some(session.token.as_str());
+
+        let staging = staging_dir(dir.path());
+        fs::write(staging.join("tasks.jsonl"), "{\"a\":2}\n").unwrap();
+        fs::write(staging.join("briefs/b-3.md"), "three\n").unwrap();
+        fs::remove_file(staging.join("briefs/b-2.md")).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("tasks.jsonl")).unwrap(),
+            "{\"a\":1}\n"
+        );
+
+        let result = commit(dir.path(), token).unwrap();
+        assert_eq!(
+            result,
+            TxCommit {
+                tasks_changed: true,
+                briefs_written: 1,
+                briefs_removed: 1,
+            }
+        );
+        assert!(!is_open(dir.path()));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("tasks.jsonl")).unwrap(),
+            "{\"a\":2}\n"
+        );
+        assert!(dir.path().join("briefs/b-3.md").exists());
+        assert!(!dir.path().join("briefs/b-2.md").exists());
+    }
+
+    #[test]
+    fn abort_leaves_live_files_alone() {
+        let dir = shape_dir();
+        let session = begin(dir.path()).unwrap();
+        fs::write(staging_dir(dir.path()).join("tasks.jsonl"), "").unwrap();
+
+        abort(dir.path(), Some(&session.token), false).unwrap();
+        assert!(!staging_dir(dir.path()).exists());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("tasks.jsonl")).unwrap(),
+            "{\"a\":1}\n"
+        );
+        assert!(abort(dir.path(), Some(&session.token), false).is_err());
+    }
+
+    #[test]
+    fn commit_refuses_after_outside_changes() {
+        let dir = shape_dir();
+        let session = begin(dir.path()).unwrap();
+        fs::write(dir.path().join("briefs/b-1.md"), "edited\n").unwrap();
+
+        let err = commit(dir.path(), Some(&session.token)).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(TxError::Conflict)));
+        assert!(is_open(dir.path()));
+    }
+
+    #[test]
+    fn only_the_owner_joins_commits_or_aborts() {
+        let dir = shape_dir();
+        let session = begin(dir.path()).unwrap();
+        assert_eq!(session.token.len(), 32);
+        assert!(session.token.bytes().all(|b| b.is_ascii_hexdigit()));
+        let token = Some(session.token.as_str());
+        assert_eq!(joined(dir.path(), token), Some(staging_dir(dir.path())));
+        assert_eq!(joined(dir.path(), None), None);
+        assert_eq!(joined(dir.path(), Some("other")), None);
+
+        // Live stores are closed to everyone else while the session is open
+        let err = ensure_writable(dir.path()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(TxError::HeldElsewhere(_))
+        ));
+        assert!(ensure_writable(&staging_dir(dir.path())).is_ok());
+
+        let err = commit(dir.path(), Some("other")).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(TxError::NotOwner)));
+        let err = abort(dir.path(), None, false).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(TxError::NotOwner)));
+        assert!(is_open(dir.path()));
+
+        abort(dir.path(), None, true).unwrap();
+        assert!(!is_open(dir.path()));
+        assert!(ensure_writable(dir.path()).is_ok());
+    }
+}
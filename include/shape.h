@@ -0,0 +1,66 @@
+/*
+ * shape.h - C interface to shape projects
+ *
+ * Build the library with the `ffi` feature:
+ *
+ *   cargo rustc --release --lib --features ffi --crate-type cdylib
+ *
+ * Memory ownership:
+ *   - shape_open returns a handle owned by the caller; release it with
+ *     shape_close.
+ *   - Every char* returned by this library is owned by the caller and must
+ *     be released with shape_string_free (never with free).
+ *   - Strings passed in are borrowed for the duration of the call only.
+ *
+ * Errors:
+ *   Functions return NULL on failure. shape_last_error returns the message
+ *   for the most recent failure on the calling thread. Internal panics are
+ *   caught and reported the same way; they never abort the host.
+ *
+ * All strings are UTF-8. Results are JSON.
+ */
+
+#ifndef SHAPE_H
+#define SHAPE_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct ShapeHandle ShapeHandle;
+
+/* Opens the project at `path`. NULL on failure. */
+ShapeHandle *shape_open(const char *path);
+
+/* Releases a handle. NULL is a no-op. */
+void shape_close(ShapeHandle *handle);
+
+/* Ready tasks as a JSON array. NULL on failure. */
+char *shape_ready_json(const ShapeHandle *handle);
+
+/*
+ * Applies a JSON operation and returns the affected brief or task as JSON.
+ * NULL on failure.
+ *
+ *   {"op": "create_brief", "title": "...", "type": "minimal"}
+ *   {"op": "add_task", "title": "...", "parent": "b-7f2a3b1"}
+ *   {"op": "add_dependency", "id": "...", "depends_on": "..."}
+ *   {"op": "claim", "id": "...", "agent": "..."}
+ *   {"op": "unclaim", "id": "...", "agent": "..."}
+ *   {"op": "start", "id": "..."}
+ *   {"op": "complete", "id": "..."}
+ *   {"op": "add_note", "id": "...", "agent": "...", "text": "..."}
+ */
+char *shape_apply_json(const ShapeHandle *handle, const char *op_json);
+
+/* Last error on this thread, or NULL. Release with shape_string_free. */
+char *shape_last_error(void);
+
+/* Releases a string returned by this library. NULL is a no-op. */
+void shape_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SHAPE_H */
@@ -0,0 +1,41 @@
+"""Tests for the shape_api bindings."""
+
+import pytest
+
+import shape_api
+
+
+@pytest.fixture
+def project():
+    return shape_api.Project.temporary()
+
+
+def test_ready_follows_dependencies(project):
+    brief = project.create_brief("Search")
+    index = project.add_task("Build index", parent=brief["id"])
+    ui = project.add_task("Search UI", parent=brief["id"])
+    project.add_dependency(ui["id"], index["id"])
+
+    assert [t["id"] for t in project.ready()] == [index["id"]]
+
+    project.complete(index["id"])
+    assert [t["id"] for t in project.ready()] == [ui["id"]]
+
+
+def test_claim_and_note(project):
+    task = project.add_task("Standalone")
+
+    claimed = project.claim(task["id"], "agent-1")
+    assert claimed["claimed_by"] == "agent-1"
+    assert claimed["status"] == "in_progress"
+
+    with pytest.raises(shape_api.ShapeError):
+        project.claim(task["id"], "agent-2")
+
+    noted = project.add_note(task["id"], "agent-1", "Halfway there")
+    assert noted["notes"][0]["text"] == "Halfway there"
+
+
+def test_unknown_task_raises(project):
+    with pytest.raises(shape_api.ShapeError):
+        project.task("t-0000000")
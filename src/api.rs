@@ -0,0 +1,602 @@
+//! High-level library API
+//!
+//! [`ShapeApi`] wraps a [`Project`] and exposes the common workflows
+//! (query ready tasks, claim, complete, add notes) without having to stitch
+//! together stores and domain types by hand.
+//!
+//! ```
+//! use shape_cli::api::ShapeApi;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! // A throwaway project in a temporary directory, removed on drop
+//! let api = ShapeApi::temporary()?;
+//!
+//! let brief = api.create_brief("Search", "minimal")?;
+//! let index = api.add_task(Some(&brief.id.to_string()), "Build index")?;
+//! let ui = api.add_task(Some(&brief.id.to_string()), "Search UI")?;
+//! api.add_dependency(&ui.id, &index.id)?;
+//!
+//! let ready = api.ready()?;
+//! assert_eq!(ready.len(), 1);
+//!
+//! api.claim(&index.id, "agent-1")?;
+//! api.add_note(&index.id, "agent-1", "Using tantivy")?;
+//! api.complete(&index.id)?;
+//!
+//! assert_eq!(api.ready()?[0].id, ui.id);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::cli::freeze::{self, Override};
+use crate::cli::{gates, review, team, undo};
+use crate::domain::{Brief, BriefId, DependencyGraph, Task, TaskId, TaskStatus};
+use crate::storage::Project;
+
+/// A write operation, as accepted by [`ShapeApi::apply`].
+///
+/// Serialized with an `op` tag, e.g.
+/// `{"op": "claim", "id": "b-7f2a3b1.1", "agent": "agent-1"}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation {
+    CreateBrief {
+        title: String,
+        #[serde(default = "default_brief_type", rename = "type")]
+        brief_type: String,
+    },
+    AddTask {
+        title: String,
+        #[serde(default)]
+        parent: Option<String>,
+    },
+    AddDependency {
+        id: TaskId,
+        depends_on: TaskId,
+    },
+    Claim {
+        id: TaskId,
+        agent: String,
+    },
+    Unclaim {
+        id: TaskId,
+        agent: String,
+    },
+    Start {
+        id: TaskId,
+    },
+    Complete {
+        id: TaskId,
+    },
+    AddNote {
+        id: TaskId,
+        agent: String,
+        text: String,
+    },
+}
+
+fn default_brief_type() -> String {
+    "minimal".to_string()
+}
+
+/// High-level facade over a shape project.
+///
+/// Writes go through the same checks as the CLI: they refuse frozen tasks
+/// and tasks of frozen briefs unless an override is set with
+/// [`ShapeApi::with_override`] (or the process was started with
+/// `--override`; overrides are audited), and [`ShapeApi::complete`] refuses
+/// tasks with unmet completion gates or a missing review. Task writes are
+/// recorded for `shape undo`, and every write holds the daemon's write fence
+/// until it is done.
+pub struct ShapeApi {
+    project: Project,
+
+    /// Directory to remove on drop (set for temporary projects)
+    temp_root: Option<PathBuf>,
+
+    /// Reason for changing frozen entities anyway
+    override_reason: Option<String>,
+}
+
+impl ShapeApi {
+    /// Opens an existing project at `root`
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        Ok(Self {
+            project: Project::open(root)?,
+            temp_root: None,
+            override_reason: None,
+        })
+    }
+
+    /// Opens the project containing the current directory
+    pub fn open_current() -> Result<Self> {
+        Ok(Self {
+            project: Project::open_current()?,
+            temp_root: None,
+            override_reason: None,
+        })
+    }
+
+    /// Initializes (or re-opens) a project at `root`
+    pub fn init(root: impl Into<PathBuf>) -> Result<Self> {
+        Ok(Self {
+            project: Project::init(root)?,
+            temp_root: None,
+            override_reason: None,
+        })
+    }
+
+    /// Creates a project in a fresh temporary directory.
+    ///
+    /// The directory is deleted when the `ShapeApi` is dropped, which makes
+    /// this suitable for tests.
+    pub fn temporary() -> Result<Self> {
+        let nanos = Utc::now().timestamp_nanos_opt().unwrap_or_default();
+        let root =
+            std::env::temp_dir().join(format!("shape-api-{}-{:x}", std::process::id(), nanos));
+        fs::create_dir_all(&root)?;
+
+        Ok(Self {
+            project: Project::init(&root)?,
+            temp_root: Some(root),
+            override_reason: None,
+        })
+    }
+
+    /// Allows writes to frozen briefs and tasks, recording `reason` in
+    /// `.shape/audit.jsonl` for each one
+    pub fn with_override(mut self, reason: impl Into<String>) -> Self {
+        self.override_reason = Some(reason.into());
+        self
+    }
+
+    /// Returns the underlying project
+    pub fn project(&self) -> &Project {
+        &self.project
+    }
+
+    /// Returns the project root directory
+    pub fn root(&self) -> &Path {
+        self.project.root()
+    }
+
+    /// Returns all briefs, sorted by ID
+    pub fn briefs(&self) -> Result<Vec<Brief>> {
+        let mut briefs: Vec<_> = self
+            .project
+            .brief_store()
+            .read_all()?
+            .into_values()
+            .collect();
+        briefs.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(briefs)
+    }
+
+    /// Returns a brief by ID
+    pub fn brief(&self, id: &BriefId) -> Result<Brief> {
+        self.project
+            .brief_store()
+            .read(id)?
+            .ok_or_else(|| anyhow::anyhow!("Brief not found: {}", id))
+    }
+
+    /// Creates a brief with an empty body
+    pub fn create_brief(&self, title: &str, brief_type: &str) -> Result<Brief> {
+        let _fence = self.project.write_fence("create_brief")?;
+        let mut brief = Brief::with_scheme(title, brief_type, self.project.id_scheme());
+        brief.id = self.project.new_brief_id(title, brief_type)?;
+        self.project.brief_store().write(&brief)?;
+        Ok(brief)
+    }
+
+    /// Returns all tasks, sorted by ID
+    pub fn tasks(&self) -> Result<Vec<Task>> {
+        let mut tasks: Vec<_> = self
+            .project
+            .task_store()
+            .read_all()?
+            .into_values()
+            .collect();
+        tasks.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(tasks)
+    }
+
+    /// Returns a task by ID
+    pub fn task(&self, id: &TaskId) -> Result<Task> {
+        self.project
+            .task_store()
+            .read_all()?
+            .remove(id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))
+    }
+
+    /// Adds a task.
+    ///
+    /// `parent` may be a brief ID, a task ID (creates a subtask), or `None`
+    /// for a standalone task.
+    pub fn add_task(&self, parent: Option<&str>, title: &str) -> Result<Task> {
+        undo::journaled_in(&self.project, "add_task", || {
+            self.append_task(parent, title)
+        })
+    }
+
+    fn append_task(&self, parent: Option<&str>, title: &str) -> Result<Task> {
+        let store = self.project.task_store();
+
+        let id = match parent {
+            None => self.project.new_standalone_task_id(title)?,
+            Some(parent) if parent.contains('.') || parent.starts_with("t-") => {
+                let parent_id: TaskId = parent.parse()?;
+                if let Some(parent) = store.read_all()?.get(&parent_id) {
+                    freeze::ensure_task_mutable_with(
+                        &self.project,
+                        parent,
+                        self.override_for("add_task").as_ref(),
+                    )?;
+                }
+                store.next_subtask_id(&parent_id)?
+            }
+            Some(parent) => {
+                let brief_id: BriefId = parent.parse()?;
+                let brief = self
+                    .project
+                    .brief_store()
+                    .read(&brief_id)?
+                    .ok_or_else(|| anyhow::anyhow!("Brief not found: {}", brief_id))?;
+                freeze::ensure_brief_mutable_with(
+                    &self.project,
+                    &brief,
+                    self.override_for("add_task").as_ref(),
+                )?;
+                store.next_task_id(&brief_id)?
+            }
+        };
+
+        let task = Task::new(id, title);
+        store.append(&task)?;
+        Ok(task)
+    }
+
+    /// Adds a blocking dependency: `task` waits for `depends_on`
+    pub fn add_dependency(&self, task: &TaskId, depends_on: &TaskId) -> Result<Task> {
+        let tasks = self.project.task_store().read_all()?;
+        if !tasks.contains_key(depends_on) {
+            anyhow::bail!("Dependency task not found: {}", depends_on);
+        }
+
+        let mut graph = DependencyGraph::from_tasks(tasks.values())?;
+        graph.add_dependency(task, depends_on)?;
+
+        self.modify(task, "add_dependency", |t| {
+            t.add_dependency(depends_on.clone());
+            Ok(())
+        })
+    }
+
+    /// Returns tasks whose blocking dependencies are all complete, sorted by ID
+    pub fn ready(&self) -> Result<Vec<Task>> {
+        let tasks = self.project.task_store().read_all()?;
+        let statuses: HashMap<TaskId, TaskStatus> =
+            tasks.iter().map(|(id, t)| (id.clone(), t.status)).collect();
+
+        let mut ready: Vec<_> = tasks
+            .into_values()
+            .filter(|t| t.is_ready(&statuses))
+            .collect();
+        ready.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(ready)
+    }
+
+    /// Claims a task for `agent` and marks it in progress.
+    ///
+    /// Fails if another agent holds an unexpired claim. Re-claiming by the
+    /// same agent refreshes the claim.
+    pub fn claim(&self, id: &TaskId, agent: &str) -> Result<Task> {
+        let config = &self.project.config().project.agent;
+        let timeout = config.claim_timeout_hours;
+
+        self.modify(id, "claim", |task| {
+            if !config.may_claim(task, agent) {
+                anyhow::bail!("Task {} requires a human", task.id);
+            }
+            if let Some(holder) = &task.claimed_by {
+                if holder == agent {
+                    task.claimed_at = Some(Utc::now());
+                    return Ok(());
+                }
+                if !task.is_claim_expired(timeout) {
+                    anyhow::bail!("Task {} is claimed by \"{}\"", task.id, holder);
+                }
+            }
+            task.claim(agent);
+            Ok(())
+        })
+    }
+
+    /// Releases a claim
+    pub fn unclaim(&self, id: &TaskId, agent: &str) -> Result<Task> {
+        self.modify(id, "unclaim", |task| {
+            task.unclaim(Some(agent));
+            Ok(())
+        })
+    }
+
+    /// Marks a task as in progress
+    pub fn start(&self, id: &TaskId) -> Result<Task> {
+        self.modify(id, "start", |task| {
+            task.start();
+            Ok(())
+        })
+    }
+
+    /// Marks a task as done.
+    ///
+    /// Fails, like `shape task done`, if the task needs an approving review
+    /// or doesn't pass its completion gates.
+    pub fn complete(&self, id: &TaskId) -> Result<Task> {
+        self.modify(id, "complete", |task| {
+            review::ensure_approved(&self.project, task)?;
+            if !gates::unmet(&self.project, task)?.is_empty() {
+                return Err(gates::refusal(&self.project, task)?.into());
+            }
+            task.complete();
+            Ok(())
+        })
+    }
+
+    /// Adds a note to a task. `@handle` mentions are checked against the
+    /// team directory and fire the `mention` hook.
+    pub fn add_note(&self, id: &TaskId, agent: &str, text: &str) -> Result<Task> {
+        let mentioned = team::check_mentions(&self.project, text)?;
+        let task = self.modify(id, "add_note", |task| {
+            task.add_note(agent, text);
+            Ok(())
+        })?;
+        team::notify_mentions(&self.project, &task, agent, &mentioned, text);
+        Ok(task)
+    }
+
+    /// Applies an [`Operation`] and returns the affected brief or task as JSON
+    pub fn apply(&self, op: Operation) -> Result<serde_json::Value> {
+        let value = match op {
+            Operation::CreateBrief { title, brief_type } => {
+                serde_json::to_value(self.create_brief(&title, &brief_type)?)?
+            }
+            Operation::AddTask { title, parent } => {
+                serde_json::to_value(self.add_task(parent.as_deref(), &title)?)?
+            }
+            Operation::AddDependency { id, depends_on } => {
+                serde_json::to_value(self.add_dependency(&id, &depends_on)?)?
+            }
+            Operation::Claim { id, agent } => serde_json::to_value(self.claim(&id, &agent)?)?,
+            Operation::Unclaim { id, agent } => serde_json::to_value(self.unclaim(&id, &agent)?)?,
+            Operation::Start { id } => serde_json::to_value(self.start(&id)?)?,
+            Operation::Complete { id } => serde_json::to_value(self.complete(&id)?)?,
+            Operation::AddNote { id, agent, text } => {
+                serde_json::to_value(self.add_note(&id, &agent, &text)?)?
+            }
+        };
+        Ok(value)
+    }
+
+    /// The override for `action`, if one was set
+    fn override_for(&self, action: &str) -> Option<Override> {
+        self.override_reason.as_ref().map(|reason| Override {
+            reason: reason.clone(),
+            command: action.to_string(),
+        })
+    }
+
+    /// Applies a change to a single task and persists it, journaled as
+    /// `action`.
+    ///
+    /// Fails if the task or its brief is frozen, unless overridden.
+    fn modify(
+        &self,
+        id: &TaskId,
+        action: &str,
+        f: impl FnOnce(&mut Task) -> Result<()>,
+    ) -> Result<Task> {
+        undo::journaled_in(&self.project, action, || {
+            // Checks and change run under the store's write lock, so two
+            // clients can't both pass a check such as a claim conflict
+            self.project.task_store().modify(|tasks| {
+                let task = tasks
+                    .get_mut(id)
+                    .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+
+                freeze::ensure_task_mutable_with(
+                    &self.project,
+                    task,
+                    self.override_for(action).as_ref(),
+                )?;
+                f(task)?;
+                Ok(task.clone())
+            })
+        })
+    }
+}
+
+impl Drop for ShapeApi {
+    fn drop(&mut self) {
+        if let Some(root) = &self.temp_root {
+            let _ = fs::remove_dir_all(root);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temporary_project_is_removed_on_drop() {
+        let api = ShapeApi::temporary().unwrap();
+        let root = api.root().to_path_buf();
+        assert!(root.join(".shape").is_dir());
+
+        drop(api);
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn add_task_allocates_sequential_ids() {
+        let api = ShapeApi::temporary().unwrap();
+        let brief = api.create_brief("Brief", "minimal").unwrap();
+        let brief_id = brief.id.to_string();
+
+        let first = api.add_task(Some(&brief_id), "First").unwrap();
+        let second = api.add_task(Some(&brief_id), "Second").unwrap();
+        let sub = api.add_task(Some(&first.id.to_string()), "Sub").unwrap();
+        let standalone = api.add_task(None, "Standalone").unwrap();
+
+        assert_eq!(first.id.to_string(), format!("{}.1", brief_id));
+        assert_eq!(second.id.to_string(), format!("{}.2", brief_id));
+        assert_eq!(sub.id.to_string(), format!("{}.1.1", brief_id));
+        assert!(standalone.is_standalone());
+        assert_eq!(api.tasks().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn claim_conflicts_with_other_agent() {
+        let api = ShapeApi::temporary().unwrap();
+        let task = api.add_task(None, "Contended").unwrap();
+
+        let claimed = api.claim(&task.id, "agent-1").unwrap();
+        assert_eq!(claimed.claimed_by.as_deref(), Some("agent-1"));
+        assert_eq!(claimed.status, TaskStatus::InProgress);
+
+        assert!(api.claim(&task.id, "agent-2").is_err());
+        assert!(api.claim(&task.id, "agent-1").is_ok());
+    }
+
+    #[test]
+    fn concurrent_claims_have_one_winner() {
+        let api = ShapeApi::temporary().unwrap();
+        let agents = 8;
+        for round in 0..20 {
+            let task = api.add_task(None, &format!("Contested {}", round)).unwrap();
+            let start = std::sync::Barrier::new(agents);
+            let winners = std::thread::scope(|s| {
+                let claims: Vec<_> = (0..agents)
+                    .map(|i| {
+                        let (root, id, start) = (api.root(), &task.id, &start);
+                        s.spawn(move || {
+                            let api = ShapeApi::open(root).unwrap();
+                            start.wait();
+                            api.claim(id, &format!("agent-{}", i)).is_ok()
+                        })
+                    })
+                    .collect();
+                claims
+                    .into_iter()
+                    .filter_map(|c| c.join().unwrap().then_some(()))
+                    .count()
+            });
+            assert_eq!(winners, 1, "round {}", round);
+        }
+    }
+
+    #[test]
+    fn agents_cannot_claim_tasks_requiring_a_human() {
+        let api = ShapeApi::temporary().unwrap();
+        let task = api.add_task(None, "Sign the contract").unwrap();
+        api.modify(&task.id, "meta", |t| {
+            t.set_meta(crate::domain::REQUIRES_HUMAN_META_KEY, true);
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(api.claim(&task.id, "agent-1").is_err());
+    }
+
+    #[test]
+    fn apply_parses_tagged_operations() {
+        let api = ShapeApi::temporary().unwrap();
+        let task = api.add_task(None, "Task").unwrap();
+
+        let op: Operation = serde_json::from_value(serde_json::json!({
+            "op": "add_note",
+            "id": task.id.to_string(),
+            "agent": "agent-1",
+            "text": "Done soon",
+        }))
+        .unwrap();
+        let result = api.apply(op).unwrap();
+
+        assert_eq!(result["notes"][0]["text"], "Done soon");
+    }
+
+    #[test]
+    fn dependency_cycles_are_rejected() {
+        let api = ShapeApi::temporary().unwrap();
+        let a = api.add_task(None, "A").unwrap();
+        let b = api.add_task(None, "B").unwrap();
+
+        api.add_dependency(&b.id, &a.id).unwrap();
+        assert!(api.add_dependency(&a.id, &b.id).is_err());
+    }
+
+    #[test]
+    fn frozen_tasks_refuse_writes_unless_overridden() {
+        let api = ShapeApi::temporary().unwrap();
+        let task = api.add_task(None, "Frozen").unwrap();
+        let mut frozen = api.task(&task.id).unwrap();
+        frozen.freeze("release candidate", "lead");
+        api.project().task_store().update(&frozen).unwrap();
+
+        let err = api.complete(&task.id).unwrap_err();
+        assert!(format!("{:#}", err).contains("frozen"));
+        assert_eq!(api.task(&task.id).unwrap().status, TaskStatus::Todo);
+
+        let api = api.with_override("hotfix");
+        assert_eq!(api.complete(&task.id).unwrap().status, TaskStatus::Done);
+        let audit = api.project().audit_log().unwrap();
+        assert_eq!(audit[0].action, "complete");
+        assert_eq!(audit[0].reason, "hotfix");
+    }
+
+    #[test]
+    fn complete_checks_reviews_and_gates() {
+        let temp = ShapeApi::temporary().unwrap();
+        let config = temp.root().join(".shape/config.toml");
+        let mut content = fs::read_to_string(&config).unwrap();
+        content.push_str("\n[reviews]\nrequire_approval = true\n\n[gates]\ndefault = [\"note\"]\n");
+        fs::write(&config, content).unwrap();
+        let api = ShapeApi::open(temp.root()).unwrap();
+
+        let task = api.add_task(None, "Reviewed").unwrap();
+        api.modify(&task.id, "meta", |t| {
+            t.set_meta("labels", serde_json::json!(["needs-review"]));
+            Ok(())
+        })
+        .unwrap();
+        let err = api.complete(&task.id).unwrap_err();
+        assert!(format!("{:#}", err).contains("approving review"));
+
+        let task = api.add_task(None, "Gated").unwrap();
+        let err = api.complete(&task.id).unwrap_err();
+        assert!(format!("{:#}", err).contains("completion gates"));
+        api.add_note(&task.id, "agent-1", "Done and tested")
+            .unwrap();
+        assert_eq!(api.complete(&task.id).unwrap().status, TaskStatus::Done);
+    }
+
+    #[test]
+    fn frozen_briefs_refuse_new_tasks() {
+        let api = ShapeApi::temporary().unwrap();
+        let mut brief = api.create_brief("Frozen", "minimal").unwrap();
+        brief.freeze("scope locked", "lead");
+        api.project().brief_store().write(&brief).unwrap();
+
+        assert!(api.add_task(Some(&brief.id.to_string()), "Late").is_err());
+    }
+}
@@ -0,0 +1,393 @@
+//! Adopting existing markdown documents as briefs
+//!
+//! `shape init --adopt docs/rfcs/` turns a folder of RFCs or design docs into
+//! briefs. Frontmatter is kept when the documents have it; otherwise the
+//! title, status, date and author are read from the first heading and
+//! `Status:`-style header lines. Originals are copied by default, or moved
+//! with `git mv` so their history follows them.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+use crate::domain::{Brief, BriefStatus};
+use crate::storage::Project;
+
+/// Brief metadata key recording the document a brief was adopted from
+pub(super) const ADOPTED_FROM_KEY: &str = "adopted_from";
+
+/// Header lines searched for `Status:`, `Date:` and `Author:` fields
+const HEADER_LINES: usize = 25;
+
+/// What happens to the original documents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum AdoptMode {
+    /// Leave the originals in place
+    Copy,
+    /// Move them under `.shape/briefs/` (via `git mv` when possible)
+    Move,
+    /// Move them and leave a symlink at the old path
+    Symlink,
+}
+
+/// A document that became a brief
+pub(super) struct Adopted {
+    pub source: String,
+    pub brief: Brief,
+}
+
+/// Fields inferred from a document
+#[derive(Debug, Default, PartialEq)]
+struct DocInfo {
+    title: Option<String>,
+    status: Option<BriefStatus>,
+    date: Option<NaiveDate>,
+    owner: Option<String>,
+    meta: Vec<(String, serde_json::Value)>,
+    body: String,
+}
+
+/// Creates briefs from the markdown files under `dir`
+///
+/// Files already adopted (matched on their original path) are skipped, so
+/// adopting the same folder twice is harmless.
+pub(super) fn adopt(
+    project: &Project,
+    dir: &Path,
+    brief_type: Option<&str>,
+    mode: AdoptMode,
+) -> Result<Vec<Adopted>> {
+    if !dir.is_dir() {
+        bail!("Not a directory: {}", dir.display());
+    }
+    #[cfg(not(unix))]
+    if mode == AdoptMode::Symlink {
+        bail!("--symlink is only supported on Unix");
+    }
+
+    let store = project.brief_store();
+    let root = project
+        .root()
+        .canonicalize()
+        .unwrap_or_else(|_| project.root().to_path_buf());
+    let brief_type = brief_type.unwrap_or(project.config().project.default_brief_type.as_str());
+
+    let already: Vec<String> = store
+        .read_all()?
+        .values()
+        .filter_map(|b| b.get_meta(ADOPTED_FROM_KEY))
+        .filter_map(|v| v.as_str().map(str::to_string))
+        .collect();
+
+    let mut files = Vec::new();
+    collect_markdown(dir, &mut files)?;
+    files.sort();
+
+    let mut adopted = Vec::new();
+    for path in files {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+        if canonical.starts_with(root.join(".shape")) {
+            continue;
+        }
+        let source = canonical
+            .strip_prefix(&root)
+            .unwrap_or(&canonical)
+            .display()
+            .to_string();
+        if already.contains(&source) {
+            continue;
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let info = infer(&content);
+
+        let title = info
+            .title
+            .clone()
+            .unwrap_or_else(|| title_from_file_name(&path));
+        let mut brief = Brief::with_scheme(&title, brief_type, project.id_scheme());
+        brief.id = project.new_brief_id(&title, brief_type)?;
+        brief.set_body(info.body.trim());
+        for (key, value) in info.meta {
+            brief.set_meta(key, value);
+        }
+        brief.set_meta(ADOPTED_FROM_KEY, source.clone());
+        if let Some(status) = info.status {
+            brief.status = status;
+        }
+        if info.owner.is_some() {
+            brief.owner = info.owner;
+        }
+        let created = info
+            .date
+            .or_else(|| date_from_file_name(&path))
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|dt| Utc.from_utc_datetime(&dt))
+            .or_else(|| modified_at(&path));
+        if let Some(created) = created {
+            brief.created_at = created;
+        }
+
+        if mode != AdoptMode::Copy {
+            let briefs_dir = root.join(".shape").join("briefs");
+            let dest = briefs_dir.join(format!("{}.md", brief.id));
+            fs::create_dir_all(&briefs_dir)?;
+            move_file(&root, &canonical, &dest)?;
+            #[cfg(unix)]
+            if mode == AdoptMode::Symlink {
+                let target = relative_path(canonical.parent().unwrap_or(&root), &dest);
+                std::os::unix::fs::symlink(&target, &canonical).with_context(|| {
+                    format!("Failed to create symlink at {}", canonical.display())
+                })?;
+            }
+        }
+        store.write(&brief)?;
+
+        adopted.push(Adopted { source, brief });
+    }
+
+    Ok(adopted)
+}
+
+fn collect_markdown(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in
+        fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()))?
+    {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_markdown(&path, files)?;
+        } else if name.ends_with(".md") && name != "readme.md" && !path.is_symlink() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Moves a file with `git mv` so history follows it, falling back to a
+/// plain rename outside git or for untracked files
+fn move_file(root: &Path, from: &Path, to: &Path) -> Result<()> {
+    let moved = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["mv", "--"])
+        .arg(from)
+        .arg(to)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    if !moved {
+        fs::rename(from, to)
+            .with_context(|| format!("Failed to move {} to {}", from.display(), to.display()))?;
+    }
+    Ok(())
+}
+
+/// Path to `to` relative to the directory `from`
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<Component> = from.components().collect();
+    let to_parts: Vec<Component> = to.components().collect();
+    let common = from
+        .iter()
+        .zip(&to_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut path = PathBuf::new();
+    for _ in common..from.len() {
+        path.push("..");
+    }
+    for part in &to_parts[common..] {
+        path.push(part);
+    }
+    path
+}
+
+fn modified_at(path: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(DateTime::<Utc>::from)
+}
+
+/// Infers brief fields from a document's frontmatter or header lines
+fn infer(content: &str) -> DocInfo {
+    let mut info = DocInfo::default();
+    let mut body = content;
+
+    if let Some(rest) = content.strip_prefix("---\n") {
+        if let Some(end) = rest.find("\n---") {
+            let yaml = &rest[..end];
+            body = rest[end + 4..].trim_start_matches(['-', '\n']);
+            if let Ok(serde_yaml::Value::Mapping(map)) = serde_yaml::from_str(yaml) {
+                for (key, value) in map {
+                    let Some(key) = key.as_str().map(str::to_lowercase) else {
+                        continue;
+                    };
+                    let text = value.as_str().map(str::to_string);
+                    match key.as_str() {
+                        "title" => info.title = text,
+                        "status" | "state" => info.status = text.as_deref().and_then(parse_status),
+                        "date" | "created" | "created_at" => {
+                            info.date = text.as_deref().and_then(parse_date)
+                        }
+                        "author" | "owner" => info.owner = text,
+                        _ => {
+                            if let Ok(json) = serde_json::to_value(&value) {
+                                info.meta.push((key, json));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    for line in body.lines().take(HEADER_LINES) {
+        let trimmed = line.trim();
+        if info.title.is_none() {
+            if let Some(heading) = trimmed.strip_prefix("# ") {
+                info.title = Some(heading.trim().to_string());
+                continue;
+            }
+        }
+        let Some((key, value)) = header_field(trimmed) else {
+            continue;
+        };
+        match key.as_str() {
+            "status" | "state" if info.status.is_none() => info.status = parse_status(&value),
+            "date" | "created" | "start date" if info.date.is_none() => {
+                info.date = parse_date(&value)
+            }
+            "author" | "authors" | "owner" if info.owner.is_none() => {
+                info.owner = value.split(',').next().map(|s| s.trim().to_string())
+            }
+            _ => {}
+        }
+    }
+
+    info.body = body.to_string();
+    info
+}
+
+/// Parses `Status: Draft`, `- **Status**: Draft` or `| Status | Draft |`
+fn header_field(line: &str) -> Option<(String, String)> {
+    let line = line.trim_start_matches(['-', '*', '>', ' ']);
+    let (key, value) = if line.starts_with('|') {
+        let cells: Vec<&str> = line.trim_matches('|').split('|').collect();
+        if cells.len() != 2 {
+            return None;
+        }
+        (cells[0], cells[1])
+    } else {
+        line.split_once(':')?
+    };
+    let key = key.trim().trim_matches('*').trim().to_lowercase();
+    let value = value.trim().trim_matches('*').trim().to_string();
+    (!key.is_empty() && key.len() <= 12 && !value.is_empty()).then_some((key, value))
+}
+
+/// Maps common RFC status words onto brief statuses
+fn parse_status(value: &str) -> Option<BriefStatus> {
+    let value = value.trim().to_lowercase();
+    match value.as_str() {
+        "draft" | "proposed" | "review" | "in review" | "discussion" => Some(BriefStatus::Proposed),
+        "accepted" | "approved" | "active" | "in progress" | "implementing" => {
+            Some(BriefStatus::InProgress)
+        }
+        "implemented" | "done" | "final" | "complete" | "completed" | "shipped" => {
+            Some(BriefStatus::Shipped)
+        }
+        "rejected" | "withdrawn" | "superseded" | "deprecated" | "obsolete" | "archived" => {
+            Some(BriefStatus::Archived)
+        }
+        other => other.replace(' ', "_").parse().ok(),
+    }
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    value
+        .get(..10)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+}
+
+/// Dates like `2024-03-01-auth.md`
+fn date_from_file_name(path: &Path) -> Option<NaiveDate> {
+    parse_date(&path.file_stem()?.to_string_lossy())
+}
+
+/// `0012-auth-flow.md` → "Auth flow"
+fn title_from_file_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let words = stem
+        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '-' || c == '_')
+        .replace(['-', '_'], " ");
+    let mut chars = words.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => stem,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infers_from_header_lines() {
+        let doc = "# Auth Flow\n\n- **Status**: Accepted\n- Date: 2024-03-01\n- Author: ana, bo\n\n## Summary\n";
+        let info = infer(doc);
+
+        assert_eq!(info.title.as_deref(), Some("Auth Flow"));
+        assert_eq!(info.status, Some(BriefStatus::InProgress));
+        assert_eq!(info.date, NaiveDate::from_ymd_opt(2024, 3, 1));
+        assert_eq!(info.owner.as_deref(), Some("ana"));
+        assert!(info.body.starts_with("# Auth Flow"));
+    }
+
+    #[test]
+    fn infers_from_frontmatter() {
+        let doc = "---\ntitle: Caching\nstatus: rejected\nrfc: 42\n---\n\nBody text\n";
+        let info = infer(doc);
+
+        assert_eq!(info.title.as_deref(), Some("Caching"));
+        assert_eq!(info.status, Some(BriefStatus::Archived));
+        assert_eq!(info.meta, vec![("rfc".to_string(), serde_json::json!(42))]);
+        assert_eq!(info.body.trim(), "Body text");
+    }
+
+    #[test]
+    fn title_and_date_from_file_name() {
+        let path = Path::new("docs/0012-auth-flow.md");
+        assert_eq!(title_from_file_name(path), "Auth flow");
+
+        let dated = Path::new("2024-03-01-cache.md");
+        assert_eq!(
+            date_from_file_name(dated),
+            NaiveDate::from_ymd_opt(2024, 3, 1)
+        );
+    }
+
+    #[test]
+    fn relative_symlink_target() {
+        let target = relative_path(
+            Path::new("/repo/docs/rfcs"),
+            Path::new("/repo/.shape/briefs/b-1234567.md"),
+        );
+        assert_eq!(target, PathBuf::from("../../.shape/briefs/b-1234567.md"));
+    }
+}
@@ -0,0 +1,130 @@
+//! Affected command - maps changed files to the tasks that reference them
+//!
+//! Compares the working branch against a base ref (or an explicit file list)
+//! and reports, through [`Output::diagnostics`]:
+//! - changed files linked to open tasks (notice)
+//! - changed files whose only linked tasks are already done (warning)
+//! - changed files not linked to any task (notice)
+
+use std::collections::BTreeMap;
+use std::process::Command;
+
+use anyhow::{Context, Result};
+
+use super::output::{Diagnostic, Output, OutputFormat, Severity, DIAGNOSTIC_FORMATS};
+use crate::domain::{LinkType, Task};
+use crate::storage::Project;
+
+/// Checks reported by `shape affected`
+const CHECKS: &[&str] = &["linked", "done-task", "unlinked"];
+
+/// Formats `shape affected` supports beyond text and JSON
+pub(super) const FORMATS: &[OutputFormat] = DIAGNOSTIC_FORMATS;
+
+/// Run the affected command
+pub fn run(output: &Output, base: &str, files: &[String]) -> Result<()> {
+    let project = Project::open_current()?;
+
+    let changed = if files.is_empty() {
+        changed_files(&project, base)?
+    } else {
+        files.iter().map(|f| normalize(f)).collect()
+    };
+
+    output.verbose_ctx(
+        "affected",
+        &format!("{} changed file(s) against {}", changed.len(), base),
+    );
+
+    let tasks = project.task_store().read_all()?;
+
+    // file -> tasks linking it
+    let mut by_file: BTreeMap<String, Vec<&Task>> = BTreeMap::new();
+    for task in tasks.values() {
+        for link in task.links.iter().filter(|l| l.link_type == LinkType::File) {
+            by_file
+                .entry(normalize(&link.reference))
+                .or_default()
+                .push(task);
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+    for file in &changed {
+        let mut linked = by_file.get(file).cloned().unwrap_or_default();
+        linked.sort_by(|a, b| a.id.cmp(&b.id));
+
+        if linked.is_empty() {
+            diagnostics.push(
+                Diagnostic::new(
+                    Severity::Notice,
+                    "unlinked",
+                    "Changed file is not linked to any task",
+                )
+                .at(file.clone(), None),
+            );
+            continue;
+        }
+
+        let open: Vec<_> = linked.iter().filter(|t| !t.status.is_complete()).collect();
+
+        if open.is_empty() {
+            let ids: Vec<_> = linked.iter().map(|t| t.id.to_string()).collect();
+            diagnostics.push(
+                Diagnostic::new(
+                    Severity::Warning,
+                    "done-task",
+                    format!(
+                        "Changed file is only linked to completed task(s): {}",
+                        ids.join(", ")
+                    ),
+                )
+                .at(file.clone(), None)
+                .with_id(ids[0].clone()),
+            );
+        } else {
+            for task in open {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Notice,
+                        "linked",
+                        format!("Affects {} ({}): {}", task.id, task.status, task.title),
+                    )
+                    .at(file.clone(), None)
+                    .with_id(task.id.to_string()),
+                );
+            }
+        }
+    }
+
+    output.diagnostics("affected", CHECKS, &diagnostics);
+    Ok(())
+}
+
+/// Lists files changed between `base` and HEAD, relative to the project root
+fn changed_files(project: &Project, base: &str) -> Result<Vec<String>> {
+    let range = format!("{}...HEAD", base);
+    let result = Command::new("git")
+        .args(["diff", "--name-only", "--relative", &range])
+        .current_dir(project.root())
+        .output()
+        .context("Failed to run git diff")?;
+
+    if !result.status.success() {
+        anyhow::bail!(
+            "git diff against {} failed: {}",
+            base,
+            String::from_utf8_lossy(&result.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&result.stdout)
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(normalize)
+        .collect())
+}
+
+fn normalize(path: &str) -> String {
+    path.trim().trim_start_matches("./").to_string()
+}
@@ -13,18 +13,29 @@
 //! 1. Check `shape next` before claiming to see unclaimed tasks
 //! 2. Use brief-specific filtering to reduce contention
 //! 3. Monitor `shape claimed` to see active claims
+//! 4. Lock the paths they change (`shape lock acquire`) so `next` steers
+//!    others elsewhere
 
 use std::collections::HashMap;
 
 use anyhow::Result;
-use chrono::Utc;
-use clap::Subcommand;
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand};
 
+use super::duration::parse_since;
+use super::error::{CliError, ErrorCode};
+use super::freeze::ensure_task_mutable;
+use super::lock_cmd;
 use super::output::Output;
+use super::page::PageArgs;
+use super::team;
+use super::undo;
 use crate::domain::{
-    BriefId, DependencyGraph, HistoryEventType, LinkType, Task, TaskId, TaskStatus,
+    by_id, effective_priorities, mentions, rank_cmp, AppetiteBudget, Brief, BriefId, Dependency,
+    DependencyGraph, HistoryEvent, HistoryEventType, Interests, LinkType, Note, Priority,
+    ResourceLock, Task, TaskId, TaskStatus,
 };
-use crate::storage::Project;
+use crate::storage::{AuditEntry, LockConflicts, Project};
 
 /// Agent subcommands
 #[derive(Subcommand)]
@@ -45,6 +56,11 @@ pub enum AgentCommands {
         /// Reason for force-claiming (required when --force is used)
         #[arg(long, required_if_eq("force", "true"))]
         reason: Option<String>,
+
+        /// Let an agent claim a task that requires a human; only [agent]
+        /// operators may, and it is recorded in .shape/audit.jsonl
+        #[arg(long, value_name = "REASON")]
+        allow_agent: Option<String>,
     },
 
     /// Release a claim on a task
@@ -65,6 +81,11 @@ pub enum AgentCommands {
         /// Number of suggestions (default: 1)
         #[arg(short, long, default_value = "1")]
         n: usize,
+
+        /// Labels to prefer, `-label` to skip tasks carrying it (default:
+        /// the agent's interests in the team directory)
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+        interests: Option<Vec<String>>,
     },
 
     /// Add a note to a task
@@ -141,8 +162,15 @@ pub enum AgentCommands {
 
     /// Show task history/timeline
     History {
-        /// Task or brief ID
-        id: String,
+        /// Task ID (omit with --all)
+        #[arg(required_unless_present = "all")]
+        id: Option<String>,
+
+        #[command(flatten)]
+        filter: HistoryArgs,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Show project or brief summary
@@ -151,6 +179,21 @@ pub enum AgentCommands {
         id: Option<String>,
     },
 
+    /// Show my work: claimed, assigned, watched and recently completed tasks
+    Mine {
+        /// Agent name (defaults to $SHAPE_AGENT or $USER)
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Days of activity and completed work to include
+        #[arg(long, default_value = "7")]
+        days: u32,
+
+        /// List notes and briefs that @mention me instead
+        #[arg(long)]
+        mentions: bool,
+    },
+
     /// Hand off a task to another agent or human
     Handoff {
         /// Task ID
@@ -176,17 +219,59 @@ pub enum AgentCommands {
     },
 }
 
+impl AgentCommands {
+    /// Name the command is journaled under for `shape undo`, or `None` for
+    /// commands that don't change tasks
+    fn journal_name(&self) -> Option<&'static str> {
+        Some(match self {
+            AgentCommands::Claimed
+            | AgentCommands::Next { .. }
+            | AgentCommands::History { .. }
+            | AgentCommands::Summary { .. }
+            | AgentCommands::Mine { .. }
+            | AgentCommands::Find { .. } => return None,
+            AgentCommands::Claim { .. } => "claim",
+            AgentCommands::Unclaim { .. } => "unclaim",
+            AgentCommands::Note { .. } => "note",
+            AgentCommands::Link { .. } => "link",
+            AgentCommands::Unlink { .. } => "unlink",
+            AgentCommands::Block { .. } => "block",
+            AgentCommands::Unblock { .. } => "unblock",
+            AgentCommands::Handoff { .. } => "handoff",
+        })
+    }
+}
+
 pub fn run(cmd: AgentCommands, output: &Output) -> Result<()> {
+    match cmd.journal_name() {
+        Some(name) => undo::journaled(name, || execute(cmd, output)),
+        None => execute(cmd, output),
+    }
+}
+
+fn execute(cmd: AgentCommands, output: &Output) -> Result<()> {
     match cmd {
         AgentCommands::Claim {
             id,
             agent,
             force,
             reason,
-        } => claim_task(output, &id, agent.as_deref(), force, reason.as_deref()),
+            allow_agent,
+        } => claim_task(
+            output,
+            &id,
+            agent.as_deref(),
+            force,
+            reason.as_deref(),
+            allow_agent.as_deref(),
+        ),
         AgentCommands::Unclaim { id } => unclaim_task(output, &id),
         AgentCommands::Claimed => list_claimed(output),
-        AgentCommands::Next { brief, n } => next_task(output, brief.as_deref(), n),
+        AgentCommands::Next {
+            brief,
+            n,
+            interests,
+        } => next_task(output, brief.as_deref(), n, interests),
         AgentCommands::Note { id, text } => add_note(output, &id, &text),
         AgentCommands::Link {
             id,
@@ -208,14 +293,71 @@ pub fn run(cmd: AgentCommands, output: &Output) -> Result<()> {
             on_task,
         } => block_task(output, &id, &reason, on_task.as_deref()),
         AgentCommands::Unblock { id } => unblock_task(output, &id),
-        AgentCommands::History { id } => show_history(output, &id),
+        AgentCommands::History { id, filter, page } => {
+            show_history(output, id.as_deref(), &filter, &page)
+        }
         AgentCommands::Summary { id } => show_summary(output, id.as_deref()),
+        AgentCommands::Mine {
+            agent,
+            days,
+            mentions,
+        } => {
+            if mentions {
+                show_mentions(output, agent.as_deref(), days)
+            } else {
+                show_mine(output, agent.as_deref(), days)
+            }
+        }
         AgentCommands::Handoff { id, reason, to } => handoff_task(output, &id, &reason, to),
         AgentCommands::Find { commit, file } => find_by_link(output, commit, file),
     }
 }
 
-fn get_agent_name(project: &Project, override_name: Option<&str>) -> String {
+/// Filters for `shape history`
+#[derive(Args, Debug, Clone, Default)]
+pub struct HistoryArgs {
+    /// Show events across all tasks, oldest first
+    #[arg(long)]
+    pub all: bool,
+
+    /// Only events since a duration ago (e.g. 24h, 7d) or a date
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Only events by this agent
+    #[arg(long)]
+    pub agent: Option<String>,
+
+    /// Only these event types (e.g. completed,claimed)
+    #[arg(long, value_delimiter = ',')]
+    pub event: Vec<String>,
+}
+
+impl HistoryArgs {
+    /// Builds a predicate matching events against the filters
+    fn matcher(&self) -> Result<impl Fn(&HistoryEvent) -> bool + '_> {
+        let since = self.since.as_deref().map(parse_since).transpose()?;
+        let events = self
+            .event
+            .iter()
+            .map(|e| {
+                serde_json::from_value::<HistoryEventType>(serde_json::Value::String(e.clone()))
+                    .map_err(|_| anyhow::anyhow!("Unknown history event: {}", e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(move |event: &HistoryEvent| {
+            since.is_none_or(|since| event.at >= since)
+                && self
+                    .agent
+                    .as_deref()
+                    .is_none_or(|agent| event.by.as_deref() == Some(agent))
+                && (events.is_empty() || events.contains(&event.event))
+        })
+    }
+}
+
+pub(super) fn get_agent_name(project: &Project, override_name: Option<&str>) -> String {
     if let Some(name) = override_name {
         return name.to_string();
     }
@@ -226,6 +368,76 @@ fn get_claim_timeout(project: &Project) -> u32 {
     project.config().project.agent.claim_timeout_hours
 }
 
+/// Records where newly created tasks came from.
+///
+/// When the current agent holds exactly one active (unexpired, unfinished)
+/// claim, each created task gets a provenance dependency on the claimed task
+/// and the claimed task gets a note listing what was spawned. With no claim,
+/// or several, nothing is linked. Returns the claimed task when linked.
+fn link_spawned_tasks(
+    project: &Project,
+    tasks: &mut HashMap<TaskId, Task>,
+    created: &mut [Task],
+) -> Option<TaskId> {
+    if !project.config().project.agent.auto_provenance || created.is_empty() {
+        return None;
+    }
+
+    let agent = get_agent_name(project, None);
+    let timeout_hours = get_claim_timeout(project);
+    let mut claims = tasks.values().filter(|t| {
+        t.claimed_by.as_deref() == Some(agent.as_str())
+            && t.status != TaskStatus::Done
+            && !t.is_claim_expired(timeout_hours)
+    });
+    let parent_id = match (claims.next(), claims.next()) {
+        (Some(parent), None) => parent.id.clone(),
+        _ => return None,
+    };
+
+    let mut spawned = Vec::new();
+    for task in created.iter_mut().filter(|t| t.id != parent_id) {
+        task.add_typed_dependency(Dependency::provenance(parent_id.clone()));
+        spawned.push(task.id.to_string());
+    }
+    if spawned.is_empty() {
+        return None;
+    }
+
+    let parent = tasks.get_mut(&parent_id)?;
+    parent.add_note(&agent, format!("Spawned {}", spawned.join(", ")));
+    Some(parent_id)
+}
+
+/// Saves a newly created task, linking it to the agent's active claim
+pub(super) fn save_new_task(project: &Project, task: &mut Task) -> Result<Option<TaskId>> {
+    let mut tasks = project.task_store().read_all()?;
+    save_new_tasks(project, &mut tasks, std::slice::from_mut(task))
+}
+
+/// Saves newly created tasks in one write, linking them to the agent's active
+/// claim. `tasks` is the current store contents.
+pub(super) fn save_new_tasks(
+    project: &Project,
+    tasks: &mut HashMap<TaskId, Task>,
+    created: &mut [Task],
+) -> Result<Option<TaskId>> {
+    let store = project.task_store();
+
+    match link_spawned_tasks(project, tasks, created) {
+        Some(parent) => {
+            let mut changed = vec![tasks[&parent].clone()];
+            changed.extend(created.iter().cloned());
+            store.update_batch(&changed)?;
+            Ok(Some(parent))
+        }
+        None => {
+            store.append_batch(created)?;
+            Ok(None)
+        }
+    }
+}
+
 /// Claims a task for an agent.
 ///
 /// Note: This uses optimistic concurrency - if two agents claim simultaneously,
@@ -238,18 +450,21 @@ fn claim_task(
     agent_override: Option<&str>,
     force: bool,
     force_reason: Option<&str>,
+    allow_agent: Option<&str>,
 ) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.task_store();
     let agent = get_agent_name(&project, agent_override);
     let timeout_hours = get_claim_timeout(&project);
 
-    let id: TaskId = id_str.parse()?;
+    let id = project.resolve_task_id(id_str)?;
     let mut tasks = store.read_all()?;
 
     let task = tasks
         .get_mut(&id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
+    let allowed_by = ensure_may_claim(&project, task, &agent, allow_agent)?;
 
     // Check if already claimed
     if let Some(ref claimed_by) = task.claimed_by {
@@ -275,12 +490,14 @@ fn claim_task(
 
         if !force && !expired {
             let remaining = task.claim_remaining_hours(timeout_hours).unwrap_or(0.0);
-            anyhow::bail!(
-                "Task {} is claimed by \"{}\" (expires in {:.1}h)\nUse --force --reason \"...\" to override",
-                id,
-                claimed_by,
-                remaining
-            );
+            return Err(CliError::new(
+                ErrorCode::ClaimConflict,
+                format!(
+                    "Task {} is claimed by \"{}\" (expires in {:.1}h)\nUse --force --reason \"...\" to override",
+                    id, claimed_by, remaining
+                ),
+            )
+            .into());
         }
 
         // If force claiming, add a note explaining why
@@ -295,6 +512,15 @@ fn claim_task(
 
     task.claim(&agent);
     store.update(task)?;
+    if let (Some(operator), Some(reason)) = (allowed_by, allow_agent) {
+        project.audit(&AuditEntry {
+            at: Utc::now(),
+            by: operator,
+            id: id.to_string(),
+            action: format!("claim --allow-agent (for {})", agent),
+            reason: reason.to_string(),
+        })?;
+    }
 
     if output.is_json() {
         output.data(&serde_json::json!({
@@ -309,20 +535,60 @@ fn claim_task(
     Ok(())
 }
 
+/// Refuses a claim on a task that requires a human by an identity that
+/// isn't one, unless an operator allows it. Returns the operator if one did.
+fn ensure_may_claim(
+    project: &Project,
+    task: &Task,
+    agent: &str,
+    allow_agent: Option<&str>,
+) -> Result<Option<String>> {
+    let config = &project.config().project.agent;
+    if config.may_claim(task, agent) {
+        return Ok(None);
+    }
+    if allow_agent.is_none() {
+        return Err(CliError::new(
+            ErrorCode::HumanRequired,
+            format!(
+                "Task {} requires a human and \"{}\" is not listed under [agent] humans\n\
+                 A human can claim it, or an operator can run: shape claim {} --agent {} --allow-agent \"...\"",
+                task.id, agent, task.id, agent
+            ),
+        )
+        .into());
+    }
+    let operator = config.effective_name();
+    if !config.is_operator(&operator) {
+        return Err(CliError::new(
+            ErrorCode::HumanRequired,
+            format!(
+                "Only [agent] operators may use --allow-agent; \"{}\" is not one",
+                operator
+            ),
+        )
+        .into());
+    }
+    Ok(Some(operator))
+}
+
 fn unclaim_task(output: &Output, id_str: &str) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.task_store();
     let agent = get_agent_name(&project, None);
 
-    let id: TaskId = id_str.parse()?;
+    let id = project.resolve_task_id(id_str)?;
     let mut tasks = store.read_all()?;
 
     let task = tasks
         .get_mut(&id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
 
     if task.claimed_by.is_none() {
-        anyhow::bail!("Task {} is not claimed", id);
+        return Err(
+            CliError::new(ErrorCode::Conflict, format!("Task {} is not claimed", id)).into(),
+        );
     }
 
     task.unclaim(Some(&agent));
@@ -346,7 +612,10 @@ fn list_claimed(output: &Output) -> Result<()> {
     let tasks = store.read_all()?;
     let timeout_hours = get_claim_timeout(&project);
 
-    let claimed: Vec<_> = tasks.values().filter(|t| t.is_claimed()).collect();
+    let claimed: Vec<_> = by_id(tasks.values())
+        .into_iter()
+        .filter(|t| t.is_claimed())
+        .collect();
 
     if output.is_json() {
         let items: Vec<_> = claimed
@@ -397,26 +666,68 @@ struct TaskScore {
     task_id: TaskId,
     title: String,
     brief_id: Option<BriefId>,
-    priority_score: f64,
+    priority: Priority,
+    /// Higher-priority task this one blocks, when its priority is inherited
+    inherited_from: Option<TaskId>,
     unblocks_count: usize,
     age_days: i64,
     estimate: Option<i64>,
+    due_at: Option<DateTime<Utc>>,
+    overdue: bool,
+    /// The agent's wanted labels the task carries
+    interests: Vec<String>,
     total_score: f64,
+    /// Other agents' locks overlapping the task's scope
+    locks: Vec<ResourceLock>,
 }
 
-fn next_task(output: &Output, brief_filter: Option<&str>, n: usize) -> Result<()> {
+fn next_task(
+    output: &Output,
+    brief_filter: Option<&str>,
+    n: usize,
+    interests: Option<Vec<String>>,
+) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.task_store();
     let tasks = store.read_all()?;
     let timeout_hours = get_claim_timeout(&project);
     let agent = get_agent_name(&project, None);
 
+    // Flags win over the interest profile in the team directory
+    let interests = match interests {
+        Some(entries) => Interests::parse(&entries),
+        None => project
+            .team_store()
+            .read()?
+            .find(&agent)
+            .map(|m| Interests::parse(&m.interests))
+            .unwrap_or_default(),
+    };
+
     // Build status map
     let statuses: HashMap<TaskId, TaskStatus> =
         tasks.iter().map(|(id, t)| (id.clone(), t.status)).collect();
 
     // Filter to brief if specified
-    let brief_id: Option<BriefId> = brief_filter.map(|s| s.parse()).transpose()?;
+    let brief_id = brief_filter
+        .map(|s| project.resolve_brief_id(s))
+        .transpose()?;
+
+    // Other agents' locks; tasks touching them are held back
+    let locks: Vec<ResourceLock> = project
+        .resource_lock_store()
+        .read_all()?
+        .into_iter()
+        .filter(|lock| lock_cmd::is_active(lock, &tasks))
+        .collect();
+    let lock_policy = project.config().project.agent.lock_conflicts;
+
+    // Blockers of higher-priority tasks inherit their priority
+    let effective = if project.config().project.agent.priority_inheritance {
+        effective_priorities(&tasks)
+    } else {
+        HashMap::new()
+    };
 
     // Find tasks that other tasks depend on (to calculate unblocks count)
     let mut unblocks_map: HashMap<TaskId, usize> = HashMap::new();
@@ -427,6 +738,7 @@ fn next_task(output: &Output, brief_filter: Option<&str>, n: usize) -> Result<()
     }
 
     // Score each ready task
+    let now = Utc::now();
     let mut scored: Vec<TaskScore> = tasks
         .values()
         .filter(|t| {
@@ -437,7 +749,10 @@ fn next_task(output: &Output, brief_filter: Option<&str>, n: usize) -> Result<()
                 }
             }
             // Must be ready for agent (not blocked, not claimed by others)
+            // and claimable by it
             t.is_ready_for_agent(&statuses, Some(&agent))
+                && project.config().project.agent.may_claim(t, &agent)
+                && !interests.disclaims(&t.labels())
         })
         .filter(|t| {
             // Exclude tasks with expired claims from others
@@ -449,52 +764,81 @@ fn next_task(output: &Output, brief_filter: Option<&str>, n: usize) -> Result<()
             true
         })
         .map(|t| {
-            // Priority score (high=3, medium=2, low=1, none=1)
-            let priority_score = t
-                .get_meta("priority")
-                .and_then(|v| v.as_str())
-                .map(|p| match p {
-                    "high" => 3.0,
-                    "medium" => 2.0,
-                    "low" => 1.0,
-                    _ => 1.0,
-                })
-                .unwrap_or(1.0);
+            // Priority (high=3, medium=2, low=1, none=1), raised to that of
+            // the tasks it blocks when inheritance is on
+            let (priority, inherited_from) = match effective.get(&t.id) {
+                Some(e) => (e.priority, e.inherited_from.clone()),
+                None => (Priority::of(t), None),
+            };
+            let priority_score = priority.score();
 
             // How many tasks does this unblock?
             let unblocks_count = unblocks_map.get(&t.id).copied().unwrap_or(0);
 
             // Age in days
-            let age_days = (Utc::now() - t.created_at).num_days();
+            let age_days = (now - t.created_at).num_days();
 
             // Estimate (smaller is better for quick wins)
             let estimate = t.get_meta("estimate").and_then(|v| v.as_i64());
 
+            // Matching an interest outweighs everything else combined, so
+            // specialized agents take their own labels first
+            let matching: Vec<String> = interests
+                .matching(&t.labels())
+                .into_iter()
+                .map(str::to_string)
+                .collect();
+            let interest_bonus = if matching.is_empty() { 0.0 } else { 100.0 };
+
             // Calculate total score
             // Formula: priority * 10 + unblocks * 5 + age_factor + quick_win_bonus
+            //          + due_bonus + interest_bonus
             let age_factor = (age_days as f64).min(30.0) / 30.0 * 5.0; // Max 5 points for age
             let quick_win_bonus = estimate
                 .map(|e| if e <= 2 { 3.0 } else { 0.0 })
                 .unwrap_or(0.0);
+            let overdue = t.is_overdue(now);
+            let due_bonus = due_bonus(t.due_at, now);
 
-            let total_score =
-                priority_score * 10.0 + unblocks_count as f64 * 5.0 + age_factor + quick_win_bonus;
+            let total_score = priority_score * 10.0
+                + unblocks_count as f64 * 5.0
+                + age_factor
+                + quick_win_bonus
+                + due_bonus
+                + interest_bonus;
 
             TaskScore {
+                locks: lock_cmd::conflicts(t, &locks, &agent)
+                    .into_iter()
+                    .cloned()
+                    .collect(),
                 task_id: t.id.clone(),
                 title: t.title.clone(),
                 brief_id: t.brief_id(),
-                priority_score,
+                priority,
+                inherited_from,
                 unblocks_count,
                 age_days,
                 estimate,
+                due_at: t.due_at,
+                overdue,
+                interests: matching,
                 total_score,
             }
         })
+        .filter(|s| s.locks.is_empty() || lock_policy != LockConflicts::Filter)
         .collect();
 
-    // Sort by score (highest first)
-    scored.sort_by(|a, b| b.total_score.partial_cmp(&a.total_score).unwrap());
+    // Sort by score (highest first), tasks touching locked resources last;
+    // ties follow the brief's backlog rank
+    scored.sort_by(|a, b| {
+        a.locks
+            .is_empty()
+            .cmp(&b.locks.is_empty())
+            .reverse()
+            .then_with(|| b.total_score.partial_cmp(&a.total_score).unwrap())
+            .then_with(|| rank_cmp(&tasks[&a.task_id], &tasks[&b.task_id]))
+    });
 
     // Take top N
     let recommendations: Vec<_> = scored.into_iter().take(n).collect();
@@ -507,15 +851,19 @@ fn next_task(output: &Output, brief_filter: Option<&str>, n: usize) -> Result<()
                     "id": s.task_id.to_string(),
                     "title": s.title,
                     "brief_id": s.brief_id.as_ref().map(|b| b.to_string()),
-                    "priority": match s.priority_score as i32 {
-                        3 => "high",
-                        2 => "medium",
-                        _ => "low",
-                    },
+                    "priority": s.priority.as_str(),
+                    "inherited_from": s.inherited_from.as_ref().map(|id| id.to_string()),
                     "unblocks": s.unblocks_count,
                     "age_days": s.age_days,
                     "estimate": s.estimate,
+                    "due_at": s.due_at,
+                    "overdue": s.overdue,
+                    "interests": s.interests,
                     "score": s.total_score,
+                    "locked_by": s.locks.iter().map(|l| serde_json::json!({
+                        "resource": l.resource,
+                        "agent": l.agent,
+                    })).collect::<Vec<_>>(),
                 })
             })
             .collect();
@@ -538,19 +886,29 @@ fn next_task(output: &Output, brief_filter: Option<&str>, n: usize) -> Result<()
         if let Some(ref brief) = first.brief_id {
             println!("  Brief: {}", brief);
         }
-        println!(
-            "  Priority: {}",
-            match first.priority_score as i32 {
-                3 => "high",
-                2 => "medium",
-                _ => "low",
-            }
-        );
+        match &first.inherited_from {
+            Some(from) => println!(
+                "  Priority: {} (inherited from {})",
+                first.priority.as_str(),
+                from
+            ),
+            None => println!("  Priority: {}", first.priority.as_str()),
+        }
         if first.unblocks_count > 0 {
             println!("  Unblocks: {} tasks", first.unblocks_count);
         }
         println!("  Age: {} days", first.age_days);
+        if let Some(due) = first.due_at {
+            let overdue = if first.overdue { " (overdue)" } else { "" };
+            println!("  Due: {}{}", due.format("%Y-%m-%d %H:%M"), overdue);
+        }
+        if !first.interests.is_empty() {
+            println!("  Interests: {}", first.interests.join(", "));
+        }
         println!("  Score: {:.2}", first.total_score);
+        for lock in &first.locks {
+            println!("  Locked: {} by {}", lock.resource, lock.agent);
+        }
         println!();
         println!("Run: shape claim {}", first.task_id);
 
@@ -572,26 +930,44 @@ fn next_task(output: &Output, brief_filter: Option<&str>, n: usize) -> Result<()
     Ok(())
 }
 
+/// Points for time pressure: up to 10 as the due date approaches over the
+/// last week, 15 once it has passed
+fn due_bonus(due_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> f64 {
+    let Some(due) = due_at else {
+        return 0.0;
+    };
+    let hours_left = (due - now).num_hours() as f64;
+    if hours_left < 0.0 {
+        15.0
+    } else {
+        ((168.0 - hours_left) / 168.0 * 10.0).max(0.0)
+    }
+}
+
 fn add_note(output: &Output, id_str: &str, text: &str) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.task_store();
     let agent = get_agent_name(&project, None);
 
-    let id: TaskId = id_str.parse()?;
+    let id = project.resolve_task_id(id_str)?;
     let mut tasks = store.read_all()?;
 
     let task = tasks
         .get_mut(&id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
+    let mentioned = team::check_mentions(&project, text)?;
 
     task.add_note(&agent, text);
     store.update(task)?;
+    team::notify_mentions(&project, task, &agent, &mentioned, text);
 
     if output.is_json() {
         output.data(&serde_json::json!({
             "id": task.id.to_string(),
             "note_added": true,
             "note_count": task.notes.len(),
+            "mentions": mentioned,
         }));
     } else {
         output.success(&format!("Added note to {}", task.id));
@@ -612,12 +988,13 @@ fn add_link(
     let store = project.task_store();
     let agent = get_agent_name(&project, None);
 
-    let id: TaskId = id_str.parse()?;
+    let id = project.resolve_task_id(id_str)?;
     let mut tasks = store.read_all()?;
 
     let task = tasks
         .get_mut(&id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
 
     let mut links_added = Vec::new();
 
@@ -668,12 +1045,13 @@ fn remove_link(
     let store = project.task_store();
     let agent = get_agent_name(&project, None);
 
-    let id: TaskId = id_str.parse()?;
+    let id = project.resolve_task_id(id_str)?;
     let mut tasks = store.read_all()?;
 
     let task = tasks
         .get_mut(&id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
 
     let mut links_removed = Vec::new();
 
@@ -730,21 +1108,24 @@ fn block_task(
     let store = project.task_store();
     let agent = get_agent_name(&project, None);
 
-    let id: TaskId = id_str.parse()?;
-    let on_task: Option<TaskId> = on_task_str.map(|s| s.parse()).transpose()?;
+    let id = project.resolve_task_id(id_str)?;
+    let on_task = on_task_str
+        .map(|s| project.resolve_task_id(s))
+        .transpose()?;
 
     let mut tasks = store.read_all()?;
 
     // Verify on_task exists if specified
     if let Some(ref on_id) = on_task {
         if !tasks.contains_key(on_id) {
-            anyhow::bail!("Task not found: {}", on_id);
+            return Err(CliError::not_found("Task", on_id).into());
         }
     }
 
     let task = tasks
         .get_mut(&id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
 
     task.block(reason, &agent, on_task.clone());
     store.update(task)?;
@@ -769,15 +1150,18 @@ fn unblock_task(output: &Output, id_str: &str) -> Result<()> {
     let store = project.task_store();
     let agent = get_agent_name(&project, None);
 
-    let id: TaskId = id_str.parse()?;
+    let id = project.resolve_task_id(id_str)?;
     let mut tasks = store.read_all()?;
 
     let task = tasks
         .get_mut(&id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
 
     if task.blocked.is_none() {
-        anyhow::bail!("Task {} is not blocked", id);
+        return Err(
+            CliError::new(ErrorCode::Conflict, format!("Task {} is not blocked", id)).into(),
+        );
     }
 
     task.unblock(Some(&agent));
@@ -795,33 +1179,49 @@ fn unblock_task(output: &Output, id_str: &str) -> Result<()> {
     Ok(())
 }
 
-fn show_history(output: &Output, id_str: &str) -> Result<()> {
+fn show_history(
+    output: &Output,
+    id_str: Option<&str>,
+    filter: &HistoryArgs,
+    paging: &PageArgs,
+) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.task_store();
-
-    let id: TaskId = id_str.parse()?;
     let tasks = store.read_all()?;
 
+    let matches = filter.matcher()?;
+
+    let Some(id_str) = id_str.filter(|_| !filter.all) else {
+        return show_all_history(output, &tasks, &matches, paging);
+    };
+
+    let id = project.resolve_task_id(id_str)?;
     let task = tasks
         .get(&id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+
+    let page = paging.paginate(task.history.iter().filter(|e| matches(e)).collect())?;
 
     if output.is_json() {
-        output.data(&serde_json::json!({
+        let mut data = serde_json::json!({
             "id": task.id.to_string(),
             "title": task.title,
-            "history": task.history,
-        }));
+            "history": page.items,
+        });
+        if page.is_paged() {
+            data["next_page_token"] = serde_json::json!(page.next_token());
+        }
+        output.data(&data);
     } else {
         println!();
         println!("Task: {} \"{}\"", task.id, task.title);
         println!();
         println!("Timeline:");
 
-        if task.history.is_empty() {
+        if page.items.is_empty() {
             println!("  (no history recorded)");
         } else {
-            for event in &task.history {
+            for event in &page.items {
                 let time = event.at.format("%Y-%m-%d %H:%M");
                 let by = event
                     .by
@@ -829,93 +1229,9 @@ fn show_history(output: &Output, id_str: &str) -> Result<()> {
                     .map(|s| format!(" by {}", s))
                     .unwrap_or_default();
 
-                let event_str = match &event.event {
-                    HistoryEventType::Created => "created".to_string(),
-                    HistoryEventType::Started => "started".to_string(),
-                    HistoryEventType::Completed => "completed".to_string(),
-                    HistoryEventType::Reopened => "reopened".to_string(),
-                    HistoryEventType::Claimed => "claimed".to_string(),
-                    HistoryEventType::Unclaimed => "unclaimed".to_string(),
-                    HistoryEventType::Note => {
-                        let text = event
-                            .data
-                            .as_ref()
-                            .and_then(|d| d.get("text"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("");
-                        format!("note: \"{}\"", text)
-                    }
-                    HistoryEventType::Linked => {
-                        let link_type = event
-                            .data
-                            .as_ref()
-                            .and_then(|d| d.get("type"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("?");
-                        let reference = event
-                            .data
-                            .as_ref()
-                            .and_then(|d| d.get("ref"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("?");
-                        format!("linked {}:{}", link_type, reference)
-                    }
-                    HistoryEventType::Unlinked => {
-                        let link_type = event
-                            .data
-                            .as_ref()
-                            .and_then(|d| d.get("type"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("?");
-                        let reference = event
-                            .data
-                            .as_ref()
-                            .and_then(|d| d.get("ref"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("?");
-                        format!("unlinked {}:{}", link_type, reference)
-                    }
-                    HistoryEventType::Blocked => {
-                        let reason = event
-                            .data
-                            .as_ref()
-                            .and_then(|d| d.get("reason"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("?");
-                        format!("blocked: \"{}\"", reason)
-                    }
-                    HistoryEventType::Unblocked => "unblocked".to_string(),
-                    HistoryEventType::Assigned => {
-                        let to = event
-                            .data
-                            .as_ref()
-                            .and_then(|d| d.get("to"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("?");
-                        format!("assigned to {}", to)
-                    }
-                    HistoryEventType::Handoff => {
-                        let reason = event
-                            .data
-                            .as_ref()
-                            .and_then(|d| d.get("reason"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("?");
-                        let to = event
-                            .data
-                            .as_ref()
-                            .and_then(|d| d.get("to"))
-                            .and_then(|v| v.as_str());
-                        if let Some(to_agent) = to {
-                            format!("handoff to {}: \"{}\"", to_agent, reason)
-                        } else {
-                            format!("handoff: \"{}\"", reason)
-                        }
-                    }
-                };
-
-                println!("  {}  {}{}", time, event_str, by);
+                println!("  {}  {}{}", time, describe_event(event), by);
             }
+            page.print_hint();
         }
 
         // Also show notes if any
@@ -941,6 +1257,217 @@ fn show_history(output: &Output, id_str: &str) -> Result<()> {
     Ok(())
 }
 
+/// Shows history events across all tasks, oldest first
+fn show_all_history(
+    output: &Output,
+    tasks: &HashMap<TaskId, Task>,
+    matches: &dyn Fn(&HistoryEvent) -> bool,
+    paging: &PageArgs,
+) -> Result<()> {
+    let mut events: Vec<(&Task, &HistoryEvent)> = tasks
+        .values()
+        .flat_map(|t| t.history.iter().map(move |e| (t, e)))
+        .filter(|(_, e)| matches(e))
+        .collect();
+    events.sort_by(|(ta, a), (tb, b)| a.at.cmp(&b.at).then_with(|| ta.id.cmp(&tb.id)));
+
+    let page = paging.paginate(events)?;
+
+    if output.is_json() {
+        let items: Vec<_> = page
+            .items
+            .iter()
+            .map(|(task, event)| {
+                serde_json::json!({
+                    "at": event.at,
+                    "event": event.event,
+                    "by": event.by,
+                    "data": event.data,
+                    "task": task.id.to_string(),
+                    "title": task.title,
+                })
+            })
+            .collect();
+        output.data(&page.json(items));
+    } else if page.items.is_empty() {
+        println!("No matching history events");
+    } else {
+        for (task, event) in &page.items {
+            let by = event
+                .by
+                .as_ref()
+                .map(|s| format!(" by {}", s))
+                .unwrap_or_default();
+            println!(
+                "{}  {:<20} {}{}  \"{}\"",
+                event.at.format("%Y-%m-%d %H:%M"),
+                task.id.to_string(),
+                describe_event(event),
+                by,
+                task.title
+            );
+        }
+        page.print_hint();
+    }
+
+    Ok(())
+}
+
+/// Describes a history event for text output
+fn describe_event(event: &HistoryEvent) -> String {
+    match &event.event {
+        HistoryEventType::Created => "created".to_string(),
+        HistoryEventType::Started => "started".to_string(),
+        HistoryEventType::Completed => "completed".to_string(),
+        HistoryEventType::Reopened => {
+            match event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("reason"))
+                .and_then(|v| v.as_str())
+            {
+                Some(reason) => format!("reopened: \"{}\"", reason),
+                None => "reopened".to_string(),
+            }
+        }
+        HistoryEventType::Claimed => "claimed".to_string(),
+        HistoryEventType::Unclaimed => "unclaimed".to_string(),
+        HistoryEventType::Note => {
+            let text = event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("text"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            format!("note: \"{}\"", text)
+        }
+        HistoryEventType::Linked => {
+            let link_type = event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("type"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            let reference = event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("ref"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            format!("linked {}:{}", link_type, reference)
+        }
+        HistoryEventType::Unlinked => {
+            let link_type = event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("type"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            let reference = event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("ref"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            format!("unlinked {}:{}", link_type, reference)
+        }
+        HistoryEventType::Blocked => {
+            let reason = event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("reason"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            format!("blocked: \"{}\"", reason)
+        }
+        HistoryEventType::Unblocked => "unblocked".to_string(),
+        HistoryEventType::Assigned => {
+            let to = event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("to"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            format!("assigned to {}", to)
+        }
+        HistoryEventType::Handoff => {
+            let reason = event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("reason"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            let to = event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("to"))
+                .and_then(|v| v.as_str());
+            if let Some(to_agent) = to {
+                format!("handoff to {}: \"{}\"", to_agent, reason)
+            } else {
+                format!("handoff: \"{}\"", reason)
+            }
+        }
+        HistoryEventType::Frozen => {
+            let reason = event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("reason"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            format!("frozen: \"{}\"", reason)
+        }
+        HistoryEventType::Unfrozen => "unfrozen".to_string(),
+        HistoryEventType::Split => {
+            let into = event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("into"))
+                .and_then(|v| v.as_array())
+                .map_or(0, Vec::len);
+            format!("split into {} subtasks", into)
+        }
+        HistoryEventType::ReviewRequested => {
+            let reviewer = event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("reviewer"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            format!("review requested from {}", reviewer)
+        }
+        HistoryEventType::Reviewed => {
+            let verdict = event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("verdict"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            match event
+                .data
+                .as_ref()
+                .and_then(|d| d.get("comment"))
+                .and_then(|v| v.as_str())
+            {
+                Some(comment) => format!("review {}: \"{}\"", verdict, comment),
+                None => format!("review {}", verdict),
+            }
+        }
+        HistoryEventType::BriefArchived => {
+            let field = |key: &str| {
+                event
+                    .data
+                    .as_ref()
+                    .and_then(|d| d.get(key))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("?")
+                    .to_string()
+            };
+            format!("brief {} archived ({})", field("brief"), field("cascade"))
+        }
+    }
+}
+
 fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
     let project = Project::open_current()?;
     let brief_store = project.brief_store();
@@ -949,6 +1476,10 @@ fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
     let briefs = brief_store.read_all()?;
     let tasks = task_store.read_all()?;
     let timeout_hours = get_claim_timeout(&project);
+    let now = Utc::now();
+    if let Err(e) = super::appetite::check_budgets(&project, by_id(briefs.values())) {
+        eprintln!("Warning: {:#}", e);
+    }
 
     // Build status map
     let statuses: HashMap<TaskId, TaskStatus> =
@@ -961,13 +1492,13 @@ fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
 
     if let Some(brief_str) = id {
         // Brief-specific summary
-        let brief_id: BriefId = brief_str.parse()?;
+        let brief_id = project.resolve_brief_id(brief_str)?;
         let brief = briefs
             .get(&brief_id)
-            .ok_or_else(|| anyhow::anyhow!("Brief not found: {}", brief_id))?;
+            .ok_or_else(|| CliError::not_found("Brief", &brief_id))?;
 
-        let brief_tasks: Vec<_> = tasks
-            .values()
+        let brief_tasks: Vec<_> = by_id(tasks.values())
+            .into_iter()
             .filter(|t| t.brief_id().as_ref() == Some(&brief_id))
             .collect();
 
@@ -992,6 +1523,9 @@ fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
             .iter()
             .filter(|t| blocked_ids.contains(&t.id) && !t.is_explicitly_blocked())
             .collect();
+        let child_briefs = super::brief::children(&briefs, &brief_id);
+        let (rollup_done, rollup_total) = super::brief::rollup(&briefs, &tasks, &brief_id);
+        let budget = AppetiteBudget::of(brief, now);
 
         if output.is_json() {
             output.data(&serde_json::json!({
@@ -999,12 +1533,30 @@ fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
                     "id": brief_id.to_string(),
                     "title": brief.title,
                     "status": brief.status,
+                    "owner": brief.owner,
+                    "co_owners": brief.co_owners,
                 },
                 "progress": {
                     "total": total,
                     "done": done,
-                    "percent": if total > 0 { done * 100 / total } else { 0 },
+                    "percent": (done * 100).checked_div(total).unwrap_or(0),
                 },
+                "appetite": budget.as_ref().map(super::appetite::budget_json),
+                "children": child_briefs.iter().map(|c| {
+                    let (done, total) = super::brief::rollup(&briefs, &tasks, &c.id);
+                    serde_json::json!({
+                        "id": c.id.to_string(),
+                        "title": c.title,
+                        "status": c.status,
+                        "done": done,
+                        "total": total,
+                    })
+                }).collect::<Vec<_>>(),
+                "rollup": (!child_briefs.is_empty()).then(|| serde_json::json!({
+                    "total": rollup_total,
+                    "done": rollup_done,
+                    "percent": (rollup_done * 100).checked_div(rollup_total).unwrap_or(0),
+                })),
                 "in_progress": in_progress.iter().map(|t| serde_json::json!({
                     "id": t.id.to_string(),
                     "title": t.title,
@@ -1025,11 +1577,14 @@ fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
         } else {
             println!();
             println!("{} ({})", brief.title, brief_id);
+            if let Some(owner) = super::brief::owner_label(brief) {
+                println!("  Owner: {}", owner);
+            }
             println!(
                 "  Progress: {}/{} tasks ({}%)",
                 done,
                 total,
-                if total > 0 { done * 100 / total } else { 0 }
+                (done * 100).checked_div(total).unwrap_or(0)
             );
             println!(
                 "  Status: {} ready, {} in progress, {} blocked",
@@ -1037,6 +1592,22 @@ fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
                 in_progress.len(),
                 explicitly_blocked.len() + dep_blocked.len()
             );
+            if let Some(budget) = &budget {
+                println!("  Appetite: {}", budget.describe());
+            }
+            if !child_briefs.is_empty() {
+                println!(
+                    "  Epic: {}/{} tasks across {} child brief(s) ({}%)",
+                    rollup_done,
+                    rollup_total,
+                    child_briefs.len(),
+                    (rollup_done * 100).checked_div(rollup_total).unwrap_or(0)
+                );
+                for child in &child_briefs {
+                    let (done, total) = super::brief::rollup(&briefs, &tasks, &child.id);
+                    println!("    {} {} ({}/{})", child.id, child.title, done, total);
+                }
+            }
 
             if !in_progress.is_empty() {
                 println!();
@@ -1073,14 +1644,33 @@ fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
         }
     } else {
         // Project-wide summary
-        let active_briefs: Vec<_> = briefs.values().filter(|b| b.is_active()).collect();
+        let active_briefs: Vec<_> = by_id(briefs.values())
+            .into_iter()
+            .filter(|b| b.is_active())
+            .collect();
+        let unowned_briefs = active_briefs.iter().filter(|b| b.owner.is_none()).count();
         let complete_briefs = briefs.values().filter(|b| b.is_complete()).count();
+        let epics: Vec<_> = active_briefs
+            .iter()
+            .filter(|b| !super::brief::children(&briefs, &b.id).is_empty())
+            .map(|b| (*b, super::brief::rollup(&briefs, &tasks, &b.id)))
+            .collect();
+        let budgets: Vec<_> = active_briefs
+            .iter()
+            .filter_map(|b| Some((*b, AppetiteBudget::of(b, now)?)))
+            .collect();
 
         let total_tasks = tasks.len();
         let done_tasks = tasks.values().filter(|t| t.status.is_complete()).count();
-        let in_progress_tasks: Vec<_> = tasks.values().filter(|t| t.status.is_active()).collect();
-        let explicitly_blocked: Vec<_> = tasks
-            .values()
+        let sorted = by_id(tasks.values());
+        let in_progress_tasks: Vec<_> = sorted
+            .iter()
+            .copied()
+            .filter(|t| t.status.is_active())
+            .collect();
+        let explicitly_blocked: Vec<_> = sorted
+            .iter()
+            .copied()
             .filter(|t| t.is_explicitly_blocked())
             .collect();
 
@@ -1099,8 +1689,9 @@ fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
 
         // Get next recommendation
         let agent = get_agent_name(&project, None);
-        let next_task = tasks
-            .values()
+        let next_task = sorted
+            .iter()
+            .copied()
             .filter(|t| t.is_ready_for_agent(&statuses, Some(&agent)))
             .max_by_key(|t| {
                 let priority = t
@@ -1121,6 +1712,7 @@ fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
                     "total": briefs.len(),
                     "active": active_briefs.len(),
                     "complete": complete_briefs,
+                    "unowned": unowned_briefs,
                 },
                 "tasks": {
                     "total": total_tasks,
@@ -1130,9 +1722,23 @@ fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
                     "blocked": blocked_ids.len(),
                     "explicitly_blocked": explicitly_blocked.len(),
                 },
+                "epics": epics.iter().map(|(b, (done, total))| serde_json::json!({
+                    "id": b.id.to_string(),
+                    "title": b.title,
+                    "done": done,
+                    "total": total,
+                    "percent": (done * 100).checked_div(*total).unwrap_or(0),
+                })).collect::<Vec<_>>(),
+                "appetites": budgets.iter().map(|(b, budget)| {
+                    let mut entry = super::appetite::budget_json(budget);
+                    entry["id"] = serde_json::json!(b.id.to_string());
+                    entry["title"] = serde_json::json!(b.title);
+                    entry
+                }).collect::<Vec<_>>(),
                 "hot_brief": hot_brief.map(|b| serde_json::json!({
                     "id": b.id.to_string(),
                     "title": b.title,
+                    "owner": b.owner,
                 })),
                 "next": next_task.map(|t| serde_json::json!({
                     "id": t.id.to_string(),
@@ -1147,6 +1753,12 @@ fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
                 active_briefs.len(),
                 complete_briefs
             );
+            if unowned_briefs > 0 {
+                println!(
+                    "  Unowned: {} active brief(s) without an owner",
+                    unowned_briefs
+                );
+            }
             println!(
                 "  Tasks: {} total ({} done, {} in progress, {} blocked, {} ready)",
                 total_tasks,
@@ -1155,6 +1767,24 @@ fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
                 explicitly_blocked.len() + blocked_ids.len(),
                 ready_ids.len()
             );
+            for (epic, (done, total)) in &epics {
+                println!(
+                    "  Epic {}: {}/{} tasks ({}%) {}",
+                    epic.id,
+                    done,
+                    total,
+                    (done * 100).checked_div(*total).unwrap_or(0),
+                    epic.title
+                );
+            }
+            for (brief, budget) in &budgets {
+                println!(
+                    "  Appetite {}: {} - {}",
+                    brief.id,
+                    budget.describe(),
+                    brief.title
+                );
+            }
 
             if let Some(brief) = hot_brief {
                 let brief_ready = tasks
@@ -1193,17 +1823,255 @@ fn show_summary(output: &Output, id: Option<&str>) -> Result<()> {
     Ok(())
 }
 
+/// Shows everything relevant to one agent.
+///
+/// Watched tasks are those in briefs the agent owns, or that the agent has
+/// touched before (history by them), which others have changed recently.
+/// `shape mine --mentions`: notes from the last `days` days and briefs
+/// updated in that time that mention the agent, by handle or alias
+fn show_mentions(output: &Output, agent_override: Option<&str>, days: u32) -> Result<()> {
+    let project = Project::open_current()?;
+    let agent = get_agent_name(&project, agent_override);
+    let since = Utc::now() - chrono::Duration::days(days as i64);
+
+    let team = project.team_store().read()?;
+    let names: Vec<&str> = match team.find(&agent) {
+        Some(member) => std::iter::once(member.handle.as_str())
+            .chain(member.aliases.iter().map(String::as_str))
+            .collect(),
+        None => vec![agent.as_str()],
+    };
+    let mentions_me = |text: &str| {
+        mentions(text)
+            .iter()
+            .any(|m| names.iter().any(|n| n.eq_ignore_ascii_case(m)))
+    };
+
+    let tasks = project.task_store().read_all()?;
+    let mut notes: Vec<(&Task, &Note)> = by_id(tasks.values())
+        .into_iter()
+        .flat_map(|t| t.notes.iter().map(move |n| (t, n)))
+        .filter(|(_, n)| n.at >= since && mentions_me(&n.text))
+        .collect();
+    notes.sort_by_key(|(_, n)| std::cmp::Reverse(n.at));
+
+    let briefs = project.brief_store().read_all()?;
+    let mut in_briefs: Vec<&Brief> = briefs
+        .values()
+        .filter(|b| b.updated_at >= since && mentions_me(&b.body))
+        .collect();
+    in_briefs.sort_by(|a, b| a.id.cmp(&b.id));
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "agent": agent,
+            "notes": notes.iter().map(|(t, n)| serde_json::json!({
+                "task": t.id.to_string(),
+                "title": t.title,
+                "by": n.by,
+                "at": n.at,
+                "text": n.text,
+            })).collect::<Vec<_>>(),
+            "briefs": in_briefs.iter().map(|b| serde_json::json!({
+                "id": b.id.to_string(),
+                "title": b.title,
+                "updated_at": b.updated_at,
+            })).collect::<Vec<_>>(),
+        }));
+        return Ok(());
+    }
+
+    println!("Mentions of {} in the last {} days", agent, days);
+    println!();
+    println!("In notes ({}):", notes.len());
+    for (t, n) in &notes {
+        println!(
+            "  {} \"{}\" - {} {}",
+            t.id,
+            t.title,
+            n.by,
+            n.at.format("%Y-%m-%d %H:%M")
+        );
+        println!("    {}", n.text.lines().next().unwrap_or_default());
+    }
+    if !in_briefs.is_empty() {
+        println!();
+        println!("In briefs ({}):", in_briefs.len());
+        for b in &in_briefs {
+            println!("  {} \"{}\"", b.id, b.title);
+        }
+    }
+    Ok(())
+}
+
+fn show_mine(output: &Output, agent_override: Option<&str>, days: u32) -> Result<()> {
+    let project = Project::open_current()?;
+    let agent = get_agent_name(&project, agent_override);
+    let timeout_hours = get_claim_timeout(&project);
+    let since = Utc::now() - chrono::Duration::days(days as i64);
+
+    let briefs = project.brief_store().read_all_meta()?;
+    let tasks = project.task_store().read_all()?;
+
+    let sorted = by_id(tasks.values());
+
+    let by_me = |e: &HistoryEvent| e.by.as_deref() == Some(agent.as_str());
+
+    let claimed: Vec<&Task> = sorted
+        .iter()
+        .copied()
+        .filter(|t| t.claimed_by.as_deref() == Some(agent.as_str()) && !t.status.is_complete())
+        .collect();
+    let assigned: Vec<&Task> = sorted
+        .iter()
+        .copied()
+        .filter(|t| {
+            t.assigned_to.as_deref() == Some(agent.as_str())
+                && t.claimed_by.as_deref() != Some(agent.as_str())
+                && !t.status.is_complete()
+        })
+        .collect();
+    let completed: Vec<&Task> = sorted
+        .iter()
+        .copied()
+        .filter(|t| {
+            t.status.is_complete()
+                && t.history
+                    .iter()
+                    .any(|e| e.event == HistoryEventType::Completed && e.at >= since && by_me(e))
+        })
+        .collect();
+
+    let to_review: Vec<&Task> = sorted
+        .iter()
+        .copied()
+        .filter(|t| t.pending_reviews().any(|r| r.reviewer == agent))
+        .collect();
+
+    let mut watching: Vec<(&Task, &HistoryEvent)> = sorted
+        .iter()
+        .copied()
+        .filter(|t| {
+            !claimed.contains(t)
+                && !assigned.contains(t)
+                && !to_review.contains(t)
+                && !completed.contains(t)
+        })
+        .filter(|t| {
+            let owns_brief = t
+                .brief_id()
+                .and_then(|id| briefs.get(&id))
+                .is_some_and(|b| b.is_owned_by(&agent));
+            owns_brief || t.history.iter().any(by_me)
+        })
+        .filter_map(|t| {
+            t.history
+                .iter()
+                .filter(|e| e.at >= since && !by_me(e))
+                .max_by_key(|e| e.at)
+                .map(|e| (t, e))
+        })
+        .collect();
+    watching.sort_by_key(|(_, e)| std::cmp::Reverse(e.at));
+
+    if output.is_json() {
+        let task_json = |t: &Task| {
+            serde_json::json!({
+                "id": t.id.to_string(),
+                "title": t.title,
+                "status": t.status,
+            })
+        };
+        output.data(&serde_json::json!({
+            "agent": agent,
+            "claimed": claimed.iter().map(|t| serde_json::json!({
+                "id": t.id.to_string(),
+                "title": t.title,
+                "status": t.status,
+                "claimed_at": t.claimed_at,
+                "expired": t.is_claim_expired(timeout_hours),
+            })).collect::<Vec<_>>(),
+            "assigned": assigned.iter().map(|t| task_json(t)).collect::<Vec<_>>(),
+            "reviews": to_review.iter().map(|t| task_json(t)).collect::<Vec<_>>(),
+            "watching": watching.iter().map(|(t, e)| serde_json::json!({
+                "id": t.id.to_string(),
+                "title": t.title,
+                "status": t.status,
+                "last_activity": {
+                    "at": e.at,
+                    "event": e.event,
+                    "by": e.by,
+                },
+            })).collect::<Vec<_>>(),
+            "completed": completed.iter().map(|t| serde_json::json!({
+                "id": t.id.to_string(),
+                "title": t.title,
+                "completed_at": t.completed_at,
+            })).collect::<Vec<_>>(),
+        }));
+        return Ok(());
+    }
+
+    println!("Work for {}", agent);
+
+    println!();
+    println!("Claimed ({}):", claimed.len());
+    for t in &claimed {
+        let remaining = t.claim_remaining_hours(timeout_hours).unwrap_or(0.0);
+        println!("  {} \"{}\" ({:.1}h left)", t.id, t.title, remaining);
+    }
+
+    if !assigned.is_empty() {
+        println!();
+        println!("Assigned ({}):", assigned.len());
+        for t in &assigned {
+            println!("  {} \"{}\" [{}]", t.id, t.title, t.status);
+        }
+    }
+
+    if !to_review.is_empty() {
+        println!();
+        println!("Reviews requested ({}):", to_review.len());
+        for t in &to_review {
+            println!("  {} \"{}\" [{}]", t.id, t.title, t.status);
+        }
+    }
+
+    if !watching.is_empty() {
+        println!();
+        println!("Watching ({}):", watching.len());
+        for (t, e) in &watching {
+            println!(
+                "  {} \"{}\" - {} {}",
+                t.id,
+                t.title,
+                describe_event(e),
+                e.at.format("%Y-%m-%d %H:%M")
+            );
+        }
+    }
+
+    println!();
+    println!("Completed in the last {} days ({}):", days, completed.len());
+    for t in &completed {
+        println!("  {} \"{}\"", t.id, t.title);
+    }
+
+    Ok(())
+}
+
 fn handoff_task(output: &Output, id_str: &str, reason: &str, to: Option<String>) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.task_store();
     let agent = get_agent_name(&project, None);
 
-    let id: TaskId = id_str.parse()?;
+    let id = project.resolve_task_id(id_str)?;
     let mut tasks = store.read_all()?;
 
     let task = tasks
         .get_mut(&id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
 
     task.handoff(reason, &agent, to.clone());
     store.update(task)?;
@@ -1230,7 +2098,7 @@ fn find_by_link(output: &Output, commit: Option<String>, file: Option<String>) -
 
     let mut matches: Vec<&Task> = Vec::new();
 
-    for task in tasks.values() {
+    for task in by_id(tasks.values()) {
         let found = task.links.iter().any(|link| {
             if let Some(ref hash) = commit {
                 if link.link_type == LinkType::Commit && link.reference.contains(hash) {
@@ -1,14 +1,27 @@
 //! Main CLI application structure
 
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
 
-use super::output::{Output, OutputFormat};
+use anyhow::Result;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
+
+use super::adopt::{self, AdoptMode};
+use super::output::{Output, OutputFormat, Projection};
+use super::page::PageArgs;
+use super::profile::Profile;
+#[cfg(feature = "self-update")]
+use super::self_update;
 use super::{
-    agent, agent_setup, brief, cache_cmd, compact, context, daemon, merge_driver, plugin_cmd,
-    query, sync_cmd, task, tui,
+    affected, agent, agent_setup, bench, bet, brief, cache_cmd, claims, compact, compliance,
+    config_cmd, context, daemon, doctor, error, freeze, gc, graph, ids, import, ingest,
+    interactive, lock_cmd, logging, maintenance, merge_driver, metrics, migrate, milestone, open,
+    plugin_cmd, prompt, query, report, review, serve, simulate, status_by, store_crypt, sync_cmd,
+    task, team, tui, tx, undo, watch_run,
 };
-use crate::storage::Project;
+use crate::domain::parse_external_ref;
+use crate::storage::{IdempotencyRecord, Project};
 
 #[derive(Parser)]
 #[command(name = "shape")]
@@ -27,6 +40,59 @@ pub struct Cli {
     #[arg(long, short = 'v', global = true)]
     pub verbose: bool,
 
+    /// Linear text for screen readers: `key: value` lines without tables,
+    /// box drawing, icons or color (also SHAPE_PLAIN)
+    #[arg(
+        long,
+        global = true,
+        env = "SHAPE_PLAIN",
+        value_parser = clap::builder::FalseyValueParser::new(),
+        conflicts_with = "format"
+    )]
+    pub plain: bool,
+
+    /// Also trace storage, plugin and graph spans with their timings
+    #[arg(long, global = true)]
+    pub trace: bool,
+
+    /// Print where the command's time went (project open, store reads,
+    /// graph building, serialization) on stderr
+    #[arg(long, global = true)]
+    pub profile: bool,
+
+    /// Also write debug logs to DIR/shape.log.<date>, rotated daily
+    /// (also SHAPE_LOG_DIR)
+    #[arg(long, global = true, env = "SHAPE_LOG_DIR", value_name = "DIR")]
+    pub log_dir: Option<PathBuf>,
+
+    /// Only include these fields in JSON records (e.g. id,title,status)
+    #[arg(long, global = true, value_delimiter = ',')]
+    pub fields: Vec<String>,
+
+    /// Omit these fields from JSON records (e.g. history,notes)
+    #[arg(long, global = true, value_delimiter = ',')]
+    pub exclude: Vec<String>,
+
+    /// Operate on the project at this path instead of the current directory
+    /// (also SHAPE_PROJECT)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub project: Option<PathBuf>,
+
+    /// Allow changing frozen briefs and tasks; the reason is recorded in
+    /// .shape/audit.jsonl
+    #[arg(long = "override", global = true, value_name = "REASON")]
+    pub override_reason: Option<String>,
+
+    /// Wait for locks held by other shape processes instead of failing after
+    /// [storage] lock_timeout_secs
+    #[arg(long, global = true)]
+    pub wait: bool,
+
+    /// Run the command once per KEY: a retry with the same key prints the
+    /// original output instead of applying the change again
+    #[arg(long, global = true, value_name = "KEY")]
+    pub idempotency_key: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -38,6 +104,22 @@ pub enum Commands {
         /// Path to initialize (defaults to current directory)
         #[arg(default_value = ".")]
         path: String,
+
+        /// Create briefs from existing markdown documents in this directory
+        #[arg(long, value_name = "DIR")]
+        adopt: Option<PathBuf>,
+
+        /// Brief type for adopted documents (defaults to the configured type)
+        #[arg(long = "type", value_name = "TYPE", requires = "adopt")]
+        brief_type: Option<String>,
+
+        /// Move adopted documents under .shape/briefs/ (with git mv when tracked)
+        #[arg(long = "move", requires = "adopt")]
+        move_docs: bool,
+
+        /// Like --move, but leave a symlink at each original path
+        #[arg(long, requires = "adopt", conflicts_with = "move_docs")]
+        symlink: bool,
     },
 
     /// Manage briefs (pitches, RFCs, etc.)
@@ -48,11 +130,27 @@ pub enum Commands {
     #[command(subcommand)]
     Task(task::TaskCommands),
 
+    /// Manage date-anchored milestones
+    #[command(subcommand)]
+    Milestone(milestone::MilestoneCommands),
+
+    /// Run the ShapeUp betting table for a cycle
+    #[command(subcommand)]
+    Bet(bet::BetCommands),
+
     /// Show tasks ready to work on
     Ready {
         /// Filter by brief ID
         #[arg(long)]
         brief: Option<String>,
+
+        /// Keep running and print an event whenever the ready set changes
+        /// (one JSON object per line with --format json)
+        #[arg(long, conflicts_with_all = ["limit", "offset", "page_token"])]
+        watch: bool,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Show blocked tasks
@@ -62,11 +160,36 @@ pub enum Commands {
         brief: Option<String>,
     },
 
+    /// Show open tasks past their due date
+    ///
+    /// Example:
+    ///   shape overdue --within 2d
+    Overdue {
+        /// Filter by brief ID
+        #[arg(long)]
+        brief: Option<String>,
+
+        /// Also show tasks due within this long (e.g. 2d, 12h)
+        #[arg(long, value_name = "DURATION")]
+        within: Option<String>,
+    },
+
     /// Show project status overview
-    Status,
+    ///
+    /// Example:
+    ///   shape status --by owner
+    Status {
+        /// Break the status down by brief type, owner, cycle or task label
+        #[arg(long, value_enum)]
+        by: Option<status_by::StatusGroup>,
+    },
 
     /// Export project context for AI
+    #[command(args_conflicts_with_subcommands = true)]
     Context {
+        #[command(subcommand)]
+        action: Option<context::ContextCommands>,
+
         /// Compact mode (minimal tokens)
         #[arg(long, short)]
         compact: bool,
@@ -75,9 +198,69 @@ pub enum Commands {
         #[arg(long)]
         brief: Option<String>,
 
+        /// Only include a task, its blocking ancestors, its siblings and its brief
+        #[arg(long, conflicts_with = "brief")]
+        for_task: Option<String>,
+
         /// Days of completed tasks to include
         #[arg(long, default_value = "7")]
         days: u32,
+
+        /// ETag from a previous export; prints only a "not modified" marker if unchanged
+        #[arg(long)]
+        etag: Option<String>,
+
+        /// Write one compact file per active brief plus index.json into this directory
+        #[arg(long, value_name = "DIR", conflicts_with_all = ["brief", "for_task", "etag"])]
+        split_out: Option<PathBuf>,
+
+        /// Which brief bodies to include (selective: only briefs with active work)
+        #[arg(long, value_enum, default_value_t)]
+        include_bodies: context::IncludeBodies,
+
+        /// Write the context with source file hashes and git commit to FILE (see `context verify`)
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["split_out", "etag"])]
+        bundle: Option<PathBuf>,
+
+        /// Emit the structure of an older context schema version
+        #[arg(long, value_name = "N", conflicts_with = "split_out")]
+        schema_version: Option<u32>,
+
+        /// Drop old work, metadata and long text until the export fits about N tokens
+        #[arg(long, value_name = "N", conflicts_with = "split_out")]
+        max_tokens: Option<usize>,
+    },
+
+    /// Render an agent prompt preset with the context for a task or brief
+    ///
+    /// Presets live in .shape/prompts/<name>.md; `coder` and `reviewer` are built in.
+    ///
+    /// Example:
+    ///   shape prompt coder --task b-7f2a3b1.3 | pbcopy
+    Prompt {
+        /// Preset name
+        #[arg(required_unless_present = "list")]
+        name: Option<String>,
+
+        /// Task to render the prompt for
+        #[arg(long)]
+        task: Option<String>,
+
+        /// Brief to render the prompt for
+        #[arg(long, conflicts_with = "task")]
+        brief: Option<String>,
+
+        /// Use the compact context export
+        #[arg(long, short)]
+        compact: bool,
+
+        /// Days of completed tasks to include in the context
+        #[arg(long, default_value = "7")]
+        days: u32,
+
+        /// List available presets
+        #[arg(long, conflicts_with_all = ["name", "task", "brief"])]
+        list: bool,
     },
 
     /// Compact old completed tasks into summaries
@@ -101,6 +284,10 @@ pub enum Commands {
         /// Undo compaction for a specific task
         #[arg(long)]
         undo: Option<String>,
+
+        /// Confirm compaction above the bulk guardrail threshold
+        #[arg(long, short = 'y')]
+        yes: bool,
     },
 
     /// Configure AI agent integration
@@ -126,12 +313,190 @@ pub enum Commands {
     #[command(subcommand)]
     Cache(cache_cmd::CacheCommands),
 
+    /// Inspect the configuration
+    #[command(subcommand)]
+    Config(config_cmd::ConfigCommands),
+
+    /// Maintain task claims (e.g. sweep expired ones)
+    #[command(subcommand)]
+    Claims(claims::ClaimsCommands),
+
+    /// Lock paths or subsystems you are working on
+    #[command(subcommand)]
+    Lock(lock_cmd::LockCommands),
+
+    /// Stage several commands and apply them as one change
+    #[command(subcommand)]
+    Tx(tx::TxCommands),
+
+    /// Maintain the team directory @mentions refer to
+    #[command(subcommand)]
+    Team(team::TeamCommands),
+
+    /// Run a command and record its result on a task
+    ///
+    /// Passes the command's output through, attaches the log to the task,
+    /// adds a note with the exit status and last lines of output, and sets
+    /// meta.run_status to passed or failed. Exits with the command's code.
+    ///
+    /// Example:
+    ///   shape watch-run b-7f2b4c1.3 -- cargo test
+    WatchRun {
+        /// Task ID to record the result on
+        id: String,
+
+        /// Lines of output to quote in the note
+        #[arg(long, default_value = "20")]
+        excerpt: usize,
+
+        /// Agent name (defaults to config or $SHAPE_AGENT)
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Command to run, after --
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// Request and record reviews of tasks
+    #[command(subcommand)]
+    Review(review::ReviewCommands),
+
+    /// Reports for humans (aging work in progress)
+    #[command(subcommand)]
+    Report(report::ReportCommands),
+
+    /// Signed archives of how a brief was built, for audits
+    #[command(subcommand)]
+    Compliance(compliance::ComplianceCommands),
+
+    /// Preview the effect of completing tasks without changing anything
+    #[command(subcommand)]
+    Simulate(simulate::SimulateCommands),
+
+    /// Export the task dependency graph
+    #[command(subcommand)]
+    Graph(graph::GraphCommands),
+
+    /// Show local command metrics (opt-in via [metrics] enabled)
+    #[command(subcommand)]
+    Metrics(metrics::MetricsCommands),
+
+    /// Time key operations and compare them with the recorded baseline
+    ///
+    /// Example:
+    ///   shape bench --runs 10
+    Bench {
+        /// Times to run each operation (the median is reported)
+        #[arg(long, default_value = "5")]
+        runs: u32,
+
+        /// Record this run as the new baseline
+        #[arg(long)]
+        save_baseline: bool,
+    },
+
+    /// Manage the brief and task ID scheme
+    #[command(subcommand)]
+    Ids(ids::IdsCommands),
+
+    /// Create triage tasks from incoming requests (e.g. email)
+    #[command(subcommand)]
+    Ingest(ingest::IngestCommands),
+
+    /// Import briefs and tasks from Trello, Linear or GitHub issues
+    #[command(subcommand)]
+    Import(import::ImportCommands),
+
     /// Search tasks and briefs
     Search {
         /// Search query
         query: String,
+
+        /// Also search the archive (plain text match on titles, descriptions
+        /// and brief bodies, listed after the results)
+        #[arg(long)]
+        include_archived: bool,
+
+        #[command(flatten)]
+        page: PageArgs,
+    },
+
+    /// Open a brief in your editor, or a task's PR, URL or file link
+    ///
+    /// Example:
+    ///   shape open b-7f2a3b1.2
+    Open {
+        /// Brief or task ID
+        id: String,
+
+        /// Print the file or URL instead of opening it
+        #[arg(long)]
+        print: bool,
+    },
+
+    /// Validate project data (use --format junit or github-annotations in CI)
+    Doctor,
+
+    /// Show tasks linked to files changed on this branch
+    Affected {
+        /// Base ref to diff against
+        #[arg(long, default_value = "main")]
+        base: String,
+
+        /// Check these files instead of running git diff
+        #[arg(long, num_args = 1..)]
+        files: Vec<String>,
+    },
+
+    /// Find files in .shape/ nothing refers to anymore
+    ///
+    /// Reports attachments of deleted tasks, sync mappings for deleted
+    /// briefs and tasks, and cache rows for removed briefs.
+    ///
+    /// Example:
+    ///   shape gc --scan
+    ///   shape gc --prune
+    Gc {
+        /// Report garbage without removing it (the default)
+        #[arg(long, conflicts_with = "prune")]
+        scan: bool,
+
+        /// Remove the garbage
+        #[arg(long)]
+        prune: bool,
     },
 
+    /// Housekeeping: archive old shipped briefs
+    #[command(subcommand)]
+    Maintenance(maintenance::MaintenanceCommands),
+
+    /// Upgrade the project's on-disk format to this version of shape
+    Migrate {
+        /// List the pending migration steps without applying them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Encrypt tasks and briefs at rest with a project passphrase
+    ///
+    /// The passphrase is read from SHAPE_PASSPHRASE, or prompted for.
+    /// Afterwards every command needs SHAPE_PASSPHRASE set, or the
+    /// passphrase saved in the system keychain with --remember.
+    ///
+    /// Example:
+    ///   SHAPE_PASSPHRASE=... shape lock-store
+    ///   shape lock-store --remember
+    LockStore {
+        /// Save the passphrase in the system keychain (macOS Keychain or
+        /// libsecret) so commands unlock the store on their own
+        #[arg(long)]
+        remember: bool,
+    },
+
+    /// Decrypt tasks and briefs and stop encrypting the store
+    UnlockStore,
+
     /// Git merge driver for tasks.jsonl (internal use)
     #[command(hide = true)]
     MergeDriver {
@@ -152,18 +517,53 @@ pub enum Commands {
     #[command(subcommand)]
     Daemon(daemon::DaemonCommands),
 
+    /// Run a protocol server for editor integrations
+    ///
+    /// Example:
+    ///   shape serve --jsonrpc-stdio
+    ///   shape serve --mcp
+    Serve {
+        /// Speak JSON-RPC 2.0 over stdin/stdout (one message per line)
+        #[arg(long, conflicts_with = "mcp")]
+        jsonrpc_stdio: bool,
+
+        /// Run as a Model Context Protocol server on stdin/stdout
+        #[arg(long)]
+        mcp: bool,
+    },
+
+    /// Update shape to the latest release
+    ///
+    /// The download is checked against the SHA-256 published with the
+    /// release. That catches corrupt downloads, not a tampered release: the
+    /// checksum comes from the same place as the binary.
+    ///
+    /// Example:
+    ///   shape self-update --check
+    ///   shape self-update
+    #[cfg(feature = "self-update")]
+    SelfUpdate {
+        /// Only report whether a newer version is out
+        #[arg(long)]
+        check: bool,
+    },
+
     /// Advanced commands (plugins, sync)
     #[command(subcommand)]
     Advanced(AdvancedCommands),
 
-    /// Launch interactive TUI viewer
+    /// Menu-driven prompts that work with screen readers, instead of the TUI
+    Interactive,
+
+    /// Launch interactive TUI viewer (`--plain` or SHAPE_PLAIN falls back to
+    /// `shape interactive`)
     Tui {
         /// Start focused on a specific brief
         #[arg(short, long)]
         brief: Option<String>,
 
-        /// Start with a specific view (overview, kanban, graph)
-        #[arg(short, long, default_value = "overview")]
+        /// Start with a specific view (overview, kanban, graph, conflicts)
+        #[arg(long, default_value = "overview")]
         view: String,
     },
 
@@ -184,6 +584,11 @@ pub enum Commands {
         /// Reason for force-claiming (required when --force is used)
         #[arg(long, required_if_eq("force", "true"))]
         reason: Option<String>,
+
+        /// Let an agent claim a task that requires a human; only [agent]
+        /// operators may, and it is recorded in .shape/audit.jsonl
+        #[arg(long, value_name = "REASON")]
+        allow_agent: Option<String>,
     },
 
     /// Release a claim on a task
@@ -201,6 +606,11 @@ pub enum Commands {
         /// Number of suggestions (default: 1)
         #[arg(short, long, default_value = "1")]
         n: usize,
+
+        /// Labels to prefer, `-label` to skip tasks carrying it (default:
+        /// the agent's interests in the team directory)
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+        interests: Option<Vec<String>>,
     },
 
     /// Add a note to a task
@@ -275,10 +685,56 @@ pub enum Commands {
         id: String,
     },
 
+    /// Freeze a brief or task so commands refuse to change it
+    ///
+    /// Freezing a brief freezes all of its tasks. Changes need
+    /// --override <REASON>, which is recorded in .shape/audit.jsonl.
+    ///
+    /// Example:
+    ///   shape freeze b-7f2b4c1 --reason "security sign-off pending"
+    Freeze {
+        /// Brief or task ID
+        id: String,
+
+        /// Why it is frozen
+        #[arg(long)]
+        reason: String,
+    },
+
+    /// Lift a freeze from a brief or task
+    Unfreeze {
+        /// Brief or task ID
+        id: String,
+    },
+
+    /// Undo the last task or agent command that changed tasks
+    ///
+    /// Puts the changed tasks back as they were. Refused when one of them
+    /// changed again since.
+    ///
+    /// Example:
+    ///   shape undo
+    ///   shape undo --last 3
+    Undo {
+        /// Number of commands to undo, newest first
+        #[arg(long, default_value = "1")]
+        last: usize,
+    },
+
+    /// Redo the most recently undone command
+    Redo,
+
     /// Show task history/timeline
     History {
-        /// Task or brief ID
-        id: String,
+        /// Task ID (omit with --all)
+        #[arg(required_unless_present = "all")]
+        id: Option<String>,
+
+        #[command(flatten)]
+        filter: agent::HistoryArgs,
+
+        #[command(flatten)]
+        page: PageArgs,
     },
 
     /// Show project or brief summary
@@ -287,6 +743,22 @@ pub enum Commands {
         id: Option<String>,
     },
 
+    /// Show my work: claimed, assigned, review-requested, watched and recently
+    /// completed tasks
+    Mine {
+        /// Agent name (defaults to $SHAPE_AGENT or $USER)
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Days of activity and completed work to include
+        #[arg(long, default_value = "7")]
+        days: u32,
+
+        /// List notes and briefs that @mention me instead
+        #[arg(long)]
+        mentions: bool,
+    },
+
     /// Hand off a task to another agent or human
     Handoff {
         /// Task ID
@@ -314,14 +786,170 @@ pub enum AdvancedCommands {
 }
 
 /// Main entry point for the CLI
-pub fn run() -> Result<()> {
-    let cli = Cli::parse();
-    let output = Output::new(cli.format, cli.verbose);
+///
+/// Failures are reported on stderr (as JSON with `--format json`) and mapped
+/// to the exit code of their [`ErrorCode`](super::error::ErrorCode).
+pub fn run() -> ExitCode {
+    let matches = Cli::command().get_matches();
+    let command = command_path(&matches);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let profile = cli.profile.then(Profile::default);
+    let _log_guard = logging::init(
+        cli.verbose,
+        cli.trace,
+        cli.log_dir.as_deref(),
+        profile.as_ref(),
+    );
+    let mut output = Output::new(cli.format, cli.verbose)
+        .with_projection(Projection::new(cli.fields.clone(), cli.exclude.clone()));
+    if cli.plain {
+        output = output.with_plain();
+    }
+    if cli.idempotency_key.is_some() {
+        output = output.with_capture();
+    }
+
+    if let Some(reason) = &cli.override_reason {
+        freeze::set_override(reason.clone(), command.clone());
+    }
+
+    let started = Instant::now();
+    let result = check_format(&command, cli.format).and_then(|()| execute(cli, &output));
+    if let Some(profile) = &profile {
+        profile.report(started.elapsed(), &output);
+    }
+    if let Ok(project) = Project::open_current() {
+        project.record_metric(&command, started.elapsed(), result.is_ok());
+    }
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            let code = error::classify(&e);
+            output.failure(code, &format!("{:#}", e));
+            ExitCode::from(code.exit_code())
+        }
+    }
+}
 
+/// Fails unless `command` (e.g. `task list`) can print `format`. Every
+/// command prints text and JSON; the other formats only where declared.
+fn check_format(command: &str, format: OutputFormat) -> Result<()> {
+    let extra = match command {
+        "affected" => affected::FORMATS,
+        "config show" => config_cmd::SHOW_FORMATS,
+        "context" => context::FORMATS,
+        "doctor" => doctor::FORMATS,
+        "graph export" => graph::EXPORT_FORMATS,
+        "task list" => task::LIST_FORMATS,
+        _ => &[],
+    };
+    if matches!(format, OutputFormat::Text | OutputFormat::Json) || extra.contains(&format) {
+        return Ok(());
+    }
+    Err(error::CliError::new(
+        error::ErrorCode::Validation,
+        format!(
+            "--format {} is not supported by `shape {}`",
+            format.name(),
+            command
+        ),
+    )
+    .into())
+}
+
+/// Subcommand names without arguments, e.g. `task list`
+fn command_path(matches: &ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        names.push(name);
+        current = sub;
+    }
+    names.join(" ")
+}
+
+fn execute(cli: Cli, output: &Output) -> Result<()> {
     output.verbose("Shape CLI starting");
 
-    match cli.command {
-        Commands::Init { path } => {
+    if let Some(path) = cli.project {
+        output.verbose(&format!("Project search root: {}", path.display()));
+        Project::set_search_root(path);
+    }
+    if cli.wait {
+        Project::wait_for_locks();
+    }
+
+    match cli.idempotency_key {
+        Some(key) => run_idempotent(&key, cli.command, output),
+        None => dispatch(cli.command, output),
+    }
+}
+
+/// Runs `command` unless it already ran with `key`, in which case the
+/// original output is printed again
+fn run_idempotent(key: &str, command: Commands, output: &Output) -> Result<()> {
+    let project = Project::open_current()?;
+    let log = project.idempotency_log();
+    // A retry waits for the original run to finish and record its result
+    let _lock = log.lock()?;
+
+    let invocation = invocation();
+    if let Some(record) = log.find(key)? {
+        if record.command != invocation {
+            return Err(error::CliError::new(
+                error::ErrorCode::Conflict,
+                format!(
+                    "Idempotency key '{}' was already used for `shape {}`",
+                    key, record.command
+                ),
+            )
+            .into());
+        }
+        output.verbose(&format!(
+            "Idempotency key '{}' seen at {}, replaying",
+            key, record.at
+        ));
+        if record.output.is_empty() {
+            output.success(&format!("Already applied with idempotency key '{}'", key));
+        } else {
+            print!("{}", record.output);
+        }
+        return Ok(());
+    }
+
+    dispatch(command, output)?;
+    log.record(IdempotencyRecord {
+        key: key.to_string(),
+        command: invocation,
+        at: chrono::Utc::now(),
+        output: output.captured().unwrap_or_default(),
+    })
+}
+
+/// Command line arguments without `--idempotency-key`
+fn invocation() -> String {
+    let mut args = std::env::args().skip(1);
+    let mut kept = Vec::new();
+    while let Some(arg) = args.next() {
+        if arg == "--idempotency-key" {
+            args.next();
+        } else if !arg.starts_with("--idempotency-key=") {
+            kept.push(arg);
+        }
+    }
+    kept.join(" ")
+}
+
+fn dispatch(command: Commands, output: &Output) -> Result<()> {
+    match command {
+        Commands::Init {
+            path,
+            adopt,
+            brief_type,
+            move_docs,
+            symlink,
+        } => {
             output.verbose_ctx("init", &format!("Initializing project at: {}", path));
             let project = Project::init(&path)?;
             output.verbose_ctx(
@@ -331,47 +959,148 @@ pub fn run() -> Result<()> {
                     project.shape_dir().display()
                 ),
             );
-            output.success(&format!(
-                "Initialized shape project at {}",
-                project.root().display()
-            ));
+            match adopt {
+                Some(dir) => {
+                    let mode = if symlink {
+                        AdoptMode::Symlink
+                    } else if move_docs {
+                        AdoptMode::Move
+                    } else {
+                        AdoptMode::Copy
+                    };
+                    let adopted = adopt::adopt(&project, &dir, brief_type.as_deref(), mode)?;
+                    if output.is_json() {
+                        let briefs: Vec<_> = adopted
+                            .iter()
+                            .map(|a| {
+                                serde_json::json!({
+                                    "id": a.brief.id.to_string(),
+                                    "title": a.brief.title,
+                                    "status": a.brief.status,
+                                    "source": a.source,
+                                })
+                            })
+                            .collect();
+                        output.data(&serde_json::json!({
+                            "root": project.root().display().to_string(),
+                            "adopted": briefs,
+                        }));
+                    } else {
+                        for a in &adopted {
+                            println!(
+                                "  {} {} ({}) ← {}",
+                                a.brief.id, a.brief.title, a.brief.status, a.source
+                            );
+                        }
+                        output.success(&format!(
+                            "Initialized shape project at {} and adopted {} document(s)",
+                            project.root().display(),
+                            adopted.len()
+                        ));
+                    }
+                }
+                None => output.success(&format!(
+                    "Initialized shape project at {}",
+                    project.root().display()
+                )),
+            }
         }
 
-        Commands::Brief(cmd) => brief::run(cmd, &output)?,
-        Commands::Task(cmd) => task::run(cmd, &output)?,
+        Commands::Brief(cmd) => brief::run(cmd, output)?,
+        Commands::Task(cmd) => task::run(cmd, output)?,
+        Commands::Milestone(cmd) => milestone::run(cmd, output)?,
+        Commands::Bet(cmd) => bet::run(cmd, output)?,
 
-        Commands::Ready { brief } => {
+        Commands::Ready {
+            brief, watch: true, ..
+        } => query::ready_watch(output, brief.as_deref())?,
+        Commands::Ready { brief, page, .. } => {
             output.verbose_ctx(
                 "ready",
                 &format!("Querying ready tasks, brief filter: {:?}", brief),
             );
-            query::ready(&output, brief.as_deref())?
+            query::ready(output, brief.as_deref(), &page)?
         }
         Commands::Blocked { brief } => {
             output.verbose_ctx(
                 "blocked",
                 &format!("Querying blocked tasks, brief filter: {:?}", brief),
             );
-            query::blocked(&output, brief.as_deref())?
+            query::blocked(output, brief.as_deref())?
         }
-        Commands::Status => {
+        Commands::Overdue { brief, within } => {
+            query::overdue(output, brief.as_deref(), within.as_deref())?
+        }
+        Commands::Status { by } => {
             output.verbose("Gathering project status");
-            query::status(&output)?
+            match by {
+                Some(by) => status_by::run(output, by)?,
+                None => query::status(output)?,
+            }
+            #[cfg(feature = "self-update")]
+            self_update::notice(output);
         }
 
         Commands::Context {
+            action: Some(cmd), ..
+        } => context::run(cmd, output)?,
+        Commands::Context {
+            action: None,
             compact: compact_mode,
             brief,
+            for_task,
             days,
+            etag,
+            split_out,
+            include_bodies,
+            bundle,
+            schema_version,
+            max_tokens,
         } => {
             output.verbose_ctx(
                 "context",
                 &format!(
-                    "Exporting context: compact={}, brief={:?}, days={}",
-                    compact_mode, brief, days
+                    "Exporting context: compact={}, brief={:?}, for_task={:?}, days={}",
+                    compact_mode, brief, for_task, days
                 ),
             );
-            context::export(&output, compact_mode, brief.as_deref(), days)?
+            let opts = context::ContextOptions {
+                // TOON is the dense rendering of the compact context, and
+                // Markdown is rendered from the full one
+                compact: (compact_mode || output.format() == OutputFormat::Toon)
+                    && output.format() != OutputFormat::Markdown,
+                brief,
+                for_task,
+                days,
+                include_bodies,
+                schema_version,
+                max_tokens,
+            };
+            context::export(
+                output,
+                &opts,
+                etag.as_deref(),
+                split_out.as_deref(),
+                bundle.as_deref(),
+            )?
+        }
+
+        Commands::Prompt { list: true, .. } => prompt::list(output)?,
+        Commands::Prompt {
+            name,
+            task,
+            brief,
+            compact: compact_mode,
+            days,
+            list: false,
+        } => {
+            let selection = prompt::Selection {
+                task: task.as_deref(),
+                brief: brief.as_deref(),
+                compact: compact_mode,
+                days,
+            };
+            prompt::run(output, name.as_deref().unwrap_or_default(), &selection)?
         }
 
         Commands::Compact {
@@ -380,16 +1109,18 @@ pub fn run() -> Result<()> {
             dry_run,
             strategy,
             undo,
+            yes,
         } => {
             if let Some(task_id) = undo {
-                compact::undo(&output, &task_id)?
+                compact::undo(output, &task_id)?
             } else {
                 compact::run(
-                    &output,
+                    output,
                     days,
                     brief.as_deref(),
                     dry_run,
                     strategy.as_deref(),
+                    yes,
                 )?
             }
         }
@@ -399,11 +1130,61 @@ pub fn run() -> Result<()> {
             claude,
             cursor,
             windsurf,
-        } => agent_setup::run(&output, show, claude, cursor, windsurf)?,
+        } => agent_setup::run(output, show, claude, cursor, windsurf)?,
+
+        Commands::Cache(cmd) => cache_cmd::run(cmd, output)?,
+        Commands::Config(cmd) => config_cmd::run(cmd, output)?,
+        Commands::Claims(cmd) => claims::run(cmd, output)?,
+        Commands::Lock(cmd) => lock_cmd::run(cmd, output)?,
+        Commands::Tx(cmd) => fenced("tx", || tx::run(cmd, output))?,
+
+        Commands::Team(cmd) => team::run(cmd, output)?,
+        Commands::WatchRun {
+            id,
+            excerpt,
+            agent,
+            command,
+        } => {
+            let exit_code = watch_run::run(output, &id, &command, excerpt, agent.as_deref())?;
+            if exit_code != 0 {
+                std::process::exit(exit_code);
+            }
+        }
+        Commands::Ids(cmd) => ids::run(cmd, output)?,
+        Commands::Graph(cmd) => graph::run(cmd, output)?,
+        Commands::Simulate(cmd) => simulate::run(cmd, output)?,
+        Commands::Report(cmd) => report::run(cmd, output)?,
+        Commands::Compliance(cmd) => compliance::run(cmd, output)?,
+        Commands::Metrics(cmd) => metrics::run(cmd, output)?,
+        Commands::Bench {
+            runs,
+            save_baseline,
+        } => bench::run(output, runs, save_baseline)?,
+        Commands::Review(cmd) => review::run(cmd, output)?,
+        Commands::Ingest(cmd) => fenced("ingest", || ingest::run(cmd, output))?,
+        Commands::Import(cmd) => fenced("import", || import::run(cmd, output))?,
 
-        Commands::Cache(cmd) => cache_cmd::run(cmd, &output)?,
+        Commands::Search {
+            query,
+            include_archived,
+            page,
+        } => search(output, &query, include_archived, &page)?,
 
-        Commands::Search { query } => search(&output, &query)?,
+        Commands::Open { id, print } => open::run(output, &id, print)?,
+
+        Commands::Doctor => doctor::run(output)?,
+
+        Commands::Affected { base, files } => affected::run(output, &base, &files)?,
+
+        Commands::Gc { scan: _, prune } => gc::run(output, prune)?,
+
+        Commands::Maintenance(cmd) => maintenance::run(cmd, output)?,
+
+        Commands::Migrate { dry_run } => migrate::run(output, dry_run)?,
+
+        Commands::LockStore { remember } => store_crypt::lock(output, remember)?,
+
+        Commands::UnlockStore => store_crypt::unlock(output)?,
 
         Commands::MergeDriver { base, ours, theirs } => {
             // This is called by git, return the exit code directly
@@ -411,21 +1192,29 @@ pub fn run() -> Result<()> {
             std::process::exit(exit_code);
         }
 
-        Commands::MergeSetup => setup_merge_driver(&output)?,
+        Commands::MergeSetup => setup_merge_driver(output)?,
+
+        Commands::Daemon(cmd) => daemon::run(cmd, output)?,
 
-        Commands::Daemon(cmd) => daemon::run(cmd, &output)?,
+        Commands::Serve { jsonrpc_stdio, mcp } => serve::run(output, jsonrpc_stdio, mcp)?,
+
+        #[cfg(feature = "self-update")]
+        Commands::SelfUpdate { check } => self_update::run(output, check)?,
 
         Commands::Advanced(advanced_cmd) => match advanced_cmd {
-            AdvancedCommands::Plugin(cmd) => plugin_cmd::run(cmd, &output)?,
-            AdvancedCommands::Sync(cmd) => sync_cmd::run(cmd, &output)?,
+            AdvancedCommands::Plugin(cmd) => plugin_cmd::run(cmd, output)?,
+            AdvancedCommands::Sync(cmd) => sync_cmd::run(cmd, output)?,
         },
 
+        Commands::Interactive => run_interactive(output)?,
+
+        Commands::Tui { .. } if output.is_plain() => run_interactive(output)?,
         Commands::Tui { brief, view } => {
             output.verbose_ctx(
                 "tui",
                 &format!("Launching TUI, brief={:?}, view={}", brief, view),
             );
-            tui::run(&output, brief.as_deref(), &view)?
+            tui::run(output, brief.as_deref(), &view)?
         }
 
         // Agent coordination commands (top-level)
@@ -434,22 +1223,31 @@ pub fn run() -> Result<()> {
             agent,
             force,
             reason,
+            allow_agent,
         } => agent::run(
             agent::AgentCommands::Claim {
                 id,
                 agent,
                 force,
                 reason,
+                allow_agent,
             },
-            &output,
+            output,
         )?,
-        Commands::Unclaim { id } => agent::run(agent::AgentCommands::Unclaim { id }, &output)?,
-        Commands::Next { brief, n } => {
-            agent::run(agent::AgentCommands::Next { brief, n }, &output)?
-        }
-        Commands::Note { id, text } => {
-            agent::run(agent::AgentCommands::Note { id, text }, &output)?
-        }
+        Commands::Unclaim { id } => agent::run(agent::AgentCommands::Unclaim { id }, output)?,
+        Commands::Next {
+            brief,
+            n,
+            interests,
+        } => agent::run(
+            agent::AgentCommands::Next {
+                brief,
+                n,
+                interests,
+            },
+            output,
+        )?,
+        Commands::Note { id, text } => agent::run(agent::AgentCommands::Note { id, text }, output)?,
         Commands::Link {
             id,
             commit,
@@ -464,7 +1262,7 @@ pub fn run() -> Result<()> {
                 file,
                 url,
             },
-            &output,
+            output,
         )?,
         Commands::Unlink {
             id,
@@ -480,7 +1278,7 @@ pub fn run() -> Result<()> {
                 file,
                 url,
             },
-            &output,
+            output,
         )?,
         Commands::Block {
             id,
@@ -492,13 +1290,31 @@ pub fn run() -> Result<()> {
                 reason,
                 on_task,
             },
-            &output,
+            output,
+        )?,
+        Commands::Unblock { id } => agent::run(agent::AgentCommands::Unblock { id }, output)?,
+        Commands::Freeze { id, reason } => freeze::freeze(output, &id, &reason)?,
+        Commands::Unfreeze { id } => freeze::unfreeze(output, &id)?,
+        Commands::Undo { last } => undo::undo(output, last)?,
+        Commands::Redo => undo::redo(output)?,
+        Commands::History { id, filter, page } => {
+            agent::run(agent::AgentCommands::History { id, filter, page }, output)?
+        }
+        Commands::Summary { id } => agent::run(agent::AgentCommands::Summary { id }, output)?,
+        Commands::Mine {
+            agent,
+            days,
+            mentions,
+        } => agent::run(
+            agent::AgentCommands::Mine {
+                agent,
+                days,
+                mentions,
+            },
+            output,
         )?,
-        Commands::Unblock { id } => agent::run(agent::AgentCommands::Unblock { id }, &output)?,
-        Commands::History { id } => agent::run(agent::AgentCommands::History { id }, &output)?,
-        Commands::Summary { id } => agent::run(agent::AgentCommands::Summary { id }, &output)?,
         Commands::Handoff { id, reason, to } => {
-            agent::run(agent::AgentCommands::Handoff { id, reason, to }, &output)?
+            agent::run(agent::AgentCommands::Handoff { id, reason, to }, output)?
         }
     }
 
@@ -506,8 +1322,57 @@ pub fn run() -> Result<()> {
     Ok(())
 }
 
+/// Runs a command that writes several times behind a write fence, so the
+/// daemon commits it once
+fn fenced(operation: &str, command: impl FnOnce() -> Result<()>) -> Result<()> {
+    let _fence = match Project::open_current() {
+        Ok(project) => Some(project.write_fence(operation)?),
+        Err(_) => None,
+    };
+    command()
+}
+
+/// Runs `shape interactive`. Each command line is parsed like a fresh
+/// invocation and prints plain text unless it asks for another format.
+fn run_interactive(output: &Output) -> Result<()> {
+    let exec = |args: &[String]| -> Result<()> {
+        let matches = match Cli::command()
+            .try_get_matches_from(std::iter::once("shape".to_string()).chain(args.to_vec()))
+        {
+            Ok(matches) => matches,
+            // --help and --version
+            Err(e) if !e.use_stderr() => {
+                print!("{}", e);
+                return Ok(());
+            }
+            Err(e) => {
+                let rendered = e.to_string();
+                let message = rendered.lines().next().unwrap_or_default();
+                let message = message.trim_start_matches("error: ");
+                return Err(error::CliError::new(
+                    error::ErrorCode::Validation,
+                    format!(
+                        "{} (enter a command followed by --help for its usage)",
+                        message
+                    ),
+                )
+                .into());
+            }
+        };
+        let cli = Cli::from_arg_matches(&matches)?;
+        check_format(&command_path(&matches), cli.format)?;
+        let mut inner = Output::new(cli.format, output.is_verbose())
+            .with_projection(Projection::new(cli.fields, cli.exclude));
+        if cli.format == OutputFormat::Text {
+            inner = inner.with_plain();
+        }
+        dispatch(cli.command, &inner)
+    };
+    interactive::run(output, &mut std::io::stdin().lock(), &exec)
+}
+
 /// Search tasks and briefs using the SQLite cache
-fn search(output: &Output, query: &str) -> Result<()> {
+fn search(output: &Output, query: &str, include_archived: bool, paging: &PageArgs) -> Result<()> {
     use crate::storage::SearchResultType;
 
     let project = Project::open_current()?;
@@ -516,11 +1381,24 @@ fn search(output: &Output, query: &str) -> Result<()> {
     // Ensure cache is up to date
     let cache = project.get_or_rebuild_cache()?;
 
-    let results = cache.search(query)?;
+    // `jira:ABC-123` would read as a column filter; search it as a phrase
+    let fts_query = if parse_external_ref(query).is_some() {
+        format!("\"{}\"", query.replace('"', "\"\""))
+    } else {
+        query.to_string()
+    };
+    let results = cache.search_page(&fts_query, paging.sql_limit(), paging.start()?)?;
+    let page = paging.page(results)?;
+    let results = &page.items;
     output.verbose_ctx("search", &format!("Found {} results", results.len()));
+    let archived = if include_archived {
+        search_archive(&project, query)?
+    } else {
+        Vec::new()
+    };
 
     if output.is_json() {
-        let items: Vec<_> = results
+        let mut items: Vec<_> = results
             .iter()
             .map(|r| {
                 serde_json::json!({
@@ -534,15 +1412,24 @@ fn search(output: &Output, query: &str) -> Result<()> {
                 })
             })
             .collect();
-        output.data(&items);
-    } else if results.is_empty() {
+        items.extend(archived.iter().map(|(kind, id, title)| {
+            serde_json::json!({
+                "id": id,
+                "title": title,
+                "snippet": "",
+                "type": kind,
+                "archived": true,
+            })
+        }));
+        output.data(&page.json(items));
+    } else if results.is_empty() && archived.is_empty() {
         println!("No results found for '{}'", query);
     } else {
         println!("Search results for '{}':", query);
         println!("{:<12} {:<20} TITLE", "TYPE", "ID");
         println!("{}", "-".repeat(70));
 
-        for result in &results {
+        for result in results {
             let type_str = match result.result_type {
                 SearchResultType::Task => "task",
                 SearchResultType::Brief => "brief",
@@ -560,13 +1447,39 @@ fn search(output: &Output, query: &str) -> Result<()> {
             }
         }
 
+        for (kind, id, title) in &archived {
+            println!("{:<12} {:<20} {} (archived)", kind, id, title);
+        }
+
         println!();
-        println!("Found {} result(s)", results.len());
+        println!("Found {} result(s)", results.len() + archived.len());
+        page.print_hint();
     }
 
     Ok(())
 }
 
+/// Archived tasks and briefs containing `query` (case-insensitive), as
+/// (type, ID, title) sorted by ID
+fn search_archive(project: &Project, query: &str) -> Result<Vec<(&'static str, String, String)>> {
+    let needle = query.to_lowercase();
+    let matches = |text: &str| text.to_lowercase().contains(&needle);
+
+    let mut found = Vec::new();
+    for brief in project.archived_brief_store().read_all()?.into_values() {
+        if matches(&brief.title) || matches(&brief.body) {
+            found.push(("brief", brief.id.to_string(), brief.title));
+        }
+    }
+    for task in project.archived_task_store().read_all()?.into_values() {
+        if matches(&task.title) || task.description.as_deref().is_some_and(matches) {
+            found.push(("task", task.id.to_string(), task.title));
+        }
+    }
+    found.sort_by(|a, b| a.1.cmp(&b.1));
+    Ok(found)
+}
+
 /// Sets up git merge driver for tasks.jsonl
 fn setup_merge_driver(output: &Output) -> Result<()> {
     use std::fs;
@@ -0,0 +1,89 @@
+//! Appetite budgets of briefs in progress
+//!
+//! `summary`, `doctor` and the TUI show how much of its appetite each brief
+//! in progress has used (see [`AppetiteBudget`]). The first time `summary`
+//! finds a brief past `appetite_warn_percent` (under `[briefs]`, 100 by
+//! default) it records an `appetite_warning` event in the brief's history
+//! and fires the `appetite_warning` hook, once per start of work.
+
+use anyhow::Result;
+use chrono::Utc;
+use serde_json::json;
+
+use super::hooks;
+use crate::domain::{AppetiteBudget, Brief, BriefEventType};
+use crate::storage::Project;
+
+pub(super) fn budget_json(budget: &AppetiteBudget) -> serde_json::Value {
+    json!({
+        "appetite": budget.appetite,
+        "started_at": budget.started_at,
+        "budget_days": budget.budget.num_days(),
+        "elapsed_days": budget.elapsed.num_days(),
+        "remaining_days": budget.remaining().num_days(),
+        "percent_used": budget.percent_used(),
+        "exceeded": budget.is_exceeded(),
+    })
+}
+
+/// Warns about briefs past the warning threshold that haven't been warned
+/// about since they started
+pub(super) fn check_budgets<'a>(
+    project: &Project,
+    briefs: impl IntoIterator<Item = &'a Brief>,
+) -> Result<()> {
+    let threshold = project.config().project.briefs.appetite_warn_percent;
+    let store = project.brief_store();
+    let now = Utc::now();
+
+    for brief in briefs {
+        // Frozen work is paused; its clock is someone else's call
+        if brief.frozen.is_some() {
+            continue;
+        }
+        let Some(budget) = AppetiteBudget::of(brief, now) else {
+            continue;
+        };
+        let already = brief
+            .history
+            .iter()
+            .any(|e| e.event == BriefEventType::AppetiteWarning && e.at >= budget.started_at);
+        if budget.percent_used() < threshold || already {
+            continue;
+        }
+
+        let Some(mut brief) = store.read(&brief.id)? else {
+            continue;
+        };
+        brief.record(
+            BriefEventType::AppetiteWarning,
+            None,
+            Some(json!({
+                "appetite": budget.appetite,
+                "percent_used": budget.percent_used(),
+            })),
+        );
+        store.write(&brief)?;
+
+        eprintln!(
+            "Warning: {} \"{}\" has used {}% of its {} appetite",
+            brief.id,
+            brief.title,
+            budget.percent_used(),
+            budget.appetite
+        );
+        let payload = json!({
+            "brief": {
+                "id": brief.id.to_string(),
+                "title": brief.title,
+                "owner": brief.owner,
+            },
+            "budget": budget_json(&budget),
+            "warn_percent": threshold,
+        });
+        if let Err(e) = hooks::fire(project, "appetite_warning", &payload) {
+            eprintln!("Warning: {:#}", e);
+        }
+    }
+    Ok(())
+}
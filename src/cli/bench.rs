@@ -0,0 +1,254 @@
+//! Bench command - times key operations on the current project
+//!
+//! Runs each operation a few times and reports the median, compared with the
+//! baseline in `.shape/.cache/bench.json` (recorded by the first run, or by
+//! `--save-baseline`). Large projects also get suggestions for the options
+//! that keep them fast: compaction, the daemon-kept cache and sharded
+//! context exports.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::json;
+
+use super::context::{self, ContextOptions};
+use super::error::{CliError, ErrorCode};
+use super::output::Output;
+use crate::domain::{DependencyGraph, TaskId, TaskStatus};
+use crate::storage::{BenchBaseline, Project, StoreSizes};
+
+/// An operation this much slower than its baseline is reported as a regression
+const REGRESSION_FACTOR: f64 = 1.5;
+
+/// Slowdowns below this many microseconds are noise, whatever the ratio
+const NOISE_US: u64 = 1_000;
+
+/// Project sizes above which `bench` suggests compaction, the daemon or shards
+const LARGE_TASK_COUNT: usize = 5_000;
+const LARGE_TASKS_BYTES: u64 = 5 * 1024 * 1024;
+const SLOW_READ_US: u64 = 200_000;
+const LARGE_CONTEXT_BYTES: usize = 256 * 1024;
+
+/// Timing of one operation
+#[derive(Debug, Serialize)]
+struct Timing {
+    operation: &'static str,
+    median_us: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    baseline_us: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    change_pct: Option<i64>,
+    regressed: bool,
+}
+
+pub fn run(output: &Output, runs: u32, save_baseline: bool) -> Result<()> {
+    if runs == 0 {
+        return Err(CliError::new(ErrorCode::Validation, "--runs must be at least 1").into());
+    }
+    let project = Project::open_current()?;
+    output.verbose_ctx("bench", &format!("Timing {} run(s) per operation", runs));
+
+    let tasks = project.task_store().read_all()?;
+    let briefs = project.brief_store().read_all_meta()?;
+    let statuses: HashMap<TaskId, TaskStatus> =
+        tasks.iter().map(|(id, t)| (id.clone(), t.status)).collect();
+    let opts = ContextOptions {
+        days: 7,
+        ..ContextOptions::default()
+    };
+    let context_bytes = serde_json::to_string(&context::generate(output, &project, &opts)?)?.len();
+
+    let medians: Vec<(&'static str, u64)> = vec![
+        (
+            "read_all",
+            median_us(runs, || {
+                project.task_store().read_all()?;
+                project.brief_store().read_all()?;
+                Ok(())
+            })?,
+        ),
+        (
+            "cache_rebuild",
+            median_us(runs, || project.rebuild_cache())?,
+        ),
+        (
+            "ready",
+            median_us(runs, || {
+                DependencyGraph::from_tasks(tasks.values())?.ready_tasks(&statuses);
+                Ok(())
+            })?,
+        ),
+        (
+            "context_export",
+            median_us(runs, || {
+                context::generate(output, &project, &opts).map(drop)
+            })?,
+        ),
+    ];
+    let read_all_us = medians[0].1;
+
+    let baseline = project.bench_baseline()?;
+    let timings: Vec<Timing> = medians
+        .iter()
+        .map(|&(operation, median_us)| {
+            let baseline_us = baseline
+                .as_ref()
+                .and_then(|b| b.operations.get(operation).copied());
+            let change_pct = baseline_us
+                .filter(|&b| b > 0)
+                .map(|b| (median_us as i64 - b as i64) * 100 / b as i64);
+            let regressed = baseline_us.is_some_and(|b| {
+                median_us as f64 > b as f64 * REGRESSION_FACTOR && median_us - b > NOISE_US
+            });
+            Timing {
+                operation,
+                median_us,
+                baseline_us,
+                change_pct,
+                regressed,
+            }
+        })
+        .collect();
+
+    let saved = save_baseline || baseline.is_none();
+    if saved {
+        project.save_bench_baseline(&BenchBaseline {
+            recorded_at: Utc::now(),
+            tasks: tasks.len(),
+            briefs: briefs.len(),
+            operations: medians
+                .iter()
+                .map(|(op, us)| (op.to_string(), *us))
+                .collect(),
+        })?;
+    }
+
+    let sizes = StoreSizes::measure(&project.shape_dir());
+    let mut suggestions = Vec::new();
+    if tasks.len() >= LARGE_TASK_COUNT || sizes.tasks_bytes >= LARGE_TASKS_BYTES {
+        suggestions.push(format!(
+            "tasks.jsonl holds {} tasks ({}): run `shape compact` to shrink old completed tasks",
+            tasks.len(),
+            format_bytes(sizes.tasks_bytes)
+        ));
+    }
+    if read_all_us >= SLOW_READ_US {
+        suggestions.push(format!(
+            "Reading the project takes {}: run `shape daemon start` to keep the SQLite cache fresh so queries skip parsing",
+            format_us(read_all_us)
+        ));
+    }
+    if context_bytes >= LARGE_CONTEXT_BYTES {
+        suggestions.push(format!(
+            "The context export is {}: write per-brief shards with `shape context --split-out DIR` so agents load only what they need",
+            format_bytes(context_bytes as u64)
+        ));
+    }
+
+    if output.is_json() {
+        output.data(&json!({
+            "runs": runs,
+            "tasks": tasks.len(),
+            "briefs": briefs.len(),
+            "tasks_bytes": sizes.tasks_bytes,
+            "context_bytes": context_bytes,
+            "operations": timings,
+            "baseline_recorded_at": baseline.as_ref().map(|b| b.recorded_at),
+            "baseline_saved": saved,
+            "suggestions": suggestions,
+        }));
+        return Ok(());
+    }
+
+    println!(
+        "Benchmarked {} tasks, {} briefs ({} run(s) each)",
+        tasks.len(),
+        briefs.len(),
+        runs
+    );
+    println!();
+    println!(
+        "{:<16} {:>10} {:>10} {:>8}",
+        "OPERATION", "MEDIAN", "BASELINE", "CHANGE"
+    );
+    for t in &timings {
+        println!(
+            "{:<16} {:>10} {:>10} {:>8}{}",
+            t.operation,
+            format_us(t.median_us),
+            t.baseline_us.map(format_us).unwrap_or_else(|| "-".into()),
+            t.change_pct
+                .map(|p| format!("{:+}%", p))
+                .unwrap_or_else(|| "-".into()),
+            if t.regressed { "  [slower]" } else { "" }
+        );
+    }
+    println!();
+    if saved {
+        println!("Baseline saved to .shape/.cache/bench.json");
+    }
+    for t in timings.iter().filter(|t| t.regressed) {
+        eprintln!(
+            "Warning: {} is slower than the baseline ({} vs {})",
+            t.operation,
+            format_us(t.median_us),
+            format_us(t.baseline_us.unwrap_or_default())
+        );
+    }
+    for suggestion in &suggestions {
+        eprintln!("Warning: {}", suggestion);
+    }
+    Ok(())
+}
+
+/// Median wall-clock time of `runs` calls to `op`, in microseconds
+fn median_us(runs: u32, mut op: impl FnMut() -> Result<()>) -> Result<u64> {
+    let mut times = Vec::with_capacity(runs as usize);
+    for _ in 0..runs {
+        let started = Instant::now();
+        op()?;
+        times.push(started.elapsed().as_micros() as u64);
+    }
+    times.sort_unstable();
+    Ok(times[times.len() / 2])
+}
+
+fn format_us(us: u64) -> String {
+    if us >= 1_000_000 {
+        format!("{:.2}s", us as f64 / 1_000_000.0)
+    } else if us >= 1_000 {
+        format!("{:.2}ms", us as f64 / 1_000.0)
+    } else {
+        format!("{}µs", us)
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_takes_the_middle_run() {
+        let mut calls = 0;
+        let median = median_us(3, || {
+            calls += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(calls, 3);
+        assert!(median < 1_000_000);
+        assert_eq!(format_us(1_500), "1.50ms");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MB");
+    }
+}
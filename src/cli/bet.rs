@@ -0,0 +1,331 @@
+//! Bet CLI commands - the ShapeUp betting table
+//!
+//! `bet open --cycle c-1` opens a table, stakeholders `bet cast` the briefs
+//! they want built with an appetite, and `bet close` tallies the bets: briefs
+//! with enough backers move to `betting` (or `in_progress` with `--start`)
+//! with the most-backed appetite. Every decision is recorded in the brief's
+//! history, and `bet report` summarizes a table.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::Utc;
+use clap::Subcommand;
+use serde_json::json;
+
+use super::error::{CliError, ErrorCode};
+use super::freeze::ensure_brief_mutable;
+use super::output::Output;
+use crate::domain::{
+    parse_appetite, Bet, BettingTable, Brief, BriefEventType, BriefId, BriefStatus, Decision,
+    Outcome, APPETITES, APPETITE_META_KEY,
+};
+use crate::storage::Project;
+
+#[derive(Subcommand)]
+pub enum BetCommands {
+    /// Open the betting table for a cycle
+    ///
+    /// Example:
+    ///   shape bet open --cycle c-1
+    Open {
+        /// Cycle the table decides
+        #[arg(long)]
+        cycle: String,
+    },
+
+    /// Bet on a brief at the open table
+    ///
+    /// Example:
+    ///   shape bet cast b-7f2a3b1 --by alice --appetite 2w
+    Cast {
+        /// Brief ID
+        brief: String,
+
+        /// Who is betting (default: the configured agent name)
+        #[arg(long)]
+        by: Option<String>,
+
+        /// Appetite: 1w, 2w or 6w
+        #[arg(long)]
+        appetite: String,
+    },
+
+    /// Close the table and move winning briefs to betting
+    Close {
+        /// Backers a brief needs to win
+        #[arg(long, default_value = "1")]
+        min_backers: usize,
+
+        /// Move winning briefs straight to in_progress
+        #[arg(long)]
+        start: bool,
+    },
+
+    /// Summarize a betting table (default: the open or most recent one)
+    Report {
+        /// Cycle of the table
+        cycle: Option<String>,
+    },
+}
+
+pub fn run(cmd: BetCommands, output: &Output) -> Result<()> {
+    match cmd {
+        BetCommands::Open { cycle } => open(output, &cycle),
+        BetCommands::Cast {
+            brief,
+            by,
+            appetite,
+        } => cast(output, &brief, by, &appetite),
+        BetCommands::Close { min_backers, start } => close(output, min_backers, start),
+        BetCommands::Report { cycle } => report(output, cycle.as_deref()),
+    }
+}
+
+fn open(output: &Output, cycle: &str) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.bet_store();
+
+    if let Some(table) = store.open_table()? {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!(
+                "The betting table for {} is still open; close it with `shape bet close`",
+                table.cycle
+            ),
+        )
+        .into());
+    }
+    if store.read(cycle)?.is_some() {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!("Cycle {} already had a betting table", cycle),
+        )
+        .into());
+    }
+
+    let table = BettingTable::open(cycle);
+    store.upsert(&table)?;
+
+    if output.is_json() {
+        output.data(&json!({ "cycle": table.cycle, "opened_at": table.opened_at }));
+    } else {
+        output.success(&format!("Opened betting table for {}", table.cycle));
+    }
+    Ok(())
+}
+
+fn cast(output: &Output, brief_str: &str, by: Option<String>, appetite: &str) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.bet_store();
+    let mut table = open_table(&project)?;
+
+    let id = project.resolve_brief_id(brief_str)?;
+    let brief = project
+        .brief_store()
+        .read(&id)?
+        .ok_or_else(|| CliError::not_found("Brief", &id))?;
+    if brief.status.is_complete() {
+        return Err(CliError::new(
+            ErrorCode::Validation,
+            format!(
+                "Brief {} is {}; only open briefs can be bet on",
+                id, brief.status
+            ),
+        )
+        .into());
+    }
+    let appetite = parse_appetite(appetite).ok_or_else(|| {
+        CliError::new(
+            ErrorCode::Validation,
+            format!(
+                "Invalid appetite: {} (expected 1w, 2w or 6w: {})",
+                appetite,
+                APPETITES.join(", ")
+            ),
+        )
+    })?;
+    let by = by.unwrap_or_else(|| project.config().project.agent.effective_name());
+
+    table.cast(Bet {
+        brief: id.clone(),
+        by: by.clone(),
+        appetite: appetite.to_string(),
+        at: Utc::now(),
+    });
+    store.upsert(&table)?;
+
+    if output.is_json() {
+        output.data(&json!({
+            "cycle": table.cycle,
+            "brief": id.to_string(),
+            "by": by,
+            "appetite": appetite,
+        }));
+    } else {
+        output.success(&format!(
+            "{} bet {} on {} ({})",
+            by, appetite, id, brief.title
+        ));
+    }
+    Ok(())
+}
+
+fn close(output: &Output, min_backers: usize, start: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let brief_store = project.brief_store();
+    let mut table = open_table(&project)?;
+
+    // Check every brief first so a frozen one doesn't leave a half-closed table
+    let mut briefs = Vec::new();
+    for decision in table.tally(min_backers) {
+        let brief = brief_store
+            .read(&decision.brief)?
+            .ok_or_else(|| CliError::not_found("Brief", &decision.brief))?;
+        ensure_brief_mutable(&project, &brief)?;
+        briefs.push(brief);
+    }
+
+    let decisions = table.close(min_backers).to_vec();
+    let target = if start {
+        BriefStatus::InProgress
+    } else {
+        BriefStatus::Betting
+    };
+    for (mut brief, decision) in briefs.into_iter().zip(&decisions) {
+        let data = json!({
+            "cycle": table.cycle,
+            "appetite": decision.appetite,
+            "backers": decision.backers,
+        });
+        match decision.outcome {
+            Outcome::Bet => {
+                if matches!(brief.status, BriefStatus::Proposed | BriefStatus::Betting) {
+                    brief.set_status(target);
+                }
+                brief.set_meta(APPETITE_META_KEY, json!(decision.appetite));
+                brief.record(BriefEventType::Bet, None, Some(data));
+            }
+            Outcome::Passed => brief.record(BriefEventType::Passed, None, Some(data)),
+        }
+        brief_store.write(&brief)?;
+    }
+    project.bet_store().upsert(&table)?;
+
+    print_report(output, &project, &table)
+}
+
+fn report(output: &Output, cycle: Option<&str>) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.bet_store();
+
+    let table = match cycle {
+        Some(cycle) => store.read(cycle)?.ok_or_else(|| {
+            CliError::new(
+                ErrorCode::NotFound,
+                format!("No betting table for cycle {}", cycle),
+            )
+        })?,
+        None => match store.open_table()? {
+            Some(table) => table,
+            None => store.read_all()?.pop().ok_or_else(|| {
+                CliError::new(
+                    ErrorCode::NotFound,
+                    "No betting tables yet; open one with `shape bet open --cycle <CYCLE>`",
+                )
+            })?,
+        },
+    };
+
+    print_report(output, &project, &table)
+}
+
+/// The open table, or an error saying there is none
+fn open_table(project: &Project) -> Result<BettingTable> {
+    project.bet_store().open_table()?.ok_or_else(|| {
+        CliError::new(
+            ErrorCode::Validation,
+            "No betting table is open; open one with `shape bet open --cycle <CYCLE>`",
+        )
+        .into()
+    })
+}
+
+/// Prints a table's decisions, or the standing bets while it is open
+fn print_report(output: &Output, project: &Project, table: &BettingTable) -> Result<()> {
+    let briefs: HashMap<BriefId, Brief> = project.brief_store().read_all_meta()?;
+    let title = |id: &BriefId| briefs.get(id).map(|b| b.title.as_str()).unwrap_or("?");
+    let rows: Vec<Decision> = if table.is_open() {
+        table.tally(1)
+    } else {
+        table.decisions.clone()
+    };
+    let picked = rows.iter().filter(|d| d.outcome == Outcome::Bet).count();
+
+    if output.is_json() {
+        let rows: Vec<_> = rows
+            .iter()
+            .map(|d| {
+                let mut row = json!({
+                    "brief": d.brief.to_string(),
+                    "title": title(&d.brief),
+                    "appetite": d.appetite,
+                    "backers": d.backers,
+                });
+                if !table.is_open() {
+                    row["outcome"] = json!(d.outcome);
+                }
+                row
+            })
+            .collect();
+        output.data(&json!({
+            "cycle": table.cycle,
+            "open": table.is_open(),
+            "opened_at": table.opened_at,
+            "closed_at": table.closed_at,
+            "bets": table.bets.len(),
+            "briefs": rows,
+        }));
+        return Ok(());
+    }
+
+    match table.closed_at {
+        Some(closed) => println!(
+            "Betting table {} (closed {})",
+            table.cycle,
+            closed.format("%Y-%m-%d")
+        ),
+        None => println!("Betting table {} (open)", table.cycle),
+    }
+    if rows.is_empty() {
+        println!("  No bets placed");
+        return Ok(());
+    }
+    for d in &rows {
+        let label = match (table.is_open(), d.outcome) {
+            (true, _) => "",
+            (false, Outcome::Bet) => "BET    ",
+            (false, Outcome::Passed) => "PASSED ",
+        };
+        println!(
+            "  {}{}  {:<30} {:<8} {}",
+            label,
+            d.brief,
+            title(&d.brief),
+            d.appetite,
+            d.backers.join(", ")
+        );
+    }
+    if table.is_open() {
+        println!("{} bet(s) on {} brief(s)", table.bets.len(), rows.len());
+    } else {
+        println!(
+            "{} bet(s) on {} brief(s): {} picked, {} passed",
+            table.bets.len(),
+            rows.len(),
+            picked,
+            rows.len() - picked
+        );
+    }
+    Ok(())
+}
@@ -1,12 +1,23 @@
 //! Brief CLI commands
 
-use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Result};
 use clap::Subcommand;
 
+use super::agent::get_agent_name;
+use super::error::{CliError, ErrorCode};
+use super::freeze::{ensure_brief_mutable, ensure_task_mutable};
 use super::output::Output;
-use crate::domain::{Brief, BriefId, BriefStatus};
-use crate::plugin::{MinimalBriefType, ShapeUpBriefType};
-use crate::storage::Project;
+use super::render::{Color, Style, Theme};
+use super::task;
+use super::translate;
+use crate::domain::{
+    by_id, format_external_ids, parse_external_ref, Brief, BriefId, BriefStatus, Task, TaskId,
+    TaskStatus, TaskTree,
+};
+use crate::plugin::{self, MinimalBriefType, PrdBriefType, RfcBriefType, ShapeUpBriefType};
+use crate::storage::{BriefStore, Project};
 
 #[derive(Subcommand)]
 pub enum BriefCommands {
@@ -15,9 +26,18 @@ pub enum BriefCommands {
         /// Brief title
         title: String,
 
-        /// Brief type (default: minimal)
-        #[arg(long, short = 't', default_value = "minimal")]
+        /// Brief type: minimal, shapeup, rfc, prd or a plugin type
+        #[arg(
+            long = "type",
+            short = 't',
+            alias = "brief-type",
+            default_value = "minimal"
+        )]
         brief_type: String,
+
+        /// Epic (umbrella brief) this brief belongs to
+        #[arg(long)]
+        parent: Option<String>,
     },
 
     /// List all briefs
@@ -25,12 +45,28 @@ pub enum BriefCommands {
         /// Filter by status
         #[arg(long, short)]
         status: Option<String>,
+
+        /// Filter by owner or co-owner
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Group briefs under their epics, with rolled-up progress
+        #[arg(long)]
+        tree: bool,
+
+        /// Also list briefs moved to the archive
+        #[arg(long)]
+        include_archived: bool,
     },
 
     /// Show brief details
     Show {
         /// Brief ID
         id: String,
+
+        /// Show the translation into this language, if there is one
+        #[arg(long, value_name = "LANG")]
+        lang: Option<String>,
     },
 
     /// Update brief status
@@ -41,25 +77,184 @@ pub enum BriefCommands {
         /// New status
         status: String,
     },
+
+    /// Archive a brief, deciding what happens to its open tasks
+    ///
+    /// A brief with open tasks needs --cascade: `done` completes them,
+    /// `orphan` moves them out as standalone tasks (old IDs keep resolving)
+    /// and `block` blocks them with the archival as the reason.
+    ///
+    /// Example:
+    ///   shape brief archive b-1234567 --cascade orphan
+    Archive {
+        /// Brief ID
+        id: String,
+
+        /// What to do with tasks that aren't done
+        #[arg(long, value_enum)]
+        cascade: Option<ArchiveCascade>,
+    },
+
+    /// Set the owner (and co-owners) of a brief
+    ///
+    /// Example:
+    ///   shape brief own b-1234567 alice --co-owner bob
+    Own {
+        /// Brief ID
+        id: String,
+
+        /// Owner name
+        #[arg(required_unless_present = "clear")]
+        owner: Option<String>,
+
+        /// Co-owner name (repeatable)
+        #[arg(long = "co-owner")]
+        co_owners: Vec<String>,
+
+        /// Remove the owner and co-owners
+        #[arg(long, conflicts_with_all = ["owner", "co_owners"])]
+        clear: bool,
+    },
+
+    /// Place a brief under an epic (umbrella brief)
+    ///
+    /// Epics are one level deep: an epic cannot itself have a parent.
+    ///
+    /// Example:
+    ///   shape brief parent b-1234567 b-7654321
+    Parent {
+        /// Brief ID
+        id: String,
+
+        /// Epic brief ID
+        #[arg(required_unless_present = "clear")]
+        parent: Option<String>,
+
+        /// Make the brief top-level again
+        #[arg(long, conflicts_with = "parent")]
+        clear: bool,
+    },
+
+    /// Record the brief's ID in an external tool
+    ///
+    /// Example:
+    ///   shape brief external b-1234567 github:456
+    External {
+        /// Brief ID
+        id: String,
+
+        /// External ID as system:key (just the system with --remove)
+        external: String,
+
+        /// Forget the ID for this system
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// Export a translated copy of a brief
+    ///
+    /// The translation is stored in .shape/briefs/i18n/ and shown by
+    /// `brief show --lang`; the brief itself is unchanged.
+    ///
+    /// Example:
+    ///   shape brief export b-1234567 --translate fr --via deepl
+    Export {
+        /// Brief ID
+        id: String,
+
+        /// Language to translate into, e.g. fr or pt-BR
+        #[arg(long, value_name = "LANG")]
+        translate: String,
+
+        /// Translate plugin to use (shape-translate-<NAME>)
+        #[arg(long, value_name = "NAME")]
+        via: String,
+    },
+}
+
+/// What `brief archive` does with the brief's open tasks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ArchiveCascade {
+    /// Mark them done
+    Done,
+    /// Move them out of the brief as standalone tasks
+    Orphan,
+    /// Block them, citing the archived brief
+    Block,
+}
+
+impl ArchiveCascade {
+    fn as_str(self) -> &'static str {
+        match self {
+            ArchiveCascade::Done => "done",
+            ArchiveCascade::Orphan => "orphan",
+            ArchiveCascade::Block => "block",
+        }
+    }
 }
 
 pub fn run(cmd: BriefCommands, output: &Output) -> Result<()> {
     match cmd {
-        BriefCommands::New { title, brief_type } => new_brief(output, &title, &brief_type),
-        BriefCommands::List { status } => list_briefs(output, status.as_deref()),
-        BriefCommands::Show { id } => show_brief(output, &id),
+        BriefCommands::New {
+            title,
+            brief_type,
+            parent,
+        } => new_brief(output, &title, &brief_type, parent.as_deref()),
+        BriefCommands::List {
+            status,
+            owner,
+            tree,
+            include_archived,
+        } => list_briefs(
+            output,
+            status.as_deref(),
+            owner.as_deref(),
+            tree,
+            include_archived,
+        ),
+        BriefCommands::Show { id, lang } => show_brief(output, &id, lang.as_deref()),
         BriefCommands::Status { id, status } => set_status(output, &id, &status),
+        BriefCommands::Archive { id, cascade } => archive_brief(output, &id, cascade),
+        BriefCommands::Own {
+            id,
+            owner,
+            co_owners,
+            clear: _,
+        } => set_owner(output, &id, owner, co_owners),
+        BriefCommands::Parent {
+            id,
+            parent,
+            clear: _,
+        } => set_parent(output, &id, parent.as_deref()),
+        BriefCommands::External {
+            id,
+            external,
+            remove,
+        } => set_external_id(output, &id, &external, remove),
+        BriefCommands::Export { id, translate, via } => {
+            translate::export(output, &id, &translate, &via)
+        }
     }
 }
 
-fn new_brief(output: &Output, title: &str, brief_type: &str) -> Result<()> {
+fn new_brief(
+    output: &Output,
+    title: &str,
+    brief_type: &str,
+    parent_str: Option<&str>,
+) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.brief_store();
+    let parent = parent_str
+        .map(|p| resolve_epic(&project, &store.read_all_meta()?, None, p))
+        .transpose()?;
 
     // Get template based on type
     let template = match brief_type {
         "minimal" => MinimalBriefType::template(title),
         "shapeup" => ShapeUpBriefType::template(title),
+        "rfc" => RfcBriefType::template(title),
+        "prd" => PrdBriefType::template(title),
         _ => {
             // Fall back to minimal for unknown types
             // External plugin-based types would be loaded here
@@ -68,8 +263,10 @@ fn new_brief(output: &Output, title: &str, brief_type: &str) -> Result<()> {
     };
 
     // Create brief
-    let mut brief = Brief::new(title, brief_type);
+    let mut brief = Brief::with_scheme(title, brief_type, project.id_scheme());
+    brief.id = project.new_brief_id(title, brief_type)?;
     brief.set_body(&template.body);
+    brief.parent = parent;
 
     // Apply template frontmatter to meta
     if let Some(obj) = template.frontmatter.as_object() {
@@ -89,6 +286,7 @@ fn new_brief(output: &Output, title: &str, brief_type: &str) -> Result<()> {
             "title": brief.title,
             "type": brief.brief_type,
             "status": brief.status,
+            "parent": brief.parent.as_ref().map(|p| p.to_string()),
         }));
     } else {
         output.success(&format!("Created brief: {} ({})", brief.id, brief.title));
@@ -97,59 +295,290 @@ fn new_brief(output: &Output, title: &str, brief_type: &str) -> Result<()> {
     Ok(())
 }
 
-fn list_briefs(output: &Output, status_filter: Option<&str>) -> Result<()> {
+fn list_briefs(
+    output: &Output,
+    status_filter: Option<&str>,
+    owner_filter: Option<&str>,
+    tree: bool,
+    include_archived: bool,
+) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.brief_store();
 
-    let list = if let Some(status_str) = status_filter {
-        let status: BriefStatus = status_str
-            .parse()
-            .map_err(|_| anyhow::anyhow!("Invalid status: {}", status_str))?;
-        store
-            .list_by_status(status)?
-            .into_iter()
-            .map(|(id, title)| (id, title, status))
-            .collect()
-    } else {
-        store.list()?
-    };
+    let status = status_filter
+        .map(|s| {
+            s.parse::<BriefStatus>()
+                .map_err(|_| CliError::new(ErrorCode::Validation, format!("Invalid status: {}", s)))
+        })
+        .transpose()?;
+    let mut list = listing(&store, status, owner_filter)?;
+    let mut archived: HashSet<BriefId> = HashSet::new();
+    if include_archived {
+        let extra = listing(&project.archived_brief_store(), status, owner_filter)?;
+        archived.extend(extra.iter().map(|(id, _, _)| id.clone()));
+        list.extend(extra);
+        list.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+
+    if tree {
+        let mut briefs = store.read_all_meta()?;
+        let mut tasks = project.task_store().read_all()?;
+        if include_archived {
+            briefs.extend(project.archived_brief_store().read_all_meta()?);
+            tasks.extend(project.archived_task_store().read_all()?);
+        }
+        let listed: Vec<&Brief> = list
+            .iter()
+            .filter_map(|(id, _, _)| briefs.get(id))
+            .collect();
+        let theme = Theme::new(&project.config().project.display);
+        print_tree(output, &theme, &listed, &briefs, &tasks);
+        return Ok(());
+    }
 
     if output.is_json() {
         let items: Vec<_> = list
             .iter()
             .map(|(id, title, status)| {
-                serde_json::json!({
+                let mut item = serde_json::json!({
                     "id": id.to_string(),
                     "title": title,
                     "status": status,
-                })
+                });
+                if archived.contains(id) {
+                    item["archived"] = serde_json::json!(true);
+                }
+                item
             })
             .collect();
         output.data(&items);
     } else if list.is_empty() {
         println!("No briefs found.");
     } else {
-        println!("{:<12} {:<15} TITLE", "ID", "STATUS");
+        let theme = Theme::new(&project.config().project.display);
+        let style = Style::stdout().with_theme(&theme);
+        println!(
+            "{}{:<12} {:<15} TITLE",
+            " ".repeat(theme.lead_width()),
+            "ID",
+            "STATUS"
+        );
         println!("{}", "-".repeat(60));
-        for (id, title, status) in list {
-            println!("{:<12} {:<15} {}", id, status, title);
+        for (id, title, status) in &list {
+            let lead = style.paint(
+                &theme.lead(&theme.brief_icon(*status)),
+                status_color(*status),
+            );
+            let marker = if archived.contains(id) {
+                " (archived)"
+            } else {
+                ""
+            };
+            println!("{}{:<12} {:<15} {}{}", lead, id, status, title, marker);
         }
     }
 
     Ok(())
 }
 
-fn show_brief(output: &Output, id_str: &str) -> Result<()> {
+/// IDs, titles and statuses of the briefs in `store`, filtered by status and
+/// owner
+fn listing(
+    store: &BriefStore,
+    status: Option<BriefStatus>,
+    owner: Option<&str>,
+) -> Result<Vec<(BriefId, String, BriefStatus)>> {
+    let mut list: Vec<_> = match status {
+        Some(status) => store
+            .list_by_status(status)?
+            .into_iter()
+            .map(|(id, title)| (id, title, status))
+            .collect(),
+        None => store.list()?,
+    };
+    if let Some(owner) = owner {
+        let briefs = store.read_all_meta()?;
+        list.retain(|(id, _, _)| briefs.get(id).is_some_and(|b| b.is_owned_by(owner)));
+    }
+    Ok(list)
+}
+
+/// Prints listed briefs grouped under their epics. Briefs whose epic is not
+/// listed are shown at the top level.
+fn print_tree(
+    output: &Output,
+    theme: &Theme,
+    listed: &[&Brief],
+    briefs: &HashMap<BriefId, Brief>,
+    tasks: &HashMap<TaskId, Task>,
+) {
+    let is_listed = |id: &BriefId| listed.iter().any(|b| b.id == *id);
+    let roots: Vec<&Brief> = listed
+        .iter()
+        .copied()
+        .filter(|b| b.parent.as_ref().is_none_or(|p| !is_listed(p)))
+        .collect();
+    let listed_children = |epic: &Brief| -> Vec<&Brief> {
+        children(briefs, &epic.id)
+            .into_iter()
+            .filter(|c| is_listed(&c.id))
+            .collect()
+    };
+
+    if output.is_json() {
+        let node = |b: &Brief| {
+            let (done, total) = rollup(briefs, tasks, &b.id);
+            serde_json::json!({
+                "id": b.id.to_string(),
+                "title": b.title,
+                "status": b.status,
+                "progress": { "done": done, "total": total },
+            })
+        };
+        let items: Vec<_> = roots
+            .iter()
+            .map(|root| {
+                let mut item = node(root);
+                item["children"] = listed_children(root).into_iter().map(node).collect();
+                item
+            })
+            .collect();
+        output.data(&items);
+        return;
+    }
+
+    if roots.is_empty() {
+        println!("No briefs found.");
+        return;
+    }
+    let style = Style::stdout().with_theme(theme);
+    println!(
+        "{}{:<14} {:<15} {:>7}  TITLE",
+        " ".repeat(theme.lead_width()),
+        "ID",
+        "STATUS",
+        "DONE"
+    );
+    println!("{}", "-".repeat(60));
+    let row = |b: &Brief, indent: &str| {
+        let (done, total) = rollup(briefs, tasks, &b.id);
+        let lead = style.paint(
+            &theme.lead(&theme.brief_icon(b.status)),
+            status_color(b.status),
+        );
+        println!(
+            "{}{:<14} {:<15} {:>7}  {}",
+            lead,
+            format!("{}{}", indent, b.id),
+            b.status,
+            format!("{}/{}", done, total),
+            b.title
+        );
+    };
+    for root in roots {
+        row(root, "");
+        for child in listed_children(root) {
+            row(child, "  ");
+        }
+    }
+}
+
+fn status_color(status: BriefStatus) -> Color {
+    match status {
+        BriefStatus::Proposed => Color::Dim,
+        BriefStatus::Betting => Color::Magenta,
+        BriefStatus::InProgress => Color::Yellow,
+        BriefStatus::Shipped => Color::Green,
+        BriefStatus::Archived => Color::Dim,
+    }
+}
+
+/// Child briefs of an epic, in ID order
+pub(super) fn children<'a>(briefs: &'a HashMap<BriefId, Brief>, epic: &BriefId) -> Vec<&'a Brief> {
+    by_id(briefs.values().filter(|b| b.parent.as_ref() == Some(epic)))
+}
+
+/// Completed and total leaf tasks of a brief, including those of its child
+/// briefs (see [`TaskTree`])
+pub(super) fn rollup(
+    briefs: &HashMap<BriefId, Brief>,
+    tasks: &HashMap<TaskId, Task>,
+    id: &BriefId,
+) -> (usize, usize) {
+    let mut members: HashSet<BriefId> = children(briefs, id)
+        .into_iter()
+        .map(|b| b.id.clone())
+        .collect();
+    members.insert(id.clone());
+
+    let progress = TaskTree::new(
+        tasks
+            .values()
+            .filter(|t| t.brief_id().is_some_and(|b| members.contains(&b))),
+    )
+    .total_progress();
+    (progress.done, progress.total)
+}
+
+/// Resolves an epic for `child` (or a brief being created), keeping the
+/// hierarchy one level deep
+fn resolve_epic(
+    project: &Project,
+    briefs: &HashMap<BriefId, Brief>,
+    child: Option<&BriefId>,
+    epic_str: &str,
+) -> Result<BriefId> {
+    let epic_id = project.resolve_brief_id(epic_str)?;
+    let epic = briefs
+        .get(&epic_id)
+        .ok_or_else(|| CliError::not_found("Brief", &epic_id))?;
+
+    let invalid =
+        |msg: String| -> Result<BriefId> { Err(CliError::new(ErrorCode::Validation, msg).into()) };
+    if child == Some(&epic_id) {
+        return invalid(format!("{} cannot be its own epic", epic_id));
+    }
+    if let Some(grandparent) = &epic.parent {
+        return invalid(format!(
+            "{} is already under {}; epics are one level deep",
+            epic_id, grandparent
+        ));
+    }
+    if let Some(child) = child {
+        if !children(briefs, child).is_empty() {
+            return invalid(format!(
+                "{} is an epic with child briefs and cannot be nested",
+                child
+            ));
+        }
+    }
+    Ok(epic_id)
+}
+
+fn show_brief(output: &Output, id_str: &str, lang: Option<&str>) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.brief_store();
     let task_store = project.task_store();
 
-    let id: BriefId = id_str.parse()?;
+    let id = project.resolve_brief_id(id_str)?;
     let brief = store
         .read(&id)?
-        .ok_or_else(|| anyhow::anyhow!("Brief not found: {}", id))?;
+        .ok_or_else(|| CliError::not_found("Brief", &id))?;
+    let (brief, translation) = match lang {
+        Some(lang) => translate::localized(&project, brief, lang)?,
+        None => (brief, None),
+    };
+    let stale = translation.as_ref().is_some_and(|t| t.is_stale(&brief));
 
+    let warnings = store.warnings(&id)?;
     let tasks = task_store.read_for_brief(&id)?;
+    let tree = TaskTree::new(tasks.values());
+    let progress = tree.total_progress();
+    let briefs = store.read_all_meta()?;
+    let child_briefs = children(&briefs, &id);
+    let rolled_up = (!child_briefs.is_empty())
+        .then(|| task_store.read_all().map(|all| rollup(&briefs, &all, &id)))
+        .transpose()?;
 
     if output.is_json() {
         output.data(&serde_json::json!({
@@ -157,22 +586,99 @@ fn show_brief(output: &Output, id_str: &str) -> Result<()> {
             "title": brief.title,
             "type": brief.brief_type,
             "status": brief.status,
+            "stage": plugin::stage_name(&brief.brief_type, brief.status),
             "created_at": brief.created_at,
             "updated_at": brief.updated_at,
             "body": brief.body,
+            "summary": brief.current_summary(),
+            "reading_minutes": brief.reading_minutes(),
+            "owner": brief.owner,
+            "co_owners": brief.co_owners,
+            "external_ids": brief.external_ids,
+            "frozen": brief.frozen,
+            "parent": brief.parent.as_ref().map(|p| p.to_string()),
+            "history": brief.history,
+            "children": child_briefs.iter().map(|c| c.id.to_string()).collect::<Vec<_>>(),
+            "rollup": rolled_up.map(|(done, total)| serde_json::json!({
+                "done": done,
+                "total": total,
+            })),
             "meta": brief.meta,
-            "tasks": tasks.values().map(|t| serde_json::json!({
+            "progress": task::progress_json(progress),
+            "tasks": by_id(tasks.values()).into_iter().map(|t| serde_json::json!({
                 "id": t.id.to_string(),
                 "title": t.title,
                 "status": t.status,
+                "rollup_status": tree.rollup_status(t),
+                "progress": task::progress_json(tree.progress(t)),
             })).collect::<Vec<_>>(),
+            "warnings": warnings,
+            "translation": translation.as_ref().map(|t| serde_json::json!({
+                "lang": t.lang,
+                "via": t.via,
+                "translated_at": t.translated_at,
+                "stale": stale,
+            })),
+            "translations": project.translation_store().languages(&id)?,
         }));
     } else {
+        for warning in &warnings {
+            eprintln!("Warning: {}.md {}", brief.id, warning);
+        }
+        match (lang, &translation) {
+            (Some(lang), None) => eprintln!(
+                "Warning: no {} translation of {}; showing the canonical brief",
+                lang, brief.id
+            ),
+            _ if stale => eprintln!(
+                "Warning: the brief changed after it was translated; re-export it with shape brief export {} --translate {}",
+                brief.id,
+                lang.unwrap_or_default()
+            ),
+            _ => {}
+        }
         println!("Brief: {} ({})", brief.id, brief.brief_type);
         println!("Title: {}", brief.title);
-        println!("Status: {}", brief.status);
+        if let Some(t) = &translation {
+            println!(
+                "Language: {} (translated {} via {})",
+                t.lang,
+                t.translated_at.format("%Y-%m-%d"),
+                t.via
+            );
+        }
+        println!("Status: {}", status_label(&brief));
+        if let Some(owner) = owner_label(&brief) {
+            println!("Owner: {}", owner);
+        }
+        if !brief.external_ids.is_empty() {
+            println!("External: {}", format_external_ids(&brief.external_ids));
+        }
+        if let Some(frozen) = &brief.frozen {
+            println!("Frozen: {} (by {})", frozen.reason, frozen.by);
+        }
+        if let Some(parent) = &brief.parent {
+            let title = briefs.get(parent).map(|p| p.title.as_str()).unwrap_or("?");
+            println!("Epic: {} ({})", parent, title);
+        }
+        if let Some((done, total)) = rolled_up {
+            println!(
+                "Child briefs: {} ({}/{} tasks done overall)",
+                child_briefs.len(),
+                done,
+                total
+            );
+        }
         println!("Created: {}", brief.created_at.format("%Y-%m-%d %H:%M"));
         println!("Updated: {}", brief.updated_at.format("%Y-%m-%d %H:%M"));
+        if !brief.body.is_empty() {
+            println!("Reading time: {} min", brief.reading_minutes());
+        }
+
+        if let Some(summary) = brief.current_summary() {
+            println!("\nSummary:");
+            println!("{}", summary);
+        }
 
         if !brief.meta.is_empty() {
             println!("\nMetadata:");
@@ -181,21 +687,56 @@ fn show_brief(output: &Output, id_str: &str) -> Result<()> {
             }
         }
 
+        if !brief.history.is_empty() {
+            println!("\nHistory:");
+            for event in &brief.history {
+                let data = event.data.as_ref();
+                let field = |key: &str| data.and_then(|d| d[key].as_str()).unwrap_or("-");
+                println!(
+                    "  {} {} {} ({})",
+                    event.at.format("%Y-%m-%d"),
+                    event.event,
+                    field("cycle"),
+                    field("appetite")
+                );
+            }
+        }
+
         if !brief.body.is_empty() {
             println!("\nContent:");
             println!("{}", brief.body);
         }
 
         if !tasks.is_empty() {
-            println!("\nTasks ({}):", tasks.len());
-            for task in tasks.values() {
-                let status_icon = match task.status {
-                    crate::domain::TaskStatus::Todo => "[ ]",
-                    crate::domain::TaskStatus::InProgress => "[~]",
-                    crate::domain::TaskStatus::Done => "[x]",
-                };
-                println!("  {} {} {}", status_icon, task.id, task.title);
+            println!(
+                "\nTasks ({}, {}% done: {}/{} leaf tasks):",
+                tasks.len(),
+                progress.percent(),
+                progress.done,
+                progress.total
+            );
+            fn print_level(tree: &TaskTree, level: &[&Task], indent: &str) {
+                for task in level {
+                    let status_icon = match tree.rollup_status(task) {
+                        crate::domain::TaskStatus::Todo => "[ ]",
+                        crate::domain::TaskStatus::InProgress => "[~]",
+                        crate::domain::TaskStatus::Done => "[x]",
+                    };
+                    let children = tree.children(&task.id);
+                    let counts = if children.is_empty() {
+                        String::new()
+                    } else {
+                        let p = tree.progress(task);
+                        format!(" ({}/{})", p.done, p.total)
+                    };
+                    println!(
+                        "{}{} {} {}{}",
+                        indent, status_icon, task.id, task.title, counts
+                    );
+                    print_level(tree, children, &format!("{}  ", indent));
+                }
             }
+            print_level(&tree, tree.roots(), "  ");
         }
     }
 
@@ -206,14 +747,35 @@ fn set_status(output: &Output, id_str: &str, status_str: &str) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.brief_store();
 
-    let id: BriefId = id_str.parse()?;
+    let id = project.resolve_brief_id(id_str)?;
     let mut brief = store
         .read(&id)?
-        .ok_or_else(|| anyhow::anyhow!("Brief not found: {}", id))?;
+        .ok_or_else(|| CliError::not_found("Brief", &id))?;
+    ensure_brief_mutable(&project, &brief)?;
+
+    // Types with their own lifecycle (rfc, prd) accept stage names too
+    let status = match plugin::stage_status(&brief.brief_type, status_str) {
+        Some(status) => status,
+        None => status_str.parse().map_err(|_| {
+            CliError::new(
+                ErrorCode::Validation,
+                format!("Invalid status: {}", status_str),
+            )
+        })?,
+    };
 
-    let status: BriefStatus = status_str
-        .parse()
-        .map_err(|_| anyhow::anyhow!("Invalid status: {}", status_str))?;
+    let config = &project.config().project;
+    if status == BriefStatus::Shipped && config.briefs.owner_only_ship && brief.owner.is_some() {
+        let agent = config.agent.effective_name();
+        if !brief.is_owned_by(&agent) {
+            anyhow::bail!(
+                "Only the owner of {} ({}) may mark it shipped; you are '{}'",
+                brief.id,
+                owner_label(&brief).unwrap_or_default(),
+                agent
+            );
+        }
+    }
 
     brief.set_status(status);
     store.write(&brief)?;
@@ -222,10 +784,300 @@ fn set_status(output: &Output, id_str: &str, status_str: &str) -> Result<()> {
         output.data(&serde_json::json!({
             "id": brief.id.to_string(),
             "status": brief.status,
+            "stage": plugin::stage_name(&brief.brief_type, brief.status),
+        }));
+    } else {
+        output.success(&format!(
+            "Updated {} status to {}",
+            brief.id,
+            status_label(&brief)
+        ));
+    }
+
+    Ok(())
+}
+
+fn archive_brief(output: &Output, id_str: &str, cascade: Option<ArchiveCascade>) -> Result<()> {
+    let project = Project::open_current()?;
+    let brief_store = project.brief_store();
+    let task_store = project.task_store();
+
+    let id = project.resolve_brief_id(id_str)?;
+    let mut brief = brief_store
+        .read(&id)?
+        .ok_or_else(|| CliError::not_found("Brief", &id))?;
+    ensure_brief_mutable(&project, &brief)?;
+
+    let agent = get_agent_name(&project, None);
+    // The cascade reads and rewrites tasks under one write lock, so nothing
+    // written meanwhile is lost
+    let (open, cascade, moved, statuses) = task_store.modify(|tasks| {
+        let mut open: Vec<TaskId> = tasks
+            .values()
+            .filter(|t| t.brief_id().as_ref() == Some(&id))
+            .filter(|t| !t.status.is_complete() && !t.is_compacted())
+            .map(|t| t.id.clone())
+            .collect();
+        open.sort();
+
+        if brief.status == BriefStatus::Archived && open.is_empty() {
+            return Err(CliError::new(
+                ErrorCode::Conflict,
+                format!("Brief {} is already archived", id),
+            )
+            .into());
+        }
+        let cascade = match cascade {
+            Some(cascade) => cascade,
+            None if open.is_empty() => ArchiveCascade::Done,
+            None => {
+                return Err(CliError::new(
+                    ErrorCode::Validation,
+                    format!(
+                        "Brief {} has {} open task(s); choose what happens to them with --cascade done|orphan|block",
+                        id,
+                        open.len()
+                    ),
+                )
+                .into())
+            }
+        };
+        for task_id in &open {
+            ensure_task_mutable(&project, &tasks[task_id])?;
+        }
+
+        let mut moved: Vec<(TaskId, TaskId)> = Vec::new();
+        for task_id in &open {
+            let task = tasks.get_mut(task_id).expect("open task");
+            match cascade {
+                ArchiveCascade::Done => task.complete(),
+                ArchiveCascade::Block => {
+                    task.block(format!("Brief {} was archived", id), &agent, None)
+                }
+                ArchiveCascade::Orphan => {
+                    let new = project.new_standalone_task_id(&task.title)?;
+                    if moved.iter().any(|(_, taken)| taken == &new) {
+                        bail!("Two orphaned tasks would get the ID {}; retry", new);
+                    }
+                    moved.push((task_id.clone(), new));
+                }
+            }
+            let from = (cascade == ArchiveCascade::Orphan).then_some(task_id);
+            task.record_brief_archived(&id, cascade.as_str(), &agent, from);
+        }
+
+        if !moved.is_empty() {
+            let map: HashMap<TaskId, TaskId> = moved.iter().cloned().collect();
+            *tasks = std::mem::take(tasks)
+                .into_values()
+                .map(|mut task| {
+                    task.remap_ids(|id| map.get(id).cloned());
+                    (task.id.clone(), task)
+                })
+                .collect();
+        }
+
+        let statuses: HashMap<TaskId, TaskStatus> = open
+            .iter()
+            .map(|old| {
+                let new = moved.iter().find(|(o, _)| o == old).map(|(_, n)| n);
+                let id = new.unwrap_or(old);
+                (id.clone(), tasks[id].status)
+            })
+            .collect();
+        Ok((open, cascade, moved, statuses))
+    })?;
+
+    if !moved.is_empty() {
+        // Old IDs keep resolving for agents that still hold them
+        let changes: Vec<(String, String)> = moved
+            .iter()
+            .map(|(old, new)| (old.to_string(), new.to_string()))
+            .collect();
+        project.record_redirects(&changes, "brief archive --cascade orphan")?;
+        super::ids::remap_sync_mappings(&project, &changes)?;
+    }
+
+    brief.set_status(BriefStatus::Archived);
+    brief_store.write(&brief)?;
+
+    if output.is_json() {
+        let affected: Vec<_> = open
+            .iter()
+            .map(|old| {
+                let new = moved.iter().find(|(o, _)| o == old).map(|(_, n)| n);
+                let id = new.unwrap_or(old);
+                serde_json::json!({
+                    "id": id.to_string(),
+                    "was": new.map(|_| old.to_string()),
+                    "status": statuses[id],
+                })
+            })
+            .collect();
+        output.data(&serde_json::json!({
+            "id": brief.id.to_string(),
+            "status": brief.status,
+            "cascade": (!open.is_empty()).then_some(cascade.as_str()),
+            "tasks": affected,
+        }));
+    } else {
+        let what = match cascade {
+            ArchiveCascade::Done => "marked done",
+            ArchiveCascade::Orphan => "moved out as standalone tasks",
+            ArchiveCascade::Block => "blocked",
+        };
+        if open.is_empty() {
+            output.success(&format!("Archived {}", brief.id));
+        } else {
+            output.success(&format!(
+                "Archived {}; {} open task(s) {}",
+                brief.id,
+                open.len(),
+                what
+            ));
+        }
+        for (old, new) in &moved {
+            println!("  {} -> {}", old, new);
+        }
+    }
+
+    Ok(())
+}
+
+fn set_owner(
+    output: &Output,
+    id_str: &str,
+    owner: Option<String>,
+    co_owners: Vec<String>,
+) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.brief_store();
+
+    let id = project.resolve_brief_id(id_str)?;
+    let mut brief = store
+        .read(&id)?
+        .ok_or_else(|| CliError::not_found("Brief", &id))?;
+    ensure_brief_mutable(&project, &brief)?;
+
+    brief.set_owner(owner, co_owners);
+    store.write(&brief)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": brief.id.to_string(),
+            "owner": brief.owner,
+            "co_owners": brief.co_owners,
+        }));
+    } else {
+        match owner_label(&brief) {
+            Some(label) => output.success(&format!("{} is owned by {}", brief.id, label)),
+            None => output.success(&format!("Cleared owner of {}", brief.id)),
+        }
+    }
+
+    Ok(())
+}
+
+fn set_parent(output: &Output, id_str: &str, parent_str: Option<&str>) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.brief_store();
+
+    let id = project.resolve_brief_id(id_str)?;
+    let briefs = store.read_all()?;
+    let mut brief = briefs
+        .get(&id)
+        .cloned()
+        .ok_or_else(|| CliError::not_found("Brief", &id))?;
+    ensure_brief_mutable(&project, &brief)?;
+
+    let parent = parent_str
+        .map(|p| resolve_epic(&project, &briefs, Some(&id), p))
+        .transpose()?;
+    brief.set_parent(parent);
+    store.write(&brief)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": brief.id.to_string(),
+            "parent": brief.parent.as_ref().map(|p| p.to_string()),
+        }));
+    } else {
+        match &brief.parent {
+            Some(parent) => output.success(&format!("{} is now under {}", brief.id, parent)),
+            None => output.success(&format!("{} is now top-level", brief.id)),
+        }
+    }
+
+    Ok(())
+}
+
+fn set_external_id(output: &Output, id_str: &str, external: &str, remove: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.brief_store();
+
+    let id = project.resolve_brief_id(id_str)?;
+    let mut brief = store
+        .read(&id)?
+        .ok_or_else(|| CliError::not_found("Brief", &id))?;
+    ensure_brief_mutable(&project, &brief)?;
+
+    let (system, key) = parse_external_arg(external, remove)?;
+    match key {
+        Some(key) => brief.set_external_id(system, key),
+        None => {
+            if !brief.remove_external_id(system) {
+                bail!("{} has no {} ID", brief.id, system);
+            }
+        }
+    }
+    store.write(&brief)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": brief.id.to_string(),
+            "external_ids": brief.external_ids,
         }));
     } else {
-        output.success(&format!("Updated {} status to {}", brief.id, brief.status));
+        match key {
+            Some(key) => output.success(&format!("{} is {}:{}", brief.id, system, key)),
+            None => output.success(&format!("Removed {} ID from {}", system, brief.id)),
+        }
     }
 
     Ok(())
 }
+
+/// Parses the `external` argument: `system:key`, or just `system` when
+/// removing. Returns the key only when setting.
+pub(super) fn parse_external_arg(external: &str, remove: bool) -> Result<(&str, Option<&str>)> {
+    match (parse_external_ref(external), remove) {
+        (Some((system, _)), true) => Ok((system, None)),
+        (Some((system, key)), false) => Ok((system, Some(key))),
+        (None, true) if parse_external_ref(&format!("{}:-", external)).is_some() => {
+            Ok((external, None))
+        }
+        _ => bail!(
+            "Invalid external ID '{}': expected system:key (e.g. jira:ABC-123)",
+            external
+        ),
+    }
+}
+
+/// Formats the owner and co-owners, e.g. `alice (+bob, carol)`
+/// Status with the lifecycle stage for types that name their own,
+/// e.g. `in_progress (accepted)`
+fn status_label(brief: &Brief) -> String {
+    match plugin::stage_name(&brief.brief_type, brief.status) {
+        Some(stage) => format!("{} ({})", brief.status, stage),
+        None => brief.status.to_string(),
+    }
+}
+
+pub(super) fn owner_label(brief: &Brief) -> Option<String> {
+    let owner = brief.owner.as_ref()?;
+    if brief.co_owners.is_empty() {
+        Some(owner.clone())
+    } else {
+        Some(format!("{} (+{})", owner, brief.co_owners.join(", ")))
+    }
+}
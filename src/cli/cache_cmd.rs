@@ -34,6 +34,8 @@ fn rebuild(output: &Output) -> Result<()> {
     let (todo, in_progress, done) = cache.task_counts()?;
     let brief_counts = cache.brief_counts()?;
     let total_briefs: usize = brief_counts.values().sum();
+    // Briefs whose frontmatter fails validation are left out of the index
+    let skipped = project.brief_store().invalid_files()?;
 
     if output.is_json() {
         output.data(&serde_json::json!({
@@ -41,6 +43,10 @@ fn rebuild(output: &Output) -> Result<()> {
             "duration_ms": duration.as_millis(),
             "tasks": todo + in_progress + done,
             "briefs": total_briefs,
+            "skipped": skipped.iter().map(|(path, error)| serde_json::json!({
+                "file": path.display().to_string(),
+                "error": error,
+            })).collect::<Vec<_>>(),
         }));
     } else {
         output.success(&format!(
@@ -49,6 +55,9 @@ fn rebuild(output: &Output) -> Result<()> {
             todo + in_progress + done,
             total_briefs
         ));
+        for (path, error) in &skipped {
+            eprintln!("Warning: skipped {}: {}", path.display(), error);
+        }
     }
 
     Ok(())
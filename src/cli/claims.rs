@@ -0,0 +1,153 @@
+//! Claim maintenance commands
+//!
+//! `shape claims sweep` releases every claim older than the configured
+//! timeout so abandoned work shows up in `shape ready` again. Each swept task
+//! gets a note saying whose claim expired, and can be assigned to an
+//! escalation agent (e.g. `human`) for follow-up. The daemon runs the same
+//! sweep when `agent.sweep_interval_minutes` is set.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use serde::Serialize;
+
+use super::freeze::{frozen_briefs, is_frozen};
+use super::output::Output;
+use crate::domain::{Task, TaskId};
+use crate::storage::Project;
+
+#[derive(Subcommand)]
+pub enum ClaimsCommands {
+    /// Release claims that are past the claim timeout
+    ///
+    /// Example:
+    ///   shape claims sweep --assign-to human
+    Sweep {
+        /// Assign swept tasks to this agent (default: agent.sweep_assign_to)
+        #[arg(long)]
+        assign_to: Option<String>,
+
+        /// Show what would be released without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+pub fn run(cmd: ClaimsCommands, output: &Output) -> Result<()> {
+    match cmd {
+        ClaimsCommands::Sweep { assign_to, dry_run } => {
+            sweep_cmd(output, assign_to.as_deref(), dry_run)
+        }
+    }
+}
+
+/// A claim released by a sweep
+#[derive(Debug, Serialize)]
+pub(super) struct SweptClaim {
+    pub id: TaskId,
+    pub title: String,
+    pub claimed_by: String,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub assigned_to: Option<String>,
+}
+
+fn sweep_cmd(output: &Output, assign_to: Option<&str>, dry_run: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let swept = sweep_expired_claims(&project, assign_to, dry_run)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "dry_run": dry_run,
+            "swept": swept,
+        }));
+    } else if swept.is_empty() {
+        println!("No expired claims.");
+    } else {
+        let verb = if dry_run { "Would release" } else { "Released" };
+        output.success(&format!("{} {} expired claim(s)", verb, swept.len()));
+        for claim in &swept {
+            let assigned = claim
+                .assigned_to
+                .as_deref()
+                .map(|a| format!(" → {}", a))
+                .unwrap_or_default();
+            println!(
+                "  {} ({}){} {}",
+                claim.id, claim.claimed_by, assigned, claim.title
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Releases every claim past the timeout, notes why on the task, and assigns
+/// it to `assign_to` (or the configured escalation agent). With `dry_run`,
+/// only reports what would be swept.
+pub(super) fn sweep_expired_claims(
+    project: &Project,
+    assign_to: Option<&str>,
+    dry_run: bool,
+) -> Result<Vec<SweptClaim>> {
+    let agent_config = &project.config().project.agent;
+    let timeout_hours = agent_config.claim_timeout_hours;
+    let sweeper = agent_config.effective_name();
+    let assign_to = assign_to
+        .map(str::to_string)
+        .or_else(|| agent_config.sweep_assign_to.clone());
+
+    let frozen_briefs = frozen_briefs(project)?;
+    let sweep = |tasks: &mut HashMap<TaskId, Task>, apply: bool| {
+        let mut expired: Vec<_> = tasks
+            .values()
+            .filter(|t| t.is_claimed() && t.is_claim_expired(timeout_hours))
+            .filter(|t| !is_frozen(t, &frozen_briefs))
+            .map(|t| t.id.clone())
+            .collect();
+        expired.sort();
+
+        let mut swept = Vec::new();
+        for id in expired {
+            let task = tasks.get_mut(&id).expect("expired task is in the map");
+            let claimed_by = task.claimed_by.clone().unwrap_or_default();
+            let claimed_at = task.claimed_at;
+
+            if apply {
+                let since = claimed_at
+                    .map(|at| format!(" (claimed {})", at.format("%Y-%m-%d %H:%M")))
+                    .unwrap_or_default();
+                task.add_note(
+                    &sweeper,
+                    format!(
+                        "Claim by {} expired after {}h{}; released by claims sweep",
+                        claimed_by, timeout_hours, since
+                    ),
+                );
+                task.unclaim(Some(&sweeper));
+                if let Some(to) = &assign_to {
+                    task.assign(to, Some(&sweeper));
+                }
+            }
+
+            swept.push(SweptClaim {
+                id: task.id.clone(),
+                title: task.title.clone(),
+                claimed_by,
+                claimed_at,
+                assigned_to: assign_to.clone(),
+            });
+        }
+        swept
+    };
+
+    let store = project.task_store();
+    let swept = sweep(&mut store.read_all()?, false);
+    if dry_run || swept.is_empty() {
+        return Ok(swept);
+    }
+    // Sweep again under the write lock, so tasks written since the read
+    // above are kept
+    store.modify(|tasks| Ok(sweep(tasks, true)))
+}
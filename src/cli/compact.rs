@@ -5,6 +5,9 @@ use std::collections::HashMap;
 use anyhow::Result;
 use chrono::{Duration, Utc};
 
+use super::error::CliError;
+use super::freeze::{frozen_briefs, is_frozen};
+use super::guard::{confirm_bulk, BulkChange};
 use super::output::Output;
 use crate::domain::{BriefId, TaskId};
 use crate::storage::{CompactionStrategy, Project};
@@ -52,6 +55,7 @@ pub fn run(
     brief_filter: Option<&str>,
     dry_run: bool,
     strategy: Option<&str>,
+    yes: bool,
 ) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.task_store();
@@ -81,12 +85,14 @@ pub fn run(
 
     let mut tasks = store.read_all()?;
     let cutoff = Utc::now() - Duration::days(days as i64);
+    let frozen_briefs = frozen_briefs(&project)?;
 
     // Collect candidate info without holding references
     let mut candidates: Vec<CandidateInfo> = tasks
         .values()
         .filter(|t| {
             t.status.is_complete()
+                && !is_frozen(t, &frozen_briefs)
                 && !t.is_compacted()
                 && !t.is_compaction_representative()
                 && t.completed_at.map(|c| c < cutoff).unwrap_or(false)
@@ -100,7 +106,7 @@ pub fn run(
 
     // Apply brief filter if specified
     if let Some(brief_str) = brief_filter {
-        let brief_id: BriefId = brief_str.parse()?;
+        let brief_id = project.resolve_brief_id(brief_str)?;
         candidates.retain(|c| c.brief_id.as_ref() == Some(&brief_id));
         output.verbose_ctx(
             "compact",
@@ -183,25 +189,41 @@ pub fn run(
         });
 
         result.total_compacted += task_ids.len();
+    }
+
+    // Apply and save changes if not dry run
+    if !dry_run && result.total_compacted > 0 {
+        let changes: Vec<BulkChange> = result
+            .groups
+            .iter()
+            .flat_map(|g| {
+                g.task_ids.iter().map(|id| BulkChange {
+                    id: id.to_string(),
+                    title: tasks.get(id).map(|t| t.title.clone()).unwrap_or_default(),
+                    from: "done".to_string(),
+                    to: format!("compacted into {}", g.representative_id),
+                })
+            })
+            .collect();
+
+        if !confirm_bulk(output, &project, "compact", &changes, yes)? {
+            return Ok(());
+        }
 
-        // Apply changes if not dry run
-        if !dry_run {
+        for group in &result.groups {
             // Mark representative with summary and compacted task list
-            if let Some(rep) = tasks.get_mut(&representative_id) {
-                rep.set_compaction(summary.clone(), task_ids.clone());
+            if let Some(rep) = tasks.get_mut(&group.representative_id) {
+                rep.set_compaction(group.summary.clone(), group.task_ids.clone());
             }
 
             // Mark other tasks as compacted into representative
-            for task_id in task_ids.iter().skip(1) {
+            for task_id in group.task_ids.iter().skip(1) {
                 if let Some(task) = tasks.get_mut(task_id) {
-                    task.compact_into(representative_id.clone());
+                    task.compact_into(group.representative_id.clone());
                 }
             }
         }
-    }
 
-    // Save changes if not dry run
-    if !dry_run && result.total_compacted > 0 {
         store.write_all(&tasks)?;
     }
 
@@ -275,12 +297,12 @@ pub fn undo(output: &Output, task_id_str: &str) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.task_store();
 
-    let task_id: TaskId = task_id_str.parse()?;
+    let task_id = project.resolve_task_id(task_id_str)?;
     let mut tasks = store.read_all()?;
 
     let task = tasks
         .get(&task_id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", task_id))?;
+        .ok_or_else(|| CliError::not_found("Task", &task_id))?;
 
     if !task.is_compaction_representative() {
         anyhow::bail!("Task {} is not a compaction representative", task_id);
@@ -378,7 +400,7 @@ fn generate_smart_summary(titles: &[&str]) -> String {
         .filter(|(_, count)| *count >= threshold)
         .collect();
 
-    common_words.sort_by(|a, b| b.1.cmp(&a.1));
+    common_words.sort_by_key(|w| std::cmp::Reverse(w.1));
 
     if common_words.is_empty() {
         // Fall back to basic if no common theme found
@@ -0,0 +1,330 @@
+//! `shape compliance`: evidence of how a brief was built, for audits
+//!
+//! `export --brief ID` assembles one JSON archive holding the brief, every
+//! task under it (archived ones included) with its history, notes and
+//! reviews, the audit log entries that touch them, and the betting-table
+//! bets and decisions on the brief. The archive is hashed with BLAKE3 and,
+//! given a key in `SHAPE_COMPLIANCE_KEY`, signed with a keyed BLAKE3 MAC;
+//! `verify` recomputes both to show nothing was altered since.
+//!
+//! The signature is symmetric: verifying takes the same key, and anyone who
+//! holds it can sign. Keep it with whoever signs off audits, not in the repo.
+//!
+//! Anyone can strip the signature and recompute the digest, so `verify`
+//! fails unsigned archives unless `--allow-unsigned` says a digest is enough.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::Subcommand;
+use serde_json::{json, Value};
+
+use super::agent::get_agent_name;
+use super::context::git_commit;
+use super::error::{CliError, ErrorCode};
+use super::output::Output;
+use crate::domain::{Task, TaskId};
+use crate::storage::Project;
+
+/// `format` field identifying a compliance archive
+const ARCHIVE_FORMAT: &str = "shape-compliance-archive";
+
+/// Context string for deriving the MAC key from `SHAPE_COMPLIANCE_KEY`
+const KEY_CONTEXT: &str = "shape-cli 2026 compliance archive signing key";
+
+/// Signature algorithm recorded in the archive
+const SIGNATURE_ALGORITHM: &str = "blake3-keyed";
+
+#[derive(Subcommand)]
+pub enum ComplianceCommands {
+    /// Write a signed archive of a brief's tasks, history, reviews and audit
+    /// entries
+    ///
+    /// Example:
+    ///   SHAPE_COMPLIANCE_KEY=... shape compliance export --brief b-7f2a3b1
+    Export {
+        /// Brief ID
+        #[arg(long)]
+        brief: String,
+
+        /// Archive file (default: compliance-<brief>-<date>.json)
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+
+        /// Write the archive with a digest only, without SHAPE_COMPLIANCE_KEY
+        #[arg(long)]
+        unsigned: bool,
+    },
+
+    /// Check that an archive is unchanged and its signature matches
+    ///
+    /// Example:
+    ///   SHAPE_COMPLIANCE_KEY=... shape compliance verify compliance-b-7f2a3b1-20260301.json
+    Verify {
+        /// Archive file
+        file: PathBuf,
+
+        /// Accept an archive without a signature, checking its digest only
+        #[arg(long)]
+        allow_unsigned: bool,
+    },
+}
+
+pub fn run(cmd: ComplianceCommands, output: &Output) -> Result<()> {
+    match cmd {
+        ComplianceCommands::Export {
+            brief,
+            output: path,
+            unsigned,
+        } => export(output, &brief, path, unsigned),
+        ComplianceCommands::Verify {
+            file,
+            allow_unsigned,
+        } => verify(output, &file, allow_unsigned),
+    }
+}
+
+fn export(output: &Output, brief_str: &str, path: Option<PathBuf>, unsigned: bool) -> Result<()> {
+    let key = signing_key();
+    if key.is_none() && !unsigned {
+        return Err(CliError::new(
+            ErrorCode::Validation,
+            "No signing key: set SHAPE_COMPLIANCE_KEY, or pass --unsigned for an archive with only a digest",
+        )
+        .into());
+    }
+
+    let project = Project::open_current()?;
+    let brief_id = project.resolve_brief_id(brief_str)?;
+    let brief = match project.brief_store().read(&brief_id)? {
+        Some(brief) => brief,
+        None => project
+            .archived_brief_store()
+            .read(&brief_id)?
+            .ok_or_else(|| CliError::not_found("Brief", &brief_id))?,
+    };
+
+    let live = project.task_store().read_for_brief(&brief_id)?;
+    let archived = project.archived_task_store().read_for_brief(&brief_id)?;
+    let mut archived_ids: Vec<&TaskId> = archived.keys().collect();
+    archived_ids.sort();
+    let mut tasks: Vec<&Task> = live.values().chain(archived.values()).collect();
+    tasks.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let reviews: Vec<Value> = tasks
+        .iter()
+        .flat_map(|t| {
+            t.reviews.iter().map(|r| {
+                let mut review = json!(r);
+                review["task"] = json!(t.id.to_string());
+                review
+            })
+        })
+        .collect();
+
+    // Entries name the brief or one of its tasks
+    let ids: Vec<String> = std::iter::once(brief_id.to_string())
+        .chain(tasks.iter().map(|t| t.id.to_string()))
+        .collect();
+    let audit: Vec<_> = project
+        .audit_log()?
+        .into_iter()
+        .filter(|e| ids.contains(&e.id))
+        .collect();
+
+    let bets: Vec<Value> = project
+        .bet_store()
+        .read_all()?
+        .into_iter()
+        .filter_map(|table| {
+            let bets: Vec<_> = table.bets.iter().filter(|b| b.brief == brief_id).collect();
+            let decision = table.decisions.iter().find(|d| d.brief == brief_id);
+            (!bets.is_empty() || decision.is_some()).then(|| {
+                json!({
+                    "cycle": table.cycle,
+                    "bets": bets,
+                    "decision": decision,
+                })
+            })
+        })
+        .collect();
+
+    let mut archive = json!({
+        "format": ARCHIVE_FORMAT,
+        "version": 1,
+        "created_at": Utc::now(),
+        "created_by": get_agent_name(&project, None),
+        "shape_version": env!("CARGO_PKG_VERSION"),
+        "git_commit": git_commit(&project),
+        "brief_id": brief_id.to_string(),
+        "contents": {
+            "brief": brief,
+            "tasks": tasks,
+            "archived_tasks": archived_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+            "reviews": reviews,
+            "audit": audit,
+            "bets": bets,
+        },
+    });
+    let digest = digest(&archive);
+    archive["digest"] = json!(digest.to_hex().to_string());
+    archive["signature"] = match &key {
+        Some(key) => json!({
+            "algorithm": SIGNATURE_ALGORITHM,
+            "key_id": key_id(key),
+            "value": blake3::keyed_hash(key, digest.as_bytes()).to_hex().to_string(),
+        }),
+        None => Value::Null,
+    };
+
+    let path = path.unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "compliance-{}-{}.json",
+            brief_id,
+            Utc::now().format("%Y%m%d")
+        ))
+    });
+    fs::write(&path, serde_json::to_string_pretty(&archive)? + "\n")
+        .with_context(|| format!("Failed to write archive: {}", path.display()))?;
+
+    if output.is_json() {
+        output.data(&json!({
+            "archive": path.display().to_string(),
+            "brief_id": brief_id.to_string(),
+            "tasks": tasks.len(),
+            "reviews": reviews.len(),
+            "audit_entries": audit.len(),
+            "digest": archive["digest"],
+            "signed": key.is_some(),
+            "key_id": key.as_ref().map(key_id),
+        }));
+    } else {
+        output.success(&format!(
+            "Wrote compliance archive {} ({} tasks, {} reviews, {} audit entries)",
+            path.display(),
+            tasks.len(),
+            reviews.len(),
+            audit.len()
+        ));
+        match &key {
+            Some(key) => println!("  Signed with key {}", key_id(key)),
+            None => println!("  Unsigned: the digest shows accidental changes, not tampering"),
+        }
+    }
+    Ok(())
+}
+
+/// Recomputes an archive's digest and signature. Exits nonzero when either
+/// differs, when the archive is signed and no key is available to check, or
+/// when it is unsigned and `allow_unsigned` is false.
+fn verify(output: &Output, path: &Path, allow_unsigned: bool) -> Result<()> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read archive: {}", path.display()))?;
+    let mut archive: Value = serde_json::from_str(&content)
+        .map_err(|e| CliError::new(ErrorCode::Validation, format!("Invalid archive: {}", e)))?;
+    if archive["format"] != ARCHIVE_FORMAT {
+        return Err(CliError::new(
+            ErrorCode::Validation,
+            format!("{} is not a compliance archive", path.display()),
+        )
+        .into());
+    }
+
+    let fields = archive
+        .as_object_mut()
+        .expect("format field implies an object");
+    let recorded_digest = fields.remove("digest").unwrap_or(Value::Null);
+    let signature = fields.remove("signature").unwrap_or(Value::Null);
+    let digest = digest(&archive);
+    let intact = recorded_digest.as_str() == Some(digest.to_hex().as_str());
+
+    let key = signing_key();
+    // None when there is no signature, or nothing to check it with
+    let signature_ok = match (&signature, &key) {
+        (Value::Null, _) | (_, None) => None,
+        (signature, Some(key)) => Some(
+            signature["algorithm"] == SIGNATURE_ALGORITHM
+                && signature["value"]
+                    .as_str()
+                    .and_then(|v| blake3::Hash::from_hex(v).ok())
+                    .is_some_and(|v| v == blake3::keyed_hash(key, digest.as_bytes())),
+        ),
+    };
+    let signed = !signature.is_null();
+    let verified = intact
+        && if signed {
+            signature_ok == Some(true)
+        } else {
+            allow_unsigned
+        };
+
+    if output.is_json() {
+        output.data(&json!({
+            "archive": path.display().to_string(),
+            "brief_id": archive["brief_id"],
+            "verified": verified,
+            "intact": intact,
+            "signed": signed,
+            "signature_valid": signature_ok,
+            "key_id": signature["key_id"],
+        }));
+    } else {
+        println!(
+            "{}: brief {}, created {} by {}",
+            path.display(),
+            archive["brief_id"].as_str().unwrap_or("-"),
+            archive["created_at"].as_str().unwrap_or("-"),
+            archive["created_by"].as_str().unwrap_or("-")
+        );
+        if intact {
+            println!("  Digest matches");
+        } else {
+            println!("  Digest differs: the archive was changed after export");
+        }
+        match (signed, signature_ok) {
+            (false, _) if allow_unsigned => {
+                println!("  Unsigned: the digest shows accidental changes, not tampering")
+            }
+            (false, _) => println!(
+                "  Unsigned: refused, a stripped signature looks the same (use --allow-unsigned)"
+            ),
+            (true, None) => println!(
+                "  Signed with key {}, not checked: set SHAPE_COMPLIANCE_KEY",
+                signature["key_id"].as_str().unwrap_or("-")
+            ),
+            (true, Some(true)) => println!(
+                "  Signature valid (key {})",
+                signature["key_id"].as_str().unwrap_or("-")
+            ),
+            (true, Some(false)) => println!(
+                "  Signature invalid: wrong key, or the archive was changed (key {})",
+                signature["key_id"].as_str().unwrap_or("-")
+            ),
+        }
+    }
+
+    if !verified {
+        anyhow::bail!("compliance archive {} did not verify", path.display());
+    }
+    Ok(())
+}
+
+/// MAC key derived from `SHAPE_COMPLIANCE_KEY`, if set
+fn signing_key() -> Option<[u8; 32]> {
+    let secret = std::env::var("SHAPE_COMPLIANCE_KEY")
+        .ok()
+        .filter(|s| !s.is_empty())?;
+    Some(blake3::derive_key(KEY_CONTEXT, secret.as_bytes()))
+}
+
+/// Short public fingerprint of a key, so a verifier can tell which key signed
+fn key_id(key: &[u8; 32]) -> String {
+    blake3::hash(key).to_hex()[..16].to_string()
+}
+
+/// Hash of the archive's canonical serialization (object keys sorted)
+fn digest(archive: &Value) -> blake3::Hash {
+    blake3::hash(archive.to_string().as_bytes())
+}
@@ -0,0 +1,61 @@
+//! Config CLI commands
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use super::output::{self, Output, OutputFormat};
+use crate::storage::Project;
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Show the effective configuration, defaults included
+    ///
+    /// Text output is TOML, as in config.toml; --format json|yaml|toml for tools.
+    ///
+    /// Example:
+    ///   shape config show project --format yaml
+    Show {
+        /// Only this part: project (.shape/config.toml) or global (user config)
+        #[arg(value_enum)]
+        section: Option<Section>,
+    },
+}
+
+/// Part of the configuration
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum Section {
+    Project,
+    Global,
+}
+
+/// Formats `shape config show` supports beyond text and JSON
+pub(super) const SHOW_FORMATS: &[OutputFormat] = output::TRANSCODED_FORMATS;
+
+pub fn run(cmd: ConfigCommands, output: &Output) -> Result<()> {
+    match cmd {
+        ConfigCommands::Show { section } => show(output, section),
+    }
+}
+
+fn show(output: &Output, section: Option<Section>) -> Result<()> {
+    let project = Project::open_current()?;
+    let config = project.config();
+
+    let value = match section {
+        Some(Section::Project) => serde_json::to_value(&config.project)?,
+        Some(Section::Global) => serde_json::to_value(&config.global)?,
+        None => serde_json::json!({
+            "project": config.project,
+            "global": config.global,
+        }),
+    };
+
+    if output.is_structured() {
+        output.data(&value);
+    } else {
+        // Shown as it would be written in config.toml
+        print!("{}", output::to_toml(&value)?);
+    }
+
+    Ok(())
+}
@@ -1,41 +1,469 @@
 //! Context export for AI agents
+//!
+//! Output is deterministic: briefs and tasks are ordered by ID and object
+//! keys are sorted, so identical project state always serializes to the same
+//! bytes. The `etag` field is a hash of that serialization; passing it back
+//! with `--etag` returns a short "not modified" response instead of the full
+//! context when nothing changed.
+//!
+//! `--split-out DIR` writes one compact file per active brief plus an
+//! `index.json` instead, so agents can load only the briefs they need.
+//!
+//! `--include-bodies selective` includes full brief bodies only where work is
+//! happening (briefs with ready or in-progress tasks) and summaries for the
+//! rest, listing every body it left out or cut under `elided_bodies`.
+//!
+//! `--bundle FILE` writes the context together with the options that produced
+//! it, BLAKE3 hashes of the source files and the git commit. `shape context
+//! verify FILE` regenerates the context and reports whether it still matches,
+//! so an agent run can be tied to the exact context it was given.
+//!
+//! `--max-tokens N` trims the export until its estimated size fits, dropping
+//! the least useful parts first (see [`Trim`]), and says what it dropped
+//! under `budget`.
+//!
+//! Every export carries `context_schema_version`. When the structure changes
+//! the version is bumped and a shim is added to [`downgrade`], so pipelines
+//! built against an older structure can pin it with `--schema-version N`.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{Duration, Utc};
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
 
-use super::output::Output;
-use crate::domain::{BriefId, DependencyGraph, TaskId, TaskStatus};
-use crate::storage::Project;
+use super::brief;
+use super::error::{CliError, ErrorCode};
+use super::output::{Output, OutputFormat};
+use crate::domain::{by_id, Brief, BriefId, DependencyGraph, Task, TaskId, TaskStatus};
+use crate::storage::{ContextConfig, Project};
+
+/// How much of each brief body the context includes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum IncludeBodies {
+    /// First 500 characters of every body (none in compact mode)
+    #[default]
+    Truncated,
+    /// Full bodies for briefs with ready or in-progress tasks, summaries for the rest
+    Selective,
+}
+
+/// What a context export selects; recorded in bundles so `context verify`
+/// can regenerate the same context
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContextOptions {
+    pub compact: bool,
+    pub brief: Option<String>,
+    pub for_task: Option<String>,
+    pub days: u32,
+    pub include_bodies: IncludeBodies,
+    /// Structure to emit; `None` means [`CONTEXT_SCHEMA_VERSION`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
+    /// Token budget the export is trimmed to fit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+}
+
+/// Version of the context structure this build emits
+pub const CONTEXT_SCHEMA_VERSION: u32 = 3;
+
+/// Oldest version `--schema-version` can still produce
+pub const OLDEST_SCHEMA_VERSION: u32 = 1;
+
+impl ContextOptions {
+    /// The schema version to emit, validated against the supported range
+    fn schema_version(&self) -> Result<u32> {
+        let version = self.schema_version.unwrap_or(CONTEXT_SCHEMA_VERSION);
+        if !(OLDEST_SCHEMA_VERSION..=CONTEXT_SCHEMA_VERSION).contains(&version) {
+            return Err(CliError::new(
+                ErrorCode::Validation,
+                format!(
+                    "Unsupported context schema version {} (supported: {}-{})",
+                    version, OLDEST_SCHEMA_VERSION, CONTEXT_SCHEMA_VERSION
+                ),
+            )
+            .into());
+        }
+        if version < 2 && self.include_bodies == IncludeBodies::Selective {
+            return Err(CliError::new(
+                ErrorCode::Validation,
+                "--include-bodies selective needs context schema version 2 or later",
+            )
+            .into());
+        }
+        Ok(version)
+    }
+}
+
+#[derive(Subcommand)]
+pub enum ContextCommands {
+    /// Check whether a bundle written by --bundle would regenerate identically
+    ///
+    /// Example:
+    ///   shape context verify run-42.ctx
+    Verify {
+        /// Bundle file
+        file: PathBuf,
+    },
+}
+
+pub fn run(cmd: ContextCommands, output: &Output) -> Result<()> {
+    match cmd {
+        ContextCommands::Verify { file } => verify(output, &file),
+    }
+}
+
+/// Formats `shape context` supports beyond text and JSON
+pub(super) const FORMATS: &[OutputFormat] = &[
+    OutputFormat::Yaml,
+    OutputFormat::Toml,
+    OutputFormat::Toon,
+    OutputFormat::Markdown,
+];
 
 /// Export project context for AI consumption
-pub fn export(output: &Output, compact: bool, brief_filter: Option<&str>, days: u32) -> Result<()> {
+pub fn export(
+    output: &Output,
+    opts: &ContextOptions,
+    etag: Option<&str>,
+    split_out: Option<&Path>,
+    bundle: Option<&Path>,
+) -> Result<()> {
     let project = Project::open_current()?;
     output.verbose_ctx(
         "context",
         &format!("Opened project at: {}", project.root().display()),
     );
 
-    let brief_store = project.brief_store();
-    let task_store = project.task_store();
+    if let Some(dir) = split_out {
+        let (briefs, tasks) = load(output, &project)?;
+        let (statuses, ready, blocked) = readiness(&tasks)?;
+        let selection = Selection {
+            ready: &ready,
+            blocked: &blocked,
+            statuses: &statuses,
+            days: opts.days,
+            selective: selective(&project, opts.include_bodies),
+        };
+        return write_shards(output, &project, dir, &briefs, &tasks, &selection);
+    }
+
+    let version = opts.schema_version()?;
+    if version < 2 && etag.is_some() {
+        return Err(CliError::new(
+            ErrorCode::Validation,
+            "--etag needs context schema version 2 or later",
+        )
+        .into());
+    }
+
+    let mut context = generate(output, &project, opts)?;
+    let current = context_etag(&context);
+    if let Some(path) = bundle {
+        return write_bundle(output, &project, opts, context, &current, path);
+    }
+
+    let revision = project.revision();
+    if etag == Some(current.as_str()) {
+        output.verbose_ctx("context", "Context unchanged since supplied etag");
+        output.data(&serde_json::json!({
+            "not_modified": true,
+            "etag": current,
+            "revision": revision,
+        }));
+        return Ok(());
+    }
+
+    if context["budget"]["fits"] == false {
+        eprintln!(
+            "Warning: context is still about {} tokens after trimming everything optional (--max-tokens {})",
+            context["budget"]["estimated_tokens"], context["budget"]["max_tokens"]
+        );
+    }
+    if output.format() == OutputFormat::Markdown {
+        print!("{}", to_markdown(&context));
+        return Ok(());
+    }
+    if version >= 2 {
+        context["etag"] = serde_json::json!(current);
+        context["revision"] = serde_json::json!(revision);
+    }
+    output.data(&context);
+    Ok(())
+}
+
+/// Fits a brief body under the brief's `###` heading: drops a leading
+/// heading repeating the title, nests the other headings below it and
+/// leaves out headings with nothing under them
+fn demote_headings(body: &str, title: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in body.trim().lines() {
+        let heading = line.starts_with('#');
+        if heading && lines.is_empty() && line.trim_start_matches('#').trim() == title {
+            continue;
+        }
+        if lines.last().is_some_and(|l| l.starts_with("###")) && (heading || line.trim().is_empty())
+        {
+            if heading {
+                lines.pop();
+            } else {
+                continue;
+            }
+        }
+        lines.push(if heading {
+            format!("###{}", line)
+        } else {
+            line.to_string()
+        });
+    }
+    if lines.last().is_some_and(|l| l.starts_with("###")) {
+        lines.pop();
+    }
+    lines.join("\n").trim().to_string()
+}
+
+/// Renders a full context as a short Markdown document to paste into a
+/// chat: briefs with their summaries, then the task sections with one line
+/// per task. Descriptions are cut to their first line.
+fn to_markdown(context: &serde_json::Value) -> String {
+    use std::fmt::Write;
+
+    let str_of = |v: &serde_json::Value, key: &str| v[key].as_str().unwrap_or_default().to_string();
+    let date = |v: &serde_json::Value, key: &str| {
+        v[key]
+            .as_str()
+            .and_then(|s| s.get(..10))
+            .map(str::to_string)
+    };
+    let items = |section: &str| -> Vec<serde_json::Value> {
+        context["tasks"][section]
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    // Ready includes tasks already started; those are listed as in progress
+    let in_progress = items("in_progress");
+    let started: HashSet<String> = in_progress.iter().map(|t| str_of(t, "id")).collect();
+    let ready: Vec<serde_json::Value> = items("ready")
+        .into_iter()
+        .filter(|t| !started.contains(&str_of(t, "id")))
+        .collect();
+    let blocked = items("blocked");
+
+    let mut md = String::from("# Project context\n\n");
+    let _ = writeln!(
+        md,
+        "{} ready, {} blocked, {} in progress",
+        ready.len(),
+        blocked.len(),
+        in_progress.len(),
+    );
+
+    let briefs = context["briefs"].as_array().cloned().unwrap_or_default();
+    if !briefs.is_empty() {
+        md.push_str("\n## Briefs\n");
+        for brief in &briefs {
+            let _ = writeln!(
+                md,
+                "\n### {}: {} ({})",
+                str_of(brief, "id"),
+                str_of(brief, "title"),
+                str_of(brief, "status")
+            );
+            let text = match brief["summary"].as_str() {
+                Some(summary) if !summary.is_empty() => summary.to_string(),
+                _ => demote_headings(&str_of(brief, "body"), &str_of(brief, "title")),
+            };
+            if !text.is_empty() {
+                let _ = writeln!(md, "\n{}", text);
+            }
+        }
+    }
+
+    let line = |task: &serde_json::Value| {
+        let mut line = format!("- `{}` {}", str_of(task, "id"), str_of(task, "title"));
+        if let Some(brief) = task["brief"].as_str() {
+            let _ = write!(line, " ({})", brief);
+        }
+        if let Some(due) = date(task, "due_at") {
+            let _ = write!(line, ", due {}", due);
+        }
+        line
+    };
+    let description = |task: &serde_json::Value| {
+        task["description"]
+            .as_str()
+            .and_then(|d| d.lines().find(|l| !l.trim().is_empty()))
+            .map(|l| format!("  {}", l.trim()))
+    };
+
+    let mut section = |title: &str, lines: Vec<String>| {
+        if !lines.is_empty() {
+            let _ = writeln!(md, "\n## {}\n", title);
+            for line in lines {
+                let _ = writeln!(md, "{}", line);
+            }
+        }
+    };
 
-    let briefs = brief_store.read_all()?;
-    let tasks = task_store.read_all()?;
+    section(
+        "In progress",
+        in_progress
+            .iter()
+            .flat_map(|t| std::iter::once(line(t)).chain(description(t)))
+            .collect(),
+    );
+    section(
+        "Ready",
+        ready
+            .iter()
+            .flat_map(|t| std::iter::once(line(t)).chain(description(t)))
+            .collect(),
+    );
+    section(
+        "Blocked",
+        blocked
+            .iter()
+            .map(|t| {
+                let blockers: Vec<String> = t["blocked_by"]
+                    .as_array()
+                    .map(|b| b.iter().map(|d| format!("`{}`", str_of(d, "id"))).collect())
+                    .unwrap_or_default();
+                if blockers.is_empty() {
+                    line(t)
+                } else {
+                    format!("{}, waiting on {}", line(t), blockers.join(", "))
+                }
+            })
+            .collect(),
+    );
+    section(
+        "Recently completed",
+        items("recently_completed")
+            .iter()
+            .map(|t| match date(t, "completed_at") {
+                Some(at) => format!("{}, done {}", line(t), at),
+                None => line(t),
+            })
+            .collect(),
+    );
+    section(
+        "Earlier work",
+        items("compacted")
+            .iter()
+            .map(|t| {
+                format!(
+                    "- {} ({} tasks)",
+                    str_of(t, "summary"),
+                    t["task_count"].as_u64().unwrap_or_default()
+                )
+            })
+            .collect(),
+    );
+
+    let truncated = context["budget"]["truncated"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    if !truncated.is_empty() {
+        let dropped: Vec<String> = truncated
+            .iter()
+            .map(|t| {
+                format!(
+                    "{} ({})",
+                    str_of(t, "section").replace('_', " "),
+                    t["dropped"].as_u64().unwrap_or_default()
+                )
+            })
+            .collect();
+        let _ = writeln!(
+            md,
+            "\n_Trimmed for a {} token budget, leaving out: {}_",
+            context["budget"]["max_tokens"],
+            dropped.join(", ")
+        );
+    }
+    md
+}
 
+fn load(
+    output: &Output,
+    project: &Project,
+) -> Result<(HashMap<BriefId, Brief>, HashMap<TaskId, Task>)> {
+    let briefs = project.brief_store().read_all()?;
+    let tasks = project.task_store().read_all()?;
     output.verbose_ctx(
         "context",
         &format!("Loaded {} briefs, {} tasks", briefs.len(), tasks.len()),
     );
+    Ok((briefs, tasks))
+}
+
+type Readiness = (HashMap<TaskId, TaskStatus>, Vec<TaskId>, Vec<TaskId>);
+
+/// Statuses plus ready and blocked task IDs. Computed over the whole project
+/// so that dependencies outside a selection are still honored.
+fn readiness(tasks: &HashMap<TaskId, Task>) -> Result<Readiness> {
+    let statuses: HashMap<TaskId, TaskStatus> =
+        tasks.iter().map(|(id, t)| (id.clone(), t.status)).collect();
+    let graph = DependencyGraph::from_tasks(tasks.values())?;
+    let ready = graph.ready_tasks(&statuses);
+    let blocked = graph.blocked_tasks(&statuses);
+    Ok((statuses, ready, blocked))
+}
+
+fn selective(project: &Project, include_bodies: IncludeBodies) -> Option<&ContextConfig> {
+    (include_bodies == IncludeBodies::Selective).then_some(&project.config().project.context)
+}
+
+/// Builds the context for `opts` without `etag` or `revision`
+pub(super) fn generate(
+    output: &Output,
+    project: &Project,
+    opts: &ContextOptions,
+) -> Result<serde_json::Value> {
+    let version = opts.schema_version()?;
+    let (briefs, tasks) = load(output, project)?;
+    let (statuses, all_ready_ids, all_blocked_ids) = readiness(&tasks)?;
+    // Filter by task closure or brief if specified
+    let (briefs, tasks) = if let Some(task_str) = opts.for_task.as_deref() {
+        let task_id = project.resolve_task_id(task_str)?;
+        output.verbose_ctx(
+            "context",
+            &format!("Selecting context for task: {}", task_id),
+        );
+
+        let selected = task_closure(&tasks, &task_id)?;
+        let filtered_tasks: HashMap<_, _> = tasks
+            .into_iter()
+            .filter(|(id, _)| selected.contains(id))
+            .collect();
+
+        output.verbose_ctx(
+            "context",
+            &format!("Selected {} tasks for task closure", filtered_tasks.len()),
+        );
 
-    // Filter by brief if specified
-    let (briefs, tasks) = if let Some(brief_str) = brief_filter {
-        let brief_id: BriefId = brief_str.parse()?;
+        let filtered_briefs: HashMap<_, _> = briefs
+            .into_iter()
+            .filter(|(id, _)| task_id.brief_id().as_ref() == Some(id))
+            .collect();
+
+        (filtered_briefs, filtered_tasks)
+    } else if let Some(brief_str) = opts.brief.as_deref() {
+        let brief_id = project.resolve_brief_id(brief_str)?;
         output.verbose_ctx("context", &format!("Filtering by brief: {}", brief_id));
 
         let brief = briefs
             .get(&brief_id)
-            .ok_or_else(|| anyhow::anyhow!("Brief not found: {}", brief_id))?;
+            .ok_or_else(|| CliError::not_found("Brief", &brief_id))?;
 
         let filtered_tasks: HashMap<_, _> = tasks
             .into_iter()
@@ -55,20 +483,573 @@ pub fn export(output: &Output, compact: bool, brief_filter: Option<&str>, days:
         (briefs, tasks)
     };
 
-    // Build status map
-    let statuses: HashMap<TaskId, TaskStatus> =
-        tasks.iter().map(|(id, t)| (id.clone(), t.status)).collect();
+    let context = build(
+        output,
+        opts.compact,
+        &briefs,
+        &tasks,
+        &all_ready_ids,
+        &all_blocked_ids,
+        &statuses,
+        opts.days,
+        selective(project, opts.include_bodies),
+    );
+    let mut context = downgrade(context, version);
+    if let Some(max_tokens) = opts.max_tokens {
+        fit_budget(&mut context, max_tokens);
+    }
+    Ok(context)
+}
 
-    // Build dependency graph
-    let graph = DependencyGraph::from_tasks(tasks.values())?;
+/// Rough token count of a context: about four bytes of serialized JSON per
+/// token, which is close for English text and IDs under common tokenizers
+fn estimate_tokens(context: &serde_json::Value) -> usize {
+    serde_json::to_string(context)
+        .unwrap_or_default()
+        .len()
+        .div_ceil(4)
+}
+
+/// What `--max-tokens` drops, in order, until the context fits. Ready,
+/// in-progress and blocked tasks themselves are always kept.
+#[derive(Debug, Clone, Copy)]
+enum Trim {
+    /// Summaries of compacted groups of old done tasks
+    Compacted,
+    /// Tasks completed within `--days`
+    RecentlyCompleted,
+    /// Task and brief metadata
+    Meta,
+    /// Brief bodies
+    Bodies,
+    /// Task descriptions
+    Descriptions,
+    /// Brief summaries
+    Summaries,
+}
+
+impl Trim {
+    const ORDER: [Trim; 6] = [
+        Trim::Compacted,
+        Trim::RecentlyCompleted,
+        Trim::Meta,
+        Trim::Bodies,
+        Trim::Descriptions,
+        Trim::Summaries,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Trim::Compacted => "compacted",
+            Trim::RecentlyCompleted => "recently_completed",
+            Trim::Meta => "meta",
+            Trim::Bodies => "brief_bodies",
+            Trim::Descriptions => "descriptions",
+            Trim::Summaries => "brief_summaries",
+        }
+    }
+
+    /// Drops this part of the context, returning how many entries went
+    fn apply(self, context: &mut serde_json::Value) -> usize {
+        let briefs = &["briefs"][..];
+        let tasks = &["tasks", "standalone_tasks"][..];
+        match self {
+            // Full exports nest task lists under `tasks`, compact ones don't
+            Trim::Compacted => clear_list(context, "compacted"),
+            Trim::RecentlyCompleted => {
+                clear_list(context, "recently_completed") + clear_list(context, "recently_done")
+            }
+            Trim::Meta => strip_key(context, &[briefs, tasks].concat(), "meta"),
+            Trim::Bodies => strip_key(context, briefs, "body"),
+            Trim::Descriptions => strip_key(context, tasks, "description"),
+            Trim::Summaries => strip_key(context, briefs, "summary"),
+        }
+    }
+}
+
+/// Empties the list `key` at the top level or under `tasks`
+fn clear_list(context: &mut serde_json::Value, key: &str) -> usize {
+    let mut cleared = 0;
+    let mut clear = |list: Option<&mut serde_json::Value>| {
+        if let Some(list) = list.and_then(|l| l.as_array_mut()) {
+            cleared += list.len();
+            list.clear();
+        }
+    };
+    clear(context.get_mut(key));
+    clear(context.get_mut("tasks").and_then(|t| t.get_mut(key)));
+    cleared
+}
+
+/// Removes `key` from every object in the lists under the top-level `roots`,
+/// returning how many objects had a non-empty value for it
+fn strip_key(context: &mut serde_json::Value, roots: &[&str], key: &str) -> usize {
+    fn walk(value: &mut serde_json::Value, key: &str) -> usize {
+        match value {
+            serde_json::Value::Array(items) => items
+                .iter_mut()
+                .map(|item| match item.as_object_mut() {
+                    Some(fields) => usize::from(fields.remove(key).is_some_and(|v| {
+                        !(v.is_null()
+                            || v.as_object().is_some_and(|o| o.is_empty())
+                            || v.as_str().is_some_and(str::is_empty))
+                    })),
+                    None => walk(item, key),
+                })
+                .sum(),
+            serde_json::Value::Object(fields) => fields.values_mut().map(|v| walk(v, key)).sum(),
+            _ => 0,
+        }
+    }
+    let mut stripped = 0;
+    for root in roots {
+        if let Some(value) = context.get_mut(*root) {
+            stripped += walk(value, key);
+        }
+    }
+    stripped
+}
+
+/// Trims the context with [`Trim::ORDER`] until its estimate is within
+/// `max_tokens`, then records the outcome under `budget`. When everything
+/// droppable is gone and it still doesn't fit, `fits` is false.
+fn fit_budget(context: &mut serde_json::Value, max_tokens: usize) {
+    let mut truncated = Vec::new();
+    let report = |truncated: &Vec<serde_json::Value>, estimate: usize| {
+        serde_json::json!({
+            "max_tokens": max_tokens,
+            "estimated_tokens": estimate,
+            "fits": estimate <= max_tokens,
+            "truncated": truncated,
+        })
+    };
+
+    // The report counts toward the budget, so estimate with it in place
+    context["budget"] = report(&truncated, 0);
+    for trim in Trim::ORDER {
+        if estimate_tokens(context) <= max_tokens {
+            break;
+        }
+        let dropped = trim.apply(context);
+        if dropped > 0 {
+            truncated.push(serde_json::json!({ "section": trim.name(), "dropped": dropped }));
+            context["budget"] = report(&truncated, 0);
+        }
+    }
+    // `elided_bodies` no longer describes bodies that were all dropped
+    if truncated
+        .iter()
+        .any(|t| t["section"] == Trim::Bodies.name())
+    {
+        if let Some(fields) = context.as_object_mut() {
+            fields.remove("elided_bodies");
+        }
+    }
+    let estimate = estimate_tokens(context);
+    context["budget"] = report(&truncated, estimate);
+}
+
+/// Converts a current context to the structure of schema version `to`.
+///
+/// Each step undoes one version's additions, so a context can be taken back
+/// to any supported version. Version 1 has no `context_schema_version` field.
+fn downgrade(mut context: serde_json::Value, to: u32) -> serde_json::Value {
+    if to >= CONTEXT_SCHEMA_VERSION {
+        context["context_schema_version"] = serde_json::json!(CONTEXT_SCHEMA_VERSION);
+        return context;
+    }
+
+    // 3 -> 2: drop due dates
+    if let Some(object) = context.as_object_mut() {
+        object.remove("due");
+    }
+    for section in ["ready", "in_progress"] {
+        let tasks = context
+            .get_mut("tasks")
+            .and_then(|t| t.get_mut(section))
+            .and_then(|t| t.as_array_mut());
+        for task in tasks.into_iter().flatten() {
+            if let Some(task) = task.as_object_mut() {
+                task.remove("due_at");
+            }
+        }
+    }
+    if to == 2 {
+        context["context_schema_version"] = serde_json::json!(2);
+        return context;
+    }
+
+    // 2 -> 1: drop brief summaries, reading time, parents, pending reviews,
+    // epics and elided bodies
+    if let Some(object) = context.as_object_mut() {
+        for key in ["pending_reviews", "epics", "elided_bodies"] {
+            object.remove(key);
+        }
+    }
+    for brief in context["briefs"].as_array_mut().into_iter().flatten() {
+        if let Some(brief) = brief.as_object_mut() {
+            for key in ["summary", "reading_minutes", "parent"] {
+                brief.remove(key);
+            }
+        }
+    }
+    context
+}
 
-    // Get ready and blocked tasks
-    let ready_ids = graph.ready_tasks(&statuses);
-    let blocked_ids = graph.blocked_tasks(&statuses);
+/// `format` field identifying a context bundle
+const BUNDLE_FORMAT: &str = "shape-context-bundle";
+
+/// Writes the context with everything needed to check it later: the options
+/// that produced it, hashes of the files it was built from and the git commit
+fn write_bundle(
+    output: &Output,
+    project: &Project,
+    opts: &ContextOptions,
+    context: serde_json::Value,
+    etag: &str,
+    path: &Path,
+) -> Result<()> {
+    let sources = source_hashes(project)?;
+    let commit = git_commit(project);
+    let bundle = serde_json::json!({
+        "format": BUNDLE_FORMAT,
+        "version": 1,
+        "created_at": Utc::now(),
+        "git_commit": commit,
+        "options": opts,
+        "sources": sources,
+        "etag": etag,
+        "context": context,
+    });
+    let content = serde_json::to_string_pretty(&bundle)?;
+    fs::write(path, content + "\n")
+        .with_context(|| format!("Failed to write bundle: {}", path.display()))?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "bundle": path.display().to_string(),
+            "etag": etag,
+            "git_commit": commit,
+            "sources": sources.len(),
+        }));
+    } else {
+        output.success(&format!(
+            "Wrote context bundle {} (etag {}, {} source files)",
+            path.display(),
+            etag,
+            sources.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Regenerates a bundle's context with its recorded options and compares it
+/// with the stored one. Exits nonzero when the context would differ.
+fn verify(output: &Output, path: &Path) -> Result<()> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read bundle: {}", path.display()))?;
+    let bundle: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| CliError::new(ErrorCode::Validation, format!("Invalid bundle: {}", e)))?;
+    if bundle["format"] != BUNDLE_FORMAT {
+        return Err(CliError::new(
+            ErrorCode::Validation,
+            format!("{} is not a context bundle", path.display()),
+        )
+        .into());
+    }
+    let opts: ContextOptions = serde_json::from_value(bundle["options"].clone())
+        .map_err(|e| CliError::new(ErrorCode::Validation, format!("Invalid bundle: {}", e)))?;
+    let recorded: BTreeMap<String, String> =
+        serde_json::from_value(bundle["sources"].clone()).unwrap_or_default();
+
+    let project = Project::open_current()?;
+    let current = context_etag(&generate(output, &project, &opts)?);
+    let matches = bundle["etag"] == current.as_str();
+
+    let sources = source_hashes(&project)?;
+    let changed: Vec<&String> = recorded
+        .keys()
+        .chain(sources.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|file| recorded.get(*file) != sources.get(*file))
+        .collect();
+    let commit = git_commit(&project);
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "reproducible": matches,
+            "etag": {
+                "bundle": bundle["etag"],
+                "current": current,
+            },
+            "changed_sources": changed,
+            "git_commit": {
+                "bundle": bundle["git_commit"],
+                "current": commit,
+            },
+        }));
+    } else {
+        if matches {
+            println!("Context reproducible (etag {})", current);
+        } else {
+            println!(
+                "Context differs: bundle etag {}, current {}",
+                bundle["etag"].as_str().unwrap_or("-"),
+                current
+            );
+        }
+        for file in &changed {
+            println!("  changed: {}", file);
+        }
+        if bundle["git_commit"].as_str() != commit.as_deref() {
+            println!(
+                "  git commit: {} -> {}",
+                bundle["git_commit"].as_str().unwrap_or("-"),
+                commit.as_deref().unwrap_or("-")
+            );
+        }
+    }
+
+    if !matches {
+        anyhow::bail!("context bundle {} is not reproducible", path.display());
+    }
+    Ok(())
+}
+
+/// BLAKE3 hashes of the files a context is built from, keyed by path
+/// relative to the project root
+fn source_hashes(project: &Project) -> Result<BTreeMap<String, String>> {
+    let shape_dir = project.shape_dir();
+    let mut files = vec![
+        shape_dir.join("config.toml"),
+        project.task_store().path().to_path_buf(),
+    ];
+    let briefs_dir = project.brief_store().dir().to_path_buf();
+    if let Ok(entries) = fs::read_dir(&briefs_dir) {
+        files.extend(
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "md")),
+        );
+    }
+
+    let mut hashes = BTreeMap::new();
+    for file in files {
+        let Ok(bytes) = fs::read(&file) else {
+            continue;
+        };
+        let name = project
+            .relative_path(&file)
+            .unwrap_or_else(|| file.clone())
+            .to_string_lossy()
+            .replace('\\', "/");
+        hashes.insert(name, blake3::hash(&bytes).to_hex().to_string());
+    }
+    Ok(hashes)
+}
+
+/// Current `HEAD` commit, if the project is in a git repository
+pub(super) fn git_commit(project: &Project) -> Option<String> {
+    let out = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(project.root())
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Project-wide state shared by every shard
+struct Selection<'a> {
+    ready: &'a [TaskId],
+    blocked: &'a [TaskId],
+    statuses: &'a HashMap<TaskId, TaskStatus>,
+    days: u32,
+    selective: Option<&'a ContextConfig>,
+}
+
+/// Name of the shard index in a `--split-out` directory
+const SHARD_INDEX: &str = "index.json";
+
+/// Writes one compact context file per active brief (plus one for standalone
+/// tasks) and an index describing them, so agents can load only the briefs
+/// they need. Shards listed in a previous index but no longer active are
+/// removed.
+fn write_shards(
+    output: &Output,
+    project: &Project,
+    dir: &Path,
+    briefs: &HashMap<BriefId, Brief>,
+    tasks: &HashMap<TaskId, Task>,
+    selection: &Selection,
+) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    let previous = previous_shards(dir);
+
+    let shard = |shard_briefs: &HashMap<BriefId, Brief>, shard_tasks: &HashMap<TaskId, Task>| {
+        build(
+            output,
+            true,
+            shard_briefs,
+            shard_tasks,
+            selection.ready,
+            selection.blocked,
+            selection.statuses,
+            selection.days,
+            selection.selective,
+        )
+    };
+
+    let mut written = Vec::new();
+    let mut entries = Vec::new();
+    for brief in by_id(briefs.values()) {
+        if brief.status.is_complete() {
+            continue;
+        }
+        let brief_tasks: HashMap<_, _> = tasks
+            .iter()
+            .filter(|(_, t)| t.brief_id().as_ref() == Some(&brief.id))
+            .map(|(id, t)| (id.clone(), t.clone()))
+            .collect();
+        let brief_map = HashMap::from([(brief.id.clone(), brief.clone())]);
+        let context = shard(&brief_map, &brief_tasks);
+
+        let file = format!("{}.json", brief.id);
+        let mut entry = shard_entry(&file, &context, brief_tasks.len());
+        entry["id"] = serde_json::json!(brief.id.to_string());
+        entry["title"] = serde_json::json!(brief.title);
+        entry["status"] = serde_json::json!(brief.status);
+        if let Some(summary) = brief.current_summary() {
+            entry["summary"] = serde_json::json!(summary);
+        }
+        write_json(&dir.join(&file), &context)?;
+        written.push(file);
+        entries.push(entry);
+    }
+
+    let standalone_tasks: HashMap<_, _> = tasks
+        .iter()
+        .filter(|(_, t)| t.is_standalone())
+        .map(|(id, t)| (id.clone(), t.clone()))
+        .collect();
+    let standalone = if standalone_tasks.is_empty() {
+        serde_json::Value::Null
+    } else {
+        let file = "standalone.json".to_string();
+        let context = shard(&HashMap::new(), &standalone_tasks);
+        let entry = shard_entry(&file, &context, standalone_tasks.len());
+        write_json(&dir.join(&file), &context)?;
+        written.push(file);
+        entry
+    };
+
+    let mut index = serde_json::json!({
+        "briefs": entries,
+        "standalone": standalone,
+        "context_schema_version": CONTEXT_SCHEMA_VERSION,
+    });
+    index["etag"] = serde_json::json!(context_etag(&index));
+    index["revision"] = serde_json::json!(project.revision());
+    write_json(&dir.join(SHARD_INDEX), &index)?;
+
+    for stale in previous.iter().filter(|f| !written.contains(f)) {
+        let _ = fs::remove_file(dir.join(stale));
+    }
+
+    output.verbose_ctx(
+        "context",
+        &format!("Wrote {} shard(s) to {}", written.len(), dir.display()),
+    );
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "dir": dir.display().to_string(),
+            "index": SHARD_INDEX,
+            "files": written,
+            "etag": index["etag"],
+        }));
+    } else {
+        output.success(&format!(
+            "Wrote {} context shard(s) and {} to {}",
+            written.len(),
+            SHARD_INDEX,
+            dir.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Index entry for a shard: its file, content hash and task counts
+fn shard_entry(file: &str, context: &serde_json::Value, tasks: usize) -> serde_json::Value {
+    let count = |key: &str| context[key].as_array().map_or(0, |a| a.len());
+    serde_json::json!({
+        "file": file,
+        "etag": context_etag(context),
+        "tasks": tasks,
+        "ready": count("ready"),
+        "in_progress": count("in_progress"),
+        "blocked": count("blocked"),
+    })
+}
+
+/// Files listed in an existing shard index
+fn previous_shards(dir: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(dir.join(SHARD_INDEX)) else {
+        return Vec::new();
+    };
+    let Ok(index) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return Vec::new();
+    };
+    index["briefs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .chain(std::iter::once(&index["standalone"]))
+        .filter_map(|entry| entry["file"].as_str())
+        // Only plain file names, never paths out of the directory
+        .filter(|f| !f.contains('/') && !f.contains('\\') && *f != SHARD_INDEX)
+        .map(str::to_string)
+        .collect()
+}
+
+fn write_json(path: &Path, value: &serde_json::Value) -> Result<()> {
+    let mut content = serde_json::to_string(value)?;
+    content.push('\n');
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Builds the context for a selection of briefs and tasks. Ready and blocked
+/// IDs come from the whole project and are narrowed to the selection.
+#[allow(clippy::too_many_arguments)]
+fn build(
+    output: &Output,
+    compact: bool,
+    briefs: &HashMap<BriefId, Brief>,
+    tasks: &HashMap<TaskId, Task>,
+    all_ready_ids: &[TaskId],
+    all_blocked_ids: &[TaskId],
+    statuses: &HashMap<TaskId, TaskStatus>,
+    days: u32,
+    selective: Option<&ContextConfig>,
+) -> serde_json::Value {
+    let mut ready_ids: Vec<TaskId> = all_ready_ids
+        .iter()
+        .filter(|id| tasks.contains_key(id))
+        .cloned()
+        .collect();
+    let mut blocked_ids: Vec<TaskId> = all_blocked_ids
+        .iter()
+        .filter(|id| tasks.contains_key(id))
+        .cloned()
+        .collect();
+    ready_ids.sort();
+    blocked_ids.sort();
 
     // Filter completed tasks by date (excluding compacted tasks)
     let cutoff = Utc::now() - Duration::days(days as i64);
-    let recent_completed: Vec<_> = tasks
+    let mut recent_completed: Vec<_> = tasks
         .values()
         .filter(|t| {
             t.status.is_complete()
@@ -79,13 +1060,13 @@ pub fn export(output: &Output, compact: bool, brief_filter: Option<&str>, days:
         .collect();
 
     // Collect compacted task representatives (summaries)
-    let compacted: Vec<_> = tasks
+    let mut compacted: Vec<_> = tasks
         .values()
         .filter(|t| t.is_compaction_representative())
         .collect();
 
     // In-progress tasks
-    let in_progress: Vec<_> = tasks.values().filter(|t| t.status.is_active()).collect();
+    let mut in_progress: Vec<_> = tasks.values().filter(|t| t.status.is_active()).collect();
 
     output.verbose_ctx("context", &format!(
         "Context summary: {} ready, {} blocked, {} in_progress, {} recently_completed, {} compacted groups",
@@ -93,14 +1074,22 @@ pub fn export(output: &Output, compact: bool, brief_filter: Option<&str>, days:
     ));
 
     // Collect standalone tasks
-    let standalone_tasks: Vec<_> = tasks.values().filter(|t| t.is_standalone()).collect();
+    let mut standalone_tasks: Vec<_> = tasks.values().filter(|t| t.is_standalone()).collect();
+
+    for list in [
+        &mut recent_completed,
+        &mut compacted,
+        &mut in_progress,
+        &mut standalone_tasks,
+    ] {
+        list.sort_by(|a, b| a.id.cmp(&b.id));
+    }
 
-    if compact {
+    let mut context = if compact {
         // Compact format - minimal tokens
         export_compact(
-            output,
-            &briefs,
-            &tasks,
+            briefs,
+            tasks,
             &ready_ids,
             &blocked_ids,
             &in_progress,
@@ -111,23 +1100,197 @@ pub fn export(output: &Output, compact: bool, brief_filter: Option<&str>, days:
     } else {
         // Full format
         export_full(
-            output,
-            &briefs,
-            &tasks,
+            briefs,
+            tasks,
             &ready_ids,
             &blocked_ids,
             &in_progress,
             &recent_completed,
             &compacted,
-            &statuses,
+            statuses,
             &standalone_tasks,
         )
+    };
+
+    // Reviews waiting for a verdict, so agents know what is stuck on people
+    let mut pending_reviews: Vec<serde_json::Value> = Vec::new();
+    for task in by_id(tasks.values()) {
+        for review in task.pending_reviews() {
+            pending_reviews.push(if compact {
+                serde_json::json!(format!("{}: {}", task.id, review.reviewer))
+            } else {
+                serde_json::json!({
+                    "task": task.id.to_string(),
+                    "reviewer": review.reviewer,
+                    "requested_at": review.requested_at,
+                })
+            });
+        }
+    }
+    if !pending_reviews.is_empty() {
+        context["pending_reviews"] = serde_json::json!(pending_reviews);
+    }
+
+    // Epics: each brief names its parent, and epics list their children
+    let mut epics: Vec<serde_json::Value> = Vec::new();
+    for entry in context["briefs"].as_array_mut().into_iter().flatten() {
+        let Some(brief) = briefs.values().find(|b| entry["id"] == b.id.to_string()) else {
+            continue;
+        };
+        if let Some(parent) = &brief.parent {
+            entry["parent"] = serde_json::json!(parent.to_string());
+        }
+        let children: Vec<String> = brief::children(briefs, &brief.id)
+            .iter()
+            .map(|c| c.id.to_string())
+            .collect();
+        if children.is_empty() {
+            continue;
+        }
+        let (done, total) = brief::rollup(briefs, tasks, &brief.id);
+        epics.push(if compact {
+            serde_json::json!(format!(
+                "{}: {} ({}/{} done) -> {}",
+                brief.id,
+                brief.title,
+                done,
+                total,
+                children.join(", ")
+            ))
+        } else {
+            serde_json::json!({
+                "id": brief.id.to_string(),
+                "title": brief.title,
+                "children": children,
+                "progress": { "done": done, "total": total },
+            })
+        });
+    }
+    if !epics.is_empty() {
+        context["epics"] = serde_json::json!(epics);
     }
+
+    if let Some(limits) = selective {
+        let active = ready_ids
+            .iter()
+            .filter_map(|id| tasks.get(id))
+            .chain(in_progress.iter().copied());
+        select_bodies(&mut context, briefs, active, limits, compact);
+    }
+    context
+}
+
+/// Applies `--include-bodies selective` to the exported briefs: those with at
+/// least `selective_min_active` ready or in-progress tasks get their body (cut
+/// at `selective_max_chars`), the rest keep only their summary. Bodies left
+/// out or cut are reported under `elided_bodies`.
+fn select_bodies<'a>(
+    context: &mut serde_json::Value,
+    briefs: &HashMap<BriefId, Brief>,
+    active: impl Iterator<Item = &'a Task>,
+    limits: &ContextConfig,
+    compact: bool,
+) {
+    let mut active_counts: HashMap<BriefId, usize> = HashMap::new();
+    for task in active {
+        if let Some(brief_id) = task.brief_id() {
+            *active_counts.entry(brief_id).or_default() += 1;
+        }
+    }
+
+    let lookup: HashMap<String, &Brief> = briefs.values().map(|b| (b.id.to_string(), b)).collect();
+    let mut elided = Vec::new();
+    for entry in context["briefs"].as_array_mut().into_iter().flatten() {
+        let Some(brief) = entry["id"].as_str().and_then(|id| lookup.get(id)) else {
+            continue;
+        };
+        let chars = brief.body.chars().count();
+        let active = active_counts.get(&brief.id).copied().unwrap_or(0);
+
+        let reason = if active < limits.selective_min_active {
+            if let Some(fields) = entry.as_object_mut() {
+                fields.remove("body");
+            }
+            "no active tasks"
+        } else if chars > limits.selective_max_chars {
+            let cut: String = brief
+                .body
+                .chars()
+                .take(limits.selective_max_chars)
+                .collect();
+            entry["body"] = serde_json::json!(format!("{}...", cut));
+            "truncated"
+        } else {
+            entry["body"] = serde_json::json!(brief.body);
+            continue;
+        };
+        if chars == 0 {
+            continue;
+        }
+
+        elided.push(if compact {
+            serde_json::json!(format!("{}: {} ({} chars)", brief.id, reason, chars))
+        } else {
+            serde_json::json!({
+                "id": brief.id.to_string(),
+                "reason": reason,
+                "chars": chars,
+                "active_tasks": active,
+            })
+        });
+    }
+    context["elided_bodies"] = serde_json::json!(elided);
+}
+
+/// Content hash of a context export. `serde_json` keeps object keys sorted,
+/// so the compact serialization is canonical for a given value.
+fn context_etag(context: &serde_json::Value) -> String {
+    let canonical = serde_json::to_string(context).unwrap_or_default();
+    blake3::hash(canonical.as_bytes()).to_hex()[..16].to_string()
+}
+
+/// Returns the minimal coherent task set for working on `task_id`:
+/// the task itself, its transitive blocking dependencies, and its siblings
+/// (tasks with the same parent under the same brief).
+fn task_closure(tasks: &HashMap<TaskId, Task>, task_id: &TaskId) -> Result<HashSet<TaskId>> {
+    let task = tasks
+        .get(task_id)
+        .ok_or_else(|| CliError::not_found("Task", task_id))?;
+
+    let mut selected = HashSet::new();
+    selected.insert(task_id.clone());
+
+    // Blocking ancestor chain
+    let mut queue: VecDeque<&TaskId> = task.depends_on.blocking_task_ids().collect();
+    while let Some(dep_id) = queue.pop_front() {
+        if !selected.insert(dep_id.clone()) {
+            continue;
+        }
+        if let Some(dep) = tasks.get(dep_id) {
+            queue.extend(dep.depends_on.blocking_task_ids());
+        }
+    }
+
+    // Siblings (a top-level standalone task has none)
+    let parent = task_id.parent();
+    if !task_id.is_standalone() || parent.is_some() {
+        for other in tasks.values() {
+            if other.id.hash() == task_id.hash()
+                && other.id.is_standalone() == task_id.is_standalone()
+                && other.id.parent() == parent
+            {
+                selected.insert(other.id.clone());
+            }
+        }
+    }
+
+    // Only keep IDs that exist (dangling dependencies are reported by doctor)
+    selected.retain(|id| tasks.contains_key(id));
+    Ok(selected)
 }
 
 #[allow(clippy::too_many_arguments)]
 fn export_compact(
-    output: &Output,
     briefs: &HashMap<BriefId, crate::domain::Brief>,
     tasks: &HashMap<TaskId, crate::domain::Task>,
     ready_ids: &[TaskId],
@@ -136,7 +1299,7 @@ fn export_compact(
     recent_completed: &[&crate::domain::Task],
     compacted: &[&crate::domain::Task],
     standalone_tasks: &[&crate::domain::Task],
-) -> Result<()> {
+) -> serde_json::Value {
     // Separate standalone tasks by status for the standalone_tasks section
     let standalone_ready: Vec<_> = standalone_tasks
         .iter()
@@ -163,14 +1326,28 @@ fn export_compact(
         })
         .collect();
 
+    // Open tasks with a due date, soonest first
+    let now = Utc::now();
+    let mut due: Vec<_> = tasks
+        .values()
+        .filter(|t| !t.status.is_complete())
+        .filter_map(|t| Some((t.due_at?, t)))
+        .collect();
+    due.sort_by(|(a_due, a), (b_due, b)| a_due.cmp(b_due).then_with(|| a.id.cmp(&b.id)));
+
     // Compact format: optimized for token efficiency
-    let context = serde_json::json!({
-        "briefs": briefs.values().map(|b| {
-            serde_json::json!({
+    serde_json::json!({
+        "briefs": sorted_briefs(briefs).into_iter().map(|b| {
+            let mut brief = serde_json::json!({
                 "id": b.id.to_string(),
                 "title": b.title,
                 "status": b.status,
-            })
+            });
+            // Prefer the short summary over the body to keep tokens down
+            if let Some(summary) = b.current_summary() {
+                brief["summary"] = serde_json::json!(summary);
+            }
+            brief
         }).collect::<Vec<_>>(),
 
         "ready": ready_ids.iter().filter_map(|id| {
@@ -192,6 +1369,11 @@ fn export_compact(
             format!("{}: {}", t.id, t.title)
         }).collect::<Vec<_>>(),
 
+        "due": due.iter().map(|(due_at, t)| {
+            let when = if t.is_overdue(now) { "overdue since" } else { "due" };
+            format!("{}: {} ({} {})", t.id, t.title, when, due_at.format("%Y-%m-%d"))
+        }).collect::<Vec<_>>(),
+
         "compacted": compacted.iter().map(|t| {
             serde_json::json!({
                 "id": t.id.to_string(),
@@ -206,15 +1388,11 @@ fn export_compact(
             "in_progress": standalone_in_progress,
             "blocked": standalone_blocked,
         },
-    });
-
-    output.data(&context);
-    Ok(())
+    })
 }
 
 #[allow(clippy::too_many_arguments)]
 fn export_full(
-    output: &Output,
     briefs: &HashMap<BriefId, crate::domain::Brief>,
     tasks: &HashMap<TaskId, crate::domain::Task>,
     ready_ids: &[TaskId],
@@ -224,7 +1402,7 @@ fn export_full(
     compacted: &[&crate::domain::Task],
     statuses: &HashMap<TaskId, TaskStatus>,
     standalone_tasks: &[&crate::domain::Task],
-) -> Result<()> {
+) -> serde_json::Value {
     // Separate standalone tasks by status
     let standalone_ready: Vec<_> = standalone_tasks
         .iter()
@@ -288,13 +1466,15 @@ fn export_full(
         .collect();
 
     // Full format: more detail for comprehensive understanding
-    let context = serde_json::json!({
-        "briefs": briefs.values().map(|b| {
+    serde_json::json!({
+        "briefs": sorted_briefs(briefs).into_iter().map(|b| {
             serde_json::json!({
                 "id": b.id.to_string(),
                 "title": b.title,
                 "type": b.brief_type,
                 "status": b.status,
+                "summary": b.current_summary(),
+                "reading_minutes": b.reading_minutes(),
                 "body": if b.body.len() > 500 {
                     format!("{}...", &b.body[..500])
                 } else {
@@ -312,6 +1492,7 @@ fn export_full(
                         "title": t.title,
                         "standalone": t.is_standalone(),
                         "brief": t.brief_id().map(|a| a.to_string()),
+                        "due_at": t.due_at,
                         "description": t.description,
                         "meta": t.meta,
                     })
@@ -325,6 +1506,7 @@ fn export_full(
                     "standalone": t.is_standalone(),
                     "brief": t.brief_id().map(|a| a.to_string()),
                     "started_at": t.updated_at,
+                    "due_at": t.due_at,
                     "description": t.description,
                     "meta": t.meta,
                 })
@@ -393,8 +1575,10 @@ fn export_full(
             "in_progress_count": in_progress.len(),
             "compacted_groups": compacted.len(),
         },
-    });
+    })
+}
 
-    output.data(&context);
-    Ok(())
+/// Briefs ordered by ID, for deterministic output
+fn sorted_briefs(briefs: &HashMap<BriefId, crate::domain::Brief>) -> Vec<&crate::domain::Brief> {
+    by_id(briefs.values())
 }
@@ -1,21 +1,30 @@
 //! Background daemon for automatic git synchronization
 //!
 //! The daemon watches `.shape/` for changes and automatically commits them.
+//! When `agent.sweep_interval_minutes` is set it also releases expired claims
+//! on that interval (see `shape claims sweep`). Commits back off while a CLI
+//! command holds the task store lock, so they never capture a write midway,
+//! and wait while a command that writes several times holds a write fence
+//! (see [`WriteFence`]), so the whole operation becomes one commit. Its
+//! message comes from the operation journal when the command was journaled.
 
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::mpsc;
+use std::thread;
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use clap::Subcommand;
 use notify::RecursiveMode;
 use notify_debouncer_mini::new_debouncer;
 
+use super::claims::sweep_expired_claims;
 use super::output::Output;
-use crate::storage::{DaemonConfig, Project};
+use crate::storage::{DaemonConfig, FenceHolder, JournalEntry, Project, WriteFence};
 
 /// Maximum log file size before rotation (1MB)
 const MAX_LOG_SIZE: u64 = 1024 * 1024;
@@ -23,6 +32,16 @@ const MAX_LOG_SIZE: u64 = 1024 * 1024;
 /// Number of log files to keep
 const LOG_ROTATION_COUNT: usize = 7;
 
+/// Longest single wait between lock checks before committing
+const MAX_COMMIT_BACKOFF: Duration = Duration::from_secs(2);
+
+/// How often a held commit checks whether the write fence is down
+const FENCE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Task IDs named in a journal-derived commit message before the rest are
+/// counted
+const MAX_MESSAGE_IDS: usize = 3;
+
 #[derive(Subcommand)]
 pub enum DaemonCommands {
     /// Start the background daemon
@@ -543,10 +562,40 @@ fn run_daemon_loop(project: &Project, config: &DaemonConfig) -> Result<()> {
         ),
     )?;
 
+    let sweep_interval = match project.config().project.agent.sweep_interval_minutes {
+        0 => None,
+        minutes => Some(Duration::from_secs(minutes * 60)),
+    };
+    let mut last_sweep = Instant::now();
+    // Changes seen but not yet committed, held back by a write fence
+    let mut pending = false;
+    // Fence last reported in the log, so a long hold is logged once
+    let mut held_by: Option<u32> = None;
+    // Journal entries recorded after this go into the next commit message
+    let mut last_commit = Utc::now();
+
     // Main event loop
     loop {
-        match rx.recv() {
-            Ok(Ok(events)) => {
+        let until_sweep = sweep_interval.map(|i| i.saturating_sub(last_sweep.elapsed()));
+        let wait = match (pending.then_some(FENCE_POLL_INTERVAL), until_sweep) {
+            (Some(poll), Some(sweep)) => Some(poll.min(sweep)),
+            (poll, sweep) => poll.or(sweep),
+        };
+        let event = match wait {
+            Some(wait) => match rx.recv_timeout(wait) {
+                Ok(event) => Ok(Some(event)),
+                Err(mpsc::RecvTimeoutError::Timeout) => Ok(None),
+                Err(mpsc::RecvTimeoutError::Disconnected) => Err(mpsc::RecvError),
+            },
+            None => rx.recv().map(Some),
+        };
+        if sweep_interval.is_some_and(|i| last_sweep.elapsed() >= i) {
+            sweep_claims(project)?;
+            last_sweep = Instant::now();
+        }
+
+        match event {
+            Ok(Some(Ok(events))) => {
                 // Filter out events for ignored paths
                 let relevant_events: Vec<_> = events
                     .iter()
@@ -561,41 +610,73 @@ fn run_daemon_loop(project: &Project, config: &DaemonConfig) -> Result<()> {
                     &shape_dir,
                     &format!("Detected {} change(s)", relevant_events.len()),
                 )?;
+                pending = config.auto_commit;
+            }
+            Ok(Some(Err(error))) => {
+                log_message(&shape_dir, &format!("Watch error: {:?}", error))?;
+                continue;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log_message(&shape_dir, &format!("Channel error: {}", e))?;
+                break;
+            }
+        }
+        if !pending {
+            continue;
+        }
 
-                if config.auto_commit {
-                    match auto_commit(&project_root, config) {
-                        Ok(Some(message)) => {
-                            log_message(&shape_dir, &format!("Committed: {}", message))?;
-
-                            if config.auto_push {
-                                match auto_push(&project_root, config) {
-                                    Ok(true) => {
-                                        log_message(&shape_dir, "Pushed to remote")?;
-                                    }
-                                    Ok(false) => {
-                                        log_message(&shape_dir, "Nothing to push")?;
-                                    }
-                                    Err(e) => {
-                                        log_message(&shape_dir, &format!("Push failed: {}", e))?;
-                                    }
-                                }
-                            }
+        if let Some(holder) = active_fence(&shape_dir) {
+            if held_by != Some(holder.pid) {
+                log_message(
+                    &shape_dir,
+                    &format!(
+                        "Holding commit until `shape {}` (PID {}) finishes",
+                        holder.operation, holder.pid
+                    ),
+                )?;
+                held_by = Some(holder.pid);
+            }
+            continue;
+        }
+        held_by = None;
+
+        if !wait_for_writes(project, config)? {
+            log_message(
+                &shape_dir,
+                &format!(
+                    "Write still in flight after {}s, deferring commit",
+                    config.commit_backoff_max_seconds
+                ),
+            )?;
+            continue;
+        }
+        pending = false;
+        let journaled = journal_commit_message(project, config, last_commit);
+        match auto_commit(&project_root, config, journaled) {
+            Ok(Some(message)) => {
+                last_commit = Utc::now();
+                log_message(&shape_dir, &format!("Committed: {}", message))?;
+
+                if config.auto_push {
+                    match auto_push(&project_root, config) {
+                        Ok(true) => {
+                            log_message(&shape_dir, "Pushed to remote")?;
                         }
-                        Ok(None) => {
-                            log_message(&shape_dir, "No changes to commit")?;
+                        Ok(false) => {
+                            log_message(&shape_dir, "Nothing to push")?;
                         }
                         Err(e) => {
-                            log_message(&shape_dir, &format!("Commit failed: {}", e))?;
+                            log_message(&shape_dir, &format!("Push failed: {}", e))?;
                         }
                     }
                 }
             }
-            Ok(Err(error)) => {
-                log_message(&shape_dir, &format!("Watch error: {:?}", error))?;
+            Ok(None) => {
+                log_message(&shape_dir, "No changes to commit")?;
             }
             Err(e) => {
-                log_message(&shape_dir, &format!("Channel error: {}", e))?;
-                break;
+                log_message(&shape_dir, &format!("Commit failed: {}", e))?;
             }
         }
     }
@@ -603,8 +684,67 @@ fn run_daemon_loop(project: &Project, config: &DaemonConfig) -> Result<()> {
     Ok(())
 }
 
+/// The oldest write fence of a running process. Fences left behind by
+/// processes that died are cleared.
+fn active_fence(shape_dir: &Path) -> Option<FenceHolder> {
+    WriteFence::holders(shape_dir).into_iter().find(|holder| {
+        let alive = holder.pid == std::process::id() || is_process_running(holder.pid);
+        if !alive {
+            WriteFence::clear(shape_dir, holder.pid);
+        }
+        alive
+    })
+}
+
+/// Backs off while a CLI command holds the task store lock, so commits never
+/// capture a half-finished write. Returns false if the store is still locked
+/// after `commit_backoff_max_seconds`; the writer's own change then triggers
+/// the next commit.
+fn wait_for_writes(project: &Project, config: &DaemonConfig) -> Result<bool> {
+    let store = project.task_store();
+    let limit = Duration::from_secs(config.commit_backoff_max_seconds);
+    let start = Instant::now();
+    let mut delay = Duration::from_millis(100);
+
+    while store.is_locked() {
+        if start.elapsed() >= limit {
+            return Ok(false);
+        }
+        log_message(
+            &project.shape_dir(),
+            &format!(
+                "Task store locked by a write in flight, retrying commit in {}ms",
+                delay.as_millis()
+            ),
+        )?;
+        thread::sleep(delay);
+        delay = (delay * 2).min(MAX_COMMIT_BACKOFF);
+    }
+    Ok(true)
+}
+
+/// Runs a claim sweep from the daemon loop, logging the outcome
+fn sweep_claims(project: &Project) -> Result<()> {
+    let shape_dir = project.shape_dir();
+    // An open `shape tx` session holds the task store; sweep once it ends
+    if project.tx_session()?.is_some() {
+        return Ok(());
+    }
+    match sweep_expired_claims(project, None, false) {
+        Ok(swept) if swept.is_empty() => Ok(()),
+        Ok(swept) => {
+            let ids: Vec<_> = swept.iter().map(|c| c.id.to_string()).collect();
+            log_message(
+                &shape_dir,
+                &format!("Swept {} expired claim(s): {}", ids.len(), ids.join(", ")),
+            )
+        }
+        Err(e) => log_message(&shape_dir, &format!("Claim sweep failed: {}", e)),
+    }
+}
+
 /// Checks if a path should be ignored by the watcher
-fn should_ignore_path(path: &Path) -> bool {
+pub(super) fn should_ignore_path(path: &Path) -> bool {
     let path_str = path.to_string_lossy();
 
     // Ignore cache directory
@@ -627,6 +767,11 @@ fn should_ignore_path(path: &Path) -> bool {
         return true;
     }
 
+    // Ignore the undo journal (local, not committed)
+    if path_str.ends_with("journal.jsonl") {
+        return true;
+    }
+
     // Ignore daemon files (our own files)
     if path_str.ends_with("daemon.pid") || path_str.ends_with("daemon.log") {
         return true;
@@ -640,11 +785,25 @@ fn should_ignore_path(path: &Path) -> bool {
     false
 }
 
-/// Automatically commits changes to git
-fn auto_commit(project_root: &Path, config: &DaemonConfig) -> Result<Option<String>> {
+/// Automatically commits changes to git, with `message` or one generated
+/// from the changed files
+fn auto_commit(
+    project_root: &Path,
+    config: &DaemonConfig,
+    message: Option<String>,
+) -> Result<Option<String>> {
+    // Local files that older projects' .gitignore doesn't cover yet: the
+    // undo journal and the plaintext index of archived briefs
+    let pathspec = [
+        ".shape/",
+        ":(exclude).shape/journal.jsonl",
+        ":(exclude).shape/archive/briefs/index.jsonl",
+    ];
+
     // Check if there are changes to commit
     let status_output = Command::new("git")
-        .args(["status", "--porcelain", ".shape/"])
+        .args(["status", "--porcelain", "--"])
+        .args(pathspec)
         .current_dir(project_root)
         .output()
         .context("Failed to run git status")?;
@@ -655,11 +814,12 @@ fn auto_commit(project_root: &Path, config: &DaemonConfig) -> Result<Option<Stri
     }
 
     // Parse changes to generate commit message
-    let message = generate_commit_message(&status, config);
+    let message = message.unwrap_or_else(|| generate_commit_message(&status, config));
 
     // Stage .shape/ changes
     Command::new("git")
-        .args(["add", ".shape/"])
+        .args(["add", "--"])
+        .args(pathspec)
         .current_dir(project_root)
         .status()
         .context("Failed to stage changes")?;
@@ -683,6 +843,58 @@ fn auto_commit(project_root: &Path, config: &DaemonConfig) -> Result<Option<Stri
     }
 }
 
+/// Commit message for the commands journaled since `since`, if any
+fn journal_commit_message(
+    project: &Project,
+    config: &DaemonConfig,
+    since: DateTime<Utc>,
+) -> Option<String> {
+    let entries: Vec<JournalEntry> = project
+        .journal()
+        .entries()
+        .ok()?
+        .into_iter()
+        .filter(|e| e.at > since && !e.undone)
+        .collect();
+    journal_message(&config.commit_message_format, &entries)
+}
+
+/// Fills `format` (`{action}`, `{id}`, `{count}`) from journal entries:
+/// the commands run and the tasks they changed
+fn journal_message(format: &str, entries: &[JournalEntry]) -> Option<String> {
+    if entries.is_empty() {
+        return None;
+    }
+    let mut actions: Vec<&str> = Vec::new();
+    let mut ids: Vec<String> = Vec::new();
+    for entry in entries {
+        if !actions.contains(&entry.command.as_str()) {
+            actions.push(&entry.command);
+        }
+        for change in &entry.changes {
+            let id = change.id.to_string();
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+
+    let mut id = ids
+        .iter()
+        .take(MAX_MESSAGE_IDS)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if ids.len() > MAX_MESSAGE_IDS {
+        id.push_str(&format!(" and {} more", ids.len() - MAX_MESSAGE_IDS));
+    }
+    let message = format
+        .replace("{action}", &actions.join(", "))
+        .replace("{id}", &id)
+        .replace("{count}", &ids.len().to_string());
+    Some(message.trim().to_string())
+}
+
 /// Generates a commit message based on the changes
 fn generate_commit_message(status: &str, _config: &DaemonConfig) -> String {
     let lines: Vec<&str> = status.lines().collect();
@@ -801,6 +1013,7 @@ mod tests {
         assert!(should_ignore_path(Path::new(".shape/daemon.pid")));
         assert!(should_ignore_path(Path::new(".shape/daemon.log")));
         assert!(should_ignore_path(Path::new(".shape/daemon.log.1")));
+        assert!(should_ignore_path(Path::new(".shape/journal.jsonl")));
 
         assert!(!should_ignore_path(Path::new(".shape/tasks.jsonl")));
         assert!(!should_ignore_path(Path::new(".shape/briefs/b-1234567.md")));
@@ -838,4 +1051,52 @@ mod tests {
         let message = generate_commit_message(status, &config);
         assert_eq!(message, "shape: update 1 task, 1 brief");
     }
+
+    #[test]
+    fn test_journal_message_names_commands_and_tasks() {
+        use crate::domain::{BriefId, Task, TaskId};
+        use crate::storage::TaskChange;
+
+        let brief = BriefId::new("Daemon", Utc::now());
+        let entry = |command: &str, seqs: &[u32]| JournalEntry {
+            seq: 1,
+            at: Utc::now(),
+            by: "alice".to_string(),
+            command: command.to_string(),
+            changes: seqs
+                .iter()
+                .map(|&seq| {
+                    let id = TaskId::new(&brief, seq);
+                    TaskChange {
+                        id: id.clone(),
+                        before: None,
+                        after: Some(Task::new(id, "t")),
+                    }
+                })
+                .collect(),
+            undone: false,
+        };
+        let format = DaemonConfig::default().commit_message_format;
+
+        assert_eq!(journal_message(&format, &[]), None);
+        assert_eq!(
+            journal_message(&format, &[entry("task done", &[1])]).unwrap(),
+            format!("shape: task done {}.1", brief)
+        );
+        assert_eq!(
+            journal_message(
+                &format,
+                &[entry("task import", &[1, 2, 3, 4]), entry("claim", &[4, 5])]
+            )
+            .unwrap(),
+            format!(
+                "shape: task import, claim {b}.1, {b}.2, {b}.3 and 2 more",
+                b = brief
+            )
+        );
+        assert_eq!(
+            journal_message("{action}: {count} tasks", &[entry("task split", &[1, 2])]).unwrap(),
+            "task split: 2 tasks"
+        );
+    }
 }
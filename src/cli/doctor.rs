@@ -0,0 +1,304 @@
+//! Doctor command - validates project data
+//!
+//! Runs a set of checks over briefs and tasks and reports findings through
+//! [`Output::diagnostics`], so results can be shown as text, JSON, GitHub
+//! annotations or a JUnit report.
+
+use std::collections::HashMap;
+use std::fs;
+
+use anyhow::Result;
+use chrono::Utc;
+
+use super::output::{Diagnostic, Output, OutputFormat, Severity, DIAGNOSTIC_FORMATS};
+use crate::domain::{by_id, AppetiteBudget, DependencyGraph, GraphError, TaskId};
+use crate::plugin;
+use crate::storage::{IssueSeverity, Project};
+
+/// Checks run by `shape doctor`, in report order
+const CHECKS: &[&str] = &[
+    "frontmatter",
+    "sections",
+    "orphans",
+    "dependencies",
+    "cycles",
+    "claims",
+    "collisions",
+    "mentions",
+    "appetite",
+];
+
+/// Formats `shape doctor` supports beyond text and JSON
+pub(super) const FORMATS: &[OutputFormat] = DIAGNOSTIC_FORMATS;
+
+/// Run the doctor command
+pub fn run(output: &Output) -> Result<()> {
+    let project = Project::open_current()?;
+    let diagnostics = check_project(&project)?;
+
+    output.diagnostics("doctor", CHECKS, &diagnostics);
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        anyhow::bail!("doctor found {} error(s)", errors);
+    }
+
+    Ok(())
+}
+
+/// Runs all checks and returns the findings
+fn check_project(project: &Project) -> Result<Vec<Diagnostic>> {
+    let brief_store = project.brief_store();
+    let task_store = project.task_store();
+
+    let briefs = brief_store.read_all_meta()?;
+    let tasks = task_store.read_all()?;
+
+    let tasks_file = display_path(project, task_store.path());
+    let task_lines = task_line_numbers(task_store.path());
+    let task_diagnostic = |severity: Severity, check: &str, id: &TaskId, message: String| {
+        Diagnostic::new(severity, check, message)
+            .at(tasks_file.clone(), task_lines.get(&id.to_string()).copied())
+            .with_id(id.to_string())
+    };
+
+    let mut diagnostics = Vec::new();
+
+    // frontmatter: brief files that cannot be parsed, or whose keys look mistyped
+    for (path, issues) in brief_store.frontmatter_issues()? {
+        for issue in issues {
+            let severity = match issue.severity {
+                IssueSeverity::Error => Severity::Error,
+                IssueSeverity::Warning => Severity::Warning,
+            };
+            diagnostics.push(
+                Diagnostic::new(severity, "frontmatter", issue.detail())
+                    .at(display_path(project, &path), Some(issue.line)),
+            );
+        }
+    }
+
+    // sections: rfc and prd briefs missing a required section
+    let documents: Vec<_> = by_id(briefs.values())
+        .into_iter()
+        .filter(|b| !plugin::required_sections(&b.brief_type).is_empty())
+        .map(|b| b.id.clone())
+        .collect();
+    for id in documents {
+        let Some(brief) = brief_store.read(&id)? else {
+            continue;
+        };
+        for section in plugin::missing_sections(&brief.brief_type, &brief.body) {
+            diagnostics.push(
+                Diagnostic::new(
+                    Severity::Warning,
+                    "sections",
+                    format!(
+                        "{} brief {} is missing required section: ## {}",
+                        brief.brief_type.to_uppercase(),
+                        brief.id,
+                        section
+                    ),
+                )
+                .at(
+                    display_path(project, &brief_store.dir().join(format!("{}.md", id))),
+                    None,
+                )
+                .with_id(id.to_string()),
+            );
+        }
+    }
+
+    let sorted = by_id(tasks.values());
+
+    // orphans: tasks whose brief no longer exists
+    for task in &sorted {
+        if let Some(brief_id) = task.brief_id() {
+            if !briefs.contains_key(&brief_id) {
+                diagnostics.push(task_diagnostic(
+                    Severity::Error,
+                    "orphans",
+                    &task.id,
+                    format!("Task {} belongs to missing brief {}", task.id, brief_id),
+                ));
+            }
+        }
+    }
+
+    // dependencies: references to tasks that do not exist
+    for task in &sorted {
+        for dep in task.depends_on.iter() {
+            if !tasks.contains_key(&dep.task) {
+                diagnostics.push(task_diagnostic(
+                    Severity::Error,
+                    "dependencies",
+                    &task.id,
+                    format!(
+                        "Task {} has a {} dependency on missing task {}",
+                        task.id,
+                        dep.dep_type.label(),
+                        dep.task
+                    ),
+                ));
+            }
+        }
+    }
+
+    // cycles: blocking dependency cycles among existing tasks
+    let mut graph = DependencyGraph::new();
+    for task in &sorted {
+        graph.add_task(task.id.clone());
+    }
+    for task in &sorted {
+        for dep_id in task.depends_on.blocking_task_ids() {
+            if !tasks.contains_key(dep_id) {
+                continue;
+            }
+            if let Err(e @ (GraphError::CycleDetected(..) | GraphError::SelfDependency(_))) =
+                graph.add_dependency(&task.id, dep_id)
+            {
+                diagnostics.push(task_diagnostic(
+                    Severity::Error,
+                    "cycles",
+                    &task.id,
+                    e.to_string(),
+                ));
+            }
+        }
+    }
+
+    // claims: claims that have outlived the configured timeout
+    let timeout = project.config().project.agent.claim_timeout_hours;
+    for task in &sorted {
+        if let Some(agent) = &task.claimed_by {
+            if task.is_claim_expired(timeout) {
+                diagnostics.push(task_diagnostic(
+                    Severity::Warning,
+                    "claims",
+                    &task.id,
+                    format!(
+                        "Claim on {} by {} expired (timeout {}h)",
+                        task.id, agent, timeout
+                    ),
+                ));
+            }
+        }
+    }
+
+    // collisions: briefs and standalone tasks that share a hash
+    for (hash, ids) in project.id_collisions()? {
+        diagnostics.push(
+            Diagnostic::new(
+                Severity::Error,
+                "collisions",
+                format!(
+                    "IDs share the hash {}: {} (run 'shape ids migrate --collisions' to widen them)",
+                    hash,
+                    ids.join(", ")
+                ),
+            )
+            .with_id(ids[0].clone()),
+        );
+    }
+
+    // mentions: @handles in brief bodies and notes missing from team.toml
+    let team_store = project.team_store();
+    if team_store.exists() {
+        let team = team_store.read()?;
+        let unknown = |text: &str| {
+            team.unknown_mentions(text)
+                .iter()
+                .map(|m| format!("@{}", m))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        for brief in by_id(brief_store.read_all()?.values()) {
+            let names = unknown(&brief.body);
+            if !names.is_empty() {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Warning,
+                        "mentions",
+                        format!("Brief {} mentions unknown {}", brief.id, names),
+                    )
+                    .at(
+                        display_path(project, &brief_store.dir().join(format!("{}.md", brief.id))),
+                        None,
+                    )
+                    .with_id(brief.id.to_string()),
+                );
+            }
+        }
+        for task in &sorted {
+            let names: Vec<String> = task
+                .notes
+                .iter()
+                .map(|n| unknown(&n.text))
+                .filter(|n| !n.is_empty())
+                .collect();
+            if !names.is_empty() {
+                diagnostics.push(task_diagnostic(
+                    Severity::Warning,
+                    "mentions",
+                    &task.id,
+                    format!("Notes on {} mention unknown {}", task.id, names.join(", ")),
+                ));
+            }
+        }
+    }
+
+    // appetite: briefs in progress past their appetite, or close to it
+    let threshold = project.config().project.briefs.appetite_warn_percent;
+    let now = Utc::now();
+    for brief in by_id(briefs.values()) {
+        let Some(budget) = AppetiteBudget::of(brief, now) else {
+            continue;
+        };
+        let severity = if budget.is_exceeded() {
+            Severity::Warning
+        } else if budget.percent_used() >= threshold {
+            Severity::Notice
+        } else {
+            continue;
+        };
+        diagnostics.push(
+            Diagnostic::new(
+                severity,
+                "appetite",
+                format!("Brief {} is {}", brief.id, budget.describe()),
+            )
+            .at(
+                display_path(project, &brief_store.dir().join(format!("{}.md", brief.id))),
+                None,
+            )
+            .with_id(brief.id.to_string()),
+        );
+    }
+
+    Ok(diagnostics)
+}
+
+/// Maps task IDs to their 1-based line number in the JSONL file
+fn task_line_numbers(path: &std::path::Path) -> HashMap<String, usize> {
+    let content = fs::read_to_string(path).unwrap_or_default();
+
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let value: serde_json::Value = serde_json::from_str(line).ok()?;
+            Some((value.get("id")?.as_str()?.to_string(), i + 1))
+        })
+        .collect()
+}
+
+fn display_path(project: &Project, path: &std::path::Path) -> String {
+    project
+        .relative_path(path)
+        .unwrap_or_else(|| path.to_path_buf())
+        .display()
+        .to_string()
+}
@@ -0,0 +1,108 @@
+//! Parsing of relative times for filter flags like `--since 24h`
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use super::error::{CliError, ErrorCode};
+
+/// Parses a duration such as `30m`, `24h`, `7d` or `2w`
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(|| {
+        CliError::new(
+            ErrorCode::Validation,
+            format!("Missing unit in duration '{}' (use m, h, d or w)", s),
+        )
+    })?;
+    let (amount, unit) = s.split_at(split);
+
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| CliError::new(ErrorCode::Validation, format!("Invalid duration: {}", s)))?;
+
+    match unit {
+        "m" => Ok(Duration::minutes(amount)),
+        "h" => Ok(Duration::hours(amount)),
+        "d" => Ok(Duration::days(amount)),
+        "w" => Ok(Duration::weeks(amount)),
+        _ => Err(CliError::new(
+            ErrorCode::Validation,
+            format!("Unknown unit in duration '{}' (use m, h, d or w)", s),
+        )
+        .into()),
+    }
+}
+
+/// Parses a point in time: a duration ago (`24h`), a date (`2025-01-16`)
+/// or an RFC 3339 timestamp
+pub fn parse_since(s: &str) -> Result<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(s) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    Ok(Utc::now() - parse_duration(s)?)
+}
+
+/// Parses a due date: a date (`2024-07-01`, due at the end of that day), an
+/// RFC 3339 timestamp, or a duration from now (`3d`)
+pub fn parse_due(s: &str) -> Result<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(s) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(23, 59, 59).unwrap_or_default().and_utc());
+    }
+    Ok(Utc::now() + parse_duration(s)?)
+}
+
+/// Formats a duration compactly in its largest whole unit (`45m`, `5h`, `3d`)
+pub fn format_duration(d: Duration) -> String {
+    if d.num_days() > 0 {
+        format!("{}d", d.num_days())
+    } else if d.num_hours() > 0 {
+        format!("{}h", d.num_hours())
+    } else {
+        format!("{}m", d.num_minutes().max(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("24h").unwrap(), Duration::hours(24));
+        assert_eq!(parse_duration("2w").unwrap(), Duration::weeks(2));
+        assert!(parse_duration("24").is_err());
+        assert!(parse_duration("3y").is_err());
+    }
+
+    #[test]
+    fn formats_durations_in_largest_unit() {
+        assert_eq!(format_duration(Duration::minutes(45)), "45m");
+        assert_eq!(format_duration(Duration::minutes(150)), "2h");
+        assert_eq!(format_duration(Duration::hours(80)), "3d");
+    }
+
+    #[test]
+    fn parses_dates_and_timestamps() {
+        let date = parse_since("2025-01-16").unwrap();
+        assert_eq!(date.to_rfc3339(), "2025-01-16T00:00:00+00:00");
+
+        let ts = parse_since("2025-01-16T10:30:00Z").unwrap();
+        assert_eq!(ts.to_rfc3339(), "2025-01-16T10:30:00+00:00");
+
+        assert!(parse_since("7d").unwrap() < Utc::now());
+    }
+
+    #[test]
+    fn due_dates_end_with_their_day() {
+        let due = parse_due("2024-07-01").unwrap();
+        assert_eq!(due.to_rfc3339(), "2024-07-01T23:59:59+00:00");
+        assert!(parse_due("3d").unwrap() > Utc::now());
+        assert!(parse_due("soon").is_err());
+    }
+}
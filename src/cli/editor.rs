@@ -0,0 +1,63 @@
+//! Editing text in the user's editor
+//!
+//! The editor is the `editor` global config setting, then `$VISUAL`, then
+//! `$EDITOR`, falling back to `vi`. Like git's `COMMIT_EDITMSG`, the text is
+//! written to a scratch file under `.shape/.cache/` and read back once the
+//! editor exits.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result};
+
+use crate::storage::Project;
+
+/// Opens `initial` in the editor and returns the saved text, trimmed.
+///
+/// `name` is the scratch file name, e.g. `TASK_DESCRIPTION.md`.
+pub fn edit(project: &Project, name: &str, initial: &str) -> Result<String> {
+    let dir = project.cache_dir();
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    let path = dir.join(name);
+    fs::write(&path, initial).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    open(project, &path)?;
+
+    let text =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let _ = fs::remove_file(&path);
+    Ok(text.trim().to_string())
+}
+
+/// Opens a file in the editor and waits for it to exit
+pub fn open(project: &Project, path: &Path) -> Result<()> {
+    let editor = command(project);
+
+    // The setting may carry arguments, e.g. `code --wait`
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to start editor `{}`", editor))?;
+    if !status.success() {
+        anyhow::bail!("Editor `{}` exited with {}", editor, status);
+    }
+    Ok(())
+}
+
+/// The configured editor command
+pub fn command(project: &Project) -> String {
+    project
+        .config()
+        .global
+        .editor
+        .clone()
+        .or_else(|| std::env::var("VISUAL").ok())
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
@@ -0,0 +1,247 @@
+//! Error codes for machine consumption
+//!
+//! Every failure maps to a stable [`ErrorCode`]. With `--format json` the
+//! error is printed to stderr as
+//! `{"success": false, "error": {"code": "E_NOT_FOUND", "message": "..."}}`,
+//! and the process exits with the code's category number so agents can branch
+//! without matching message text.
+//!
+//! Typed errors from the library (`ProjectError`, `GraphError`, ...) are
+//! classified by [`classify`]; CLI checks that have no library type raise a
+//! [`CliError`] carrying its code directly.
+
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::domain::{GraphError, IdError};
+use crate::plugin::PluginError;
+use crate::storage::{
+    CacheError, ConfigError, EncryptionError, FormatError, FrontmatterError, LockError,
+    ProjectError, TxError,
+};
+
+/// Stable error code. The string form never changes once released; new codes
+/// may be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// Anything not covered by a more specific code
+    General,
+    /// A task, brief, milestone or template doesn't exist
+    NotFound,
+    /// The operation conflicts with the current state
+    Conflict,
+    /// The task is claimed by another agent
+    ClaimConflict,
+    /// Bulk change refused without `--yes`
+    ConfirmationRequired,
+    /// The brief or task is frozen and no `--override` was given
+    Frozen,
+    /// The task can't be completed before a review approves it
+    ReviewRequired,
+    /// The task doesn't pass its completion gates and no `--skip-gates` was
+    /// given
+    GatesFailed,
+    /// The task requires a human and the claiming identity isn't one
+    HumanRequired,
+    /// The path or subsystem is locked by another agent
+    ResourceLocked,
+    /// An argument or value is malformed
+    Validation,
+    /// A brief or task ID doesn't parse
+    InvalidId,
+    /// Adding the dependency would create a cycle
+    DependencyCycle,
+    /// A file lock couldn't be acquired in time
+    LockTimeout,
+    /// A plugin failed or returned an error
+    Plugin,
+    /// The named plugin isn't installed
+    PluginNotFound,
+    /// No `.shape/` directory was found
+    NotInProject,
+    /// The project was written by a newer shape
+    UnsupportedFormat,
+    /// `config.toml` is invalid
+    Config,
+    /// Reading or writing project files failed
+    Io,
+    /// The store is encrypted and no passphrase is available
+    StoreLocked,
+    /// The passphrase doesn't unlock the encrypted store
+    BadPassphrase,
+    /// A release couldn't be fetched, verified or installed
+    UpdateFailed,
+}
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::General => "E_GENERAL",
+            ErrorCode::NotFound => "E_NOT_FOUND",
+            ErrorCode::Conflict => "E_CONFLICT",
+            ErrorCode::ClaimConflict => "E_CLAIM_CONFLICT",
+            ErrorCode::ConfirmationRequired => "E_CONFIRMATION_REQUIRED",
+            ErrorCode::Frozen => "E_FROZEN",
+            ErrorCode::ReviewRequired => "E_REVIEW_REQUIRED",
+            ErrorCode::GatesFailed => "E_GATES_FAILED",
+            ErrorCode::HumanRequired => "E_HUMAN_REQUIRED",
+            ErrorCode::ResourceLocked => "E_RESOURCE_LOCKED",
+            ErrorCode::Validation => "E_VALIDATION",
+            ErrorCode::InvalidId => "E_INVALID_ID",
+            ErrorCode::DependencyCycle => "E_DEPENDENCY_CYCLE",
+            ErrorCode::LockTimeout => "E_LOCK_TIMEOUT",
+            ErrorCode::Plugin => "E_PLUGIN",
+            ErrorCode::PluginNotFound => "E_PLUGIN_NOT_FOUND",
+            ErrorCode::NotInProject => "E_NOT_IN_PROJECT",
+            ErrorCode::UnsupportedFormat => "E_UNSUPPORTED_FORMAT",
+            ErrorCode::Config => "E_CONFIG",
+            ErrorCode::Io => "E_IO",
+            ErrorCode::StoreLocked => "E_STORE_LOCKED",
+            ErrorCode::BadPassphrase => "E_BAD_PASSPHRASE",
+            ErrorCode::UpdateFailed => "E_UPDATE_FAILED",
+        }
+    }
+
+    /// Process exit code. Codes in the same category share a number; 2 is
+    /// left to clap for usage errors.
+    pub fn exit_code(self) -> u8 {
+        match self {
+            ErrorCode::General => 1,
+            ErrorCode::NotFound => 3,
+            ErrorCode::Conflict
+            | ErrorCode::ClaimConflict
+            | ErrorCode::ConfirmationRequired
+            | ErrorCode::Frozen
+            | ErrorCode::ReviewRequired
+            | ErrorCode::GatesFailed
+            | ErrorCode::HumanRequired
+            | ErrorCode::ResourceLocked => 4,
+            ErrorCode::Validation | ErrorCode::InvalidId | ErrorCode::DependencyCycle => 5,
+            ErrorCode::LockTimeout => 6,
+            ErrorCode::Plugin | ErrorCode::PluginNotFound => 7,
+            ErrorCode::NotInProject => 8,
+            ErrorCode::UnsupportedFormat => 9,
+            ErrorCode::Config => 10,
+            ErrorCode::Io => 11,
+            ErrorCode::StoreLocked | ErrorCode::BadPassphrase => 12,
+            ErrorCode::UpdateFailed => 13,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A CLI failure with an explicit code
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct CliError {
+    pub code: ErrorCode,
+    message: String,
+}
+
+impl CliError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// `"{kind} not found: {id}"`
+    pub fn not_found(kind: &str, id: impl fmt::Display) -> Self {
+        Self::new(ErrorCode::NotFound, format!("{} not found: {}", kind, id))
+    }
+}
+
+/// Returns the code for an error, using the first typed error in its chain
+pub fn classify(err: &anyhow::Error) -> ErrorCode {
+    err.chain().find_map(code_for).unwrap_or(ErrorCode::General)
+}
+
+fn code_for(err: &(dyn std::error::Error + 'static)) -> Option<ErrorCode> {
+    if let Some(e) = err.downcast_ref::<CliError>() {
+        return Some(e.code);
+    }
+    if let Some(e) = err.downcast_ref::<ProjectError>() {
+        return Some(match e {
+            ProjectError::NotInProject | ProjectError::NotFound { .. } => ErrorCode::NotInProject,
+            ProjectError::AlreadyExists(_) => ErrorCode::Conflict,
+            ProjectError::CreateFailed(_) => ErrorCode::Io,
+            ProjectError::ExternalIdNotFound(_) => ErrorCode::NotFound,
+            ProjectError::AmbiguousExternalId(..) => ErrorCode::Conflict,
+        });
+    }
+    if let Some(e) = err.downcast_ref::<GraphError>() {
+        return Some(match e {
+            GraphError::CycleDetected(..) | GraphError::SelfDependency(_) => {
+                ErrorCode::DependencyCycle
+            }
+            GraphError::TaskNotFound(_) => ErrorCode::NotFound,
+        });
+    }
+    if let Some(e) = err.downcast_ref::<PluginError>() {
+        return Some(match e {
+            PluginError::NotFound(_) => ErrorCode::PluginNotFound,
+            PluginError::Failed(_) => ErrorCode::Plugin,
+        });
+    }
+    if err.is::<IdError>() {
+        return Some(ErrorCode::InvalidId);
+    }
+    if err.is::<FormatError>() {
+        return Some(ErrorCode::UnsupportedFormat);
+    }
+    if err.is::<ConfigError>() || err.is::<toml::de::Error>() {
+        return Some(ErrorCode::Config);
+    }
+    if err.is::<CacheError>() {
+        return Some(ErrorCode::Io);
+    }
+    if err.is::<FrontmatterError>() {
+        return Some(ErrorCode::Validation);
+    }
+    if err.is::<LockError>() {
+        return Some(ErrorCode::LockTimeout);
+    }
+    if let Some(e) = err.downcast_ref::<EncryptionError>() {
+        return Some(match e {
+            EncryptionError::Locked => ErrorCode::StoreLocked,
+            EncryptionError::WrongPassphrase => ErrorCode::BadPassphrase,
+            EncryptionError::Corrupt(_) => ErrorCode::Io,
+        });
+    }
+    if err.is::<TxError>() {
+        return Some(ErrorCode::Conflict);
+    }
+    if let Some(e) = err.downcast_ref::<std::io::Error>() {
+        return Some(match e.kind() {
+            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => ErrorCode::LockTimeout,
+            _ => ErrorCode::Io,
+        });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn classifies_through_context() {
+        let err = Err::<(), _>(CliError::not_found("Task", "t-1234567"))
+            .context("Failed to show task")
+            .unwrap_err();
+        assert_eq!(classify(&err), ErrorCode::NotFound);
+        assert_eq!(classify(&anyhow::anyhow!("boom")), ErrorCode::General);
+
+        let err = anyhow::Error::new(ProjectError::NotInProject);
+        assert_eq!(classify(&err).as_str(), "E_NOT_IN_PROJECT");
+        assert_eq!(classify(&err).exit_code(), 8);
+    }
+}
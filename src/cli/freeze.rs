@@ -0,0 +1,217 @@
+//! Frozen briefs and tasks
+//!
+//! `shape freeze <id> --reason R` marks a brief or task as frozen. Commands
+//! that change a frozen task (or any task of a frozen brief) fail with
+//! `E_FROZEN` unless `--override <REASON>` is given; overrides are recorded
+//! in `.shape/audit.jsonl`.
+
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use anyhow::Result;
+
+use super::agent::get_agent_name;
+use super::error::{CliError, ErrorCode};
+use super::output::Output;
+use crate::domain::{Brief, BriefId, FreezeInfo, Task};
+use crate::storage::{AuditEntry, Project};
+
+/// `--override` reason and the command it was given to
+pub(crate) struct Override {
+    pub(crate) reason: String,
+    pub(crate) command: String,
+}
+
+static OVERRIDE: OnceLock<Override> = OnceLock::new();
+
+/// Allows this run to change frozen entities (`--override`). Only the first
+/// call has an effect.
+pub fn set_override(reason: String, command: String) {
+    let _ = OVERRIDE.set(Override { reason, command });
+}
+
+/// Fails if the task or its brief is frozen, unless overridden
+pub(crate) fn ensure_task_mutable(project: &Project, task: &Task) -> Result<()> {
+    ensure_task_mutable_with(project, task, None)
+}
+
+/// [`ensure_task_mutable`] with an explicit override, falling back to
+/// `--override` (library callers such as [`crate::api::ShapeApi`])
+pub(crate) fn ensure_task_mutable_with(
+    project: &Project,
+    task: &Task,
+    over: Option<&Override>,
+) -> Result<()> {
+    let over = over.or(OVERRIDE.get());
+    let id = task.id.to_string();
+    if let Some(info) = &task.frozen {
+        return check(project, &id, format!("Task {}", id), info, over);
+    }
+    let Some(brief_id) = task.id.brief_id() else {
+        return Ok(());
+    };
+    match project
+        .brief_store()
+        .read(&brief_id)?
+        .and_then(|b| b.frozen)
+    {
+        Some(info) => check(
+            project,
+            &id,
+            format!("Task {} belongs to brief {} which", id, brief_id),
+            &info,
+            over,
+        ),
+        None => Ok(()),
+    }
+}
+
+/// Fails if the brief is frozen, unless overridden
+pub(crate) fn ensure_brief_mutable(project: &Project, brief: &Brief) -> Result<()> {
+    ensure_brief_mutable_with(project, brief, None)
+}
+
+/// [`ensure_brief_mutable`] with an explicit override, falling back to
+/// `--override`
+pub(crate) fn ensure_brief_mutable_with(
+    project: &Project,
+    brief: &Brief,
+    over: Option<&Override>,
+) -> Result<()> {
+    let over = over.or(OVERRIDE.get());
+    let id = brief.id.to_string();
+    match &brief.frozen {
+        Some(info) => check(project, &id, format!("Brief {}", id), info, over),
+        None => Ok(()),
+    }
+}
+
+/// IDs of frozen briefs, for skipping their tasks in bulk maintenance
+pub(super) fn frozen_briefs(project: &Project) -> Result<HashSet<BriefId>> {
+    Ok(project
+        .brief_store()
+        .read_all()?
+        .into_values()
+        .filter(|b| b.frozen.is_some())
+        .map(|b| b.id)
+        .collect())
+}
+
+/// Returns true if the task or its brief (one of `frozen_briefs`) is frozen
+pub(super) fn is_frozen(task: &Task, frozen_briefs: &HashSet<BriefId>) -> bool {
+    task.frozen.is_some() || task.brief_id().is_some_and(|b| frozen_briefs.contains(&b))
+}
+
+/// Refuses the change, or audits it when `--override` was given
+fn check(
+    project: &Project,
+    id: &str,
+    subject: String,
+    info: &FreezeInfo,
+    over: Option<&Override>,
+) -> Result<()> {
+    let Some(over) = over else {
+        return Err(CliError::new(
+            ErrorCode::Frozen,
+            format!(
+                "{} is frozen by {}: {} (use --override <REASON> to change it anyway)",
+                subject, info.by, info.reason
+            ),
+        )
+        .into());
+    };
+
+    project.audit(&AuditEntry {
+        at: chrono::Utc::now(),
+        by: get_agent_name(project, None),
+        id: id.to_string(),
+        action: over.command.clone(),
+        reason: over.reason.clone(),
+    })
+}
+
+/// Freezes a brief or task
+pub fn freeze(output: &Output, id: &str, reason: &str) -> Result<()> {
+    let project = Project::open_current()?;
+    let agent = get_agent_name(&project, None);
+
+    let (kind, id, info) = if let Ok(brief_id) = project.resolve_brief_id(id) {
+        let store = project.brief_store();
+        let mut brief = store
+            .read(&brief_id)?
+            .ok_or_else(|| CliError::not_found("Brief", &brief_id))?;
+        brief.freeze(reason, agent);
+        store.write(&brief)?;
+        ("brief", brief_id.to_string(), brief.frozen)
+    } else {
+        let task_id = project.resolve_task_id(id)?;
+        let store = project.task_store();
+        let mut task = store
+            .read_all()?
+            .remove(&task_id)
+            .ok_or_else(|| CliError::not_found("Task", &task_id))?;
+        task.freeze(reason, agent);
+        store.update(&task)?;
+        ("task", task_id.to_string(), task.frozen)
+    };
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": id,
+            "type": kind,
+            "frozen": info,
+        }));
+    } else {
+        output.success(&format!("Froze {}: {} ({})", kind, id, reason));
+    }
+    Ok(())
+}
+
+/// Lifts a freeze from a brief or task
+pub fn unfreeze(output: &Output, id: &str) -> Result<()> {
+    let project = Project::open_current()?;
+
+    let (kind, id, was_frozen) = if let Ok(brief_id) = project.resolve_brief_id(id) {
+        let store = project.brief_store();
+        let mut brief = store
+            .read(&brief_id)?
+            .ok_or_else(|| CliError::not_found("Brief", &brief_id))?;
+        let was_frozen = brief.unfreeze();
+        if was_frozen {
+            store.write(&brief)?;
+        }
+        ("Brief", brief_id.to_string(), was_frozen)
+    } else {
+        let task_id = project.resolve_task_id(id)?;
+        let store = project.task_store();
+        let mut task = store
+            .read_all()?
+            .remove(&task_id)
+            .ok_or_else(|| CliError::not_found("Task", &task_id))?;
+        let agent = get_agent_name(&project, None);
+        let was_frozen = task.unfreeze(Some(&agent));
+        if was_frozen {
+            store.update(&task)?;
+        }
+        ("Task", task_id.to_string(), was_frozen)
+    };
+
+    if !was_frozen {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!("{} {} is not frozen", kind, id),
+        )
+        .into());
+    }
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": id,
+            "type": kind.to_lowercase(),
+            "frozen": null,
+        }));
+    } else {
+        output.success(&format!("Unfroze {}: {}", kind.to_lowercase(), id));
+    }
+    Ok(())
+}
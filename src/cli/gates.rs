@@ -0,0 +1,188 @@
+//! Completion gates for `task done`
+//!
+//! `[gates]` lists checks a task must pass before it is completed (see
+//! [`Gate`]), and a brief's `definition_of_done` adds more for its tasks.
+//! `task done` refuses with a report of the unmet gates unless
+//! `--skip-gates` is given, which is recorded in `.shape/audit.jsonl`.
+
+use anyhow::Result;
+
+use super::agent::get_agent_name;
+use super::error::{CliError, ErrorCode};
+use super::freeze::ensure_task_mutable;
+use super::output::Output;
+use crate::domain::{checked_items, definition_of_done, Gate, Task, CHECKED_META_KEY};
+use crate::storage::{AuditEntry, Project};
+
+/// Gates of the task: configured ones, then its brief's definition of done
+pub(super) fn for_task(project: &Project, task: &Task) -> Result<Vec<Gate>> {
+    let mut gates: Vec<Gate> = project
+        .config()
+        .project
+        .gates
+        .for_labels(&task.labels())
+        .into_iter()
+        .cloned()
+        .collect();
+    let brief = match task.brief_id() {
+        Some(id) => project.brief_store().read(&id)?,
+        None => None,
+    };
+    for gate in brief.iter().flat_map(|b| definition_of_done(b, task)) {
+        if !gates.contains(&gate) {
+            gates.push(gate);
+        }
+    }
+    Ok(gates)
+}
+
+/// Gates of the task that it doesn't pass
+pub(crate) fn unmet(project: &Project, task: &Task) -> Result<Vec<Gate>> {
+    let mut gates = for_task(project, task)?;
+    gates.retain(|gate| !gate.check(task));
+    Ok(gates)
+}
+
+/// Refusal listing every gate of the task, met or not, with a command to
+/// satisfy each unmet one
+pub(crate) fn refusal(project: &Project, task: &Task) -> Result<CliError> {
+    let id = task.id.to_string();
+    let gates = for_task(project, task)?;
+    let report: Vec<String> = gates
+        .iter()
+        .map(|gate| {
+            if gate.check(task) {
+                format!("  [x] {}", gate)
+            } else {
+                format!("  [ ] {}: {}", gate, gate.hint(&id))
+            }
+        })
+        .collect();
+    Ok(CliError::new(
+        ErrorCode::GatesFailed,
+        format!(
+            "Task {} doesn't pass its completion gates (--skip-gates <REASON> completes it anyway):\n{}",
+            id,
+            report.join("\n")
+        ),
+    ))
+}
+
+/// Records that a task was completed with unmet gates
+pub(super) fn audit_skip(
+    project: &Project,
+    task: &Task,
+    unmet: &[String],
+    reason: &str,
+) -> Result<()> {
+    let reason = if reason.is_empty() {
+        "no reason given"
+    } else {
+        reason
+    };
+    project.audit(&AuditEntry {
+        at: chrono::Utc::now(),
+        by: get_agent_name(project, None),
+        id: task.id.to_string(),
+        action: format!("task done --skip-gates ({})", unmet.join(", ")),
+        reason: reason.to_string(),
+    })
+}
+
+/// `task check`: ticks a checklist item off, or lists the task's gates
+pub(super) fn check(
+    output: &Output,
+    id_str: &str,
+    item: Option<&str>,
+    uncheck: bool,
+) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.task_store();
+    let id = project.resolve_task_id(id_str)?;
+    let mut tasks = store.read_all()?;
+    let task = tasks
+        .get_mut(&id)
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    let gates = for_task(&project, task)?;
+
+    let Some(item) = item else {
+        if output.is_json() {
+            let items: Vec<_> = gates
+                .iter()
+                .map(|gate| {
+                    serde_json::json!({
+                        "gate": gate.to_string(),
+                        "met": gate.check(task),
+                        "hint": gate.hint(&id.to_string()),
+                    })
+                })
+                .collect();
+            output.data(&serde_json::json!({ "id": id.to_string(), "gates": items }));
+        } else if gates.is_empty() {
+            println!("{} has no completion gates.", id);
+        } else {
+            for gate in &gates {
+                let mark = if gate.check(task) { "x" } else { " " };
+                println!("[{}] {}", mark, gate);
+            }
+        }
+        return Ok(());
+    };
+
+    let items: Vec<&str> = gates
+        .iter()
+        .filter_map(|gate| match gate {
+            Gate::Checked(item) => Some(item.as_str()),
+            _ => None,
+        })
+        .collect();
+    let Some(item) = items
+        .iter()
+        .find(|i| i.eq_ignore_ascii_case(item.trim()))
+        .map(|i| i.to_string())
+    else {
+        return Err(CliError::new(
+            ErrorCode::Validation,
+            format!(
+                "'{}' is not a checklist item of {} (items: {})",
+                item,
+                id,
+                if items.is_empty() {
+                    "none".to_string()
+                } else {
+                    items.join(", ")
+                }
+            ),
+        )
+        .into());
+    };
+    ensure_task_mutable(&project, task)?;
+
+    let mut checked: Vec<String> = checked_items(task)
+        .into_iter()
+        .filter(|i| *i != item)
+        .map(str::to_string)
+        .collect();
+    if !uncheck {
+        checked.push(item.clone());
+    }
+    if checked.is_empty() {
+        task.remove_meta(CHECKED_META_KEY);
+    } else {
+        task.set_meta(CHECKED_META_KEY, serde_json::json!(checked));
+    }
+    store.update(task)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": id.to_string(),
+            "item": item,
+            "checked": !uncheck,
+        }));
+    } else if uncheck {
+        output.success(&format!("Unchecked \"{}\" on {}", item, id));
+    } else {
+        output.success(&format!("Checked \"{}\" on {}", item, id));
+    }
+    Ok(())
+}
@@ -0,0 +1,250 @@
+//! Garbage detection in `.shape/`
+//!
+//! Long-lived projects accumulate files nothing points at anymore:
+//! attachments of deleted tasks, sync mappings for deleted briefs and tasks,
+//! and cache rows for removed briefs. `shape gc` reports them with their
+//! sizes; `--prune` deletes them.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::output::Output;
+use crate::domain::LinkType;
+use crate::plugin::prune_mappings;
+use crate::storage::Project;
+
+/// One piece of garbage
+#[derive(Debug, Serialize)]
+struct Garbage {
+    /// `attachment`, `sync_mapping` or `cache`
+    kind: &'static str,
+
+    /// Path relative to the project root
+    path: String,
+
+    /// Why it is garbage
+    reason: String,
+
+    /// Bytes reclaimed by pruning it
+    bytes: u64,
+}
+
+pub fn run(output: &Output, prune: bool) -> Result<()> {
+    let project = Project::open_current()?;
+
+    let mut garbage = scan_attachments(&project, prune)?;
+    garbage.extend(scan_sync_mappings(&project, prune)?);
+    garbage.extend(scan_cache(&project, prune)?);
+    let total: u64 = garbage.iter().map(|g| g.bytes).sum();
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "pruned": prune,
+            "items": garbage,
+            "total_bytes": total,
+        }));
+        return Ok(());
+    }
+
+    if garbage.is_empty() {
+        println!("No garbage found.");
+        return Ok(());
+    }
+    for g in &garbage {
+        println!("{:<12} {:>10}  {}  ({})", g.kind, g.bytes, g.path, g.reason);
+    }
+    println!();
+    if prune {
+        output.success(&format!(
+            "Pruned {} item(s), {} bytes",
+            garbage.len(),
+            total
+        ));
+    } else {
+        println!(
+            "{} item(s), {} bytes. Run `shape gc --prune` to remove them.",
+            garbage.len(),
+            total
+        );
+    }
+
+    Ok(())
+}
+
+/// Attachment directories of deleted tasks, and files no task links to
+fn scan_attachments(project: &Project, prune: bool) -> Result<Vec<Garbage>> {
+    let dir = project.attachments_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let tasks = project.task_store().read_all()?;
+    let task_ids: HashSet<String> = tasks.keys().map(|id| id.to_string()).collect();
+    let linked: HashSet<&str> = tasks
+        .values()
+        .flat_map(|t| &t.links)
+        .filter(|l| l.link_type == LinkType::File)
+        .map(|l| l.reference.as_str())
+        .collect();
+
+    let mut garbage = Vec::new();
+    for entry in sorted_entries(&dir)? {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path();
+        let rel = format!(".shape/attachments/{}", name);
+
+        if !path.is_dir() {
+            garbage.push(Garbage {
+                kind: "attachment",
+                path: rel,
+                reason: "not in a task's attachment directory".to_string(),
+                bytes: size_of(&path),
+            });
+            if prune {
+                fs::remove_file(&path)?;
+            }
+            continue;
+        }
+
+        if !task_ids.contains(&name) {
+            garbage.push(Garbage {
+                kind: "attachment",
+                path: format!("{}/", rel),
+                reason: format!("task {} no longer exists", name),
+                bytes: size_of(&path),
+            });
+            if prune {
+                fs::remove_dir_all(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+            }
+            continue;
+        }
+
+        for file in sorted_entries(&path)? {
+            let file_rel = format!("{}/{}", rel, file.file_name().to_string_lossy());
+            if linked.contains(file_rel.as_str()) {
+                continue;
+            }
+            garbage.push(Garbage {
+                kind: "attachment",
+                path: file_rel,
+                reason: format!("not linked from task {}", name),
+                bytes: size_of(&file.path()),
+            });
+            if prune {
+                if file.path().is_dir() {
+                    fs::remove_dir_all(file.path())?;
+                } else {
+                    fs::remove_file(file.path())?;
+                }
+            }
+        }
+    }
+    Ok(garbage)
+}
+
+/// Sync mappings whose local brief or task was deleted
+fn scan_sync_mappings(project: &Project, prune: bool) -> Result<Vec<Garbage>> {
+    let dir = project.sync_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut local_ids: HashSet<String> = project
+        .task_store()
+        .read_all()?
+        .keys()
+        .map(|id| id.to_string())
+        .collect();
+    local_ids.extend(
+        project
+            .brief_store()
+            .read_all()?
+            .keys()
+            .map(|id| id.to_string()),
+    );
+
+    let mut garbage = Vec::new();
+    for entry in sorted_entries(&dir)? {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "jsonl") {
+            continue;
+        }
+        let rel = format!(".shape/sync/{}", entry.file_name().to_string_lossy());
+        let removed = prune_mappings(&path, |m| local_ids.contains(&m.local_id), !prune)?;
+        for mapping in removed {
+            let bytes = serde_json::to_string(&mapping).map_or(0, |l| l.len() as u64 + 1);
+            garbage.push(Garbage {
+                kind: "sync_mapping",
+                path: rel.clone(),
+                reason: format!(
+                    "{} (remote {}) no longer exists",
+                    mapping.local_id, mapping.remote_id
+                ),
+                bytes,
+            });
+        }
+    }
+    Ok(garbage)
+}
+
+/// Cache rows for briefs whose files were removed
+fn scan_cache(project: &Project, prune: bool) -> Result<Vec<Garbage>> {
+    let db = project.cache_dir().join("shape.db");
+    if !db.exists() {
+        return Ok(Vec::new());
+    }
+
+    let briefs = project.brief_store().read_all_meta()?;
+    let existing: HashSet<String> = briefs.keys().map(|id| id.to_string()).collect();
+    let mut removed: Vec<String> = project
+        .cache()?
+        .list_briefs()?
+        .into_iter()
+        .map(|b| b.id)
+        .filter(|id| !existing.contains(id))
+        .collect();
+    removed.sort();
+
+    if prune && !removed.is_empty() {
+        project.rebuild_cache()?;
+    }
+    Ok(removed
+        .into_iter()
+        .map(|id| Garbage {
+            kind: "cache",
+            path: ".shape/.cache/shape.db".to_string(),
+            reason: format!("brief {} was removed", id),
+            bytes: 0,
+        })
+        .collect())
+}
+
+fn sorted_entries(dir: &Path) -> Result<Vec<fs::DirEntry>> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|e| e.ok())
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+    Ok(entries)
+}
+
+/// Size of a file, or of everything under a directory
+fn size_of(path: &Path) -> u64 {
+    if path.is_dir() {
+        fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| size_of(&e.path()))
+                    .sum()
+            })
+            .unwrap_or(0)
+    } else {
+        fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    }
+}
@@ -0,0 +1,295 @@
+//! GitHub-flavored markdown task lists
+//!
+//! `shape task list <brief> --format gfm` prints tasks as a checklist that
+//! can be pasted into an issue or PR description, each item ending with its
+//! task ID in an HTML comment (hidden once rendered) and subtasks nested
+//! under their parent:
+//!
+//! ```text
+//! - [x] Design schema <!-- b-7f2a3b1.1 -->
+//! - [ ] Build API <!-- b-7f2a3b1.2 -->
+//!   - [ ] Auth endpoints <!-- b-7f2a3b1.2.1 -->
+//! ```
+//!
+//! `shape import gfm` reads such a list back, edited or ticked on GitHub,
+//! and applies each checkbox to its task: ticked completes it, unticked
+//! reopens it. Items without an ID comment are reported and left alone.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use super::freeze::ensure_task_mutable;
+use super::gates;
+use super::output::Output;
+use super::review;
+use crate::domain::{by_rank, Brief, BriefId, Task, TaskId, TaskStatus};
+use crate::storage::Project;
+
+/// Renders tasks as a checklist. With `headings`, each brief's tasks (and
+/// standalone tasks) get a heading of their own.
+pub(super) fn task_list(
+    tasks: &[&Task],
+    briefs: &HashMap<BriefId, Brief>,
+    headings: bool,
+) -> String {
+    let present: HashSet<&TaskId> = tasks.iter().map(|t| &t.id).collect();
+    let mut children: HashMap<TaskId, Vec<&Task>> = HashMap::new();
+    let mut groups: BTreeMap<Option<BriefId>, Vec<&Task>> = BTreeMap::new();
+    for task in by_rank(tasks.iter().copied()) {
+        match task.id.parent().filter(|p| present.contains(p)) {
+            Some(parent) => children.entry(parent).or_default().push(task),
+            None => groups.entry(task.brief_id()).or_default().push(task),
+        }
+    }
+
+    let mut out = String::new();
+    // Standalone tasks (no brief) sort first in the map but read best last
+    let (standalone, by_brief): (Vec<_>, Vec<_>) =
+        groups.into_iter().partition(|(brief, _)| brief.is_none());
+    for (brief, roots) in by_brief.into_iter().chain(standalone) {
+        if headings {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            let heading = match brief.as_ref().and_then(|id| briefs.get(id)) {
+                Some(b) => format!("### {} ({})", b.title, b.id),
+                None => match brief {
+                    Some(id) => format!("### {}", id),
+                    None => "### Standalone".to_string(),
+                },
+            };
+            out.push_str(&heading);
+            out.push_str("\n\n");
+        }
+        for task in roots {
+            push_item(&mut out, task, &children, 0);
+        }
+    }
+    out
+}
+
+fn push_item(out: &mut String, task: &Task, children: &HashMap<TaskId, Vec<&Task>>, depth: usize) {
+    let mark = if task.status.is_complete() { 'x' } else { ' ' };
+    out.push_str(&format!(
+        "{}- [{}] {} <!-- {} -->\n",
+        "  ".repeat(depth),
+        mark,
+        task.title.trim(),
+        task.id
+    ));
+    for child in children.get(&task.id).into_iter().flatten() {
+        push_item(out, child, children, depth + 1);
+    }
+}
+
+/// A checklist item read back from markdown
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Item {
+    checked: bool,
+    title: String,
+    /// ID from the trailing `<!-- ... -->` comment
+    id: Option<String>,
+}
+
+/// Reads the checklist items in `text`; other lines are ignored
+fn parse(text: &str) -> Vec<Item> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let rest = line
+                .strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))
+                .or_else(|| line.strip_prefix("+ "))?;
+            let (checked, rest) = match rest.get(..3)? {
+                "[ ]" => (false, &rest[3..]),
+                "[x]" | "[X]" => (true, &rest[3..]),
+                _ => return None,
+            };
+            let rest = rest.trim();
+            let (title, id) = match rest.strip_suffix("-->").and_then(|r| r.rsplit_once("<!--")) {
+                Some((title, id)) => (title.trim(), Some(id.trim().to_string())),
+                None => (rest, None),
+            };
+            Some(Item {
+                checked,
+                title: title.to_string(),
+                id: id.filter(|id| !id.is_empty()),
+            })
+        })
+        .filter(|item| !item.title.is_empty() || item.id.is_some())
+        .collect()
+}
+
+/// What `shape import gfm` did: status changes, skipped items with the
+/// reason, and titles of items without a task ID
+type ImportOutcome = (
+    Vec<(TaskId, TaskStatus, TaskStatus)>,
+    Vec<(String, String)>,
+    Vec<String>,
+);
+
+/// `shape import gfm`: applies checkbox states from `file` (`-` for stdin)
+pub(super) fn import(output: &Output, file: &Path, dry_run: bool) -> Result<()> {
+    let text = if file == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .context("Failed to read stdin")?;
+        text
+    } else {
+        std::fs::read_to_string(file)
+            .with_context(|| format!("Failed to read {}", file.display()))?
+    };
+    let items = parse(&text);
+
+    let project = Project::open_current()?;
+    let store = project.task_store();
+    let apply = |tasks: &mut HashMap<TaskId, Task>| -> Result<ImportOutcome> {
+        let mut changed: Vec<(TaskId, TaskStatus, TaskStatus)> = Vec::new();
+        let mut skipped: Vec<(String, String)> = Vec::new();
+        let mut unmatched: Vec<String> = Vec::new();
+        for item in &items {
+            let Some(id_str) = &item.id else {
+                unmatched.push(item.title.clone());
+                continue;
+            };
+            let id = match project.resolve_task_id(id_str) {
+                Ok(id) if tasks.contains_key(&id) => id,
+                _ => {
+                    skipped.push((id_str.clone(), "no such task".to_string()));
+                    continue;
+                }
+            };
+            let task = &tasks[&id];
+            if item.checked == task.status.is_complete() {
+                continue;
+            }
+            if let Err(e) = ensure_task_mutable(&project, task) {
+                skipped.push((id.to_string(), format!("{:#}", e)));
+                continue;
+            }
+            if item.checked {
+                if let Err(e) = review::ensure_approved(&project, task) {
+                    skipped.push((id.to_string(), format!("{:#}", e)));
+                    continue;
+                }
+                let unmet = gates::unmet(&project, task)?;
+                if !unmet.is_empty() {
+                    let names: Vec<String> = unmet.iter().map(|g| g.to_string()).collect();
+                    skipped.push((id.to_string(), format!("unmet gates: {}", names.join(", "))));
+                    continue;
+                }
+            }
+
+            let from = task.status;
+            let task = tasks.get_mut(&id).expect("task exists");
+            if item.checked {
+                task.complete();
+            } else {
+                task.reopen();
+            }
+            changed.push((id, from, task.status));
+        }
+        Ok((changed, skipped, unmatched))
+    };
+    // Checks and changes happen under one write lock, so nothing written
+    // meanwhile is lost
+    let (changed, skipped, unmatched) = if dry_run {
+        apply(&mut store.read_all()?)?
+    } else {
+        store.modify(apply)?
+    };
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "dry_run": dry_run,
+            "items": items.len(),
+            "changed": changed.iter().map(|(id, from, to)| serde_json::json!({
+                "id": id.to_string(),
+                "from": from,
+                "to": to,
+            })).collect::<Vec<_>>(),
+            "skipped": skipped.iter().map(|(id, reason)| serde_json::json!({
+                "id": id,
+                "reason": reason,
+            })).collect::<Vec<_>>(),
+            "unmatched": unmatched,
+        }));
+        return Ok(());
+    }
+
+    let verb = if dry_run { "Would change" } else { "Changed" };
+    if changed.is_empty() {
+        println!("No status changes in {} checklist items.", items.len());
+    } else {
+        println!(
+            "{} {} of {} checklist items:",
+            verb,
+            changed.len(),
+            items.len()
+        );
+        for (id, from, to) in &changed {
+            println!("  {}  {} -> {}", id, from, to);
+        }
+    }
+    if !skipped.is_empty() {
+        println!("Skipped:");
+        for (id, reason) in &skipped {
+            println!("  {}  {}", id, reason);
+        }
+    }
+    if !unmatched.is_empty() {
+        println!("Items without a task ID (left alone):");
+        for title in &unmatched {
+            println!("  {}", title);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_nested_checklist_and_parses_it_back() {
+        let brief = Brief::new("Launch", "minimal");
+        let mut design = Task::new(TaskId::new(&brief.id, 1), "Design schema");
+        design.complete();
+        let build = Task::new(TaskId::new(&brief.id, 2), "Build API");
+        let auth = Task::new(build.id.subtask(1), "Auth endpoints");
+        let briefs: HashMap<BriefId, Brief> = [(brief.id.clone(), brief.clone())].into();
+
+        let text = task_list(&[&auth, &build, &design], &briefs, false);
+        assert_eq!(
+            text,
+            format!(
+                "- [x] Design schema <!-- {} -->\n- [ ] Build API <!-- {} -->\n  - [ ] Auth endpoints <!-- {} -->\n",
+                design.id, build.id, auth.id
+            )
+        );
+        let with_heading = task_list(&[&design], &briefs, true);
+        assert!(with_heading.starts_with(&format!("### Launch ({})\n\n", brief.id)));
+
+        let edited = format!(
+            "Some intro\n{}* [X] Extra item\n- [ ]\n",
+            text.replace("[ ] Build", "[x] Build")
+        );
+        let items = parse(&edited);
+        assert_eq!(items.len(), 4);
+        assert!(items[1].checked);
+        assert_eq!(items[1].id.as_deref(), Some(build.id.to_string().as_str()));
+        assert_eq!(items[2].title, "Auth endpoints");
+        assert_eq!(
+            items[3],
+            Item {
+                checked: true,
+                title: "Extra item".to_string(),
+                id: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! Dependency graph export
+//!
+//! `shape graph export` writes tasks as nodes and their dependencies as
+//! edges for graph analysis tools. `--format json-graph` (or `json`) emits
+//! the [JSON Graph Format](https://jsongraphformat.info) v2 and
+//! `--format gexf` a GEXF 1.3 document for Gephi and similar tools. Edges
+//! point from the dependency to the task that depends on it, so `blocks`
+//! edges read in the order work happens.
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use super::output::{xml_escape, Output, OutputFormat};
+use crate::domain::{by_id, Task};
+use crate::storage::Project;
+
+#[derive(Subcommand)]
+pub enum GraphCommands {
+    /// Export the task dependency graph (--format json-graph or gexf)
+    ///
+    /// Example:
+    ///   shape graph export --format gexf > tasks.gexf
+    Export {
+        /// Only include tasks of this brief
+        #[arg(long)]
+        brief: Option<String>,
+    },
+}
+
+/// Formats `shape graph export` supports beyond text and JSON
+pub(super) const EXPORT_FORMATS: &[OutputFormat] = &[OutputFormat::JsonGraph, OutputFormat::Gexf];
+
+pub fn run(cmd: GraphCommands, output: &Output) -> Result<()> {
+    match cmd {
+        GraphCommands::Export { brief } => export(output, brief.as_deref()),
+    }
+}
+
+/// A dependency between two exported tasks
+struct Edge<'a> {
+    source: &'a Task,
+    target: &'a Task,
+    kind: String,
+}
+
+fn export(output: &Output, brief_filter: Option<&str>) -> Result<()> {
+    let project = Project::open_current()?;
+    let tasks = match brief_filter {
+        Some(brief_str) => {
+            let brief_id = project.resolve_brief_id(brief_str)?;
+            project.task_store().read_for_brief(&brief_id)?
+        }
+        None => project.task_store().read_all()?,
+    };
+
+    let nodes = by_id(tasks.values());
+    // Dependencies on tasks outside the selection are left out
+    let edges: Vec<Edge> = nodes
+        .iter()
+        .flat_map(|task| {
+            task.depends_on.iter().filter_map(|dep| {
+                tasks.get(&dep.task).map(|source| Edge {
+                    source,
+                    target: task,
+                    kind: dep_kind(dep.dep_type),
+                })
+            })
+        })
+        .collect();
+
+    match output.format() {
+        OutputFormat::Json | OutputFormat::JsonGraph => output.data(&json_graph(&nodes, &edges)),
+        OutputFormat::Gexf => print!("{}", gexf(&nodes, &edges)),
+        _ => {
+            for edge in &edges {
+                println!("{} -> {} ({})", edge.source.id, edge.target.id, edge.kind);
+            }
+            println!(
+                "{} task(s), {} dependency edge(s)",
+                nodes.len(),
+                edges.len()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Serialized dependency type (`blocks`, `provenance`, ...)
+fn dep_kind(dep_type: crate::domain::DependencyType) -> String {
+    serde_json::to_value(dep_type)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Labels from `meta.labels`
+fn labels(task: &Task) -> Vec<&str> {
+    task.get_meta("labels")
+        .and_then(|v| v.as_array())
+        .map(|labels| labels.iter().filter_map(|l| l.as_str()).collect())
+        .unwrap_or_default()
+}
+
+fn json_graph(nodes: &[&Task], edges: &[Edge]) -> serde_json::Value {
+    let nodes: serde_json::Map<String, serde_json::Value> = nodes
+        .iter()
+        .map(|t| {
+            (
+                t.id.to_string(),
+                serde_json::json!({
+                    "label": t.title,
+                    "metadata": {
+                        "status": t.status,
+                        "brief": t.brief_id().map(|b| b.to_string()),
+                        "labels": labels(t),
+                    },
+                }),
+            )
+        })
+        .collect();
+    let edges: Vec<_> = edges
+        .iter()
+        .map(|e| {
+            serde_json::json!({
+                "source": e.source.id.to_string(),
+                "target": e.target.id.to_string(),
+                "relation": e.kind,
+                "directed": true,
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "graph": {
+            "directed": true,
+            "type": "shape-tasks",
+            "nodes": nodes,
+            "edges": edges,
+        }
+    })
+}
+
+fn gexf(nodes: &[&Task], edges: &[Edge]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n");
+    xml.push_str("  <graph mode=\"static\" defaultedgetype=\"directed\">\n");
+    xml.push_str("    <attributes class=\"node\">\n");
+    for attr in ["status", "brief", "labels"] {
+        xml.push_str(&format!(
+            "      <attribute id=\"{0}\" title=\"{0}\" type=\"string\"/>\n",
+            attr
+        ));
+    }
+    xml.push_str("    </attributes>\n");
+
+    xml.push_str("    <nodes>\n");
+    for task in nodes {
+        let brief = task.brief_id().map(|b| b.to_string()).unwrap_or_default();
+        xml.push_str(&format!(
+            "      <node id=\"{}\" label=\"{}\">\n        <attvalues>\n",
+            xml_escape(&task.id.to_string()),
+            xml_escape(&task.title)
+        ));
+        for (attr, value) in [
+            ("status", task.status.to_string()),
+            ("brief", brief),
+            ("labels", labels(task).join("|")),
+        ] {
+            xml.push_str(&format!(
+                "          <attvalue for=\"{}\" value=\"{}\"/>\n",
+                attr,
+                xml_escape(&value)
+            ));
+        }
+        xml.push_str("        </attvalues>\n      </node>\n");
+    }
+    xml.push_str("    </nodes>\n");
+
+    xml.push_str("    <edges>\n");
+    for (i, edge) in edges.iter().enumerate() {
+        xml.push_str(&format!(
+            "      <edge id=\"{}\" source=\"{}\" target=\"{}\" label=\"{}\"/>\n",
+            i,
+            xml_escape(&edge.source.id.to_string()),
+            xml_escape(&edge.target.id.to_string()),
+            edge.kind
+        ));
+    }
+    xml.push_str("    </edges>\n  </graph>\n</gexf>\n");
+    xml
+}
@@ -0,0 +1,99 @@
+//! Guardrails for commands that modify many tasks at once
+//!
+//! When a command would touch more tasks than `guardrails.bulk_threshold`,
+//! it must be confirmed with `--yes` (or interactively on a terminal).
+//! In JSON mode an unconfirmed bulk change returns a dry-run style summary
+//! instead of applying anything.
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::error::{CliError, ErrorCode};
+use super::output::Output;
+use crate::storage::Project;
+
+/// A single pending change in a bulk operation
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkChange {
+    /// ID of the task being changed
+    pub id: String,
+
+    /// Task title
+    pub title: String,
+
+    /// Value before the change
+    pub from: String,
+
+    /// Value after the change
+    pub to: String,
+}
+
+/// Checks whether a bulk change may proceed.
+///
+/// Returns `Ok(true)` when the change is below the threshold, `yes` was given,
+/// or the user confirmed interactively. Returns `Ok(false)` after printing a
+/// confirmation summary in JSON mode. Fails in non-interactive text mode.
+pub fn confirm_bulk(
+    output: &Output,
+    project: &Project,
+    action: &str,
+    changes: &[BulkChange],
+    yes: bool,
+) -> Result<bool> {
+    let threshold = project.config().project.guardrails.bulk_threshold;
+
+    if yes || threshold == 0 || changes.len() <= threshold {
+        return Ok(true);
+    }
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "confirmation_required": true,
+            "dry_run": true,
+            "action": action,
+            "count": changes.len(),
+            "threshold": threshold,
+            "changes": changes,
+        }));
+        return Ok(false);
+    }
+
+    println!(
+        "This would {} {} tasks (threshold: {}):",
+        action,
+        changes.len(),
+        threshold
+    );
+    for change in changes {
+        println!(
+            "  {:<20} {} -> {}  {}",
+            change.id, change.from, change.to, change.title
+        );
+    }
+
+    if io::stdin().is_terminal() {
+        print!("Proceed? [y/N] ");
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        io::stdin().lock().read_line(&mut answer)?;
+        if matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            return Ok(true);
+        }
+
+        println!("Aborted. No changes made.");
+        return Ok(false);
+    }
+
+    Err(CliError::new(
+        ErrorCode::ConfirmationRequired,
+        format!(
+            "Refusing to {} {} tasks without confirmation. Re-run with --yes to apply.",
+            action,
+            changes.len()
+        ),
+    )
+    .into())
+}
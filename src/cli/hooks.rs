@@ -0,0 +1,63 @@
+//! Hooks - shell commands run on project events
+//!
+//! Hooks are configured per event under `[hooks]` in `.shape/config.toml`:
+//!
+//! ```toml
+//! [hooks]
+//! digest = "jq -r .markdown | mail -s 'Weekly digest' team@example.com"
+//! ```
+//!
+//! The command runs through the shell in the project root with the event
+//! payload as JSON on stdin and the event name in `SHAPE_EVENT`. Its output
+//! goes to stderr instead of stdout while a protocol server owns stdout.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{Context, Result};
+
+use crate::storage::Project;
+
+/// Set by `shape serve`: stdout carries protocol messages only
+static STDOUT_RESERVED: AtomicBool = AtomicBool::new(false);
+
+/// Sends the output of hooks fired from now on to stderr
+pub(super) fn reserve_stdout() {
+    STDOUT_RESERVED.store(true, Ordering::Relaxed);
+}
+
+/// Runs the hook configured for `event`. Returns false when there is none.
+pub(super) fn fire(project: &Project, event: &str, payload: &serde_json::Value) -> Result<bool> {
+    let Some(command) = project.config().project.hooks.get(event) else {
+        return Ok(false);
+    };
+
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut cmd = Command::new(shell);
+    cmd.args([flag, command])
+        .current_dir(project.root())
+        .env("SHAPE_EVENT", event)
+        .stdin(Stdio::piped());
+    if STDOUT_RESERVED.load(Ordering::Relaxed) {
+        cmd.stdout(std::io::stderr());
+    }
+    let mut child = cmd
+        .spawn()
+        .with_context(|| format!("Failed to run {} hook: {}", event, command))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores its input closes the pipe early; that's fine
+        let _ = stdin.write_all(serde_json::to_string(payload)?.as_bytes());
+    }
+    let status = child
+        .wait()
+        .with_context(|| format!("Failed to run {} hook: {}", event, command))?;
+    if !status.success() {
+        anyhow::bail!("The {} hook failed ({}): {}", event, status, command);
+    }
+    Ok(true)
+}
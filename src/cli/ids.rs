@@ -0,0 +1,336 @@
+//! ID scheme CLI commands
+//!
+//! `[ids]` in config only affects newly created briefs and tasks. `ids migrate`
+//! rewrites existing IDs to the configured scheme so a store doesn't end up
+//! with a mix of forms. `ids migrate --collisions` instead widens only the
+//! hashes that briefs or standalone tasks share. Old IDs are recorded as redirects and sync mappings
+//! follow the new IDs, so claims held by agents and external links survive.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Result};
+use clap::Subcommand;
+use serde_json::json;
+
+use super::output::Output;
+use super::sync_cmd::mapped_plugins;
+use crate::domain::{widen_colliding, Brief, BriefId, Task, TaskId};
+use crate::plugin::{mapping_path, read_mappings, write_mappings};
+use crate::storage::Project;
+
+#[derive(Subcommand)]
+pub enum IdsCommands {
+    /// Rewrite existing brief and task IDs to the configured scheme
+    Migrate {
+        /// Show the mapping without writing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Only widen the hashes of IDs that collide, leaving the rest as is
+        #[arg(long)]
+        collisions: bool,
+    },
+}
+
+pub fn run(cmd: IdsCommands, output: &Output) -> Result<()> {
+    match cmd {
+        IdsCommands::Migrate {
+            dry_run,
+            collisions,
+        } => migrate(output, dry_run, collisions),
+    }
+}
+
+/// New root of a brief or top-level standalone task
+struct Root {
+    prefix: String,
+    hash: String,
+    slug: Option<String>,
+}
+
+fn migrate(output: &Output, dry_run: bool, collisions: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let brief_store = project.brief_store();
+    let task_store = project.task_store();
+
+    let briefs = brief_store.read_all()?;
+    let plan = if dry_run {
+        plan(&project, &briefs, &task_store.read_all()?, collisions)?
+    } else {
+        // Planned and applied under the write lock, so a task added or
+        // changed meanwhile is migrated (and redirected) too
+        task_store.modify(|tasks| {
+            let plan = plan(&project, &briefs, tasks, collisions)?;
+            if plan.mapping.is_empty() {
+                return Ok(plan);
+            }
+            // Write new brief files before removing old ones so an
+            // interrupted migration never loses a brief
+            for (old, new) in &plan.renamed_briefs {
+                let mut brief = briefs[old].clone();
+                brief.id = new.clone();
+                brief_store.write(&brief)?;
+            }
+            *tasks = std::mem::take(tasks)
+                .into_values()
+                .map(|mut task| {
+                    task.remap_ids(|id| plan.remap(id));
+                    (task.id.clone(), task)
+                })
+                .collect();
+            Ok(plan)
+        })?
+    };
+    let Plan {
+        renamed_briefs,
+        mapping,
+        changes,
+        claimed,
+        ..
+    } = plan;
+
+    if !dry_run && !mapping.is_empty() {
+        for (old, _) in &renamed_briefs {
+            brief_store.remove(old)?;
+        }
+
+        // Old IDs keep resolving for agents that still hold them
+        let reason = if collisions {
+            "ids migrate --collisions"
+        } else {
+            "ids migrate"
+        };
+        project.record_redirects(&changes, reason)?;
+        remap_sync_mappings(&project, &changes)?;
+    }
+    if output.is_json() {
+        let changed: Vec<_> = mapping
+            .iter()
+            .map(|(old, new)| json!({ "old": old, "new": new }))
+            .collect();
+        let claimed: Vec<_> = claimed
+            .iter()
+            .map(|(old, new, agent)| json!({ "old": old, "new": new, "claimed_by": agent }))
+            .collect();
+        output.data(&json!({
+            "dry_run": dry_run,
+            "changed": changed,
+            "claimed": claimed,
+        }));
+    } else if mapping.is_empty() && collisions {
+        output.success("No ID collisions");
+    } else if mapping.is_empty() {
+        output.success("All IDs already match the configured scheme");
+    } else {
+        for (old, new) in &mapping {
+            println!("{} → {}", old, new);
+        }
+        if !claimed.is_empty() {
+            println!();
+            println!("Claimed tasks (old IDs keep resolving for their agents):");
+            for (old, new, agent) in &claimed {
+                println!("  {} → {} (claimed by {})", old, new, agent);
+            }
+        }
+        println!();
+        if dry_run {
+            println!("{} ID(s) would be rewritten (dry run)", mapping.len());
+        } else {
+            output.success(&format!("Rewrote {} ID(s)", mapping.len()));
+        }
+    }
+
+    Ok(())
+}
+
+/// IDs a migration changes
+struct Plan {
+    /// New root of each brief and top-level standalone task, keyed by the
+    /// old root as written (e.g. "b-7f2b4c1")
+    roots: HashMap<String, Root>,
+
+    renamed_briefs: Vec<(BriefId, BriefId)>,
+
+    /// Renamed briefs and top-level standalone tasks, old to new
+    mapping: Vec<(String, String)>,
+
+    /// Every changed ID, for the redirect table and sync mappings
+    changes: Vec<(String, String)>,
+
+    /// Claimed tasks that move: old ID, new ID, agent
+    claimed: Vec<(String, String, String)>,
+}
+
+impl Plan {
+    /// New ID of `id`, if it changes
+    fn remap(&self, id: &TaskId) -> Option<TaskId> {
+        let old_root = match id.brief_id() {
+            Some(brief_id) => brief_id.to_string(),
+            None => id.standalone_root()?.to_string(),
+        };
+        let root = self.roots.get(&old_root)?;
+        let new = id.with_root(&root.prefix, &root.hash, root.slug.as_deref());
+        (&new != id).then_some(new)
+    }
+}
+
+/// Works out the new IDs for `briefs` and `tasks`
+fn plan(
+    project: &Project,
+    briefs: &HashMap<BriefId, Brief>,
+    tasks: &HashMap<TaskId, Task>,
+    collisions: bool,
+) -> Result<Plan> {
+    let scheme = project.id_scheme();
+    let widened = if collisions {
+        widened_hashes(project)?
+    } else {
+        HashMap::new()
+    };
+
+    let mut roots: HashMap<String, Root> = HashMap::new();
+    let mut renamed_briefs = Vec::new();
+    let mut mapping: Vec<(String, String)> = Vec::new();
+    let mut taken = HashSet::new();
+
+    let mut brief_ids: Vec<_> = briefs.keys().collect();
+    brief_ids.sort();
+    for old in brief_ids {
+        let brief = &briefs[old];
+        let new = if collisions {
+            match widened.get(&old.to_string()) {
+                Some(hash) => old.with_hash(hash),
+                None => old.clone(),
+            }
+        } else {
+            scheme.migrate_brief_id(old, &brief.title, &brief.brief_type)
+        };
+        if !taken.insert(new.to_string()) {
+            bail!("Migration would give two briefs the ID {}", new);
+        }
+        roots.insert(
+            old.to_string(),
+            Root {
+                prefix: new.prefix().to_string(),
+                hash: new.hash().to_string(),
+                slug: new.slug().map(str::to_string),
+            },
+        );
+        if &new != old {
+            mapping.push((old.to_string(), new.to_string()));
+            renamed_briefs.push((old.clone(), new));
+        }
+    }
+
+    let mut standalone: Vec<_> = tasks
+        .values()
+        .filter(|t| t.id.is_standalone() && t.id.segments().is_empty())
+        .collect();
+    standalone.sort_by(|a, b| a.id.cmp(&b.id));
+    for task in standalone {
+        let new = if collisions {
+            match widened.get(&task.id.to_string()) {
+                Some(hash) => task.id.with_root(task.id.prefix(), hash, task.id.slug()),
+                None => task.id.clone(),
+            }
+        } else {
+            scheme.migrate_standalone_id(&task.id, &task.title)
+        };
+        if !taken.insert(new.to_string()) {
+            bail!("Migration would give two tasks the ID {}", new);
+        }
+        roots.insert(
+            task.id.to_string(),
+            Root {
+                prefix: new.prefix().to_string(),
+                hash: new.hash().to_string(),
+                slug: new.slug().map(str::to_string),
+            },
+        );
+        if new != task.id {
+            mapping.push((task.id.to_string(), new.to_string()));
+        }
+    }
+
+    let changes = renamed_briefs
+        .iter()
+        .map(|(old, new)| (old.to_string(), new.to_string()))
+        .collect();
+    let mut plan = Plan {
+        roots,
+        renamed_briefs,
+        mapping,
+        changes,
+        claimed: Vec::new(),
+    };
+    let mut task_ids: Vec<_> = tasks.keys().collect();
+    task_ids.sort();
+    for old in task_ids {
+        if let Some(new) = plan.remap(old) {
+            if let Some(agent) = &tasks[old].claimed_by {
+                plan.claimed
+                    .push((old.to_string(), new.to_string(), agent.clone()));
+            }
+            plan.changes.push((old.to_string(), new.to_string()));
+        }
+    }
+    Ok(plan)
+}
+
+/// Widened hashes for every colliding brief and standalone task, keyed by
+/// its old ID
+fn widened_hashes(project: &Project) -> Result<HashMap<String, String>> {
+    let collisions = project.id_collisions()?;
+    let brief_hashes = project
+        .brief_store()
+        .read_all_meta()?
+        .into_keys()
+        .map(|id| id.hash().to_ascii_lowercase());
+    let task_hashes = project
+        .task_store()
+        .read_all()?
+        .into_keys()
+        .filter(|id| id.is_standalone())
+        .map(|id| id.hash().to_ascii_lowercase());
+    let mut taken: HashSet<String> = brief_hashes.chain(task_hashes).collect();
+
+    let mut widened = HashMap::new();
+    for (hash, ids) in collisions {
+        let seeds: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let Some(hashes) = widen_colliding(&hash, &seeds, &taken) else {
+            bail!(
+                "Cannot tell {1} apart by widening their hash {0} to 12 characters",
+                hash,
+                ids.join(", ")
+            );
+        };
+        for (id, new) in ids.into_iter().zip(hashes) {
+            taken.insert(new.clone());
+            widened.insert(id, new);
+        }
+    }
+    Ok(widened)
+}
+
+/// Points sync mappings of changed local IDs at the new IDs
+pub(super) fn remap_sync_mappings(project: &Project, changes: &[(String, String)]) -> Result<()> {
+    let changes: HashMap<&str, &str> = changes
+        .iter()
+        .map(|(old, new)| (old.as_str(), new.as_str()))
+        .collect();
+    for plugin in mapped_plugins(project)? {
+        let path = mapping_path(&project.sync_dir(), &plugin);
+        let mut mappings = read_mappings(&path)?;
+        let mut changed = false;
+        for mapping in &mut mappings {
+            if let Some(new) = changes.get(mapping.local_id.as_str()) {
+                mapping.local_id = new.to_string();
+                changed = true;
+            }
+        }
+        if changed {
+            write_mappings(&path, &mappings)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,1091 @@
+//! Import commands
+//!
+//! Map another tool's export onto briefs and tasks:
+//!
+//! - `shape import trello board.json` reads a Trello board export. Lists
+//!   become briefs (or labels with `--lists-as labels`), cards become tasks,
+//!   checklist items become subtasks and comments become notes.
+//! - `shape import linear issues.csv` reads a Linear CSV export. Projects
+//!   become briefs, issues become tasks and sub-issues become subtasks.
+//! - `shape import github-issues owner/repo` fetches a repository's issues
+//!   once through the GitHub API. Milestones become briefs and issues become
+//!   tasks; nothing is ever written back (for that, see `shape sync`).
+//! - `shape import gfm checklist.md` reads back a markdown task list made
+//!   with `task list --format gfm` and applies its checkboxes to the tasks
+//!   (see [`gfm`]).
+//!
+//! Everything the other imports bring in remembers its ID in the source tool
+//! as an external ID (`trello:<card>`, `linear:ENG-12`, `github:42`), so re-running an
+//! import skips what is already there. `--dry-run` prints the same report
+//! without writing anything.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::{Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::error::CliError;
+use super::freeze::ensure_brief_mutable;
+use super::gfm;
+use super::output::Output;
+use crate::domain::{Brief, BriefId, LinkType, Task, TaskId, TaskStatus};
+use crate::storage::Project;
+
+/// What Trello lists become
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListsAs {
+    /// One brief per list
+    Briefs,
+    /// A label on each card
+    Labels,
+}
+
+/// Which GitHub issues to fetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum IssueState {
+    Open,
+    Closed,
+    All,
+}
+
+#[derive(Subcommand)]
+pub enum ImportCommands {
+    /// Import a Trello board export (Board menu > Print and export > JSON)
+    ///
+    /// Example:
+    ///   shape import trello board.json --dry-run
+    Trello {
+        /// Exported board JSON
+        file: PathBuf,
+
+        /// Map lists to briefs or to labels (default: briefs, or labels with --brief)
+        #[arg(long, value_enum)]
+        lists_as: Option<ListsAs>,
+
+        /// Put every card in this brief
+        #[arg(long)]
+        brief: Option<String>,
+
+        /// Print the ID mapping without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Import a Linear CSV export (Settings > Import / Export > Export CSV)
+    ///
+    /// Example:
+    ///   shape import linear issues.csv --dry-run
+    Linear {
+        /// Exported issues CSV
+        file: PathBuf,
+
+        /// Put every issue in this brief instead of one brief per project
+        #[arg(long)]
+        brief: Option<String>,
+
+        /// Print the ID mapping without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Fetch a GitHub repository's issues once, without syncing back
+    ///
+    /// Milestones become briefs and issues become tasks; pull requests are
+    /// skipped. A token in GITHUB_TOKEN or GH_TOKEN is sent when set, and
+    /// GITHUB_API_URL points at GitHub Enterprise.
+    ///
+    /// Example:
+    ///   shape import github-issues owner/repo --label roadmap --dry-run
+    GithubIssues {
+        /// Repository as owner/repo
+        repo: String,
+
+        /// Only issues with this label (repeat to require several)
+        #[arg(long = "label", value_name = "LABEL")]
+        labels: Vec<String>,
+
+        /// Which issues to fetch
+        #[arg(long, value_enum, default_value_t = IssueState::Open)]
+        state: IssueState,
+
+        /// Put every issue in this brief instead of one brief per milestone
+        #[arg(long)]
+        brief: Option<String>,
+
+        /// Print the ID mapping without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Apply checkbox states from a markdown task list made with
+    /// `task list --format gfm`
+    ///
+    /// Example:
+    ///   shape import gfm checklist.md --dry-run
+    Gfm {
+        /// Markdown file, or - for stdin
+        file: PathBuf,
+
+        /// Print the status changes without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+pub fn run(cmd: ImportCommands, output: &Output) -> Result<()> {
+    match cmd {
+        ImportCommands::Trello {
+            file,
+            lists_as,
+            brief,
+            dry_run,
+        } => {
+            let lists_as = match (lists_as, &brief) {
+                (Some(ListsAs::Briefs), Some(_)) => {
+                    bail!("--lists-as briefs can't be combined with --brief")
+                }
+                (Some(lists_as), _) => lists_as,
+                (None, Some(_)) => ListsAs::Labels,
+                (None, None) => ListsAs::Briefs,
+            };
+            let board: TrelloBoard = serde_json::from_str(&read(&file)?)
+                .with_context(|| format!("Not a Trello board export: {}", file.display()))?;
+            import(
+                output,
+                trello_plan(&board, lists_as),
+                brief.as_deref(),
+                dry_run,
+            )
+        }
+        ImportCommands::Linear {
+            file,
+            brief,
+            dry_run,
+        } => {
+            let plan = linear_plan(&read(&file)?, brief.is_none())
+                .with_context(|| format!("Not a Linear CSV export: {}", file.display()))?;
+            import(output, plan, brief.as_deref(), dry_run)
+        }
+        ImportCommands::GithubIssues {
+            repo,
+            labels,
+            state,
+            brief,
+            dry_run,
+        } => {
+            let issues = fetch_github_issues(&repo, &labels, state)?;
+            import(
+                output,
+                github_plan(&repo, &issues, brief.is_none()),
+                brief.as_deref(),
+                dry_run,
+            )
+        }
+        ImportCommands::Gfm { file, dry_run } => gfm::import(output, &file, dry_run),
+    }
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// An export mapped onto briefs and tasks, before anything is written
+#[derive(Debug, Default)]
+struct Plan {
+    /// External ID system, e.g. `trello`
+    system: &'static str,
+    briefs: Vec<PlannedBrief>,
+    /// Parents come before their subtasks
+    tasks: Vec<PlannedTask>,
+}
+
+#[derive(Debug)]
+struct PlannedBrief {
+    source: String,
+    title: String,
+}
+
+#[derive(Debug, Default)]
+struct PlannedTask {
+    source: String,
+    title: String,
+    description: Option<String>,
+    /// Source ID of the planned brief
+    brief: Option<String>,
+    /// Source ID of the parent task, making this a subtask
+    parent: Option<String>,
+    labels: Vec<String>,
+    status: TaskStatus,
+    notes: Vec<PlannedNote>,
+    url: Option<String>,
+    meta: Vec<(&'static str, String)>,
+}
+
+#[derive(Debug)]
+struct PlannedNote {
+    by: String,
+    text: String,
+    at: Option<DateTime<Utc>>,
+}
+
+/// One line of the ID-mapping report
+#[derive(Debug, Serialize)]
+struct Mapping {
+    kind: &'static str,
+    source: String,
+    id: String,
+    title: String,
+    /// `create`, or `exists` when an earlier import already brought it in
+    action: &'static str,
+}
+
+/// Writes `plan` (unless `dry_run`) and reports the source → Shape IDs
+fn import(output: &Output, plan: Plan, target: Option<&str>, dry_run: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let brief_store = project.brief_store();
+    let task_store = project.task_store();
+    let scheme = project.id_scheme();
+    let system = plan.system;
+
+    let target = match target {
+        Some(s) => {
+            let id = project.resolve_brief_id(s)?;
+            let brief = brief_store
+                .read(&id)?
+                .ok_or_else(|| CliError::not_found("Brief", &id))?;
+            ensure_brief_mutable(&project, &brief)?;
+            Some(id)
+        }
+        None => None,
+    };
+
+    let briefs = brief_store.read_all_meta()?;
+    let tasks = task_store.read_all()?;
+    let known_briefs: HashMap<&str, &BriefId> = briefs
+        .values()
+        .filter_map(|b| b.external_ids.get(system).map(|key| (key.as_str(), &b.id)))
+        .collect();
+    let known_tasks: HashMap<&str, &TaskId> = tasks
+        .values()
+        .filter_map(|t| t.external_ids.get(system).map(|key| (key.as_str(), &t.id)))
+        .collect();
+
+    let mut report = Vec::new();
+    let now = Utc::now();
+    let brief_type = project.config().project.default_brief_type.as_str();
+
+    // Briefs, keyed by source ID
+    let mut brief_ids: HashMap<String, BriefId> = HashMap::new();
+    // Hashes of briefs and standalone tasks; new IDs widen past them
+    let mut taken: HashSet<String> = briefs
+        .keys()
+        .map(|id| id.hash().to_string())
+        .chain(
+            tasks
+                .keys()
+                .filter(|id| id.is_standalone())
+                .map(|id| id.hash().to_string()),
+        )
+        .collect();
+    let mut new_briefs = Vec::new();
+    for planned in &plan.briefs {
+        if let Some(&id) = known_briefs.get(planned.source.as_str()) {
+            brief_ids.insert(planned.source.clone(), id.clone());
+            report.push(mapping("brief", &planned.source, id, &planned.title, false));
+            continue;
+        }
+        let mut brief = Brief::with_scheme(&planned.title, brief_type, scheme);
+        brief.id = scheme.unique_brief_id(&planned.title, brief_type, now, &taken);
+        taken.insert(brief.id.hash().to_string());
+        brief.set_external_id(system, &planned.source);
+        brief_ids.insert(planned.source.clone(), brief.id.clone());
+        report.push(mapping(
+            "brief",
+            &planned.source,
+            &brief.id,
+            &planned.title,
+            true,
+        ));
+        new_briefs.push(brief);
+    }
+
+    // Tasks; sequence numbers continue after existing tasks
+    let mut next_seq: HashMap<String, u32> = HashMap::new();
+    for id in tasks.keys() {
+        let scope = match id.parent() {
+            Some(parent) => parent.to_string(),
+            None => match id.brief_id() {
+                Some(brief) => brief.to_string(),
+                None => continue,
+            },
+        };
+        let seq = next_seq.entry(scope).or_insert(0);
+        *seq = (*seq).max(*id.segments().last().unwrap_or(&0));
+    }
+    let mut task_ids: HashMap<String, TaskId> = HashMap::new();
+    let mut new_tasks = Vec::new();
+    for planned in &plan.tasks {
+        let kind = if planned.parent.is_some() {
+            "subtask"
+        } else {
+            "task"
+        };
+        if let Some(&id) = known_tasks.get(planned.source.as_str()) {
+            task_ids.insert(planned.source.clone(), id.clone());
+            report.push(mapping(kind, &planned.source, id, &planned.title, false));
+            continue;
+        }
+
+        let parent = planned.parent.as_ref().and_then(|p| task_ids.get(p));
+        let brief = target
+            .as_ref()
+            .or_else(|| planned.brief.as_ref().and_then(|b| brief_ids.get(b)));
+        let id = match (parent, brief) {
+            (Some(parent), _) => {
+                let seq = next_seq.entry(parent.to_string()).or_insert(0);
+                *seq += 1;
+                parent.subtask(*seq)
+            }
+            (None, Some(brief)) => {
+                let seq = next_seq.entry(brief.to_string()).or_insert(0);
+                *seq += 1;
+                TaskId::new(brief, *seq)
+            }
+            (None, None) => {
+                let id = scheme.unique_standalone_task_id(&planned.title, now, &taken);
+                taken.insert(id.hash().to_string());
+                id
+            }
+        };
+        task_ids.insert(planned.source.clone(), id.clone());
+        report.push(mapping(kind, &planned.source, &id, &planned.title, true));
+        new_tasks.push(build_task(system, id, planned));
+    }
+
+    if !dry_run {
+        for brief in &new_briefs {
+            brief_store.write(brief)?;
+        }
+        task_store.append_batch(&new_tasks)?;
+    }
+
+    let created = report.iter().filter(|m| m.action == "create").count();
+    if output.is_json() {
+        output.data(&json!({
+            "source": system,
+            "dry_run": dry_run,
+            "created": created,
+            "existing": report.len() - created,
+            "mapping": report,
+        }));
+        return Ok(());
+    }
+
+    for m in &report {
+        let action = match (m.action, dry_run) {
+            ("exists", _) => "exists ",
+            (_, true) => "would  ",
+            (_, false) => "created",
+        };
+        println!(
+            "{} {:<8} {:<24} -> {}  {}",
+            action, m.kind, m.source, m.id, m.title
+        );
+    }
+    let verb = if dry_run { "Would import" } else { "Imported" };
+    output.success(&format!(
+        "{} {} item(s) from {} ({} already imported)",
+        verb,
+        created,
+        system,
+        report.len() - created
+    ));
+    Ok(())
+}
+
+fn mapping(
+    kind: &'static str,
+    source: &str,
+    id: &impl ToString,
+    title: &str,
+    create: bool,
+) -> Mapping {
+    Mapping {
+        kind,
+        source: source.to_string(),
+        id: id.to_string(),
+        title: title.to_string(),
+        action: if create { "create" } else { "exists" },
+    }
+}
+
+fn build_task(system: &str, id: TaskId, planned: &PlannedTask) -> Task {
+    let mut task = Task::new(id, &planned.title);
+    if let Some(description) = &planned.description {
+        task.set_description(description);
+    }
+    if !planned.labels.is_empty() {
+        task.set_meta("labels", json!(planned.labels));
+    }
+    for (key, value) in &planned.meta {
+        task.set_meta(*key, value.as_str());
+    }
+    task.set_external_id(system, &planned.source);
+    if let Some(url) = &planned.url {
+        task.add_link(LinkType::Url, url, None);
+    }
+    for note in &planned.notes {
+        task.add_note(&note.by, &note.text);
+        if let (Some(at), Some(added)) = (note.at, task.notes.last_mut()) {
+            added.at = at;
+        }
+    }
+    match planned.status {
+        TaskStatus::Todo => {}
+        TaskStatus::InProgress => task.start(),
+        TaskStatus::Done => task.complete(),
+    }
+    task
+}
+
+/// Status implied by a Trello list or Linear state name
+fn status_from_name(name: &str) -> TaskStatus {
+    let name = name.trim().to_lowercase();
+    if [
+        "done",
+        "complete",
+        "completed",
+        "shipped",
+        "canceled",
+        "cancelled",
+        "duplicate",
+    ]
+    .iter()
+    .any(|s| name == *s || name.starts_with(&format!("{} ", s)))
+    {
+        TaskStatus::Done
+    } else if ["doing", "in progress", "in review", "started"].contains(&name.as_str()) {
+        TaskStatus::InProgress
+    } else {
+        TaskStatus::Todo
+    }
+}
+
+// =============================================================================
+// Trello
+// =============================================================================
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct TrelloBoard {
+    lists: Vec<TrelloList>,
+    cards: Vec<TrelloCard>,
+    checklists: Vec<TrelloChecklist>,
+    actions: Vec<TrelloAction>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct TrelloList {
+    id: String,
+    name: String,
+    closed: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct TrelloCard {
+    id: String,
+    name: String,
+    desc: String,
+    id_list: String,
+    closed: bool,
+    labels: Vec<TrelloLabel>,
+    short_url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TrelloLabel {
+    name: String,
+    color: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct TrelloChecklist {
+    id_card: String,
+    check_items: Vec<TrelloCheckItem>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TrelloCheckItem {
+    id: String,
+    name: String,
+    state: String,
+    pos: f64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct TrelloAction {
+    #[serde(rename = "type")]
+    kind: String,
+    date: Option<DateTime<Utc>>,
+    data: serde_json::Value,
+    member_creator: Option<TrelloMember>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct TrelloMember {
+    username: String,
+    full_name: String,
+}
+
+/// Maps a Trello board: lists → briefs or labels, open cards → tasks,
+/// checklist items → subtasks, comments → notes
+fn trello_plan(board: &TrelloBoard, lists_as: ListsAs) -> Plan {
+    let lists: HashMap<&str, &TrelloList> = board
+        .lists
+        .iter()
+        .filter(|l| !l.closed)
+        .map(|l| (l.id.as_str(), l))
+        .collect();
+
+    let mut plan = Plan {
+        system: "trello",
+        ..Plan::default()
+    };
+    if lists_as == ListsAs::Briefs {
+        plan.briefs = board
+            .lists
+            .iter()
+            .filter(|l| !l.closed)
+            .filter(|l| board.cards.iter().any(|c| !c.closed && c.id_list == l.id))
+            .map(|l| PlannedBrief {
+                source: l.id.clone(),
+                title: l.name.clone(),
+            })
+            .collect();
+    }
+
+    for card in board.cards.iter().filter(|c| !c.closed) {
+        let Some(list) = lists.get(card.id_list.as_str()) else {
+            continue;
+        };
+        let mut labels: Vec<String> = card
+            .labels
+            .iter()
+            .filter_map(|l| match l.name.trim() {
+                "" => l.color.clone(),
+                name => Some(name.to_string()),
+            })
+            .collect();
+        let brief = match lists_as {
+            ListsAs::Briefs => Some(list.id.clone()),
+            ListsAs::Labels => {
+                labels.push(list.name.clone());
+                None
+            }
+        };
+
+        // Comments are exported newest first
+        let mut notes: Vec<PlannedNote> = board
+            .actions
+            .iter()
+            .filter(|a| a.kind == "commentCard")
+            .filter(|a| a.data["card"]["id"].as_str() == Some(card.id.as_str()))
+            .filter_map(|a| {
+                let text = a.data["text"].as_str()?.to_string();
+                let by = a
+                    .member_creator
+                    .as_ref()
+                    .map(|m| match m.username.as_str() {
+                        "" => m.full_name.clone(),
+                        username => username.to_string(),
+                    })
+                    .unwrap_or_else(|| "trello".to_string());
+                Some(PlannedNote {
+                    by,
+                    text,
+                    at: a.date,
+                })
+            })
+            .collect();
+        notes.reverse();
+
+        plan.tasks.push(PlannedTask {
+            source: card.id.clone(),
+            title: card.name.clone(),
+            description: Some(card.desc.trim().to_string()).filter(|d| !d.is_empty()),
+            brief,
+            labels,
+            status: status_from_name(&list.name),
+            notes,
+            url: card.short_url.clone(),
+            ..PlannedTask::default()
+        });
+
+        let mut items: Vec<&TrelloCheckItem> = board
+            .checklists
+            .iter()
+            .filter(|c| c.id_card == card.id)
+            .flat_map(|c| &c.check_items)
+            .collect();
+        items.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+        for item in items {
+            plan.tasks.push(PlannedTask {
+                source: item.id.clone(),
+                title: item.name.clone(),
+                parent: Some(card.id.clone()),
+                status: if item.state == "complete" {
+                    TaskStatus::Done
+                } else {
+                    TaskStatus::Todo
+                },
+                ..PlannedTask::default()
+            });
+        }
+    }
+    plan
+}
+
+// =============================================================================
+// Linear
+// =============================================================================
+
+/// Maps a Linear CSV export: projects → briefs (when `project_briefs`),
+/// issues → tasks, sub-issues → subtasks
+fn linear_plan(csv: &str, project_briefs: bool) -> Result<Plan> {
+    let mut rows = parse_csv(csv).into_iter();
+    let header = rows.next().unwrap_or_default();
+    let column = |name: &str| header.iter().position(|h| h.trim() == name);
+    let (Some(id_col), Some(title_col)) = (column("ID"), column("Title")) else {
+        bail!("Expected ID and Title columns");
+    };
+    let col = |row: &[String], name: &str| -> String {
+        column(name)
+            .and_then(|i| row.get(i))
+            .map(|v| v.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut plan = Plan {
+        system: "linear",
+        ..Plan::default()
+    };
+    let mut issues = Vec::new();
+    for row in rows {
+        let id = row.get(id_col).map(|v| v.trim()).unwrap_or_default();
+        if id.is_empty() {
+            continue;
+        }
+        let project = col(&row, "Project");
+        let project_id = Some(col(&row, "Project ID"))
+            .filter(|p| !p.is_empty())
+            .unwrap_or_else(|| project.clone());
+        let brief = (project_briefs && !project.is_empty()).then(|| {
+            if !plan.briefs.iter().any(|b| b.source == project_id) {
+                plan.briefs.push(PlannedBrief {
+                    source: project_id.clone(),
+                    title: project.clone(),
+                });
+            }
+            project_id.clone()
+        });
+
+        let state = col(&row, "Status");
+        let mut meta = vec![("linear_status", state.clone())];
+        if !project_briefs && !project.is_empty() {
+            meta.push(("linear_project", project));
+        }
+        let assignee = col(&row, "Assignee");
+        if !assignee.is_empty() {
+            meta.push(("assignee", assignee));
+        }
+        issues.push(PlannedTask {
+            source: id.to_string(),
+            title: row.get(title_col).cloned().unwrap_or_default(),
+            description: Some(col(&row, "Description")).filter(|d| !d.is_empty()),
+            brief,
+            parent: Some(col(&row, "Parent issue")).filter(|p| !p.is_empty()),
+            labels: col(&row, "Labels")
+                .split(',')
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .collect(),
+            status: status_from_name(&state),
+            meta,
+            ..PlannedTask::default()
+        });
+    }
+
+    // Parents first; sub-issues whose parent isn't in the export stay top-level
+    let sources: HashSet<String> = issues.iter().map(|i| i.source.clone()).collect();
+    for issue in &mut issues {
+        if issue.parent.as_ref().is_some_and(|p| !sources.contains(p)) {
+            issue.parent = None;
+        }
+    }
+    let mut placed: HashSet<String> = HashSet::new();
+    while !issues.is_empty() {
+        let (ready, rest): (Vec<_>, Vec<_>) = issues
+            .into_iter()
+            .partition(|i| i.parent.as_ref().is_none_or(|p| placed.contains(p)));
+        if ready.is_empty() {
+            bail!("Sub-issues form a cycle: {}", rest[0].source);
+        }
+        placed.extend(ready.iter().map(|i| i.source.clone()));
+        plan.tasks.extend(ready);
+        issues = rest;
+    }
+    Ok(plan)
+}
+
+// =============================================================================
+// GitHub
+// =============================================================================
+
+/// Issues per API request, the most GitHub allows
+const GITHUB_PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GithubIssue {
+    number: u64,
+    title: String,
+    body: Option<String>,
+    state: String,
+    html_url: Option<String>,
+    labels: Vec<GithubLabel>,
+    assignees: Vec<GithubUser>,
+    milestone: Option<GithubMilestone>,
+    /// Present when the "issue" is a pull request
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GithubLabel {
+    name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GithubUser {
+    login: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GithubMilestone {
+    number: u64,
+    title: String,
+}
+
+/// Fetches every issue (and pull request) of `repo` matching `labels` and
+/// `state`, a page at a time
+fn fetch_github_issues(
+    repo: &str,
+    labels: &[String],
+    state: IssueState,
+) -> Result<Vec<GithubIssue>> {
+    let valid = repo
+        .split_once('/')
+        .is_some_and(|(owner, name)| !owner.is_empty() && !name.is_empty() && !name.contains('/'));
+    if !valid {
+        bail!("Expected a repository as owner/repo, got '{}'", repo);
+    }
+    let api = std::env::var("GITHUB_API_URL")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "https://api.github.com".to_string());
+    let token = ["GITHUB_TOKEN", "GH_TOKEN"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()));
+    let state = match state {
+        IssueState::Open => "open",
+        IssueState::Closed => "closed",
+        IssueState::All => "all",
+    };
+    let labels = if labels.is_empty() {
+        String::new()
+    } else {
+        format!("&labels={}", percent_encode(&labels.join(",")))
+    };
+
+    let mut issues = Vec::new();
+    for page in 1.. {
+        let url = format!(
+            "{}/repos/{}/issues?state={}{}&per_page={}&page={}",
+            api.trim_end_matches('/'),
+            repo,
+            state,
+            labels,
+            GITHUB_PAGE_SIZE,
+            page
+        );
+        let batch: Vec<GithubIssue> = serde_json::from_slice(&github_get(&url, token.as_deref())?)
+            .with_context(|| format!("Unexpected response from {}", url))?;
+        let last = batch.len() < GITHUB_PAGE_SIZE;
+        issues.extend(batch);
+        if last {
+            break;
+        }
+    }
+    Ok(issues)
+}
+
+/// GETs a GitHub API URL through curl. The token goes in on stdin so it
+/// never shows up in the process list.
+fn github_get(url: &str, token: Option<&str>) -> Result<Vec<u8>> {
+    let mut cmd = Command::new("curl");
+    cmd.args(["-fsSL", "--max-time", "60"])
+        .args(["-H", "Accept: application/vnd.github+json"])
+        .args(["-A", concat!("shape/", env!("CARGO_PKG_VERSION"))])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if token.is_some() {
+        cmd.args(["-H", "@-"]).stdin(Stdio::piped());
+    }
+    let mut child = cmd.arg(url).spawn().context("Couldn't run curl")?;
+    if let (Some(token), Some(mut stdin)) = (token, child.stdin.take()) {
+        writeln!(stdin, "Authorization: Bearer {}", token)?;
+    }
+    let out = child.wait_with_output()?;
+    if !out.status.success() {
+        bail!(
+            "GitHub request {} failed: {}",
+            url,
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(out.stdout)
+}
+
+/// Percent-encodes a query parameter value
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Maps GitHub issues: milestones → briefs (when `milestone_briefs`),
+/// issues → tasks, oldest first. Pull requests are left out.
+///
+/// Tasks are keyed by issue number, as the `github` sync plugin keys them,
+/// and briefs by `milestone-<number>`.
+fn github_plan(repo: &str, issues: &[GithubIssue], milestone_briefs: bool) -> Plan {
+    let mut issues: Vec<&GithubIssue> =
+        issues.iter().filter(|i| i.pull_request.is_none()).collect();
+    issues.sort_by_key(|i| i.number);
+
+    let mut plan = Plan {
+        system: "github",
+        ..Plan::default()
+    };
+    for issue in issues {
+        let brief = issue
+            .milestone
+            .as_ref()
+            .filter(|_| milestone_briefs)
+            .map(|milestone| {
+                let source = format!("milestone-{}", milestone.number);
+                if !plan.briefs.iter().any(|b| b.source == source) {
+                    plan.briefs.push(PlannedBrief {
+                        source: source.clone(),
+                        title: milestone.title.clone(),
+                    });
+                }
+                source
+            });
+
+        let mut meta = vec![("github_repo", repo.to_string())];
+        if !issue.assignees.is_empty() {
+            let logins: Vec<&str> = issue.assignees.iter().map(|a| a.login.as_str()).collect();
+            meta.push(("assignee", logins.join(", ")));
+        }
+        plan.tasks.push(PlannedTask {
+            source: issue.number.to_string(),
+            title: issue.title.clone(),
+            description: issue
+                .body
+                .as_deref()
+                .map(str::trim)
+                .filter(|b| !b.is_empty())
+                .map(str::to_string),
+            brief,
+            labels: issue.labels.iter().map(|l| l.name.clone()).collect(),
+            status: if issue.state == "closed" {
+                TaskStatus::Done
+            } else {
+                TaskStatus::Todo
+            },
+            url: issue.html_url.clone(),
+            meta,
+            ..PlannedTask::default()
+        });
+    }
+    plan
+}
+
+/// Parses CSV (RFC 4180: quoted fields may hold commas, newlines and `""`)
+fn parse_csv(input: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = input.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.is_empty() => quoted = true,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quoted_csv() {
+        let rows =
+            parse_csv("ID,Title\r\nENG-1,\"Fix \"\"login\"\", again\"\nENG-2,\"two\nlines\"\n");
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1], ["ENG-1", "Fix \"login\", again"]);
+        assert_eq!(rows[2][1], "two\nlines");
+    }
+
+    #[test]
+    fn maps_trello_lists_cards_checklists_and_comments() {
+        let board: TrelloBoard = serde_json::from_value(json!({
+            "lists": [
+                { "id": "l1", "name": "Search" },
+                { "id": "l2", "name": "Done" },
+                { "id": "l3", "name": "Old", "closed": true }
+            ],
+            "cards": [
+                { "id": "c1", "name": "Index docs", "desc": "Use tantivy", "idList": "l1",
+                  "labels": [{ "name": "backend" }, { "name": "", "color": "red" }] },
+                { "id": "c2", "name": "Ship it", "idList": "l2" },
+                { "id": "c3", "name": "Archived", "idList": "l1", "closed": true }
+            ],
+            "checklists": [
+                { "idCard": "c1", "checkItems": [
+                    { "id": "i2", "name": "Second", "state": "incomplete", "pos": 2 },
+                    { "id": "i1", "name": "First", "state": "complete", "pos": 1 }
+                ] }
+            ],
+            "actions": [
+                { "type": "commentCard", "date": "2024-01-02T00:00:00Z",
+                  "data": { "text": "later", "card": { "id": "c1" } },
+                  "memberCreator": { "username": "bob" } },
+                { "type": "commentCard", "date": "2024-01-01T00:00:00Z",
+                  "data": { "text": "earlier", "card": { "id": "c1" } },
+                  "memberCreator": { "username": "alice" } }
+            ]
+        }))
+        .unwrap();
+
+        let plan = trello_plan(&board, ListsAs::Briefs);
+        let briefs: Vec<&str> = plan.briefs.iter().map(|b| b.title.as_str()).collect();
+        assert_eq!(briefs, ["Search", "Done"]);
+        let titles: Vec<&str> = plan.tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, ["Index docs", "First", "Second", "Ship it"]);
+        assert_eq!(plan.tasks[0].labels, ["backend", "red"]);
+        assert_eq!(plan.tasks[0].notes[0].text, "earlier");
+        assert_eq!(plan.tasks[1].parent.as_deref(), Some("c1"));
+        assert_eq!(plan.tasks[1].status, TaskStatus::Done);
+        assert_eq!(plan.tasks[3].status, TaskStatus::Done);
+
+        let plan = trello_plan(&board, ListsAs::Labels);
+        assert!(plan.briefs.is_empty());
+        assert_eq!(plan.tasks[0].labels, ["backend", "red", "Search"]);
+    }
+
+    #[test]
+    fn maps_linear_projects_and_sub_issues() {
+        let csv = "ID,Title,Description,Status,Project,Labels,Parent issue\n\
+                   ENG-2,Child,,In Progress,Search,,ENG-1\n\
+                   ENG-1,Parent,Body,Todo,Search,\"bug, p1\",\n\
+                   ENG-3,Loose,,Canceled,,,ENG-99\n";
+        let plan = linear_plan(csv, true).unwrap();
+        assert_eq!(plan.briefs.len(), 1);
+        assert_eq!(plan.briefs[0].title, "Search");
+        let order: Vec<&str> = plan.tasks.iter().map(|t| t.source.as_str()).collect();
+        assert_eq!(order, ["ENG-1", "ENG-3", "ENG-2"]);
+        assert_eq!(plan.tasks[0].labels, ["bug", "p1"]);
+        assert_eq!(plan.tasks[1].parent, None);
+        assert_eq!(plan.tasks[1].status, TaskStatus::Done);
+        assert_eq!(plan.tasks[2].status, TaskStatus::InProgress);
+
+        assert!(linear_plan("Name,Email\n", true).is_err());
+    }
+
+    #[test]
+    fn maps_github_milestones_and_issues() {
+        let issues: Vec<GithubIssue> = serde_json::from_value(json!([
+            { "number": 7, "title": "Later", "state": "closed",
+              "milestone": { "number": 2, "title": "v1" } },
+            { "number": 8, "title": "A PR", "state": "open", "pull_request": {} },
+            { "number": 3, "title": "Search", "body": " Index docs \n", "state": "open",
+              "labels": [{ "name": "roadmap" }], "assignees": [{ "login": "alice" }],
+              "milestone": { "number": 2, "title": "v1" },
+              "html_url": "https://github.com/acme/app/issues/3" },
+            { "number": 5, "title": "Loose", "state": "open" }
+        ]))
+        .unwrap();
+
+        let plan = github_plan("acme/app", &issues, true);
+        assert_eq!(plan.briefs.len(), 1);
+        assert_eq!(plan.briefs[0].source, "milestone-2");
+        let order: Vec<&str> = plan.tasks.iter().map(|t| t.source.as_str()).collect();
+        assert_eq!(order, ["3", "5", "7"]);
+        assert_eq!(plan.tasks[0].description.as_deref(), Some("Index docs"));
+        assert_eq!(plan.tasks[0].labels, ["roadmap"]);
+        assert_eq!(plan.tasks[0].brief.as_deref(), Some("milestone-2"));
+        assert_eq!(plan.tasks[1].brief, None);
+        assert_eq!(plan.tasks[2].status, TaskStatus::Done);
+
+        let plan = github_plan("acme/app", &issues, false);
+        assert!(plan.briefs.is_empty());
+        assert_eq!(plan.tasks[0].brief, None);
+
+        assert_eq!(
+            percent_encode("good first issue,ui"),
+            "good%20first%20issue%2Cui"
+        );
+    }
+}
@@ -0,0 +1,617 @@
+//! Ingest commands
+//!
+//! Turn incoming requests into triage tasks. `shape ingest email` reads a
+//! raw RFC 822 message on stdin so a mail filter (procmail, sieve, a
+//! forwarding rule) can pipe requests straight in:
+//!
+//! ```bash
+//! shape ingest email < message.eml
+//! ```
+//!
+//! The subject becomes the title and the text body the description. The
+//! sender and message ID are kept in metadata, and attachments are saved
+//! under `.shape/attachments/{task-id}/` and linked as files.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use serde_json::json;
+
+use super::output::Output;
+use super::task::allocate_task_id;
+use crate::domain::{LinkType, Task};
+use crate::storage::Project;
+
+/// Label given to ingested tasks
+const TRIAGE_LABEL: &str = "triage";
+
+#[derive(Subcommand)]
+pub enum IngestCommands {
+    /// Create a triage task from an email message read on stdin
+    ///
+    /// Example:
+    ///   shape ingest email < message.eml
+    Email {
+        /// Add the task to this brief instead of creating a standalone task
+        #[arg(long)]
+        brief: Option<String>,
+
+        /// Extra label (repeatable); `triage` is always added
+        #[arg(long)]
+        label: Vec<String>,
+    },
+}
+
+pub fn run(cmd: IngestCommands, output: &Output) -> Result<()> {
+    match cmd {
+        IngestCommands::Email { brief, label } => {
+            let mut raw = Vec::new();
+            std::io::stdin()
+                .read_to_end(&mut raw)
+                .context("Failed to read message from stdin")?;
+            ingest_email(output, &raw, brief.as_deref(), &label)
+        }
+    }
+}
+
+fn ingest_email(output: &Output, raw: &[u8], brief: Option<&str>, labels: &[String]) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.task_store();
+
+    let message = Message::parse(raw);
+    if message.headers.is_empty() {
+        bail!("Input is not an email message (no headers found)");
+    }
+    let email = Email::from_message(&message);
+
+    // Mail filters retry on failure; don't create the same request twice
+    if let Some(message_id) = &email.message_id {
+        let tasks = store.read_all()?;
+        if let Some(existing) = tasks
+            .values()
+            .find(|t| t.get_meta("message_id").and_then(|v| v.as_str()) == Some(message_id))
+        {
+            if output.is_json() {
+                output.data(&json!({
+                    "id": existing.id.to_string(),
+                    "title": existing.title,
+                    "duplicate": true,
+                }));
+            } else {
+                output.success(&format!(
+                    "Already ingested as {} - {}",
+                    existing.id, existing.title
+                ));
+            }
+            return Ok(());
+        }
+    }
+
+    let title = email
+        .subject
+        .clone()
+        .unwrap_or_else(|| "(no subject)".to_string());
+    let task_id = allocate_task_id(&project, brief, &title)?;
+    let mut task = Task::new(task_id, &title);
+    if !email.body.trim().is_empty() {
+        task.set_description(email.body.trim());
+    }
+
+    let mut all_labels = vec![TRIAGE_LABEL.to_string()];
+    all_labels.extend(labels.iter().filter(|l| *l != TRIAGE_LABEL).cloned());
+    task.set_meta("labels", json!(all_labels));
+    task.set_meta("source", "email");
+    if let Some(from) = &email.from {
+        task.set_meta("from", from.as_str());
+    }
+    if let Some(message_id) = &email.message_id {
+        task.set_meta("message_id", message_id.as_str());
+    }
+    if let Some(date) = &email.date {
+        task.set_meta("sent_at", date.as_str());
+    }
+
+    let mut saved = Vec::new();
+    if !email.attachments.is_empty() {
+        let dir = project.attachments_dir().join(task.id.to_string());
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        for (i, attachment) in email.attachments.iter().enumerate() {
+            let name = safe_file_name(attachment.name.as_deref(), i);
+            let path = dir.join(&name);
+            fs::write(&path, &attachment.data)
+                .with_context(|| format!("Failed to write attachment: {}", path.display()))?;
+            let reference = format!(".shape/attachments/{}/{}", task.id, name);
+            task.add_link(LinkType::File, reference.clone(), email.from.as_deref());
+            saved.push(reference);
+        }
+    }
+
+    store.append(&task)?;
+
+    if output.is_json() {
+        output.data(&json!({
+            "id": task.id.to_string(),
+            "title": task.title,
+            "from": email.from,
+            "message_id": email.message_id,
+            "attachments": saved,
+            "duplicate": false,
+        }));
+    } else {
+        output.success(&format!("Created task: {} - {}", task.id, task.title));
+        if let Some(from) = &email.from {
+            println!("  From: {}", from);
+        }
+        for path in &saved {
+            println!("  Attachment: {}", path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps only the final path component and replaces anything unusual, so an
+/// attachment name can't escape the attachments directory
+fn safe_file_name(name: Option<&str>, index: usize) -> String {
+    let base = name
+        .and_then(|n| n.rsplit(['/', '\\']).next())
+        .unwrap_or("")
+        .trim();
+    let cleaned: String = base
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | ' ') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.').trim().to_string();
+    if cleaned.is_empty() {
+        format!("attachment-{}", index + 1)
+    } else {
+        cleaned
+    }
+}
+
+/// The parts of an email a task needs
+#[derive(Debug, Default)]
+struct Email {
+    subject: Option<String>,
+    from: Option<String>,
+    message_id: Option<String>,
+    date: Option<String>,
+    body: String,
+    attachments: Vec<Attachment>,
+}
+
+#[derive(Debug)]
+struct Attachment {
+    name: Option<String>,
+    data: Vec<u8>,
+}
+
+impl Email {
+    fn from_message(message: &Message) -> Self {
+        let header = |name: &str| {
+            message
+                .header(name)
+                .map(decode_encoded_words)
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+
+        let mut email = Email {
+            subject: header("subject"),
+            from: header("from"),
+            message_id: header("message-id")
+                .map(|id| id.trim_matches(|c| c == '<' || c == '>').to_string()),
+            date: header("date"),
+            ..Email::default()
+        };
+
+        let mut html = None;
+        message.walk(&mut |part| {
+            let (mime, params) = part.content_type();
+            let disposition = part.header("content-disposition").unwrap_or("");
+            let file_name = header_param(disposition, "filename")
+                .or_else(|| params.get("name").cloned())
+                .map(|n| decode_encoded_words(&n));
+            let is_attachment = disposition
+                .trim_start()
+                .to_lowercase()
+                .starts_with("attachment")
+                || file_name.is_some();
+
+            if is_attachment {
+                email.attachments.push(Attachment {
+                    name: file_name,
+                    data: part.decoded_body(),
+                });
+            } else if mime == "text/plain" && email.body.is_empty() {
+                email.body = part.text(params.get("charset").map(String::as_str));
+            } else if mime == "text/html" && html.is_none() {
+                html = Some(part.text(params.get("charset").map(String::as_str)));
+            }
+        });
+
+        if email.body.trim().is_empty() {
+            if let Some(html) = html {
+                email.body = strip_html(&html);
+            }
+        }
+
+        email
+    }
+}
+
+/// A parsed MIME entity: headers plus either a body or child parts
+#[derive(Debug)]
+struct Message {
+    /// Header names lowercased, in order, continuation lines unfolded
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    parts: Vec<Message>,
+}
+
+impl Message {
+    fn parse(raw: &[u8]) -> Self {
+        let (head, body) = split_head(raw);
+        let headers = parse_headers(&String::from_utf8_lossy(head));
+        let mut message = Message {
+            headers,
+            body: body.to_vec(),
+            parts: Vec::new(),
+        };
+
+        let (mime, params) = message.content_type();
+        if mime.starts_with("multipart/") {
+            if let Some(boundary) = params.get("boundary") {
+                message.parts = split_multipart(body, boundary)
+                    .into_iter()
+                    .map(Message::parse)
+                    .collect();
+            }
+        }
+        message
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Lowercased MIME type and its parameters (`text/plain` by default)
+    fn content_type(&self) -> (String, HashMap<String, String>) {
+        let value = self.header("content-type").unwrap_or("text/plain");
+        let mime = value.split(';').next().unwrap_or("").trim().to_lowercase();
+        let params = value
+            .split(';')
+            .skip(1)
+            .filter_map(|p| {
+                let (k, v) = p.split_once('=')?;
+                Some((
+                    k.trim().to_lowercase(),
+                    v.trim().trim_matches('"').to_string(),
+                ))
+            })
+            .collect();
+        (mime, params)
+    }
+
+    /// Visits every leaf part in order
+    fn walk(&self, visit: &mut impl FnMut(&Message)) {
+        if self.parts.is_empty() {
+            visit(self);
+        } else {
+            for part in &self.parts {
+                part.walk(visit);
+            }
+        }
+    }
+
+    /// Body with its transfer encoding undone
+    fn decoded_body(&self) -> Vec<u8> {
+        let encoding = self
+            .header("content-transfer-encoding")
+            .unwrap_or("")
+            .trim()
+            .to_lowercase();
+        match encoding.as_str() {
+            "base64" => decode_base64(&self.body),
+            "quoted-printable" => decode_quoted_printable(&self.body),
+            _ => self.body.clone(),
+        }
+    }
+
+    fn text(&self, charset: Option<&str>) -> String {
+        decode_charset(&self.decoded_body(), charset)
+    }
+}
+
+/// Splits a message at the first blank line, accepting CRLF or LF endings
+fn split_head(raw: &[u8]) -> (&[u8], &[u8]) {
+    for (i, window) in raw.windows(2).enumerate() {
+        if window == b"\n\n" {
+            return (&raw[..i], &raw[i + 2..]);
+        }
+        if window == b"\n\r" && raw.get(i + 2) == Some(&b'\n') {
+            return (&raw[..i], &raw[i + 3..]);
+        }
+    }
+    (raw, &[])
+}
+
+fn parse_headers(head: &str) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in head.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            if !name.is_empty() && !name.contains(' ') {
+                headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+            }
+        }
+    }
+    headers
+}
+
+/// Reads a parameter like `filename="a.pdf"` from a header value
+fn header_param(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|p| {
+        let (k, v) = p.split_once('=')?;
+        (k.trim().eq_ignore_ascii_case(name)).then(|| v.trim().trim_matches('"').to_string())
+    })
+}
+
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let text = body;
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut pos = 0;
+
+    while pos < text.len() {
+        let end = text[pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map(|i| pos + i + 1)
+            .unwrap_or(text.len());
+        let line = String::from_utf8_lossy(&text[pos..end]);
+        let line = line.trim_end();
+        if line == delimiter || line == format!("{}--", delimiter) {
+            if let Some(s) = start {
+                // The line break before a delimiter belongs to the delimiter
+                let mut part_end = pos;
+                if part_end > s && text[part_end - 1] == b'\n' {
+                    part_end -= 1;
+                    if part_end > s && text[part_end - 1] == b'\r' {
+                        part_end -= 1;
+                    }
+                }
+                parts.push(&text[s..part_end]);
+            }
+            if line.ends_with("--") && line != delimiter {
+                break;
+            }
+            start = Some(end);
+        }
+        pos = end;
+    }
+
+    parts
+}
+
+fn decode_base64(input: &[u8]) -> Vec<u8> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+            b'+' | b'-' => Some(62),
+            b'/' | b'_' => Some(63),
+            _ => None,
+        }
+    }
+
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &c in input {
+        if c == b'=' {
+            break;
+        }
+        let Some(v) = value(c) else {
+            continue;
+        };
+        buffer = (buffer << 6) | v;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    out
+}
+
+fn decode_quoted_printable(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] == b'=' {
+            // Soft line break
+            if input.get(i + 1) == Some(&b'\n') {
+                i += 2;
+                continue;
+            }
+            if input.get(i + 1) == Some(&b'\r') && input.get(i + 2) == Some(&b'\n') {
+                i += 3;
+                continue;
+            }
+            let hex = input
+                .get(i + 1..i + 3)
+                .and_then(|h| std::str::from_utf8(h).ok())
+                .and_then(|h| u8::from_str_radix(h, 16).ok());
+            if let Some(byte) = hex {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(input[i]);
+        i += 1;
+    }
+    out
+}
+
+/// UTF-8 and ASCII decode as-is; Latin-1 maps bytes to code points.
+/// Anything else falls back to lossy UTF-8.
+fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    let charset = charset.unwrap_or("utf-8").to_lowercase();
+    if matches!(
+        charset.as_str(),
+        "iso-8859-1" | "latin1" | "latin-1" | "windows-1252"
+    ) {
+        bytes.iter().map(|&b| b as char).collect()
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+/// Decodes RFC 2047 encoded words (`=?UTF-8?B?...?=`) in a header value
+fn decode_encoded_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut last_was_word = false;
+
+    while let Some(start) = rest.find("=?") {
+        let decoded = rest[start + 2..].find("?=").and_then(|len| {
+            let inner = &rest[start + 2..start + 2 + len];
+            let mut fields = inner.splitn(3, '?');
+            let charset = fields.next()?;
+            let encoding = fields.next()?;
+            let text = fields.next()?;
+            let bytes = match encoding.to_ascii_uppercase().as_str() {
+                "B" => decode_base64(text.as_bytes()),
+                "Q" => decode_quoted_printable(text.replace('_', " ").as_bytes()),
+                _ => return None,
+            };
+            Some((decode_charset(&bytes, Some(charset)), start + 2 + len + 2))
+        });
+
+        match decoded {
+            Some((text, end)) => {
+                let between = &rest[..start];
+                // Whitespace between adjacent encoded words is dropped
+                if !(last_was_word && between.trim().is_empty()) {
+                    out.push_str(between);
+                }
+                out.push_str(&text);
+                rest = &rest[end..];
+                last_was_word = true;
+            }
+            None => {
+                out.push_str(&rest[..start + 2]);
+                rest = &rest[start + 2..];
+                last_was_word = false;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Crude HTML to text for HTML-only messages
+fn strip_html(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULTIPART: &str = "From: =?UTF-8?Q?Ana_Mar=C3=ADa?= <ana@example.com>\r\n\
+Subject: =?UTF-8?B?TG9naW4gYnJva2Vu?=\r\n\
+\x20on Safari\r\n\
+Message-ID: <abc123@example.com>\r\n\
+Content-Type: multipart/mixed; boundary=\"XYZ\"\r\n\
+\r\n\
+preamble\r\n\
+--XYZ\r\n\
+Content-Type: text/plain; charset=utf-8\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\
+\r\n\
+Steps to repro=\r\n\
+duce: open =3D login\r\n\
+--XYZ\r\n\
+Content-Type: text/plain; name=\"log.txt\"\r\n\
+Content-Disposition: attachment; filename=\"../log.txt\"\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+aGVsbG8gd29ybGQ=\r\n\
+--XYZ--\r\n";
+
+    #[test]
+    fn parses_multipart_message() {
+        let email = Email::from_message(&Message::parse(MULTIPART.as_bytes()));
+
+        assert_eq!(email.subject.as_deref(), Some("Login broken on Safari"));
+        assert_eq!(email.from.as_deref(), Some("Ana María <ana@example.com>"));
+        assert_eq!(email.message_id.as_deref(), Some("abc123@example.com"));
+        assert_eq!(email.body, "Steps to reproduce: open = login");
+        assert_eq!(email.attachments.len(), 1);
+        assert_eq!(email.attachments[0].name.as_deref(), Some("../log.txt"));
+        assert_eq!(email.attachments[0].data, b"hello world");
+    }
+
+    #[test]
+    fn plain_message_and_html_fallback() {
+        let plain = Email::from_message(&Message::parse(b"Subject: Hi\n\nJust text\n"));
+        assert_eq!(plain.subject.as_deref(), Some("Hi"));
+        assert_eq!(plain.body.trim(), "Just text");
+
+        let html = Email::from_message(&Message::parse(
+            b"Subject: Hi\nContent-Type: text/html\n\n<p>Hello &amp; <b>bye</b></p>\n",
+        ));
+        assert_eq!(html.body, "Hello & bye");
+    }
+
+    #[test]
+    fn attachment_names_stay_inside_directory() {
+        assert_eq!(safe_file_name(Some("../../etc/passwd"), 0), "passwd");
+        assert_eq!(safe_file_name(Some("..\\evil.bat"), 0), "evil.bat");
+        assert_eq!(safe_file_name(Some(".."), 2), "attachment-3");
+        assert_eq!(safe_file_name(None, 0), "attachment-1");
+    }
+}
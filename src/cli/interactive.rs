@@ -0,0 +1,210 @@
+//! `shape interactive`: a line-by-line prompt flow for people who can't use
+//! the TUI, e.g. with a screen reader
+//!
+//! Every choice runs an ordinary shape command, so results read the same as
+//! `--plain` output. Any other input is run as a command line of its own.
+
+use std::io::{BufRead, Write};
+
+use anyhow::Result;
+
+use super::output::Output;
+
+/// A menu entry: what it does, the prompts it asks and the command it runs
+struct Choice {
+    label: &'static str,
+    prompts: &'static [&'static str],
+    command: fn(&[String]) -> Vec<String>,
+}
+
+const CHOICES: &[Choice] = &[
+    Choice {
+        label: "Ready tasks",
+        prompts: &[],
+        command: |_| args(&["ready"]),
+    },
+    Choice {
+        label: "Blocked tasks",
+        prompts: &[],
+        command: |_| args(&["blocked"]),
+    },
+    Choice {
+        label: "All tasks",
+        prompts: &[],
+        command: |_| args(&["task", "list"]),
+    },
+    Choice {
+        label: "Show a task",
+        prompts: &["Task ID"],
+        command: |answers| args(&["task", "show", &answers[0]]),
+    },
+    Choice {
+        label: "Start a task",
+        prompts: &["Task ID"],
+        command: |answers| args(&["task", "start", &answers[0]]),
+    },
+    Choice {
+        label: "Complete a task",
+        prompts: &["Task ID"],
+        command: |answers| args(&["task", "done", &answers[0]]),
+    },
+    Choice {
+        label: "Add a task",
+        prompts: &[
+            "Brief or parent task ID, empty for a standalone task",
+            "Title",
+        ],
+        command: |answers| match answers[0].as_str() {
+            "" => args(&["task", "add", &answers[1]]),
+            parent => args(&["task", "add", parent, &answers[1]]),
+        },
+    },
+    Choice {
+        label: "Briefs",
+        prompts: &[],
+        command: |_| args(&["brief", "list"]),
+    },
+    Choice {
+        label: "Project status",
+        prompts: &[],
+        command: |_| args(&["status"]),
+    },
+];
+
+fn args(parts: &[&str]) -> Vec<String> {
+    parts.iter().map(|s| s.to_string()).collect()
+}
+
+/// Runs the prompt loop until `quit` or end of input. `exec` runs a command
+/// line (without the leading `shape`); its errors are reported and the loop
+/// goes on.
+pub fn run(
+    output: &Output,
+    input: &mut dyn BufRead,
+    exec: &dyn Fn(&[String]) -> Result<()>,
+) -> Result<()> {
+    println!(
+        "Shape interactive mode. Enter a number from the menu, a shape command \
+         such as task list, menu to list the choices again, or quit."
+    );
+    print_menu();
+
+    while let Some(line) = prompt(input, "Choice")? {
+        let line = line.trim();
+        let command = match line {
+            "" => continue,
+            "q" | "quit" | "exit" => break,
+            "menu" | "m" | "?" => {
+                print_menu();
+                continue;
+            }
+            _ => match line.parse::<usize>() {
+                Ok(n) if (1..=CHOICES.len()).contains(&n) => {
+                    let choice = &CHOICES[n - 1];
+                    let mut answers = Vec::new();
+                    for question in choice.prompts {
+                        match prompt(input, question)? {
+                            Some(answer) => answers.push(answer.trim().to_string()),
+                            None => return Ok(()),
+                        }
+                    }
+                    (choice.command)(&answers)
+                }
+                Ok(_) => {
+                    println!("No choice {}. Enter menu to list the choices.", line);
+                    continue;
+                }
+                Err(_) => split_words(line),
+            },
+        };
+
+        if matches!(
+            command.first().map(String::as_str),
+            Some("interactive" | "tui")
+        ) {
+            println!("Already in interactive mode.");
+            continue;
+        }
+        output.verbose_ctx("interactive", &format!("Running: {}", command.join(" ")));
+        if let Err(e) = exec(&command) {
+            output.error(&format!("{:#}", e));
+        }
+        println!();
+    }
+
+    println!("Goodbye.");
+    Ok(())
+}
+
+fn print_menu() {
+    println!("Menu:");
+    for (i, choice) in CHOICES.iter().enumerate() {
+        println!("{}. {}", i + 1, choice.label);
+    }
+    println!("q. Quit");
+}
+
+/// Splits a command line on whitespace, keeping quoted words together
+fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quote = None;
+    let mut in_word = false;
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// Asks a question and reads one line, `None` at end of input
+fn prompt(input: &mut dyn BufRead, question: &str) -> Result<Option<String>> {
+    print!("{}: ", question);
+    std::io::stdout().flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        println!();
+        return Ok(None);
+    }
+    Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_words_keeps_quotes_together() {
+        assert_eq!(
+            split_words(r#"task add b-1234567 "Fix the login form" -l 'ui bug'"#),
+            args(&[
+                "task",
+                "add",
+                "b-1234567",
+                "Fix the login form",
+                "-l",
+                "ui bug"
+            ])
+        );
+        assert_eq!(split_words(r#"  ready  "" "#), args(&["ready", ""]));
+    }
+}
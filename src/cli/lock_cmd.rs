@@ -0,0 +1,266 @@
+//! Resource lock commands
+//!
+//! `shape lock acquire src/auth --task b-x.3 --ttl 2h` tells other agents a
+//! path or subsystem is being changed, even when their tasks aren't linked
+//! to this one by dependencies. `next` keeps tasks whose scope overlaps
+//! another agent's lock out of the way (see `agent.lock_conflicts`). A lock
+//! lapses at its TTL or once its task is done.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::Utc;
+use clap::Subcommand;
+
+use super::agent::get_agent_name;
+use super::duration::{format_duration, parse_duration};
+use super::error::{CliError, ErrorCode};
+use super::output::Output;
+use crate::domain::{ResourceLock, Task, TaskId};
+use crate::storage::Project;
+
+#[derive(Subcommand)]
+pub enum LockCommands {
+    /// Lock a path or subsystem you are working on
+    ///
+    /// Example:
+    ///   shape lock acquire src/auth --task b-7f2b4c1.3 --ttl 2h
+    Acquire {
+        /// Path or subsystem, e.g. src/auth or billing
+        resource: String,
+
+        /// Task the lock is for; the lock lapses once it is done
+        #[arg(long)]
+        task: Option<String>,
+
+        /// Release the lock automatically after this long (e.g. 30m, 2h)
+        #[arg(long)]
+        ttl: Option<String>,
+
+        /// Agent name (defaults to config or $SHAPE_AGENT)
+        #[arg(long)]
+        agent: Option<String>,
+    },
+
+    /// Release a lock
+    Release {
+        /// Locked path or subsystem
+        resource: String,
+
+        /// Agent name (defaults to config or $SHAPE_AGENT)
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Release a lock held by another agent
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// List held resource locks
+    List {
+        /// Include locks that have lapsed
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+pub fn run(cmd: LockCommands, output: &Output) -> Result<()> {
+    match cmd {
+        LockCommands::Acquire {
+            resource,
+            task,
+            ttl,
+            agent,
+        } => acquire(
+            output,
+            &resource,
+            task.as_deref(),
+            ttl.as_deref(),
+            agent.as_deref(),
+        ),
+        LockCommands::Release {
+            resource,
+            agent,
+            force,
+        } => release(output, &resource, agent.as_deref(), force),
+        LockCommands::List { all } => list(output, all),
+    }
+}
+
+/// Returns true while a lock holds: before its TTL and until its task is done
+pub(super) fn is_active(lock: &ResourceLock, tasks: &HashMap<TaskId, Task>) -> bool {
+    let task_done = lock
+        .task
+        .as_ref()
+        .and_then(|id| tasks.get(id))
+        .is_some_and(|t| t.status.is_complete());
+    !lock.is_expired(Utc::now()) && !task_done
+}
+
+/// Locks held by agents other than `agent` that overlap the task's scope
+pub(super) fn conflicts<'a>(
+    task: &Task,
+    locks: &'a [ResourceLock],
+    agent: &str,
+) -> Vec<&'a ResourceLock> {
+    locks
+        .iter()
+        .filter(|lock| lock.agent != agent && lock.conflicts_with(task))
+        .collect()
+}
+
+fn acquire(
+    output: &Output,
+    resource: &str,
+    task: Option<&str>,
+    ttl: Option<&str>,
+    agent: Option<&str>,
+) -> Result<()> {
+    let project = Project::open_current()?;
+    let agent = get_agent_name(&project, agent);
+    let tasks = project.task_store().read_all()?;
+    let store = project.resource_lock_store();
+
+    let mut lock = ResourceLock::new(resource, &agent);
+    if lock.resource.is_empty() {
+        return Err(CliError::new(ErrorCode::Validation, "Resource must not be empty").into());
+    }
+    if let Some(task) = task {
+        let id = project.resolve_task_id(task)?;
+        if !tasks.contains_key(&id) {
+            return Err(CliError::not_found("Task", &id).into());
+        }
+        lock.task = Some(id);
+    }
+    if let Some(ttl) = ttl {
+        lock.expires_at = Some(lock.acquired_at + parse_duration(ttl)?);
+    }
+
+    let mut locks = store.read_all()?;
+    if let Some(held) = locks
+        .iter()
+        .find(|l| l.agent != agent && is_active(l, &tasks) && l.overlaps(&lock.resource))
+    {
+        return Err(CliError::new(
+            ErrorCode::ResourceLocked,
+            format!(
+                "{} overlaps {}, locked by {}{}",
+                lock.resource,
+                held.resource,
+                held.agent,
+                held.task
+                    .as_ref()
+                    .map(|t| format!(" for {}", t))
+                    .unwrap_or_default()
+            ),
+        )
+        .into());
+    }
+
+    // Re-acquiring refreshes the lock; lapsed locks are dropped on the way
+    locks.retain(|l| is_active(l, &tasks) && !(l.agent == agent && l.resource == lock.resource));
+    locks.push(lock.clone());
+    store.write_all(&locks)?;
+
+    if output.is_json() {
+        output.data(&lock);
+    } else {
+        let until = lock
+            .expires_at
+            .map(|at| format!(" for {}", format_duration(at - lock.acquired_at)))
+            .unwrap_or_default();
+        output.success(&format!("Locked {} as {}{}", lock.resource, agent, until));
+    }
+    Ok(())
+}
+
+fn release(output: &Output, resource: &str, agent: Option<&str>, force: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let agent = get_agent_name(&project, agent);
+    let store = project.resource_lock_store();
+    let resource = ResourceLock::new(resource, &agent).resource;
+
+    let mut locks = store.read_all()?;
+    let held: Vec<&ResourceLock> = locks.iter().filter(|l| l.resource == resource).collect();
+    if held.is_empty() {
+        return Err(CliError::not_found("Lock", &resource).into());
+    }
+    if !force && !held.iter().any(|l| l.agent == agent) {
+        return Err(CliError::new(
+            ErrorCode::ResourceLocked,
+            format!(
+                "{} is locked by {} (use --force to release it)",
+                resource, held[0].agent
+            ),
+        )
+        .into());
+    }
+
+    let released: Vec<ResourceLock> = locks
+        .iter()
+        .filter(|l| l.resource == resource && (force || l.agent == agent))
+        .cloned()
+        .collect();
+    locks.retain(|l| !released.contains(l));
+    store.write_all(&locks)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({ "released": released }));
+    } else {
+        for lock in &released {
+            output.success(&format!(
+                "Released {} (held by {})",
+                lock.resource, lock.agent
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn list(output: &Output, all: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let tasks = project.task_store().read_all()?;
+    let locks: Vec<(ResourceLock, bool)> = project
+        .resource_lock_store()
+        .read_all()?
+        .into_iter()
+        .map(|lock| {
+            let active = is_active(&lock, &tasks);
+            (lock, active)
+        })
+        .filter(|(_, active)| all || *active)
+        .collect();
+
+    if output.is_json() {
+        let items: Vec<_> = locks
+            .iter()
+            .map(|(lock, active)| {
+                let mut value = serde_json::to_value(lock).unwrap_or_default();
+                value["active"] = serde_json::json!(active);
+                value
+            })
+            .collect();
+        output.data(&items);
+        return Ok(());
+    }
+
+    if locks.is_empty() {
+        println!("No resource locks held.");
+        return Ok(());
+    }
+    let now = Utc::now();
+    for (lock, active) in &locks {
+        let task = lock
+            .task
+            .as_ref()
+            .map(|t| format!(" for {}", t))
+            .unwrap_or_default();
+        let state = match lock.expires_at {
+            _ if !active => " (lapsed)".to_string(),
+            Some(at) => format!(" ({} left)", format_duration(at - now)),
+            None => String::new(),
+        };
+        println!("  {}  {}{}{}", lock.resource, lock.agent, task, state);
+    }
+    Ok(())
+}
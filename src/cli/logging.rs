@@ -0,0 +1,150 @@
+//! Diagnostic logging
+//!
+//! Diagnostics go through `tracing`. `--verbose` prints info events on stderr
+//! as the familiar `[verbose] ...` lines, and `--trace` adds the debug spans
+//! around storage reads and writes, plugin calls and graph computation, each
+//! reported with its timing when it closes. Without either flag `SHAPE_LOG`
+//! takes a filter directive such as `shape_cli::storage=debug`.
+//!
+//! `--log-dir DIR` (or `SHAPE_LOG_DIR`) also writes debug-level logs to
+//! `DIR/shape.log.<date>`, rotated daily, and `--profile` times the same
+//! spans (see [`Profile`]).
+
+use std::fmt;
+use std::path::Path;
+
+use super::profile::Profile;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::fmt::format::{FmtSpan, Writer};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// Installs the global subscriber. The returned guard flushes the log file
+/// when dropped, so hold it until the command finishes.
+pub(super) fn init(
+    verbose: bool,
+    trace: bool,
+    log_dir: Option<&Path>,
+    profile: Option<&Profile>,
+) -> Option<WorkerGuard> {
+    let stderr_filter = match (trace, verbose) {
+        (true, _) => EnvFilter::new("shape_cli=debug,shape_core=debug"),
+        (false, true) => EnvFilter::new("shape_cli=info,shape_core=info"),
+        (false, false) => {
+            EnvFilter::try_from_env("SHAPE_LOG").unwrap_or_else(|_| EnvFilter::new("off"))
+        }
+    };
+    let stderr = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_ansi(false)
+        .with_span_events(FmtSpan::CLOSE)
+        .event_format(Compact)
+        .with_filter(stderr_filter);
+
+    let (file, guard) = match log_dir {
+        Some(dir) => {
+            let _ = std::fs::create_dir_all(dir);
+            let appender = tracing_appender::rolling::daily(dir, "shape.log");
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = tracing_subscriber::fmt::layer()
+                .with_writer(writer)
+                .with_ansi(false)
+                .with_span_events(FmtSpan::CLOSE)
+                .with_filter(EnvFilter::new("shape_cli=debug,shape_core=debug"));
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    // Only fails if a subscriber is already set, e.g. when embedded
+    let _ = tracing_subscriber::registry()
+        .with(stderr)
+        .with(file)
+        .with(profile.map(|p| p.layer()))
+        .try_init();
+    guard
+}
+
+/// Formats events as `[verbose] message`, `[verbose:context] message` or
+/// `[trace] span{fields}: message key=value`
+struct Compact;
+
+impl<S, N> FormatEvent<S, N> for Compact
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+
+        let tag = match *event.metadata().level() {
+            Level::ERROR => "error",
+            Level::WARN => "warn",
+            Level::INFO => "verbose",
+            _ => "trace",
+        };
+        match &fields.context {
+            Some(context) => write!(writer, "[{}:{}] ", tag, context)?,
+            None => write!(writer, "[{}] ", tag)?,
+        }
+
+        if let Some(scope) = ctx.event_scope() {
+            let mut spans = Vec::new();
+            for span in scope.from_root() {
+                let extensions = span.extensions();
+                match extensions.get::<FormattedFields<N>>() {
+                    Some(f) if !f.is_empty() => spans.push(format!("{}{{{}}}", span.name(), f)),
+                    _ => spans.push(span.name().to_string()),
+                }
+            }
+            if !spans.is_empty() {
+                write!(writer, "{}: ", spans.join(":"))?;
+            }
+        }
+
+        write!(writer, "{}", fields.message)?;
+        for (name, value) in &fields.rest {
+            write!(writer, " {}={}", name, value)?;
+        }
+        writeln!(writer)
+    }
+}
+
+/// An event's fields, with `message` and `context` pulled out
+#[derive(Default)]
+struct Fields {
+    message: String,
+    context: Option<String>,
+    rest: Vec<(&'static str, String)>,
+}
+
+impl Fields {
+    fn set(&mut self, field: &Field, value: String) {
+        match field.name() {
+            "message" => self.message = value,
+            "context" => self.context = Some(value),
+            name => self.rest.push((name, value)),
+        }
+    }
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.set(field, format!("{:?}", value));
+    }
+}
@@ -0,0 +1,230 @@
+//! `shape maintenance`: housekeeping that keeps the live stores small
+//!
+//! `archive-shipped` moves shipped briefs that haven't changed in a while,
+//! with their done tasks, into the archive (see
+//! [`archive`](crate::storage::archive)). A brief stays put while any of its
+//! tasks is open, or while a task that isn't being archived depends on one of
+//! them, since moving the task would leave that dependency dangling.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::Result;
+use chrono::Utc;
+use clap::Subcommand;
+use serde_json::json;
+
+use super::duration;
+use super::error::{CliError, ErrorCode};
+use super::output::Output;
+use crate::domain::{Brief, BriefId, BriefStatus, Task, TaskId};
+use crate::storage::{archive, Project};
+
+#[derive(Subcommand)]
+pub enum MaintenanceCommands {
+    /// Move old shipped briefs and their done tasks to the archive
+    ///
+    /// Example:
+    ///   shape maintenance archive-shipped --older-than 90d
+    ///   shape maintenance archive-shipped --dry-run
+    ArchiveShipped {
+        /// Only briefs last updated longer ago than this (e.g. 90d, 12w);
+        /// defaults to [briefs] archive_shipped_after_days
+        #[arg(long)]
+        older_than: Option<String>,
+
+        /// Show what would be archived without moving anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+pub fn run(cmd: MaintenanceCommands, output: &Output) -> Result<()> {
+    match cmd {
+        MaintenanceCommands::ArchiveShipped {
+            older_than,
+            dry_run,
+        } => archive_shipped(output, older_than.as_deref(), dry_run),
+    }
+}
+
+fn archive_shipped(output: &Output, older_than: Option<&str>, dry_run: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    if project.tx_session()?.is_some() {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            "A transaction is open. Run 'shape tx commit' or 'shape tx abort' first.",
+        )
+        .into());
+    }
+
+    let age = match older_than {
+        Some(s) => duration::parse_duration(s)?,
+        None => match project.config().project.briefs.archive_shipped_after_days {
+            0 => {
+                return Err(CliError::new(
+                    ErrorCode::Validation,
+                    "No age given. Pass --older-than (e.g. 90d) or set [briefs] archive_shipped_after_days.",
+                )
+                .into())
+            }
+            days => chrono::Duration::days(days as i64),
+        },
+    };
+    let cutoff = Utc::now() - age;
+
+    let brief_store = project.brief_store();
+    let task_store = project.task_store();
+    let briefs = brief_store.read_all()?;
+    let tasks = task_store.read_all()?;
+
+    let candidates: Vec<&Brief> = briefs
+        .values()
+        .filter(|b| b.status == BriefStatus::Shipped && b.updated_at < cutoff)
+        .collect();
+    let Selection { archived, skipped } = select(&candidates, &tasks);
+
+    let moved_tasks: Vec<TaskId> = {
+        let mut ids: Vec<TaskId> = tasks
+            .values()
+            .filter(|t| t.brief_id().is_some_and(|b| archived.contains_key(&b)))
+            .map(|t| t.id.clone())
+            .collect();
+        ids.sort();
+        ids
+    };
+
+    if !dry_run && !archived.is_empty() {
+        // Into the archive first: an interrupted run leaves copies in both
+        // places, never in neither
+        let shape_dir = project.shape_dir();
+        archive::mirror_encryption(&shape_dir)?;
+        archive::remove_legacy_index(&shape_dir)?;
+        project.ensure_gitignored("archive/briefs/index.jsonl")?;
+        let archived_briefs = project.archived_brief_store();
+        for id in archived.keys() {
+            archived_briefs.write(&briefs[id])?;
+        }
+        let moved: Vec<Task> = moved_tasks.iter().map(|id| tasks[id].clone()).collect();
+        project.archived_task_store().update_batch(&moved)?;
+
+        task_store.modify(|tasks| {
+            for id in &moved_tasks {
+                tasks.remove(id);
+            }
+            Ok(())
+        })?;
+        for id in archived.keys() {
+            brief_store.remove(id)?;
+        }
+    }
+
+    if output.is_json() {
+        output.data(&json!({
+            "dry_run": dry_run,
+            "cutoff": cutoff,
+            "archived": archived
+                .iter()
+                .map(|(id, (title, count))| json!({
+                    "id": id.to_string(),
+                    "title": title,
+                    "tasks": count,
+                }))
+                .collect::<Vec<_>>(),
+            "skipped": skipped
+                .iter()
+                .map(|(id, reason)| json!({ "id": id.to_string(), "reason": reason }))
+                .collect::<Vec<_>>(),
+        }));
+        return Ok(());
+    }
+
+    if archived.is_empty() {
+        println!(
+            "No shipped briefs to archive (unchanged since {})",
+            cutoff.format("%Y-%m-%d")
+        );
+    } else {
+        let verb = if dry_run { "Would archive" } else { "Archived" };
+        output.success(&format!(
+            "{} {} brief(s) and {} task(s)",
+            verb,
+            archived.len(),
+            moved_tasks.len()
+        ));
+        for (id, (title, count)) in &archived {
+            println!("  {}  {} ({} tasks)", id, title, count);
+        }
+    }
+    if !skipped.is_empty() {
+        println!("Skipped:");
+        for (id, reason) in &skipped {
+            println!("  {}  {}", id, reason);
+        }
+    }
+    Ok(())
+}
+
+/// Which shipped briefs move to the archive
+#[derive(Debug, Default)]
+struct Selection {
+    /// Briefs to archive, with their title and task count
+    archived: BTreeMap<BriefId, (String, usize)>,
+
+    /// Briefs that stay, with the reason
+    skipped: BTreeMap<BriefId, String>,
+}
+
+/// Splits candidate briefs into those that can move and those that must stay
+fn select(candidates: &[&Brief], tasks: &HashMap<TaskId, Task>) -> Selection {
+    let mut skipped = BTreeMap::new();
+    let mut moving: HashSet<BriefId> = HashSet::new();
+    for brief in candidates {
+        if brief.frozen.is_some() {
+            skipped.insert(brief.id.clone(), "frozen".to_string());
+        } else if let Some(open) = tasks
+            .values()
+            .find(|t| t.brief_id().as_ref() == Some(&brief.id) && !t.status.is_complete())
+        {
+            skipped.insert(brief.id.clone(), format!("task {} is still open", open.id));
+        } else {
+            moving.insert(brief.id.clone());
+        }
+    }
+
+    // Dropping one brief can strand a task another brief's tasks depend on,
+    // so repeat until nothing more drops out
+    loop {
+        let stranded = tasks.values().find_map(|t| {
+            let stays = !t.brief_id().is_some_and(|b| moving.contains(&b));
+            if !stays {
+                return None;
+            }
+            t.depends_on.iter().find_map(|d| {
+                let brief = d.task.brief_id()?;
+                moving
+                    .contains(&brief)
+                    .then(|| (brief, format!("{} depends on {}", t.id, d.task)))
+            })
+        });
+        match stranded {
+            Some((brief, reason)) => {
+                moving.remove(&brief);
+                skipped.insert(brief, reason);
+            }
+            None => break,
+        }
+    }
+
+    let archived = candidates
+        .iter()
+        .filter(|b| moving.contains(&b.id))
+        .map(|b| {
+            let count = tasks
+                .values()
+                .filter(|t| t.brief_id().as_ref() == Some(&b.id))
+                .count();
+            (b.id.clone(), (b.title.clone(), count))
+        })
+        .collect();
+    Selection { archived, skipped }
+}
@@ -0,0 +1,278 @@
+//! MCP server - `shape serve --mcp`
+//!
+//! Speaks the Model Context Protocol over stdin/stdout (JSON-RPC 2.0, one
+//! message per line) so AI clients can use Shape as a tool provider instead
+//! of shelling out. The server answers `initialize`, `ping`, `tools/list`
+//! and `tools/call`; the tools are listed in [`tools`].
+//!
+//! A tool that fails (unknown task, claim conflict, ...) returns a result
+//! with `isError: true` and the message as text, as MCP expects, so the model
+//! sees what went wrong. Only protocol problems are JSON-RPC errors.
+
+use std::io::{self, BufRead, Write};
+
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use super::agent::get_agent_name;
+use super::context::{self, ContextOptions};
+use super::output::Output;
+use super::serve::{error_response, optional_param, param, RpcError, INVALID_PARAMS};
+use super::serve::{INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR};
+use crate::api::ShapeApi;
+use crate::domain::TaskStatus;
+
+/// Protocol revisions this server can speak, oldest first
+const PROTOCOL_VERSIONS: [&str; 3] = ["2024-11-05", "2025-03-26", "2025-06-18"];
+
+/// Run the MCP server until stdin closes
+pub(super) fn run(output: &Output) -> Result<()> {
+    let api = ShapeApi::open_current()?;
+    output.verbose_ctx(
+        "serve",
+        &format!("MCP server on stdio for {}", api.root().display()),
+    );
+
+    let server = McpServer { api, output };
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle_line(&line) {
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+struct McpServer<'a> {
+    api: ShapeApi,
+    output: &'a Output,
+}
+
+impl McpServer<'_> {
+    /// Handles one message; notifications get no response
+    fn handle_line(&self, line: &str) -> Option<Value> {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, e)),
+        };
+
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "Missing method",
+            ));
+        };
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = self.dispatch(method, &params);
+        id.map(|id| match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(e) => error_response(id, e.code, e.message),
+        })
+    }
+
+    fn dispatch(&self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => {
+                let requested: Option<String> = optional_param(params, "protocolVersion")?;
+                let version = requested
+                    .filter(|v| PROTOCOL_VERSIONS.contains(&v.as_str()))
+                    .unwrap_or_else(|| PROTOCOL_VERSIONS[PROTOCOL_VERSIONS.len() - 1].to_string());
+                Ok(json!({
+                    "protocolVersion": version,
+                    "capabilities": { "tools": {} },
+                    "serverInfo": {
+                        "name": "shape",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                }))
+            }
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tools() })),
+            "tools/call" => {
+                let name: String = param(params, "name")?;
+                if !tools().iter().any(|t| t["name"] == name.as_str()) {
+                    return Err(RpcError::new(
+                        INVALID_PARAMS,
+                        format!("Unknown tool: {}", name),
+                    ));
+                }
+                let args = params.get("arguments").cloned().unwrap_or(json!({}));
+                Ok(match self.call_tool(&name, &args) {
+                    Ok(value) => json!({
+                        "content": [{ "type": "text", "text": value.to_string() }],
+                        "isError": false,
+                    }),
+                    Err(e) => json!({
+                        "content": [{ "type": "text", "text": format!("{:#}", e) }],
+                        "isError": true,
+                    }),
+                })
+            }
+            // Notifications such as notifications/initialized need no answer
+            m if m.starts_with("notifications/") => Ok(Value::Null),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {}", method),
+            )),
+        }
+    }
+
+    fn call_tool(&self, name: &str, args: &Value) -> Result<Value> {
+        let project = self.api.project();
+        let text = |key: &str| -> Result<Option<String>> {
+            optional_param(args, key).map_err(|e| anyhow::anyhow!(e.message))
+        };
+        let required = |key: &str| -> Result<String> {
+            text(key)?.ok_or_else(|| anyhow::anyhow!("Missing argument: {}", key))
+        };
+        let task_id = || project.resolve_task_id(&required("task_id")?);
+        let agent = || -> Result<String> {
+            Ok(text("agent")?.unwrap_or_else(|| get_agent_name(project, None)))
+        };
+
+        let value = match name {
+            "shape_list_briefs" => serde_json::to_value(self.api.briefs()?)?,
+            "shape_list_tasks" => {
+                let brief = text("brief")?
+                    .map(|b| project.resolve_brief_id(&b))
+                    .transpose()?;
+                let status: Option<TaskStatus> =
+                    optional_param(args, "status").map_err(|e| anyhow::anyhow!(e.message))?;
+                let tasks: Vec<_> = self
+                    .api
+                    .tasks()?
+                    .into_iter()
+                    .filter(|t| brief.is_none() || t.brief_id() == brief)
+                    .filter(|t| status.is_none_or(|s| t.status == s))
+                    .collect();
+                serde_json::to_value(tasks)?
+            }
+            "shape_ready" => {
+                let brief = text("brief")?
+                    .map(|b| project.resolve_brief_id(&b))
+                    .transpose()?;
+                let ready: Vec<_> = self
+                    .api
+                    .ready()?
+                    .into_iter()
+                    .filter(|t| brief.is_none() || t.brief_id() == brief)
+                    .collect();
+                serde_json::to_value(ready)?
+            }
+            "shape_claim" => serde_json::to_value(self.api.claim(&task_id()?, &agent()?)?)?,
+            "shape_task_start" => serde_json::to_value(self.api.start(&task_id()?)?)?,
+            "shape_task_done" => serde_json::to_value(self.api.complete(&task_id()?)?)?,
+            "shape_note" => {
+                let id = task_id()?;
+                serde_json::to_value(self.api.add_note(&id, &agent()?, &required("text")?)?)?
+            }
+            "shape_context" => {
+                let opts = ContextOptions {
+                    compact: optional_param(args, "compact")
+                        .map_err(|e| anyhow::anyhow!(e.message))?
+                        .unwrap_or(true),
+                    brief: text("brief")?,
+                    for_task: text("task_id")?,
+                    days: 7,
+                    max_tokens: optional_param(args, "max_tokens")
+                        .map_err(|e| anyhow::anyhow!(e.message))?,
+                    ..ContextOptions::default()
+                };
+                context::generate(self.output, project, &opts)?
+            }
+            _ => unreachable!("tool names are checked against tools()"),
+        };
+        Ok(value)
+    }
+}
+
+/// Tool definitions for `tools/list`
+fn tools() -> Vec<Value> {
+    let task_id = json!({ "type": "string", "description": "Task ID, e.g. b-7f2a3b1.1" });
+    let agent = json!({
+        "type": "string",
+        "description": "Agent name (default: the configured agent, SHAPE_AGENT or $USER)",
+    });
+    let brief = json!({ "type": "string", "description": "Brief ID to filter by" });
+    let tool = |name: &str, description: &str, properties: Value, required: &[&str]| {
+        json!({
+            "name": name,
+            "description": description,
+            "inputSchema": {
+                "type": "object",
+                "properties": properties,
+                "required": required,
+            },
+        })
+    };
+
+    vec![
+        tool("shape_list_briefs", "List all briefs", json!({}), &[]),
+        tool(
+            "shape_list_tasks",
+            "List tasks, optionally for one brief or with one status",
+            json!({
+                "brief": brief,
+                "status": { "type": "string", "enum": ["todo", "in_progress", "done"] },
+            }),
+            &[],
+        ),
+        tool(
+            "shape_ready",
+            "List tasks whose blocking dependencies are all done",
+            json!({ "brief": brief }),
+            &[],
+        ),
+        tool(
+            "shape_claim",
+            "Claim a task so other agents leave it alone, and mark it in progress",
+            json!({ "task_id": task_id, "agent": agent }),
+            &["task_id"],
+        ),
+        tool(
+            "shape_task_start",
+            "Mark a task in progress",
+            json!({ "task_id": task_id }),
+            &["task_id"],
+        ),
+        tool(
+            "shape_task_done",
+            "Mark a task done",
+            json!({ "task_id": task_id }),
+            &["task_id"],
+        ),
+        tool(
+            "shape_note",
+            "Add a note to a task",
+            json!({
+                "task_id": task_id,
+                "text": { "type": "string", "description": "Note text" },
+                "agent": agent,
+            }),
+            &["task_id", "text"],
+        ),
+        tool(
+            "shape_context",
+            "Project context: briefs and ready, blocked, in-progress and recent tasks",
+            json!({
+                "compact": { "type": "boolean", "description": "Compact export (default: true)" },
+                "brief": brief,
+                "task_id": {
+                    "type": "string",
+                    "description": "Only this task, its blocking ancestors, siblings and brief",
+                },
+                "max_tokens": { "type": "integer", "description": "Trim the export to fit about this many tokens" },
+            }),
+            &[],
+        ),
+    ]
+}
@@ -3,6 +3,9 @@
 //! This module implements a custom git merge driver for JSONL files.
 //! Git calls this driver with three file paths: base, ours, theirs.
 //! We merge task-by-task using field-level timestamps.
+//!
+//! In an encrypted store the three versions are decrypted with the project's
+//! passphrase and the merged result is sealed again.
 
 use std::collections::HashMap;
 use std::fs;
@@ -11,6 +14,8 @@ use std::path::Path;
 use anyhow::{Context, Result};
 
 use crate::domain::{merge_tasks, Task, TaskId};
+use crate::storage::encryption::{self, EncryptionError, StoreCipher};
+use crate::storage::{check_version, Project, TasksHeader};
 
 /// Git merge driver result codes
 pub const MERGE_SUCCESS: i32 = 0;
@@ -23,10 +28,25 @@ pub const MERGE_CONFLICT: i32 = 1;
 /// - %A = ours (current branch) - output should be written here
 /// - %B = theirs (branch being merged)
 pub fn run_merge_driver(base_path: &Path, ours_path: &Path, theirs_path: &Path) -> Result<i32> {
+    // Git runs the driver from the repository root
+    let cipher = match Project::open_current() {
+        Ok(project) => encryption::cipher(&project.shape_dir())?,
+        Err(_) => None,
+    };
+
     // Read all three versions
-    let base_tasks = read_tasks_file(base_path)?;
-    let ours_tasks = read_tasks_file(ours_path)?;
-    let theirs_tasks = read_tasks_file(theirs_path)?;
+    let base_tasks = read_tasks_file(base_path, cipher.as_ref())?;
+    let ours_tasks = read_tasks_file(ours_path, cipher.as_ref())?;
+    let theirs_tasks = read_tasks_file(theirs_path, cipher.as_ref())?;
+
+    // Keep the newest format header either side carries
+    let header = [ours_path, theirs_path]
+        .iter()
+        .map(|path| read_header(path))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .max_by_key(|h| h.format_version);
 
     // Collect all task IDs from all three versions
     let mut all_ids: Vec<TaskId> = base_tasks
@@ -126,10 +146,10 @@ pub fn run_merge_driver(base_path: &Path, ours_path: &Path, theirs_path: &Path)
     }
 
     // Sort tasks by ID for consistent output
-    merged_tasks.sort_by(|a, b| a.id.to_string().cmp(&b.id.to_string()));
+    merged_tasks.sort_by(|a, b| a.id.cmp(&b.id));
 
     // Write merged result to ours_path (git expects output there)
-    write_tasks_file(ours_path, &merged_tasks)?;
+    write_tasks_file(ours_path, header, &merged_tasks, cipher.as_ref())?;
 
     // Return appropriate exit code
     if had_conflicts {
@@ -140,7 +160,7 @@ pub fn run_merge_driver(base_path: &Path, ours_path: &Path, theirs_path: &Path)
 }
 
 /// Reads tasks from a JSONL file
-fn read_tasks_file(path: &Path) -> Result<HashMap<TaskId, Task>> {
+fn read_tasks_file(path: &Path, cipher: Option<&StoreCipher>) -> Result<HashMap<TaskId, Task>> {
     let content = fs::read_to_string(path)
         .with_context(|| format!("Failed to read file: {}", path.display()))?;
 
@@ -151,7 +171,27 @@ fn read_tasks_file(path: &Path) -> Result<HashMap<TaskId, Task>> {
             continue;
         }
 
-        let task: Task = serde_json::from_str(line)
+        if let Some(header) = TasksHeader::parse(line) {
+            check_version(&path.display().to_string(), header.format_version)?;
+            continue;
+        }
+
+        let line = match cipher {
+            _ if !encryption::is_sealed(line) => line.to_string(),
+            Some(cipher) => cipher
+                .open(line)
+                .with_context(|| format!("Failed to decrypt task at line {}", line_num + 1))?,
+            None => {
+                return Err(EncryptionError::Corrupt(format!(
+                    "{} has encrypted tasks but the project has no {}",
+                    path.display(),
+                    encryption::ENCRYPTION_FILE
+                ))
+                .into())
+            }
+        };
+
+        let task: Task = serde_json::from_str(&line)
             .with_context(|| format!("Failed to parse task at line {}", line_num + 1))?;
 
         tasks.insert(task.id.clone(), task);
@@ -160,12 +200,37 @@ fn read_tasks_file(path: &Path) -> Result<HashMap<TaskId, Task>> {
     Ok(tasks)
 }
 
+/// Reads the format header line, if present
+fn read_header(path: &Path) -> Result<Option<TasksHeader>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+
+    Ok(content
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .and_then(TasksHeader::parse))
+}
+
 /// Writes tasks to a JSONL file
-fn write_tasks_file(path: &Path, tasks: &[Task]) -> Result<()> {
+fn write_tasks_file(
+    path: &Path,
+    header: Option<TasksHeader>,
+    tasks: &[Task],
+    cipher: Option<&StoreCipher>,
+) -> Result<()> {
     let mut content = String::new();
 
+    if let Some(header) = header {
+        content.push_str(&serde_json::to_string(&header).context("Failed to serialize header")?);
+        content.push('\n');
+    }
+
     for task in tasks {
         let line = serde_json::to_string(task).context("Failed to serialize task")?;
+        let line = match cipher {
+            Some(cipher) => cipher.seal(&line),
+            None => line,
+        };
         content.push_str(&line);
         content.push('\n');
     }
@@ -196,7 +261,7 @@ mod tests {
 
     fn write_test_file(dir: &Path, name: &str, tasks: &[Task]) -> std::path::PathBuf {
         let path = dir.join(name);
-        write_tasks_file(&path, tasks).unwrap();
+        write_tasks_file(&path, None, tasks, None).unwrap();
         path
     }
 
@@ -222,7 +287,7 @@ mod tests {
         assert_eq!(result, MERGE_SUCCESS);
 
         // Read merged result
-        let merged = read_tasks_file(&ours_path).unwrap();
+        let merged = read_tasks_file(&ours_path, None).unwrap();
         assert_eq!(merged.len(), 2);
         assert!(merged.contains_key(&task1.id));
         assert!(merged.contains_key(&task2.id));
@@ -255,7 +320,7 @@ mod tests {
         // No conflict since different fields were modified
         assert_eq!(result, MERGE_SUCCESS);
 
-        let merged = read_tasks_file(&ours_path).unwrap();
+        let merged = read_tasks_file(&ours_path, None).unwrap();
         let merged_task = merged.get(&task1.id).unwrap();
         assert_eq!(merged_task.title, "Ours title");
         assert_eq!(
@@ -288,7 +353,33 @@ mod tests {
         assert_eq!(result, MERGE_CONFLICT);
 
         // Modified version should be kept
-        let merged = read_tasks_file(&ours_path).unwrap();
+        let merged = read_tasks_file(&ours_path, None).unwrap();
         assert!(merged.contains_key(&task1.id));
     }
+
+    #[test]
+    fn merge_keeps_format_header() {
+        let dir = TempDir::new().unwrap();
+        let brief = BriefId::new("Test", Utc::now());
+        let task1 = make_task(&brief, 1, "Task 1");
+
+        let base_path = write_test_file(dir.path(), "base", std::slice::from_ref(&task1));
+        let ours_path = write_test_file(dir.path(), "ours", std::slice::from_ref(&task1));
+        let theirs_path = dir.path().join("theirs");
+        write_tasks_file(
+            &theirs_path,
+            Some(TasksHeader::current()),
+            std::slice::from_ref(&task1),
+            None,
+        )
+        .unwrap();
+
+        run_merge_driver(&base_path, &ours_path, &theirs_path).unwrap();
+
+        assert_eq!(
+            read_header(&ours_path).unwrap(),
+            Some(TasksHeader::current())
+        );
+        assert_eq!(read_tasks_file(&ours_path, None).unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,150 @@
+//! Local metrics commands
+//!
+//! `shape metrics show` summarizes `.shape/.cache/metrics.jsonl`, which is
+//! only written when `[metrics] enabled = true`: per-command latency
+//! percentiles and failure counts, plus the latest store sizes.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use clap::Subcommand;
+use serde::Serialize;
+
+use super::output::Output;
+use crate::storage::{MetricRecord, Project, StoreSizes};
+
+#[derive(Subcommand)]
+pub enum MetricsCommands {
+    /// Summarize recorded command latencies and store sizes
+    ///
+    /// Example:
+    ///   shape metrics show --days 7
+    Show {
+        /// Only include commands starting with this (e.g. "task")
+        #[arg(long)]
+        command: Option<String>,
+
+        /// Only include the last N days
+        #[arg(long)]
+        days: Option<i64>,
+    },
+}
+
+pub fn run(cmd: MetricsCommands, output: &Output) -> Result<()> {
+    match cmd {
+        MetricsCommands::Show { command, days } => show(output, command.as_deref(), days),
+    }
+}
+
+/// Latency summary for one command
+#[derive(Debug, Serialize)]
+struct CommandStats {
+    command: String,
+    runs: usize,
+    failures: usize,
+    p50_ms: u64,
+    p95_ms: u64,
+    max_ms: u64,
+}
+
+impl CommandStats {
+    fn from_records(command: String, records: &[&MetricRecord]) -> Self {
+        let mut durations: Vec<u64> = records.iter().map(|r| r.duration_ms).collect();
+        durations.sort_unstable();
+        Self {
+            command,
+            runs: records.len(),
+            failures: records.iter().filter(|r| !r.success).count(),
+            p50_ms: percentile(&durations, 50),
+            p95_ms: percentile(&durations, 95),
+            max_ms: durations.last().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn show(output: &Output, command: Option<&str>, days: Option<i64>) -> Result<()> {
+    let project = Project::open_current()?;
+    let enabled = project.config().project.metrics.enabled;
+    let since = days.map(|d| Utc::now() - Duration::days(d));
+
+    let records = project.metrics()?;
+    let selected: Vec<&MetricRecord> = records
+        .iter()
+        .filter(|r| command.is_none_or(|c| r.command.starts_with(c)))
+        .filter(|r| since.is_none_or(|s| r.at >= s))
+        .collect();
+
+    let mut by_command: BTreeMap<&str, Vec<&MetricRecord>> = BTreeMap::new();
+    for record in &selected {
+        by_command.entry(&record.command).or_default().push(record);
+    }
+    let stats: Vec<CommandStats> = by_command
+        .into_iter()
+        .map(|(name, records)| CommandStats::from_records(name.to_string(), &records))
+        .collect();
+    let sizes: Option<StoreSizes> = records.last().map(|r| r.sizes);
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "enabled": enabled,
+            "records": selected.len(),
+            "commands": stats,
+            "sizes": sizes,
+        }));
+        return Ok(());
+    }
+
+    if !enabled {
+        println!(
+            "Metrics are off. Set [metrics] enabled = true in .shape/config.toml to record them."
+        );
+    }
+    if stats.is_empty() {
+        println!("No metrics recorded.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<24} {:>6} {:>6} {:>8} {:>8} {:>8}",
+        "COMMAND", "RUNS", "FAILED", "P50 ms", "P95 ms", "MAX ms"
+    );
+    for s in &stats {
+        println!(
+            "{:<24} {:>6} {:>6} {:>8} {:>8} {:>8}",
+            s.command, s.runs, s.failures, s.p50_ms, s.p95_ms, s.max_ms
+        );
+    }
+    if let Some(sizes) = sizes {
+        println!();
+        println!(
+            "Stores: tasks.jsonl {} bytes, {} briefs, cache {} bytes",
+            sizes.tasks_bytes, sizes.briefs, sizes.cache_bytes
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        assert_eq!(percentile(&[], 50), 0);
+        assert_eq!(percentile(&[7], 95), 7);
+        let values: Vec<u64> = (1..=20).collect();
+        assert_eq!(percentile(&values, 50), 10);
+        assert_eq!(percentile(&values, 95), 19);
+    }
+}
@@ -0,0 +1,64 @@
+//! Migrate command - upgrades the on-disk format to the current version
+//!
+//! Applies the steps from [`pending_migrations`] one version at a time.
+//! Each step is idempotent, so an interrupted migration can be re-run.
+
+use anyhow::Result;
+use serde_json::json;
+
+use super::output::Output;
+use crate::storage::{pending_migrations, Project, FORMAT_VERSION};
+
+/// Run the migrate command
+pub fn run(output: &Output, dry_run: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let from = project.format_version()?;
+    let steps = pending_migrations(from);
+
+    if !dry_run {
+        for step in &steps {
+            output.verbose_ctx(
+                "migrate",
+                &format!("v{} -> v{}: {}", step.from, step.from + 1, step.description),
+            );
+            step.apply(project.root())?;
+        }
+    }
+
+    if output.is_json() {
+        let applied: Vec<_> = steps
+            .iter()
+            .map(|s| {
+                json!({
+                    "from": s.from,
+                    "to": s.from + 1,
+                    "description": s.description,
+                })
+            })
+            .collect();
+        output.data(&json!({
+            "from_version": from,
+            "to_version": FORMAT_VERSION,
+            "dry_run": dry_run,
+            "migrations": applied,
+        }));
+    } else if steps.is_empty() {
+        output.success(&format!("Already at format version {}", FORMAT_VERSION));
+    } else {
+        let verb = if dry_run { "Would migrate" } else { "Migrated" };
+        println!(
+            "{} from format version {} to {}:",
+            verb, from, FORMAT_VERSION
+        );
+        for step in &steps {
+            println!(
+                "  v{} -> v{}: {}",
+                step.from,
+                step.from + 1,
+                step.description
+            );
+        }
+    }
+
+    Ok(())
+}
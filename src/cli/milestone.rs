@@ -0,0 +1,435 @@
+//! Milestone CLI commands
+//!
+//! Milestones are date-anchored targets ("Conference demo — March 3") that
+//! briefs and tasks work toward. Targeting sets the `milestone` metadata key;
+//! tasks inherit their brief's milestone unless they target one themselves.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use clap::Subcommand;
+use serde_json::json;
+
+use super::error::{CliError, ErrorCode};
+use super::freeze::{ensure_brief_mutable, ensure_task_mutable};
+use super::output::Output;
+use crate::domain::{
+    brief_milestone, task_milestone, Brief, BriefId, Milestone, Task, TaskId, TaskStatus,
+    MILESTONE_META_KEY,
+};
+use crate::storage::Project;
+
+#[derive(Subcommand)]
+pub enum MilestoneCommands {
+    /// Create a milestone
+    Add {
+        /// Milestone title
+        title: String,
+
+        /// Milestone date (YYYY-MM-DD)
+        #[arg(long)]
+        date: String,
+
+        /// Longer description
+        #[arg(long, short)]
+        description: Option<String>,
+    },
+
+    /// List milestones with progress toward each
+    List {
+        /// Only show milestones whose date has passed with work outstanding
+        #[arg(long)]
+        overdue: bool,
+    },
+
+    /// Show a milestone and the briefs and tasks targeting it
+    Show {
+        /// Milestone ID
+        id: String,
+    },
+
+    /// Target briefs or tasks at a milestone
+    Target {
+        /// Milestone ID
+        id: String,
+
+        /// Brief or task IDs
+        #[arg(required = true)]
+        items: Vec<String>,
+    },
+
+    /// Clear the milestone target of briefs or tasks
+    Untarget {
+        /// Brief or task IDs
+        #[arg(required = true)]
+        items: Vec<String>,
+    },
+
+    /// Delete a milestone and clear targets pointing at it
+    Remove {
+        /// Milestone ID
+        id: String,
+    },
+}
+
+pub fn run(cmd: MilestoneCommands, output: &Output) -> Result<()> {
+    match cmd {
+        MilestoneCommands::Add {
+            title,
+            date,
+            description,
+        } => add(output, &title, &date, description),
+        MilestoneCommands::List { overdue } => list(output, overdue),
+        MilestoneCommands::Show { id } => show(output, &id),
+        MilestoneCommands::Target { id, items } => target(output, Some(&id), &items),
+        MilestoneCommands::Untarget { items } => target(output, None, &items),
+        MilestoneCommands::Remove { id } => remove(output, &id),
+    }
+}
+
+/// Progress of the work targeting one milestone
+#[derive(Debug, Default)]
+struct Progress {
+    briefs: Vec<BriefId>,
+    tasks: Vec<TaskId>,
+    done: usize,
+}
+
+impl Progress {
+    fn percent(&self) -> usize {
+        if self.tasks.is_empty() {
+            0
+        } else {
+            self.done * 100 / self.tasks.len()
+        }
+    }
+
+    /// `done`, `overdue`, or `upcoming`
+    fn state(&self, milestone: &Milestone, today: NaiveDate) -> &'static str {
+        if !self.tasks.is_empty() && self.done == self.tasks.len() {
+            "done"
+        } else if milestone.is_past(today) {
+            "overdue"
+        } else {
+            "upcoming"
+        }
+    }
+}
+
+/// Groups briefs and tasks by the milestone they target
+fn progress_by_milestone(
+    briefs: &HashMap<BriefId, Brief>,
+    tasks: &HashMap<TaskId, Task>,
+) -> HashMap<String, Progress> {
+    let mut progress: HashMap<String, Progress> = HashMap::new();
+
+    for brief in briefs.values() {
+        if let Some(id) = brief_milestone(brief) {
+            progress
+                .entry(id.to_string())
+                .or_default()
+                .briefs
+                .push(brief.id.clone());
+        }
+    }
+
+    for task in tasks.values() {
+        let brief = task.brief_id().and_then(|id| briefs.get(&id));
+        if let Some(id) = task_milestone(task, brief) {
+            let entry = progress.entry(id.to_string()).or_default();
+            entry.tasks.push(task.id.clone());
+            if task.status == TaskStatus::Done {
+                entry.done += 1;
+            }
+        }
+    }
+
+    for entry in progress.values_mut() {
+        entry.briefs.sort();
+        entry.tasks.sort();
+    }
+
+    progress
+}
+
+fn milestone_json(
+    milestone: &Milestone,
+    progress: &Progress,
+    today: NaiveDate,
+) -> serde_json::Value {
+    json!({
+        "id": milestone.id,
+        "title": milestone.title,
+        "date": milestone.date,
+        "description": milestone.description,
+        "days_left": milestone.days_until(today),
+        "state": progress.state(milestone, today),
+        "briefs": progress.briefs.len(),
+        "tasks": {
+            "total": progress.tasks.len(),
+            "done": progress.done,
+        },
+        "percent": progress.percent(),
+    })
+}
+
+fn find(project: &Project, id: &str) -> Result<Milestone> {
+    project
+        .milestone_store()
+        .read(id)?
+        .ok_or_else(|| CliError::not_found("Milestone", id).into())
+}
+
+fn add(output: &Output, title: &str, date: &str, description: Option<String>) -> Result<()> {
+    let project = Project::open_current()?;
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+        CliError::new(
+            ErrorCode::Validation,
+            format!("Invalid date '{}': expected YYYY-MM-DD", date),
+        )
+    })?;
+
+    let mut milestone = Milestone::new(title, date);
+    milestone.description = description;
+    project.milestone_store().upsert(&milestone)?;
+
+    if output.is_json() {
+        output.data(&milestone);
+    } else {
+        output.success(&format!(
+            "Created milestone {}: {} ({})",
+            milestone.id, milestone.title, milestone.date
+        ));
+    }
+
+    Ok(())
+}
+
+fn list(output: &Output, overdue_only: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let milestones = project.milestone_store().read_all()?;
+    let briefs = project.brief_store().read_all_meta()?;
+    let tasks = project.task_store().read_all()?;
+    let progress = progress_by_milestone(&briefs, &tasks);
+    let today = Utc::now().date_naive();
+    let empty = Progress::default();
+
+    let rows: Vec<_> = milestones
+        .iter()
+        .map(|m| (m, progress.get(&m.id).unwrap_or(&empty)))
+        .filter(|(m, p)| !overdue_only || p.state(m, today) == "overdue")
+        .collect();
+
+    if output.is_json() {
+        let items: Vec<_> = rows
+            .iter()
+            .map(|(m, p)| milestone_json(m, p, today))
+            .collect();
+        output.data(&items);
+        return Ok(());
+    }
+
+    if rows.is_empty() {
+        println!("No milestones found.");
+        return Ok(());
+    }
+
+    for (milestone, progress) in rows {
+        let when = match milestone.days_until(today) {
+            0 => "today".to_string(),
+            d if d > 0 => format!("in {}d", d),
+            d => format!("{}d ago", -d),
+        };
+        println!(
+            "{}  {}  {:<8} {:>3}% ({}/{})  {:<8} {}",
+            milestone.id,
+            milestone.date,
+            when,
+            progress.percent(),
+            progress.done,
+            progress.tasks.len(),
+            progress.state(milestone, today),
+            milestone.title
+        );
+    }
+
+    Ok(())
+}
+
+fn show(output: &Output, id: &str) -> Result<()> {
+    let project = Project::open_current()?;
+    let milestone = find(&project, id)?;
+    let briefs = project.brief_store().read_all_meta()?;
+    let tasks = project.task_store().read_all()?;
+    let progress = progress_by_milestone(&briefs, &tasks)
+        .remove(&milestone.id)
+        .unwrap_or_default();
+    let today = Utc::now().date_naive();
+
+    if output.is_json() {
+        let mut value = milestone_json(&milestone, &progress, today);
+        value["briefs"] = json!(progress
+            .briefs
+            .iter()
+            .map(|id| json!({"id": id.to_string(), "title": briefs[id].title}))
+            .collect::<Vec<_>>());
+        value["tasks"] = json!(progress
+            .tasks
+            .iter()
+            .map(|id| json!({
+                "id": id.to_string(),
+                "title": tasks[id].title,
+                "status": tasks[id].status,
+            }))
+            .collect::<Vec<_>>());
+        output.data(&value);
+        return Ok(());
+    }
+
+    println!("Milestone: {}", milestone.id);
+    println!("Title: {}", milestone.title);
+    println!(
+        "Date: {} ({} days left)",
+        milestone.date,
+        milestone.days_until(today)
+    );
+    println!("State: {}", progress.state(&milestone, today));
+    println!(
+        "Progress: {}% ({}/{} tasks done)",
+        progress.percent(),
+        progress.done,
+        progress.tasks.len()
+    );
+    if let Some(description) = &milestone.description {
+        println!("\n{}", description);
+    }
+
+    if !progress.briefs.is_empty() {
+        println!("\nBriefs ({}):", progress.briefs.len());
+        for id in &progress.briefs {
+            println!("  {} {}", id, briefs[id].title);
+        }
+    }
+
+    if !progress.tasks.is_empty() {
+        println!("\nTasks ({}):", progress.tasks.len());
+        for id in &progress.tasks {
+            let task = &tasks[id];
+            let status_icon = match task.status {
+                TaskStatus::Todo => "[ ]",
+                TaskStatus::InProgress => "[~]",
+                TaskStatus::Done => "[x]",
+            };
+            println!("  {} {} {}", status_icon, task.id, task.title);
+        }
+    }
+
+    Ok(())
+}
+
+/// Sets (or with `None`, clears) the milestone of briefs and tasks
+fn target(output: &Output, milestone_id: Option<&str>, items: &[String]) -> Result<()> {
+    let project = Project::open_current()?;
+    if let Some(id) = milestone_id {
+        find(&project, id)?;
+    }
+
+    let brief_store = project.brief_store();
+    let mut briefs = Vec::new();
+    let mut task_ids = Vec::new();
+    for item in items {
+        if let Ok(brief_id) = project.resolve_brief_id(item) {
+            let brief = brief_store
+                .read(&brief_id)?
+                .ok_or_else(|| CliError::not_found("Brief", &brief_id))?;
+            ensure_brief_mutable(&project, &brief)?;
+            briefs.push(brief);
+        } else {
+            task_ids.push(project.resolve_task_id(item)?);
+        }
+    }
+
+    if !task_ids.is_empty() {
+        project.task_store().modify(|tasks| {
+            for task_id in &task_ids {
+                let task = tasks
+                    .get_mut(task_id)
+                    .ok_or_else(|| CliError::not_found("Task", task_id))?;
+                ensure_task_mutable(&project, task)?;
+                match milestone_id {
+                    Some(id) => task.set_meta(MILESTONE_META_KEY, id),
+                    None => {
+                        task.remove_meta(MILESTONE_META_KEY);
+                    }
+                }
+            }
+            Ok(())
+        })?;
+    }
+    for mut brief in briefs {
+        match milestone_id {
+            Some(id) => brief.set_meta(MILESTONE_META_KEY, id),
+            None => {
+                brief.remove_meta(MILESTONE_META_KEY);
+            }
+        }
+        brief_store.write(&brief)?;
+    }
+
+    if output.is_json() {
+        output.data(&json!({
+            "milestone": milestone_id,
+            "items": items,
+        }));
+    } else {
+        match milestone_id {
+            Some(id) => output.success(&format!("Targeted {} item(s) at {}", items.len(), id)),
+            None => output.success(&format!("Cleared milestone on {} item(s)", items.len())),
+        }
+    }
+
+    Ok(())
+}
+
+fn remove(output: &Output, id: &str) -> Result<()> {
+    let project = Project::open_current()?;
+    let milestone = find(&project, id)?;
+
+    let brief_store = project.brief_store();
+    let mut cleared = 0;
+    for mut brief in brief_store.read_all()?.into_values() {
+        if brief_milestone(&brief) == Some(id) {
+            brief.remove_meta(MILESTONE_META_KEY);
+            brief_store.write(&brief)?;
+            cleared += 1;
+        }
+    }
+
+    cleared += project.task_store().modify(|tasks| {
+        let mut count = 0;
+        for task in tasks.values_mut() {
+            if task.get_meta(MILESTONE_META_KEY).and_then(|v| v.as_str()) == Some(id) {
+                task.remove_meta(MILESTONE_META_KEY);
+                count += 1;
+            }
+        }
+        Ok(count)
+    })?;
+
+    project.milestone_store().remove(&milestone.id)?;
+
+    if output.is_json() {
+        output.data(&json!({
+            "removed": milestone.id,
+            "cleared": cleared,
+        }));
+    } else {
+        output.success(&format!(
+            "Removed milestone {} (cleared {} target(s))",
+            milestone.id, cleared
+        ));
+    }
+
+    Ok(())
+}
@@ -9,9 +9,13 @@
 //! | Core | Project management | `init`, `status` |
 //! | Brief | Document lifecycle | `brief new`, `brief list`, `brief show` |
 //! | Task | Work item management | `task add`, `task start`, `task done` |
-//! | Agent | Multi-agent coordination | `claim`, `next`, `note`, `block` |
+//! | Agent | Multi-agent coordination | `claim`, `next`, `note`, `block`, `lock acquire` |
 //! | Query | Task state queries | `ready`, `blocked` |
+//! | Planning | Milestones and the betting table | `milestone add`, `bet open`, `bet close` |
 //! | Context | AI integration | `context`, `context --compact` |
+//! | Graph | Dependency graph export | `graph export --format gexf` |
+//! | CI | Validation and change impact | `doctor`, `affected` |
+//! | Integration | Editor protocol server | `serve --jsonrpc-stdio` |
 //! | Advanced | Plugins and sync | `plugin list`, `sync run` |
 //!
 //! ## Output Formats
@@ -20,6 +24,9 @@
 //! - `text` (default) - Human-readable output
 //! - `json` - Machine-parseable JSON
 //!
+//! Diagnostic commands (`doctor`, `affected`) also support
+//! `github-annotations` and `junit` for CI.
+//!
 //! ## Verbose Mode
 //!
 //! Use `--verbose` (or `-v`) for debug output:
@@ -31,21 +38,76 @@
 //!
 //! Call [`run()`] to parse arguments and execute the appropriate command.
 
+mod adopt;
+mod affected;
 mod agent;
 mod agent_setup;
 mod app;
+mod appetite;
+mod bench;
+mod bet;
 mod brief;
 mod cache_cmd;
+mod claims;
 mod compact;
+mod compliance;
+mod config_cmd;
 mod context;
 mod daemon;
+mod doctor;
+mod duration;
+mod editor;
+mod error;
+pub(crate) mod freeze;
+pub(crate) mod gates;
+mod gc;
+mod gfm;
+mod graph;
+mod guard;
+mod hooks;
+mod ids;
+mod import;
+mod ingest;
+mod interactive;
+mod lock_cmd;
+mod logging;
+mod maintenance;
+mod mcp;
 mod merge_driver;
+mod metrics;
+mod migrate;
+mod milestone;
+mod open;
 mod output;
+mod page;
+mod pipeline;
 mod plugin_cmd;
+mod profile;
+mod prompt;
 mod query;
+mod render;
+mod reopen;
+mod report;
+pub(crate) mod review;
+#[cfg(feature = "self-update")]
+mod self_update;
+mod serve;
+mod simulate;
+mod split;
+mod status_by;
+mod store_crypt;
 mod sync_cmd;
 mod task;
+mod task_import;
+pub(crate) mod team;
+mod template;
+mod toon;
+mod translate;
 mod tui;
+mod tx;
+pub(crate) mod undo;
+mod watch_run;
 
 pub use app::{run, Cli, Commands};
 pub use output::{Output, OutputFormat};
+pub use toon::{from_toon, to_toon};
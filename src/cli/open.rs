@@ -0,0 +1,206 @@
+//! Open command - jump from an ID to the file or link behind it
+//!
+//! Briefs open in the editor. Tasks open their first link of the preferred
+//! type (`[open] prefer`, default PR, then URL, then file): URLs in the
+//! browser, files in the editor. PR numbers and commit hashes become URLs on
+//! the `origin` remote when it is hosted on GitHub or GitLab.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use anyhow::{Context, Result};
+
+use super::editor;
+use super::error::{CliError, ErrorCode};
+use super::output::Output;
+use crate::domain::{Link, LinkType};
+use crate::storage::Project;
+
+/// What an ID opens
+enum Target {
+    File(PathBuf),
+    Url(String),
+}
+
+pub fn run(output: &Output, id: &str, print: bool) -> Result<()> {
+    let project = Project::open_current()?;
+
+    let (kind, id, target) = if let Ok(brief_id) = project.resolve_brief_id(id) {
+        let store = project.brief_store();
+        if !store.exists(&brief_id) {
+            return Err(CliError::not_found("Brief", &brief_id).into());
+        }
+        let path = store.dir().join(format!("{}.md", brief_id));
+        ("brief", brief_id.to_string(), Target::File(path))
+    } else {
+        let task_id = project.resolve_task_id(id)?;
+        let task = project
+            .task_store()
+            .read_all()?
+            .remove(&task_id)
+            .ok_or_else(|| CliError::not_found("Task", &task_id))?;
+        let prefer = &project.config().project.open.prefer;
+        let target = prefer
+            .iter()
+            .flat_map(|ty| task.links.iter().filter(move |l| l.link_type == *ty))
+            .find_map(|link| link_target(&project, link))
+            .ok_or_else(|| {
+                let types: Vec<_> = prefer.iter().map(|t| t.as_str()).collect();
+                let types = match types.split_last() {
+                    Some((last, rest)) if !rest.is_empty() => {
+                        format!("{} or {}", rest.join(", "), last)
+                    }
+                    _ => types.join(""),
+                };
+                CliError::new(
+                    ErrorCode::NotFound,
+                    format!(
+                        "Task {} has no {} link to open. Add one with `shape link {} --pr ...`",
+                        task_id, types, task_id
+                    ),
+                )
+            })?;
+        ("task", task_id.to_string(), target)
+    };
+
+    let (target_kind, value) = match &target {
+        Target::File(path) => ("file", path.display().to_string()),
+        Target::Url(url) => ("url", url.clone()),
+    };
+
+    if !print {
+        match &target {
+            Target::File(path) => editor::open(&project, path)?,
+            Target::Url(url) => open_url(&project, url)?,
+        }
+    }
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": id,
+            "type": kind,
+            "target": target_kind,
+            "value": value,
+            "opened": !print,
+        }));
+    } else if print {
+        println!("{}", value);
+    } else {
+        output.success(&format!("Opened {}", value));
+    }
+
+    Ok(())
+}
+
+/// File or URL a link points at, if it can be opened
+fn link_target(project: &Project, link: &Link) -> Option<Target> {
+    let reference = link.reference.trim();
+    if reference.starts_with("http://") || reference.starts_with("https://") {
+        return Some(Target::Url(reference.to_string()));
+    }
+    match link.link_type {
+        LinkType::Url => Some(Target::Url(reference.to_string())),
+        LinkType::File => Some(Target::File(project.root().join(reference))),
+        LinkType::Pr => {
+            let number = reference.trim_start_matches('#');
+            if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            let (base, gitlab) = remote_base(project)?;
+            let path = if gitlab { "-/merge_requests" } else { "pull" };
+            Some(Target::Url(format!("{}/{}/{}", base, path, number)))
+        }
+        LinkType::Commit => {
+            let (base, gitlab) = remote_base(project)?;
+            let path = if gitlab { "-/commit" } else { "commit" };
+            Some(Target::Url(format!("{}/{}/{}", base, path, reference)))
+        }
+    }
+}
+
+/// Web URL of the `origin` remote and whether it is GitLab
+fn remote_base(project: &Project) -> Option<(String, bool)> {
+    let out = Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .current_dir(project.root())
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    web_url(String::from_utf8_lossy(&out.stdout).trim())
+}
+
+/// `git@github.com:owner/repo.git` or `https://github.com/owner/repo.git`
+/// as `https://github.com/owner/repo`
+fn web_url(remote: &str) -> Option<(String, bool)> {
+    let rest = if let Some(ssh) = remote.strip_prefix("git@") {
+        ssh.replacen(':', "/", 1)
+    } else {
+        remote
+            .strip_prefix("https://")
+            .or_else(|| remote.strip_prefix("ssh://git@"))?
+            .to_string()
+    };
+    let host = rest.split('/').next()?;
+    let gitlab = host.contains("gitlab");
+    if !gitlab && !host.contains("github") {
+        return None;
+    }
+    let rest = rest.trim_end_matches('/').trim_end_matches(".git");
+    Some((format!("https://{}", rest), gitlab))
+}
+
+/// Opens a URL with `[open] browser`, `$BROWSER` or the platform opener
+fn open_url(project: &Project, url: &str) -> Result<()> {
+    let browser = project
+        .config()
+        .project
+        .open
+        .browser
+        .clone()
+        .or_else(|| std::env::var("BROWSER").ok())
+        .filter(|b| !b.trim().is_empty());
+    let mut command = match &browser {
+        Some(browser) => {
+            let mut words = browser.split_whitespace();
+            let mut command = Command::new(words.next().unwrap_or_default());
+            command.args(words);
+            command
+        }
+        None if cfg!(target_os = "macos") => Command::new("open"),
+        None if cfg!(windows) => {
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        }
+        None => Command::new("xdg-open"),
+    };
+    let status = command
+        .arg(url)
+        .status()
+        .with_context(|| format!("Failed to open {}", url))?;
+    if !status.success() {
+        anyhow::bail!("Opening {} failed ({})", url, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn web_url_from_remotes() {
+        assert_eq!(
+            web_url("git@github.com:luuuc/shape-cli.git"),
+            Some(("https://github.com/luuuc/shape-cli".to_string(), false))
+        );
+        assert_eq!(
+            web_url("https://gitlab.com/group/app.git"),
+            Some(("https://gitlab.com/group/app".to_string(), true))
+        );
+        assert_eq!(web_url("/srv/git/app.git"), None);
+        assert_eq!(web_url("https://example.com/app.git"), None);
+    }
+}
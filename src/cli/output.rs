@@ -1,93 +1,451 @@
 //! Output formatting for CLI commands
 
+use std::cell::RefCell;
+
 use serde::Serialize;
 
+use super::error::ErrorCode;
+
 /// Output format
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
 pub enum OutputFormat {
     #[default]
     Text,
     Json,
+    /// GitHub Actions workflow commands (diagnostic commands only)
+    #[value(name = "github-annotations")]
+    GithubAnnotations,
+    /// JUnit XML report (diagnostic commands only)
+    Junit,
+    /// JSON Graph Format (graph export only)
+    #[value(name = "json-graph")]
+    JsonGraph,
+    /// GEXF XML (graph export only)
+    Gexf,
+    /// YAML transcoded from the JSON output (structured data commands only)
+    Yaml,
+    /// TOML transcoded from the JSON output (structured data commands only)
+    Toml,
+    /// TOON tabular text transcoded from the JSON output (structured data commands only)
+    Toon,
+    /// GitHub-flavored markdown task list (task list only)
+    Gfm,
+    /// Markdown document for pasting into a chat (context only)
+    #[value(alias = "md")]
+    Markdown,
+}
+
+impl OutputFormat {
+    /// Name as given to `--format`
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::GithubAnnotations => "github-annotations",
+            OutputFormat::Junit => "junit",
+            OutputFormat::JsonGraph => "json-graph",
+            OutputFormat::Gexf => "gexf",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Toml => "toml",
+            OutputFormat::Toon => "toon",
+            OutputFormat::Gfm => "gfm",
+            OutputFormat::Markdown => "markdown",
+        }
+    }
+}
+
+/// Formats [`Output::data`] transcodes from the JSON output
+pub const TRANSCODED_FORMATS: &[OutputFormat] =
+    &[OutputFormat::Yaml, OutputFormat::Toml, OutputFormat::Toon];
+
+/// Formats [`Output::diagnostics`] renders, beyond text and JSON
+pub const DIAGNOSTIC_FORMATS: &[OutputFormat] = &[
+    OutputFormat::GithubAnnotations,
+    OutputFormat::Junit,
+    OutputFormat::Yaml,
+    OutputFormat::Toml,
+    OutputFormat::Toon,
+];
+
+/// Severity of a diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+    Notice,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Notice => "notice",
+        }
+    }
+}
+
+/// A single finding reported by a diagnostic command (doctor, affected)
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    /// Severity of the finding
+    pub severity: Severity,
+
+    /// Name of the check that produced it
+    pub check: String,
+
+    /// Human-readable message
+    pub message: String,
+
+    /// File the finding refers to, relative to the project root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+
+    /// 1-based line number within `file`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+
+    /// Brief or task ID the finding refers to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, check: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            check: check.to_string(),
+            message: message.into(),
+            file: None,
+            line: None,
+            id: None,
+        }
+    }
+
+    /// Sets the file and optional line
+    pub fn at(mut self, file: impl Into<String>, line: Option<usize>) -> Self {
+        self.file = Some(file.into());
+        self.line = line;
+        self
+    }
+
+    /// Sets the brief or task ID
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+}
+
+/// Field selection applied to JSON output (`--fields`, `--exclude`).
+///
+/// Applies to every record in the output: any object carrying an `id`, at
+/// any depth. Wrapper objects and arrays are walked but not filtered, so
+/// `{"tasks": [...]}` keeps its shape while each task is projected.
+#[derive(Debug, Clone, Default)]
+pub struct Projection {
+    /// Keep only these fields (all fields when `None`)
+    pub fields: Option<Vec<String>>,
+
+    /// Drop these fields
+    pub exclude: Vec<String>,
+}
+
+impl Projection {
+    pub fn new(fields: Vec<String>, exclude: Vec<String>) -> Self {
+        Self {
+            fields: (!fields.is_empty()).then_some(fields),
+            exclude,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_none() && self.exclude.is_empty()
+    }
+
+    /// Projects a JSON value
+    pub fn apply(&self, value: serde_json::Value) -> serde_json::Value {
+        use serde_json::Value;
+
+        match value {
+            Value::Array(items) => Value::Array(items.into_iter().map(|v| self.apply(v)).collect()),
+            Value::Object(map) => {
+                let is_record = map.contains_key("id");
+                Value::Object(
+                    map.into_iter()
+                        .filter(|(key, _)| !is_record || self.keeps(key))
+                        .map(|(key, v)| {
+                            // Fields of a record are kept whole; only wrappers recurse
+                            if is_record {
+                                (key, v)
+                            } else {
+                                (key, self.apply(v))
+                            }
+                        })
+                        .collect(),
+                )
+            }
+            other => other,
+        }
+    }
+
+    fn keeps(&self, key: &str) -> bool {
+        if self.exclude.iter().any(|e| e == key) {
+            return false;
+        }
+        match &self.fields {
+            Some(fields) => fields.iter().any(|f| f == key),
+            None => true,
+        }
+    }
 }
 
 /// Output helper for consistent formatting
 pub struct Output {
     format: OutputFormat,
     verbose: bool,
+    plain: bool,
+    projection: Projection,
+    captured: Option<RefCell<String>>,
 }
 
 impl Output {
     pub fn new(format: OutputFormat, verbose: bool) -> Self {
-        Self { format, verbose }
+        Self {
+            format,
+            verbose,
+            plain: false,
+            projection: Projection::default(),
+            captured: None,
+        }
+    }
+
+    /// Keeps a copy of the messages and data printed, see [`Output::captured`]
+    pub fn with_capture(mut self) -> Self {
+        self.captured = Some(RefCell::new(String::new()));
+        self
+    }
+
+    /// Success messages and data printed so far, when capturing
+    pub fn captured(&self) -> Option<String> {
+        self.captured.as_ref().map(|c| c.borrow().clone())
+    }
+
+    /// Prints to stdout, keeping a copy when capturing
+    fn emit(&self, text: &str) {
+        print!("{}", text);
+        if let Some(captured) = &self.captured {
+            captured.borrow_mut().push_str(text);
+        }
+    }
+
+    /// Linear text for screen readers: commands produce their structured
+    /// data, printed as `key: value` lines without tables or color
+    pub fn with_plain(mut self) -> Self {
+        self.plain = true;
+        self
+    }
+
+    /// Sets the field projection applied to structured data
+    pub fn with_projection(mut self, projection: Projection) -> Self {
+        self.projection = projection;
+        self
     }
 
     /// Prints a success message
     pub fn success(&self, message: &str) {
-        match self.format {
-            OutputFormat::Text => println!("{}", message),
-            OutputFormat::Json => {
-                println!(
-                    "{}",
-                    serde_json::json!({
-                        "success": true,
-                        "message": message
-                    })
-                );
-            }
+        if self.plain {
+            self.emit(&format!("{}\n", message));
+        } else if self.is_json() {
+            let json = serde_json::json!({
+                "success": true,
+                "message": message
+            });
+            self.emit(&format!("{}\n", json));
+        } else {
+            self.emit(&format!("{}\n", message));
         }
     }
 
     /// Prints an error message
     pub fn error(&self, message: &str) {
-        match self.format {
-            OutputFormat::Text => eprintln!("Error: {}", message),
-            OutputFormat::Json => {
-                eprintln!(
-                    "{}",
-                    serde_json::json!({
-                        "success": false,
-                        "error": message
-                    })
-                );
-            }
+        if self.is_json() && !self.plain {
+            eprintln!(
+                "{}",
+                serde_json::json!({
+                    "success": false,
+                    "error": message
+                })
+            );
+        } else {
+            eprintln!("Error: {}", message);
+        }
+    }
+
+    /// Reports a failed command on stderr, with its code in JSON mode
+    pub fn failure(&self, code: ErrorCode, message: &str) {
+        if self.is_json() && !self.plain {
+            eprintln!(
+                "{}",
+                serde_json::json!({
+                    "success": false,
+                    "error": {
+                        "code": code.as_str(),
+                        "exit_code": code.exit_code(),
+                        "message": message,
+                    }
+                })
+            );
+        } else {
+            eprintln!("Error: {}", message);
         }
     }
 
     /// Prints structured data
     pub fn data<T: Serialize>(&self, data: &T) {
+        if !self.projection.is_empty() {
+            if let Ok(value) = serde_json::to_value(data) {
+                return self.print_data(&self.projection.apply(value));
+            }
+        }
+        self.print_data(data);
+    }
+
+    fn print_data<T: Serialize + ?Sized>(&self, data: &T) {
+        let _span = tracing::debug_span!("output.serialize").entered();
+        if self.plain {
+            if let Ok(value) = serde_json::to_value(data) {
+                self.emit(&plain_text(&value));
+            }
+            return;
+        }
         match self.format {
-            OutputFormat::Text => {
+            OutputFormat::Json => {
+                if let Ok(json) = serde_json::to_string(data) {
+                    self.emit(&format!("{}\n", json));
+                }
+            }
+            OutputFormat::Yaml => match serde_yaml::to_string(data) {
+                Ok(yaml) => self.emit(&yaml),
+                Err(e) => self.error(&format!("Cannot render YAML: {}", e)),
+            },
+            OutputFormat::Toml => match to_toml(data) {
+                Ok(toml) => self.emit(&toml),
+                Err(e) => self.error(&format!("Cannot render TOML: {}", e)),
+            },
+            OutputFormat::Toon => match serde_json::to_value(data) {
+                Ok(value) => self.emit(&super::toon::to_toon(&value)),
+                Err(e) => self.error(&format!("Cannot render TOON: {}", e)),
+            },
+            _ => {
                 // For text format, we expect the caller to handle it
                 // This is a fallback that pretty-prints JSON
                 if let Ok(json) = serde_json::to_string_pretty(data) {
-                    println!("{}", json);
+                    self.emit(&format!("{}\n", json));
                 }
             }
-            OutputFormat::Json => {
-                if let Ok(json) = serde_json::to_string(data) {
-                    println!("{}", json);
+        }
+    }
+
+    /// Prints the findings of a diagnostic command in the selected format.
+    ///
+    /// `checks` lists every check that ran, so JUnit reports include passing
+    /// checks as test cases too.
+    pub fn diagnostics(&self, suite: &str, checks: &[&str], diagnostics: &[Diagnostic]) {
+        let errors = diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .count();
+        let warnings = diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Warning)
+            .count();
+
+        match self.format {
+            OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Toon => {
+                self.data(&serde_json::json!({
+                    "suite": suite,
+                    "checks": checks,
+                    "errors": errors,
+                    "warnings": warnings,
+                    "diagnostics": diagnostics,
+                }))
+            }
+            OutputFormat::GithubAnnotations => {
+                for d in diagnostics {
+                    println!("{}", github_annotation(suite, d));
                 }
             }
+            OutputFormat::Junit => print!("{}", junit_report(suite, checks, diagnostics)),
+            OutputFormat::Text
+            | OutputFormat::JsonGraph
+            | OutputFormat::Gexf
+            | OutputFormat::Gfm
+            | OutputFormat::Markdown => {
+                for d in diagnostics {
+                    let location = match (&d.file, d.line) {
+                        (Some(file), Some(line)) => format!(" ({}:{})", file, line),
+                        (Some(file), None) => format!(" ({})", file),
+                        _ => String::new(),
+                    };
+                    println!(
+                        "{}[{}]: {}{}",
+                        d.severity.as_str(),
+                        d.check,
+                        d.message,
+                        location
+                    );
+                }
+                if !diagnostics.is_empty() {
+                    println!();
+                }
+                println!(
+                    "{}: {} check(s), {} error(s), {} warning(s)",
+                    suite,
+                    checks.len(),
+                    errors,
+                    warnings
+                );
+            }
         }
     }
 
     /// Prints a table row (text only, ignored in JSON mode)
     pub fn row(&self, columns: &[&str]) {
-        if self.format == OutputFormat::Text {
+        if !self.is_json() {
             println!("{}", columns.join("\t"));
         }
     }
 
     /// Prints a blank line (text only)
     pub fn blank(&self) {
-        if self.format == OutputFormat::Text {
+        if !self.is_json() {
             println!();
         }
     }
 
-    /// Returns true if using JSON format
+    /// Returns the selected output format
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    /// Returns true if commands should print structured data: JSON, or
+    /// `--plain`, which renders the same data as linear text
     pub fn is_json(&self) -> bool {
-        self.format == OutputFormat::Json
+        self.format == OutputFormat::Json || self.plain
+    }
+
+    /// Returns true in `--plain` mode
+    pub fn is_plain(&self) -> bool {
+        self.plain
+    }
+
+    /// Returns true if using a structured format: JSON, YAML, TOML or TOON
+    pub fn is_structured(&self) -> bool {
+        matches!(
+            self.format,
+            OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Toml | OutputFormat::Toon
+        )
     }
 
     /// Returns true if using text format
@@ -100,19 +458,255 @@ impl Output {
         self.verbose
     }
 
-    /// Prints a verbose debug message (only when --verbose is set)
+    /// Logs an info-level diagnostic (shown with --verbose or --trace)
     pub fn verbose(&self, message: &str) {
-        if self.verbose {
-            eprintln!("[verbose] {}", message);
-        }
+        tracing::info!("{}", message);
     }
 
-    /// Prints a verbose debug message with context (only when --verbose is set)
+    /// Logs an info-level diagnostic tagged with a context, shown as
+    /// `[verbose:context]`
     pub fn verbose_ctx(&self, context: &str, message: &str) {
-        if self.verbose {
-            eprintln!("[verbose:{}] {}", context, message);
+        tracing::info!(context, "{}", message);
+    }
+}
+
+/// Renders data as TOML by way of its JSON form.
+///
+/// TOML has no null and needs a table at the top: nulls are left out, and
+/// anything but an object is wrapped as `items` (arrays) or `value`.
+pub(super) fn to_toml<T: Serialize + ?Sized>(data: &T) -> Result<String, toml::ser::Error> {
+    use serde_json::Value;
+
+    fn without_nulls(value: Value) -> Value {
+        match value {
+            Value::Array(items) => Value::Array(
+                items
+                    .into_iter()
+                    .filter(|v| !v.is_null())
+                    .map(without_nulls)
+                    .collect(),
+            ),
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .filter(|(_, v)| !v.is_null())
+                    .map(|(k, v)| (k, without_nulls(v)))
+                    .collect(),
+            ),
+            other => other,
         }
     }
+
+    let value = serde_json::to_value(data).map_err(serde::ser::Error::custom)?;
+    let value = match without_nulls(value) {
+        Value::Object(map) => Value::Object(map),
+        Value::Array(items) => serde_json::json!({ "items": items }),
+        Value::Null => serde_json::json!({}),
+        other => serde_json::json!({ "value": other }),
+    };
+    toml::to_string_pretty(&value)
+}
+
+/// Renders data as linear text: one `key: value` line per field, nested
+/// fields as `parent.key`, and each record of a list introduced by an
+/// `Item 2 of 5` line. Nulls are left out.
+pub(super) fn plain_text(value: &serde_json::Value) -> String {
+    let mut lines = Vec::new();
+    plain_lines(value, "", &mut lines);
+    if lines.is_empty() {
+        lines.push("Nothing to show".to_string());
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn plain_lines(value: &serde_json::Value, path: &str, lines: &mut Vec<String>) {
+    use serde_json::Value;
+
+    let label = |path: &str| {
+        if path.is_empty() {
+            "value".to_string()
+        } else {
+            path.to_string()
+        }
+    };
+    match value {
+        Value::Null => {}
+        Value::Object(map) => {
+            for (key, v) in map {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                plain_lines(v, &child, lines);
+            }
+        }
+        Value::Array(items) if items.iter().all(|v| !v.is_object() && !v.is_array()) => {
+            let scalars: Vec<String> = items.iter().filter_map(plain_scalar).collect();
+            let text = if scalars.is_empty() {
+                "none".to_string()
+            } else {
+                scalars.join(", ")
+            };
+            lines.push(format!("{}: {}", label(path), text));
+        }
+        Value::Array(items) => {
+            let count = format!(
+                "{} {}",
+                items.len(),
+                if items.len() == 1 { "item" } else { "items" }
+            );
+            lines.push(if path.is_empty() {
+                count
+            } else {
+                format!("{}: {}", path, count)
+            });
+            for (i, item) in items.iter().enumerate() {
+                let mut heading = format!("Item {} of {}", i + 1, items.len());
+                if !path.is_empty() {
+                    heading = format!("{} {}", path, heading.to_lowercase());
+                }
+                lines.push(heading);
+                plain_lines(item, "", lines);
+            }
+        }
+        scalar => {
+            if let Some(text) = plain_scalar(scalar) {
+                lines.push(format!("{}: {}", label(path), text));
+            }
+        }
+    }
+}
+
+fn plain_scalar(value: &serde_json::Value) -> Option<String> {
+    use serde_json::Value;
+
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Bool(true) => Some("yes".to_string()),
+        Value::Bool(false) => Some("no".to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Formats a diagnostic as a GitHub Actions workflow command
+fn github_annotation(suite: &str, d: &Diagnostic) -> String {
+    let mut props = vec![format!(
+        "title={}",
+        escape_annotation_property(&format!("shape {}: {}", suite, d.check))
+    )];
+    if let Some(file) = &d.file {
+        props.push(format!("file={}", escape_annotation_property(file)));
+    }
+    if let Some(line) = d.line {
+        props.push(format!("line={}", line));
+    }
+
+    format!(
+        "::{} {}::{}",
+        d.severity.as_str(),
+        props.join(","),
+        escape_annotation_data(&d.message)
+    )
+}
+
+fn escape_annotation_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_annotation_property(s: &str) -> String {
+    escape_annotation_data(s)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+/// Renders diagnostics as a JUnit XML report with one test case per check
+fn junit_report(suite: &str, checks: &[&str], diagnostics: &[Diagnostic]) -> String {
+    let failed = checks
+        .iter()
+        .filter(|c| {
+            diagnostics
+                .iter()
+                .any(|d| d.check == **c && d.severity == Severity::Error)
+        })
+        .count();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"shape\" tests=\"{}\" failures=\"{}\">\n",
+        checks.len(),
+        failed
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+        xml_escape(suite),
+        checks.len(),
+        failed
+    ));
+
+    for check in checks {
+        let findings: Vec<_> = diagnostics.iter().filter(|d| d.check == *check).collect();
+        let errors: Vec<_> = findings
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .collect();
+        let others: Vec<_> = findings
+            .iter()
+            .filter(|d| d.severity != Severity::Error)
+            .collect();
+
+        xml.push_str(&format!(
+            "    <testcase classname=\"{}\" name=\"{}\"",
+            xml_escape(suite),
+            xml_escape(check)
+        ));
+        if findings.is_empty() {
+            xml.push_str("/>\n");
+            continue;
+        }
+        xml.push_str(">\n");
+
+        if !errors.is_empty() {
+            let body: Vec<_> = errors.iter().map(|d| junit_line(d)).collect();
+            xml.push_str(&format!(
+                "      <failure message=\"{} error(s)\">{}</failure>\n",
+                errors.len(),
+                xml_escape(&body.join("\n"))
+            ));
+        }
+        if !others.is_empty() {
+            let body: Vec<_> = others.iter().map(|d| junit_line(d)).collect();
+            xml.push_str(&format!(
+                "      <system-out>{}</system-out>\n",
+                xml_escape(&body.join("\n"))
+            ));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn junit_line(d: &Diagnostic) -> String {
+    match (&d.file, d.line) {
+        (Some(file), Some(line)) => {
+            format!("{}: {} ({}:{})", d.severity.as_str(), d.message, file, line)
+        }
+        (Some(file), None) => format!("{}: {} ({})", d.severity.as_str(), d.message, file),
+        _ => format!("{}: {}", d.severity.as_str(), d.message),
+    }
+}
+
+pub(super) fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
 }
 
 /// Helper trait for types that can be displayed as text
@@ -120,3 +714,73 @@ impl Output {
 pub trait TextDisplay {
     fn display_text(&self, output: &Output);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn projection_selects_fields_on_records() {
+        let projection = Projection::new(strings(&["id", "title"]), vec![]);
+        let value = json!([{"id": "t-1", "title": "A", "history": [{"id": "x"}]}]);
+
+        assert_eq!(
+            projection.apply(value),
+            json!([{"id": "t-1", "title": "A"}])
+        );
+    }
+
+    #[test]
+    fn projection_walks_wrappers() {
+        let projection = Projection::new(vec![], strings(&["notes"]));
+        let value = json!({
+            "count": 1,
+            "tasks": [{"id": "t-1", "notes": [], "status": "todo"}],
+        });
+
+        assert_eq!(
+            projection.apply(value),
+            json!({"count": 1, "tasks": [{"id": "t-1", "status": "todo"}]})
+        );
+    }
+
+    #[test]
+    fn toml_drops_nulls_and_wraps_non_tables() {
+        let value = json!({
+            "context": {"max_chars": 2000, "prefer": null},
+            "name": "app",
+        });
+        let toml = to_toml(&value).unwrap();
+        assert!(toml.contains("name = \"app\""));
+        assert!(toml.contains("[context]\nmax_chars = 2000"));
+        assert!(!toml.contains("prefer"));
+
+        assert_eq!(
+            to_toml(&json!(["a", "b"])).unwrap(),
+            "items = [\n    \"a\",\n    \"b\",\n]\n"
+        );
+    }
+
+    #[test]
+    fn plain_text_is_linear() {
+        let value = json!([
+            {"id": "t-1", "title": "A", "done": false, "labels": ["ui", "bug"], "meta": {"p": 1}},
+            {"id": "t-2", "title": "B", "blocked": null, "deps": []},
+        ]);
+        assert_eq!(
+            plain_text(&value),
+            "2 items\nItem 1 of 2\ndone: no\nid: t-1\nlabels: ui, bug\nmeta.p: 1\ntitle: A\n\
+             Item 2 of 2\ndeps: none\nid: t-2\ntitle: B\n"
+        );
+        assert_eq!(
+            plain_text(&json!({"tasks": [{"id": "t-1"}]})),
+            "tasks: 1 item\ntasks item 1 of 1\nid: t-1\n"
+        );
+        assert_eq!(plain_text(&json!({})), "Nothing to show\n");
+    }
+}
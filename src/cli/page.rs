@@ -0,0 +1,198 @@
+//! Pagination for list-style commands
+//!
+//! Commands flatten [`PageArgs`] to accept `--limit`, `--offset` and
+//! `--page-token`. In-memory results are sliced with [`PageArgs::paginate`];
+//! cache-backed queries push `LIMIT`/`OFFSET` into SQL using
+//! [`PageArgs::sql_limit`] and finish with [`PageArgs::page`].
+//!
+//! When any paging flag is given, JSON output becomes
+//! `{"items": [...], "next_page_token": "..."}` so callers can continue.
+
+use anyhow::Result;
+use clap::Args;
+use serde_json::Value;
+
+use super::error::{CliError, ErrorCode};
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct PageArgs {
+    /// Maximum number of results
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Number of results to skip
+    #[arg(long, default_value = "0")]
+    pub offset: usize,
+
+    /// Continue from a previous JSON response's next_page_token
+    #[arg(long, conflicts_with = "offset")]
+    pub page_token: Option<String>,
+}
+
+/// One page of results
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+
+    /// Offset of the first item
+    pub offset: usize,
+
+    /// Offset of the next page, if there are more results
+    pub next_offset: Option<usize>,
+
+    /// Whether paging was requested
+    paged: bool,
+}
+
+impl PageArgs {
+    /// Effective offset, from `--page-token` or `--offset`
+    pub fn start(&self) -> Result<usize> {
+        match &self.page_token {
+            Some(token) => decode_token(token),
+            None => Ok(self.offset),
+        }
+    }
+
+    fn is_paged(&self) -> bool {
+        self.limit.is_some() || self.offset > 0 || self.page_token.is_some()
+    }
+
+    /// Row limit for a SQL query: one more than requested, to detect
+    /// whether another page exists
+    pub fn sql_limit(&self) -> Option<usize> {
+        self.limit.map(|l| l + 1)
+    }
+
+    /// Slices an in-memory result set
+    pub fn paginate<T>(&self, items: Vec<T>) -> Result<Page<T>> {
+        let start = self.start()?;
+        let items: Vec<T> = match self.sql_limit() {
+            Some(limit) => items.into_iter().skip(start).take(limit).collect(),
+            None => items.into_iter().skip(start).collect(),
+        };
+        self.page(items)
+    }
+
+    /// Builds a page from rows already offset by the query and limited to
+    /// [`sql_limit`](Self::sql_limit)
+    pub fn page<T>(&self, mut items: Vec<T>) -> Result<Page<T>> {
+        let offset = self.start()?;
+        let next_offset = match self.limit {
+            Some(limit) if items.len() > limit => {
+                items.truncate(limit);
+                Some(offset + limit)
+            }
+            _ => None,
+        };
+
+        Ok(Page {
+            items,
+            offset,
+            next_offset,
+            paged: self.is_paged(),
+        })
+    }
+}
+
+impl<T> Page<T> {
+    /// Whether paging flags were given
+    pub fn is_paged(&self) -> bool {
+        self.paged
+    }
+
+    /// Token for the next page, if any
+    pub fn next_token(&self) -> Option<String> {
+        self.next_offset.map(encode_token)
+    }
+
+    /// Wraps JSON items with the next page token when paging was requested
+    pub fn json(&self, items: Vec<Value>) -> Value {
+        if self.is_paged() {
+            serde_json::json!({
+                "items": items,
+                "next_page_token": self.next_token(),
+            })
+        } else {
+            Value::Array(items)
+        }
+    }
+
+    /// Prints a hint for fetching the next page in text mode
+    pub fn print_hint(&self) {
+        if let Some(next) = self.next_offset {
+            println!();
+            println!(
+                "Showing {}-{}. More results: --offset {}",
+                self.offset + 1,
+                self.offset + self.items.len(),
+                next
+            );
+        }
+    }
+}
+
+fn encode_token(offset: usize) -> String {
+    format!("o{:x}", offset)
+}
+
+fn decode_token(token: &str) -> Result<usize> {
+    token
+        .strip_prefix('o')
+        .and_then(|hex| usize::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| {
+            CliError::new(
+                ErrorCode::Validation,
+                format!("Invalid page token: {}", token),
+            )
+            .into()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(limit: Option<usize>, offset: usize) -> PageArgs {
+        PageArgs {
+            limit,
+            offset,
+            page_token: None,
+        }
+    }
+
+    #[test]
+    fn paginate_slices_and_reports_next() {
+        let page = args(Some(2), 1).paginate(vec![1, 2, 3, 4]).unwrap();
+        assert_eq!(page.items, vec![2, 3]);
+        assert_eq!(page.next_offset, Some(3));
+
+        let last = args(Some(2), 3).paginate(vec![1, 2, 3, 4]).unwrap();
+        assert_eq!(last.items, vec![4]);
+        assert_eq!(last.next_offset, None);
+    }
+
+    #[test]
+    fn page_token_round_trips() {
+        let page = args(Some(2), 0).paginate(vec![1, 2, 3]).unwrap();
+        let token = page.next_token().unwrap();
+
+        let next = PageArgs {
+            limit: Some(2),
+            offset: 0,
+            page_token: Some(token),
+        };
+        assert_eq!(next.paginate(vec![1, 2, 3]).unwrap().items, vec![3]);
+
+        let bad = PageArgs {
+            page_token: Some("nope".to_string()),
+            ..PageArgs::default()
+        };
+        assert!(bad.start().is_err());
+    }
+
+    #[test]
+    fn unpaged_json_stays_an_array() {
+        let page = PageArgs::default().paginate(vec![1]).unwrap();
+        assert!(page.json(vec![Value::from(1)]).is_array());
+    }
+}
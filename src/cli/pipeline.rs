@@ -0,0 +1,259 @@
+//! Task pipeline commands
+//!
+//! Pipelines are reusable chains of tasks defined in `.shape/config.toml`:
+//!
+//! ```toml
+//! [pipelines.release-checklist]
+//! steps = [
+//!     { title = "Design" },
+//!     { title = "Implement" },
+//!     { title = "Test" },
+//!     { title = "Review", after = ["Implement", "Test"] },
+//! ]
+//! ```
+//!
+//! Each step depends on the previous one unless `after` lists other steps
+//! (or is empty).
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use super::agent;
+use super::error::{CliError, ErrorCode};
+use super::freeze::ensure_brief_mutable;
+use super::output::Output;
+use crate::domain::{BriefId, DependencyGraph, Task, TaskId};
+use crate::storage::{PipelineConfig, Project};
+
+#[derive(Subcommand)]
+pub enum PipelineCommands {
+    /// Create all tasks of a pipeline under a brief
+    ///
+    /// Example:
+    ///   shape task pipeline add b-1234567 release-checklist
+    Add {
+        /// Brief ID
+        brief: String,
+
+        /// Pipeline name from config
+        name: String,
+    },
+
+    /// List configured pipelines
+    List,
+}
+
+pub fn run(cmd: PipelineCommands, output: &Output) -> Result<()> {
+    match cmd {
+        PipelineCommands::Add { brief, name } => add_pipeline(output, &brief, &name),
+        PipelineCommands::List => list_pipelines(output),
+    }
+}
+
+fn add_pipeline(output: &Output, brief_str: &str, name: &str) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.task_store();
+
+    let pipeline = project
+        .config()
+        .project
+        .pipelines
+        .get(name)
+        .ok_or_else(|| {
+            CliError::new(
+                ErrorCode::NotFound,
+                format!(
+                    "Pipeline not found: {}. Define it under [pipelines.{}] in .shape/config.toml",
+                    name, name
+                ),
+            )
+        })?;
+
+    let brief_id = project.resolve_brief_id(brief_str)?;
+    let brief = project
+        .brief_store()
+        .read(&brief_id)?
+        .ok_or_else(|| CliError::not_found("Brief", &brief_id))?;
+    ensure_brief_mutable(&project, &brief)?;
+
+    let mut tasks = store.read_all()?;
+
+    let max_seq = tasks
+        .values()
+        .filter(|t| t.brief_id().as_ref() == Some(&brief_id) && t.id.depth() == 1)
+        .map(|t| *t.id.segments().first().unwrap_or(&0))
+        .max()
+        .unwrap_or(0);
+
+    let mut created = instantiate(&brief_id, max_seq + 1, pipeline)?;
+
+    // Validate the combined graph before writing anything
+    DependencyGraph::from_tasks(tasks.values().chain(created.iter()))?;
+
+    let spawned_from = agent::save_new_tasks(&project, &mut tasks, &mut created)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "pipeline": name,
+            "brief": brief_id.to_string(),
+            "spawned_from": spawned_from.as_ref().map(|id| id.to_string()),
+            "tasks": created.iter().map(|t| {
+                serde_json::json!({
+                    "id": t.id.to_string(),
+                    "title": t.title,
+                    "depends_on": t.depends_on.blocking_task_ids().map(|d| d.to_string()).collect::<Vec<_>>(),
+                })
+            }).collect::<Vec<_>>(),
+        }));
+    } else {
+        output.success(&format!(
+            "Created {} tasks from pipeline '{}' in {}:",
+            created.len(),
+            name,
+            brief_id
+        ));
+        if let Some(parent) = &spawned_from {
+            println!("  Provenance: spawned from claimed task {}", parent);
+        }
+        for task in &created {
+            let deps: Vec<_> = task
+                .depends_on
+                .blocking_task_ids()
+                .map(|d| d.to_string())
+                .collect();
+            if deps.is_empty() {
+                println!("  {} - {}", task.id, task.title);
+            } else {
+                println!("  {} - {} (after {})", task.id, task.title, deps.join(", "));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn list_pipelines(output: &Output) -> Result<()> {
+    let project = Project::open_current()?;
+    let pipelines = &project.config().project.pipelines;
+
+    if output.is_json() {
+        output.data(pipelines);
+    } else if pipelines.is_empty() {
+        println!("No pipelines configured. Add one under [pipelines.<name>] in .shape/config.toml");
+    } else {
+        for (name, pipeline) in pipelines {
+            let steps: Vec<_> = pipeline.steps.iter().map(|s| s.title.as_str()).collect();
+            match &pipeline.description {
+                Some(desc) => println!("{} - {}", name, desc),
+                None => println!("{}", name),
+            }
+            println!("  {}", steps.join(" -> "));
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds the tasks for a pipeline, numbering them from `first_seq`
+fn instantiate(brief_id: &BriefId, first_seq: u32, pipeline: &PipelineConfig) -> Result<Vec<Task>> {
+    if pipeline.steps.is_empty() {
+        anyhow::bail!("Pipeline has no steps");
+    }
+
+    let mut ids_by_title: HashMap<&str, TaskId> = HashMap::new();
+    let mut created: Vec<Task> = Vec::new();
+
+    for (index, step) in pipeline.steps.iter().enumerate() {
+        let id = TaskId::new(brief_id, first_seq + index as u32);
+        let mut task = Task::new(id.clone(), &step.title);
+
+        if let Some(description) = &step.description {
+            task.set_description(description);
+        }
+
+        match &step.after {
+            None => {
+                if let Some(previous) = created.last() {
+                    task.add_dependency(previous.id.clone());
+                }
+            }
+            Some(after) => {
+                for title in after {
+                    let dep = ids_by_title.get(title.as_str()).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Pipeline step '{}' depends on unknown or later step '{}'",
+                            step.title,
+                            title
+                        )
+                    })?;
+                    task.add_dependency(dep.clone());
+                }
+            }
+        }
+
+        if ids_by_title.insert(&step.title, id).is_some() {
+            anyhow::bail!("Duplicate pipeline step title: {}", step.title);
+        }
+        created.push(task);
+    }
+
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::PipelineStep;
+
+    fn step(title: &str, after: Option<Vec<&str>>) -> PipelineStep {
+        PipelineStep {
+            title: title.to_string(),
+            after: after.map(|a| a.into_iter().map(String::from).collect()),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn instantiate_chains_steps_by_default() {
+        let brief: BriefId = "b-1234567".parse().unwrap();
+        let pipeline = PipelineConfig {
+            description: None,
+            steps: vec![step("Design", None), step("Build", None)],
+        };
+
+        let tasks = instantiate(&brief, 3, &pipeline).unwrap();
+        assert_eq!(tasks[0].id.to_string(), "b-1234567.3");
+        assert!(tasks[0].depends_on.is_empty());
+        assert!(tasks[1].depends_on.contains_blocking(&tasks[0].id));
+    }
+
+    #[test]
+    fn instantiate_uses_explicit_after() {
+        let brief: BriefId = "b-1234567".parse().unwrap();
+        let pipeline = PipelineConfig {
+            description: None,
+            steps: vec![
+                step("Design", None),
+                step("Docs", Some(vec![])),
+                step("Review", Some(vec!["Design", "Docs"])),
+            ],
+        };
+
+        let tasks = instantiate(&brief, 1, &pipeline).unwrap();
+        assert!(tasks[1].depends_on.is_empty());
+        assert_eq!(tasks[2].depends_on.len(), 2);
+    }
+
+    #[test]
+    fn instantiate_rejects_forward_references() {
+        let brief: BriefId = "b-1234567".parse().unwrap();
+        let pipeline = PipelineConfig {
+            description: None,
+            steps: vec![step("Design", Some(vec!["Review"])), step("Review", None)],
+        };
+
+        assert!(instantiate(&brief, 1, &pipeline).is_err());
+    }
+}
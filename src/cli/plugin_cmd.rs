@@ -4,7 +4,7 @@ use anyhow::Result;
 use clap::Subcommand;
 
 use super::output::Output;
-use crate::plugin::PluginLoader;
+use crate::plugin::{PluginError, PluginLoader};
 use crate::storage::Project;
 
 #[derive(Subcommand)]
@@ -78,7 +78,7 @@ fn test_plugin(output: &Output, name: &str) -> Result<()> {
 
     // First check if plugin exists
     if loader.get(name).is_none() {
-        anyhow::bail!("Plugin not found: {}", name);
+        return Err(PluginError::NotFound(name.to_string()).into());
     }
 
     // Try to get manifest
@@ -0,0 +1,224 @@
+//! `--profile`: where a command's time went
+//!
+//! A tracing layer times the same spans `--trace` reports (project open,
+//! store reads and writes, cache rebuilds, graph building, plugin calls,
+//! output serialization) and adds them up by where they sit in the span
+//! tree. After the command, [`Profile::report`] prints the breakdown on
+//! stderr, with time outside any span listed as unattributed.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use super::output::Output;
+
+/// Time spent in one span path, over all its calls
+#[derive(Debug, Clone, PartialEq)]
+struct SpanTiming {
+    /// Span names from the outermost span down, e.g. `cache.rebuild`,
+    /// `tasks.read_all`
+    path: Vec<&'static str>,
+    calls: usize,
+    total: Duration,
+}
+
+/// Span timings collected while a command runs
+#[derive(Debug, Clone, Default)]
+pub(super) struct Profile(Arc<Mutex<Vec<SpanTiming>>>);
+
+/// Kept on each open span
+struct Open {
+    index: usize,
+    path: Vec<&'static str>,
+    started: Instant,
+}
+
+impl Profile {
+    /// Layer recording into this profile
+    pub(super) fn layer<S>(&self) -> impl Layer<S>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        ProfileLayer(self.clone()).with_filter(EnvFilter::new("shape_cli=debug,shape_core=debug"))
+    }
+
+    /// Timings ordered as a tree: each span right after its parent, siblings
+    /// in the order they first ran
+    fn timings(&self) -> Vec<SpanTiming> {
+        let timings = self.0.lock().expect("profile lock").clone();
+        let position = |path: &[&str]| timings.iter().position(|t| t.path == path);
+        let mut keyed: Vec<(Vec<Option<usize>>, SpanTiming)> = timings
+            .iter()
+            .map(|t| {
+                let key = (1..=t.path.len()).map(|n| position(&t.path[..n])).collect();
+                (key, t.clone())
+            })
+            .collect();
+        keyed.sort_by(|a, b| a.0.cmp(&b.0));
+        keyed.into_iter().map(|(_, t)| t).collect()
+    }
+
+    /// Prints the breakdown of a command that took `elapsed` on stderr
+    pub(super) fn report(&self, elapsed: Duration, output: &Output) {
+        let timings = self.timings();
+        let attributed: Duration = timings
+            .iter()
+            .filter(|t| t.path.len() == 1)
+            .map(|t| t.total)
+            .sum();
+        let unattributed = elapsed.saturating_sub(attributed);
+
+        if output.is_json() {
+            let spans: Vec<_> = timings
+                .iter()
+                .map(|t| {
+                    serde_json::json!({
+                        "span": t.path.join(" > "),
+                        "calls": t.calls,
+                        "ms": millis(t.total),
+                    })
+                })
+                .collect();
+            let profile = serde_json::json!({
+                "profile": {
+                    "total_ms": millis(elapsed),
+                    "unattributed_ms": millis(unattributed),
+                    "spans": spans,
+                }
+            });
+            eprintln!("{}", profile);
+            return;
+        }
+
+        let width = timings
+            .iter()
+            .map(|t| (t.path.len() - 1) * 2 + t.path.last().map_or(0, |n| n.len()))
+            .max()
+            .unwrap_or(0)
+            .max("(unattributed)".len());
+        let percent = |d: Duration| {
+            if elapsed.is_zero() {
+                0.0
+            } else {
+                d.as_secs_f64() / elapsed.as_secs_f64() * 100.0
+            }
+        };
+        eprintln!();
+        eprintln!("Profile: {:.1}ms total", millis(elapsed));
+        for t in &timings {
+            let name = format!(
+                "{}{}",
+                "  ".repeat(t.path.len() - 1),
+                t.path.last().copied().unwrap_or_default()
+            );
+            eprintln!(
+                "  {:<width$}  {:>4}x  {:>9.1}ms  {:>5.1}%",
+                name,
+                t.calls,
+                millis(t.total),
+                percent(t.total)
+            );
+        }
+        eprintln!(
+            "  {:<width$}         {:>9.1}ms  {:>5.1}%",
+            "(unattributed)",
+            millis(unattributed),
+            percent(unattributed)
+        );
+    }
+}
+
+fn millis(d: Duration) -> f64 {
+    (d.as_secs_f64() * 10_000.0).round() / 10.0
+}
+
+struct ProfileLayer(Profile);
+
+impl<S> Layer<S> for ProfileLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut path = span
+            .parent()
+            .and_then(|parent| parent.extensions().get::<Open>().map(|o| o.path.clone()))
+            .unwrap_or_default();
+        path.push(span.name());
+
+        let mut timings = (self.0).0.lock().expect("profile lock");
+        let index = match timings.iter().position(|t| t.path == path) {
+            Some(index) => index,
+            None => {
+                timings.push(SpanTiming {
+                    path: path.clone(),
+                    calls: 0,
+                    total: Duration::ZERO,
+                });
+                timings.len() - 1
+            }
+        };
+        span.extensions_mut().insert(Open {
+            index,
+            path,
+            started: Instant::now(),
+        });
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        if let Some(open) = extensions.get::<Open>() {
+            let mut timings = (self.0).0.lock().expect("profile lock");
+            let timing = &mut timings[open.index];
+            timing.calls += 1;
+            timing.total += open.started.elapsed();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn times_spans_by_path_in_tree_order() {
+        let profile = Profile::default();
+        let subscriber = tracing_subscriber::registry().with(profile.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..2 {
+                let _outer = tracing::debug_span!("cache.rebuild").entered();
+                let _inner = tracing::debug_span!("tasks.read_all").entered();
+            }
+            let _read = tracing::debug_span!("tasks.read_all").entered();
+            drop(_read);
+            let _outer = tracing::debug_span!("cache.rebuild").entered();
+            let _graph = tracing::debug_span!("graph.build").entered();
+        });
+
+        let paths: Vec<(String, usize)> = profile
+            .timings()
+            .into_iter()
+            .map(|t| (t.path.join(" > "), t.calls))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("cache.rebuild".to_string(), 3),
+                ("cache.rebuild > tasks.read_all".to_string(), 2),
+                ("cache.rebuild > graph.build".to_string(), 1),
+                ("tasks.read_all".to_string(), 1),
+            ]
+        );
+    }
+}
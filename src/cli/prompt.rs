@@ -0,0 +1,213 @@
+//! Prompt command - render an agent prompt preset with project context
+//!
+//! Presets are markdown files under `.shape/prompts/`. `coder` and
+//! `reviewer` are built in and can be overridden by a file of the same name.
+//! Placeholders are filled from the selected task or brief:
+//!
+//! - `{{context}}` - the context export for the selection, as JSON
+//! - `{{task.id}}`, `{{task.title}}`, `{{task.description}}`
+//! - `{{brief.id}}`, `{{brief.title}}`, `{{brief.body}}`
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use super::context::{self, ContextOptions};
+use super::error::{CliError, ErrorCode};
+use super::output::Output;
+use crate::storage::{fill_placeholders, placeholders, Project};
+
+/// Presets available without a file in `.shape/prompts/`
+const BUILTIN: &[(&str, &str)] = &[
+    (
+        "coder",
+        "You are implementing task {{task.id}}: {{task.title}}
+
+{{task.description}}
+
+The task belongs to brief {{brief.id}} ({{brief.title}}). The project context
+below lists the task, the tasks blocking it, its siblings and the brief.
+
+```json
+{{context}}
+```
+
+Implement the task. Keep changes focused on it, add tests where the project
+has them, and summarize what you changed when you are done.
+",
+    ),
+    (
+        "reviewer",
+        "You are reviewing the work done for task {{task.id}}: {{task.title}}
+
+{{task.description}}
+
+The task belongs to brief {{brief.id}} ({{brief.title}}). The project context
+below lists the task, the tasks blocking it, its siblings and the brief.
+
+```json
+{{context}}
+```
+
+Check that the change does what the task and brief ask, point out bugs and
+missing tests, and say whether it is ready to merge.
+",
+    ),
+];
+
+/// What the prompt is rendered for
+pub struct Selection<'a> {
+    pub task: Option<&'a str>,
+    pub brief: Option<&'a str>,
+    pub compact: bool,
+    pub days: u32,
+}
+
+/// Lists presets: files in `.shape/prompts/` plus the built-ins
+pub fn list(output: &Output) -> Result<()> {
+    let project = Project::open_current()?;
+    let files = project.prompt_store().list()?;
+
+    let mut presets: Vec<(String, &str)> =
+        files.iter().map(|name| (name.clone(), "file")).collect();
+    for (name, _) in BUILTIN {
+        if !files.iter().any(|f| f == name) {
+            presets.push((name.to_string(), "builtin"));
+        }
+    }
+    presets.sort();
+
+    if output.is_json() {
+        let presets: Vec<_> = presets
+            .iter()
+            .map(|(name, source)| serde_json::json!({ "name": name, "source": source }))
+            .collect();
+        output.data(&serde_json::json!({ "presets": presets }));
+    } else {
+        for (name, source) in &presets {
+            println!("{:<20} {}", name, source);
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders a preset for the selection
+pub fn run(output: &Output, name: &str, selection: &Selection) -> Result<()> {
+    let project = Project::open_current()?;
+
+    let template = match project.prompt_store().read(name)? {
+        Some(text) => text,
+        None => BUILTIN
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, text)| text.to_string())
+            .ok_or_else(|| {
+                CliError::new(
+                    ErrorCode::NotFound,
+                    format!(
+                        "Prompt preset not found: {}. Add .shape/prompts/{}.md or see `shape prompt --list`",
+                        name, name
+                    ),
+                )
+            })?,
+    };
+
+    let mut values = HashMap::new();
+    let mut task_id = None;
+    let mut brief_id = None;
+
+    if let Some(id) = selection.task {
+        let id = project.resolve_task_id(id)?;
+        let task = project
+            .task_store()
+            .read_all()?
+            .remove(&id)
+            .ok_or_else(|| CliError::not_found("Task", &id))?;
+        values.insert("task.id".to_string(), id.to_string());
+        values.insert("task.title".to_string(), task.title.clone());
+        values.insert(
+            "task.description".to_string(),
+            task.description.clone().unwrap_or_default(),
+        );
+        brief_id = task.brief_id();
+        task_id = Some(id);
+    } else if let Some(id) = selection.brief {
+        brief_id = Some(project.resolve_brief_id(id)?);
+    }
+
+    if let Some(id) = &brief_id {
+        let brief = project
+            .brief_store()
+            .read(id)?
+            .ok_or_else(|| CliError::not_found("Brief", id))?;
+        values.insert("brief.id".to_string(), id.to_string());
+        values.insert("brief.title".to_string(), brief.title);
+        values.insert("brief.body".to_string(), brief.body.trim().to_string());
+    }
+
+    let used = placeholders(&template);
+    if used.contains("context") {
+        let opts = ContextOptions {
+            compact: selection.compact,
+            brief: brief_id
+                .as_ref()
+                .filter(|_| task_id.is_none())
+                .map(|id| id.to_string()),
+            for_task: task_id.as_ref().map(|id| id.to_string()),
+            days: selection.days,
+            ..Default::default()
+        };
+        let context = context::generate(output, &project, &opts)?;
+        values.insert(
+            "context".to_string(),
+            serde_json::to_string_pretty(&context)?,
+        );
+    }
+
+    let missing: Vec<_> = used.iter().filter(|p| !values.contains_key(*p)).collect();
+    if !missing.is_empty() {
+        let mut available: Vec<_> = values.keys().map(String::as_str).collect();
+        if !available.contains(&"context") {
+            available.push("context");
+        }
+        available.sort();
+        let mut available = available.join(", ");
+        if task_id.is_none() && brief_id.is_none() {
+            available.push_str("; select a task with --task or a brief with --brief");
+        }
+        let missing: Vec<_> = missing.iter().map(|p| format!("{{{{{}}}}}", p)).collect();
+        return Err(CliError::new(
+            ErrorCode::Validation,
+            format!(
+                "Prompt '{}' uses {} (available: {})",
+                name,
+                missing.join(", "),
+                available
+            ),
+        )
+        .into());
+    }
+
+    // Empty fields (a task without description) would leave blank runs
+    let mut prompt = fill_placeholders(&template, &values);
+    while prompt.contains("\n\n\n") {
+        prompt = prompt.replace("\n\n\n", "\n\n");
+    }
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "preset": name,
+            "task": task_id.map(|id| id.to_string()),
+            "brief": brief_id.map(|id| id.to_string()),
+            "prompt": prompt,
+        }));
+    } else {
+        print!("{}", prompt);
+        if !prompt.ends_with('\n') {
+            println!();
+        }
+    }
+
+    Ok(())
+}
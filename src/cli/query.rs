@@ -1,14 +1,32 @@
-//! Query commands (ready, blocked, status)
+//! Query commands (ready, blocked, overdue, status)
 //!
-//! These commands use SQLite cache for fast queries.
+//! These commands use SQLite cache for fast queries, except `overdue`: due
+//! dates aren't cached, so it reads the task store.
+//!
+//! `ready --watch` keeps running and prints an event each time the ready set
+//! changes, watching `tasks.jsonl` with the same debounced watcher as the
+//! daemon, so agents can subscribe instead of polling.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::mpsc;
+use std::time::Duration;
 
 use anyhow::Result;
+use chrono::Utc;
+use notify::RecursiveMode;
+use notify_debouncer_mini::new_debouncer;
 
+use super::duration::{format_duration, parse_duration};
 use super::output::Output;
-use crate::storage::Project;
+use super::page::PageArgs;
+use crate::domain::{effective_priorities, parse_external_ref, EffectivePriority};
+use crate::storage::{CachedTask, Project};
+
+/// How long the watcher waits for writes to settle before re-reading
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
 
 /// Show tasks ready to work on
-pub fn ready(output: &Output, brief_filter: Option<&str>) -> Result<()> {
+pub fn ready(output: &Output, brief_filter: Option<&str>, paging: &PageArgs) -> Result<()> {
     let project = Project::open_current()?;
     output.verbose_ctx(
         "ready",
@@ -19,43 +37,194 @@ pub fn ready(output: &Output, brief_filter: Option<&str>) -> Result<()> {
     let cache = project.get_or_rebuild_cache()?;
     output.verbose_ctx("ready", "Using SQLite cache for query");
 
+    // With priority inheritance, tasks are ordered by effective priority,
+    // so the whole set is read and paged in memory
+    let inheritance = project.config().project.agent.priority_inheritance;
+    let (limit, offset) = if inheritance {
+        (None, 0)
+    } else {
+        (paging.sql_limit(), paging.start()?)
+    };
+
     // Get ready tasks
-    let ready_tasks = if let Some(brief_str) = brief_filter {
+    let mut ready_tasks = if let Some(brief_str) = brief_filter {
         output.verbose_ctx("ready", &format!("Filtering by brief: {}", brief_str));
-        cache.ready_tasks_for_brief(brief_str)?
+        let brief_str = resolve_brief_filter(&project, brief_str)?;
+        cache.ready_tasks_for_brief(&brief_str, limit, offset)?
     } else {
-        cache.ready_tasks_detailed()?
+        cache.ready_tasks_detailed(limit, offset)?
     };
 
-    output.verbose_ctx("ready", &format!("Found {} ready tasks", ready_tasks.len()));
+    let mut priorities: HashMap<String, EffectivePriority> = HashMap::new();
+    let page = if inheritance {
+        let tasks = project.task_store().read_all()?;
+        priorities = effective_priorities(&tasks)
+            .into_iter()
+            .map(|(id, p)| (id.to_string(), p))
+            .collect();
+        // Stable sort keeps ID order within a priority
+        ready_tasks.sort_by_key(|t| std::cmp::Reverse(priorities.get(&t.id).map(|p| p.priority)));
+        paging.paginate(ready_tasks)?
+    } else {
+        paging.page(ready_tasks)?
+    };
+
+    output.verbose_ctx("ready", &format!("Found {} ready tasks", page.items.len()));
 
     if output.is_json() {
-        let items: Vec<_> = ready_tasks
+        let items: Vec<_> = page
+            .items
             .iter()
             .map(|t| {
-                serde_json::json!({
+                let mut item = serde_json::json!({
                     "id": t.id,
                     "title": t.title,
                     "standalone": t.is_standalone(),
                     "brief_id": t.brief_id,
-                })
+                });
+                if let Some(p) = priorities.get(&t.id) {
+                    item["priority"] = serde_json::json!(p.priority.as_str());
+                    item["inherited_from"] =
+                        serde_json::json!(p.inherited_from.as_ref().map(|id| id.to_string()));
+                }
+                item
             })
             .collect();
-        output.data(&items);
-    } else if ready_tasks.is_empty() {
+        output.data(&page.json(items));
+    } else if page.items.is_empty() {
         println!("No tasks ready to work on.");
     } else {
-        println!("Ready tasks ({}):", ready_tasks.len());
+        println!("Ready tasks ({}):", page.items.len());
         println!("{:<20} TITLE", "ID");
         println!("{}", "-".repeat(60));
-        for task in ready_tasks {
-            println!("{:<20} {}", task.id, task.title);
+        for task in &page.items {
+            let inherited = priorities
+                .get(&task.id)
+                .and_then(|p| Some((p.priority, p.inherited_from.as_ref()?)))
+                .map(|(priority, from)| {
+                    format!(" ({} priority, blocks {})", priority.as_str(), from)
+                })
+                .unwrap_or_default();
+            println!("{:<20} {}{}", task.id, task.title, inherited);
         }
+        page.print_hint();
     }
 
     Ok(())
 }
 
+/// Prints the ready set, then an event whenever tasks enter or leave it.
+/// Runs until interrupted.
+pub fn ready_watch(output: &Output, brief_filter: Option<&str>) -> Result<()> {
+    let project = Project::open_current()?;
+    let brief = brief_filter
+        .map(|b| resolve_brief_filter(&project, b))
+        .transpose()?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut debouncer = new_debouncer(WATCH_DEBOUNCE, tx)?;
+    // Writes replace tasks.jsonl by renaming over it, so watch its directory
+    debouncer
+        .watcher()
+        .watch(&project.shape_dir(), RecursiveMode::NonRecursive)?;
+
+    let mut current = ready_set(&project, brief.as_deref())?;
+    emit_ready_event(
+        output,
+        "snapshot",
+        &current,
+        &current.keys().collect::<Vec<_>>(),
+        &[],
+    );
+
+    for events in rx {
+        let touched = match events {
+            Ok(events) => events
+                .iter()
+                .any(|e| e.path.file_name().is_some_and(|n| n == "tasks.jsonl")),
+            Err(e) => {
+                eprintln!("Warning: {:#}", e);
+                continue;
+            }
+        };
+        if !touched {
+            continue;
+        }
+        let next = match ready_set(&project, brief.as_deref()) {
+            Ok(next) => next,
+            Err(e) => {
+                eprintln!("Warning: {:#}", e);
+                continue;
+            }
+        };
+        let added: Vec<&String> = next
+            .keys()
+            .filter(|id| !current.contains_key(*id))
+            .collect();
+        let removed: Vec<&String> = current
+            .keys()
+            .filter(|id| !next.contains_key(*id))
+            .collect();
+        if !added.is_empty() || !removed.is_empty() {
+            emit_ready_event(output, "changed", &next, &added, &removed);
+        }
+        current = next;
+    }
+    Ok(())
+}
+
+/// Ready tasks by ID, as `shape ready` lists them
+fn ready_set(project: &Project, brief: Option<&str>) -> Result<BTreeMap<String, CachedTask>> {
+    // The cache spots changes by mtime, which can miss two writes within one
+    // tick, so the watcher always rebuilds it
+    let mut cache = project.get_or_rebuild_cache()?;
+    cache.rebuild(
+        &project.task_store().read_all()?,
+        &project.brief_store().read_all()?,
+    )?;
+    let tasks = match brief {
+        Some(brief) => cache.ready_tasks_for_brief(brief, None, 0)?,
+        None => cache.ready_tasks_detailed(None, 0)?,
+    };
+    Ok(tasks.into_iter().map(|t| (t.id.clone(), t)).collect())
+}
+
+/// One line per event: a JSON object with `--format json`, otherwise the
+/// tasks that came and went
+fn emit_ready_event(
+    output: &Output,
+    event: &str,
+    ready: &BTreeMap<String, CachedTask>,
+    added: &[&String],
+    removed: &[&String],
+) {
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "event": event,
+            "at": Utc::now(),
+            "ready": ready.values().map(|t| serde_json::json!({
+                "id": t.id,
+                "title": t.title,
+                "standalone": t.is_standalone(),
+                "brief_id": t.brief_id,
+            })).collect::<Vec<_>>(),
+            "added": added,
+            "removed": removed,
+        }));
+        return;
+    }
+    let time = Utc::now().format("%H:%M:%S");
+    if event == "snapshot" {
+        println!("[{}] {} ready", time, ready.len());
+    }
+    for id in added {
+        println!("[{}] + {}  {}", time, id, ready[*id].title);
+    }
+    for id in removed {
+        println!("[{}] - {}", time, id);
+    }
+}
+
 /// Show blocked tasks
 pub fn blocked(output: &Output, brief_filter: Option<&str>) -> Result<()> {
     let project = Project::open_current()?;
@@ -71,7 +240,8 @@ pub fn blocked(output: &Output, brief_filter: Option<&str>) -> Result<()> {
     // Get blocked tasks
     let blocked_tasks = if let Some(brief_str) = brief_filter {
         output.verbose_ctx("blocked", &format!("Filtering by brief: {}", brief_str));
-        cache.blocked_tasks_for_brief(brief_str)?
+        let brief_str = resolve_brief_filter(&project, brief_str)?;
+        cache.blocked_tasks_for_brief(&brief_str)?
     } else {
         cache.blocked_tasks_detailed()?
     };
@@ -107,6 +277,61 @@ pub fn blocked(output: &Output, brief_filter: Option<&str>) -> Result<()> {
     Ok(())
 }
 
+/// Show open tasks past their due date, or due within `within`
+pub fn overdue(output: &Output, brief_filter: Option<&str>, within: Option<&str>) -> Result<()> {
+    let project = Project::open_current()?;
+    let now = Utc::now();
+    let horizon = now + within.map(parse_duration).transpose()?.unwrap_or_default();
+    let brief_id = brief_filter
+        .map(|b| project.resolve_brief_id(b))
+        .transpose()?;
+
+    let tasks = project.task_store().read_all()?;
+    let mut due: Vec<_> = tasks
+        .values()
+        .filter(|t| !t.status.is_complete())
+        .filter(|t| brief_id.is_none() || t.brief_id() == brief_id)
+        .filter_map(|t| Some((t, t.due_at.filter(|d| *d <= horizon)?)))
+        .collect();
+    due.sort_by(|(a, a_due), (b, b_due)| a_due.cmp(b_due).then_with(|| a.id.cmp(&b.id)));
+
+    if output.is_json() {
+        let items: Vec<_> = due
+            .iter()
+            .map(|(t, due_at)| {
+                serde_json::json!({
+                    "id": t.id.to_string(),
+                    "title": t.title,
+                    "brief_id": t.brief_id().map(|b| b.to_string()),
+                    "status": t.status,
+                    "due_at": due_at,
+                    "overdue": t.is_overdue(now),
+                })
+            })
+            .collect();
+        output.data(&items);
+    } else if due.is_empty() {
+        println!("No overdue tasks.");
+    } else {
+        match within {
+            Some(within) => println!("Tasks overdue or due within {} ({}):", within, due.len()),
+            None => println!("Overdue tasks ({}):", due.len()),
+        }
+        println!("{:<20} {:<12} TITLE", "ID", "DUE");
+        println!("{}", "-".repeat(60));
+        for (task, due_at) in &due {
+            let when = if task.is_overdue(now) {
+                format!("{} ago", format_duration(now - *due_at))
+            } else {
+                format!("in {}", format_duration(*due_at - now))
+            };
+            println!("{:<20} {:<12} {}", task.id, when, task.title);
+        }
+    }
+
+    Ok(())
+}
+
 /// Show project status overview
 pub fn status(output: &Output) -> Result<()> {
     let project = Project::open_current()?;
@@ -201,3 +426,13 @@ pub fn status(output: &Output) -> Result<()> {
 
     Ok(())
 }
+
+/// Resolves an external ID (`github:456`) used as a brief filter; other
+/// values are passed through as-is
+fn resolve_brief_filter(project: &Project, brief_str: &str) -> Result<String> {
+    if parse_external_ref(brief_str).is_some() {
+        Ok(project.resolve_brief_id(brief_str)?.to_string())
+    } else {
+        Ok(brief_str.to_string())
+    }
+}
@@ -0,0 +1,447 @@
+//! Terminal rendering helpers for rich text output
+//!
+//! Styling is plain ANSI and only applied when stdout is a terminal and
+//! `NO_COLOR` is unset, so piped output stays greppable. Markdown support is
+//! deliberately small: headings, bullet and numbered lists, fenced code
+//! blocks and `**bold**` spans, with paragraphs re-wrapped to the terminal.
+//!
+//! [`Theme`] holds the status icons and label colors from `[display]`, shared
+//! by text output and the TUI.
+
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+
+use crate::domain::{BriefStatus, TaskStatus};
+use crate::storage::{DisplayConfig, IconMode};
+
+/// Default width when the terminal size is unknown
+const DEFAULT_WIDTH: usize = 80;
+
+/// Widest text is rendered, even on very wide terminals
+const MAX_WIDTH: usize = 100;
+
+/// ANSI color for badges and emphasis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Dim,
+}
+
+impl Color {
+    fn code(self, high_contrast: bool) -> &'static str {
+        match (self, high_contrast) {
+            (Color::Red, false) => "31",
+            (Color::Green, false) => "32",
+            (Color::Yellow, false) => "33",
+            (Color::Blue, false) => "34",
+            (Color::Magenta, false) => "35",
+            (Color::Cyan, false) => "36",
+            (Color::Dim, false) => "2",
+            // Bold bright colors, and plain bold instead of dim
+            (Color::Red, true) => "1;91",
+            (Color::Green, true) => "1;92",
+            (Color::Yellow, true) => "1;93",
+            (Color::Blue, true) => "1;94",
+            (Color::Magenta, true) => "1;95",
+            (Color::Cyan, true) => "1;96",
+            (Color::Dim, true) => "1",
+        }
+    }
+
+    /// Parses a color name from `[display] label_colors`
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "red" => Some(Color::Red),
+            "green" => Some(Color::Green),
+            "yellow" => Some(Color::Yellow),
+            "blue" => Some(Color::Blue),
+            "magenta" => Some(Color::Magenta),
+            "cyan" => Some(Color::Cyan),
+            "dim" | "gray" | "grey" => Some(Color::Dim),
+            _ => None,
+        }
+    }
+}
+
+/// Status icons and label colors from `[display]`, with the per-user
+/// `SHAPE_ICONS` and `SHAPE_HIGH_CONTRAST` overrides applied
+#[derive(Debug, Clone, Default)]
+pub struct Theme {
+    icons: IconMode,
+    pub high_contrast: bool,
+    status_icons: BTreeMap<String, String>,
+    label_colors: BTreeMap<String, Color>,
+}
+
+impl Theme {
+    pub fn new(config: &DisplayConfig) -> Self {
+        let icons = std::env::var("SHAPE_ICONS")
+            .ok()
+            .and_then(|v| serde_json::from_value(serde_json::json!(v.to_lowercase())).ok())
+            .unwrap_or(config.icons);
+        let high_contrast = config.high_contrast
+            || std::env::var("SHAPE_HIGH_CONTRAST").is_ok_and(|v| !v.is_empty() && v != "0");
+        Self {
+            icons,
+            high_contrast,
+            status_icons: config.status_icons.clone(),
+            label_colors: config
+                .label_colors
+                .iter()
+                .filter_map(|(label, color)| Some((label.clone(), Color::parse(color)?)))
+                .collect(),
+        }
+    }
+
+    /// Icon for a task status (empty with icons off)
+    pub fn task_icon(&self, status: TaskStatus) -> String {
+        let (key, [unicode, emoji, ascii]) = match status {
+            TaskStatus::Todo => ("todo", ["○", "⬜", "[ ]"]),
+            TaskStatus::InProgress => ("in_progress", ["◐", "🔄", "[~]"]),
+            TaskStatus::Done => ("done", ["✓", "✅", "[x]"]),
+        };
+        self.icon(key, unicode, emoji, ascii)
+    }
+
+    /// Icon for a todo task waiting on dependencies (empty with icons off)
+    pub fn blocked_icon(&self) -> String {
+        self.icon("blocked", "⊘", "⛔", "[B]")
+    }
+
+    /// Icon for a brief status (empty with icons off)
+    pub fn brief_icon(&self, status: BriefStatus) -> String {
+        let (key, [unicode, emoji, ascii]) = match status {
+            BriefStatus::Proposed => ("proposed", ["◇", "💡", "[?]"]),
+            BriefStatus::Betting => ("betting", ["◆", "🎲", "[$]"]),
+            BriefStatus::InProgress => ("in_progress", ["◐", "🚧", "[~]"]),
+            BriefStatus::Shipped => ("shipped", ["✓", "🚀", "[x]"]),
+            BriefStatus::Archived => ("archived", ["✗", "📦", "[-]"]),
+        };
+        self.icon(key, unicode, emoji, ascii)
+    }
+
+    /// Cell for an activity heatmap at intensity `level` (0 to 4). ASCII and
+    /// no-icon modes get plain characters.
+    pub fn shade(&self, level: usize) -> char {
+        let shades = match self.icons {
+            IconMode::Unicode | IconMode::Emoji => ['·', '░', '▒', '▓', '█'],
+            IconMode::Ascii | IconMode::None => ['.', '-', '+', '*', '#'],
+        };
+        shades[level.min(4)]
+    }
+
+    fn icon(&self, key: &str, unicode: &str, emoji: &str, ascii: &str) -> String {
+        let icon = match self.icons {
+            IconMode::None => return String::new(),
+            IconMode::Unicode => unicode,
+            IconMode::Emoji => emoji,
+            IconMode::Ascii => ascii,
+        };
+        self.status_icons
+            .get(key)
+            .map_or(icon, String::as_str)
+            .to_string()
+    }
+
+    /// Icon padded to [`Theme::lead_width`] before a list row, or nothing
+    /// with icons off
+    pub fn lead(&self, icon: &str) -> String {
+        if icon.is_empty() {
+            return String::new();
+        }
+        let width = match self.icons {
+            IconMode::Emoji => icon.chars().count().max(2),
+            _ => icon.chars().count(),
+        };
+        format!(
+            "{}{}",
+            icon,
+            " ".repeat(self.lead_width().saturating_sub(width).max(1))
+        )
+    }
+
+    /// Columns a lead takes, so headers line up with icon rows. Emoji are
+    /// two columns wide.
+    pub fn lead_width(&self) -> usize {
+        match self.icons {
+            IconMode::None => 0,
+            IconMode::Emoji => 3,
+            IconMode::Unicode | IconMode::Ascii => {
+                let widest = self
+                    .status_icons
+                    .values()
+                    .map(|i| i.chars().count())
+                    .max()
+                    .unwrap_or(0);
+                let base = if self.icons == IconMode::Ascii { 3 } else { 1 };
+                base.max(widest) + 1
+            }
+        }
+    }
+
+    /// Configured color of a label
+    pub fn label_color(&self, label: &str) -> Option<Color> {
+        self.label_colors.get(label).copied()
+    }
+}
+
+/// Renders styled text, or plain text when color is off
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    color: bool,
+    high_contrast: bool,
+    pub width: usize,
+}
+
+impl Style {
+    /// Style for stdout: colored on a terminal unless `NO_COLOR` is set
+    pub fn stdout() -> Self {
+        let tty = std::io::stdout().is_terminal();
+        let width = crossterm::terminal::size()
+            .ok()
+            .filter(|_| tty)
+            .map_or(DEFAULT_WIDTH, |(w, _)| w as usize);
+        Self {
+            color: tty && std::env::var_os("NO_COLOR").is_none(),
+            high_contrast: false,
+            width: width.min(MAX_WIDTH),
+        }
+    }
+
+    /// Applies the theme's high-contrast mode
+    pub fn with_theme(mut self, theme: &Theme) -> Self {
+        self.high_contrast = theme.high_contrast;
+        self
+    }
+
+    #[cfg(test)]
+    fn plain(width: usize) -> Self {
+        Self {
+            color: false,
+            high_contrast: false,
+            width,
+        }
+    }
+
+    pub fn paint(&self, text: &str, color: Color) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", color.code(self.high_contrast), text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    pub fn bold(&self, text: &str) -> String {
+        if self.color {
+            format!("\x1b[1m{}\x1b[0m", text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// `[label]` in the given color
+    pub fn badge(&self, label: &str, color: Color) -> String {
+        self.paint(&format!("[{}]", label), color)
+    }
+
+    /// Renders markdown as wrapped lines, each prefixed with `indent`
+    pub fn markdown(&self, text: &str, indent: &str) -> Vec<String> {
+        let width = self.width.saturating_sub(indent.len()).max(20);
+        let mut lines = Vec::new();
+        let mut paragraph = String::new();
+        let mut in_code = false;
+
+        let flush = |paragraph: &mut String, lines: &mut Vec<String>| {
+            if !paragraph.is_empty() {
+                for line in wrap(paragraph, width) {
+                    lines.push(format!("{}{}", indent, self.inline(&line)));
+                }
+                paragraph.clear();
+            }
+        };
+
+        for raw in text.lines() {
+            let trimmed = raw.trim_start();
+            if trimmed.starts_with("```") {
+                flush(&mut paragraph, &mut lines);
+                in_code = !in_code;
+                continue;
+            }
+            if in_code {
+                lines.push(format!("{}  {}", indent, self.paint(raw, Color::Dim)));
+                continue;
+            }
+            if trimmed.is_empty() {
+                flush(&mut paragraph, &mut lines);
+                if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                    lines.push(String::new());
+                }
+                continue;
+            }
+            if trimmed.starts_with('#') {
+                flush(&mut paragraph, &mut lines);
+                let heading = trimmed.trim_start_matches('#').trim();
+                lines.push(format!("{}{}", indent, self.bold(heading)));
+                continue;
+            }
+            if let Some((marker, rest)) = list_item(trimmed) {
+                flush(&mut paragraph, &mut lines);
+                let hang = " ".repeat(marker.chars().count() + 1);
+                let item_width = width.saturating_sub(hang.len()).max(10);
+                for (i, line) in wrap(rest, item_width).iter().enumerate() {
+                    let lead = if i == 0 {
+                        format!("{} ", marker)
+                    } else {
+                        hang.clone()
+                    };
+                    lines.push(format!("{}{}{}", indent, lead, self.inline(line)));
+                }
+                continue;
+            }
+            if !paragraph.is_empty() {
+                paragraph.push(' ');
+            }
+            paragraph.push_str(trimmed);
+        }
+        flush(&mut paragraph, &mut lines);
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        lines
+    }
+
+    /// Applies `**bold**` spans
+    fn inline(&self, line: &str) -> String {
+        let mut out = String::new();
+        for (i, part) in line.split("**").enumerate() {
+            if i % 2 == 1 {
+                out.push_str(&self.bold(part));
+            } else {
+                out.push_str(part);
+            }
+        }
+        out
+    }
+}
+
+/// Splits `- item`, `* item` and `1. item` into marker and text
+fn list_item(line: &str) -> Option<(&str, &str)> {
+    if let Some(rest) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return Some(("-", rest));
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 && line[digits..].starts_with(". ") {
+        return Some((&line[..=digits], &line[digits + 2..]));
+    }
+    None
+}
+
+/// Greedy word wrap; words longer than `width` get a line of their own
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_breaks_on_words() {
+        assert_eq!(
+            wrap("the quick brown fox jumps", 10),
+            vec!["the quick", "brown fox", "jumps"]
+        );
+        assert_eq!(
+            wrap("supercalifragilistic ok", 5),
+            vec!["supercalifragilistic", "ok"]
+        );
+        assert!(wrap("", 10).is_empty());
+    }
+
+    #[test]
+    fn markdown_wraps_paragraphs_and_lists() {
+        let style = Style::plain(24);
+        let text = "# Goal\n\nMake the export fast enough\nfor large projects.\n\n- first item that wraps around\n2. second\n\n```\nlet x =   1;\n```";
+        assert_eq!(
+            style.markdown(text, "  "),
+            vec![
+                "  Goal",
+                "",
+                "  Make the export fast",
+                "  enough for large",
+                "  projects.",
+                "",
+                "  - first item that",
+                "    wraps around",
+                "  2. second",
+                "",
+                "    let x =   1;",
+            ]
+        );
+    }
+
+    #[test]
+    fn theme_icons_follow_mode_and_overrides() {
+        let mut config = DisplayConfig::default();
+        let theme = Theme::new(&config);
+        assert_eq!(theme.task_icon(TaskStatus::Done), "✓");
+        assert_eq!(theme.lead_width(), 2);
+
+        config.icons = IconMode::Ascii;
+        config.status_icons.insert("done".into(), "DONE".into());
+        let theme = Theme::new(&config);
+        assert_eq!(theme.task_icon(TaskStatus::Todo), "[ ]");
+        assert_eq!(theme.blocked_icon(), "[B]");
+        assert_eq!(theme.brief_icon(BriefStatus::Shipped), "[x]");
+        assert_eq!(theme.task_icon(TaskStatus::Done), "DONE");
+        assert_eq!(theme.lead_width(), 5);
+        assert_eq!(theme.lead("[ ]"), "[ ]  ");
+
+        config.icons = IconMode::None;
+        let theme = Theme::new(&config);
+        assert_eq!(theme.task_icon(TaskStatus::Done), "");
+        assert_eq!(theme.lead(""), "");
+        assert_eq!(theme.lead_width(), 0);
+    }
+
+    #[test]
+    fn theme_label_colors_skip_unknown_names() {
+        let mut config = DisplayConfig::default();
+        config.label_colors.insert("bug".into(), "Red".into());
+        config.label_colors.insert("ui".into(), "chartreuse".into());
+        let theme = Theme::new(&config);
+        assert_eq!(theme.label_color("bug"), Some(Color::Red));
+        assert_eq!(theme.label_color("ui"), None);
+        assert_eq!(theme.label_color("docs"), None);
+    }
+
+    #[test]
+    fn high_contrast_avoids_dim() {
+        let style = Style {
+            color: true,
+            high_contrast: true,
+            width: 80,
+        };
+        assert_eq!(style.paint("x", Color::Dim), "\x1b[1mx\x1b[0m");
+        assert_eq!(style.paint("x", Color::Red), "\x1b[1;91mx\x1b[0m");
+    }
+}
@@ -0,0 +1,268 @@
+//! `shape task reopen`: take back a completed task
+//!
+//! Tasks that waited on the reopened one and are already done were finished
+//! on the assumption that it was. They are listed as stale and, when picked
+//! (by flag, or at a prompt on a terminal), reopened along with it, with the
+//! reopened task recorded as the cause. Everyone watching the task, its
+//! brief's owners and whoever claimed or changed it, is told through the
+//! `reopen` hook.
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use anyhow::Result;
+use serde_json::json;
+
+use super::agent::get_agent_name;
+use super::error::{CliError, ErrorCode};
+use super::freeze::ensure_task_mutable;
+use super::hooks;
+use super::output::Output;
+use crate::domain::{Task, TaskId};
+use crate::storage::Project;
+
+/// Which stale dependents to reopen
+pub(super) enum Dependents {
+    /// Ask on a terminal, otherwise none
+    Prompt,
+    All,
+    Only(Vec<String>),
+}
+
+pub(super) fn run(
+    output: &Output,
+    id_str: &str,
+    reason: &str,
+    dependents: Dependents,
+) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.task_store();
+    let agent = get_agent_name(&project, None);
+
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(CliError::new(ErrorCode::Validation, "A reopen needs a --reason").into());
+    }
+
+    let id = project.resolve_task_id(id_str)?;
+    let mut tasks = store.read_all()?;
+    let task = tasks
+        .get(&id)
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
+    if !task.status.is_complete() {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!("{} is not done; only completed tasks can be reopened", id),
+        )
+        .into());
+    }
+
+    let stale = stale_dependents(&tasks, &id);
+    let selected: Vec<TaskId> = match dependents {
+        Dependents::All => stale.clone(),
+        Dependents::Only(ids) => {
+            let mut selected = Vec::new();
+            for id_str in &ids {
+                let dep = project.resolve_task_id(id_str)?;
+                if !stale.contains(&dep) {
+                    return Err(CliError::new(
+                        ErrorCode::Validation,
+                        format!("{} is not a completed task downstream of {}", dep, id),
+                    )
+                    .into());
+                }
+                if !selected.contains(&dep) {
+                    selected.push(dep);
+                }
+            }
+            selected
+        }
+        Dependents::Prompt
+            if !stale.is_empty() && io::stdin().is_terminal() && !output.is_json() =>
+        {
+            prompt_dependents(&tasks, &stale)?
+        }
+        Dependents::Prompt => Vec::new(),
+    };
+    for dep in &selected {
+        ensure_task_mutable(&project, &tasks[dep])?;
+    }
+
+    tasks
+        .get_mut(&id)
+        .expect("task exists")
+        .reopen_because(reason, &agent, None);
+    let cascade_reason = format!("{} was reopened: {}", id, reason);
+    for dep in &selected {
+        tasks
+            .get_mut(dep)
+            .expect("dependent exists")
+            .reopen_because(&cascade_reason, &agent, Some(&id));
+    }
+    let changed: Vec<Task> = std::iter::once(&id)
+        .chain(&selected)
+        .map(|t| tasks[t].clone())
+        .collect();
+    store.update_batch(&changed)?;
+
+    let still_stale: Vec<TaskId> = stale
+        .into_iter()
+        .filter(|t| !selected.contains(t))
+        .collect();
+    let watchers = watchers(&project, &tasks[&id], &agent)?;
+    notify_watchers(&project, &tasks[&id], &agent, reason, &selected, &watchers);
+
+    if output.is_json() {
+        output.data(&json!({
+            "id": id.to_string(),
+            "status": tasks[&id].status,
+            "reason": reason,
+            "reopened": ids(&selected),
+            "stale": ids(&still_stale),
+            "watchers": watchers,
+        }));
+    } else {
+        output.success(&format!("Reopened task: {}", id));
+        for dep in &selected {
+            println!("  Also reopened {}  {}", dep, tasks[dep].title);
+        }
+        if !still_stale.is_empty() {
+            println!(
+                "  Still done, but completed after depending on {}: {}",
+                id,
+                ids(&still_stale).join(", ")
+            );
+        }
+        if !watchers.is_empty() {
+            println!("  Notified: {}", watchers.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+/// Completed tasks downstream of `id` through blocking dependencies, sorted.
+/// The walk stops at tasks that aren't done: their own dependents were not
+/// finished on the strength of this one.
+fn stale_dependents(tasks: &HashMap<TaskId, Task>, id: &TaskId) -> Vec<TaskId> {
+    let mut found = BTreeSet::new();
+    let mut frontier = vec![id.clone()];
+    while let Some(current) = frontier.pop() {
+        for task in tasks.values() {
+            if task.status.is_complete()
+                && task.depends_on.contains_blocking(&current)
+                && found.insert(task.id.clone())
+            {
+                frontier.push(task.id.clone());
+            }
+        }
+    }
+    found.into_iter().collect()
+}
+
+/// Brief owners, the claimant and anyone in the task's history, except `by`
+fn watchers(project: &Project, task: &Task, by: &str) -> Result<Vec<String>> {
+    let mut watchers = BTreeSet::new();
+    if let Some(brief_id) = task.brief_id() {
+        if let Some(brief) = project.brief_store().read(&brief_id)? {
+            watchers.extend(brief.owner.clone());
+            watchers.extend(brief.co_owners.iter().cloned());
+        }
+    }
+    watchers.extend(task.claimed_by.clone());
+    watchers.extend(task.history.iter().filter_map(|e| e.by.clone()));
+    watchers.remove(by);
+    Ok(watchers.into_iter().collect())
+}
+
+/// Fires the `reopen` hook. A failing hook is reported but doesn't undo the
+/// reopen.
+fn notify_watchers(
+    project: &Project,
+    task: &Task,
+    by: &str,
+    reason: &str,
+    reopened: &[TaskId],
+    watchers: &[String],
+) {
+    let payload = json!({
+        "watchers": watchers,
+        "by": by,
+        "task": {
+            "id": task.id.to_string(),
+            "title": task.title,
+        },
+        "reason": reason,
+        "reopened": ids(reopened),
+    });
+    if let Err(e) = hooks::fire(project, "reopen", &payload) {
+        eprintln!("Warning: {:#}", e);
+    }
+}
+
+fn prompt_dependents(tasks: &HashMap<TaskId, Task>, stale: &[TaskId]) -> Result<Vec<TaskId>> {
+    println!("These tasks were completed after depending on it:");
+    for (i, id) in stale.iter().enumerate() {
+        println!("  {}. {}  {}", i + 1, id, tasks[id].title);
+    }
+    print!("Reopen which? (numbers separated by commas, 'all', or Enter for none) ");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    let answer = answer.trim();
+    if answer.eq_ignore_ascii_case("all") {
+        return Ok(stale.to_vec());
+    }
+    let mut selected = Vec::new();
+    for part in answer.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.parse::<usize>() {
+            Ok(n) if (1..=stale.len()).contains(&n) => {
+                if !selected.contains(&stale[n - 1]) {
+                    selected.push(stale[n - 1].clone());
+                }
+            }
+            _ => {
+                return Err(CliError::new(
+                    ErrorCode::Validation,
+                    format!("Not a number from the list: {}", part),
+                )
+                .into())
+            }
+        }
+    }
+    Ok(selected)
+}
+
+fn ids(ids: &[TaskId]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::BriefId;
+    use chrono::Utc;
+
+    #[test]
+    fn stale_dependents_follow_done_chain() {
+        let brief = BriefId::new("Test", Utc::now());
+        let mut tasks = HashMap::new();
+        for seq in 1..=4 {
+            let id = TaskId::new(&brief, seq);
+            let mut task = Task::new(id.clone(), format!("Task {}", seq));
+            if seq > 1 {
+                task.add_dependency(TaskId::new(&brief, seq - 1));
+            }
+            // 3 is still open, so 4 didn't build on 1
+            if seq != 3 {
+                task.complete();
+            }
+            tasks.insert(id, task);
+        }
+
+        let stale = stale_dependents(&tasks, &TaskId::new(&brief, 1));
+        assert_eq!(stale, vec![TaskId::new(&brief, 2)]);
+    }
+}
@@ -0,0 +1,585 @@
+//! Reports for humans
+//!
+//! `shape report wip` lists in-progress work by how long it has been in
+//! progress, flagging tasks with no recent activity (notes, links or history)
+//! so silently abandoned work surfaces even when nothing blocks it.
+//!
+//! `shape report digest --weekly` summarizes the period as a short narrative:
+//! shipped briefs, progress of open ones, newly blocked tasks and upcoming
+//! milestones. `--deliver` hands it to the `digest` hook for mailing or
+//! posting.
+//!
+//! `shape report heatmap` draws a year of activity as a calendar grid, one
+//! cell per day shaded by how many history events (or, with `--completed`,
+//! task completions) fell on it, across live and archived tasks.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use clap::Subcommand;
+use serde_json::json;
+
+use super::brief::rollup;
+use super::duration::{format_duration, parse_duration};
+use super::error::{CliError, ErrorCode};
+use super::hooks;
+use super::output::Output;
+use super::render::{Color, Style, Theme};
+use crate::domain::{BriefStatus, HistoryEventType, Task, TaskId, TaskStatus};
+use crate::storage::Project;
+
+#[derive(Subcommand)]
+pub enum ReportCommands {
+    /// List in-progress tasks, oldest first, flagging stale ones
+    ///
+    /// Example:
+    ///   shape report wip --stale-after 2d
+    Wip {
+        /// Flag tasks without activity for this long (default: [reports] stale_wip_hours)
+        #[arg(long, value_name = "DURATION")]
+        stale_after: Option<String>,
+
+        /// Only list stale tasks
+        #[arg(long)]
+        stale: bool,
+    },
+
+    /// Summarize the period: shipped briefs, progress, new blocks, milestones
+    ///
+    /// Prints markdown (or JSON with --format json).
+    ///
+    /// Example:
+    ///   shape report digest --weekly --deliver
+    Digest {
+        /// Cover the last 7 days (the default)
+        #[arg(long, conflicts_with = "days")]
+        weekly: bool,
+
+        /// Cover the last N days
+        #[arg(long)]
+        days: Option<u32>,
+
+        /// Also hand the digest to the `digest` hook in [hooks]
+        #[arg(long)]
+        deliver: bool,
+    },
+
+    /// Show a calendar of daily activity for a year
+    ///
+    /// Counts history events per day (UTC), or only task completions with
+    /// --completed, across live and archived tasks.
+    ///
+    /// Example:
+    ///   shape report heatmap --year 2025 --agent alice
+    Heatmap {
+        /// Calendar year (default: this year)
+        #[arg(long)]
+        year: Option<i32>,
+
+        /// Only count events recorded by this agent
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Only count tasks under this brief
+        #[arg(long)]
+        brief: Option<String>,
+
+        /// Count completed tasks instead of all history events
+        #[arg(long)]
+        completed: bool,
+    },
+}
+
+pub fn run(cmd: ReportCommands, output: &Output) -> Result<()> {
+    match cmd {
+        ReportCommands::Wip { stale_after, stale } => wip(output, stale_after.as_deref(), stale),
+        ReportCommands::Digest {
+            weekly: _,
+            days,
+            deliver,
+        } => digest(output, days.unwrap_or(7), deliver),
+        ReportCommands::Heatmap {
+            year,
+            agent,
+            brief,
+            completed,
+        } => heatmap(output, year, agent.as_deref(), brief.as_deref(), completed),
+    }
+}
+
+fn wip(output: &Output, stale_after: Option<&str>, stale_only: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let threshold = match stale_after {
+        Some(s) => parse_duration(s)?,
+        None => Duration::hours(project.config().project.reports.stale_wip_hours as i64),
+    };
+
+    let tasks = project.task_store().read_all()?;
+    let now = Utc::now();
+    let is_stale = |t: &Task| now - t.last_activity() > threshold;
+
+    let mut wip: Vec<&Task> = tasks
+        .values()
+        .filter(|t| t.status.is_active())
+        .filter(|t| !stale_only || is_stale(t))
+        .collect();
+    wip.sort_by(|a, b| {
+        a.started_at()
+            .cmp(&b.started_at())
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    if output.is_json() {
+        let items: Vec<_> = wip
+            .iter()
+            .map(|t| {
+                serde_json::json!({
+                    "id": t.id.to_string(),
+                    "title": t.title,
+                    "claimed_by": t.claimed_by,
+                    "started_at": t.started_at(),
+                    "in_progress_hours": (now - t.started_at()).num_hours(),
+                    "last_activity": t.last_activity(),
+                    "stale": is_stale(t),
+                })
+            })
+            .collect();
+        output.data(&serde_json::json!({
+            "stale_after_hours": threshold.num_hours(),
+            "tasks": items,
+        }));
+        return Ok(());
+    }
+
+    if wip.is_empty() {
+        println!("No tasks in progress.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<20} {:>6} {:<14} {:>9}  TITLE",
+        "ID", "AGE", "CLAIMED BY", "ACTIVE"
+    );
+    println!("{}", "-".repeat(72));
+    for task in &wip {
+        let flag = if is_stale(task) { "  [stale]" } else { "" };
+        println!(
+            "{:<20} {:>6} {:<14} {:>9}  {}{}",
+            task.id,
+            format_duration(now - task.started_at()),
+            task.claimed_by.as_deref().unwrap_or("-"),
+            format!("{} ago", format_duration(now - task.last_activity())),
+            task.title,
+            flag
+        );
+    }
+
+    let stale = wip.iter().filter(|t| is_stale(t)).count();
+    if stale > 0 {
+        println!();
+        println!(
+            "{} task(s) without activity for over {}",
+            stale,
+            format_duration(threshold)
+        );
+    }
+    Ok(())
+}
+
+fn digest(output: &Output, days: u32, deliver: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let briefs = project.brief_store().read_all_meta()?;
+    let tasks = project.task_store().read_all()?;
+    let milestones = project.milestone_store().read_all()?;
+
+    let to = Utc::now();
+    let from = to - Duration::days(days as i64);
+    let in_period = |at: chrono::DateTime<Utc>| at > from && at <= to;
+
+    let mut shipped: Vec<_> = briefs
+        .values()
+        .filter(|b| b.status == BriefStatus::Shipped && in_period(b.updated_at))
+        .collect();
+    shipped.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut open: Vec<_> = briefs
+        .values()
+        .filter(|b| matches!(b.status, BriefStatus::Betting | BriefStatus::InProgress))
+        .collect();
+    open.sort_by(|a, b| a.id.cmp(&b.id));
+    let progress: Vec<_> = open
+        .iter()
+        .map(|b| {
+            let (done, total) = rollup(&briefs, &tasks, &b.id);
+            let done_this_period = tasks
+                .values()
+                .filter(|t| t.brief_id().as_ref() == Some(&b.id))
+                .filter(|t| t.completed_at.is_some_and(in_period))
+                .count();
+            json!({
+                "id": b.id.to_string(),
+                "title": b.title,
+                "done": done,
+                "total": total,
+                "percent": percent(done, total),
+                "done_this_period": done_this_period,
+            })
+        })
+        .collect();
+
+    let completed = tasks
+        .values()
+        .filter(|t| t.completed_at.is_some_and(in_period))
+        .count();
+
+    // Blocked by hand during the period, or created waiting on open dependencies
+    let statuses: HashMap<TaskId, TaskStatus> =
+        tasks.iter().map(|(id, t)| (id.clone(), t.status)).collect();
+    let mut blocked: Vec<&Task> = tasks
+        .values()
+        .filter(|t| !t.status.is_complete())
+        .filter(|t| match &t.blocked {
+            Some(info) => in_period(info.at),
+            None => in_period(t.created_at) && t.is_blocked(&statuses),
+        })
+        .collect();
+    blocked.sort_by(|a, b| a.id.cmp(&b.id));
+    let newly_blocked: Vec<_> = blocked
+        .iter()
+        .map(|t| {
+            json!({
+                "id": t.id.to_string(),
+                "title": t.title,
+                "reason": t.blocked.as_ref().map(|b| b.reason.as_str()),
+            })
+        })
+        .collect();
+
+    let today = to.date_naive();
+    let mut upcoming: Vec<_> = milestones
+        .iter()
+        .filter(|m| (0..=days as i64).contains(&m.days_until(today)))
+        .collect();
+    upcoming.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.id.cmp(&b.id)));
+    let upcoming: Vec<_> = upcoming
+        .iter()
+        .map(|m| {
+            json!({
+                "id": m.id,
+                "title": m.title,
+                "date": m.date,
+                "days_until": m.days_until(today),
+            })
+        })
+        .collect();
+
+    let digest = json!({
+        "period": {
+            "from": from.date_naive(),
+            "to": today,
+            "days": days,
+        },
+        "shipped": shipped
+            .iter()
+            .map(|b| json!({ "id": b.id.to_string(), "title": b.title }))
+            .collect::<Vec<_>>(),
+        "progress": progress,
+        "completed_tasks": completed,
+        "newly_blocked": newly_blocked,
+        "upcoming": upcoming,
+    });
+    let markdown = digest_markdown(&digest);
+
+    if deliver {
+        let payload = json!({ "event": "digest", "digest": digest, "markdown": markdown });
+        if !hooks::fire(&project, "digest", &payload)? {
+            return Err(CliError::new(
+                ErrorCode::Validation,
+                "No digest hook configured; add `digest = \"<command>\"` under [hooks] in .shape/config.toml",
+            )
+            .into());
+        }
+    }
+
+    if output.is_json() {
+        let mut data = digest;
+        if deliver {
+            data["delivered"] = json!(true);
+        }
+        output.data(&data);
+    } else {
+        print!("{}", markdown);
+        if deliver {
+            output.success("Delivered digest via the digest hook");
+        }
+    }
+    Ok(())
+}
+
+fn heatmap(
+    output: &Output,
+    year: Option<i32>,
+    agent: Option<&str>,
+    brief: Option<&str>,
+    completed_only: bool,
+) -> Result<()> {
+    let project = Project::open_current()?;
+    let year = year.unwrap_or_else(|| Utc::now().year());
+    let (Some(first), Some(last)) = (
+        NaiveDate::from_ymd_opt(year, 1, 1),
+        NaiveDate::from_ymd_opt(year, 12, 31),
+    ) else {
+        return Err(CliError::new(ErrorCode::Validation, format!("Invalid year: {}", year)).into());
+    };
+    let brief_id = brief.map(|b| project.resolve_brief_id(b)).transpose()?;
+
+    let mut tasks = project.task_store().read_all()?;
+    tasks.extend(project.archived_task_store().read_all()?);
+
+    let mut counts: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for task in tasks.values() {
+        if brief_id.is_some() && task.brief_id() != brief_id {
+            continue;
+        }
+        for event in &task.history {
+            let day = event.at.date_naive();
+            if day.year() != year
+                || (completed_only && event.event != HistoryEventType::Completed)
+                || agent.is_some_and(|a| event.by.as_deref() != Some(a))
+            {
+                continue;
+            }
+            *counts.entry(day).or_default() += 1;
+        }
+    }
+
+    let total: usize = counts.values().sum();
+    let max = counts.values().copied().max().unwrap_or(0);
+    let busiest = counts
+        .iter()
+        .find(|(_, &count)| count == max)
+        .map(|(&day, &count)| (day, count));
+    let (mut streak, mut longest_streak) = (0, 0);
+    for day in first.iter_days().take_while(|d| *d <= last) {
+        streak = if counts.contains_key(&day) {
+            streak + 1
+        } else {
+            0
+        };
+        longest_streak = longest_streak.max(streak);
+    }
+
+    if output.is_json() {
+        output.data(&json!({
+            "year": year,
+            "metric": if completed_only { "completed" } else { "events" },
+            "agent": agent,
+            "brief": brief_id.as_ref().map(|id| id.to_string()),
+            "total": total,
+            "active_days": counts.len(),
+            "max": max,
+            "busiest": busiest.map(|(day, count)| json!({ "date": day, "count": count })),
+            "longest_streak": longest_streak,
+            "days": counts
+                .iter()
+                .map(|(day, count)| json!({ "date": day, "count": count }))
+                .collect::<Vec<_>>(),
+        }));
+        return Ok(());
+    }
+
+    let theme = Theme::new(&project.config().project.display);
+    let style = Style::stdout().with_theme(&theme);
+    let mut scope = Vec::new();
+    if let Some(agent) = agent {
+        scope.push(format!("by {}", agent));
+    }
+    if let Some(id) = &brief_id {
+        scope.push(format!("on {}", id));
+    }
+    println!(
+        "{} {} in {}{}",
+        total,
+        if completed_only {
+            "task(s) completed"
+        } else {
+            "event(s)"
+        },
+        year,
+        if scope.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", scope.join(", "))
+        }
+    );
+    println!();
+
+    // Columns are Monday-to-Sunday weeks, the first holding January 1st
+    let start = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+    let weeks = (last - start).num_days() as usize / 7 + 1;
+
+    let mut months = vec![' '; weeks];
+    for month in 1..=12 {
+        let day = NaiveDate::from_ymd_opt(year, month, 1).expect("valid month start");
+        let column = (day - start).num_days() as usize / 7;
+        let name = day.format("%b").to_string();
+        // Skip a label that would run into the previous one
+        if column + name.len() <= weeks
+            && (column == 0 || months[column - 1] == ' ')
+            && months[column..column + name.len()]
+                .iter()
+                .all(|c| *c == ' ')
+        {
+            months[column..column + name.len()].copy_from_slice(&name.chars().collect::<Vec<_>>());
+        }
+    }
+    println!("    {}", months.iter().collect::<String>().trim_end());
+
+    let cell = |level: usize| {
+        let shade = theme.shade(level).to_string();
+        if level == 0 {
+            style.paint(&shade, Color::Dim)
+        } else {
+            style.paint(&shade, Color::Green)
+        }
+    };
+    for weekday in 0..7 {
+        let label = match weekday {
+            0 => "Mon",
+            2 => "Wed",
+            4 => "Fri",
+            _ => "",
+        };
+        let row: String = (0..weeks)
+            .map(|week| {
+                let day = start + Duration::days((week * 7 + weekday) as i64);
+                if day < first || day > last {
+                    " ".to_string()
+                } else {
+                    cell(heat_level(counts.get(&day).copied().unwrap_or(0), max))
+                }
+            })
+            .collect();
+        println!("{:<4}{}", label, row.trim_end());
+    }
+    println!(
+        "    Less {} More",
+        (0..=4).map(cell).collect::<Vec<_>>().join(" ")
+    );
+
+    if let Some((day, count)) = busiest {
+        println!();
+        println!(
+            "Busiest day: {} ({})  Active days: {}  Longest streak: {} day(s)",
+            day,
+            count,
+            counts.len(),
+            longest_streak
+        );
+    }
+    Ok(())
+}
+
+/// Shade of a day with `count` events, 0 (none) to 4 (the busiest days)
+fn heat_level(count: usize, max: usize) -> usize {
+    if count == 0 {
+        0
+    } else {
+        (count * 4).div_ceil(max).clamp(1, 4)
+    }
+}
+
+fn percent(done: usize, total: usize) -> usize {
+    (done * 100).checked_div(total).unwrap_or(0)
+}
+
+/// Renders a digest built by `digest` as markdown
+fn digest_markdown(digest: &serde_json::Value) -> String {
+    let str_of = |v: &serde_json::Value| v.as_str().unwrap_or_default().to_string();
+    let period = &digest["period"];
+    let heading = if period["days"] == 7 {
+        "Weekly digest".to_string()
+    } else {
+        format!("{}-day digest", period["days"])
+    };
+    let mut md = format!(
+        "# {}: {} to {}\n",
+        heading,
+        str_of(&period["from"]),
+        str_of(&period["to"])
+    );
+
+    let section = |md: &mut String,
+                   title: &str,
+                   items: &[serde_json::Value],
+                   empty: &str,
+                   line: &dyn Fn(&serde_json::Value) -> String| {
+        md.push_str(&format!("\n## {}\n\n", title));
+        if items.is_empty() {
+            md.push_str(&format!("{}\n", empty));
+        }
+        for item in items {
+            md.push_str(&format!("- {}\n", line(item)));
+        }
+    };
+    let list = |key: &str| digest[key].as_array().cloned().unwrap_or_default();
+
+    section(
+        &mut md,
+        "Shipped",
+        &list("shipped"),
+        "Nothing shipped.",
+        &|b| format!("{} ({})", str_of(&b["title"]), str_of(&b["id"])),
+    );
+    section(
+        &mut md,
+        "Progress",
+        &list("progress"),
+        "No briefs in progress.",
+        &|b| {
+            format!(
+                "{} ({}): {}% ({}/{} tasks, {} this period)",
+                str_of(&b["title"]),
+                str_of(&b["id"]),
+                b["percent"],
+                b["done"],
+                b["total"],
+                b["done_this_period"]
+            )
+        },
+    );
+    md.push_str(&format!(
+        "\n{} task(s) completed this period.\n",
+        digest["completed_tasks"]
+    ));
+    section(
+        &mut md,
+        "Newly blocked",
+        &list("newly_blocked"),
+        "Nothing newly blocked.",
+        &|t| match t["reason"].as_str() {
+            Some(reason) => format!("{} ({}): {}", str_of(&t["title"]), str_of(&t["id"]), reason),
+            None => format!(
+                "{} ({}): waiting on dependencies",
+                str_of(&t["title"]),
+                str_of(&t["id"])
+            ),
+        },
+    );
+    section(
+        &mut md,
+        "Upcoming",
+        &list("upcoming"),
+        "No milestones due.",
+        &|m| {
+            format!(
+                "{}: {} (in {} day(s))",
+                str_of(&m["date"]),
+                str_of(&m["title"]),
+                m["days_until"]
+            )
+        },
+    );
+    md
+}
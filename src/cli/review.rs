@@ -0,0 +1,183 @@
+//! Review commands
+//!
+//! `shape review request` asks someone to review a task; `approve` and
+//! `reject` record their verdict on the task. With `[reviews]
+//! require_approval = true`, tasks carrying the review label (default
+//! `needs-review`) can't be completed until approved.
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use super::agent::get_agent_name;
+use super::error::{CliError, ErrorCode};
+use super::freeze::ensure_task_mutable;
+use super::output::Output;
+use crate::domain::{ReviewVerdict, Task};
+use crate::storage::Project;
+
+#[derive(Subcommand)]
+pub enum ReviewCommands {
+    /// Ask someone to review a task
+    ///
+    /// Example:
+    ///   shape review request b-7f2a3b1.1 --from bob
+    Request {
+        /// Task ID
+        id: String,
+
+        /// Reviewer
+        #[arg(long)]
+        from: String,
+    },
+
+    /// Approve a task
+    ///
+    /// Example:
+    ///   shape review approve b-7f2a3b1.1 --by bob --comment "LGTM"
+    Approve {
+        /// Task ID
+        id: String,
+
+        /// Reviewer (defaults to $SHAPE_AGENT or $USER)
+        #[arg(long)]
+        by: Option<String>,
+
+        /// Review comment
+        #[arg(long)]
+        comment: Option<String>,
+    },
+
+    /// Reject a task
+    ///
+    /// Example:
+    ///   shape review reject b-7f2a3b1.1 --by bob --comment "Missing tests"
+    Reject {
+        /// Task ID
+        id: String,
+
+        /// Reviewer (defaults to $SHAPE_AGENT or $USER)
+        #[arg(long)]
+        by: Option<String>,
+
+        /// Review comment
+        #[arg(long)]
+        comment: Option<String>,
+    },
+}
+
+pub fn run(cmd: ReviewCommands, output: &Output) -> Result<()> {
+    match cmd {
+        ReviewCommands::Request { id, from } => request(output, &id, &from),
+        ReviewCommands::Approve { id, by, comment } => {
+            verdict(output, &id, by.as_deref(), ReviewVerdict::Approved, comment)
+        }
+        ReviewCommands::Reject { id, by, comment } => {
+            verdict(output, &id, by.as_deref(), ReviewVerdict::Rejected, comment)
+        }
+    }
+}
+
+fn request(output: &Output, id_str: &str, reviewer: &str) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.task_store();
+    let agent = get_agent_name(&project, None);
+
+    let id = project.resolve_task_id(id_str)?;
+    let mut tasks = store.read_all()?;
+    let task = tasks
+        .get_mut(&id)
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
+
+    if !task.request_review(reviewer, &agent) {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!("A review of {} from {} is already pending", id, reviewer),
+        )
+        .into());
+    }
+    store.update(task)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": task.id.to_string(),
+            "reviewer": reviewer,
+            "reviews": task.reviews,
+        }));
+    } else {
+        output.success(&format!(
+            "Requested review of {} from {}",
+            task.id, reviewer
+        ));
+    }
+
+    Ok(())
+}
+
+fn verdict(
+    output: &Output,
+    id_str: &str,
+    reviewer: Option<&str>,
+    verdict: ReviewVerdict,
+    comment: Option<String>,
+) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.task_store();
+    let reviewer = get_agent_name(&project, reviewer);
+
+    let id = project.resolve_task_id(id_str)?;
+    let mut tasks = store.read_all()?;
+    let task = tasks
+        .get_mut(&id)
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
+
+    task.record_review(&reviewer, verdict, comment);
+    store.update(task)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": task.id.to_string(),
+            "reviewer": reviewer,
+            "verdict": verdict,
+            "approved": task.is_approved(),
+            "reviews": task.reviews,
+        }));
+    } else {
+        let verb = match verdict {
+            ReviewVerdict::Approved => "Approved",
+            ReviewVerdict::Rejected => "Rejected",
+        };
+        output.success(&format!("{} {} (review by {})", verb, task.id, reviewer));
+    }
+
+    Ok(())
+}
+
+/// Fails if the task needs an approving review before it can be completed
+pub(crate) fn ensure_approved(project: &Project, task: &Task) -> Result<()> {
+    let config = &project.config().project.reviews;
+    if !config.require_approval || !task.has_label(&config.label) || task.is_approved() {
+        return Ok(());
+    }
+    let pending: Vec<&str> = task
+        .pending_reviews()
+        .map(|r| r.reviewer.as_str())
+        .collect();
+    let hint = if pending.is_empty() {
+        format!(
+            "request one with: shape review request {} --from <NAME>",
+            task.id
+        )
+    } else {
+        format!("waiting on {}", pending.join(", "))
+    };
+    Err(CliError::new(
+        ErrorCode::ReviewRequired,
+        format!(
+            "Task {} is labeled {} and needs an approving review ({})",
+            task.id, config.label, hint
+        ),
+    )
+    .into())
+}
@@ -0,0 +1,402 @@
+//! `shape self-update`: replace the running binary with the latest release
+//!
+//! Releases come from a feed: the GitHub "latest release" API, or a file
+//! holding the same JSON (`[update] feed_url` in the user config, or
+//! `SHAPE_UPDATE_FEED`). The archive for this platform is checked against
+//! the SHA-256 the release workflow publishes next to it, unpacked with
+//! `tar` and renamed over the current executable. Downloads go through
+//! `curl`, which every platform we release for ships.
+//!
+//! The checksum comes from the same feed as the archive, so it catches a
+//! corrupt or truncated download, not a compromised release: whoever can
+//! replace the archive can replace its `.sha256` too. Trust rests on the
+//! feed's HTTPS origin.
+//!
+//! `shape status` calls [`notice`]: once the last check is older than
+//! `[update] check_interval_hours`, it asks the feed again with a short
+//! timeout and mentions a newer version on stderr. The outcome is kept in
+//! the user cache directory, so the feed is asked at most once an interval.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use super::error::{CliError, ErrorCode};
+use super::output::Output;
+use crate::storage::Config;
+
+const DEFAULT_FEED: &str = "https://api.github.com/repos/shape-cli/shape/releases/latest";
+const CURRENT: &str = env!("CARGO_PKG_VERSION");
+
+/// Seconds allowed for the feed, the archive, and the passive check
+const FEED_TIMEOUT: u64 = 30;
+const DOWNLOAD_TIMEOUT: u64 = 300;
+const NOTICE_TIMEOUT: u64 = 3;
+
+pub(super) fn run(output: &Output, check: bool) -> Result<()> {
+    let config = Config::load()?;
+    let release = latest_release(&feed_url(&config), FEED_TIMEOUT)?;
+    let latest = release.version();
+    record_check(Some(latest));
+    let available = is_newer(latest, CURRENT);
+
+    if check || !available {
+        if output.is_json() {
+            output.data(&json!({
+                "current": CURRENT,
+                "latest": latest,
+                "update_available": available,
+                "updated": false,
+            }));
+        } else if available {
+            println!(
+                "shape {} is available (you have {}). Run 'shape self-update' to install it.",
+                latest, CURRENT
+            );
+        } else {
+            output.success(&format!("shape {} is up to date", CURRENT));
+        }
+        return Ok(());
+    }
+
+    let platform = platform().ok_or_else(|| {
+        update_error(format!(
+            "No release builds for {}-{}; build from source instead",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ))
+    })?;
+    let archive_name = archive_name(platform);
+    let archive = release.asset(&archive_name)?;
+    let checksum = release.asset(&format!("{}.sha256", archive_name))?;
+
+    output.verbose_ctx(
+        "self-update",
+        &format!("Downloading {}", archive.browser_download_url),
+    );
+    let bytes = fetch(&archive.browser_download_url, DOWNLOAD_TIMEOUT)?;
+    let sums = fetch(&checksum.browser_download_url, FEED_TIMEOUT)?;
+    let expected = String::from_utf8_lossy(&sums)
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let actual = sha256_hex(&bytes);
+    if expected != actual {
+        return Err(update_error(format!(
+            "Checksum mismatch for {}: expected {}, got {}. Nothing was installed.",
+            archive_name, expected, actual
+        ))
+        .into());
+    }
+
+    let exe = std::env::current_exe()
+        .and_then(fs::canonicalize)
+        .context("Failed to locate the running shape binary")?;
+    install(&exe, &bytes, &archive_name)?;
+
+    if output.is_json() {
+        output.data(&json!({
+            "current": CURRENT,
+            "latest": latest,
+            "update_available": true,
+            "updated": true,
+            "path": exe,
+        }));
+    } else {
+        output.success(&format!("Updated shape {} → {}", CURRENT, latest));
+        println!("  {}", exe.display());
+    }
+    Ok(())
+}
+
+/// The passive check behind `shape status`. It never fails the command: an
+/// unreachable feed is recorded like any other check and only retried once
+/// the interval has passed again.
+pub(super) fn notice(output: &Output) {
+    if std::env::var_os("SHAPE_NO_UPDATE_CHECK").is_some() {
+        return;
+    }
+    let Ok(config) = Config::load() else {
+        return;
+    };
+    let interval = config.global.update.check_interval_hours;
+    if interval == 0 {
+        return;
+    }
+
+    let latest = match read_check() {
+        Some(last) if Utc::now() - last.checked_at < chrono::Duration::hours(interval as i64) => {
+            last.latest
+        }
+        _ => {
+            let latest = match latest_release(&feed_url(&config), NOTICE_TIMEOUT) {
+                Ok(release) => Some(release.version().to_string()),
+                Err(e) => {
+                    output.verbose_ctx("self-update", &format!("Version check failed: {:#}", e));
+                    None
+                }
+            };
+            record_check(latest.as_deref());
+            latest
+        }
+    };
+    if let Some(latest) = latest.filter(|l| is_newer(l, CURRENT)) {
+        eprintln!(
+            "A new version of shape is available: {} (you have {}). Run 'shape self-update' to install it.",
+            latest, CURRENT
+        );
+    }
+}
+
+/// The parts of a GitHub release we use
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+impl Release {
+    fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    fn asset(&self, name: &str) -> Result<&Asset> {
+        self.assets.iter().find(|a| a.name == name).ok_or_else(|| {
+            update_error(format!(
+                "Release {} has no {}; nothing was installed",
+                self.tag_name, name
+            ))
+            .into()
+        })
+    }
+}
+
+fn feed_url(config: &Config) -> String {
+    std::env::var("SHAPE_UPDATE_FEED")
+        .ok()
+        .or_else(|| config.global.update.feed_url.clone())
+        .unwrap_or_else(|| DEFAULT_FEED.to_string())
+}
+
+fn latest_release(feed: &str, timeout: u64) -> Result<Release> {
+    let body = fetch(feed, timeout)?;
+    serde_json::from_slice(&body)
+        .map_err(|e| update_error(format!("Release feed {} is not valid: {}", feed, e)).into())
+}
+
+/// Reads a local path or `file://` URL directly and anything else through
+/// curl
+fn fetch(url: &str, timeout: u64) -> Result<Vec<u8>> {
+    let local = match url.strip_prefix("file://") {
+        Some(path) => Some(PathBuf::from(path)),
+        None if !url.contains("://") => Some(PathBuf::from(url)),
+        None => None,
+    };
+    if let Some(path) = local {
+        return fs::read(&path)
+            .map_err(|e| update_error(format!("Failed to read {}: {}", path.display(), e)).into());
+    }
+
+    let out = Command::new("curl")
+        .args(["-fsSL", "--max-time", &timeout.to_string()])
+        .args(["-H", "Accept: application/vnd.github+json"])
+        .args(["-A", &format!("shape/{}", CURRENT)])
+        .arg(url)
+        .output()
+        .map_err(|e| update_error(format!("Couldn't run curl: {}", e)))?;
+    if !out.status.success() {
+        return Err(update_error(format!(
+            "Download of {} failed: {}",
+            url,
+            String::from_utf8_lossy(&out.stderr).trim()
+        ))
+        .into());
+    }
+    Ok(out.stdout)
+}
+
+/// Unpacks the archive in a directory next to the executable, so the final
+/// rename stays on one filesystem, and swaps the new binary in
+fn install(exe: &Path, archive: &[u8], archive_name: &str) -> Result<()> {
+    let dir = exe
+        .parent()
+        .context("The shape binary has no parent directory")?;
+    let staging = dir.join(format!(".shape-update-{}", std::process::id()));
+    fs::create_dir_all(&staging).map_err(|e| {
+        update_error(format!(
+            "Can't write to {} ({}); rerun with permission to replace {}",
+            dir.display(),
+            e,
+            exe.display()
+        ))
+    })?;
+    let result = unpack_and_swap(exe, &staging, archive, archive_name);
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+fn unpack_and_swap(exe: &Path, staging: &Path, archive: &[u8], archive_name: &str) -> Result<()> {
+    let archive_path = staging.join(archive_name);
+    fs::write(&archive_path, archive)?;
+    let status = Command::new("tar")
+        .arg("-xf")
+        .arg(&archive_path)
+        .arg("-C")
+        .arg(staging)
+        .status()
+        .map_err(|e| update_error(format!("Couldn't run tar: {}", e)))?;
+    if !status.success() {
+        return Err(update_error(format!("Failed to unpack {}", archive_name)).into());
+    }
+
+    let binary = staging.join(if cfg!(windows) { "shape.exe" } else { "shape" });
+    if !binary.is_file() {
+        return Err(update_error(format!("{} holds no shape binary", archive_name)).into());
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&binary, fs::Permissions::from_mode(0o755))?;
+    }
+    // Windows won't replace a running executable, but it will rename one
+    #[cfg(windows)]
+    {
+        let old = exe.with_extension("exe.old");
+        let _ = fs::remove_file(&old);
+        fs::rename(exe, &old)?;
+    }
+    fs::rename(&binary, exe)
+        .map_err(|e| update_error(format!("Failed to replace {}: {}", exe.display(), e)).into())
+}
+
+/// Release asset name for this build, as in the release workflow matrix
+fn platform() -> Option<&'static str> {
+    let musl = cfg!(target_env = "musl");
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("macos", "x86_64") => Some("darwin-x64"),
+        ("macos", "aarch64") => Some("darwin-arm64"),
+        ("linux", "x86_64") if musl => Some("linux-x64-musl"),
+        ("linux", "aarch64") if musl => Some("linux-arm64-musl"),
+        ("linux", "x86_64") => Some("linux-x64"),
+        ("linux", "aarch64") => Some("linux-arm64"),
+        ("windows", "x86_64") => Some("windows-x64"),
+        _ => None,
+    }
+}
+
+fn archive_name(platform: &str) -> String {
+    if platform.starts_with("windows") {
+        format!("shape-{}.zip", platform)
+    } else {
+        format!("shape-{}.tar.gz", platform)
+    }
+}
+
+/// Whether `latest` is a later version than `current`. A pre-release sorts
+/// before the release it leads up to; anything unparseable is never newer.
+fn is_newer(latest: &str, current: &str) -> bool {
+    fn key(version: &str) -> Option<(Vec<u64>, bool)> {
+        let version = version.trim().trim_start_matches('v');
+        let (core, release) = match version.split_once('-') {
+            Some((core, _)) => (core, false),
+            None => (version, true),
+        };
+        let parts = core
+            .split('.')
+            .map(|p| p.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        Some((parts, release))
+    }
+    matches!((key(latest), key(current)), (Some(l), Some(c)) if l > c)
+}
+
+/// Outcome of the last check against the feed
+#[derive(Debug, Serialize, Deserialize)]
+struct LastCheck {
+    checked_at: DateTime<Utc>,
+    latest: Option<String>,
+}
+
+fn check_path() -> Option<PathBuf> {
+    ProjectDirs::from("dev", "shape", "shape-cli")
+        .map(|dirs| dirs.cache_dir().join("update-check.json"))
+}
+
+fn read_check() -> Option<LastCheck> {
+    let content = fs::read_to_string(check_path()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Best effort: without a writable cache the next status just checks again
+fn record_check(latest: Option<&str>) {
+    let Some(path) = check_path() else {
+        return;
+    };
+    let check = LastCheck {
+        checked_at: Utc::now(),
+        latest: latest.map(str::to_string),
+    };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Ok(content) = serde_json::to_string(&check) {
+        let _ = fs::write(path, content);
+    }
+}
+
+fn update_error(message: impl Into<String>) -> CliError {
+    CliError::new(ErrorCode::UpdateFailed, message)
+}
+
+/// Lowercase hex SHA-256, the form the `.sha256` files releases ship with
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_matches_known_digests() {
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // Two blocks once padded
+        assert_eq!(
+            sha256_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn newer_versions() {
+        assert!(is_newer("0.2.0", "0.1.0"));
+        assert!(is_newer("v1.0.0", "0.9.12"));
+        assert!(is_newer("0.10.0", "0.9.0"));
+        assert!(is_newer("0.2.0", "0.2.0-rc.1"));
+        assert!(!is_newer("0.2.0-rc.1", "0.2.0"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("nightly", "0.1.0"));
+    }
+}
@@ -0,0 +1,313 @@
+//! Serve command - long-running protocol server for editor integrations
+//!
+//! `shape serve --jsonrpc-stdio` speaks JSON-RPC 2.0 over stdin/stdout, one
+//! message per line. Methods:
+//!
+//! | Method | Params | Result |
+//! |--------|--------|--------|
+//! | `briefs.list` | | all briefs |
+//! | `briefs.get` | `{id}` | one brief |
+//! | `tasks.list` | `{brief?}` | all tasks, optionally for one brief |
+//! | `tasks.get` | `{id}` | one task |
+//! | `tasks.ready` | | ready tasks |
+//! | `apply` | an operation, e.g. `{op: "claim", id, agent}` | affected brief or task |
+//! | `watch` / `unwatch` | | `true` |
+//! | `shutdown` | | `null`, then the server exits |
+//!
+//! While watching, the server sends `changed` notifications with the
+//! project-relative paths that changed under `.shape/`.
+//!
+//! `shape serve --mcp` speaks the Model Context Protocol instead (see
+//! [`mcp`](super::mcp)), sharing the JSON-RPC plumbing below.
+
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::{json, Value};
+
+use super::daemon::should_ignore_path;
+use super::output::Output;
+use crate::api::{Operation, ShapeApi};
+use crate::domain::{BriefId, TaskId};
+
+/// Debounce window for change notifications
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+// JSON-RPC 2.0 error codes
+pub(super) const PARSE_ERROR: i64 = -32700;
+pub(super) const INVALID_REQUEST: i64 = -32600;
+pub(super) const METHOD_NOT_FOUND: i64 = -32601;
+pub(super) const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+/// Run the serve command
+pub fn run(output: &Output, jsonrpc_stdio: bool, mcp: bool) -> Result<()> {
+    // A hook fired by a write would otherwise print into the message stream
+    super::hooks::reserve_stdout();
+    if mcp {
+        return super::mcp::run(output);
+    }
+    if !jsonrpc_stdio {
+        anyhow::bail!("No transport selected. Use --jsonrpc-stdio or --mcp");
+    }
+
+    let api = ShapeApi::open_current()?;
+    output.verbose_ctx(
+        "serve",
+        &format!("JSON-RPC server on stdio for {}", api.root().display()),
+    );
+
+    Server::new(api).run_stdio()
+}
+
+/// A JSON-RPC error response
+pub(super) struct RpcError {
+    pub(super) code: i64,
+    pub(super) message: String,
+}
+
+impl RpcError {
+    pub(super) fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::new(SERVER_ERROR, format!("{:#}", e))
+    }
+}
+
+impl From<serde_json::Error> for RpcError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::new(INVALID_PARAMS, e.to_string())
+    }
+}
+
+struct Server {
+    api: ShapeApi,
+    out: Arc<Mutex<io::Stdout>>,
+    watching: Arc<AtomicBool>,
+
+    /// Kept alive while the server runs; created on the first `watch`
+    watcher: Option<RecommendedWatcher>,
+}
+
+impl Server {
+    fn new(api: ShapeApi) -> Self {
+        Self {
+            api,
+            out: Arc::new(Mutex::new(io::stdout())),
+            watching: Arc::new(AtomicBool::new(false)),
+            watcher: None,
+        }
+    }
+
+    fn run_stdio(mut self) -> Result<()> {
+        let stdin = io::stdin();
+
+        for line in stdin.lock().lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let (response, shutdown) = self.handle_line(&line);
+            if let Some(response) = response {
+                send(&self.out, &response)?;
+            }
+            if shutdown {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handles one message, returning the response (if any) and whether to exit
+    fn handle_line(&mut self, line: &str) -> (Option<Value>, bool) {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(e) => return (Some(error_response(Value::Null, PARSE_ERROR, e)), false),
+        };
+
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return (
+                Some(error_response(
+                    id.unwrap_or(Value::Null),
+                    INVALID_REQUEST,
+                    "Missing method",
+                )),
+                false,
+            );
+        };
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+        let shutdown = method == "shutdown";
+
+        let result = self.dispatch(method, params);
+
+        // Requests without an id are notifications and get no response
+        let response = id.map(|id| match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(e) => error_response(id, e.code, e.message),
+        });
+
+        (response, shutdown)
+    }
+
+    fn dispatch(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "briefs.list" => Ok(serde_json::to_value(self.api.briefs()?)?),
+            "briefs.get" => {
+                let id: BriefId = param(&params, "id")?;
+                Ok(serde_json::to_value(self.api.brief(&id)?)?)
+            }
+            "tasks.list" => {
+                let brief: Option<BriefId> = optional_param(&params, "brief")?;
+                let tasks: Vec<_> = self
+                    .api
+                    .tasks()?
+                    .into_iter()
+                    .filter(|t| brief.is_none() || t.brief_id() == brief)
+                    .collect();
+                Ok(serde_json::to_value(tasks)?)
+            }
+            "tasks.get" => {
+                let id: TaskId = param(&params, "id")?;
+                Ok(serde_json::to_value(self.api.task(&id)?)?)
+            }
+            "tasks.ready" => Ok(serde_json::to_value(self.api.ready()?)?),
+            "apply" => {
+                let op: Operation = serde_json::from_value(params)?;
+                Ok(self.api.apply(op)?)
+            }
+            "watch" => {
+                self.start_watcher()?;
+                self.watching.store(true, Ordering::SeqCst);
+                Ok(Value::Bool(true))
+            }
+            "unwatch" => {
+                self.watching.store(false, Ordering::SeqCst);
+                Ok(Value::Bool(true))
+            }
+            "shutdown" => Ok(Value::Null),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {}", method),
+            )),
+        }
+    }
+
+    /// Starts the file watcher that emits `changed` notifications
+    fn start_watcher(&mut self) -> Result<()> {
+        if self.watcher.is_some() {
+            return Ok(());
+        }
+
+        let root = self.api.root().to_path_buf();
+        let shape_dir = self.api.project().shape_dir();
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        watcher.watch(&shape_dir, RecursiveMode::Recursive)?;
+
+        let out = Arc::clone(&self.out);
+        let watching = Arc::clone(&self.watching);
+
+        thread::spawn(move || {
+            while let Ok(first) = rx.recv() {
+                // Collect everything that arrives within the debounce window
+                let mut events = vec![first];
+                while let Ok(event) = rx.recv_timeout(WATCH_DEBOUNCE) {
+                    events.push(event);
+                }
+
+                if !watching.load(Ordering::SeqCst) {
+                    continue;
+                }
+
+                // Reads show up as access events; only report writes
+                let mut paths: Vec<String> = events
+                    .into_iter()
+                    .flatten()
+                    .filter(|e| !matches!(e.kind, EventKind::Access(_)))
+                    .flat_map(|e| e.paths)
+                    .filter(|p| !should_ignore_path(p))
+                    .map(|p| relative(&root, &p))
+                    .collect();
+                paths.sort();
+                paths.dedup();
+
+                if paths.is_empty() {
+                    continue;
+                }
+
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "changed",
+                    "params": {"paths": paths},
+                });
+                if send(&out, &notification).is_err() {
+                    break;
+                }
+            }
+        });
+
+        self.watcher = Some(watcher);
+        Ok(())
+    }
+}
+
+fn send(out: &Mutex<io::Stdout>, message: &Value) -> io::Result<()> {
+    let mut out = out.lock().unwrap_or_else(|e| e.into_inner());
+    writeln!(out, "{}", message)?;
+    out.flush()
+}
+
+pub(super) fn error_response(id: Value, code: i64, message: impl ToString) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": code, "message": message.to_string()},
+    })
+}
+
+pub(super) fn param<T: serde::de::DeserializeOwned>(
+    params: &Value,
+    name: &str,
+) -> Result<T, RpcError> {
+    optional_param(params, name)?
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Missing param: {}", name)))
+}
+
+pub(super) fn optional_param<T: serde::de::DeserializeOwned>(
+    params: &Value,
+    name: &str,
+) -> Result<Option<T>, RpcError> {
+    match params.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid param {}: {}", name, e))),
+    }
+}
+
+fn relative(root: &std::path::Path, path: &std::path::Path) -> String {
+    // Watch events may report canonicalized paths
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    path.strip_prefix(&root)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .display()
+        .to_string()
+}
@@ -0,0 +1,207 @@
+//! What-if analysis
+//!
+//! `shape simulate done <ID>...` reports what completing tasks would change
+//! without touching the project: which tasks become ready, how the critical
+//! path moves and the new projected finish dates. Projections take
+//! `meta.estimate` as days (one day for tasks without an estimate) and
+//! assume blocked work starts as soon as its dependencies finish, waiting
+//! out any dependency lag (`shape task dep --lag`) or overlapping by a lead.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, Utc};
+use clap::Subcommand;
+
+use super::error::CliError;
+use super::output::Output;
+use crate::domain::{by_id, BriefId, DependencyGraph, Task, TaskId, TaskStatus};
+use crate::storage::Project;
+
+#[derive(Subcommand)]
+pub enum SimulateCommands {
+    /// Show the effect of completing tasks (nothing is changed)
+    ///
+    /// Example:
+    ///   shape simulate done b-1234567.3 b-1234567.5
+    Done {
+        /// Tasks to pretend are done
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+}
+
+pub fn run(cmd: SimulateCommands, output: &Output) -> Result<()> {
+    match cmd {
+        SimulateCommands::Done { ids } => simulate_done(output, &ids),
+    }
+}
+
+/// Projected state of the project for one set of statuses
+struct Projection {
+    ready: Vec<TaskId>,
+    critical_path: Vec<TaskId>,
+    days: i64,
+    briefs: HashMap<BriefId, i64>,
+}
+
+impl Projection {
+    fn new(
+        graph: &DependencyGraph,
+        tasks: &HashMap<TaskId, Task>,
+        statuses: &HashMap<TaskId, TaskStatus>,
+    ) -> Self {
+        let duration = |id: &TaskId| tasks.get(id).map_or(1, estimate_days);
+        let (critical_path, days) = graph.critical_path(statuses, duration);
+
+        let mut briefs: HashMap<BriefId, i64> = HashMap::new();
+        for (id, finish) in graph.earliest_finish(statuses, duration) {
+            if let Some(brief_id) = id.brief_id() {
+                let entry = briefs.entry(brief_id).or_default();
+                *entry = (*entry).max(finish);
+            }
+        }
+
+        Self {
+            ready: graph.ready_tasks(statuses),
+            critical_path,
+            days,
+            briefs,
+        }
+    }
+}
+
+/// Task length for projections: `meta.estimate` in days, at least one
+fn estimate_days(task: &Task) -> i64 {
+    task.get_meta("estimate")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(1)
+        .max(1)
+}
+
+/// Date `days` from today, or none when no work remains
+fn finish_date(days: i64) -> Option<NaiveDate> {
+    (days > 0).then(|| Utc::now().date_naive() + Duration::days(days))
+}
+
+fn simulate_done(output: &Output, id_strs: &[String]) -> Result<()> {
+    let project = Project::open_current()?;
+    let tasks = project.task_store().read_all()?;
+    let briefs = project.brief_store().read_all_meta()?;
+
+    let mut simulated = Vec::new();
+    for id_str in id_strs {
+        let id = project.resolve_task_id(id_str)?;
+        if !tasks.contains_key(&id) {
+            return Err(CliError::not_found("Task", &id).into());
+        }
+        simulated.push(id);
+    }
+
+    let graph = DependencyGraph::from_tasks(tasks.values())?;
+    let statuses: HashMap<TaskId, TaskStatus> =
+        tasks.iter().map(|(id, t)| (id.clone(), t.status)).collect();
+    let mut what_if = statuses.clone();
+    for id in &simulated {
+        what_if.insert(id.clone(), TaskStatus::Done);
+    }
+
+    let before = Projection::new(&graph, &tasks, &statuses);
+    let after = Projection::new(&graph, &tasks, &what_if);
+
+    let was_ready: HashSet<&TaskId> = before.ready.iter().collect();
+    let newly_ready: Vec<&Task> = after
+        .ready
+        .iter()
+        .filter(|id| !was_ready.contains(id))
+        .filter_map(|id| tasks.get(id))
+        .collect();
+
+    // Briefs whose projected finish moves
+    let changed_briefs: Vec<_> = by_id(briefs.values())
+        .into_iter()
+        .filter_map(|brief| {
+            let old = before.briefs.get(&brief.id).copied().unwrap_or(0);
+            let new = after.briefs.get(&brief.id).copied().unwrap_or(0);
+            (old != new).then_some((brief, old, new))
+        })
+        .collect();
+
+    if output.is_json() {
+        let path = |p: &Projection| {
+            serde_json::json!({
+                "tasks": p.critical_path.iter().map(|t| t.to_string()).collect::<Vec<_>>(),
+                "days": p.days,
+            })
+        };
+        output.data(&serde_json::json!({
+            "simulated": simulated.iter().map(|t| t.to_string()).collect::<Vec<_>>(),
+            "newly_ready": newly_ready.iter().map(|t| serde_json::json!({
+                "id": t.id.to_string(),
+                "title": t.title,
+            })).collect::<Vec<_>>(),
+            "critical_path": {
+                "before": path(&before),
+                "after": path(&after),
+            },
+            "finish": {
+                "before": finish_date(before.days),
+                "after": finish_date(after.days),
+            },
+            "briefs": changed_briefs.iter().map(|(brief, old, new)| serde_json::json!({
+                "id": brief.id.to_string(),
+                "title": brief.title,
+                "before": finish_date(*old),
+                "after": finish_date(*new),
+            })).collect::<Vec<_>>(),
+        }));
+        return Ok(());
+    }
+
+    let ids: Vec<String> = simulated.iter().map(|t| t.to_string()).collect();
+    println!("If done: {}", ids.join(", "));
+    println!();
+
+    if newly_ready.is_empty() {
+        println!("No tasks become ready.");
+    } else {
+        println!("Becomes ready:");
+        for task in &newly_ready {
+            println!("  {:<20} {}", task.id, task.title);
+        }
+    }
+    println!();
+
+    let chain = |p: &Projection| {
+        let ids: Vec<String> = p.critical_path.iter().map(|t| t.to_string()).collect();
+        if ids.is_empty() {
+            "(none)".to_string()
+        } else {
+            ids.join(" -> ")
+        }
+    };
+    println!("Critical path: {} -> {} day(s)", before.days, after.days);
+    println!("  now:   {}", chain(&before));
+    println!("  after: {}", chain(&after));
+
+    let date = |days| finish_date(days).map_or_else(|| "-".to_string(), |d| d.to_string());
+    println!(
+        "Projected finish: {} -> {}",
+        date(before.days),
+        date(after.days)
+    );
+    if !changed_briefs.is_empty() {
+        println!();
+        for (brief, old, new) in &changed_briefs {
+            println!(
+                "  {:<12} {} -> {}  {}",
+                brief.id,
+                date(*old),
+                date(*new),
+                brief.title
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,330 @@
+//! `shape task split`: break an oversized task into subtasks
+//!
+//! The subtasks take over the task's place in the dependency graph: they
+//! inherit what it waited on, and tasks that waited on it wait on them
+//! instead. The task stays as a container holding its notes and links,
+//! depending on every subtask so it only becomes ready once they are done.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use anyhow::Result;
+
+use super::error::{CliError, ErrorCode};
+use super::freeze::ensure_task_mutable;
+use super::output::Output;
+use crate::domain::{Dependency, DependencyType, Task, TaskId};
+use crate::storage::Project;
+
+/// What a split changed
+#[derive(Debug, Default)]
+struct Split {
+    /// New subtasks, in order
+    subtasks: Vec<TaskId>,
+
+    /// Tasks the parent depended on, now depended on by the subtasks
+    inherited: Vec<TaskId>,
+
+    /// Tasks that depended on the parent and now depend on the subtasks
+    rewired: Vec<TaskId>,
+}
+
+pub(super) fn run(
+    output: &Output,
+    id_str: &str,
+    titles: Vec<String>,
+    sequential: bool,
+) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.task_store();
+
+    let id = project.resolve_task_id(id_str)?;
+    let parent_title = {
+        let tasks = store.read_all()?;
+        ensure_splittable(&project, &tasks, &id)?;
+        tasks[&id].title.clone()
+    };
+
+    // Prompt before taking the write lock: nothing is held while the user
+    // types
+    let titles = if titles.is_empty() && io::stdin().is_terminal() && !output.is_json() {
+        prompt_titles(&parent_title)?
+    } else {
+        titles
+    };
+    if titles.len() < 2 {
+        return Err(CliError::new(
+            ErrorCode::Validation,
+            "A split needs at least two subtasks; give each title with --title",
+        )
+        .into());
+    }
+
+    let (split, tasks) = store.modify(|tasks| {
+        // The task may have changed since it was read for the prompt
+        ensure_splittable(&project, tasks, &id)?;
+        let split = split(tasks, &id, &titles, sequential);
+        let subtasks: HashMap<TaskId, Task> = split
+            .subtasks
+            .iter()
+            .map(|s| (s.clone(), tasks[s].clone()))
+            .collect();
+        Ok((split, subtasks))
+    })?;
+
+    if output.is_json() {
+        let subtasks: Vec<_> = split
+            .subtasks
+            .iter()
+            .map(|s| {
+                let deps: Vec<String> = tasks[s]
+                    .depends_on
+                    .blocking_task_ids()
+                    .map(|d| d.to_string())
+                    .collect();
+                serde_json::json!({
+                    "id": s.to_string(),
+                    "title": tasks[s].title,
+                    "depends_on": deps,
+                })
+            })
+            .collect();
+        output.data(&serde_json::json!({
+            "id": id.to_string(),
+            "subtasks": subtasks,
+            "inherited": split.inherited.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            "rewired": split.rewired.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            "sequential": sequential,
+        }));
+    } else {
+        output.success(&format!(
+            "Split {} into {} subtasks:",
+            id,
+            split.subtasks.len()
+        ));
+        for subtask in &split.subtasks {
+            println!("  {}  {}", subtask, tasks[subtask].title);
+        }
+        if !split.inherited.is_empty() {
+            println!("  Subtasks now wait on: {}", join(&split.inherited));
+        }
+        if !split.rewired.is_empty() {
+            println!("  Now waiting on the subtasks: {}", join(&split.rewired));
+        }
+        println!(
+            "  {} stays open as a container until its subtasks are done",
+            id
+        );
+    }
+
+    Ok(())
+}
+
+/// Fails unless task `id` exists, may be changed and is still open
+fn ensure_splittable(project: &Project, tasks: &HashMap<TaskId, Task>, id: &TaskId) -> Result<()> {
+    let parent = tasks
+        .get(id)
+        .ok_or_else(|| CliError::not_found("Task", id))?;
+    ensure_task_mutable(project, parent)?;
+    if parent.status.is_complete() {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!("{} is already done; only open tasks can be split", id),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Creates subtasks of `parent` titled `titles` and moves the parent's
+/// blocking dependencies onto them.
+///
+/// With `sequential`, the subtasks are chained in order: only the first
+/// inherits what the parent waited on, and dependents wait on the last.
+/// Otherwise every subtask inherits and dependents wait on all of them.
+fn split(
+    tasks: &mut HashMap<TaskId, Task>,
+    parent_id: &TaskId,
+    titles: &[String],
+    sequential: bool,
+) -> Split {
+    let is_child = |id: &TaskId| id.parent().as_ref() == Some(parent_id);
+    let next_seq = tasks
+        .keys()
+        .filter(|id| is_child(id))
+        .filter_map(|id| id.segments().last().copied())
+        .max()
+        .unwrap_or(0)
+        + 1;
+
+    let parent = &tasks[parent_id];
+    // Dependencies on the parent's own subtasks (from an earlier split) stay
+    let inherited: Vec<TaskId> = parent
+        .depends_on
+        .blocking_task_ids()
+        .filter(|d| !is_child(d))
+        .cloned()
+        .collect();
+    let inherited_meta: Vec<(&str, serde_json::Value)> = ["labels", "priority"]
+        .into_iter()
+        .filter_map(|key| Some((key, parent.get_meta(key)?.clone())))
+        .collect();
+    let assignee = parent.assigned_to.clone();
+
+    let mut subtasks = Vec::new();
+    for (i, title) in titles.iter().enumerate() {
+        let id = parent_id.subtask(next_seq + i as u32);
+        let mut task = Task::new(id.clone(), title.as_str());
+        task.add_typed_dependency(Dependency::provenance(parent_id.clone()));
+        for (key, value) in &inherited_meta {
+            task.set_meta(*key, value.clone());
+        }
+        task.assigned_to = assignee.clone();
+
+        let waits_on: &[TaskId] = match subtasks.last() {
+            Some(previous) if sequential => std::slice::from_ref(previous),
+            _ => &inherited,
+        };
+        for dep in waits_on {
+            task.add_typed_dependency(Dependency::blocks(dep.clone()));
+        }
+        tasks.insert(id.clone(), task);
+        subtasks.push(id);
+    }
+
+    let mut rewired = Vec::new();
+    let waited_on: &[TaskId] = if sequential {
+        std::slice::from_ref(subtasks.last().expect("at least one subtask"))
+    } else {
+        &subtasks
+    };
+    for task in tasks.values_mut() {
+        if task.id == *parent_id
+            || is_child(&task.id)
+            || !task.depends_on.contains_blocking(parent_id)
+        {
+            continue;
+        }
+        task.remove_typed_dependency(parent_id, DependencyType::Blocks);
+        for subtask in waited_on {
+            task.add_typed_dependency(Dependency::blocks(subtask.clone()));
+        }
+        rewired.push(task.id.clone());
+    }
+    rewired.sort();
+
+    let parent = tasks.get_mut(parent_id).expect("parent exists");
+    for dep in &inherited {
+        parent.remove_typed_dependency(dep, DependencyType::Blocks);
+    }
+    parent.mark_split(&subtasks);
+
+    Split {
+        subtasks,
+        inherited,
+        rewired,
+    }
+}
+
+/// Asks for subtask titles, one per line, until an empty line
+fn prompt_titles(parent_title: &str) -> Result<Vec<String>> {
+    println!(
+        "Splitting \"{}\". Enter subtask titles, one per line; an empty line finishes.",
+        parent_title
+    );
+    let mut titles = Vec::new();
+    let stdin = io::stdin();
+    loop {
+        print!("Subtask {}: ", titles.len() + 1);
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        titles.push(line.trim().to_string());
+    }
+    Ok(titles)
+}
+
+fn join(ids: &[TaskId]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::BriefId;
+    use chrono::Utc;
+
+    fn project() -> (HashMap<TaskId, Task>, Vec<TaskId>) {
+        let brief = BriefId::new("Test", Utc::now());
+        let ids: Vec<TaskId> = (1..=4).map(|seq| TaskId::new(&brief, seq)).collect();
+        let mut tasks: HashMap<TaskId, Task> = ids
+            .iter()
+            .map(|id| (id.clone(), Task::new(id.clone(), format!("Task {}", id))))
+            .collect();
+        // 1 -> 2 -> 3, and 4 waits on 2 too
+        let mut dep = |from: usize, on: usize| {
+            tasks
+                .get_mut(&ids[from])
+                .unwrap()
+                .add_typed_dependency(Dependency::blocks(ids[on].clone()));
+        };
+        dep(1, 0);
+        dep(2, 1);
+        dep(3, 1);
+        (tasks, ids)
+    }
+
+    fn blocking(tasks: &HashMap<TaskId, Task>, id: &TaskId) -> Vec<TaskId> {
+        let mut deps: Vec<TaskId> = tasks[id].depends_on.blocking_task_ids().cloned().collect();
+        deps.sort();
+        deps
+    }
+
+    #[test]
+    fn split_moves_dependencies_to_all_subtasks() {
+        let (mut tasks, ids) = project();
+        let titles = vec!["A".to_string(), "B".to_string()];
+        let result = split(&mut tasks, &ids[1], &titles, false);
+
+        let [a, b] = [&result.subtasks[0], &result.subtasks[1]];
+        assert_eq!(a, &ids[1].subtask(1));
+        assert_eq!(blocking(&tasks, a), vec![ids[0].clone()]);
+        assert_eq!(blocking(&tasks, b), vec![ids[0].clone()]);
+        assert_eq!(blocking(&tasks, &ids[2]), vec![a.clone(), b.clone()]);
+        assert_eq!(blocking(&tasks, &ids[3]), vec![a.clone(), b.clone()]);
+        assert_eq!(result.rewired, vec![ids[2].clone(), ids[3].clone()]);
+
+        let parent = &tasks[&ids[1]];
+        assert!(parent.is_container());
+        assert_eq!(blocking(&tasks, &ids[1]), vec![a.clone(), b.clone()]);
+        assert!(tasks[a]
+            .depends_on
+            .by_type(DependencyType::Provenance)
+            .any(|d| d.task == ids[1]));
+    }
+
+    #[test]
+    fn sequential_split_chains_subtasks() {
+        let (mut tasks, ids) = project();
+        let titles = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        let result = split(&mut tasks, &ids[1], &titles, true);
+
+        let s = &result.subtasks;
+        assert_eq!(blocking(&tasks, &s[0]), vec![ids[0].clone()]);
+        assert_eq!(blocking(&tasks, &s[1]), vec![s[0].clone()]);
+        assert_eq!(blocking(&tasks, &s[2]), vec![s[1].clone()]);
+        assert_eq!(blocking(&tasks, &ids[2]), vec![s[2].clone()]);
+
+        // A second split numbers on from the first
+        let more = vec!["D".to_string(), "E".to_string()];
+        let again = split(&mut tasks, &ids[1], &more, false);
+        assert_eq!(again.subtasks[0], ids[1].subtask(4));
+        assert!(again.inherited.is_empty());
+        assert_eq!(blocking(&tasks, &ids[1]).len(), 5);
+    }
+}
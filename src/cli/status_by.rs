@@ -0,0 +1,346 @@
+//! `shape status --by`: status broken down by brief type, owner, cycle or
+//! task label
+//!
+//! Each group rolls up its briefs and tasks with the share of tasks done and
+//! how that moved over the last week, judged from task creation and
+//! completion times, so the area of the project that is stuck stands out.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+
+use super::output::Output;
+use crate::domain::{by_id, Brief, BriefId, Task, TaskId, TaskStatus};
+use crate::storage::Project;
+
+/// What `status --by` groups by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StatusGroup {
+    /// Brief type (minimal, shapeup, rfc, ...)
+    Type,
+    /// Brief owner
+    Owner,
+    /// Cycle of the brief's winning bet
+    Cycle,
+    /// Task label; a task with several labels counts in each
+    Label,
+}
+
+impl StatusGroup {
+    fn as_str(self) -> &'static str {
+        match self {
+            StatusGroup::Type => "type",
+            StatusGroup::Owner => "owner",
+            StatusGroup::Cycle => "cycle",
+            StatusGroup::Label => "label",
+        }
+    }
+
+    /// Group name for tasks without a value
+    fn none_name(self) -> &'static str {
+        match self {
+            StatusGroup::Type => "(no type)",
+            StatusGroup::Owner => "(unowned)",
+            StatusGroup::Cycle => "(no cycle)",
+            StatusGroup::Label => "(unlabeled)",
+        }
+    }
+
+    /// Groups of a brief; labels belong to tasks, so briefs join label
+    /// groups through their tasks
+    fn brief_keys(self, brief: &Brief) -> Vec<String> {
+        let key = match self {
+            StatusGroup::Type => Some(brief.brief_type.clone()),
+            StatusGroup::Owner => brief.owner.clone(),
+            StatusGroup::Cycle => brief.cycle().map(str::to_string),
+            StatusGroup::Label => return Vec::new(),
+        };
+        vec![key.unwrap_or_else(|| self.none_name().to_string())]
+    }
+
+    fn task_keys(self, task: &Task, brief: Option<&Brief>) -> Vec<String> {
+        if self == StatusGroup::Label {
+            let labels = task.labels();
+            if labels.is_empty() {
+                return vec![self.none_name().to_string()];
+            }
+            return labels.into_iter().map(str::to_string).collect();
+        }
+        match brief {
+            Some(brief) => self.brief_keys(brief),
+            None => vec![STANDALONE.to_string()],
+        }
+    }
+}
+
+/// Group of tasks that belong to no brief, outside label grouping
+const STANDALONE: &str = "(standalone)";
+
+/// Task counts at a point in time
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct TaskCounts {
+    total: usize,
+    done: usize,
+}
+
+impl TaskCounts {
+    fn percent_done(&self) -> i64 {
+        (self.done * 100).checked_div(self.total).unwrap_or(0) as i64
+    }
+}
+
+/// A brief's progress within a group
+#[derive(Debug)]
+struct BriefRow<'a> {
+    brief: &'a Brief,
+    done: usize,
+    total: usize,
+}
+
+/// Rollup of one group
+#[derive(Debug, Default)]
+struct Group<'a> {
+    briefs: Vec<BriefRow<'a>>,
+    todo: usize,
+    in_progress: usize,
+    blocked: usize,
+    now: TaskCounts,
+    week_ago: TaskCounts,
+}
+
+impl Group<'_> {
+    fn active_briefs(&self) -> usize {
+        self.briefs.iter().filter(|b| b.brief.is_active()).count()
+    }
+
+    fn complete_briefs(&self) -> usize {
+        self.briefs.iter().filter(|b| b.brief.is_complete()).count()
+    }
+}
+
+/// Groups briefs and tasks; tasks count as of `now` and as of a week before
+fn group<'a>(
+    by: StatusGroup,
+    briefs: &'a HashMap<BriefId, Brief>,
+    tasks: &HashMap<TaskId, Task>,
+    now: DateTime<Utc>,
+) -> BTreeMap<String, Group<'a>> {
+    let cutoff = now - Duration::days(7);
+    let statuses: HashMap<TaskId, TaskStatus> =
+        tasks.iter().map(|(id, t)| (id.clone(), t.status)).collect();
+
+    let mut groups: BTreeMap<String, Group<'a>> = BTreeMap::new();
+    let mut rows: HashMap<(String, BriefId), usize> = HashMap::new();
+    let mut row = |groups: &mut BTreeMap<String, Group<'a>>, key: &str, brief: &'a Brief| {
+        let group = groups.entry(key.to_string()).or_default();
+        *rows
+            .entry((key.to_string(), brief.id.clone()))
+            .or_insert_with(|| {
+                group.briefs.push(BriefRow {
+                    brief,
+                    done: 0,
+                    total: 0,
+                });
+                group.briefs.len() - 1
+            })
+    };
+
+    for brief in by_id(briefs.values()) {
+        for key in by.brief_keys(brief) {
+            row(&mut groups, &key, brief);
+        }
+    }
+
+    for task in by_id(tasks.values()) {
+        let brief = task.brief_id().and_then(|id| briefs.get(&id));
+        for key in by.task_keys(task, brief) {
+            let index = brief.map(|b| row(&mut groups, &key, b));
+            let group = groups.entry(key).or_default();
+            let done = task.status.is_complete();
+            if let Some(index) = index {
+                group.briefs[index].total += 1;
+                group.briefs[index].done += done as usize;
+            }
+            match task.status {
+                TaskStatus::Todo => group.todo += 1,
+                TaskStatus::InProgress => group.in_progress += 1,
+                TaskStatus::Done => {}
+            }
+            group.blocked += task.is_blocked(&statuses) as usize;
+            group.now.total += 1;
+            group.now.done += done as usize;
+            if task.created_at <= cutoff {
+                group.week_ago.total += 1;
+                group.week_ago.done += task.completed_at.is_some_and(|at| at <= cutoff) as usize;
+            }
+        }
+    }
+    groups
+}
+
+pub(super) fn run(output: &Output, by: StatusGroup) -> Result<()> {
+    let project = Project::open_current()?;
+    let briefs = project.brief_store().read_all_meta()?;
+    let tasks = project.task_store().read_all()?;
+    let groups = group(by, &briefs, &tasks, Utc::now());
+
+    // Catch-all groups such as (unowned) come last
+    let mut ordered: Vec<(&String, &Group)> = groups.iter().collect();
+    ordered.sort_by_key(|(name, _)| name.starts_with('('));
+
+    if output.is_json() {
+        let items: Vec<_> = ordered
+            .iter()
+            .map(|(name, g)| {
+                serde_json::json!({
+                    "name": name,
+                    "briefs": {
+                        "total": g.briefs.len(),
+                        "active": g.active_briefs(),
+                        "complete": g.complete_briefs(),
+                    },
+                    "tasks": {
+                        "total": g.now.total,
+                        "todo": g.todo,
+                        "in_progress": g.in_progress,
+                        "done": g.now.done,
+                        "blocked": g.blocked,
+                    },
+                    "percent_done": g.now.percent_done(),
+                    "week_delta": {
+                        "tasks": g.now.total as i64 - g.week_ago.total as i64,
+                        "done": g.now.done as i64 - g.week_ago.done as i64,
+                        "percent_done": g.now.percent_done() - g.week_ago.percent_done(),
+                    },
+                    "children": g.briefs.iter().map(|row| serde_json::json!({
+                        "id": row.brief.id.to_string(),
+                        "title": row.brief.title,
+                        "status": row.brief.status,
+                        "done": row.done,
+                        "total": row.total,
+                    })).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        output.data(&serde_json::json!({ "by": by.as_str(), "groups": items }));
+        return Ok(());
+    }
+
+    println!("Project Status by {}", by.as_str());
+    println!("{}", "=".repeat(40));
+    if ordered.is_empty() {
+        println!("\nNothing to group yet.");
+        return Ok(());
+    }
+    let width = ordered
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp(5, 24);
+    println!();
+    println!(
+        "{:<width$}  {:<12} {:>5} {:>5} {:>7} {:>6}  LAST 7 DAYS",
+        "GROUP", "BRIEFS", "TASKS", "DONE", "BLOCKED", "DONE%"
+    );
+    for (name, g) in &ordered {
+        let briefs = if g.briefs.is_empty() {
+            "-".to_string()
+        } else {
+            format!("{} ({} active)", g.briefs.len(), g.active_briefs())
+        };
+        println!(
+            "{:<width$}  {:<12} {:>5} {:>5} {:>7} {:>5}%  {}",
+            name,
+            briefs,
+            g.now.total,
+            g.now.done,
+            g.blocked,
+            g.now.percent_done(),
+            week_delta(g)
+        );
+        for (i, row) in g.briefs.iter().enumerate() {
+            let branch = if i + 1 == g.briefs.len() {
+                "└─"
+            } else {
+                "├─"
+            };
+            println!(
+                "  {} {} {} ({}/{} done)",
+                branch, row.brief.id, row.brief.title, row.done, row.total
+            );
+        }
+    }
+    Ok(())
+}
+
+/// "+3 tasks, +2 done, +10pp", or "no change"
+fn week_delta(g: &Group) -> String {
+    let signed = |n: i64, unit: &str| format!("{:+}{}", n, unit);
+    let tasks = g.now.total as i64 - g.week_ago.total as i64;
+    let done = g.now.done as i64 - g.week_ago.done as i64;
+    let points = g.now.percent_done() - g.week_ago.percent_done();
+    let parts: Vec<String> = [(tasks, " tasks"), (done, " done"), (points, "pp")]
+        .into_iter()
+        .filter(|(n, _)| *n != 0)
+        .map(|(n, unit)| signed(n, unit))
+        .collect();
+    if parts.is_empty() {
+        "no change".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_by_owner_and_label_with_weekly_delta() {
+        let now = Utc::now();
+        let mut alice = Brief::new("Search", "shapeup");
+        alice.owner = Some("alice".to_string());
+        let unowned = Brief::new("Billing", "minimal");
+
+        let mut tasks = HashMap::new();
+        let mut add = |brief: &BriefId, seq: u32, labels: &str, age_days: i64, done: bool| {
+            let mut task = Task::new(TaskId::new(brief, seq), format!("Task {}", seq));
+            task.created_at = now - Duration::days(age_days);
+            if !labels.is_empty() {
+                task.set_meta(
+                    "labels",
+                    serde_json::json!(labels.split(',').collect::<Vec<_>>()),
+                );
+            }
+            if done {
+                task.complete();
+            }
+            tasks.insert(task.id.clone(), task);
+        };
+        add(&alice.id, 1, "ui", 10, true);
+        add(&alice.id, 2, "ui,bug", 10, false);
+        add(&unowned.id, 1, "", 2, false);
+
+        let briefs: HashMap<BriefId, Brief> = [alice.clone(), unowned.clone()]
+            .into_iter()
+            .map(|b| (b.id.clone(), b))
+            .collect();
+
+        let by_owner = group(StatusGroup::Owner, &briefs, &tasks, now);
+        let a = &by_owner["alice"];
+        assert_eq!((a.now.total, a.now.done, a.now.percent_done()), (2, 1, 50));
+        // Both tasks existed a week ago; the one done was completed since
+        assert_eq!(a.week_ago, TaskCounts { total: 2, done: 0 });
+        assert_eq!(week_delta(a), "+1 done, +50pp");
+        assert_eq!(by_owner["(unowned)"].briefs[0].brief.id, unowned.id);
+        assert_eq!(week_delta(&by_owner["(unowned)"]), "+1 tasks");
+
+        let by_label = group(StatusGroup::Label, &briefs, &tasks, now);
+        assert_eq!(by_label["ui"].now.total, 2);
+        assert_eq!(by_label["bug"].now.total, 1);
+        assert_eq!(by_label["bug"].briefs[0].total, 1);
+        assert_eq!(by_label["(unlabeled)"].briefs[0].brief.id, unowned.id);
+    }
+}
@@ -0,0 +1,187 @@
+//! `shape lock-store` and `shape unlock-store`: encryption at rest
+//!
+//! Locking derives a key from a project passphrase and rewrites every task
+//! line and brief file sealed (see [`encryption`]). Unlocking rewrites them
+//! as plaintext and drops the encryption settings.
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use serde_json::json;
+
+use super::error::{CliError, ErrorCode};
+use super::output::Output;
+use crate::storage::archive;
+use crate::storage::encryption::{self, PASSPHRASE_ENV};
+use crate::storage::{EncryptionError, Project};
+
+/// Resealing only staged copies would leave the live store half switched
+fn refuse_in_tx(project: &Project) -> Result<()> {
+    if project.tx_session()?.is_some() {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            "A transaction is open. Run 'shape tx commit' or 'shape tx abort' first.",
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// `shape lock-store`: encrypts the store with a new passphrase
+pub fn lock(output: &Output, remember: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    refuse_in_tx(&project)?;
+    let shape_dir = project.shape_dir();
+    if encryption::is_enabled(&shape_dir) {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            "The store is already encrypted. Run 'shape unlock-store' first to change the passphrase.",
+        )
+        .into());
+    }
+
+    let passphrase = read_passphrase(true)?.ok_or_else(|| {
+        CliError::new(
+            ErrorCode::Validation,
+            format!(
+                "No passphrase given. Set {} or enter one when prompted.",
+                PASSPHRASE_ENV
+            ),
+        )
+    })?;
+
+    let cipher = encryption::enable(&shape_dir, &passphrase)?;
+    archive::mirror_encryption(&shape_dir)?;
+    let tasks = project.task_store().reseal(Some(&cipher))?
+        + project.archived_task_store().reseal(Some(&cipher))?;
+    let briefs = project.brief_store().reseal(Some(&cipher))?
+        + project.archived_brief_store().reseal(Some(&cipher))?;
+    let remembered = remember
+        && encryption::info(&shape_dir)?
+            .is_some_and(|info| encryption::remember(&info, &passphrase));
+
+    if output.is_json() {
+        output.data(&json!({
+            "encrypted": true,
+            "tasks": tasks,
+            "briefs": briefs,
+            "remembered": remembered,
+        }));
+        return Ok(());
+    }
+
+    output.success(&format!("Encrypted {} tasks and {} briefs", tasks, briefs));
+    if remembered {
+        println!("Passphrase saved in the system keychain.");
+    } else if remember {
+        println!(
+            "No supported keychain found; set {} to unlock.",
+            PASSPHRASE_ENV
+        );
+    } else {
+        println!(
+            "Set {} (or re-run with --remember) so commands can read the store.",
+            PASSPHRASE_ENV
+        );
+    }
+    Ok(())
+}
+
+/// `shape unlock-store`: decrypts the store and turns encryption off
+pub fn unlock(output: &Output) -> Result<()> {
+    let project = Project::open_current()?;
+    refuse_in_tx(&project)?;
+    let shape_dir = project.shape_dir();
+    let Some(info) = encryption::info(&shape_dir)? else {
+        return Err(CliError::new(ErrorCode::Conflict, "The store is not encrypted").into());
+    };
+
+    // Once unlocked here, the key stays available to the stores below
+    match encryption::cipher(&shape_dir) {
+        Ok(_) => {}
+        Err(e) if matches!(e.downcast_ref(), Some(EncryptionError::Locked)) => {
+            let passphrase = read_passphrase(false)?.ok_or(e)?;
+            encryption::unlock_with(&info, &passphrase)?;
+        }
+        Err(e) => return Err(e),
+    }
+
+    // Everything is plaintext again before the settings go, so an interrupted
+    // unlock can simply be re-run
+    let tasks = project.task_store().reseal(None)? + project.archived_task_store().reseal(None)?;
+    let briefs =
+        project.brief_store().reseal(None)? + project.archived_brief_store().reseal(None)?;
+    encryption::disable(&shape_dir)?;
+    archive::mirror_encryption(&shape_dir)?;
+    encryption::forget(&info);
+
+    if output.is_json() {
+        output.data(&json!({
+            "encrypted": false,
+            "tasks": tasks,
+            "briefs": briefs,
+        }));
+        return Ok(());
+    }
+
+    output.success(&format!(
+        "Decrypted {} tasks and {} briefs; the store is no longer encrypted",
+        tasks, briefs
+    ));
+    Ok(())
+}
+
+/// Passphrase from the environment, else typed at a hidden prompt (twice when
+/// `confirm`), else the first line of piped stdin. `None` if none is given.
+fn read_passphrase(confirm: bool) -> Result<Option<String>> {
+    if let Some(passphrase) = std::env::var(PASSPHRASE_ENV).ok().filter(|p| !p.is_empty()) {
+        return Ok(Some(passphrase));
+    }
+
+    if !io::stdin().is_terminal() {
+        let mut line = String::new();
+        io::stdin().lock().read_line(&mut line)?;
+        let line = line.trim_end_matches(['\r', '\n']);
+        return Ok((!line.is_empty()).then(|| line.to_string()));
+    }
+
+    let passphrase = prompt_hidden("Passphrase: ")?;
+    if passphrase.is_empty() {
+        return Ok(None);
+    }
+    if confirm && prompt_hidden("Repeat passphrase: ")? != passphrase {
+        return Err(CliError::new(ErrorCode::Validation, "Passphrases don't match").into());
+    }
+    Ok(Some(passphrase))
+}
+
+/// Reads a line from the terminal without echoing it
+fn prompt_hidden(prompt: &str) -> Result<String> {
+    eprint!("{}", prompt);
+    io::stderr().flush()?;
+
+    terminal::enable_raw_mode()?;
+    let mut input = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(CliError::new(ErrorCode::General, "Aborted").into())
+                }
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(anyhow::Error::from(e)),
+        }
+    };
+    terminal::disable_raw_mode()?;
+    eprintln!();
+    result.map(|()| input)
+}
@@ -1,10 +1,25 @@
 //! Sync commands
-
-use anyhow::Result;
+//!
+//! `shape sync map` manages the local ↔ remote ID mappings in
+//! `.shape/sync/<plugin>.jsonl` directly, so drift can be repaired without
+//! the plugin installed or the sync state edited by hand.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
 use clap::Subcommand;
 
+use super::error::{CliError, ErrorCode};
 use super::output::Output;
-use crate::plugin::{EntityType, PluginLoader, SyncPlugin};
+use crate::domain::{BriefId, TaskId};
+use crate::plugin::{
+    mapping_path, prune_mappings, read_mappings, write_mappings, EntityType, IdMapping,
+    PluginLoader, SyncPlugin,
+};
 use crate::storage::Project;
 
 #[derive(Subcommand)]
@@ -31,6 +46,100 @@ pub enum SyncCommands {
         #[arg(long)]
         plugin: String,
     },
+
+    /// Inspect and repair local ↔ remote ID mappings
+    #[command(subcommand)]
+    Map(MapCommands),
+}
+
+#[derive(Subcommand)]
+pub enum MapCommands {
+    /// List mappings, flagging those whose local brief or task is gone
+    ///
+    /// Example:
+    ///   shape sync map list github --broken
+    List {
+        /// Plugin name (all sync plugins if omitted)
+        plugin: Option<String>,
+
+        /// Only list broken mappings
+        #[arg(long)]
+        broken: bool,
+    },
+
+    /// Map a local brief or task to a remote ID
+    ///
+    /// Example:
+    ///   shape sync map add github task b-7f2a3b1.2 1234
+    Add {
+        /// Plugin name
+        plugin: String,
+
+        /// Entity type
+        #[arg(value_enum)]
+        kind: MappedKind,
+
+        /// Local brief or task ID
+        local: String,
+
+        /// Remote ID
+        remote: String,
+    },
+
+    /// Remove a mapping, or every broken one with --broken
+    ///
+    /// Example:
+    ///   shape sync map remove github b-7f2a3b1.2
+    Remove {
+        /// Plugin name
+        plugin: String,
+
+        /// Local ID of the mapping to remove
+        #[arg(required_unless_present = "broken", conflicts_with = "broken")]
+        local: Option<String>,
+
+        /// Remove all mappings whose local brief or task no longer exists
+        #[arg(long)]
+        broken: bool,
+    },
+
+    /// Print a plugin's mappings as JSON
+    Export {
+        /// Plugin name
+        plugin: String,
+    },
+
+    /// Load mappings from JSON written by `sync map export` ("-" for stdin)
+    ///
+    /// Example:
+    ///   shape sync map import github mappings.json --replace
+    Import {
+        /// Plugin name
+        plugin: String,
+
+        /// JSON file with an array of mappings
+        file: PathBuf,
+
+        /// Replace all existing mappings instead of merging by local ID
+        #[arg(long)]
+        replace: bool,
+    },
+}
+
+/// Entity type of a manual mapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MappedKind {
+    Brief,
+    Task,
+}
+
+impl From<MappedKind> for EntityType {
+    fn from(kind: MappedKind) -> Self {
+        match kind {
+            MappedKind::Brief => EntityType::Brief,
+            MappedKind::Task => EntityType::Task,
+        }
+    }
 }
 
 pub fn run(cmd: SyncCommands, output: &Output) -> Result<()> {
@@ -42,6 +151,30 @@ pub fn run(cmd: SyncCommands, output: &Output) -> Result<()> {
             remote,
             plugin,
         } => link_ids(output, &local, &remote, &plugin),
+        SyncCommands::Map(cmd) => run_map(cmd, output),
+    }
+}
+
+fn run_map(cmd: MapCommands, output: &Output) -> Result<()> {
+    match cmd {
+        MapCommands::List { plugin, broken } => map_list(output, plugin.as_deref(), broken),
+        MapCommands::Add {
+            plugin,
+            kind,
+            local,
+            remote,
+        } => map_add(output, &plugin, kind.into(), &local, &remote),
+        MapCommands::Remove {
+            plugin,
+            local,
+            broken,
+        } => map_remove(output, &plugin, local.as_deref(), broken),
+        MapCommands::Export { plugin } => map_export(&plugin),
+        MapCommands::Import {
+            plugin,
+            file,
+            replace,
+        } => map_import(output, &plugin, &file, replace),
     }
 }
 
@@ -52,12 +185,7 @@ fn run_sync(output: &Output, plugin_name: &str) -> Result<()> {
     loader.add_plugin_dir(project.plugins_dir());
     loader.discover()?;
 
-    // Prepend "shape-sync-" if needed
-    let full_name = if plugin_name.starts_with("shape-sync-") {
-        plugin_name.to_string()
-    } else {
-        format!("shape-sync-{}", plugin_name)
-    };
+    let full_name = full_plugin_name(plugin_name);
 
     if loader.get(&full_name).is_none() {
         anyhow::bail!(
@@ -93,6 +221,7 @@ fn run_sync(output: &Output, plugin_name: &str) -> Result<()> {
 
     // Push local changes
     let push_result = sync.push(&brief_values, &task_values)?;
+    record_external_ids(&project, plugin_system(&full_name), &sync.mappings()?)?;
 
     // Pull remote changes
     let (pull_result, _pulled_briefs, _pulled_tasks) = sync.pull()?;
@@ -211,22 +340,19 @@ fn link_ids(output: &Output, local: &str, remote: &str, plugin_name: &str) -> Re
     loader.add_plugin_dir(project.plugins_dir());
     loader.discover()?;
 
-    let full_name = if plugin_name.starts_with("shape-sync-") {
-        plugin_name.to_string()
-    } else {
-        format!("shape-sync-{}", plugin_name)
-    };
+    let full_name = full_plugin_name(plugin_name);
 
     let sync = SyncPlugin::new(&loader, &full_name, &project.sync_dir());
 
     // Determine entity type from ID format
-    let entity_type = if local.contains('.') {
+    let entity_type = if local.parse::<TaskId>().is_ok() {
         EntityType::Task
     } else {
         EntityType::Brief
     };
 
     sync.link(local, remote, entity_type)?;
+    record_external_ids(&project, plugin_system(&full_name), &sync.mappings()?)?;
 
     if output.is_json() {
         output.data(&serde_json::json!({
@@ -241,3 +367,374 @@ fn link_ids(output: &Output, local: &str, remote: &str, plugin_name: &str) -> Re
 
     Ok(())
 }
+
+/// Full sync plugin name (`github` → `shape-sync-github`)
+fn full_plugin_name(plugin_name: &str) -> String {
+    if plugin_name.starts_with("shape-sync-") {
+        plugin_name.to_string()
+    } else {
+        format!("shape-sync-{}", plugin_name)
+    }
+}
+
+/// External ID system name for a sync plugin (`shape-sync-github` → `github`)
+fn plugin_system(full_name: &str) -> &str {
+    full_name.strip_prefix("shape-sync-").unwrap_or(full_name)
+}
+
+/// Copies the plugin's ID mappings onto the briefs and tasks they refer to,
+/// so remote IDs show up in `show` output and work as identifiers
+fn record_external_ids(project: &Project, system: &str, mappings: &[IdMapping]) -> Result<()> {
+    let brief_store = project.brief_store();
+    let task_store = project.task_store();
+    let mut tasks = task_store.read_all()?;
+    let mut tasks_changed = false;
+
+    for mapping in mappings {
+        match mapping.entity_type {
+            EntityType::Brief => {
+                let Ok(id) = mapping.local_id.parse::<BriefId>() else {
+                    continue;
+                };
+                if let Some(mut brief) = brief_store.read(&id)? {
+                    if brief.external_ids.get(system) != Some(&mapping.remote_id) {
+                        brief.set_external_id(system, mapping.remote_id.clone());
+                        brief_store.write(&brief)?;
+                    }
+                }
+            }
+            EntityType::Task => {
+                let Ok(id) = mapping.local_id.parse::<TaskId>() else {
+                    continue;
+                };
+                if let Some(task) = tasks.get_mut(&id) {
+                    if task.external_ids.get(system) != Some(&mapping.remote_id) {
+                        task.set_external_id(system, mapping.remote_id.clone());
+                        tasks_changed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    if tasks_changed {
+        task_store.write_all(&tasks)?;
+    }
+
+    Ok(())
+}
+
+/// IDs of every brief and task in the project
+fn local_ids(project: &Project) -> Result<HashSet<String>> {
+    let mut ids: HashSet<String> = project
+        .task_store()
+        .read_all()?
+        .keys()
+        .map(|id| id.to_string())
+        .collect();
+    ids.extend(
+        project
+            .brief_store()
+            .read_all_meta()?
+            .keys()
+            .map(|id| id.to_string()),
+    );
+    Ok(ids)
+}
+
+/// Plugin names with a mapping file, sorted
+pub(super) fn mapped_plugins(project: &Project) -> Result<Vec<String>> {
+    let dir = project.sync_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut names: Vec<String> = fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+fn map_list(output: &Output, plugin: Option<&str>, broken_only: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let plugins = match plugin {
+        Some(name) => vec![full_plugin_name(name)],
+        None => mapped_plugins(&project)?,
+    };
+    let existing = local_ids(&project)?;
+
+    let mut rows = Vec::new();
+    for name in &plugins {
+        for mapping in read_mappings(&mapping_path(&project.sync_dir(), name))? {
+            let broken = !existing.contains(&mapping.local_id);
+            if !broken_only || broken {
+                rows.push((name.as_str(), mapping, broken));
+            }
+        }
+    }
+
+    if output.is_json() {
+        let items: Vec<_> = rows
+            .iter()
+            .map(|(plugin, m, broken)| {
+                serde_json::json!({
+                    "plugin": plugin,
+                    "local_id": m.local_id,
+                    "remote_id": m.remote_id,
+                    "entity_type": m.entity_type,
+                    "last_sync": m.last_sync,
+                    "broken": broken,
+                })
+            })
+            .collect();
+        output.data(&items);
+        return Ok(());
+    }
+
+    if rows.is_empty() {
+        println!("No mappings found.");
+        return Ok(());
+    }
+    println!(
+        "{:<22} {:<6} {:<20} {:<14} LAST SYNC",
+        "PLUGIN", "TYPE", "LOCAL", "REMOTE"
+    );
+    println!("{}", "-".repeat(80));
+    for (plugin, m, broken) in &rows {
+        let kind = match m.entity_type {
+            EntityType::Brief => "brief",
+            EntityType::Task => "task",
+        };
+        println!(
+            "{:<22} {:<6} {:<20} {:<14} {}{}",
+            plugin,
+            kind,
+            m.local_id,
+            m.remote_id,
+            m.last_sync.format("%Y-%m-%d %H:%M"),
+            if *broken { "  [broken]" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+fn map_add(
+    output: &Output,
+    plugin: &str,
+    entity_type: EntityType,
+    local: &str,
+    remote: &str,
+) -> Result<()> {
+    let project = Project::open_current()?;
+    let full_name = full_plugin_name(plugin);
+
+    let local_id = match entity_type {
+        EntityType::Task => {
+            let id = project.resolve_task_id(local)?;
+            if !project.task_store().read_all()?.contains_key(&id) {
+                return Err(CliError::not_found("Task", &id).into());
+            }
+            id.to_string()
+        }
+        EntityType::Brief => {
+            let id = project.resolve_brief_id(local)?;
+            if !project.brief_store().exists(&id) {
+                return Err(CliError::not_found("Brief", &id).into());
+            }
+            id.to_string()
+        }
+    };
+
+    let path = mapping_path(&project.sync_dir(), &full_name);
+    let mut mappings = read_mappings(&path)?;
+    if let Some(other) = mappings
+        .iter()
+        .find(|m| m.remote_id == remote && m.local_id != local_id)
+    {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!(
+                "Remote {} is already mapped to {}; remove that mapping first",
+                remote, other.local_id
+            ),
+        )
+        .into());
+    }
+
+    mappings.retain(|m| m.local_id != local_id);
+    mappings.push(IdMapping {
+        local_id: local_id.clone(),
+        remote_id: remote.to_string(),
+        entity_type,
+        last_sync: Utc::now(),
+    });
+    write_mappings(&path, &mappings)?;
+    record_external_ids(&project, plugin_system(&full_name), &mappings)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "plugin": full_name,
+            "local_id": local_id,
+            "remote_id": remote,
+            "entity_type": entity_type,
+        }));
+    } else {
+        output.success(&format!(
+            "Mapped {} to {} ({})",
+            local_id, remote, full_name
+        ));
+    }
+    Ok(())
+}
+
+fn map_remove(output: &Output, plugin: &str, local: Option<&str>, broken: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let full_name = full_plugin_name(plugin);
+    let path = mapping_path(&project.sync_dir(), &full_name);
+
+    let removed = if broken {
+        let existing = local_ids(&project)?;
+        prune_mappings(&path, |m| existing.contains(&m.local_id), false)?
+    } else {
+        let local = local.unwrap_or_default();
+        let mut mappings = read_mappings(&path)?;
+        // Accept the ID as typed or in its resolved form
+        let resolved = project
+            .resolve_task_id(local)
+            .map(|id| id.to_string())
+            .or_else(|_| project.resolve_brief_id(local).map(|id| id.to_string()))
+            .unwrap_or_else(|_| local.to_string());
+        let (removed, kept): (Vec<_>, Vec<_>) = mappings
+            .drain(..)
+            .partition(|m| m.local_id == local || m.local_id == resolved);
+        if removed.is_empty() {
+            return Err(CliError::new(
+                ErrorCode::NotFound,
+                format!("No {} mapping for {}", full_name, local),
+            )
+            .into());
+        }
+        write_mappings(&path, &kept)?;
+        removed
+    };
+    forget_external_ids(&project, plugin_system(&full_name), &removed)?;
+
+    if output.is_json() {
+        let ids: Vec<_> = removed.iter().map(|m| &m.local_id).collect();
+        output.data(&serde_json::json!({
+            "plugin": full_name,
+            "removed": ids,
+        }));
+    } else if removed.is_empty() {
+        println!("No broken mappings.");
+    } else {
+        for m in &removed {
+            output.success(&format!(
+                "Removed mapping {} -> {}",
+                m.local_id, m.remote_id
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn map_export(plugin: &str) -> Result<()> {
+    let project = Project::open_current()?;
+    let path = mapping_path(&project.sync_dir(), &full_plugin_name(plugin));
+    let mappings = read_mappings(&path)?;
+    println!("{}", serde_json::to_string_pretty(&mappings)?);
+    Ok(())
+}
+
+fn map_import(output: &Output, plugin: &str, file: &Path, replace: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let full_name = full_plugin_name(plugin);
+
+    let content = if file == Path::new("-") {
+        let mut buf = String::new();
+        std::io::stdin()
+            .read_to_string(&mut buf)
+            .context("Failed to read stdin")?;
+        buf
+    } else {
+        fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?
+    };
+    let imported: Vec<IdMapping> = serde_json::from_str(&content).map_err(|e| {
+        CliError::new(
+            ErrorCode::Validation,
+            format!("Invalid mappings JSON: {}", e),
+        )
+    })?;
+
+    let path = mapping_path(&project.sync_dir(), &full_name);
+    let mut mappings = if replace {
+        Vec::new()
+    } else {
+        read_mappings(&path)?
+    };
+    for mapping in &imported {
+        mappings.retain(|m| m.local_id != mapping.local_id);
+        mappings.push(mapping.clone());
+    }
+    write_mappings(&path, &mappings)?;
+    record_external_ids(&project, plugin_system(&full_name), &mappings)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "plugin": full_name,
+            "imported": imported.len(),
+            "total": mappings.len(),
+        }));
+    } else {
+        output.success(&format!(
+            "Imported {} mapping(s) into {} ({} total)",
+            imported.len(),
+            full_name,
+            mappings.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Clears the external IDs that removed mappings put on briefs and tasks
+fn forget_external_ids(project: &Project, system: &str, removed: &[IdMapping]) -> Result<()> {
+    let brief_store = project.brief_store();
+    let task_store = project.task_store();
+    let mut tasks = task_store.read_all()?;
+    let mut tasks_changed = false;
+
+    for mapping in removed {
+        match mapping.entity_type {
+            EntityType::Brief => {
+                let Ok(id) = mapping.local_id.parse::<BriefId>() else {
+                    continue;
+                };
+                if let Some(mut brief) = brief_store.read(&id)? {
+                    if brief.external_ids.get(system) == Some(&mapping.remote_id) {
+                        brief.remove_external_id(system);
+                        brief_store.write(&brief)?;
+                    }
+                }
+            }
+            EntityType::Task => {
+                let Ok(id) = mapping.local_id.parse::<TaskId>() else {
+                    continue;
+                };
+                if let Some(task) = tasks.get_mut(&id) {
+                    if task.external_ids.get(system) == Some(&mapping.remote_id) {
+                        task.remove_external_id(system);
+                        tasks_changed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    if tasks_changed {
+        task_store.write_all(&tasks)?;
+    }
+    Ok(())
+}
@@ -1,13 +1,35 @@
 //! Task CLI commands
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Subcommand;
 
-use super::output::Output;
-use crate::domain::{BriefId, DependencyGraph, Task, TaskId, TaskStatus};
-use crate::storage::Project;
+use super::agent;
+use super::brief;
+use super::duration;
+use super::editor;
+use super::error::{CliError, ErrorCode};
+use super::freeze::{ensure_brief_mutable, ensure_task_mutable};
+use super::gates;
+use super::gfm;
+use super::guard::{confirm_bulk, BulkChange};
+use super::output::{Output, OutputFormat};
+use super::page::PageArgs;
+use super::pipeline::{self, PipelineCommands};
+use super::render::{Color, Style, Theme};
+use super::reopen;
+use super::review;
+use super::split;
+use super::task_import;
+use super::template::{self, TemplateCommands};
+use super::undo;
+use crate::domain::{
+    by_id, by_rank, format_external_ids, place, DependencyGraph, GraphError, Progress, Task,
+    TaskId, TaskStatus, TaskTree, PRIORITY_META_KEY,
+};
+use crate::storage::{Project, TaskStore};
 
 #[derive(Subcommand)]
 pub enum TaskCommands {
@@ -17,6 +39,13 @@ pub enum TaskCommands {
     ///   shape task add "Fix typo"              # Standalone task
     ///   shape task add b-1234567 "Build API"   # Task under brief
     ///   shape task add b-1234567.1 "Subtask"   # Subtask under task
+    ///   shape task add github:42 --sub "Step"  # Subtask under any task reference
+    ///
+    /// A label with a `[description_templates]` entry in config.toml starts
+    /// the description from that scaffold.
+    ///
+    /// Example:
+    ///   shape task add b-7f2a3b1 "Crash on login" --label bug --edit
     Add {
         /// For standalone: just the title
         /// For brief tasks: parent ID (brief or task)
@@ -24,6 +53,45 @@ pub enum TaskCommands {
 
         /// Task title (when first arg is parent ID)
         second: Option<String>,
+
+        /// Label (repeatable), stored in meta.labels
+        #[arg(long = "label", short = 'l')]
+        labels: Vec<String>,
+
+        /// Task description (replaces any label scaffold)
+        #[arg(long, short = 'd')]
+        description: Option<String>,
+
+        /// Write the description in your editor, starting from the scaffold
+        #[arg(long)]
+        edit: bool,
+
+        /// Treat the first argument as the parent task, even when it doesn't
+        /// look like a task ID (e.g. an external reference)
+        #[arg(long, requires = "second")]
+        sub: bool,
+    },
+
+    /// Create tasks in bulk from JSON Lines or a markdown checklist
+    ///
+    /// Items refer to each other by position (from 1) or title, in
+    /// `depends_on` and `parent` fields or a trailing "(after: ...)" on a
+    /// checklist item. Indented checklist items become subtasks.
+    ///
+    /// Examples:
+    ///   shape task import plan.md --brief b-7f2a3b1
+    ///   make-plan | shape task import --brief b-7f2a3b1 --dry-run
+    Import {
+        /// JSON Lines or markdown file (default: stdin)
+        file: Option<PathBuf>,
+
+        /// Brief for the top-level tasks (omit for standalone tasks)
+        #[arg(long)]
+        brief: Option<String>,
+
+        /// Print the ID mapping without writing anything
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// List tasks (all, for a brief, or standalone only)
@@ -34,27 +102,102 @@ pub enum TaskCommands {
         /// Show only standalone tasks
         #[arg(long)]
         standalone: bool,
+
+        /// Also list tasks moved to the archive with their brief
+        #[arg(long)]
+        include_archived: bool,
+
+        #[command(flatten)]
+        page: PageArgs,
+    },
+
+    /// Show a task's subtasks (or a brief's tasks) as a tree with rolled-up
+    /// progress
+    ///
+    /// Progress counts leaf tasks: a task split into four subtasks weighs
+    /// four, and a done task counts its whole subtree as done.
+    ///
+    /// Example:
+    ///   shape task tree b-7f2a3b1.1
+    Tree {
+        /// Task or brief ID
+        id: String,
     },
 
-    /// Show task details
+    /// Show task details as a card (--short for a plain field list)
     Show {
         /// Task ID
         id: String,
+
+        /// Plain field list instead of the formatted card
+        #[arg(long)]
+        short: bool,
     },
 
-    /// Mark task as in progress
+    /// Mark one or more tasks as in progress
     Start {
-        /// Task ID
-        id: String,
+        /// Task IDs
+        #[arg(required = true)]
+        ids: Vec<String>,
+
+        /// Confirm changes above the bulk guardrail threshold
+        #[arg(long, short = 'y')]
+        yes: bool,
     },
 
-    /// Mark task as done
+    /// Mark one or more tasks as done
     Done {
+        /// Task IDs
+        #[arg(required = true)]
+        ids: Vec<String>,
+
+        /// Confirm changes above the bulk guardrail threshold
+        #[arg(long, short = 'y')]
+        yes: bool,
+
+        /// Complete tasks that don't pass their [gates]; recorded with the
+        /// reason in .shape/audit.jsonl
+        #[arg(
+            long,
+            value_name = "REASON",
+            num_args = 0..=1,
+            default_missing_value = ""
+        )]
+        skip_gates: Option<String>,
+    },
+
+    /// Reopen a completed task
+    ///
+    /// Done tasks that depended on it are listed; pick which to reopen too
+    /// with --dependents or --all-dependents, or at the prompt on a terminal.
+    ///
+    /// Example:
+    ///   shape task reopen b-7f2a3b1.1 --reason "Fails on empty input"
+    Reopen {
         /// Task ID
         id: String,
+
+        /// Why the task is being reopened (recorded in its history)
+        #[arg(long, short = 'r')]
+        reason: String,
+
+        /// Also reopen these completed dependents
+        #[arg(long, num_args = 1.., conflicts_with = "all_dependents")]
+        dependents: Vec<String>,
+
+        /// Also reopen every completed dependent
+        #[arg(long)]
+        all_dependents: bool,
     },
 
     /// Add a dependency between tasks
+    ///
+    /// A blocking dependency can carry a lag for schedule projections
+    /// (`simulate`): --lag 2d for bake time after the dependency finishes,
+    /// --lead 1d to overlap with its end. Adding it again changes the lag.
+    ///
+    /// Example:
+    ///   shape task dep b-7f2a3b1.4 b-7f2a3b1.3 --lag 2d
     Dep {
         /// Task that will be blocked (or linked)
         task: String,
@@ -77,6 +220,14 @@ pub enum TaskCommands {
         /// Create a duplicates link (t-2 duplicates t-1)
         #[arg(long, conflicts_with_all = ["blocks", "from", "related"])]
         duplicates: bool,
+
+        /// Start this long after the dependency finishes (rounded up to days)
+        #[arg(long, value_name = "DURATION", conflicts_with_all = ["from", "related", "duplicates", "lead"])]
+        lag: Option<String>,
+
+        /// Start this long before the dependency finishes (rounded up to days)
+        #[arg(long, value_name = "DURATION", conflicts_with_all = ["from", "related", "duplicates"])]
+        lead: Option<String>,
     },
 
     /// Remove a dependency
@@ -135,6 +286,29 @@ pub enum TaskCommands {
         close: bool,
     },
 
+    /// Split an oversized task into subtasks that take over its dependencies
+    ///
+    /// The subtasks inherit what the task waited on, and tasks waiting on it
+    /// wait on the subtasks instead. The task stays as a container that
+    /// becomes ready once every subtask is done. Without --title, titles are
+    /// asked for one per line.
+    ///
+    /// Example:
+    ///   shape task split b-1234567.3 --title "Schema" --title "API" --sequential
+    Split {
+        /// Task ID
+        id: String,
+
+        /// Subtask title (repeatable, in order)
+        #[arg(long = "title", short = 't')]
+        titles: Vec<String>,
+
+        /// Chain the subtasks in order: the first inherits the task's
+        /// dependencies and dependents wait on the last
+        #[arg(long)]
+        sequential: bool,
+    },
+
     /// Set task metadata
     Meta {
         /// Task ID
@@ -146,13 +320,196 @@ pub enum TaskCommands {
         /// Metadata value (JSON)
         value: String,
     },
+
+    /// Set or clear a task's due date
+    ///
+    /// A date is due at the end of that day (UTC); a duration counts from
+    /// now.
+    ///
+    /// Examples:
+    ///   shape task due b-1234567.1 2024-07-01
+    ///   shape task due b-1234567.1 3d
+    ///   shape task due b-1234567.1 --clear
+    Due {
+        /// Task ID
+        id: String,
+
+        /// Date (YYYY-MM-DD), RFC 3339 timestamp or duration from now (3d)
+        #[arg(required_unless_present = "clear", conflicts_with = "clear")]
+        when: Option<String>,
+
+        /// Remove the due date
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Edit a task's title, description, priority or metadata
+    ///
+    /// Without any flags, opens the task as TOML in your editor and saves
+    /// what you change.
+    ///
+    /// Examples:
+    ///   shape task edit b-1234567.1 --title "Build REST API" --priority high
+    ///   shape task edit b-1234567.1 --meta estimate=3 --meta owner=alice
+    ///   shape task edit b-1234567.1
+    Edit {
+        /// Task ID
+        id: String,
+
+        /// New title
+        #[arg(long)]
+        title: Option<String>,
+
+        /// New description (an empty one clears it)
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Priority
+        #[arg(long, value_parser = ["high", "medium", "low"])]
+        priority: Option<String>,
+
+        /// Metadata as key=value, the value parsed as JSON if it can be (repeatable)
+        #[arg(long = "meta", value_name = "KEY=VALUE")]
+        meta: Vec<String>,
+    },
+
+    /// Tick off a checklist item of the task's definition of done
+    ///
+    /// Without an item, lists the task's completion gates and whether each
+    /// is met.
+    ///
+    /// Examples:
+    ///   shape task check b-1234567.1 "Docs updated"
+    ///   shape task check b-1234567.1
+    Check {
+        /// Task ID
+        id: String,
+
+        /// Checklist item, as written in the definition of done
+        item: Option<String>,
+
+        /// Untick the item instead
+        #[arg(long, requires = "item")]
+        uncheck: bool,
+    },
+
+    /// Move a task within its brief's backlog order
+    ///
+    /// Ranked tasks are listed first by `task list` and break score ties in
+    /// `next`.
+    ///
+    /// Example:
+    ///   shape task rank b-1234567.3 --before b-1234567.1
+    #[command(group(clap::ArgGroup::new("anchor").required(true)))]
+    Rank {
+        /// Task to move
+        id: String,
+
+        /// Place directly before this task
+        #[arg(long, group = "anchor")]
+        before: Option<String>,
+
+        /// Place directly after this task
+        #[arg(long, group = "anchor")]
+        after: Option<String>,
+    },
+
+    /// Record the task's ID in an external tool
+    ///
+    /// Example:
+    ///   shape task external b-1234567.1 jira:ABC-123
+    External {
+        /// Task ID
+        id: String,
+
+        /// External ID as system:key (just the system with --remove)
+        external: String,
+
+        /// Forget the ID for this system
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// Instantiate reusable task pipelines
+    #[command(subcommand)]
+    Pipeline(PipelineCommands),
+
+    /// Manage reusable task templates
+    #[command(subcommand)]
+    Template(TemplateCommands),
+
+    /// Create a task from a template
+    ///
+    /// Example:
+    ///   shape task from-template code-review --brief b-1234567 --set pr=412
+    FromTemplate {
+        /// Template name
+        name: String,
+
+        /// Parent brief or task ID (omit for a standalone task)
+        #[arg(long, alias = "parent")]
+        brief: Option<String>,
+
+        /// Template parameter as key=value (repeatable)
+        #[arg(long)]
+        set: Vec<String>,
+    },
+}
+
+impl TaskCommands {
+    /// Name the command is journaled under for `shape undo`, or `None` for
+    /// commands that don't change tasks
+    fn journal_name(&self) -> Option<&'static str> {
+        Some(match self {
+            TaskCommands::List { .. }
+            | TaskCommands::Tree { .. }
+            | TaskCommands::Show { .. }
+            | TaskCommands::Template(_) => return None,
+            TaskCommands::Add { .. } => "task add",
+            TaskCommands::Import { .. } => "task import",
+            TaskCommands::Start { .. } => "task start",
+            TaskCommands::Done { .. } => "task done",
+            TaskCommands::Reopen { .. } => "task reopen",
+            TaskCommands::Dep { .. } => "task dep",
+            TaskCommands::Undep { .. } => "task undep",
+            TaskCommands::Link { .. } => "task link",
+            TaskCommands::Provenance { .. } => "task provenance",
+            TaskCommands::Dup { .. } => "task dup",
+            TaskCommands::Split { .. } => "task split",
+            TaskCommands::Meta { .. } => "task meta",
+            TaskCommands::Due { .. } => "task due",
+            TaskCommands::Edit { .. } => "task edit",
+            TaskCommands::Check { .. } => "task check",
+            TaskCommands::Rank { .. } => "task rank",
+            TaskCommands::External { .. } => "task external",
+            TaskCommands::Pipeline(_) => "task pipeline",
+            TaskCommands::FromTemplate { .. } => "task from-template",
+        })
+    }
 }
 
+/// Formats `shape task list` supports beyond text and JSON
+pub(super) const LIST_FORMATS: &[OutputFormat] = &[OutputFormat::Gfm];
+
 pub fn run(cmd: TaskCommands, output: &Output) -> Result<()> {
+    match cmd.journal_name() {
+        Some(name) => undo::journaled(name, || execute(cmd, output)),
+        None => execute(cmd, output),
+    }
+}
+
+fn execute(cmd: TaskCommands, output: &Output) -> Result<()> {
     use crate::domain::DependencyType;
 
     match cmd {
-        TaskCommands::Add { first, second } => {
+        TaskCommands::Add {
+            first,
+            second,
+            labels,
+            description,
+            edit,
+            sub,
+        } => {
             // Determine if this is standalone or brief-based based on arguments:
             // - One arg: standalone task with title = first
             // - Two args: brief task with parent = first, title = second
@@ -160,14 +517,50 @@ pub fn run(cmd: TaskCommands, output: &Output) -> Result<()> {
                 Some(title) => (Some(first.as_str()), title),
                 None => (None, first),
             };
-            add_task(output, parent, &title)
+            add_task(output, parent, &title, &labels, description, edit, sub)
+        }
+        TaskCommands::Import {
+            file,
+            brief,
+            dry_run,
+        } => task_import::run(output, file.as_deref(), brief.as_deref(), dry_run),
+        TaskCommands::List {
+            brief,
+            standalone,
+            include_archived,
+            page,
+        } => list_tasks(
+            output,
+            brief.as_deref(),
+            standalone,
+            include_archived,
+            &page,
+        ),
+        TaskCommands::Tree { id } => show_tree(output, &id),
+        TaskCommands::Show { id, short } => show_task(output, &id, short),
+        TaskCommands::Start { ids, yes } => {
+            transition_tasks(output, &ids, TaskStatus::InProgress, yes, None)
         }
-        TaskCommands::List { brief, standalone } => {
-            list_tasks(output, brief.as_deref(), standalone)
+        TaskCommands::Done {
+            ids,
+            yes,
+            skip_gates,
+        } => transition_tasks(output, &ids, TaskStatus::Done, yes, skip_gates.as_deref()),
+        TaskCommands::Reopen {
+            id,
+            reason,
+            dependents,
+            all_dependents,
+        } => {
+            let dependents = if all_dependents {
+                reopen::Dependents::All
+            } else if dependents.is_empty() {
+                reopen::Dependents::Prompt
+            } else {
+                reopen::Dependents::Only(dependents)
+            };
+            reopen::run(output, &id, &reason, dependents)
         }
-        TaskCommands::Show { id } => show_task(output, &id),
-        TaskCommands::Start { id } => start_task(output, &id),
-        TaskCommands::Done { id } => complete_task(output, &id),
         TaskCommands::Dep {
             task,
             depends_on,
@@ -175,7 +568,14 @@ pub fn run(cmd: TaskCommands, output: &Output) -> Result<()> {
             from,
             related,
             duplicates,
+            lag,
+            lead,
         } => {
+            let lag_days = match (lag, lead) {
+                (Some(lag), _) => whole_days(duration::parse_duration(&lag)?),
+                (None, Some(lead)) => -whole_days(duration::parse_duration(&lead)?),
+                (None, None) => 0,
+            };
             let dep_type = if from {
                 DependencyType::Provenance
             } else if related {
@@ -185,7 +585,7 @@ pub fn run(cmd: TaskCommands, output: &Output) -> Result<()> {
             } else {
                 DependencyType::Blocks // default
             };
-            add_typed_dependency(output, &task, &depends_on, dep_type)
+            add_typed_dependency(output, &task, &depends_on, dep_type, lag_days)
         }
         TaskCommands::Undep {
             task,
@@ -209,107 +609,208 @@ pub fn run(cmd: TaskCommands, output: &Output) -> Result<()> {
             remove_typed_dependency(output, &task, &depends_on, dep_type)
         }
         TaskCommands::Link { task, related_to } => {
-            add_typed_dependency(output, &task, &related_to, DependencyType::Related)
+            add_typed_dependency(output, &task, &related_to, DependencyType::Related, 0)
         }
         TaskCommands::Provenance { task, from } => {
-            add_typed_dependency(output, &task, &from, DependencyType::Provenance)
+            add_typed_dependency(output, &task, &from, DependencyType::Provenance, 0)
         }
         TaskCommands::Dup {
             task,
             original,
             close,
         } => add_duplicate(output, &task, &original, close),
+        TaskCommands::Split {
+            id,
+            titles,
+            sequential,
+        } => split::run(output, &id, titles, sequential),
         TaskCommands::Meta { id, key, value } => set_meta(output, &id, &key, &value),
+        TaskCommands::Due { id, when, .. } => set_due(output, &id, when.as_deref()),
+        TaskCommands::Edit {
+            id,
+            title,
+            description,
+            priority,
+            meta,
+        } => {
+            let fields = EditFields {
+                title,
+                description,
+                priority,
+                meta,
+            };
+            edit_task(output, &id, fields)
+        }
+        TaskCommands::Check { id, item, uncheck } => {
+            gates::check(output, &id, item.as_deref(), uncheck)
+        }
+        TaskCommands::Rank { id, before, after } => match (before, after) {
+            (Some(anchor), _) => rank_task(output, &id, &anchor, false),
+            (None, Some(anchor)) => rank_task(output, &id, &anchor, true),
+            (None, None) => unreachable!("clap requires --before or --after"),
+        },
+        TaskCommands::External {
+            id,
+            external,
+            remove,
+        } => set_external_id(output, &id, &external, remove),
+        TaskCommands::Pipeline(cmd) => pipeline::run(cmd, output),
+        TaskCommands::Template(cmd) => template::run(cmd, output),
+        TaskCommands::FromTemplate { name, brief, set } => {
+            template::from_template(output, &name, brief.as_deref(), &set)
+        }
     }
 }
 
-fn add_task(output: &Output, parent_str: Option<&str>, title: &str) -> Result<()> {
-    use chrono::Utc;
-
+fn add_task(
+    output: &Output,
+    parent_str: Option<&str>,
+    title: &str,
+    labels: &[String],
+    description: Option<String>,
+    edit: bool,
+    sub: bool,
+) -> Result<()> {
     let project = Project::open_current()?;
+
+    let task_id = match parent_str {
+        Some(parent) if sub => allocate_subtask_id(&project, parent)?,
+        _ => allocate_task_id(&project, parent_str, title)?,
+    };
+    let mut task = Task::new(task_id.clone(), title);
+    if !labels.is_empty() {
+        task.set_meta("labels", labels);
+    }
+    let mut description = description.or_else(|| {
+        project
+            .config()
+            .project
+            .description_template(labels)
+            .map(str::to_string)
+    });
+    if edit {
+        let text = editor::edit(
+            &project,
+            "TASK_DESCRIPTION.md",
+            description.as_deref().unwrap_or_default(),
+        )?;
+        description = (!text.is_empty()).then_some(text);
+    }
+    task.description = description;
+    let spawned_from = agent::save_new_task(&project, &mut task)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": task.id.to_string(),
+            "title": task.title,
+            "status": task.status,
+            "standalone": task.is_standalone(),
+            "description": task.description,
+            "spawned_from": spawned_from.map(|id| id.to_string()),
+        }));
+    } else {
+        output.success(&format!("Created task: {} - {}", task.id, task.title));
+        if let Some(parent) = spawned_from {
+            println!("  Provenance: spawned from claimed task {}", parent);
+        }
+    }
+
+    Ok(())
+}
+
+/// Picks the ID for a new task: standalone, under a brief, or a subtask
+pub(super) fn allocate_task_id(
+    project: &Project,
+    parent_str: Option<&str>,
+    title: &str,
+) -> Result<TaskId> {
     let store = project.task_store();
 
     let task_id = match parent_str {
         None => {
             // No parent - create standalone task
-            TaskId::new_standalone(title, Utc::now())
+            project.new_standalone_task_id(title)?
         }
         Some(parent) => {
             // Check if parent is a task ID (contains '.' or starts with 't-')
             if parent.contains('.') || parent.starts_with("t-") {
-                // Parent is a task - create subtask
-                let parent_id: TaskId = parent.parse()?;
-                let tasks = store.read_all()?;
-
-                // Find max subtask sequence for this parent
-                let max_seq = tasks
-                    .values()
-                    .filter(|t| t.id.parent().as_ref() == Some(&parent_id))
-                    .map(|t| *t.id.segments().last().unwrap_or(&0))
-                    .max()
-                    .unwrap_or(0);
-
-                parent_id.subtask(max_seq + 1)
+                allocate_subtask_id(project, parent)?
             } else {
                 // Parent is a brief - create top-level task under brief
-                let brief_id: BriefId = parent.parse()?;
-
-                // Verify brief exists
-                let brief_store = project.brief_store();
-                if !brief_store.exists(&brief_id) {
-                    anyhow::bail!("Brief not found: {}", brief_id);
-                }
-
-                let tasks = store.read_for_brief(&brief_id)?;
+                let brief_id = project.resolve_brief_id(parent)?;
 
-                // Find max task sequence for this brief
-                let max_seq = tasks
-                    .values()
-                    .filter(|t| t.id.depth() == 1)
-                    .map(|t| *t.id.segments().first().unwrap_or(&0))
-                    .max()
-                    .unwrap_or(0);
+                let brief = project
+                    .brief_store()
+                    .read(&brief_id)?
+                    .ok_or_else(|| CliError::not_found("Brief", &brief_id))?;
+                ensure_brief_mutable(project, &brief)?;
 
-                TaskId::new(&brief_id, max_seq + 1)
+                store.next_task_id(&brief_id)?
             }
         }
     };
 
-    let task = Task::new(task_id.clone(), title);
-    store.append(&task)?;
-
-    if output.is_json() {
-        output.data(&serde_json::json!({
-            "id": task.id.to_string(),
-            "title": task.title,
-            "status": task.status,
-            "standalone": task.is_standalone(),
-        }));
-    } else {
-        output.success(&format!("Created task: {} - {}", task.id, task.title));
-    }
-
-    Ok(())
+    Ok(task_id)
 }
 
-fn list_tasks(output: &Output, brief_str: Option<&str>, standalone_only: bool) -> Result<()> {
-    let project = Project::open_current()?;
+/// Picks the next subtask ID under an existing, unfrozen task
+pub(super) fn allocate_subtask_id(project: &Project, parent_str: &str) -> Result<TaskId> {
+    let parent_id = project.resolve_task_id(parent_str)?;
     let store = project.task_store();
+    let parent = store
+        .read_all()?
+        .remove(&parent_id)
+        .ok_or_else(|| CliError::not_found("Task", &parent_id))?;
+    ensure_task_mutable(project, &parent)?;
+    store.next_subtask_id(&parent_id)
+}
 
-    let tasks = if standalone_only {
-        store.read_standalone()?
-    } else if let Some(brief_str) = brief_str {
-        let brief_id: BriefId = brief_str.parse()?;
-        store.read_for_brief(&brief_id)?
-    } else {
-        store.read_all()?
+fn list_tasks(
+    output: &Output,
+    brief_str: Option<&str>,
+    standalone_only: bool,
+    include_archived: bool,
+    paging: &PageArgs,
+) -> Result<()> {
+    let project = Project::open_current()?;
+    let brief_id = match brief_str {
+        Some(brief_str) if !standalone_only => Some(project.resolve_brief_id(brief_str)?),
+        _ => None,
+    };
+    let read = |store: TaskStore| -> Result<HashMap<TaskId, Task>> {
+        if standalone_only {
+            store.read_standalone()
+        } else if let Some(brief_id) = &brief_id {
+            store.read_for_brief(brief_id)
+        } else {
+            store.read_all()
+        }
     };
 
-    if output.is_json() {
-        let items: Vec<_> = tasks
-            .values()
+    let mut tasks = read(project.task_store())?;
+    let mut archived: HashSet<TaskId> = HashSet::new();
+    if include_archived {
+        let extra = read(project.archived_task_store())?;
+        archived.extend(extra.keys().cloned());
+        tasks.extend(extra);
+    }
+
+    // Backlog order (ranked first, then by ID) so pages are stable
+    let sorted = by_rank(tasks.values());
+    let page = paging.paginate(sorted)?;
+
+    if output.format() == OutputFormat::Gfm {
+        let briefs = project.brief_store().read_all_meta()?;
+        print!(
+            "{}",
+            gfm::task_list(&page.items, &briefs, brief_str.is_none())
+        );
+    } else if output.is_json() {
+        let items: Vec<_> = page
+            .items
+            .iter()
             .map(|t| {
-                serde_json::json!({
+                let mut item = serde_json::json!({
                     "id": t.id.to_string(),
                     "title": t.title,
                     "status": t.status,
@@ -319,12 +820,17 @@ fn list_tasks(output: &Output, brief_str: Option<&str>, standalone_only: bool) -
                         serde_json::json!({
                             "task": d.task.to_string(),
                             "type": d.dep_type,
+                            "lag_days": d.lag_days,
                         })
                     }).collect::<Vec<_>>(),
-                })
+                });
+                if archived.contains(&t.id) {
+                    item["archived"] = serde_json::json!(true);
+                }
+                item
             })
             .collect();
-        output.data(&items);
+        output.data(&page.json(items));
     } else if tasks.is_empty() {
         if standalone_only {
             println!("No standalone tasks");
@@ -334,36 +840,167 @@ fn list_tasks(output: &Output, brief_str: Option<&str>, standalone_only: bool) -
             println!("No tasks");
         }
     } else {
-        println!("{:<20} {:<12} TITLE", "ID", "STATUS");
+        let theme = Theme::new(&project.config().project.display);
+        let style = Style::stdout().with_theme(&theme);
+        println!(
+            "{}{:<20} {:<12} TITLE",
+            " ".repeat(theme.lead_width()),
+            "ID",
+            "STATUS"
+        );
         println!("{}", "-".repeat(60));
 
-        // Sort by ID
-        let mut sorted: Vec<_> = tasks.values().collect();
-        sorted.sort_by(|a, b| a.id.to_string().cmp(&b.id.to_string()));
-
-        for task in sorted {
+        for task in &page.items {
             let status = match task.status {
                 TaskStatus::Todo => "todo",
                 TaskStatus::InProgress => "in_progress",
                 TaskStatus::Done => "done",
             };
-            println!("{:<20} {:<12} {}", task.id, status, task.title);
+            let lead = style.paint(
+                &theme.lead(&theme.task_icon(task.status)),
+                status_color(task.status),
+            );
+            let marker = if archived.contains(&task.id) {
+                " (archived)"
+            } else {
+                ""
+            };
+            println!(
+                "{}{:<20} {:<12} {}{}{}",
+                lead,
+                task.id,
+                status,
+                task.title,
+                label_badges(&style, &theme, task),
+                marker
+            );
+        }
+        page.print_hint();
+    }
+
+    Ok(())
+}
+
+fn show_tree(output: &Output, id_str: &str) -> Result<()> {
+    let project = Project::open_current()?;
+    let tasks = project.task_store().read_all()?;
+
+    // A task reference shows that task's subtree, anything else a brief's tasks
+    let (tree, roots, brief) = match project.resolve_task_id(id_str) {
+        Ok(id) => {
+            let task = tasks
+                .get(&id)
+                .ok_or_else(|| CliError::not_found("Task", &id))?;
+            (TaskTree::new(tasks.values()), vec![task], None)
+        }
+        Err(_) => {
+            let id = project.resolve_brief_id(id_str)?;
+            let brief = project
+                .brief_store()
+                .read(&id)?
+                .ok_or_else(|| CliError::not_found("Brief", &id))?;
+            let tree = TaskTree::new(tasks.values().filter(|t| t.brief_id() == Some(id.clone())));
+            let roots = tree.roots().to_vec();
+            (tree, roots, Some(brief))
+        }
+    };
+    let summary = |p: Progress| format!("{}/{} ({}%)", p.done, p.total, p.percent());
+
+    if output.is_json() {
+        fn node(tree: &TaskTree, task: &Task) -> serde_json::Value {
+            serde_json::json!({
+                "id": task.id.to_string(),
+                "title": task.title,
+                "status": task.status,
+                "rollup_status": tree.rollup_status(task),
+                "progress": progress_json(tree.progress(task)),
+                "subtasks": tree
+                    .children(&task.id)
+                    .iter()
+                    .map(|c| node(tree, c))
+                    .collect::<Vec<_>>(),
+            })
         }
+        match &brief {
+            Some(brief) => output.data(&serde_json::json!({
+                "brief_id": brief.id.to_string(),
+                "title": brief.title,
+                "progress": progress_json(tree.total_progress()),
+                "tasks": roots.iter().map(|t| node(&tree, t)).collect::<Vec<_>>(),
+            })),
+            None => output.data(&node(&tree, roots[0])),
+        }
+        return Ok(());
     }
 
+    let theme = Theme::new(&project.config().project.display);
+    let style = Style::stdout().with_theme(&theme);
+    let label = |task: &Task| {
+        let rollup = tree.rollup_status(task);
+        let mut line = format!(
+            "{} {}  {}",
+            status_marker(&style, &theme, rollup),
+            task.id,
+            task.title
+        );
+        if !tree.children(&task.id).is_empty() {
+            let mut progress = summary(tree.progress(task));
+            if rollup.is_complete() && !task.status.is_complete() {
+                progress.push_str(", all subtasks done");
+            }
+            line.push_str(&format!("  {}", style.paint(&progress, Color::Dim)));
+        }
+        line
+    };
+    if let Some(brief) = &brief {
+        println!(
+            "{}  {}  {}",
+            brief.id,
+            style.bold(&brief.title),
+            style.paint(&summary(tree.total_progress()), Color::Dim)
+        );
+        if roots.is_empty() {
+            println!("No tasks.");
+        }
+    }
+    for task in roots {
+        println!("{}", label(task));
+        print_subtasks(&tree, task, "", &label);
+    }
     Ok(())
 }
 
-fn show_task(output: &Output, id_str: &str) -> Result<()> {
+/// `{"done", "total", "percent"}` for JSON output
+pub(super) fn progress_json(progress: Progress) -> serde_json::Value {
+    serde_json::json!({
+        "done": progress.done,
+        "total": progress.total,
+        "percent": progress.percent(),
+    })
+}
+
+/// Draws the subtasks of `task` below it, with box-drawing branches
+fn print_subtasks(tree: &TaskTree, task: &Task, prefix: &str, label: &dyn Fn(&Task) -> String) {
+    let children = tree.children(&task.id);
+    for (i, child) in children.iter().enumerate() {
+        let last = i + 1 == children.len();
+        let branch = if last { "└─" } else { "├─" };
+        println!("{}{} {}", prefix, branch, label(child));
+        let below = format!("{}{}", prefix, if last { "   " } else { "│  " });
+        print_subtasks(tree, child, &below, label);
+    }
+}
+
+fn show_task(output: &Output, id_str: &str, short: bool) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.task_store();
 
-    let id: TaskId = id_str.parse()?;
+    let id = project.resolve_task_id(id_str)?;
     let tasks = store.read_all()?;
 
     let task = tasks
         .get(&id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
 
     // Build status map for ready/blocked calculation
     let statuses: HashMap<TaskId, TaskStatus> =
@@ -383,11 +1020,14 @@ fn show_task(output: &Output, id_str: &str) -> Result<()> {
                 serde_json::json!({
                     "task": d.task.to_string(),
                     "type": d.dep_type,
+                    "lag_days": d.lag_days,
                 })
             }).collect::<Vec<_>>(),
             "created_at": task.created_at,
             "updated_at": task.updated_at,
             "completed_at": task.completed_at,
+            "due_at": task.due_at,
+            "overdue": task.is_overdue(chrono::Utc::now()),
             "description": task.description,
             "meta": task.meta,
             "is_ready": is_ready,
@@ -398,174 +1038,577 @@ fn show_task(output: &Output, id_str: &str) -> Result<()> {
             "notes": task.notes,
             "links": task.links,
             "blocked": task.blocked,
+            "frozen": task.frozen,
+            "rank": task.rank,
             "history": task.history,
             "assigned_to": task.assigned_to,
+            "external_ids": task.external_ids,
         }));
+    } else if short {
+        print_short(task, &tasks, &statuses, is_ready, is_blocked);
     } else {
-        println!("Task: {}", task.id);
-        println!("Title: {}", task.title);
-        println!("Status: {:?}", task.status);
-        if let Some(brief) = task.brief_id() {
-            println!("Brief: {}", brief);
-        } else {
-            println!("Type: Standalone");
+        let claim_timeout = project.config().project.agent.claim_timeout_hours;
+        let theme = Theme::new(&project.config().project.display);
+        print_card(
+            task,
+            &tasks,
+            &statuses,
+            &theme,
+            is_ready,
+            is_blocked,
+            claim_timeout,
+        );
+    }
+
+    Ok(())
+}
+
+/// Minimal `task show` output (`--short`)
+fn print_short(
+    task: &Task,
+    tasks: &HashMap<TaskId, Task>,
+    statuses: &HashMap<TaskId, TaskStatus>,
+    is_ready: bool,
+    is_blocked: bool,
+) {
+    println!("Task: {}", task.id);
+    println!("Title: {}", task.title);
+    println!("Status: {:?}", task.status);
+    if let Some(brief) = task.brief_id() {
+        println!("Brief: {}", brief);
+    } else {
+        println!("Type: Standalone");
+    }
+    if !task.external_ids.is_empty() {
+        println!("External: {}", format_external_ids(&task.external_ids));
+    }
+    if let Some(frozen) = &task.frozen {
+        println!("Frozen: {} (by {})", frozen.reason, frozen.by);
+    }
+    println!("Created: {}", task.created_at.format("%Y-%m-%d %H:%M"));
+    println!("Updated: {}", task.updated_at.format("%Y-%m-%d %H:%M"));
+
+    if let Some(completed) = task.completed_at {
+        println!("Completed: {}", completed.format("%Y-%m-%d %H:%M"));
+    }
+    if let Some(due) = task.due_at {
+        println!("Due: {}", due.format("%Y-%m-%d %H:%M"));
+    }
+
+    // Display dependencies grouped by type
+    use crate::domain::DependencyType;
+    let blocking: Vec<_> = task.depends_on.by_type(DependencyType::Blocks).collect();
+    let provenance: Vec<_> = task
+        .depends_on
+        .by_type(DependencyType::Provenance)
+        .collect();
+    let related: Vec<_> = task.depends_on.by_type(DependencyType::Related).collect();
+    let duplicates: Vec<_> = task
+        .depends_on
+        .by_type(DependencyType::Duplicates)
+        .collect();
+
+    if !blocking.is_empty() {
+        println!("\nDependencies:");
+        for dep in &blocking {
+            let dep_status = statuses
+                .get(&dep.task)
+                .map(|s| format!("{:?}", s))
+                .unwrap_or_else(|| "?".to_string());
+            println!(
+                "  [{}] {}: {} ({}){}",
+                dep.dep_type.label(),
+                dep.task,
+                tasks
+                    .get(&dep.task)
+                    .map(|t| t.title.as_str())
+                    .unwrap_or("?"),
+                dep_status,
+                lag_label(dep.lag_days)
+            );
         }
-        println!("Created: {}", task.created_at.format("%Y-%m-%d %H:%M"));
-        println!("Updated: {}", task.updated_at.format("%Y-%m-%d %H:%M"));
+    }
 
-        if let Some(completed) = task.completed_at {
-            println!("Completed: {}", completed.format("%Y-%m-%d %H:%M"));
+    if !provenance.is_empty() {
+        println!("\nProvenance:");
+        for dep in &provenance {
+            println!(
+                "  [{}] {}: {}",
+                dep.dep_type.label(),
+                dep.task,
+                tasks
+                    .get(&dep.task)
+                    .map(|t| t.title.as_str())
+                    .unwrap_or("?")
+            );
         }
+    }
 
-        // Display dependencies grouped by type
-        use crate::domain::DependencyType;
-        let blocking: Vec<_> = task.depends_on.by_type(DependencyType::Blocks).collect();
-        let provenance: Vec<_> = task
-            .depends_on
-            .by_type(DependencyType::Provenance)
-            .collect();
-        let related: Vec<_> = task.depends_on.by_type(DependencyType::Related).collect();
-        let duplicates: Vec<_> = task
-            .depends_on
-            .by_type(DependencyType::Duplicates)
-            .collect();
+    if !related.is_empty() {
+        println!("\nRelated:");
+        for dep in &related {
+            println!(
+                "  [{}] {}: {}",
+                dep.dep_type.label(),
+                dep.task,
+                tasks
+                    .get(&dep.task)
+                    .map(|t| t.title.as_str())
+                    .unwrap_or("?")
+            );
+        }
+    }
 
-        if !blocking.is_empty() {
-            println!("\nDependencies:");
-            for dep in &blocking {
-                let dep_status = statuses
+    if !duplicates.is_empty() {
+        println!("\nDuplicates:");
+        for dep in &duplicates {
+            println!(
+                "  [{}] {}: {} (WARNING: may be duplicate)",
+                dep.dep_type.label(),
+                dep.task,
+                tasks
                     .get(&dep.task)
-                    .map(|s| format!("{:?}", s))
-                    .unwrap_or_else(|| "?".to_string());
-                println!(
-                    "  [{}] {}: {} ({})",
-                    dep.dep_type.label(),
-                    dep.task,
-                    tasks
-                        .get(&dep.task)
-                        .map(|t| t.title.as_str())
-                        .unwrap_or("?"),
-                    dep_status
-                );
-            }
+                    .map(|t| t.title.as_str())
+                    .unwrap_or("?")
+            );
         }
+    }
 
-        if !provenance.is_empty() {
-            println!("\nProvenance:");
-            for dep in &provenance {
-                println!(
-                    "  [{}] {}: {}",
-                    dep.dep_type.label(),
-                    dep.task,
-                    tasks
-                        .get(&dep.task)
-                        .map(|t| t.title.as_str())
-                        .unwrap_or("?")
-                );
-            }
+    if let Some(desc) = &task.description {
+        println!("\nDescription:");
+        println!("{}", desc);
+    }
+
+    if !task.meta.is_empty() {
+        println!("\nMetadata:");
+        for (key, value) in task.meta.iter() {
+            println!("  {}: {}", key, value);
         }
+    }
 
-        if !related.is_empty() {
-            println!("\nRelated:");
-            for dep in &related {
-                println!(
-                    "  [{}] {}: {}",
-                    dep.dep_type.label(),
-                    dep.task,
-                    tasks
-                        .get(&dep.task)
-                        .map(|t| t.title.as_str())
-                        .unwrap_or("?")
-                );
-            }
+    println!();
+    if is_ready {
+        println!("Status: READY (all dependencies complete)");
+    } else if is_blocked {
+        println!("Status: BLOCKED (waiting on dependencies)");
+    }
+}
+
+/// History events and notes shown on the card
+const CARD_RECENT: usize = 5;
+
+/// Levels of blocking dependencies drawn in the card's tree
+const CARD_TREE_DEPTH: usize = 3;
+
+/// `task show` card: badges, key fields, rendered description, dependency
+/// tree, claim lease and recent activity
+fn print_card(
+    task: &Task,
+    tasks: &HashMap<TaskId, Task>,
+    statuses: &HashMap<TaskId, TaskStatus>,
+    theme: &Theme,
+    is_ready: bool,
+    is_blocked: bool,
+    claim_timeout: u32,
+) {
+    use crate::domain::DependencyType;
+
+    let style = Style::stdout().with_theme(theme);
+    let rule = "─".repeat(style.width.min(72));
+
+    println!(
+        "{}  {}",
+        style.bold(&task.id.to_string()),
+        style.bold(&task.title)
+    );
+    let mut badges = vec![status_badge(&style, task.status)];
+    if let Some(priority) = task.get_meta("priority").and_then(|v| v.as_str()) {
+        let color = match priority {
+            "urgent" | "critical" | "high" => Color::Red,
+            "medium" => Color::Yellow,
+            _ => Color::Dim,
+        };
+        badges.push(style.badge(priority, color));
+    }
+    if task.blocked.is_some() || is_blocked {
+        badges.push(style.badge("blocked", Color::Red));
+    } else if is_ready {
+        badges.push(style.badge("ready", Color::Green));
+    }
+    if task.frozen.is_some() {
+        badges.push(style.badge("frozen", Color::Blue));
+    }
+    if task.is_container() {
+        badges.push(style.badge("container", Color::Cyan));
+    }
+    for label in task.labels() {
+        badges.push(style.badge(label, theme.label_color(label).unwrap_or(Color::Dim)));
+    }
+    println!("{}", badges.join(" "));
+    println!("{}", style.paint(&rule, Color::Dim));
+
+    match task.brief_id() {
+        Some(brief) => println!("Brief: {}", brief),
+        None => println!("Type: Standalone"),
+    }
+    if let Some(assignee) = &task.assigned_to {
+        println!("Assigned: {}", assignee);
+    }
+    if let Some(agent) = &task.claimed_by {
+        let lease = match task.claim_remaining_hours(claim_timeout) {
+            Some(h) if h > 0.0 => format!("lease {:.1}h left", h),
+            Some(_) => style.paint("lease expired", Color::Red),
+            None => "no lease".to_string(),
+        };
+        let since = task
+            .claimed_at
+            .map(|at| format!(" since {}", at.format("%Y-%m-%d %H:%M")))
+            .unwrap_or_default();
+        println!("Claimed: {}{} ({})", agent, since, lease);
+    }
+    if let Some(block) = &task.blocked {
+        println!("Blocked: {} (by {})", block.reason, block.by);
+    }
+    if let Some(frozen) = &task.frozen {
+        println!("Frozen: {} (by {})", frozen.reason, frozen.by);
+    }
+    if !task.external_ids.is_empty() {
+        println!("External: {}", format_external_ids(&task.external_ids));
+    }
+    let mut dates = format!(
+        "Created: {}  Updated: {}",
+        task.created_at.format("%Y-%m-%d %H:%M"),
+        task.updated_at.format("%Y-%m-%d %H:%M")
+    );
+    if let Some(completed) = task.completed_at {
+        dates.push_str(&format!(
+            "  Completed: {}",
+            completed.format("%Y-%m-%d %H:%M")
+        ));
+    }
+    if let Some(due) = task.due_at {
+        dates.push_str(&format!("  Due: {}", due.format("%Y-%m-%d %H:%M")));
+        if task.is_overdue(chrono::Utc::now()) {
+            dates.push_str(" (overdue)");
         }
+    }
+    println!("{}", dates);
 
-        if !duplicates.is_empty() {
-            println!("\nDuplicates:");
-            for dep in &duplicates {
-                println!(
-                    "  [{}] {}: {} (WARNING: may be duplicate)",
-                    dep.dep_type.label(),
-                    dep.task,
-                    tasks
-                        .get(&dep.task)
-                        .map(|t| t.title.as_str())
-                        .unwrap_or("?")
-                );
-            }
+    if let Some(desc) = task.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        println!("\n{}", style.bold("Description"));
+        for line in style.markdown(desc, "  ") {
+            println!("{}", line);
+        }
+    }
+
+    if task
+        .depends_on
+        .by_type(DependencyType::Blocks)
+        .next()
+        .is_some()
+    {
+        println!("\n{}", style.bold("Depends on"));
+        let mut seen = vec![task.id.clone()];
+        print_dep_tree(&style, theme, task, tasks, statuses, "  ", 1, &mut seen);
+    }
+
+    let dependents: Vec<&Task> = by_id(tasks.values().filter(|t| {
+        t.depends_on
+            .by_type(DependencyType::Blocks)
+            .any(|d| d.task == task.id)
+    }));
+    if !dependents.is_empty() {
+        println!("\n{}", style.bold("Blocks"));
+        for dependent in dependents {
+            println!(
+                "  {} {}  {}",
+                status_marker(&style, theme, dependent.status),
+                dependent.id,
+                dependent.title
+            );
         }
+    }
 
-        if let Some(desc) = &task.description {
-            println!("\nDescription:");
-            println!("{}", desc);
+    let others: Vec<_> = task
+        .depends_on
+        .iter()
+        .filter(|d| d.dep_type != DependencyType::Blocks)
+        .collect();
+    if !others.is_empty() {
+        println!("\n{}", style.bold("Related"));
+        for dep in others {
+            println!(
+                "  [{}] {}  {}",
+                dep.dep_type.label(),
+                dep.task,
+                tasks.get(&dep.task).map_or("?", |t| t.title.as_str())
+            );
         }
+    }
 
-        if !task.meta.is_empty() {
-            println!("\nMetadata:");
-            for (key, value) in task.meta.iter() {
-                println!("  {}: {}", key, value);
-            }
+    let meta: Vec<_> = task
+        .meta
+        .iter()
+        .filter(|(k, _)| !matches!(k.as_str(), "priority" | "labels" | "container"))
+        .collect();
+    if !meta.is_empty() {
+        println!("\n{}", style.bold("Metadata"));
+        for (key, value) in meta {
+            println!("  {}: {}", key, value);
         }
+    }
 
-        println!();
-        if is_ready {
-            println!("Status: READY (all dependencies complete)");
-        } else if is_blocked {
-            println!("Status: BLOCKED (waiting on dependencies)");
+    if !task.notes.is_empty() {
+        println!("\n{}", style.bold("Notes"));
+        for note in task.notes.iter().rev().take(CARD_RECENT).rev() {
+            let head = format!("{} {}", note.at.format("%Y-%m-%d %H:%M"), note.by);
+            println!("  {}", style.paint(&head, Color::Dim));
+            for line in style.markdown(&note.text, "    ") {
+                println!("{}", line);
+            }
         }
     }
 
-    Ok(())
+    if !task.history.is_empty() {
+        println!("\n{}", style.bold("History"));
+        for event in task.history.iter().rev().take(CARD_RECENT).rev() {
+            let kind = serde_json::to_value(&event.event)
+                .ok()
+                .and_then(|v| v.as_str().map(|s| s.replace('_', " ")))
+                .unwrap_or_default();
+            let by = event
+                .by
+                .as_deref()
+                .map(|b| format!(" by {}", b))
+                .unwrap_or_default();
+            println!(
+                "  {}  {}{}",
+                style.paint(&event.at.format("%Y-%m-%d %H:%M").to_string(), Color::Dim),
+                kind,
+                by
+            );
+        }
+    }
 }
 
-fn start_task(output: &Output, id_str: &str) -> Result<()> {
-    let project = Project::open_current()?;
-    let store = project.task_store();
+/// Draws blocking dependencies as a tree, stopping at [`CARD_TREE_DEPTH`]
+/// levels and at tasks already drawn
+#[allow(clippy::too_many_arguments)]
+fn print_dep_tree(
+    style: &Style,
+    theme: &Theme,
+    task: &Task,
+    tasks: &HashMap<TaskId, Task>,
+    statuses: &HashMap<TaskId, TaskStatus>,
+    prefix: &str,
+    depth: usize,
+    seen: &mut Vec<TaskId>,
+) {
+    use crate::domain::DependencyType;
 
-    let id: TaskId = id_str.parse()?;
-    let mut tasks = store.read_all()?;
+    let deps: Vec<_> = task.depends_on.by_type(DependencyType::Blocks).collect();
+    for (i, dep) in deps.iter().enumerate() {
+        let last = i + 1 == deps.len();
+        let branch = if last { "└─" } else { "├─" };
+        let status = statuses.get(&dep.task).copied();
+        let marker = status.map_or_else(|| "?".to_string(), |s| status_marker(style, theme, s));
+        let title = tasks.get(&dep.task).map_or("?", |t| t.title.as_str());
+        println!(
+            "{}{} {} {}  {}{}",
+            prefix,
+            branch,
+            marker,
+            dep.task,
+            title,
+            style.paint(&lag_label(dep.lag_days), Color::Dim)
+        );
+
+        if seen.contains(&dep.task) {
+            continue;
+        }
+        seen.push(dep.task.clone());
+        if let Some(dep_task) = tasks.get(&dep.task) {
+            if depth < CARD_TREE_DEPTH && dep_task.status != TaskStatus::Done {
+                let child = format!("{}{}", prefix, if last { "   " } else { "│  " });
+                print_dep_tree(
+                    style,
+                    theme,
+                    dep_task,
+                    tasks,
+                    statuses,
+                    &child,
+                    depth + 1,
+                    seen,
+                );
+            }
+        }
+    }
+}
 
-    let task = tasks
-        .get_mut(&id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+fn status_badge(style: &Style, status: TaskStatus) -> String {
+    match status {
+        TaskStatus::Todo => style.badge("todo", Color::Dim),
+        TaskStatus::InProgress => style.badge("in progress", Color::Yellow),
+        TaskStatus::Done => style.badge("done", Color::Green),
+    }
+}
 
-    task.start();
-    store.update(task)?;
+fn status_marker(style: &Style, theme: &Theme, status: TaskStatus) -> String {
+    match theme.task_icon(status).as_str() {
+        "" => style.paint(&status.to_string(), status_color(status)),
+        icon => style.paint(icon, status_color(status)),
+    }
+}
 
-    if output.is_json() {
-        output.data(&serde_json::json!({
-            "id": task.id.to_string(),
-            "status": task.status,
-        }));
-    } else {
-        output.success(&format!("Started task: {}", task.id));
+fn status_color(status: TaskStatus) -> Color {
+    match status {
+        TaskStatus::Todo => Color::Dim,
+        TaskStatus::InProgress => Color::Yellow,
+        TaskStatus::Done => Color::Green,
     }
+}
 
-    Ok(())
+/// ` [label]` badges in each label's configured color
+fn label_badges(style: &Style, theme: &Theme, task: &Task) -> String {
+    task.labels()
+        .iter()
+        .map(|l| {
+            format!(
+                " {}",
+                style.badge(l, theme.label_color(l).unwrap_or(Color::Dim))
+            )
+        })
+        .collect()
 }
 
-fn complete_task(output: &Output, id_str: &str) -> Result<()> {
+fn transition_tasks(
+    output: &Output,
+    id_strs: &[String],
+    target: TaskStatus,
+    yes: bool,
+    skip_gates: Option<&str>,
+) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.task_store();
 
-    let id: TaskId = id_str.parse()?;
-    let mut tasks = store.read_all()?;
+    let mut ids: Vec<TaskId> = Vec::new();
+    for id_str in id_strs {
+        let id = project.resolve_task_id(id_str)?;
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
 
-    let task = tasks
-        .get_mut(&id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+    // Unmet gate names of tasks completed with --skip-gates
+    let check = |tasks: &HashMap<TaskId, Task>| -> Result<HashMap<TaskId, Vec<String>>> {
+        let mut skipped = HashMap::new();
+        for id in &ids {
+            let task = tasks
+                .get(id)
+                .ok_or_else(|| CliError::not_found("Task", id))?;
+            ensure_task_mutable(&project, task)?;
+            if target == TaskStatus::Done {
+                review::ensure_approved(&project, task)?;
+                let unmet = gates::unmet(&project, task)?;
+                if !unmet.is_empty() {
+                    if skip_gates.is_none() {
+                        return Err(gates::refusal(&project, task)?.into());
+                    }
+                    skipped.insert(id.clone(), unmet.iter().map(|g| g.to_string()).collect());
+                }
+            }
+        }
+        Ok(skipped)
+    };
 
-    task.complete();
-    store.update(task)?;
+    let tasks = store.read_all()?;
+    check(&tasks)?;
+    let changes: Vec<BulkChange> = ids
+        .iter()
+        .map(|id| {
+            let task = &tasks[id];
+            BulkChange {
+                id: id.to_string(),
+                title: task.title.clone(),
+                from: task.status.to_string(),
+                to: target.to_string(),
+            }
+        })
+        .collect();
+
+    let action = match target {
+        TaskStatus::Done => "complete",
+        _ => "start",
+    };
+    if !confirm_bulk(output, &project, action, &changes, yes)? {
+        return Ok(());
+    }
+
+    // Checked again under the lock, as the tasks may have changed while the
+    // prompt was open
+    let (tasks, skipped) = store.modify(|tasks| {
+        let skipped = check(tasks)?;
+        let mut changed = HashMap::new();
+        for id in &ids {
+            let task = tasks.get_mut(id).unwrap();
+            match target {
+                TaskStatus::Done => task.complete(),
+                TaskStatus::InProgress => task.start(),
+                TaskStatus::Todo => task.reopen(),
+            }
+            changed.insert(id.clone(), task.clone());
+        }
+        Ok((changed, skipped))
+    })?;
+    for id in &ids {
+        if let Some(unmet) = skipped.get(id) {
+            gates::audit_skip(&project, &tasks[id], unmet, skip_gates.unwrap_or_default())?;
+        }
+    }
 
     if output.is_json() {
-        output.data(&serde_json::json!({
-            "id": task.id.to_string(),
-            "status": task.status,
-            "completed_at": task.completed_at,
-        }));
+        let items: Vec<_> = ids
+            .iter()
+            .map(|id| {
+                let task = &tasks[id];
+                if target == TaskStatus::Done {
+                    let mut item = serde_json::json!({
+                        "id": task.id.to_string(),
+                        "status": task.status,
+                        "completed_at": task.completed_at,
+                    });
+                    if let Some(unmet) = skipped.get(id) {
+                        item["skipped_gates"] = serde_json::json!(unmet);
+                    }
+                    item
+                } else {
+                    serde_json::json!({
+                        "id": task.id.to_string(),
+                        "status": task.status,
+                    })
+                }
+            })
+            .collect();
+
+        if items.len() == 1 {
+            output.data(&items[0]);
+        } else {
+            output.data(&items);
+        }
     } else {
-        output.success(&format!("Completed task: {}", task.id));
+        let verb = match target {
+            TaskStatus::Done => "Completed",
+            _ => "Started",
+        };
+        for id in &ids {
+            output.success(&format!("{} task: {}", verb, id));
+            if let Some(unmet) = skipped.get(id) {
+                println!(
+                    "  Skipped gates: {} (recorded in the audit log)",
+                    unmet.join(", ")
+                );
+            }
+        }
     }
 
     Ok(())
@@ -576,36 +1619,57 @@ fn add_typed_dependency(
     task_str: &str,
     depends_on_str: &str,
     dep_type: crate::domain::DependencyType,
+    lag_days: i64,
 ) -> Result<()> {
     use crate::domain::Dependency;
 
     let project = Project::open_current()?;
     let store = project.task_store();
 
-    let task_id: TaskId = task_str.parse()?;
-    let depends_on_id: TaskId = depends_on_str.parse()?;
+    let task_id = project.resolve_task_id(task_str)?;
+    let depends_on_id = project.resolve_task_id(depends_on_str)?;
 
     let mut tasks = store.read_all()?;
 
     // Verify both tasks exist
     if !tasks.contains_key(&task_id) {
-        anyhow::bail!("Task not found: {}", task_id);
+        return Err(CliError::not_found("Task", &task_id).into());
     }
     if !tasks.contains_key(&depends_on_id) {
-        anyhow::bail!("Dependency task not found: {}", depends_on_id);
+        return Err(CliError::not_found("Dependency task", &depends_on_id).into());
     }
 
+    ensure_task_mutable(&project, &tasks[&task_id])?;
+
     // Only check for cycles with blocking dependencies
+    let mut dep_type = dep_type;
     if dep_type.affects_ready() {
         let mut graph = DependencyGraph::from_tasks(tasks.values())?;
-        graph.add_dependency(&task_id, &depends_on_id)?;
+        if let Err(e) = graph.add_dependency(&task_id, &depends_on_id) {
+            let GraphError::CycleDetected(..) = e else {
+                return Err(e.into());
+            };
+            let cycle = graph
+                .dependency_path(&depends_on_id, &task_id)
+                .map(|path| {
+                    std::iter::once(&task_id)
+                        .chain(&path)
+                        .map(|id| id.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" → ")
+                })
+                .unwrap_or_else(|| format!("{} → {}", task_id, depends_on_id));
+            dep_type = resolve_cycle(output, &cycle)?;
+        }
     }
 
-    // Create the typed dependency
-    let dependency = Dependency {
-        task: depends_on_id.clone(),
-        dep_type,
+    // Create the typed dependency; only blocking ones are scheduled with lag
+    let lag_days = if dep_type.affects_ready() {
+        lag_days
+    } else {
+        0
     };
+    let dependency = Dependency::of_type(depends_on_id.clone(), dep_type).with_lag(lag_days);
 
     // Update the task
     let task = tasks.get_mut(&task_id).unwrap();
@@ -617,6 +1681,7 @@ fn add_typed_dependency(
             "task": task_id.to_string(),
             "depends_on": depends_on_id.to_string(),
             "type": dep_type,
+            "lag_days": lag_days,
         }));
     } else {
         let label = match dep_type {
@@ -625,12 +1690,69 @@ fn add_typed_dependency(
             crate::domain::DependencyType::Related => "now linked to",
             crate::domain::DependencyType::Duplicates => "marked as duplicate of",
         };
-        output.success(&format!("{} {} {}", task_id, label, depends_on_id));
+        output.success(&format!(
+            "{} {} {}{}",
+            task_id,
+            label,
+            depends_on_id,
+            lag_label(lag_days)
+        ));
     }
 
     Ok(())
 }
 
+/// Days covering `duration`, rounded up
+fn whole_days(duration: chrono::Duration) -> i64 {
+    (duration.num_minutes() + 24 * 60 - 1).div_euclid(24 * 60)
+}
+
+/// ` (2d lag)` or ` (1d lead)` after a dependency, or nothing without lag
+fn lag_label(lag_days: i64) -> String {
+    match lag_days {
+        0 => String::new(),
+        lag if lag > 0 => format!(" ({}d lag)", lag),
+        lead => format!(" ({}d lead)", -lead),
+    }
+}
+
+/// Explains a dependency cycle and, on a terminal, offers a link type that
+/// doesn't affect readiness instead. Fails when none is picked.
+fn resolve_cycle(output: &Output, cycle: &str) -> Result<crate::domain::DependencyType> {
+    use crate::domain::DependencyType;
+    use std::io::{self, BufRead, IsTerminal, Write};
+
+    let refused = || {
+        CliError::new(
+            ErrorCode::DependencyCycle,
+            format!(
+                "Adding this dependency would create a cycle: {}. \
+                 Use --related or --from for a link that doesn't block.",
+                cycle
+            ),
+        )
+    };
+    if output.is_json() || !io::stdin().is_terminal() {
+        return Err(refused().into());
+    }
+
+    println!("Adding this dependency would create a cycle:");
+    println!("  {}", cycle);
+    println!("A link that doesn't affect ready/blocked can be added instead:");
+    println!("  r. related     the tasks are connected, in either order");
+    println!("  p. provenance  the task came out of the other one");
+    print!("Add it as which? (r, p, or Enter to cancel) ");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    match answer.trim().to_lowercase().as_str() {
+        "r" | "related" => Ok(DependencyType::Related),
+        "p" | "provenance" | "from" => Ok(DependencyType::Provenance),
+        _ => Err(refused().into()),
+    }
+}
+
 fn remove_typed_dependency(
     output: &Output,
     task_str: &str,
@@ -640,14 +1762,15 @@ fn remove_typed_dependency(
     let project = Project::open_current()?;
     let store = project.task_store();
 
-    let task_id: TaskId = task_str.parse()?;
-    let depends_on_id: TaskId = depends_on_str.parse()?;
+    let task_id = project.resolve_task_id(task_str)?;
+    let depends_on_id = project.resolve_task_id(depends_on_str)?;
 
     let mut tasks = store.read_all()?;
 
     let task = tasks
         .get_mut(&task_id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", task_id))?;
+        .ok_or_else(|| CliError::not_found("Task", &task_id))?;
+    ensure_task_mutable(&project, task)?;
 
     if let Some(dt) = dep_type {
         task.remove_typed_dependency(&depends_on_id, dt);
@@ -681,21 +1804,22 @@ fn add_duplicate(output: &Output, task_str: &str, original_str: &str, close: boo
     let project = Project::open_current()?;
     let store = project.task_store();
 
-    let task_id: TaskId = task_str.parse()?;
-    let original_id: TaskId = original_str.parse()?;
+    let task_id = project.resolve_task_id(task_str)?;
+    let original_id = project.resolve_task_id(original_str)?;
 
     let mut tasks = store.read_all()?;
 
     // Verify both tasks exist
     if !tasks.contains_key(&task_id) {
-        anyhow::bail!("Task not found: {}", task_id);
+        return Err(CliError::not_found("Task", &task_id).into());
     }
     if !tasks.contains_key(&original_id) {
-        anyhow::bail!("Original task not found: {}", original_id);
+        return Err(CliError::not_found("Original task", &original_id).into());
     }
 
     // Update the task
     let task = tasks.get_mut(&task_id).unwrap();
+    ensure_task_mutable(&project, task)?;
     task.add_typed_dependency(Dependency::duplicates(original_id.clone()));
 
     if close {
@@ -721,16 +1845,66 @@ fn add_duplicate(output: &Output, task_str: &str, original_str: &str, close: boo
     Ok(())
 }
 
+fn rank_task(output: &Output, id_str: &str, anchor_str: &str, after: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.task_store();
+
+    let id = project.resolve_task_id(id_str)?;
+    let anchor = project.resolve_task_id(anchor_str)?;
+    let mut tasks = store.read_all()?;
+
+    let task = tasks
+        .get(&id)
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    let other = tasks
+        .get(&anchor)
+        .ok_or_else(|| CliError::not_found("Task", &anchor))?;
+    if id == anchor {
+        return Err(CliError::new(
+            ErrorCode::Validation,
+            "Cannot rank a task relative to itself",
+        )
+        .into());
+    }
+    if task.is_standalone() || task.brief_id() != other.brief_id() {
+        return Err(CliError::new(
+            ErrorCode::Validation,
+            format!("{} and {} are not in the same brief", id, anchor),
+        )
+        .into());
+    }
+    ensure_task_mutable(&project, task)?;
+
+    let changed = place(&mut tasks, &id, &anchor, after);
+    let updated: Vec<Task> = changed.iter().map(|t| tasks[t].clone()).collect();
+    store.update_batch(&updated)?;
+
+    let position = if after { "after" } else { "before" };
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": id.to_string(),
+            "rank": tasks[&id].rank,
+            position: anchor.to_string(),
+            "reranked": changed.iter().map(|t| t.to_string()).collect::<Vec<_>>(),
+        }));
+    } else {
+        output.success(&format!("Moved {} {} {}", id, position, anchor));
+    }
+
+    Ok(())
+}
+
 fn set_meta(output: &Output, id_str: &str, key: &str, value_str: &str) -> Result<()> {
     let project = Project::open_current()?;
     let store = project.task_store();
 
-    let id: TaskId = id_str.parse()?;
+    let id = project.resolve_task_id(id_str)?;
     let mut tasks = store.read_all()?;
 
     let task = tasks
         .get_mut(&id)
-        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
 
     // Try to parse value as JSON, fall back to string
     let value: serde_json::Value = serde_json::from_str(value_str)
@@ -751,3 +1925,300 @@ fn set_meta(output: &Output, id_str: &str, key: &str, value_str: &str) -> Result
 
     Ok(())
 }
+
+fn set_due(output: &Output, id_str: &str, when: Option<&str>) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.task_store();
+
+    let id = project.resolve_task_id(id_str)?;
+    let mut tasks = store.read_all()?;
+
+    let task = tasks
+        .get_mut(&id)
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
+
+    let due_at = when.map(duration::parse_due).transpose()?;
+    task.set_due(due_at);
+    store.update(task)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": task.id.to_string(),
+            "due_at": task.due_at,
+        }));
+    } else {
+        match task.due_at {
+            Some(due) => output.success(&format!(
+                "{} is due {}",
+                task.id,
+                due.format("%Y-%m-%d %H:%M UTC")
+            )),
+            None => output.success(&format!("Cleared due date of {}", task.id)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Field changes asked for on the `task edit` command line
+struct EditFields {
+    title: Option<String>,
+    description: Option<String>,
+    priority: Option<String>,
+    meta: Vec<String>,
+}
+
+impl EditFields {
+    fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.priority.is_none()
+            && self.meta.is_empty()
+    }
+}
+
+/// A task as shown in the editor by `task edit`
+#[derive(serde::Serialize, serde::Deserialize)]
+struct EditBuffer {
+    title: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    priority: Option<String>,
+
+    #[serde(default)]
+    description: String,
+
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    meta: std::collections::BTreeMap<String, toml::Value>,
+}
+
+fn edit_task(output: &Output, id_str: &str, fields: EditFields) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.task_store();
+
+    let id = project.resolve_task_id(id_str)?;
+    let mut tasks = store.read_all()?;
+
+    let task = tasks
+        .get_mut(&id)
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
+
+    let changed = if fields.is_empty() {
+        edit_in_editor(&project, task)?
+    } else {
+        apply_edit(task, fields)?
+    };
+    if !changed.is_empty() {
+        store.update(task)?;
+    }
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": task.id.to_string(),
+            "title": task.title,
+            "description": task.description,
+            "meta": task.meta,
+            "changed": changed,
+        }));
+    } else if changed.is_empty() {
+        output.success(&format!("No changes to {}", task.id));
+    } else {
+        output.success(&format!("Updated {}: {}", task.id, changed.join(", ")));
+    }
+    Ok(())
+}
+
+/// Applies command-line changes, returning the names of the fields that changed
+fn apply_edit(task: &mut Task, fields: EditFields) -> Result<Vec<String>> {
+    let mut changed = Vec::new();
+    if let Some(title) = fields.title {
+        if set_title(task, &title)? {
+            changed.push("title".to_string());
+        }
+    }
+    if let Some(description) = fields.description {
+        if set_description(task, &description) {
+            changed.push("description".to_string());
+        }
+    }
+    if let Some(priority) = fields.priority {
+        if set_meta_value(task, PRIORITY_META_KEY, serde_json::json!(priority)) {
+            changed.push(PRIORITY_META_KEY.to_string());
+        }
+    }
+    for entry in &fields.meta {
+        let (key, value) = entry
+            .split_once('=')
+            .filter(|(key, _)| !key.trim().is_empty())
+            .ok_or_else(|| {
+                CliError::new(
+                    ErrorCode::Validation,
+                    format!("Invalid --meta '{}': expected key=value", entry),
+                )
+            })?;
+        let value = serde_json::from_str(value)
+            .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+        if set_meta_value(task, key.trim(), value) && !changed.iter().any(|c| c == key.trim()) {
+            changed.push(key.trim().to_string());
+        }
+    }
+    Ok(changed)
+}
+
+/// Round-trips the task through a TOML buffer in the editor, returning the
+/// names of the fields that changed
+fn edit_in_editor(project: &Project, task: &mut Task) -> Result<Vec<String>> {
+    let priority = task
+        .get_meta(PRIORITY_META_KEY)
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    // TOML has no null, so metadata holding one stays out of the buffer and
+    // is kept as is
+    let meta: std::collections::BTreeMap<String, toml::Value> = task
+        .meta
+        .iter()
+        .filter(|(key, _)| key.as_str() != PRIORITY_META_KEY || priority.is_none())
+        .filter_map(|(key, value)| Some((key.clone(), toml::Value::try_from(value).ok()?)))
+        .collect();
+    let shown: Vec<String> = meta
+        .keys()
+        .cloned()
+        .chain(priority.as_ref().map(|_| PRIORITY_META_KEY.to_string()))
+        .collect();
+    let buffer = EditBuffer {
+        title: task.title.clone(),
+        priority,
+        description: task.description.clone().unwrap_or_default(),
+        meta,
+    };
+    let initial = format!(
+        "# Editing {}. Save and close to apply; empty the file to cancel.\n{}",
+        task.id,
+        toml::to_string_pretty(&buffer)?
+    );
+
+    let text = editor::edit(project, "TASK_EDIT.toml", &initial)?;
+    if text
+        .lines()
+        .all(|l| l.trim().is_empty() || l.trim_start().starts_with('#'))
+    {
+        return Ok(Vec::new());
+    }
+    let edited: EditBuffer = toml::from_str(&text).map_err(|e| {
+        CliError::new(
+            ErrorCode::Validation,
+            format!("Invalid task buffer, nothing saved: {}", e),
+        )
+    })?;
+    if let Some(priority) = &edited.priority {
+        if !matches!(priority.as_str(), "high" | "medium" | "low") {
+            return Err(CliError::new(
+                ErrorCode::Validation,
+                format!("Invalid priority '{}': use high, medium or low", priority),
+            )
+            .into());
+        }
+    }
+
+    let mut changed = Vec::new();
+    if set_title(task, &edited.title)? {
+        changed.push("title".to_string());
+    }
+    if set_description(task, &edited.description) {
+        changed.push("description".to_string());
+    }
+    let mut meta: Vec<(String, serde_json::Value)> = Vec::new();
+    for (key, value) in edited.meta {
+        meta.push((key, serde_json::to_value(value)?));
+    }
+    if let Some(priority) = edited.priority {
+        meta.push((PRIORITY_META_KEY.to_string(), serde_json::json!(priority)));
+    }
+    for (key, value) in &meta {
+        if set_meta_value(task, key, value.clone()) {
+            changed.push(key.clone());
+        }
+    }
+    // Keys taken out of the buffer are removed
+    for key in shown
+        .into_iter()
+        .filter(|s| !meta.iter().any(|(k, _)| k == s))
+    {
+        task.remove_meta(&key);
+        changed.push(key);
+    }
+    Ok(changed)
+}
+
+fn set_title(task: &mut Task, title: &str) -> Result<bool> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(CliError::new(ErrorCode::Validation, "Title cannot be empty").into());
+    }
+    if title == task.title {
+        return Ok(false);
+    }
+    task.set_title(title);
+    Ok(true)
+}
+
+fn set_description(task: &mut Task, description: &str) -> bool {
+    let description = description.trim();
+    if task.description.as_deref().unwrap_or_default() == description {
+        return false;
+    }
+    if description.is_empty() {
+        task.clear_description();
+    } else {
+        task.set_description(description);
+    }
+    true
+}
+
+fn set_meta_value(task: &mut Task, key: &str, value: serde_json::Value) -> bool {
+    if task.get_meta(key) == Some(&value) {
+        return false;
+    }
+    task.set_meta(key, value);
+    true
+}
+
+fn set_external_id(output: &Output, id_str: &str, external: &str, remove: bool) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.task_store();
+
+    let id = project.resolve_task_id(id_str)?;
+    let mut tasks = store.read_all()?;
+
+    let task = tasks
+        .get_mut(&id)
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    ensure_task_mutable(&project, task)?;
+
+    let (system, key) = brief::parse_external_arg(external, remove)?;
+    match key {
+        Some(key) => task.set_external_id(system, key),
+        None => {
+            if !task.remove_external_id(system) {
+                bail!("{} has no {} ID", task.id, system);
+            }
+        }
+    }
+    store.update(task)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": task.id.to_string(),
+            "external_ids": task.external_ids,
+        }));
+    } else {
+        match key {
+            Some(key) => output.success(&format!("{} is {}:{}", task.id, system, key)),
+            None => output.success(&format!("Removed {} ID from {}", system, task.id)),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,486 @@
+//! `shape task import`: create a batch of tasks in one write
+//!
+//! The input is JSON Lines, one task per line:
+//!
+//! ```text
+//! {"title": "Schema", "labels": ["db"]}
+//! {"title": "API", "depends_on": ["Schema"]}
+//! {"title": "Endpoints", "parent": 2}
+//! ```
+//!
+//! or a markdown checklist, where indented items become subtasks of the item
+//! above them, `[x]` marks a task done and a trailing `(after: ...)` lists
+//! dependencies:
+//!
+//! ```text
+//! - [ ] Schema
+//! - [ ] API (after: Schema)
+//!   - [ ] Endpoints
+//! ```
+//!
+//! Items refer to each other by position (from 1) or by title, and to tasks
+//! already in the project by ID. Every ID is picked before anything is
+//! written, so an item may depend on one further down; the batch is then
+//! appended to the task store under a single lock.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::error::{CliError, ErrorCode};
+use super::freeze::{ensure_brief_mutable, ensure_task_mutable};
+use super::output::Output;
+use crate::domain::{DependencyGraph, GraphError, Task, TaskId, TaskStatus};
+use crate::storage::Project;
+
+/// One task to create
+#[derive(Debug, Default, Deserialize)]
+struct Item {
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    depends_on: Vec<Ref>,
+    /// Makes this a subtask
+    #[serde(default)]
+    parent: Option<Ref>,
+    #[serde(default)]
+    status: TaskStatus,
+}
+
+/// How an item names another task
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Ref {
+    /// Position in the batch, from 1
+    Index(usize),
+    /// A title in the batch, or the ID of an existing task
+    Name(String),
+}
+
+/// What a reference resolved to
+#[derive(Debug, Clone)]
+enum Target {
+    Batch(usize),
+    Existing(TaskId),
+}
+
+pub(super) fn run(
+    output: &Output,
+    file: Option<&Path>,
+    brief: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let project = Project::open_current()?;
+    let store = project.task_store();
+
+    let input = match file {
+        Some(path) if path != Path::new("-") => fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?,
+        _ => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .context("Failed to read stdin")?;
+            input
+        }
+    };
+    let items = parse(&input)?;
+    if items.is_empty() {
+        return Err(CliError::new(
+            ErrorCode::Validation,
+            "No tasks found: expected JSON Lines or a markdown checklist (- [ ] Title)",
+        )
+        .into());
+    }
+
+    let brief = match brief {
+        Some(s) => {
+            let id = project.resolve_brief_id(s)?;
+            let brief = project
+                .brief_store()
+                .read(&id)?
+                .ok_or_else(|| CliError::not_found("Brief", &id))?;
+            ensure_brief_mutable(&project, &brief)?;
+            Some(id)
+        }
+        None => None,
+    };
+
+    let tasks = store.read_all()?;
+    let resolve = |r: &Ref, item: usize| -> Result<Target> {
+        let target = resolve_ref(&project, &items, r, item)?;
+        if matches!(target, Target::Batch(i) if i == item) {
+            return Err(invalid(item, "refers to itself"));
+        }
+        Ok(target)
+    };
+
+    // Parents first, so a subtask can number itself after its parent's ID
+    let mut parents = Vec::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        let parent = match &item.parent {
+            None => None,
+            Some(r) => match resolve(r, i)? {
+                Target::Batch(p) if p > i => {
+                    return Err(invalid(i, "has a parent further down; list parents first"))
+                }
+                Target::Existing(id) => {
+                    let parent = tasks
+                        .get(&id)
+                        .ok_or_else(|| CliError::not_found("Task", &id))?;
+                    ensure_task_mutable(&project, parent)?;
+                    Some(Target::Existing(id))
+                }
+                target => Some(target),
+            },
+        };
+        parents.push(parent);
+    }
+
+    // Sequence numbers continue after the existing tasks in each scope
+    let mut next_seq: HashMap<String, u32> = HashMap::new();
+    for id in tasks.keys() {
+        let scope = match id.parent() {
+            Some(parent) => parent.to_string(),
+            None => match id.brief_id() {
+                Some(brief) => brief.to_string(),
+                None => continue,
+            },
+        };
+        let seq = next_seq.entry(scope).or_insert(0);
+        *seq = (*seq).max(*id.segments().last().unwrap_or(&0));
+    }
+    // Hashes of briefs and standalone tasks; new standalone IDs widen past them
+    let mut taken: HashSet<String> = project
+        .brief_store()
+        .read_all_meta()?
+        .keys()
+        .map(|id| id.hash().to_string())
+        .chain(
+            tasks
+                .keys()
+                .filter(|id| id.is_standalone())
+                .map(|id| id.hash().to_string()),
+        )
+        .collect();
+    let scheme = project.id_scheme();
+    let now = Utc::now();
+
+    let mut ids: Vec<TaskId> = Vec::with_capacity(items.len());
+    for (item, parent) in items.iter().zip(&parents) {
+        let parent = match parent {
+            Some(Target::Batch(p)) => Some(&ids[*p]),
+            Some(Target::Existing(id)) => Some(id),
+            None => None,
+        };
+        let id = match (parent, &brief) {
+            (Some(parent), _) => {
+                let seq = next_seq.entry(parent.to_string()).or_insert(0);
+                *seq += 1;
+                parent.subtask(*seq)
+            }
+            (None, Some(brief)) => {
+                let seq = next_seq.entry(brief.to_string()).or_insert(0);
+                *seq += 1;
+                TaskId::new(brief, *seq)
+            }
+            (None, None) => {
+                let id = scheme.unique_standalone_task_id(&item.title, now, &taken);
+                taken.insert(id.hash().to_string());
+                id
+            }
+        };
+        ids.push(id);
+    }
+
+    let mut new_tasks = Vec::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        let mut task = Task::new(ids[i].clone(), item.title.trim());
+        if let Some(description) = &item.description {
+            task.set_description(description);
+        }
+        if !item.labels.is_empty() {
+            task.set_meta("labels", json!(item.labels));
+        }
+        for r in &item.depends_on {
+            match resolve(r, i)? {
+                Target::Batch(d) => task.add_dependency(ids[d].clone()),
+                Target::Existing(id) => task.add_dependency(id),
+            }
+        }
+        match item.status {
+            TaskStatus::Todo => {}
+            TaskStatus::InProgress => task.start(),
+            TaskStatus::Done => task.complete(),
+        }
+        new_tasks.push(task);
+    }
+
+    if let Err(GraphError::CycleDetected(task, depends_on)) =
+        DependencyGraph::from_tasks(tasks.values().chain(&new_tasks))
+    {
+        return Err(CliError::new(
+            ErrorCode::DependencyCycle,
+            format!(
+                "The batch's dependencies form a cycle ({} waits on {})",
+                title_of(&new_tasks, &task),
+                title_of(&new_tasks, &depends_on)
+            ),
+        )
+        .into());
+    }
+
+    if !dry_run {
+        store.append_batch(&new_tasks)?;
+    }
+
+    if output.is_json() {
+        let created: Vec<_> = new_tasks
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                json!({
+                    "index": i + 1,
+                    "id": t.id.to_string(),
+                    "title": t.title,
+                    "status": t.status,
+                    "parent": t.id.parent().map(|p| p.to_string()),
+                    "depends_on": t
+                        .depends_on
+                        .blocking_task_ids()
+                        .map(|d| d.to_string())
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        output.data(&json!({
+            "brief": brief.map(|b| b.to_string()),
+            "dry_run": dry_run,
+            "created": created.len(),
+            "tasks": created,
+        }));
+        return Ok(());
+    }
+
+    for (i, task) in new_tasks.iter().enumerate() {
+        println!("{:>4}  {:<24} {}", i + 1, task.id, task.title);
+    }
+    let verb = if dry_run { "Would create" } else { "Created" };
+    output.success(&format!("{} {} task(s)", verb, new_tasks.len()));
+    Ok(())
+}
+
+/// JSON Lines when the first line holds an object, a checklist otherwise
+fn parse(input: &str) -> Result<Vec<Item>> {
+    let first = input.lines().map(str::trim).find(|l| !l.is_empty());
+    if first.is_some_and(|l| l.starts_with('{')) {
+        parse_jsonl(input)
+    } else {
+        parse_checklist(input)
+    }
+}
+
+fn parse_jsonl(input: &str) -> Result<Vec<Item>> {
+    let mut items = Vec::new();
+    for (n, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let item: Item = serde_json::from_str(line)
+            .map_err(|e| CliError::new(ErrorCode::Validation, format!("Line {}: {}", n + 1, e)))?;
+        if item.title.trim().is_empty() {
+            return Err(CliError::new(
+                ErrorCode::Validation,
+                format!("Line {}: the title is empty", n + 1),
+            )
+            .into());
+        }
+        items.push(item);
+    }
+    Ok(items)
+}
+
+/// Checklist items (`- [ ]`, `* [x]`, ...); every other line is ignored
+fn parse_checklist(input: &str) -> Result<Vec<Item>> {
+    let mut items: Vec<Item> = Vec::new();
+    // Indent and position of the items that can still take subtasks
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    for line in input.lines() {
+        let indent: usize = line
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum();
+        let Some(rest) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|bullet| line.trim_start().strip_prefix(bullet))
+        else {
+            continue;
+        };
+        let (status, text) = if let Some(text) = rest.strip_prefix("[ ]") {
+            (TaskStatus::Todo, text)
+        } else if let Some(text) = rest
+            .strip_prefix("[x]")
+            .or_else(|| rest.strip_prefix("[X]"))
+        {
+            (TaskStatus::Done, text)
+        } else {
+            continue;
+        };
+        let (title, depends_on) = split_after(text.trim());
+        if title.is_empty() {
+            continue;
+        }
+
+        while open.last().is_some_and(|&(i, _)| i >= indent) {
+            open.pop();
+        }
+        let parent = open.last().map(|&(_, p)| Ref::Index(p + 1));
+        open.push((indent, items.len()));
+        items.push(Item {
+            title: title.to_string(),
+            depends_on,
+            parent,
+            status,
+            ..Item::default()
+        });
+    }
+    Ok(items)
+}
+
+/// Splits a trailing `(after: 2, Schema)` off a checklist title
+fn split_after(text: &str) -> (&str, Vec<Ref>) {
+    let Some(start) = text.rfind("(after:") else {
+        return (text, Vec::new());
+    };
+    let Some(list) = text[start..]
+        .strip_prefix("(after:")
+        .and_then(|r| r.strip_suffix(')'))
+    else {
+        return (text, Vec::new());
+    };
+    let refs = list
+        .split(',')
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(|r| match r.parse() {
+            Ok(index) => Ref::Index(index),
+            Err(_) => Ref::Name(r.to_string()),
+        })
+        .collect();
+    (text[..start].trim_end(), refs)
+}
+
+/// Resolves a reference made by the item at `item` (0-based)
+fn resolve_ref(project: &Project, items: &[Item], r: &Ref, item: usize) -> Result<Target> {
+    match r {
+        Ref::Index(n) if (1..=items.len()).contains(n) => Ok(Target::Batch(n - 1)),
+        Ref::Index(n) => Err(invalid(
+            item,
+            &format!("refers to item {}, but the batch has {}", n, items.len()),
+        )),
+        Ref::Name(name) => {
+            let name = name.trim();
+            let matches: Vec<usize> = items
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| other.title.trim().eq_ignore_ascii_case(name))
+                .map(|(i, _)| i)
+                .collect();
+            match matches[..] {
+                [i] => Ok(Target::Batch(i)),
+                [] => project.resolve_task_id(name).map(Target::Existing).map_err(|_| {
+                    invalid(
+                        item,
+                        &format!("refers to \"{}\", which is neither a title in the batch nor a task", name),
+                    )
+                }),
+                _ => Err(invalid(
+                    item,
+                    &format!(
+                        "refers to \"{}\", which {} items share; use its position instead",
+                        name,
+                        matches.len()
+                    ),
+                )),
+            }
+        }
+    }
+}
+
+fn invalid(item: usize, problem: &str) -> anyhow::Error {
+    CliError::new(
+        ErrorCode::Validation,
+        format!("Item {} {}", item + 1, problem),
+    )
+    .into()
+}
+
+fn title_of(batch: &[Task], id: &TaskId) -> String {
+    match batch.iter().find(|t| &t.id == id) {
+        Some(task) => format!("\"{}\"", task.title),
+        None => id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checklist_nesting_and_after() {
+        let items = parse(
+            "# Plan\n\
+             - [ ] Schema\n\
+             - [x] API (after: Schema, 1)\n\
+             \x20 - [ ] Endpoints\n\
+             \x20   - [ ] Auth endpoint\n\
+             \x20 - [ ] Docs\n\
+             - [ ] Ship (after: 2)\n\
+             - not a task\n",
+        )
+        .unwrap();
+
+        let titles: Vec<&str> = items.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            [
+                "Schema",
+                "API",
+                "Endpoints",
+                "Auth endpoint",
+                "Docs",
+                "Ship"
+            ]
+        );
+        assert_eq!(items[1].status, TaskStatus::Done);
+        assert_eq!(items[1].depends_on.len(), 2);
+        let parent = |i: usize| match items[i].parent {
+            Some(Ref::Index(p)) => Some(p),
+            _ => None,
+        };
+        assert_eq!(parent(0), None);
+        assert_eq!(parent(2), Some(2));
+        assert_eq!(parent(3), Some(3));
+        assert_eq!(parent(4), Some(2));
+        assert_eq!(parent(5), None);
+    }
+
+    #[test]
+    fn jsonl_refs_by_index_or_title() {
+        let items = parse("{\"title\": \"A\"}\n\n{\"title\": \"B\", \"depends_on\": [1, \"A\"]}\n")
+            .unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(matches!(items[1].depends_on[0], Ref::Index(1)));
+        assert!(matches!(&items[1].depends_on[1], Ref::Name(n) if n == "A"));
+    }
+}
@@ -0,0 +1,282 @@
+//! Team directory commands and @-mentions
+//!
+//! `.shape/team.toml` lists the humans and agents on a project (see
+//! [`TeamStore`](crate::storage::TeamStore)). Once it exists, `@handle`
+//! mentions in notes must name a member, `shape doctor` flags unknown ones in
+//! brief bodies, and each note that mentions someone fires the `mention`
+//! hook.
+
+use anyhow::Result;
+use clap::Subcommand;
+use serde_json::json;
+
+use super::error::{CliError, ErrorCode};
+use super::hooks;
+use super::output::Output;
+use crate::domain::{is_valid_handle, mentions, MemberKind, Task, TeamMember};
+use crate::storage::Project;
+
+#[derive(Subcommand)]
+pub enum TeamCommands {
+    /// List team members
+    List,
+
+    /// Add a member, or update one with the same handle
+    ///
+    /// Example:
+    ///   shape team add alice --name "Alice Chen" --alias achen
+    ///   shape team add reviewer --agent
+    ///   shape team add db-bot --agent --interests backend,db,-frontend
+    Add {
+        /// Handle used in @mentions
+        handle: String,
+
+        /// Display name
+        #[arg(long)]
+        name: Option<String>,
+
+        /// The member is an agent rather than a person
+        #[arg(long)]
+        agent: bool,
+
+        /// Another name the member goes by, e.g. its agent name (repeatable)
+        #[arg(long = "alias")]
+        aliases: Vec<String>,
+
+        /// Labels the member prefers in `shape next`, `-label` to disclaim
+        /// one; replaces the member's interests
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+        interests: Option<Vec<String>>,
+    },
+
+    /// Remove a member
+    Remove {
+        /// Handle of the member
+        handle: String,
+    },
+}
+
+pub fn run(cmd: TeamCommands, output: &Output) -> Result<()> {
+    match cmd {
+        TeamCommands::List => list(output),
+        TeamCommands::Add {
+            handle,
+            name,
+            agent,
+            aliases,
+            interests,
+        } => add(output, &handle, name, agent, aliases, interests),
+        TeamCommands::Remove { handle } => remove(output, &handle),
+    }
+}
+
+fn member_json(member: &TeamMember) -> serde_json::Value {
+    json!({
+        "handle": member.handle,
+        "name": member.name,
+        "kind": member.kind,
+        "aliases": member.aliases,
+        "interests": member.interests,
+    })
+}
+
+fn list(output: &Output) -> Result<()> {
+    let project = Project::open_current()?;
+    let team = project.team_store().read()?;
+
+    if output.is_json() {
+        let members: Vec<_> = team.members.iter().map(member_json).collect();
+        output.data(&members);
+        return Ok(());
+    }
+
+    if team.members.is_empty() {
+        println!("No team members. Add one with 'shape team add <handle>'.");
+        return Ok(());
+    }
+    for member in &team.members {
+        let mut line = format!("@{} ({})", member.handle, member.kind.as_str());
+        if let Some(name) = &member.name {
+            line.push_str(&format!("  {}", name));
+        }
+        if !member.aliases.is_empty() {
+            line.push_str(&format!("  aka {}", member.aliases.join(", ")));
+        }
+        if !member.interests.is_empty() {
+            line.push_str(&format!("  interests: {}", member.interests.join(", ")));
+        }
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+fn add(
+    output: &Output,
+    handle: &str,
+    name: Option<String>,
+    agent: bool,
+    aliases: Vec<String>,
+    interests: Option<Vec<String>>,
+) -> Result<()> {
+    let handle = handle.trim_start_matches('@');
+    if !is_valid_handle(handle) {
+        return Err(CliError::new(
+            ErrorCode::Validation,
+            format!(
+                "Invalid handle '{}': use letters, digits, '-', '_' and '.'",
+                handle
+            ),
+        )
+        .into());
+    }
+
+    let project = Project::open_current()?;
+    let store = project.team_store();
+    let mut team = store.read()?;
+
+    // A handle or alias can only point at one member
+    let taken = std::iter::once(handle)
+        .chain(aliases.iter().map(String::as_str))
+        .find_map(|n| team.find(n).filter(|m| m.handle != handle));
+    if let Some(other) = taken {
+        return Err(CliError::new(
+            ErrorCode::Conflict,
+            format!("@{} already answers to one of these names", other.handle),
+        )
+        .into());
+    }
+
+    let kind = if agent {
+        MemberKind::Agent
+    } else {
+        MemberKind::Human
+    };
+    let updated = team.members.iter().any(|m| m.handle == handle);
+    match team.members.iter_mut().find(|m| m.handle == handle) {
+        Some(member) => {
+            member.kind = kind;
+            if name.is_some() {
+                member.name = name;
+            }
+            for alias in aliases {
+                if !member.aliases.contains(&alias) {
+                    member.aliases.push(alias);
+                }
+            }
+            if let Some(interests) = interests {
+                member.interests = interests;
+            }
+        }
+        None => {
+            let mut member = TeamMember::new(handle, kind);
+            member.name = name;
+            member.aliases = aliases;
+            member.interests = interests.unwrap_or_default();
+            team.members.push(member);
+        }
+    }
+    store.write(&team)?;
+
+    let member = team.find(handle).expect("member was just added");
+    if output.is_json() {
+        output.data(&member_json(member));
+    } else if updated {
+        output.success(&format!("Updated @{}", member.handle));
+    } else {
+        output.success(&format!("Added @{}", member.handle));
+    }
+    Ok(())
+}
+
+fn remove(output: &Output, handle: &str) -> Result<()> {
+    let handle = handle.trim_start_matches('@');
+    let project = Project::open_current()?;
+    let store = project.team_store();
+    let mut team = store.read()?;
+
+    let before = team.members.len();
+    team.members.retain(|m| m.handle != handle);
+    if team.members.len() == before {
+        return Err(CliError::not_found("Team member", format!("@{}", handle)).into());
+    }
+    store.write(&team)?;
+
+    if output.is_json() {
+        output.data(&json!({ "handle": handle, "removed": true }));
+    } else {
+        output.success(&format!("Removed @{}", handle));
+    }
+    Ok(())
+}
+
+/// Handles mentioned in `text`. With a team directory, every mention must
+/// name a member and comes back as the member's handle.
+pub(crate) fn check_mentions(project: &Project, text: &str) -> Result<Vec<String>> {
+    let found = mentions(text);
+    let store = project.team_store();
+    if found.is_empty() || !store.exists() {
+        return Ok(found);
+    }
+
+    let team = store.read()?;
+    let unknown = team.unknown_mentions(text);
+    if !unknown.is_empty() {
+        let known: Vec<String> = team
+            .members
+            .iter()
+            .map(|m| format!("@{}", m.handle))
+            .collect();
+        return Err(CliError::new(
+            ErrorCode::Validation,
+            format!(
+                "Unknown mention(s): {}. Team members: {}",
+                unknown
+                    .iter()
+                    .map(|m| format!("@{}", m))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                if known.is_empty() {
+                    "none".to_string()
+                } else {
+                    known.join(", ")
+                }
+            ),
+        )
+        .into());
+    }
+
+    let mut handles: Vec<String> = Vec::new();
+    for name in found {
+        let handle = team.find(&name).map_or(name, |m| m.handle.clone());
+        if !handles.contains(&handle) {
+            handles.push(handle);
+        }
+    }
+    Ok(handles)
+}
+
+/// Fires the `mention` hook for a note on `task` mentioning `handles`.
+/// A failing hook is reported but doesn't undo the note.
+pub(crate) fn notify_mentions(
+    project: &Project,
+    task: &Task,
+    by: &str,
+    handles: &[String],
+    text: &str,
+) {
+    if handles.is_empty() {
+        return;
+    }
+    let payload = json!({
+        "mentions": handles,
+        "by": by,
+        "task": {
+            "id": task.id.to_string(),
+            "title": task.title,
+        },
+        "text": text,
+    });
+    if let Err(e) = hooks::fire(project, "mention", &payload) {
+        eprintln!("Warning: {:#}", e);
+    }
+}
@@ -0,0 +1,260 @@
+//! Task template commands
+//!
+//! Templates are reusable single-task shapes stored under
+//! `.shape/templates/tasks/`. Text fields may contain `{{param}}`
+//! placeholders filled in with `--set param=value`:
+//!
+//! ```bash
+//! shape task template add code-review --title "Review {{pr}}" --label review --estimate 1
+//! shape task from-template code-review --brief b-1234567 --set pr=412
+//! ```
+//!
+//! Unlike pipelines, a template creates one task.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use super::agent;
+use super::error::{CliError, ErrorCode};
+use super::output::Output;
+use super::task::allocate_task_id;
+use crate::domain::Task;
+use crate::storage::{Project, TaskTemplate};
+
+#[derive(Subcommand)]
+pub enum TemplateCommands {
+    /// Create or replace a task template
+    ///
+    /// Example:
+    ///   shape task template add code-review --title "Review {{pr}}" --label review
+    Add {
+        /// Template name
+        name: String,
+
+        /// Task title ({{param}} placeholders allowed)
+        #[arg(long)]
+        title: String,
+
+        /// Task description ({{param}} placeholders allowed)
+        #[arg(long, short)]
+        description: Option<String>,
+
+        /// Label to apply (repeatable)
+        #[arg(long = "label")]
+        labels: Vec<String>,
+
+        /// Estimate to apply
+        #[arg(long)]
+        estimate: Option<i64>,
+    },
+
+    /// List task templates
+    List,
+
+    /// Show a task template and its parameters
+    Show {
+        /// Template name
+        name: String,
+    },
+
+    /// Delete a task template
+    Remove {
+        /// Template name
+        name: String,
+    },
+}
+
+pub fn run(cmd: TemplateCommands, output: &Output) -> Result<()> {
+    match cmd {
+        TemplateCommands::Add {
+            name,
+            title,
+            description,
+            labels,
+            estimate,
+        } => add_template(
+            output,
+            &name,
+            TaskTemplate {
+                title,
+                description,
+                labels,
+                estimate,
+            },
+        ),
+        TemplateCommands::List => list_templates(output),
+        TemplateCommands::Show { name } => show_template(output, &name),
+        TemplateCommands::Remove { name } => remove_template(output, &name),
+    }
+}
+
+fn add_template(output: &Output, name: &str, template: TaskTemplate) -> Result<()> {
+    let project = Project::open_current()?;
+    project.template_store().write(name, &template)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "name": name,
+            "template": template,
+            "params": template.params(),
+        }));
+    } else {
+        output.success(&format!("Saved task template '{}'", name));
+    }
+
+    Ok(())
+}
+
+fn list_templates(output: &Output) -> Result<()> {
+    let project = Project::open_current()?;
+    let templates = project.template_store().list()?;
+
+    if output.is_json() {
+        let items: Vec<_> = templates
+            .iter()
+            .map(|(name, t)| {
+                serde_json::json!({
+                    "name": name,
+                    "title": t.title,
+                    "params": t.params(),
+                })
+            })
+            .collect();
+        output.data(&items);
+    } else if templates.is_empty() {
+        println!(
+            "No task templates. Create one with: shape task template add <NAME> --title <TITLE>"
+        );
+    } else {
+        for (name, template) in &templates {
+            println!("{:<20} {}", name, template.title);
+        }
+    }
+
+    Ok(())
+}
+
+fn show_template(output: &Output, name: &str) -> Result<()> {
+    let project = Project::open_current()?;
+    let template = find(&project, name)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "name": name,
+            "template": template,
+            "params": template.params(),
+        }));
+    } else {
+        println!("Template: {}", name);
+        println!("Title: {}", template.title);
+        if let Some(description) = &template.description {
+            println!("Description: {}", description);
+        }
+        if !template.labels.is_empty() {
+            println!("Labels: {}", template.labels.join(", "));
+        }
+        if let Some(estimate) = template.estimate {
+            println!("Estimate: {}", estimate);
+        }
+        let params = template.params();
+        if !params.is_empty() {
+            println!("Params: {}", params.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+fn remove_template(output: &Output, name: &str) -> Result<()> {
+    let project = Project::open_current()?;
+    if !project.template_store().remove(name)? {
+        return Err(CliError::not_found("Task template", name).into());
+    }
+
+    if output.is_json() {
+        output.data(&serde_json::json!({ "removed": name }));
+    } else {
+        output.success(&format!("Removed task template '{}'", name));
+    }
+
+    Ok(())
+}
+
+fn find(project: &Project, name: &str) -> Result<TaskTemplate> {
+    project.template_store().read(name)?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "Task template not found: {}. Create it with: shape task template add {} --title <TITLE>",
+            name,
+            name
+        )
+    })
+}
+
+/// Parses `key=value` pairs from `--set`
+fn parse_params(pairs: &[String]) -> Result<HashMap<String, String>> {
+    pairs
+        .iter()
+        .map(|pair| {
+            pair.split_once('=')
+                .map(|(k, v)| (k.trim().to_string(), v.to_string()))
+                .ok_or_else(|| {
+                    CliError::new(
+                        ErrorCode::Validation,
+                        format!("Invalid --set '{}': expected key=value", pair),
+                    )
+                    .into()
+                })
+        })
+        .collect()
+}
+
+/// Creates a task from a template
+pub fn from_template(
+    output: &Output,
+    name: &str,
+    parent: Option<&str>,
+    set: &[String],
+) -> Result<()> {
+    let project = Project::open_current()?;
+    let rendered = find(&project, name)?.render(&parse_params(set)?)?;
+
+    let task_id = allocate_task_id(&project, parent, &rendered.title)?;
+    let mut task = Task::new(task_id, rendered.title);
+    task.description = rendered.description.or_else(|| {
+        project
+            .config()
+            .project
+            .description_template(&rendered.labels)
+            .map(str::to_string)
+    });
+    if !rendered.labels.is_empty() {
+        task.set_meta("labels", rendered.labels);
+    }
+    if let Some(estimate) = rendered.estimate {
+        task.set_meta("estimate", estimate);
+    }
+    let spawned_from = agent::save_new_task(&project, &mut task)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": task.id.to_string(),
+            "title": task.title,
+            "status": task.status,
+            "template": name,
+            "meta": task.meta,
+            "spawned_from": spawned_from.map(|id| id.to_string()),
+        }));
+    } else {
+        output.success(&format!(
+            "Created task from '{}': {} - {}",
+            name, task.id, task.title
+        ));
+        if let Some(parent) = spawned_from {
+            println!("  Provenance: spawned from claimed task {}", parent);
+        }
+    }
+
+    Ok(())
+}
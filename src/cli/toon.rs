@@ -0,0 +1,489 @@
+//! TOON: a token-lean tabular text form of JSON output
+//!
+//! `--format toon` transcodes the JSON a command would print into indented
+//! `key: value` lines where arrays carry their length and arrays of flat
+//! records become a header plus one comma-separated row per record:
+//!
+//! ```text
+//! # toon: key[N]: a,b is a list; key[N]{f,g}: is followed by N rows of f,g; "..." is a JSON string
+//! briefs[2]{id,status,title}:
+//!   b-7f2a3b1,proposed,Auth rework
+//!   b-9c1d2e4,in_progress,"Billing, phase 2"
+//! ready[2]: b-7f2a3b1.1: Design schema,b-7f2a3b1.2: Build API
+//! standalone_tasks:
+//!   blocked[0]:
+//! ```
+//!
+//! Strings are bare unless they would read back as something else (a number,
+//! `true`, a delimiter, surrounding spaces...), in which case they are JSON
+//! quoted, so [`from_toon`] gives back exactly the value that was encoded.
+//! Lists that are neither flat values nor uniform records fall back to one
+//! `- item` per line.
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::{Map, Value};
+
+/// First line of every document, so a reader needs no prior knowledge of the
+/// format
+pub(super) const LEGEND: &str = "# toon: key[N]: a,b is a list; key[N]{f,g}: is followed by N rows of f,g; \"...\" is a JSON string";
+
+const INDENT: &str = "  ";
+
+/// Encodes a JSON value as TOON, legend first
+pub fn to_toon(value: &Value) -> String {
+    let mut lines = vec![LEGEND.to_string()];
+    match value {
+        Value::Object(map) => encode_fields(map, 0, &mut lines),
+        Value::Array(items) => encode_array("", items, 0, &mut lines),
+        // A bare scalar is always quoted so it can't be mistaken for a field
+        scalar => lines.push(scalar.to_string()),
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+fn encode_fields(map: &Map<String, Value>, depth: usize, lines: &mut Vec<String>) {
+    for (key, value) in map {
+        let key = encode_key(key);
+        match value {
+            Value::Object(fields) => {
+                lines.push(format!("{}{}:", INDENT.repeat(depth), key));
+                encode_fields(fields, depth + 1, lines);
+            }
+            Value::Array(items) => encode_array(&key, items, depth, lines),
+            scalar => lines.push(format!(
+                "{}{}: {}",
+                INDENT.repeat(depth),
+                key,
+                encode_scalar(scalar, false)
+            )),
+        }
+    }
+}
+
+fn encode_array(key: &str, items: &[Value], depth: usize, lines: &mut Vec<String>) {
+    let pad = INDENT.repeat(depth);
+    if items.is_empty() {
+        lines.push(format!("{}{}[0]:", pad, key));
+    } else if items.iter().all(is_scalar) {
+        let row: Vec<String> = items.iter().map(|v| encode_scalar(v, true)).collect();
+        lines.push(format!(
+            "{}{}[{}]: {}",
+            pad,
+            key,
+            items.len(),
+            row.join(",")
+        ));
+    } else if let Some(fields) = table_fields(items) {
+        let header: Vec<String> = fields.iter().map(|f| encode_key(f)).collect();
+        lines.push(format!(
+            "{}{}[{}]{{{}}}:",
+            pad,
+            key,
+            items.len(),
+            header.join(",")
+        ));
+        let row_pad = INDENT.repeat(depth + 1);
+        for item in items {
+            let row: Vec<String> = fields
+                .iter()
+                .map(|f| encode_scalar(&item[f.as_str()], true))
+                .collect();
+            lines.push(format!("{}{}", row_pad, row.join(",")));
+        }
+    } else {
+        lines.push(format!("{}{}[{}]:", pad, key, items.len()));
+        let item_pad = INDENT.repeat(depth + 1);
+        for item in items {
+            match item {
+                Value::Object(fields) => {
+                    lines.push(format!("{}-", item_pad));
+                    encode_fields(fields, depth + 2, lines);
+                }
+                Value::Array(nested) => {
+                    lines.push(format!("{}-", item_pad));
+                    encode_array("", nested, depth + 2, lines);
+                }
+                scalar => lines.push(format!("{}- {}", item_pad, encode_scalar(scalar, false))),
+            }
+        }
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(value, Value::Array(_) | Value::Object(_))
+}
+
+/// Field names shared by every item, if all items are non-empty objects with
+/// the same keys and only scalar values
+fn table_fields(items: &[Value]) -> Option<Vec<String>> {
+    let first = items.first()?.as_object()?;
+    if first.is_empty() {
+        return None;
+    }
+    let fields: Vec<String> = first.keys().cloned().collect();
+    let uniform = items.iter().all(|item| {
+        item.as_object().is_some_and(|map| {
+            map.len() == fields.len() && fields.iter().all(|f| map.get(f).is_some_and(is_scalar))
+        })
+    });
+    uniform.then_some(fields)
+}
+
+fn encode_key(key: &str) -> String {
+    let bare = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if bare {
+        key.to_string()
+    } else {
+        Value::from(key).to_string()
+    }
+}
+
+/// A scalar as written; `delimited` values sit in a comma-separated list
+fn encode_scalar(value: &Value, delimited: bool) -> String {
+    match value {
+        Value::String(s) if needs_quotes(s, delimited) => value.to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn needs_quotes(s: &str, delimited: bool) -> bool {
+    s.is_empty()
+        || s.trim() != s
+        || s.starts_with('#')
+        || s.contains('"')
+        || s.contains(|c: char| c.is_control())
+        || (delimited && s.contains(','))
+        || !matches!(decode_bare(s), Value::String(_))
+}
+
+/// Decodes a TOON document back into the JSON value it was encoded from
+pub fn from_toon(text: &str) -> Result<Value> {
+    let lines: Vec<Line> = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty() && !l.trim_start().starts_with('#'))
+        .map(|(i, l)| Line::new(i + 1, l))
+        .collect::<Result<_>>()?;
+    let Some(first) = lines.first() else {
+        return Ok(Value::Object(Map::new()));
+    };
+
+    let mut pos = 0;
+    if first.text.starts_with('[') {
+        let value = decode_array_line(&lines, &mut pos, 0)?.1;
+        ensure_consumed(&lines, pos)?;
+        return Ok(value);
+    }
+    if lines.len() == 1 && split_field(first.text).is_none() {
+        return decode_scalar(first.text).map_err(|e| first.error(e));
+    }
+    let value = decode_fields(&lines, &mut pos, 0)?;
+    ensure_consumed(&lines, pos)?;
+    Ok(Value::Object(value))
+}
+
+struct Line<'a> {
+    number: usize,
+    depth: usize,
+    text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    fn new(number: usize, raw: &'a str) -> Result<Self> {
+        let text = raw.trim_start_matches(' ');
+        let spaces = raw.len() - text.len();
+        if !spaces.is_multiple_of(INDENT.len()) {
+            bail!("line {}: indentation is not a multiple of two", number);
+        }
+        Ok(Self {
+            number,
+            depth: spaces / INDENT.len(),
+            text: text.trim_end(),
+        })
+    }
+
+    fn error(&self, e: impl std::fmt::Display) -> anyhow::Error {
+        anyhow!("line {}: {}", self.number, e)
+    }
+}
+
+fn ensure_consumed(lines: &[Line], pos: usize) -> Result<()> {
+    match lines.get(pos) {
+        Some(line) => Err(line.error("unexpected indentation")),
+        None => Ok(()),
+    }
+}
+
+fn decode_fields(lines: &[Line], pos: &mut usize, depth: usize) -> Result<Map<String, Value>> {
+    let mut map = Map::new();
+    while let Some(line) = lines.get(*pos) {
+        if line.depth < depth {
+            break;
+        }
+        if line.depth > depth {
+            return Err(line.error("unexpected indentation"));
+        }
+        let (key, value) = if is_array_header(line.text) {
+            decode_array_line(lines, pos, depth)?
+        } else {
+            let (key, rest) =
+                split_field(line.text).ok_or_else(|| line.error("expected 'key: value'"))?;
+            let key = decode_key(key).map_err(|e| line.error(e))?;
+            *pos += 1;
+            if rest.is_empty() {
+                (key, Value::Object(decode_fields(lines, pos, depth + 1)?))
+            } else {
+                (key, decode_scalar(rest).map_err(|e| line.error(e))?)
+            }
+        };
+        map.insert(key, value);
+    }
+    Ok(map)
+}
+
+/// Returns true if the key part of a field line ends in `[N]` or `[N]{...}`
+fn is_array_header(text: &str) -> bool {
+    split_field(text).is_some_and(|(key, _)| key.ends_with(']') || key.ends_with('}'))
+}
+
+/// Splits `key: value` (or `key:`) at the first colon outside quotes
+fn split_field(text: &str) -> Option<(&str, &str)> {
+    let at = find_unquoted(text, ':')?;
+    let rest = &text[at + 1..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some((&text[..at], rest.trim_start()))
+}
+
+/// Decodes an array field starting at `lines[*pos]`, returning its key
+fn decode_array_line(lines: &[Line], pos: &mut usize, depth: usize) -> Result<(String, Value)> {
+    let line = &lines[*pos];
+    let (head, rest) = split_field(line.text).ok_or_else(|| line.error("expected 'key[N]:'"))?;
+    let open = rfind_unquoted(head, '[').ok_or_else(|| line.error("expected '[N]'"))?;
+    let key = if open == 0 {
+        String::new()
+    } else {
+        decode_key(&head[..open]).map_err(|e| line.error(e))?
+    };
+    let close = head[open..]
+        .find(']')
+        .map(|i| open + i)
+        .ok_or_else(|| line.error("unclosed '['"))?;
+    let len: usize = head[open + 1..close]
+        .parse()
+        .map_err(|_| line.error("array length is not a number"))?;
+    let fields = &head[close + 1..];
+    *pos += 1;
+
+    let items = if let Some(fields) = fields.strip_prefix('{').and_then(|f| f.strip_suffix('}')) {
+        let fields: Vec<String> = split_unquoted(fields)
+            .into_iter()
+            .map(decode_key)
+            .collect::<Result<_>>()
+            .map_err(|e| line.error(e))?;
+        let mut rows = Vec::with_capacity(len);
+        for _ in 0..len {
+            let row = lines
+                .get(*pos)
+                .filter(|l| l.depth == depth + 1)
+                .ok_or_else(|| line.error(format!("expected {} rows", len)))?;
+            let cells = split_unquoted(row.text);
+            if cells.len() != fields.len() {
+                return Err(row.error(format!("expected {} values", fields.len())));
+            }
+            let mut record = Map::new();
+            for (field, cell) in fields.iter().zip(cells) {
+                record.insert(
+                    field.clone(),
+                    decode_scalar(cell).map_err(|e| row.error(e))?,
+                );
+            }
+            rows.push(Value::Object(record));
+            *pos += 1;
+        }
+        rows
+    } else if !fields.is_empty() {
+        return Err(line.error("expected '{fields}' after the array length"));
+    } else if !rest.is_empty() {
+        split_unquoted(rest)
+            .into_iter()
+            .map(decode_scalar)
+            .collect::<Result<_>>()
+            .map_err(|e| line.error(e))?
+    } else {
+        let mut items = Vec::with_capacity(len);
+        while items.len() < len {
+            let item = lines
+                .get(*pos)
+                .filter(|l| l.depth == depth + 1 && l.text.starts_with('-'))
+                .ok_or_else(|| line.error(format!("expected {} items", len)))?;
+            *pos += 1;
+            items.push(match item.text.strip_prefix("- ") {
+                Some(scalar) => decode_scalar(scalar).map_err(|e| item.error(e))?,
+                None if item.text == "-" => match lines.get(*pos) {
+                    Some(next) if next.depth == depth + 2 && next.text.starts_with('[') => {
+                        decode_array_line(lines, pos, depth + 2)?.1
+                    }
+                    _ => Value::Object(decode_fields(lines, pos, depth + 2)?),
+                },
+                None => return Err(item.error("expected '- item'")),
+            });
+        }
+        items
+    };
+
+    if items.len() != len {
+        return Err(line.error(format!("expected {} items, found {}", len, items.len())));
+    }
+    Ok((key, Value::Array(items)))
+}
+
+fn decode_key(key: &str) -> Result<String> {
+    if key.starts_with('"') {
+        Ok(serde_json::from_str(key)?)
+    } else if key.is_empty() {
+        bail!("missing key")
+    } else {
+        Ok(key.to_string())
+    }
+}
+
+fn decode_scalar(text: &str) -> Result<Value> {
+    if text.starts_with('"') {
+        return Ok(Value::String(serde_json::from_str(text)?));
+    }
+    Ok(decode_bare(text))
+}
+
+/// Reads an unquoted scalar: a JSON literal or number, else the text itself
+fn decode_bare(text: &str) -> Value {
+    match text {
+        "null" => Value::Null,
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => match serde_json::from_str::<serde_json::Number>(text) {
+            Ok(n) => Value::Number(n),
+            Err(_) => Value::String(text.to_string()),
+        },
+    }
+}
+
+/// Byte offsets of `target` outside JSON-quoted strings
+fn unquoted_positions(text: &str, target: char) -> impl Iterator<Item = usize> + '_ {
+    let mut quoted = false;
+    let mut escaped = false;
+    text.char_indices().filter_map(move |(i, c)| {
+        if quoted {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => quoted = false,
+                _ => {}
+            }
+            None
+        } else if c == '"' {
+            quoted = true;
+            None
+        } else {
+            (c == target).then_some(i)
+        }
+    })
+}
+
+fn find_unquoted(text: &str, target: char) -> Option<usize> {
+    unquoted_positions(text, target).next()
+}
+
+fn rfind_unquoted(text: &str, target: char) -> Option<usize> {
+    unquoted_positions(text, target).last()
+}
+
+fn split_unquoted(text: &str) -> Vec<&str> {
+    let mut cells = Vec::new();
+    let mut start = 0;
+    for at in unquoted_positions(text, ',') {
+        cells.push(&text[start..at]);
+        start = at + 1;
+    }
+    cells.push(&text[start..]);
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A compact context as `shape context --compact` prints it
+    fn compact_context() -> Value {
+        json!({
+            "briefs": [
+                {"id": "b-7f2a3b1", "title": "Auth rework", "status": "in_progress", "summary": "Move to OAuth: phase 1"},
+                {"id": "b-9c1d2e4", "title": "Billing, phase 2", "status": "proposed", "summary": ""},
+            ],
+            "ready": ["b-7f2a3b1.1: Design schema", "b-7f2a3b1.2: Retry, then \"fail\""],
+            "in_progress": ["b-7f2a3b1.3:  Leading spaces "],
+            "blocked": ["b-7f2a3b1.4: Ship (blocked by b-7f2a3b1.1, b-7f2a3b1.2)"],
+            "recently_done": [],
+            "compacted": [
+                {"id": "b-7f2a3b1.5", "summary": "Set up CI\nand lint", "task_count": 3, "completed_at": "2026-10-01T12:00:00Z"},
+                {"id": "b-7f2a3b1.6", "summary": "true", "task_count": 2, "completed_at": null},
+            ],
+            "standalone_tasks": {"ready": ["t-1a2b3c4: 42"], "in_progress": [], "blocked": []},
+            "pending_reviews": ["b-7f2a3b1.3: #alice"],
+            "epics": [{"id": "b-7f2a3b1", "children": ["b-9c1d2e4"]}, "- loose"],
+            "context_schema_version": 2,
+            "weird key": {"": -1.5, "nested": {}},
+        })
+    }
+
+    #[test]
+    fn compact_context_round_trips() {
+        let context = compact_context();
+        let text = to_toon(&context);
+        assert_eq!(from_toon(&text).unwrap(), context);
+    }
+
+    #[test]
+    fn records_become_rows_under_a_legend() {
+        let text = to_toon(&compact_context());
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], LEGEND);
+        assert!(lines.contains(&"briefs[2]{id,status,summary,title}:"));
+        assert!(lines.contains(&"  b-9c1d2e4,proposed,\"\",\"Billing, phase 2\""));
+        assert!(lines.contains(&"recently_done[0]:"));
+        assert!(text.contains("ready[2]: b-7f2a3b1.1: Design schema,\"b-7f2a3b1.2: Retry"));
+        assert!(text.len() < serde_json::to_string(&compact_context()).unwrap().len());
+    }
+
+    #[test]
+    fn top_level_arrays_and_scalars_round_trip() {
+        for value in [
+            json!([{"id": "a", "n": 1}, {"id": "b", "n": 2}]),
+            json!([[1, 2], [], {"a": null}]),
+            json!("plain: text"),
+            json!(7),
+            json!({}),
+        ] {
+            assert_eq!(
+                from_toon(&to_toon(&value)).unwrap(),
+                value,
+                "{}",
+                to_toon(&value)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_rows_of_the_wrong_width() {
+        let err = from_toon("items[1]{a,b}:\n  1\n").unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+}
@@ -0,0 +1,92 @@
+//! `shape brief export --translate`: localized copies of briefs
+//!
+//! A translate plugin (`shape-translate-<name>`) receives the brief's title
+//! and sections and returns them translated. The result is stored under
+//! `.shape/briefs/i18n/`; the canonical brief is never modified.
+
+use anyhow::Result;
+use chrono::Utc;
+
+use super::error::CliError;
+use super::output::Output;
+use crate::domain::Brief;
+use crate::plugin::{join_sections, PluginLoader, TranslatePlugin};
+use crate::storage::{Project, Translation};
+
+pub(super) fn export(output: &Output, id_str: &str, lang: &str, via: &str) -> Result<()> {
+    let project = Project::open_current()?;
+    let id = project.resolve_brief_id(id_str)?;
+    let brief = project
+        .brief_store()
+        .read(&id)?
+        .ok_or_else(|| CliError::not_found("Brief", &id))?;
+
+    let mut loader = PluginLoader::new();
+    loader.add_plugin_dir(project.plugins_dir());
+    loader.discover()?;
+    let plugin_name = full_plugin_name(via);
+    if loader.get(&plugin_name).is_none() {
+        anyhow::bail!(
+            "Translate plugin not found: {}. Install it or check 'shape plugin list'.",
+            plugin_name
+        );
+    }
+
+    output.verbose_ctx(
+        "translate",
+        &format!("Translating {} into {} via {}", id, lang, plugin_name),
+    );
+    let translated = TranslatePlugin::new(&loader, &plugin_name).translate(&brief, lang)?;
+    let translation = Translation {
+        brief: id.clone(),
+        lang: lang.to_string(),
+        title: translated.title,
+        via: plugin_name,
+        source_updated_at: brief.updated_at,
+        translated_at: Utc::now(),
+        body: join_sections(&translated.sections),
+    };
+    let path = project.translation_store().write(&translation)?;
+    let relative = path.strip_prefix(project.root()).unwrap_or(&path);
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": id.to_string(),
+            "lang": translation.lang,
+            "title": translation.title,
+            "via": translation.via,
+            "path": relative.display().to_string(),
+        }));
+    } else {
+        output.success(&format!(
+            "Translated {} into {}: {}",
+            id,
+            lang,
+            relative.display()
+        ));
+    }
+    Ok(())
+}
+
+/// The brief as `brief show --lang` displays it: the translation's title and
+/// body when there is one, else the canonical brief
+pub(super) fn localized(
+    project: &Project,
+    mut brief: Brief,
+    lang: &str,
+) -> Result<(Brief, Option<Translation>)> {
+    let Some(translation) = project.translation_store().read(&brief.id, lang)? else {
+        return Ok((brief, None));
+    };
+    brief.title = translation.title.clone();
+    brief.body = translation.body.clone();
+    Ok((brief, Some(translation)))
+}
+
+fn full_plugin_name(name: &str) -> String {
+    if name.starts_with("shape-translate-") {
+        name.to_string()
+    } else {
+        format!("shape-translate-{}", name)
+    }
+}
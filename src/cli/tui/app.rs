@@ -6,11 +6,13 @@ use anyhow::Result;
 use crossterm::event::{KeyCode, KeyModifiers};
 use ratatui::prelude::*;
 
+use super::conflict::{self, Choice, ConflictFile};
 use super::event::{Event, EventHandler};
 use super::ui::Terminal;
 use super::views;
 use super::ViewMode;
-use crate::domain::{Brief, BriefId, BriefStatus, Task, TaskId, TaskStatus};
+use crate::cli::render::Theme;
+use crate::domain::{place, rank_cmp, Brief, BriefId, BriefStatus, Task, TaskId, TaskStatus};
 use crate::storage::Project;
 
 /// Which panel has focus
@@ -62,6 +64,9 @@ pub struct App {
     /// Current project
     project: Project,
 
+    /// Status icons and label colors
+    theme: Theme,
+
     /// All briefs
     briefs: HashMap<BriefId, Brief>,
 
@@ -106,6 +111,15 @@ pub struct App {
 
     /// Pending edit action (path to file to edit)
     pending_edit: Option<std::path::PathBuf>,
+
+    /// Brief files with git conflict markers
+    conflicts: Vec<ConflictFile>,
+
+    /// Selected conflicted file
+    conflict_index: usize,
+
+    /// Selected hunk in that file
+    hunk_index: usize,
 }
 
 impl App {
@@ -117,6 +131,7 @@ impl App {
 
         let briefs = brief_store.read_all()?;
         let tasks = task_store.read_all()?;
+        let conflicts = conflict::find(brief_store.dir());
 
         // Sort briefs by status (active first) then by ID
         let mut brief_list: Vec<_> = briefs.keys().cloned().collect();
@@ -147,8 +162,10 @@ impl App {
                 .cloned()
         });
 
+        let theme = Theme::new(&project.config().project.display);
         let mut app = Self {
             project,
+            theme,
             briefs,
             tasks,
             brief_list,
@@ -164,8 +181,20 @@ impl App {
             should_quit: false,
             show_completed: false,
             pending_edit: None,
+            conflicts,
+            conflict_index: 0,
+            hunk_index: 0,
         };
 
+        // Conflicted briefs are skipped when reading, so resolve them first
+        if !app.conflicts.is_empty() {
+            app.view_mode = ViewMode::Conflicts;
+            app.status_message = Some(format!(
+                "{} brief(s) have merge conflicts",
+                app.conflicts.len()
+            ));
+        }
+
         // If we have a brief filter, select it
         if let Some(ref filter_id) = app.brief_filter {
             if let Some(idx) = app.brief_list.iter().position(|id| id == filter_id) {
@@ -241,6 +270,7 @@ impl App {
             ViewMode::Overview => views::overview::draw(frame, self),
             ViewMode::Kanban => views::kanban::draw(frame, self),
             ViewMode::Graph => views::graph::draw(frame, self),
+            ViewMode::Conflicts => views::conflict::draw(frame, self),
         }
     }
 
@@ -263,6 +293,10 @@ impl App {
 
     /// Handle keys in normal mode
     fn handle_normal_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        if self.view_mode == ViewMode::Conflicts && self.handle_conflict_key(key)? {
+            return Ok(());
+        }
+
         match key.code {
             // Quit
             KeyCode::Char('q') => {
@@ -277,6 +311,14 @@ impl App {
                 self.move_selection_up();
             }
 
+            // Reorder the selected task within its brief
+            KeyCode::Char('J') => {
+                self.move_task(true)?;
+            }
+            KeyCode::Char('K') => {
+                self.move_task(false)?;
+            }
+
             // Navigation: left/right (switch panels)
             KeyCode::Char('h') | KeyCode::Left => {
                 self.focus = self.focus.prev();
@@ -307,6 +349,9 @@ impl App {
             KeyCode::Char('3') => {
                 self.view_mode = ViewMode::Graph;
             }
+            KeyCode::Char('4') => {
+                self.view_mode = ViewMode::Conflicts;
+            }
 
             // Actions
             KeyCode::Char('s') => {
@@ -347,7 +392,7 @@ impl App {
             // Help
             KeyCode::Char('?') => {
                 self.status_message = Some(
-                    "j/k:move h/l:panel s:start d:done n:new task N:new brief /:search q:quit"
+                    "j/k:move J/K:reorder h/l:panel s:start d:done n:new task N:new brief /:search q:quit"
                         .to_string(),
                 );
             }
@@ -358,6 +403,106 @@ impl App {
         Ok(())
     }
 
+    /// Handle keys in the conflict view; returns false for the keys it
+    /// leaves to normal mode (quit, view switching)
+    fn handle_conflict_key(&mut self, key: crossterm::event::KeyEvent) -> Result<bool> {
+        let hunks = self
+            .conflicts
+            .get(self.conflict_index)
+            .map_or(0, |f| f.hunks.len());
+
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down if hunks > 0 => {
+                self.hunk_index = (self.hunk_index + 1) % hunks;
+            }
+            KeyCode::Char('k') | KeyCode::Up if hunks > 0 => {
+                self.hunk_index = (self.hunk_index + hunks - 1) % hunks;
+            }
+            KeyCode::Tab if !self.conflicts.is_empty() => {
+                self.conflict_index = (self.conflict_index + 1) % self.conflicts.len();
+                self.hunk_index = 0;
+            }
+            KeyCode::Char('o') => self.choose(Some(Choice::Ours)),
+            KeyCode::Char('t') => self.choose(Some(Choice::Theirs)),
+            KeyCode::Char('b') => self.choose(Some(Choice::Both)),
+            KeyCode::Char('u') => self.choose(None),
+            KeyCode::Char('w') => self.write_resolution()?,
+            KeyCode::Char('r') => {
+                self.conflicts = conflict::find(self.project.brief_store().dir());
+                self.conflict_index = 0;
+                self.hunk_index = 0;
+                self.status_message = Some("Rescanned briefs for conflicts".to_string());
+            }
+            KeyCode::Char('?') => {
+                self.status_message = Some(
+                    "j/k:hunk o:ours t:theirs b:both u:undo Tab:next file w:write+stage r:rescan q:quit"
+                        .to_string(),
+                );
+            }
+            KeyCode::Char('q' | '1'..='4') => return Ok(false),
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    /// Resolves the selected hunk
+    fn choose(&mut self, choice: Option<Choice>) {
+        let Some(file) = self.conflicts.get_mut(self.conflict_index) else {
+            return;
+        };
+        if let Some(hunk) = file.hunks.get_mut(self.hunk_index) {
+            hunk.choice = choice;
+        }
+        // Move on to the next unresolved hunk
+        if choice.is_some() {
+            let count = file.hunks.len();
+            if let Some(next) = (1..=count)
+                .map(|step| (self.hunk_index + step) % count)
+                .find(|i| file.hunks[*i].choice.is_none())
+            {
+                self.hunk_index = next;
+            }
+        }
+    }
+
+    /// Writes the resolved file and stages it with git
+    fn write_resolution(&mut self) -> Result<()> {
+        let Some(file) = self.conflicts.get(self.conflict_index) else {
+            return Ok(());
+        };
+        let unresolved = file.unresolved();
+        if unresolved > 0 {
+            self.status_message = Some(format!("{} hunk(s) still unresolved", unresolved));
+            return Ok(());
+        }
+
+        let path = file.path.clone();
+        std::fs::write(&path, file.render().0)?;
+        let staged = std::process::Command::new("git")
+            .arg("add")
+            .arg("--")
+            .arg(&path)
+            .current_dir(self.project.root())
+            .output();
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        self.conflicts.remove(self.conflict_index);
+        self.conflict_index = 0;
+        self.hunk_index = 0;
+        self.refresh_data()?;
+        if self.conflicts.is_empty() {
+            self.view_mode = ViewMode::Overview;
+        }
+        self.status_message = Some(match staged {
+            Ok(out) if out.status.success() => format!("Resolved and staged {}", name),
+            _ => format!("Resolved {} (git add failed; stage it yourself)", name),
+        });
+        Ok(())
+    }
+
     /// Handle keys in search mode
     fn handle_search_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
         let query = if let InputMode::Search(ref q) = self.input_mode {
@@ -585,7 +730,7 @@ impl App {
                 .collect()
         };
 
-        // Sort task list: ready first, then by status, then by ID
+        // Sort task list: ready first, then by status, then by rank and ID
         self.task_list.sort_by(|a, b| {
             let task_a = self.tasks.get(a);
             let task_b = self.tasks.get(b);
@@ -599,7 +744,7 @@ impl App {
                         .then_with(|| {
                             task_status_priority(ta.status).cmp(&task_status_priority(tb.status))
                         })
-                        .then_with(|| ta.title.cmp(&tb.title))
+                        .then_with(|| rank_cmp(ta, tb))
                 }
                 _ => a.to_string().cmp(&b.to_string()),
             }
@@ -662,6 +807,45 @@ impl App {
         Ok(())
     }
 
+    /// Move the selected task past its neighbour in the list (down or up)
+    fn move_task(&mut self, down: bool) -> Result<()> {
+        let Some(task_id) = self.selected_task_id() else {
+            return Ok(());
+        };
+        let neighbour = if down {
+            self.task_list.get(self.task_index + 1)
+        } else {
+            self.task_index
+                .checked_sub(1)
+                .and_then(|i| self.task_list.get(i))
+        };
+        let Some(anchor) = neighbour.cloned() else {
+            return Ok(());
+        };
+        if self.tasks[&task_id].is_standalone()
+            || self.tasks[&task_id].brief_id() != self.tasks[&anchor].brief_id()
+        {
+            self.status_message = Some("Only tasks in the same brief can be reordered".to_string());
+            return Ok(());
+        }
+
+        let changed = place(&mut self.tasks, &task_id, &anchor, down);
+        let updated: Vec<Task> = changed.iter().map(|id| self.tasks[id].clone()).collect();
+        self.project.task_store().update_batch(&updated)?;
+
+        self.update_task_list();
+        if let Some(index) = self.task_list.iter().position(|id| *id == task_id) {
+            self.task_index = index;
+        }
+        self.status_message = Some(format!(
+            "Moved {} {} {}",
+            task_id,
+            if down { "after" } else { "before" },
+            anchor
+        ));
+        Ok(())
+    }
+
     /// Complete the selected task
     fn complete_task(&mut self) -> Result<()> {
         if let Some(task_id) = self.selected_task_id() {
@@ -709,7 +893,7 @@ impl App {
                 .unwrap_or(0);
             TaskId::new(bid, max_seq + 1)
         } else {
-            TaskId::new_standalone(&title, chrono::Utc::now())
+            self.project.new_standalone_task_id(&title)?
         };
 
         let task = Task::new(task_id.clone(), &title);
@@ -724,7 +908,8 @@ impl App {
     /// Create a new brief
     fn create_brief(&mut self, title: String) -> Result<()> {
         let brief_store = self.project.brief_store();
-        let brief = Brief::new(&title, "minimal");
+        let mut brief = Brief::with_scheme(&title, "minimal", self.project.id_scheme());
+        brief.id = self.project.new_brief_id(&title, "minimal")?;
         let brief_id = brief.id.clone();
         brief_store.write(&brief)?;
         self.brief_list.push(brief_id.clone());
@@ -835,6 +1020,10 @@ impl App {
         self.view_mode
     }
 
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
     pub fn show_completed(&self) -> bool {
         self.show_completed
     }
@@ -846,6 +1035,18 @@ impl App {
     pub fn selected_brief(&self) -> Option<&Brief> {
         self.selected_brief_id().and_then(|id| self.briefs.get(&id))
     }
+
+    pub fn conflicts(&self) -> &[ConflictFile] {
+        &self.conflicts
+    }
+
+    pub fn conflict_index(&self) -> usize {
+        self.conflict_index
+    }
+
+    pub fn hunk_index(&self) -> usize {
+        self.hunk_index
+    }
 }
 
 /// Priority for brief status (lower = higher priority)
@@ -966,6 +1167,16 @@ mod tests {
         assert_eq!(ViewMode::from_str("3").unwrap(), ViewMode::Graph);
     }
 
+    #[test]
+    fn view_mode_from_str_conflicts() {
+        use std::str::FromStr;
+        assert_eq!(
+            ViewMode::from_str("conflicts").unwrap(),
+            ViewMode::Conflicts
+        );
+        assert_eq!(ViewMode::from_str("4").unwrap(), ViewMode::Conflicts);
+    }
+
     #[test]
     fn view_mode_from_str_invalid() {
         use std::str::FromStr;
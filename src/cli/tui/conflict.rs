@@ -0,0 +1,267 @@
+//! Git conflict markers in brief files
+//!
+//! Tasks merge through the JSONL merge driver, but a brief edited on two
+//! branches can still end up with `<<<<<<<` / `=======` / `>>>>>>>` markers.
+//! [`ConflictFile`] splits such a file into common text and conflict hunks so
+//! each hunk can be resolved by picking a side.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How a hunk is resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Choice {
+    Ours,
+    Theirs,
+    /// Ours followed by theirs
+    Both,
+}
+
+/// One conflicting region
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// Markdown heading the hunk falls under, or `frontmatter`
+    pub section: Option<String>,
+    /// Branch label after `<<<<<<<`, e.g. `HEAD`
+    pub ours_label: String,
+    /// Branch label after `>>>>>>>`
+    pub theirs_label: String,
+    pub ours: String,
+    pub theirs: String,
+    pub choice: Option<Choice>,
+    /// The hunk as written, markers included
+    raw: String,
+}
+
+impl Hunk {
+    fn resolved(&self) -> Option<String> {
+        Some(match self.choice? {
+            Choice::Ours => self.ours.clone(),
+            Choice::Theirs => self.theirs.clone(),
+            Choice::Both => format!("{}{}", self.ours, self.theirs),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Common(String),
+    Conflict(usize),
+}
+
+/// A brief file with conflict markers
+#[derive(Debug, Clone)]
+pub struct ConflictFile {
+    pub path: PathBuf,
+    pub hunks: Vec<Hunk>,
+    segments: Vec<Segment>,
+}
+
+/// Where a parse is in a conflict
+enum State {
+    Common,
+    Ours,
+    Base,
+    Theirs,
+}
+
+impl ConflictFile {
+    /// Splits `content` into common text and hunks. Returns `None` when it has
+    /// no complete conflict.
+    pub fn parse(path: impl Into<PathBuf>, content: &str) -> Option<Self> {
+        let mut segments = Vec::new();
+        let mut hunks: Vec<Hunk> = Vec::new();
+        let mut common = String::new();
+        let mut state = State::Common;
+        let mut current: Option<Hunk> = None;
+
+        // Section tracking for display
+        let mut in_frontmatter = content.starts_with("---");
+        let mut delimiters = 0;
+        let mut heading: Option<String> = None;
+
+        for line in content.split_inclusive('\n') {
+            let bare = line.trim_end_matches(['\n', '\r']);
+            match state {
+                State::Common => {
+                    if let Some(label) = marker(bare, "<<<<<<<") {
+                        if !common.is_empty() {
+                            segments.push(Segment::Common(std::mem::take(&mut common)));
+                        }
+                        let section = if in_frontmatter {
+                            Some("frontmatter".to_string())
+                        } else {
+                            heading.clone()
+                        };
+                        current = Some(Hunk {
+                            section,
+                            ours_label: label.to_string(),
+                            theirs_label: String::new(),
+                            ours: String::new(),
+                            theirs: String::new(),
+                            choice: None,
+                            raw: line.to_string(),
+                        });
+                        state = State::Ours;
+                        continue;
+                    }
+                    if in_frontmatter && bare == "---" {
+                        delimiters += 1;
+                        in_frontmatter = delimiters < 2;
+                    } else if !in_frontmatter && bare.starts_with('#') {
+                        heading = Some(bare.to_string());
+                    }
+                    common.push_str(line);
+                }
+                State::Ours | State::Base | State::Theirs => {
+                    let hunk = current.as_mut()?;
+                    hunk.raw.push_str(line);
+                    if marker(bare, "|||||||").is_some() {
+                        state = State::Base;
+                    } else if bare == "=======" {
+                        state = State::Theirs;
+                    } else if let Some(label) = marker(bare, ">>>>>>>") {
+                        hunk.theirs_label = label.to_string();
+                        segments.push(Segment::Conflict(hunks.len()));
+                        hunks.push(current.take()?);
+                        state = State::Common;
+                    } else {
+                        match state {
+                            State::Ours => hunk.ours.push_str(line),
+                            State::Theirs => hunk.theirs.push_str(line),
+                            // diff3 base lines are shown by git but never picked
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        if !matches!(state, State::Common) || hunks.is_empty() {
+            return None;
+        }
+        if !common.is_empty() {
+            segments.push(Segment::Common(common));
+        }
+
+        Some(Self {
+            path: path.into(),
+            hunks,
+            segments,
+        })
+    }
+
+    /// Number of hunks without a choice
+    pub fn unresolved(&self) -> usize {
+        self.hunks.iter().filter(|h| h.choice.is_none()).count()
+    }
+
+    /// The file with resolved hunks applied and unresolved ones left as they
+    /// were, plus the line each hunk starts on
+    pub fn render(&self) -> (String, Vec<usize>) {
+        let mut text = String::new();
+        let mut starts = vec![0; self.hunks.len()];
+        for segment in &self.segments {
+            match segment {
+                Segment::Common(common) => text.push_str(common),
+                Segment::Conflict(i) => {
+                    starts[*i] = text.matches('\n').count();
+                    let hunk = &self.hunks[*i];
+                    match hunk.resolved() {
+                        Some(resolved) => text.push_str(&resolved),
+                        None => text.push_str(&hunk.raw),
+                    }
+                }
+            }
+        }
+        (text, starts)
+    }
+}
+
+/// Label after a conflict marker, if `line` is one
+fn marker<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(prefix)?;
+    if rest.is_empty() {
+        Some("")
+    } else {
+        rest.strip_prefix(' ')
+    }
+}
+
+/// Brief files in `dir` with conflict markers, sorted by path
+pub fn find(dir: &Path) -> Vec<ConflictFile> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|p| {
+            let content = fs::read_to_string(&p).ok()?;
+            ConflictFile::parse(p, &content)
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFLICTED: &str = "---
+id: b-1234567
+<<<<<<< HEAD
+status: building
+=======
+status: shipped
+>>>>>>> feature
+---
+
+# Auth
+
+## Solution
+
+<<<<<<< HEAD
+Use OAuth.
+||||||| base
+Use sessions.
+=======
+Use passkeys.
+>>>>>>> feature
+
+## Rabbit Holes
+";
+
+    #[test]
+    fn parses_hunks_with_sections() {
+        let file = ConflictFile::parse("b.md", CONFLICTED).unwrap();
+        assert_eq!(file.hunks.len(), 2);
+        assert_eq!(file.hunks[0].section.as_deref(), Some("frontmatter"));
+        assert_eq!(file.hunks[0].ours, "status: building\n");
+        assert_eq!(file.hunks[1].section.as_deref(), Some("## Solution"));
+        assert_eq!(file.hunks[1].ours_label, "HEAD");
+        assert_eq!(file.hunks[1].theirs_label, "feature");
+        assert_eq!(file.hunks[1].theirs, "Use passkeys.\n");
+        assert_eq!(file.unresolved(), 2);
+
+        // Unresolved hunks render as they were written
+        assert_eq!(file.render().0, CONFLICTED);
+        assert!(ConflictFile::parse("b.md", "# Clean\n").is_none());
+        assert!(ConflictFile::parse("b.md", "<<<<<<< HEAD\nours\n").is_none());
+    }
+
+    #[test]
+    fn renders_chosen_sides() {
+        let mut file = ConflictFile::parse("b.md", CONFLICTED).unwrap();
+        file.hunks[0].choice = Some(Choice::Theirs);
+        file.hunks[1].choice = Some(Choice::Both);
+        let (text, starts) = file.render();
+
+        assert_eq!(file.unresolved(), 0);
+        assert!(text.contains("status: shipped\n---"));
+        assert!(text.contains("Use OAuth.\nUse passkeys.\n\n## Rabbit Holes"));
+        assert!(!text.contains("<<<<<<<"));
+        assert_eq!(starts, vec![2, 9]);
+    }
+}
@@ -41,18 +41,17 @@ impl EventHandler {
                 if event::poll(tick_rate).unwrap_or(false) {
                     if let Ok(evt) = event::read() {
                         match evt {
-                            CrosstermEvent::Key(key) => {
-                                // Only send key press events, not release
+                            // Only send key press events, not release
+                            CrosstermEvent::Key(key)
                                 if key.kind == KeyEventKind::Press
-                                    && tx_clone.send(Event::Key(key)).is_err()
-                                {
-                                    break;
-                                }
+                                    && tx_clone.send(Event::Key(key)).is_err() =>
+                            {
+                                break;
                             }
-                            CrosstermEvent::Resize(w, h) => {
-                                if tx_clone.send(Event::Resize(w, h)).is_err() {
-                                    break;
-                                }
+                            CrosstermEvent::Resize(w, h)
+                                if tx_clone.send(Event::Resize(w, h)).is_err() =>
+                            {
+                                break;
                             }
                             _ => {}
                         }
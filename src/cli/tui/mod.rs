@@ -4,6 +4,7 @@
 //! briefs and tasks using ratatui.
 
 mod app;
+mod conflict;
 mod event;
 mod ui;
 mod utils;
@@ -25,6 +26,8 @@ pub enum ViewMode {
     Overview,
     Kanban,
     Graph,
+    /// Merge conflicts in brief files
+    Conflicts,
 }
 
 impl FromStr for ViewMode {
@@ -35,6 +38,7 @@ impl FromStr for ViewMode {
             "overview" | "o" | "1" => Ok(ViewMode::Overview),
             "kanban" | "k" | "2" => Ok(ViewMode::Kanban),
             "graph" | "g" | "3" => Ok(ViewMode::Graph),
+            "conflicts" | "x" | "4" => Ok(ViewMode::Conflicts),
             _ => Err(()),
         }
     }
@@ -1,5 +1,45 @@
 //! Shared utilities for TUI views
 
+use ratatui::style::{Color, Style};
+use ratatui::text::Span;
+
+use crate::cli::render::{self, Theme};
+use crate::domain::Task;
+
+/// Terminal color for a themed CLI color
+pub fn color(color: render::Color) -> Color {
+    match color {
+        render::Color::Red => Color::Red,
+        render::Color::Green => Color::Green,
+        render::Color::Yellow => Color::Yellow,
+        render::Color::Blue => Color::Blue,
+        render::Color::Magenta => Color::Magenta,
+        render::Color::Cyan => Color::Cyan,
+        render::Color::Dim => Color::DarkGray,
+    }
+}
+
+/// Color for done and inactive items; dark gray is hard to read in
+/// high-contrast mode
+pub fn muted(theme: &Theme) -> Color {
+    if theme.high_contrast {
+        Color::White
+    } else {
+        Color::DarkGray
+    }
+}
+
+/// `[label]` spans in each label's configured color
+pub fn label_spans<'a>(theme: &Theme, task: &'a Task) -> Vec<Span<'a>> {
+    task.labels()
+        .into_iter()
+        .map(|label| {
+            let fg = theme.label_color(label).map_or(muted(theme), color);
+            Span::styled(format!(" [{}]", label), Style::default().fg(fg))
+        })
+        .collect()
+}
+
 /// Truncate a string to max_len characters, adding "..." if truncated
 pub fn truncate_str(s: &str, max_len: usize) -> String {
     if s.chars().count() <= max_len {
@@ -0,0 +1,121 @@
+//! Conflict view: ours / theirs / result panes for briefs with git conflict
+//! markers
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::cli::tui::app::App;
+use crate::cli::tui::conflict::Choice;
+
+/// Draw the conflict layout
+pub fn draw(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // File and hunk
+            Constraint::Min(10),   // Panes
+            Constraint::Length(3), // Status bar
+        ])
+        .split(area);
+
+    let Some(file) = app.conflicts().get(app.conflict_index()) else {
+        let paragraph = Paragraph::new("No briefs with merge conflicts. [r]escan [1]overview")
+            .block(Block::default().title("Conflicts").borders(Borders::ALL));
+        frame.render_widget(paragraph, main_chunks[1]);
+        draw_status_bar(frame, app, main_chunks[2]);
+        return;
+    };
+    let hunk = &file.hunks[app.hunk_index()];
+
+    let name = file
+        .path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let header = format!(
+        "{} (file {}/{})  hunk {}/{}{}  {} unresolved",
+        name,
+        app.conflict_index() + 1,
+        app.conflicts().len(),
+        app.hunk_index() + 1,
+        file.hunks.len(),
+        hunk.section
+            .as_deref()
+            .map(|s| format!(" in {}", s))
+            .unwrap_or_default(),
+        file.unresolved()
+    );
+    let header = Paragraph::new(header)
+        .style(Style::default().fg(Color::Yellow))
+        .block(
+            Block::default()
+                .title("Merge conflict")
+                .borders(Borders::ALL),
+        );
+    frame.render_widget(header, main_chunks[0]);
+
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(33),
+            Constraint::Percentage(33),
+            Constraint::Percentage(34),
+        ])
+        .split(main_chunks[1]);
+
+    let chosen = |sides: &[Choice]| hunk.choice.is_some_and(|c| sides.contains(&c));
+    draw_side(
+        frame,
+        &format!("[o] Ours ({})", hunk.ours_label),
+        &hunk.ours,
+        chosen(&[Choice::Ours, Choice::Both]),
+        panes[0],
+    );
+    draw_side(
+        frame,
+        &format!("[t] Theirs ({})", hunk.theirs_label),
+        &hunk.theirs,
+        chosen(&[Choice::Theirs, Choice::Both]),
+        panes[1],
+    );
+
+    // Result pane shows the whole file, scrolled to the selected hunk
+    let (text, starts) = file.render();
+    let scroll = starts[app.hunk_index()].saturating_sub(3) as u16;
+    let result = Paragraph::new(text)
+        .scroll((scroll, 0))
+        .block(Block::default().title("Result").borders(Borders::ALL));
+    frame.render_widget(result, panes[2]);
+
+    draw_status_bar(frame, app, main_chunks[2]);
+}
+
+fn draw_side(frame: &mut Frame, title: &str, text: &str, chosen: bool, area: Rect) {
+    let border = if chosen {
+        Style::default().fg(Color::Green)
+    } else {
+        Style::default()
+    };
+    let paragraph = Paragraph::new(text.to_string())
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(title.to_string())
+                .borders(Borders::ALL)
+                .border_style(border),
+        );
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
+    let msg = app
+        .status_message()
+        .unwrap_or("[j/k]hunk [o]urs [t]heirs [b]oth [u]ndo [Tab]file [w]rite+stage [q]uit");
+    let paragraph = Paragraph::new(format!("Shape [4:Conflicts] {}", msg))
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(paragraph, area);
+}
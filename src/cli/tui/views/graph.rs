@@ -7,6 +7,7 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
+use crate::cli::render::Theme;
 use crate::cli::tui::app::{App, InputMode};
 use crate::cli::tui::utils::truncate_str;
 use crate::domain::{TaskId, TaskStatus};
@@ -48,6 +49,7 @@ fn draw_graph_panel(frame: &mut Frame, app: &App, area: Rect) {
 
 fn build_dependency_graph(app: &App) -> String {
     let tasks = app.tasks();
+    let theme = app.theme();
     let statuses: HashMap<TaskId, TaskStatus> =
         tasks.iter().map(|(id, t)| (id.clone(), t.status)).collect();
 
@@ -101,24 +103,41 @@ fn build_dependency_graph(app: &App) -> String {
     lines.push(String::new());
 
     for root in roots {
-        render_task_tree(&mut lines, root, tasks, &statuses, &mut visited, "", true);
+        render_task_tree(
+            &mut lines,
+            theme,
+            root,
+            tasks,
+            &statuses,
+            &mut visited,
+            "",
+            true,
+        );
     }
 
     // Add legend
     lines.push(String::new());
     lines.push("Legend:".to_string());
-    lines.push("  [ ] = Todo (ready)".to_string());
-    lines.push("  [~] = In Progress".to_string());
-    lines.push("  [x] = Done".to_string());
-    lines.push("  [B] = Blocked".to_string());
+    for (icon, meaning) in [
+        (theme.task_icon(TaskStatus::Todo), "Todo (ready)"),
+        (theme.task_icon(TaskStatus::InProgress), "In Progress"),
+        (theme.task_icon(TaskStatus::Done), "Done"),
+        (theme.blocked_icon(), "Blocked"),
+    ] {
+        if !icon.is_empty() {
+            lines.push(format!("  {} = {}", icon, meaning));
+        }
+    }
     lines.push(String::new());
     lines.push("  --> = depends on".to_string());
 
     lines.join("\n")
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_task_tree(
     lines: &mut Vec<String>,
+    theme: &Theme,
     task_id: &TaskId,
     tasks: &HashMap<TaskId, crate::domain::Task>,
     statuses: &HashMap<TaskId, TaskStatus>,
@@ -144,16 +163,10 @@ fn render_task_tree(
     };
 
     // Determine status indicator
-    let indicator = match task.status {
-        TaskStatus::Done => "[x]",
-        TaskStatus::InProgress => "[~]",
-        TaskStatus::Todo => {
-            if task.is_blocked(statuses) {
-                "[B]"
-            } else {
-                "[ ]"
-            }
-        }
+    let indicator = if task.status == TaskStatus::Todo && task.is_blocked(statuses) {
+        theme.blocked_icon()
+    } else {
+        theme.task_icon(task.status)
     };
 
     // Color based on status
@@ -172,10 +185,10 @@ fn render_task_tree(
 
     let connector = if is_last { "└── " } else { "├── " };
     let line = format!(
-        "{}{}{} {} ({})",
+        "{}{}{}{} ({})",
         prefix,
         connector,
-        indicator,
+        theme.lead(&indicator),
         truncate_str(&task.title, 30),
         task_id
     );
@@ -195,6 +208,7 @@ fn render_task_tree(
         let is_child_last = i == children.len() - 1;
         render_task_tree(
             lines,
+            theme,
             child_id,
             tasks,
             statuses,
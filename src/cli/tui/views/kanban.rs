@@ -5,9 +5,10 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, Paragraph},
 };
 
+use crate::cli::render::Theme;
 use crate::cli::tui::app::{App, InputMode};
-use crate::cli::tui::utils::truncate_str;
-use crate::domain::TaskStatus;
+use crate::cli::tui::utils::{label_spans, muted, truncate_str};
+use crate::domain::{Task, TaskStatus};
 
 /// Draw the kanban layout
 pub fn draw(frame: &mut Frame, app: &App) {
@@ -65,9 +66,10 @@ pub fn draw(frame: &mut Frame, app: &App) {
     done_tasks.sort_by(|a, b| a.1.title.cmp(&b.1.title));
 
     // Draw columns
-    draw_todo_column(frame, &todo_tasks, columns[0]);
-    draw_in_progress_column(frame, &in_progress_tasks, columns[1]);
-    draw_done_column(frame, &done_tasks, columns[2]);
+    let theme = app.theme();
+    draw_todo_column(frame, theme, &todo_tasks, columns[0]);
+    draw_in_progress_column(frame, theme, &in_progress_tasks, columns[1]);
+    draw_done_column(frame, theme, &done_tasks, columns[2]);
 
     // Draw status bar
     draw_status_bar(frame, app, main_chunks[1]);
@@ -75,19 +77,24 @@ pub fn draw(frame: &mut Frame, app: &App) {
 
 fn draw_todo_column(
     frame: &mut Frame,
+    theme: &Theme,
     tasks: &[(&crate::domain::TaskId, &crate::domain::Task, bool)],
     area: Rect,
 ) {
     let items: Vec<ListItem> = tasks
         .iter()
         .map(|(_, task, blocked)| {
-            let indicator = if *blocked { "[B]" } else { "[ ]" };
+            let icon = if *blocked {
+                theme.blocked_icon()
+            } else {
+                theme.task_icon(TaskStatus::Todo)
+            };
             let style = if *blocked {
                 Style::default().fg(Color::Red)
             } else {
                 Style::default().fg(Color::Green)
             };
-            ListItem::new(format!("{} {}", indicator, truncate_str(&task.title, 25))).style(style)
+            card(theme, icon, task).style(style)
         })
         .collect();
 
@@ -104,13 +111,14 @@ fn draw_todo_column(
 
 fn draw_in_progress_column(
     frame: &mut Frame,
+    theme: &Theme,
     tasks: &[(&crate::domain::TaskId, &crate::domain::Task)],
     area: Rect,
 ) {
     let items: Vec<ListItem> = tasks
         .iter()
         .map(|(_, task)| {
-            ListItem::new(format!("[~] {}", truncate_str(&task.title, 25)))
+            card(theme, theme.task_icon(TaskStatus::InProgress), task)
                 .style(Style::default().fg(Color::Yellow))
         })
         .collect();
@@ -128,14 +136,15 @@ fn draw_in_progress_column(
 
 fn draw_done_column(
     frame: &mut Frame,
+    theme: &Theme,
     tasks: &[(&crate::domain::TaskId, &crate::domain::Task)],
     area: Rect,
 ) {
     let items: Vec<ListItem> = tasks
         .iter()
         .map(|(_, task)| {
-            ListItem::new(format!("[x] {}", truncate_str(&task.title, 25)))
-                .style(Style::default().fg(Color::DarkGray))
+            card(theme, theme.task_icon(TaskStatus::Done), task)
+                .style(Style::default().fg(muted(theme)))
         })
         .collect();
 
@@ -144,12 +153,23 @@ fn draw_done_column(
         Block::default()
             .title(title)
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::DarkGray)),
+            .border_style(Style::default().fg(muted(theme))),
     );
 
     frame.render_widget(list, area);
 }
 
+/// Kanban card: status icon, title and colored labels
+fn card<'a>(theme: &Theme, icon: String, task: &'a Task) -> ListItem<'a> {
+    let mut spans = vec![Span::raw(format!(
+        "{}{}",
+        theme.lead(&icon),
+        truncate_str(&task.title, 25)
+    ))];
+    spans.extend(label_spans(theme, task));
+    ListItem::new(Line::from(spans))
+}
+
 fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     let (content, style) = match app.input_mode() {
         InputMode::Normal => {
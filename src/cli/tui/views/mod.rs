@@ -1,5 +1,6 @@
 //! TUI view implementations
 
+pub mod conflict;
 pub mod graph;
 pub mod kanban;
 pub mod overview;
@@ -1,13 +1,15 @@
 //! Overview view: Briefs + Tasks split view
 
+use chrono::Utc;
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
 };
 
+use crate::cli::render::Theme;
 use crate::cli::tui::app::{App, Focus, InputMode};
-use crate::cli::tui::utils::truncate_str;
-use crate::domain::{BriefStatus, TaskStatus};
+use crate::cli::tui::utils::{label_spans, muted, truncate_str};
+use crate::domain::{AppetiteBudget, BriefStatus, Task, TaskStatus};
 
 /// Draw the overview layout
 pub fn draw(frame: &mut Frame, app: &App) {
@@ -42,6 +44,8 @@ pub fn draw(frame: &mut Frame, app: &App) {
 /// Draw the briefs panel
 fn draw_briefs_panel(frame: &mut Frame, app: &App, area: Rect) {
     let focused = app.focus() == Focus::Briefs;
+    let theme = app.theme();
+    let now = Utc::now();
 
     let items: Vec<ListItem> = app
         .brief_list()
@@ -50,16 +54,29 @@ fn draw_briefs_panel(frame: &mut Frame, app: &App, area: Rect) {
             let brief = app.briefs().get(id);
             let (title, status_indicator) = match brief {
                 Some(a) => {
-                    let indicator = match a.status {
-                        BriefStatus::InProgress => "[IP]",
-                        BriefStatus::Proposed => "[PROP]",
-                        BriefStatus::Betting => "[BET]",
-                        BriefStatus::Shipped => "[DONE]",
-                        BriefStatus::Archived => "[ARCH]",
+                    // Text codes stand in when icons are off
+                    let indicator = match theme.brief_icon(a.status) {
+                        icon if !icon.is_empty() => icon,
+                        _ => match a.status {
+                            BriefStatus::InProgress => "[IP]",
+                            BriefStatus::Proposed => "[PROP]",
+                            BriefStatus::Betting => "[BET]",
+                            BriefStatus::Shipped => "[DONE]",
+                            BriefStatus::Archived => "[ARCH]",
+                        }
+                        .to_string(),
                     };
-                    (a.title.clone(), indicator)
+                    // Days of appetite left, or over it
+                    let budget = match AppetiteBudget::of(a, now) {
+                        Some(b) if b.is_exceeded() => {
+                            format!(" +{}d", (-b.remaining()).num_days().max(1))
+                        }
+                        Some(b) => format!(" {}d left", b.remaining().num_days()),
+                        None => String::new(),
+                    };
+                    (a.title.clone(), indicator + &budget)
                 }
-                None => (id.to_string(), ""),
+                None => (id.to_string(), String::new()),
             };
 
             let content = format!("{} {}", truncate_str(&title, 20), status_indicator);
@@ -100,6 +117,7 @@ fn draw_briefs_panel(frame: &mut Frame, app: &App, area: Rect) {
 /// Draw the tasks panel
 fn draw_tasks_panel(frame: &mut Frame, app: &App, area: Rect) {
     let focused = app.focus() == Focus::Tasks;
+    let theme = app.theme();
     let statuses = app
         .tasks()
         .iter()
@@ -149,12 +167,7 @@ fn draw_tasks_panel(frame: &mut Frame, app: &App, area: Rect) {
                 }
             }
             if let Some(task) = app.tasks().get(task_id) {
-                let indicator = "[ ]";
-                items.push(ListItem::new(format!(
-                    "  {} {}",
-                    indicator,
-                    truncate_str(&task.title, 30)
-                )));
+                items.push(task_item(theme, theme.task_icon(TaskStatus::Todo), task));
             }
         }
         flat_index += ready_tasks.len() + 1;
@@ -176,12 +189,11 @@ fn draw_tasks_panel(frame: &mut Frame, app: &App, area: Rect) {
                 }
             }
             if let Some(task) = app.tasks().get(task_id) {
-                let indicator = "[~]";
-                items.push(ListItem::new(format!(
-                    "  {} {}",
-                    indicator,
-                    truncate_str(&task.title, 30)
-                )));
+                items.push(task_item(
+                    theme,
+                    theme.task_icon(TaskStatus::InProgress),
+                    task,
+                ));
             }
         }
         flat_index += in_progress_tasks.len() + 1;
@@ -200,12 +212,7 @@ fn draw_tasks_panel(frame: &mut Frame, app: &App, area: Rect) {
                 }
             }
             if let Some(task) = app.tasks().get(task_id) {
-                let indicator = "[B]";
-                items.push(ListItem::new(format!(
-                    "  {} {}",
-                    indicator,
-                    truncate_str(&task.title, 30)
-                )));
+                items.push(task_item(theme, theme.blocked_icon(), task));
             }
         }
         flat_index += blocked_tasks.len() + 1;
@@ -216,7 +223,7 @@ fn draw_tasks_panel(frame: &mut Frame, app: &App, area: Rect) {
         items.push(
             ListItem::new(format!("Done ({})", done_tasks.len())).style(
                 Style::default()
-                    .fg(Color::DarkGray)
+                    .fg(muted(theme))
                     .add_modifier(Modifier::BOLD),
             ),
         );
@@ -227,10 +234,9 @@ fn draw_tasks_panel(frame: &mut Frame, app: &App, area: Rect) {
                 }
             }
             if let Some(task) = app.tasks().get(task_id) {
-                let indicator = "[x]";
                 items.push(
-                    ListItem::new(format!("  {} {}", indicator, truncate_str(&task.title, 30)))
-                        .style(Style::default().fg(Color::DarkGray)),
+                    task_item(theme, theme.task_icon(TaskStatus::Done), task)
+                        .style(Style::default().fg(muted(theme))),
                 );
             }
         }
@@ -266,6 +272,17 @@ fn draw_tasks_panel(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_stateful_widget(list, area, &mut state);
 }
 
+/// Task row: status icon, title and colored labels
+fn task_item<'a>(theme: &Theme, icon: String, task: &'a Task) -> ListItem<'a> {
+    let mut spans = vec![Span::raw(format!(
+        "  {}{}",
+        theme.lead(&icon),
+        truncate_str(&task.title, 30)
+    ))];
+    spans.extend(label_spans(theme, task));
+    ListItem::new(Line::from(spans))
+}
+
 /// Draw the details panel
 fn draw_details_panel(frame: &mut Frame, app: &App, area: Rect) {
     let focused = app.focus() == Focus::Details;
@@ -412,6 +429,7 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         crate::cli::tui::ViewMode::Overview => "[1:Overview]",
         crate::cli::tui::ViewMode::Kanban => "[2:Kanban]",
         crate::cli::tui::ViewMode::Graph => "[3:Graph]",
+        crate::cli::tui::ViewMode::Conflicts => "[4:Conflicts]",
     };
 
     let status_text = format!("{} {} {}", "Shape", view_str, content);
@@ -0,0 +1,136 @@
+//! `shape tx`: group several commands into one all-or-nothing change
+//!
+//! Between `shape tx begin` and `shape tx commit`, task and brief writes are
+//! staged instead of applied (see [`crate::storage::TxSession`]), so a
+//! sequence like creating tasks, wiring their dependencies and claiming one
+//! lands in the live store, and in the daemon's history, as a single change.
+//!
+//! The session belongs to the shell that began it: `begin` prints a token to
+//! export as `SHAPE_TX`. Commands without it keep seeing the live store and
+//! can't change tasks or briefs until the session is committed or aborted.
+
+use anyhow::Result;
+use clap::Subcommand;
+use serde_json::json;
+
+use super::output::Output;
+use crate::storage::Project;
+
+#[derive(Subcommand)]
+pub enum TxCommands {
+    /// Start staging task and brief changes
+    ///
+    /// Prints a token: only commands run with it in SHAPE_TX join the
+    /// session. Everyone else's task and brief writes are refused until it
+    /// ends.
+    ///
+    /// Example:
+    ///   shape tx begin
+    ///   export SHAPE_TX=<token it printed>
+    ///   shape task add b-7f2a3b1 "Write migration"
+    ///   shape task dep b-7f2a3b1.2 b-7f2a3b1.1
+    ///   shape tx commit
+    Begin,
+
+    /// Apply the staged changes to the store
+    Commit,
+
+    /// Discard the staged changes
+    Abort {
+        /// Discard a session this shell doesn't own (e.g. one whose token
+        /// was lost)
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Show whether a transaction is open
+    Status,
+}
+
+pub fn run(cmd: TxCommands, output: &Output) -> Result<()> {
+    let project = Project::open_current()?;
+    match cmd {
+        TxCommands::Begin => begin(&project, output),
+        TxCommands::Commit => commit(&project, output),
+        TxCommands::Abort { force } => abort(&project, force, output),
+        TxCommands::Status => status(&project, output),
+    }
+}
+
+fn begin(project: &Project, output: &Output) -> Result<()> {
+    let session = project.begin_tx()?;
+    if output.is_json() {
+        output.data(&json!({
+            "open": true,
+            "started_at": session.started_at,
+            "token": session.token,
+        }));
+    } else {
+        output.success(&format!(
+            "Transaction started; run 'export SHAPE_TX={}' so commands stage their changes until 'shape tx commit'",
+            session.token
+        ));
+    }
+    Ok(())
+}
+
+fn commit(project: &Project, output: &Output) -> Result<()> {
+    let result = project.commit_tx()?;
+    if output.is_json() {
+        output.data(&json!({
+            "committed": true,
+            "tasks_changed": result.tasks_changed,
+            "briefs_written": result.briefs_written,
+            "briefs_removed": result.briefs_removed,
+        }));
+    } else if result.is_empty() {
+        output.success("Transaction committed (no changes)");
+    } else {
+        let mut parts = Vec::new();
+        if result.tasks_changed {
+            parts.push("tasks".to_string());
+        }
+        if result.briefs_written > 0 {
+            parts.push(format!("{} brief(s) written", result.briefs_written));
+        }
+        if result.briefs_removed > 0 {
+            parts.push(format!("{} brief(s) removed", result.briefs_removed));
+        }
+        output.success(&format!("Transaction committed: {}", parts.join(", ")));
+    }
+    Ok(())
+}
+
+fn abort(project: &Project, force: bool, output: &Output) -> Result<()> {
+    project.abort_tx(force)?;
+    if output.is_json() {
+        output.data(&json!({ "aborted": true }));
+    } else {
+        output.success("Transaction aborted; staged changes discarded");
+    }
+    Ok(())
+}
+
+fn status(project: &Project, output: &Output) -> Result<()> {
+    let session = project.tx_session()?;
+    let token = Project::tx_token();
+    let owned = session
+        .as_ref()
+        .is_some_and(|s| s.is_owned_by(token.as_deref()));
+    if output.is_json() {
+        output.data(&json!({
+            "open": session.is_some(),
+            "owned": owned,
+            "started_at": session.as_ref().map(|s| s.started_at),
+        }));
+    } else if let Some(session) = session {
+        println!(
+            "Transaction open since {}{}",
+            session.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            if owned { "" } else { " (another session's)" }
+        );
+    } else {
+        println!("No transaction open");
+    }
+    Ok(())
+}
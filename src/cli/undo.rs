@@ -0,0 +1,208 @@
+//! `shape undo` and `shape redo`
+//!
+//! Task and agent commands that change tasks are recorded in the operation
+//! journal (see [`Journal`](crate::storage::Journal)) with every task they
+//! wrote as it was before and after. Only the command's own writes are
+//! recorded, so another agent's change made meanwhile is never undone with
+//! it. `undo` puts the earlier versions back
+//! and `redo` the later ones, inside one locked rewrite of the task store.
+//!
+//! A task that changed again since is left alone: the whole step is refused
+//! rather than overwriting someone else's work. Only tasks are restored;
+//! audit log entries, resource locks and briefs stay as they are.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde_json::json;
+
+use super::agent::get_agent_name;
+use super::error::{CliError, ErrorCode};
+use super::freeze::ensure_task_mutable;
+use super::output::Output;
+use crate::domain::{Task, TaskId};
+use crate::storage::{JournalEntry, Project, TaskChange};
+
+/// Runs `command`, recording the tasks it changed in the journal as
+/// `name` (e.g. `task done`). A write fence stays up until the entry is
+/// recorded, so the daemon commits the whole command at once.
+pub(super) fn journaled(name: &str, command: impl FnOnce() -> Result<()>) -> Result<()> {
+    let Ok(project) = Project::open_current() else {
+        return command();
+    };
+    journaled_in(&project, name, command)
+}
+
+/// [`journaled`] for a project that is already open
+pub(crate) fn journaled_in<R>(
+    project: &Project,
+    name: &str,
+    command: impl FnOnce() -> Result<R>,
+) -> Result<R> {
+    let _fence = project.write_fence(name)?;
+    let recorder = project.task_store().record_writes();
+    let result = command()?;
+
+    let changes = recorder.finish();
+    if changes.is_empty() {
+        return Ok(result);
+    }
+    let journal = project.journal();
+    let _lock = journal.lock()?;
+    journal.record(&get_agent_name(project, None), name, changes)?;
+    Ok(result)
+}
+
+/// Undoes the last `count` journaled commands, newest first
+pub fn undo(output: &Output, count: usize) -> Result<()> {
+    let project = Project::open_current()?;
+    let journal = project.journal();
+    let _lock = journal.lock()?;
+    let mut entries = journal.entries()?;
+
+    let pending: Vec<usize> = (0..entries.len())
+        .rev()
+        .filter(|&i| !entries[i].undone)
+        .take(count)
+        .collect();
+    if pending.is_empty() {
+        return Err(CliError::new(ErrorCode::Validation, "Nothing to undo").into());
+    }
+
+    // Steps already undone stay undone when a later one is refused
+    let mut undone = Vec::new();
+    let mut refused = None;
+    for i in pending {
+        if let Err(e) = replay(&project, &entries[i], Step::Undo) {
+            refused = Some(e);
+            break;
+        }
+        entries[i].undone = true;
+        journal.write(&entries)?;
+        undone.push(i);
+    }
+    let steps: Vec<&JournalEntry> = undone.iter().map(|&i| &entries[i]).collect();
+    if !steps.is_empty() {
+        report(output, Step::Undo, &steps);
+    }
+    refused.map_or(Ok(()), Err)
+}
+
+/// Redoes the most recently undone command
+pub fn redo(output: &Output) -> Result<()> {
+    let project = Project::open_current()?;
+    let journal = project.journal();
+    let _lock = journal.lock()?;
+    let mut entries = journal.entries()?;
+
+    let Some(i) = entries.iter().position(|e| e.undone) else {
+        return Err(CliError::new(ErrorCode::Validation, "Nothing to redo").into());
+    };
+    replay(&project, &entries[i], Step::Redo)?;
+    entries[i].undone = false;
+    journal.write(&entries)?;
+    report(output, Step::Redo, &[&entries[i]]);
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+enum Step {
+    Undo,
+    Redo,
+}
+
+impl Step {
+    fn label(self) -> &'static str {
+        match self {
+            Step::Undo => "undo",
+            Step::Redo => "redo",
+        }
+    }
+
+    /// The state a task must be in, and the state it is put in
+    fn states(self, change: &TaskChange) -> (Option<&Task>, Option<&Task>) {
+        match self {
+            Step::Undo => (change.after.as_ref(), change.before.as_ref()),
+            Step::Redo => (change.before.as_ref(), change.after.as_ref()),
+        }
+    }
+}
+
+/// Applies one entry in the direction of `step`, or nothing if any of its
+/// tasks changed since
+fn replay(project: &Project, entry: &JournalEntry, step: Step) -> Result<()> {
+    project.task_store().modify(|tasks| {
+        for change in &entry.changes {
+            let (expected, target) = step.states(change);
+            let current = tasks.get(&change.id);
+            if current != expected {
+                return Err(CliError::new(
+                    ErrorCode::Conflict,
+                    format!(
+                        "Can't {} `shape {}`: {} changed since",
+                        step.label(),
+                        entry.command,
+                        change.id
+                    ),
+                )
+                .into());
+            }
+            if let Some(task) = current.or(target) {
+                ensure_task_mutable(project, task)?;
+            }
+        }
+        for change in &entry.changes {
+            restore(tasks, &change.id, step.states(change).1);
+        }
+        Ok(())
+    })
+}
+
+fn restore(tasks: &mut HashMap<TaskId, Task>, id: &TaskId, task: Option<&Task>) {
+    match task {
+        Some(task) => {
+            tasks.insert(id.clone(), task.clone());
+        }
+        None => {
+            tasks.remove(id);
+        }
+    }
+}
+
+fn report(output: &Output, step: Step, entries: &[&JournalEntry]) {
+    let ids = |entry: &JournalEntry| -> Vec<String> {
+        entry.changes.iter().map(|c| c.id.to_string()).collect()
+    };
+    if output.is_json() {
+        let items: Vec<_> = entries
+            .iter()
+            .map(|e| {
+                json!({
+                    "seq": e.seq,
+                    "command": e.command,
+                    "at": e.at,
+                    "by": e.by,
+                    "tasks": ids(e),
+                })
+            })
+            .collect();
+        let key = match step {
+            Step::Undo => "undone",
+            Step::Redo => "redone",
+        };
+        output.data(&json!({ key: items }));
+    } else {
+        let verb = match step {
+            Step::Undo => "Undid",
+            Step::Redo => "Redid",
+        };
+        for entry in entries {
+            output.success(&format!(
+                "{} `shape {}` ({})",
+                verb,
+                entry.command,
+                ids(entry).join(", ")
+            ));
+        }
+    }
+}
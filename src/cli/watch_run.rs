@@ -0,0 +1,163 @@
+//! `shape watch-run`: run a command and record the result on a task
+//!
+//! `shape watch-run b-7f2b4c1.3 -- cargo test` runs the command, passing its
+//! output through, then records the evidence on the task: the full log under
+//! `.shape/attachments/<task>/` as a file link, a note with the exit status
+//! and the last lines of output, and `meta.run_status` set to `passed` or
+//! `failed`. Shape exits with the command's exit code.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+
+use super::agent::get_agent_name;
+use super::duration::format_duration;
+use super::error::{CliError, ErrorCode};
+use super::freeze::ensure_task_mutable;
+use super::output::Output;
+use crate::domain::LinkType;
+use crate::storage::Project;
+
+/// Task metadata key holding the outcome of the last `watch-run`
+pub(super) const RUN_STATUS_META_KEY: &str = "run_status";
+
+/// Runs `command` for the task and returns the exit code to exit with
+pub(super) fn run(
+    output: &Output,
+    id_str: &str,
+    command: &[String],
+    excerpt: usize,
+    agent: Option<&str>,
+) -> Result<i32> {
+    let project = Project::open_current()?;
+    let store = project.task_store();
+    let id = project.resolve_task_id(id_str)?;
+    {
+        let tasks = store.read_all()?;
+        let task = tasks
+            .get(&id)
+            .ok_or_else(|| CliError::not_found("Task", &id))?;
+        ensure_task_mutable(&project, task)?;
+    }
+    let Some((program, args)) = command.split_first() else {
+        return Err(CliError::new(ErrorCode::Validation, "No command given after --").into());
+    };
+    let shown = command.join(" ");
+
+    output.verbose_ctx("watch-run", &format!("Running {} for {}", shown, id));
+    let started_at = Utc::now();
+    let start = Instant::now();
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", program))?;
+
+    // Output is passed through as it arrives and kept, interleaved, for the
+    // log; in JSON mode all of it goes to stderr to keep stdout parseable
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let json = output.is_json();
+    let forward = |pipe: Box<dyn Read + Send>, to_stderr: bool| {
+        let log = Arc::clone(&log);
+        thread::spawn(move || {
+            for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+                if to_stderr || json {
+                    eprintln!("{}", line);
+                } else {
+                    println!("{}", line);
+                }
+                log.lock().expect("log lock").push(line);
+            }
+        })
+    };
+    let readers = [
+        child.stdout.take().map(|p| forward(Box::new(p), false)),
+        child.stderr.take().map(|p| forward(Box::new(p), true)),
+    ];
+    let status = child
+        .wait()
+        .with_context(|| format!("Failed to wait for {}", program))?;
+    for reader in readers.into_iter().flatten() {
+        let _ = reader.join();
+    }
+    let elapsed = chrono::Duration::from_std(start.elapsed()).unwrap_or_default();
+    let lines = std::mem::take(&mut *log.lock().expect("log lock"));
+
+    let exit_code = status.code().unwrap_or(-1);
+    let passed = status.success();
+    let outcome = if passed { "passed" } else { "failed" };
+
+    // Full log next to other files attached to the task
+    let dir = project.attachments_dir().join(id.to_string());
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    let name = format!("run-{}.log", started_at.format("%Y%m%dT%H%M%S%.3fZ"));
+    let mut file = fs::File::create(dir.join(&name))
+        .with_context(|| format!("Failed to write log: {}", name))?;
+    writeln!(file, "$ {}", shown)?;
+    for line in &lines {
+        writeln!(file, "{}", line)?;
+    }
+    writeln!(
+        file,
+        "# exit {} after {}",
+        exit_code,
+        format_duration(elapsed)
+    )?;
+    let reference = format!(".shape/attachments/{}/{}", id, name);
+
+    let mut note = format!(
+        "`{}` {} (exit {}) in {}",
+        shown,
+        outcome,
+        exit_code,
+        format_duration(elapsed)
+    );
+    let tail = &lines[lines.len().saturating_sub(excerpt)..];
+    if !tail.is_empty() {
+        note.push_str(&format!("\n```\n{}\n```", tail.join("\n")));
+    }
+
+    // Re-read: others may have changed the store while the command ran
+    let agent = get_agent_name(&project, agent);
+    let mut tasks = store.read_all()?;
+    let task = tasks
+        .get_mut(&id)
+        .ok_or_else(|| CliError::not_found("Task", &id))?;
+    task.add_link(LinkType::File, reference.clone(), Some(&agent));
+    task.add_note(&agent, note);
+    task.set_meta(RUN_STATUS_META_KEY, outcome);
+    store.update(task)?;
+
+    if output.is_json() {
+        output.data(&serde_json::json!({
+            "id": id.to_string(),
+            "command": command,
+            "status": outcome,
+            "exit_code": exit_code,
+            "duration_secs": elapsed.num_seconds(),
+            "log": reference,
+            "excerpt": tail,
+        }));
+    } else if passed {
+        output.success(&format!(
+            "{} passed; recorded on {} ({})",
+            shown, id, reference
+        ));
+    } else {
+        eprintln!(
+            "{} failed with exit {}; recorded on {} ({})",
+            shown, exit_code, id, reference
+        );
+    }
+
+    Ok(if passed { 0 } else { exit_code.clamp(1, 255) })
+}
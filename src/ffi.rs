@@ -0,0 +1,304 @@
+//! C ABI (feature `ffi`)
+//!
+//! A minimal, stable C interface over [`ShapeApi`] for editors and other
+//! non-Rust tools. All data crosses the boundary as UTF-8 JSON strings. The
+//! matching header is `include/shape.h`.
+//!
+//! # Memory ownership
+//!
+//! - `shape_open` returns a handle owned by the caller; release it with
+//!   `shape_close`.
+//! - Every `char *` returned by this library is owned by the caller and must
+//!   be released with `shape_string_free` (never with `free`).
+//! - Strings passed into the library are borrowed for the duration of the
+//!   call only.
+//!
+//! # Errors
+//!
+//! Functions return `NULL` on failure. The message for the most recent
+//! failure on the calling thread is available from `shape_last_error`.
+//! Panics never unwind into the caller: they are caught at the boundary and
+//! reported the same way, as a `NULL` result and a `panicked: ...` message.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use anyhow::{Context, Result};
+
+use crate::api::{Operation, ShapeApi};
+
+/// Opaque project handle
+pub struct ShapeHandle {
+    api: ShapeApi,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(error: &anyhow::Error) {
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(format!("{:#}", error)));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+}
+
+/// Converts a Rust string into a caller-owned C string
+fn into_c_string(s: String) -> *mut c_char {
+    // JSON and error messages never contain interior NULs, but strip them
+    // rather than failing if a task title somehow does
+    CString::new(s.replace('\0', ""))
+        .map(CString::into_raw)
+        .unwrap_or(ptr::null_mut())
+}
+
+/// Describes a caught panic payload
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    let msg = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+    format!("panicked: {}", msg)
+}
+
+/// Runs `f`, recording any error or panic and mapping it to a NULL result.
+///
+/// Unwinding across `extern "C"` aborts the host process, so every exported
+/// body runs inside this.
+fn guard<T>(f: impl FnOnce() -> Result<T>, null: T) -> T {
+    clear_last_error();
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(e)) => {
+            set_last_error(&e);
+            null
+        }
+        Err(payload) => {
+            set_last_error(&anyhow::anyhow!(panic_message(payload.as_ref())));
+            null
+        }
+    }
+}
+
+/// # Safety
+///
+/// `s` must be NULL or a valid NUL-terminated string.
+unsafe fn borrow_str<'a>(s: *const c_char, what: &str) -> Result<&'a str> {
+    if s.is_null() {
+        anyhow::bail!("{} is NULL", what);
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .with_context(|| format!("{} is not valid UTF-8", what))
+}
+
+/// # Safety
+///
+/// `handle` must be NULL or a pointer returned by `shape_open`.
+unsafe fn borrow_handle<'a>(handle: *const ShapeHandle) -> Result<&'a ShapeHandle> {
+    handle
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("handle is NULL"))
+}
+
+/// Opens the project at `path`.
+///
+/// Returns NULL on failure.
+///
+/// # Safety
+///
+/// `path` must be a valid NUL-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn shape_open(path: *const c_char) -> *mut ShapeHandle {
+    guard(
+        || {
+            let path = borrow_str(path, "path")?;
+            let api = ShapeApi::open(path)?;
+            Ok(Box::into_raw(Box::new(ShapeHandle { api })))
+        },
+        ptr::null_mut(),
+    )
+}
+
+/// Releases a handle returned by `shape_open`. Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `handle` must be NULL or a pointer returned by `shape_open` that has not
+/// already been closed.
+#[no_mangle]
+pub unsafe extern "C" fn shape_close(handle: *mut ShapeHandle) {
+    let _ = panic::catch_unwind(|| {
+        if !handle.is_null() {
+            drop(Box::from_raw(handle));
+        }
+    });
+}
+
+/// Returns the ready tasks as a JSON array.
+///
+/// The result must be released with `shape_string_free`. Returns NULL on
+/// failure.
+///
+/// # Safety
+///
+/// `handle` must be a live pointer returned by `shape_open`.
+#[no_mangle]
+pub unsafe extern "C" fn shape_ready_json(handle: *const ShapeHandle) -> *mut c_char {
+    guard(
+        || {
+            let handle = borrow_handle(handle)?;
+            let ready = handle.api.ready()?;
+            Ok(into_c_string(serde_json::to_string(&ready)?))
+        },
+        ptr::null_mut(),
+    )
+}
+
+/// Applies a JSON-encoded operation and returns the affected brief or task
+/// as JSON.
+///
+/// Operations are objects tagged with `op`: `create_brief`, `add_task`,
+/// `add_dependency`, `claim`, `unclaim`, `start`, `complete`, `add_note`.
+///
+/// The result must be released with `shape_string_free`. Returns NULL on
+/// failure.
+///
+/// # Safety
+///
+/// `handle` must be a live pointer returned by `shape_open` and `op_json` a
+/// valid NUL-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn shape_apply_json(
+    handle: *const ShapeHandle,
+    op_json: *const c_char,
+) -> *mut c_char {
+    guard(
+        || {
+            let handle = borrow_handle(handle)?;
+            let op: Operation = serde_json::from_str(borrow_str(op_json, "op_json")?)
+                .context("Invalid operation")?;
+            let result = handle.api.apply(op)?;
+            Ok(into_c_string(result.to_string()))
+        },
+        ptr::null_mut(),
+    )
+}
+
+/// Returns the last error message on this thread, or NULL if the previous
+/// call succeeded.
+///
+/// The result must be released with `shape_string_free`.
+#[no_mangle]
+pub extern "C" fn shape_last_error() -> *mut c_char {
+    // Not through `guard`, which would clear the message being read
+    panic::catch_unwind(|| {
+        LAST_ERROR.with(|e| match e.borrow().as_ref() {
+            Some(msg) => into_c_string(msg.clone()),
+            None => ptr::null_mut(),
+        })
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Releases a string returned by this library. Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `s` must be NULL or a pointer returned by this library that has not
+/// already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn shape_string_free(s: *mut c_char) {
+    // Leaves the last error alone: callers free the message they just read
+    let _ = panic::catch_unwind(|| {
+        if !s.is_null() {
+            drop(CString::from_raw(s));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Takes ownership of a returned string
+    unsafe fn take(s: *mut c_char) -> String {
+        assert!(!s.is_null(), "unexpected NULL result");
+        let owned = CStr::from_ptr(s).to_str().unwrap().to_string();
+        shape_string_free(s);
+        owned
+    }
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    #[test]
+    fn open_apply_and_query_ready() {
+        let project = ShapeApi::temporary().unwrap();
+        let path = c(project.root().to_str().unwrap());
+
+        unsafe {
+            let handle = shape_open(path.as_ptr());
+            assert!(!handle.is_null());
+
+            let op = c(r#"{"op": "add_task", "title": "From C"}"#);
+            let task: serde_json::Value =
+                serde_json::from_str(&take(shape_apply_json(handle, op.as_ptr()))).unwrap();
+            assert_eq!(task["title"], "From C");
+
+            let ready: serde_json::Value =
+                serde_json::from_str(&take(shape_ready_json(handle))).unwrap();
+            assert_eq!(ready[0]["id"], task["id"]);
+            assert!(shape_last_error().is_null());
+
+            shape_close(handle);
+        }
+    }
+
+    #[test]
+    fn failures_return_null_and_set_last_error() {
+        unsafe {
+            let missing = c("/nonexistent/shape/project");
+            assert!(shape_open(missing.as_ptr()).is_null());
+            assert!(!take(shape_last_error()).is_empty());
+
+            assert!(shape_open(ptr::null()).is_null());
+            assert_eq!(take(shape_last_error()), "path is NULL");
+
+            let project = ShapeApi::temporary().unwrap();
+            let path = c(project.root().to_str().unwrap());
+            let handle = shape_open(path.as_ptr());
+
+            let bad = c(r#"{"op": "explode"}"#);
+            assert!(shape_apply_json(handle, bad.as_ptr()).is_null());
+            assert!(take(shape_last_error()).starts_with("Invalid operation"));
+
+            shape_close(handle);
+        }
+    }
+
+    #[test]
+    fn panics_return_null_and_set_last_error() {
+        let result = guard(
+            || -> Result<*mut c_char> { panic!("boom") },
+            ptr::null_mut(),
+        );
+        assert!(result.is_null());
+        unsafe {
+            assert_eq!(take(shape_last_error()), "panicked: boom");
+        }
+    }
+
+    #[test]
+    fn null_pointers_are_safe_to_release() {
+        unsafe {
+            shape_close(ptr::null_mut());
+            shape_string_free(ptr::null_mut());
+        }
+    }
+}
@@ -14,9 +14,17 @@
 //!
 //! - [`domain`] - Core business logic: Briefs, Tasks, IDs, and the dependency graph
 //! - [`storage`] - Persistence layer: Markdown files for briefs, JSONL for tasks
+//!
 //! - [`plugin`] - Extensibility: Custom brief types and external tool sync
 //! - [`cli`] - Command-line interface and output formatting
 //!
+//! `domain` and `storage` live in the `shape-core` crate, re-exported here;
+//! depend on `shape-core` directly to use the data model without the CLI's
+//! dependencies.
+//!
+//! Tools embedding shape as a library should start with [`api::ShapeApi`],
+//! a high-level facade over the stores and domain types.
+//!
 //! ## Data Flow
 //!
 //! ```text
@@ -74,9 +82,15 @@
 //! 3. **AI-optimized**: Context export designed for minimal tokens
 //! 4. **Extensible**: Plugin system for custom brief types and sync
 
+pub mod api;
 pub mod cli;
-pub mod domain;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod plugin;
-pub mod storage;
+#[cfg(feature = "python")]
+pub mod python;
+
+pub use shape_core::{domain, storage};
 
+pub use api::ShapeApi;
 pub use domain::{Brief, BriefId, BriefStatus, Task, TaskId, TaskStatus};
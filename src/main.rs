@@ -3,10 +3,5 @@
 use std::process::ExitCode;
 
 fn main() -> ExitCode {
-    if let Err(e) = shape_cli::cli::run() {
-        eprintln!("Error: {:#}", e);
-        ExitCode::FAILURE
-    } else {
-        ExitCode::SUCCESS
-    }
+    shape_cli::cli::run()
 }
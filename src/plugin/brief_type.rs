@@ -7,8 +7,11 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::loader::PluginLoader;
+use super::loader::{PluginError, PluginLoader};
+use super::prd::PrdBriefType;
 use super::protocol::PluginRequest;
+use super::rfc::RfcBriefType;
+use crate::domain::BriefStatus;
 
 /// Template for creating a new brief
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,17 +76,18 @@ impl<'a> BriefTypePlugin<'a> {
         let response = self.loader.execute(&self.plugin_name, &request)?;
 
         if !response.success {
-            anyhow::bail!(
+            return Err(PluginError::Failed(format!(
                 "Plugin error: {}",
                 response
                     .error
                     .unwrap_or_else(|| "Unknown error".to_string())
-            );
+            ))
+            .into());
         }
 
         let data = response
             .data
-            .ok_or_else(|| anyhow::anyhow!("No template data returned"))?;
+            .ok_or_else(|| PluginError::Failed("No template data returned".to_string()))?;
 
         let template: BriefTemplate =
             serde_json::from_value(data).context("Failed to parse template")?;
@@ -108,17 +112,18 @@ impl<'a> BriefTypePlugin<'a> {
         let response = self.loader.execute(&self.plugin_name, &request)?;
 
         if !response.success {
-            anyhow::bail!(
+            return Err(PluginError::Failed(format!(
                 "Plugin error: {}",
                 response
                     .error
                     .unwrap_or_else(|| "Unknown error".to_string())
-            );
+            ))
+            .into());
         }
 
         let data = response
             .data
-            .ok_or_else(|| anyhow::anyhow!("No parse result returned"))?;
+            .ok_or_else(|| PluginError::Failed("No parse result returned".to_string()))?;
 
         let result: ParseResult = serde_json::from_value(data).context("Failed to parse result")?;
 
@@ -132,17 +137,18 @@ impl<'a> BriefTypePlugin<'a> {
         let response = self.loader.execute(&self.plugin_name, &request)?;
 
         if !response.success {
-            anyhow::bail!(
+            return Err(PluginError::Failed(format!(
                 "Plugin error: {}",
                 response
                     .error
                     .unwrap_or_else(|| "Unknown error".to_string())
-            );
+            ))
+            .into());
         }
 
         let data = response
             .data
-            .ok_or_else(|| anyhow::anyhow!("No statuses returned"))?;
+            .ok_or_else(|| PluginError::Failed("No statuses returned".to_string()))?;
 
         let statuses: Vec<String> =
             serde_json::from_value(data).context("Failed to parse statuses")?;
@@ -217,6 +223,125 @@ impl MinimalBriefType {
     }
 }
 
+/// A named step in a built-in type's lifecycle. Briefs store the core
+/// status it maps to, so lists, filters and context keep working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stage {
+    pub name: &'static str,
+    pub status: BriefStatus,
+}
+
+/// Lifecycle stages of a built-in type that names its own, in order
+pub fn lifecycle(brief_type: &str) -> &'static [Stage] {
+    match brief_type {
+        "rfc" => RfcBriefType::LIFECYCLE,
+        "prd" => PrdBriefType::LIFECYCLE,
+        _ => &[],
+    }
+}
+
+/// Core status for a lifecycle stage name of `brief_type`
+pub fn stage_status(brief_type: &str, name: &str) -> Option<BriefStatus> {
+    lifecycle(brief_type)
+        .iter()
+        .find(|s| s.name.eq_ignore_ascii_case(name))
+        .map(|s| s.status)
+}
+
+/// Lifecycle stage name shown for `status` in `brief_type`
+pub fn stage_name(brief_type: &str, status: BriefStatus) -> Option<&'static str> {
+    lifecycle(brief_type)
+        .iter()
+        .find(|s| s.status == status)
+        .map(|s| s.name)
+}
+
+/// Sections (`## Heading`) a built-in type requires in the body
+pub fn required_sections(brief_type: &str) -> &'static [&'static str] {
+    match brief_type {
+        "rfc" => RfcBriefType::REQUIRED_SECTIONS,
+        "prd" => PrdBriefType::REQUIRED_SECTIONS,
+        _ => &[],
+    }
+}
+
+/// Required sections of `brief_type` that `body` has no heading for, or
+/// only an empty one
+pub fn missing_sections(brief_type: &str, body: &str) -> Vec<&'static str> {
+    required_sections(brief_type)
+        .iter()
+        .copied()
+        .filter(|section| !has_section(body, section))
+        .collect()
+}
+
+/// Whether `body` has a `## section` heading with text under it
+fn has_section(body: &str, section: &str) -> bool {
+    let mut lines = body.lines();
+    while let Some(line) = lines.next() {
+        let Some(heading) = line.strip_prefix("## ") else {
+            continue;
+        };
+        if heading.trim().eq_ignore_ascii_case(section) {
+            return lines
+                .take_while(|l| !l.starts_with("## "))
+                .any(|l| !l.trim().is_empty() && !l.trim_start().starts_with("<!--"));
+        }
+    }
+    false
+}
+
+/// Validation shared by the document types: a title, a status from the
+/// lifecycle (by stage or core name) and the required sections
+pub(super) fn validate_document(
+    frontmatter: &serde_json::Value,
+    body: &str,
+    brief_type: &str,
+) -> ParseResult {
+    let mut errors = Vec::new();
+
+    if frontmatter.get("title").is_none() {
+        errors.push(ValidationError {
+            field: "title".to_string(),
+            message: "Title is required".to_string(),
+        });
+    }
+
+    if let Some(status) = frontmatter.get("status").and_then(|v| v.as_str()) {
+        let stages = lifecycle(brief_type);
+        let known = stages
+            .iter()
+            .any(|s| s.name == status || s.status.to_string() == status);
+        if !known {
+            errors.push(ValidationError {
+                field: "status".to_string(),
+                message: format!(
+                    "Invalid status: {}. Valid values: {:?}",
+                    status,
+                    stages.iter().map(|s| s.name).collect::<Vec<_>>()
+                ),
+            });
+        }
+    }
+
+    for section in missing_sections(brief_type, body) {
+        errors.push(ValidationError {
+            field: "body".to_string(),
+            message: format!("Missing required section: ## {}", section),
+        });
+    }
+
+    ParseResult {
+        valid: errors.is_empty(),
+        metadata: if errors.is_empty() {
+            Some(frontmatter.clone())
+        } else {
+            None
+        },
+        errors,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -275,4 +400,25 @@ mod tests {
         assert!(!result.valid);
         assert!(result.errors.iter().any(|e| e.field == "status"));
     }
+
+    #[test]
+    fn stages_map_to_core_statuses() {
+        assert_eq!(
+            stage_status("rfc", "Accepted"),
+            Some(BriefStatus::InProgress)
+        );
+        assert_eq!(stage_name("prd", BriefStatus::Shipped), Some("launched"));
+        assert_eq!(stage_status("minimal", "accepted"), None);
+        assert!(lifecycle("shapeup").is_empty());
+    }
+
+    #[test]
+    fn missing_sections_ignores_placeholders() {
+        let body = "# T\n\n## Summary\n\nShort.\n\n## Motivation\n\n<!-- why -->\n";
+        assert_eq!(
+            missing_sections("rfc", body),
+            vec!["Motivation", "Detailed Design"]
+        );
+        assert!(missing_sections("minimal", body).is_empty());
+    }
 }
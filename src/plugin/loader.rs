@@ -10,9 +10,19 @@ use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
 use anyhow::{Context, Result};
+use thiserror::Error;
 
 use super::protocol::{PluginManifest, PluginRequest, PluginResponse, PluginType};
 
+#[derive(Debug, Error)]
+pub enum PluginError {
+    #[error("Plugin not found: {0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    Failed(String),
+}
+
 /// Information about a discovered plugin
 #[derive(Debug, Clone)]
 pub struct PluginInfo {
@@ -156,7 +166,7 @@ impl PluginLoader {
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Plugin returned error: {}", stderr);
+            return Err(PluginError::Failed(format!("Plugin returned error: {}", stderr)).into());
         }
 
         let manifest: PluginManifest = serde_json::from_slice(&output.stdout)
@@ -166,11 +176,12 @@ impl PluginLoader {
     }
 
     /// Executes a plugin request
+    #[tracing::instrument(name = "plugin.execute", level = "debug", skip_all, fields(plugin = name))]
     pub fn execute(&self, name: &str, request: &PluginRequest) -> Result<PluginResponse> {
         let info = self
             .plugins
             .get(name)
-            .ok_or_else(|| anyhow::anyhow!("Plugin not found: {}", name))?;
+            .ok_or_else(|| PluginError::NotFound(name.to_string()))?;
 
         let mut child = Command::new(&info.path)
             .stdin(Stdio::piped())
@@ -191,7 +202,7 @@ impl PluginLoader {
         let response_line = reader
             .lines()
             .next()
-            .ok_or_else(|| anyhow::anyhow!("No response from plugin"))?
+            .ok_or_else(|| PluginError::Failed("No response from plugin".to_string()))?
             .context("Failed to read plugin response")?;
 
         let response: PluginResponse =
@@ -1,6 +1,7 @@
 //! # Plugin System
 //!
-//! Extensibility layer for custom brief types and external tool sync.
+//! Extensibility layer for custom brief types, external tool sync and
+//! brief translation.
 //!
 //! ## Overview
 //!
@@ -13,6 +14,7 @@
 //! |------|----------------|---------|
 //! | Brief Type | `shape-brief-{name}` | Custom document templates and validation |
 //! | Sync | `shape-sync-{name}` | Bidirectional sync with external tools |
+//! | Translate | `shape-translate-{name}` | Localized copies of briefs |
 //!
 //! ## Plugin Discovery
 //!
@@ -38,6 +40,8 @@
 //!
 //! - `minimal` - Basic title and status (default)
 //! - `shapeup` - ShapeUp methodology: appetite, rabbit holes, no-gos
+//! - `rfc` - Technical design for review: required sections, RFC lifecycle
+//! - `prd` - Product requirements: required sections, PRD lifecycle
 //!
 //! ## Key Types
 //!
@@ -45,15 +49,28 @@
 //! - [`PluginManifest`] - Declares plugin capabilities
 //! - [`BriefTypePlugin`] - Trait for brief type plugins
 //! - [`SyncPlugin`] - Trait for sync plugins
+//! - [`TranslatePlugin`] - Wrapper for translate plugins
 
 mod brief_type;
 mod loader;
+mod prd;
 mod protocol;
+mod rfc;
 mod shapeup;
 mod sync;
+mod translate;
 
-pub use brief_type::{BriefTemplate, BriefTypePlugin, MinimalBriefType};
-pub use loader::{PluginInfo, PluginLoader};
+pub use brief_type::{
+    lifecycle, missing_sections, required_sections, stage_name, stage_status, BriefTemplate,
+    BriefTypePlugin, MinimalBriefType, Stage,
+};
+pub use loader::{PluginError, PluginInfo, PluginLoader};
+pub use prd::PrdBriefType;
 pub use protocol::{PluginManifest, PluginMessage, PluginRequest, PluginResponse};
+pub use rfc::RfcBriefType;
 pub use shapeup::ShapeUpBriefType;
-pub use sync::{EntityType, IdMapping, SyncOperation, SyncPlugin, SyncResult};
+pub use sync::{
+    mapping_path, prune_mappings, read_mappings, write_mappings, EntityType, IdMapping,
+    SyncOperation, SyncPlugin, SyncResult,
+};
+pub use translate::{join_sections, split_sections, Section, TranslatePlugin, TranslatedText};
@@ -0,0 +1,124 @@
+//! PRD brief type
+//!
+//! Product requirements document, with:
+//! - Problem, Goals, Non-Goals, User Stories, Requirements, Success Metrics
+//!   and Open Questions sections (Problem, Goals and Requirements required)
+//! - A PRD lifecycle (draft, review, approved, launched, cancelled)
+
+use super::brief_type::{validate_document, BriefTemplate, ParseResult, Stage};
+use crate::domain::BriefStatus;
+
+/// PRD brief type implementation (built into core)
+pub struct PrdBriefType;
+
+impl PrdBriefType {
+    /// Lifecycle stages and the statuses they are stored as
+    pub const LIFECYCLE: &'static [Stage] = &[
+        Stage {
+            name: "draft",
+            status: BriefStatus::Proposed,
+        },
+        Stage {
+            name: "review",
+            status: BriefStatus::Betting,
+        },
+        Stage {
+            name: "approved",
+            status: BriefStatus::InProgress,
+        },
+        Stage {
+            name: "launched",
+            status: BriefStatus::Shipped,
+        },
+        Stage {
+            name: "cancelled",
+            status: BriefStatus::Archived,
+        },
+    ];
+
+    /// Sections a PRD must fill in
+    pub const REQUIRED_SECTIONS: &'static [&'static str] = &["Problem", "Goals", "Requirements"];
+
+    /// Gets the template for a PRD
+    pub fn template(title: &str) -> BriefTemplate {
+        let body = format!(
+            r#"# {}
+
+## Problem
+
+<!-- Who has the problem, and what does it cost them today? -->
+
+## Goals
+
+<!-- What outcomes should this deliver? -->
+
+## Non-Goals
+
+<!-- What is deliberately out of scope? -->
+
+## User Stories
+
+<!-- As a <user>, I want <capability> so that <benefit>. -->
+
+## Requirements
+
+<!-- What the product must do, most important first. -->
+
+## Success Metrics
+
+<!-- How will we know it worked? -->
+
+## Open Questions
+
+<!-- What still needs an answer? -->
+"#,
+            title
+        );
+
+        BriefTemplate {
+            frontmatter: serde_json::json!({
+                "title": title,
+                "status": "proposed",
+            }),
+            body,
+            statuses: Self::statuses(),
+        }
+    }
+
+    /// Returns the PRD lifecycle stage names
+    pub fn statuses() -> Vec<String> {
+        Self::LIFECYCLE.iter().map(|s| s.name.to_string()).collect()
+    }
+
+    /// Validates a PRD's frontmatter and required sections
+    pub fn validate(frontmatter: &serde_json::Value, body: &str) -> ParseResult {
+        validate_document(frontmatter, body, "prd")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prd_template_and_validation() {
+        let template = PrdBriefType::template("Team Billing");
+        assert!(template.body.contains("## Success Metrics"));
+        assert_eq!(template.statuses[3], "launched");
+
+        let frontmatter = serde_json::json!({ "title": "Team Billing", "status": "review" });
+        let result = PrdBriefType::validate(&frontmatter, &template.body);
+        let missing: Vec<_> = result.errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            missing,
+            [
+                "Missing required section: ## Problem",
+                "Missing required section: ## Goals",
+                "Missing required section: ## Requirements"
+            ]
+        );
+
+        let body = "## Problem\nx\n## Goals\nx\n## Requirements\n- seats\n";
+        assert!(PrdBriefType::validate(&frontmatter, body).valid);
+    }
+}
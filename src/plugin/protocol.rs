@@ -35,6 +35,9 @@ pub enum PluginType {
 
     /// Sync plugin (external tool integration)
     Sync,
+
+    /// Translate plugin (localized briefs)
+    Translate,
 }
 
 /// A message sent to a plugin
@@ -0,0 +1,129 @@
+//! RFC brief type
+//!
+//! Request for comments: a technical design put up for review, with:
+//! - Summary, Motivation, Detailed Design, Drawbacks, Alternatives and
+//!   Unresolved Questions sections (the first three required)
+//! - An RFC lifecycle (draft, review, accepted, implemented, rejected)
+
+use super::brief_type::{validate_document, BriefTemplate, ParseResult, Stage};
+use crate::domain::BriefStatus;
+
+/// RFC brief type implementation (built into core)
+pub struct RfcBriefType;
+
+impl RfcBriefType {
+    /// Lifecycle stages and the statuses they are stored as
+    pub const LIFECYCLE: &'static [Stage] = &[
+        Stage {
+            name: "draft",
+            status: BriefStatus::Proposed,
+        },
+        Stage {
+            name: "review",
+            status: BriefStatus::Betting,
+        },
+        Stage {
+            name: "accepted",
+            status: BriefStatus::InProgress,
+        },
+        Stage {
+            name: "implemented",
+            status: BriefStatus::Shipped,
+        },
+        Stage {
+            name: "rejected",
+            status: BriefStatus::Archived,
+        },
+    ];
+
+    /// Sections an RFC must fill in
+    pub const REQUIRED_SECTIONS: &'static [&'static str] =
+        &["Summary", "Motivation", "Detailed Design"];
+
+    /// Gets the template for an RFC
+    pub fn template(title: &str) -> BriefTemplate {
+        let body = format!(
+            r#"# {}
+
+## Summary
+
+<!-- One paragraph explaining the proposal. -->
+
+## Motivation
+
+<!-- Why are we doing this? What use cases does it support? -->
+
+## Detailed Design
+
+<!-- The design in enough detail for someone familiar with the codebase to implement it. -->
+
+## Drawbacks
+
+<!-- Why should we not do this? -->
+
+## Alternatives
+
+<!-- What other designs were considered, and why not them? -->
+
+## Unresolved Questions
+
+<!-- What needs to be settled before or during implementation? -->
+"#,
+            title
+        );
+
+        BriefTemplate {
+            frontmatter: serde_json::json!({
+                "title": title,
+                "status": "proposed",
+            }),
+            body,
+            statuses: Self::statuses(),
+        }
+    }
+
+    /// Returns the RFC lifecycle stage names
+    pub fn statuses() -> Vec<String> {
+        Self::LIFECYCLE.iter().map(|s| s.name.to_string()).collect()
+    }
+
+    /// Validates an RFC's frontmatter and required sections
+    pub fn validate(frontmatter: &serde_json::Value, body: &str) -> ParseResult {
+        validate_document(frontmatter, body, "rfc")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc_template_needs_required_sections_filled() {
+        let template = RfcBriefType::template("Async IO");
+        assert!(template.body.contains("## Detailed Design"));
+        assert!(template.body.contains("## Unresolved Questions"));
+
+        let frontmatter = serde_json::json!({ "title": "Async IO", "status": "proposed" });
+        let result = RfcBriefType::validate(&frontmatter, &template.body);
+        assert!(!result.valid);
+        assert_eq!(result.errors.len(), 3);
+
+        let body = template
+            .body
+            .replace("<!-- One paragraph", "Use tokio.\n<!-- One paragraph")
+            .replace("<!-- Why are we", "Blocking IO stalls.\n<!-- Why are we")
+            .replace("<!-- The design", "A runtime per worker.\n<!-- The design");
+        assert!(RfcBriefType::validate(&frontmatter, &body).valid);
+    }
+
+    #[test]
+    fn rfc_validation_accepts_stage_names() {
+        let frontmatter = serde_json::json!({ "title": "T", "status": "accepted" });
+        let body = "## Summary\nx\n## Motivation\nx\n## Detailed Design\nx\n";
+        assert!(RfcBriefType::validate(&frontmatter, body).valid);
+
+        let frontmatter = serde_json::json!({ "title": "T", "status": "launched" });
+        let result = RfcBriefType::validate(&frontmatter, body);
+        assert!(result.errors.iter().any(|e| e.field == "status"));
+    }
+}
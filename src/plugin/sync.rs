@@ -12,7 +12,7 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::loader::PluginLoader;
+use super::loader::{PluginError, PluginLoader};
 use super::protocol::PluginRequest;
 
 /// ID mapping between local and remote
@@ -75,7 +75,7 @@ impl<'a> SyncPlugin<'a> {
     /// Creates a new sync plugin wrapper
     pub fn new(loader: &'a PluginLoader, plugin_name: impl Into<String>, sync_dir: &Path) -> Self {
         let plugin_name = plugin_name.into();
-        let mapping_store = MappingStore::new(sync_dir.join(format!("{}.jsonl", plugin_name)));
+        let mapping_store = MappingStore::new(mapping_path(sync_dir, &plugin_name));
 
         Self {
             loader,
@@ -111,17 +111,18 @@ impl<'a> SyncPlugin<'a> {
         let response = self.loader.execute(&self.plugin_name, &request)?;
 
         if !response.success {
-            anyhow::bail!(
+            return Err(PluginError::Failed(format!(
                 "Push failed: {}",
                 response
                     .error
                     .unwrap_or_else(|| "Unknown error".to_string())
-            );
+            ))
+            .into());
         }
 
         let data = response
             .data
-            .ok_or_else(|| anyhow::anyhow!("No push result returned"))?;
+            .ok_or_else(|| PluginError::Failed("No push result returned".to_string()))?;
 
         // Update mappings from response
         if let Some(new_mappings) = data.get("mappings") {
@@ -150,17 +151,18 @@ impl<'a> SyncPlugin<'a> {
         let response = self.loader.execute(&self.plugin_name, &request)?;
 
         if !response.success {
-            anyhow::bail!(
+            return Err(PluginError::Failed(format!(
                 "Pull failed: {}",
                 response
                     .error
                     .unwrap_or_else(|| "Unknown error".to_string())
-            );
+            ))
+            .into());
         }
 
         let data = response
             .data
-            .ok_or_else(|| anyhow::anyhow!("No pull result returned"))?;
+            .ok_or_else(|| PluginError::Failed("No pull result returned".to_string()))?;
 
         // Update mappings from response
         if let Some(new_mappings) = data.get("mappings") {
@@ -205,6 +207,11 @@ impl<'a> SyncPlugin<'a> {
         })
     }
 
+    /// Returns the current local → remote ID mappings
+    pub fn mappings(&self) -> Result<Vec<IdMapping>> {
+        Ok(self.mapping_store.read_all()?.into_values().collect())
+    }
+
     /// Links a local ID to a remote ID
     pub fn link(&self, local_id: &str, remote_id: &str, entity_type: EntityType) -> Result<()> {
         let mapping = IdMapping {
@@ -250,6 +257,43 @@ pub struct SyncStatus {
     pub last_sync: Option<DateTime<Utc>>,
 }
 
+/// Mapping file of a sync plugin: `sync/<plugin>.jsonl`
+pub fn mapping_path(sync_dir: &Path, plugin_name: &str) -> PathBuf {
+    sync_dir.join(format!("{}.jsonl", plugin_name))
+}
+
+/// Reads the mappings in a `sync/<plugin>.jsonl` file, sorted by local ID
+pub fn read_mappings(path: &Path) -> Result<Vec<IdMapping>> {
+    let mut mappings: Vec<_> = MappingStore::new(path.to_path_buf())
+        .read_all()?
+        .into_values()
+        .collect();
+    mappings.sort_by(|a, b| a.local_id.cmp(&b.local_id));
+    Ok(mappings)
+}
+
+/// Replaces the mappings in a `sync/<plugin>.jsonl` file
+pub fn write_mappings(path: &Path, mappings: &[IdMapping]) -> Result<()> {
+    MappingStore::new(path.to_path_buf()).write_all(mappings)
+}
+
+/// Removes mappings that fail `keep` from a `sync/<plugin>.jsonl` file and
+/// returns them, sorted by local ID. With `dry_run`, nothing is written.
+pub fn prune_mappings(
+    path: &Path,
+    keep: impl Fn(&IdMapping) -> bool,
+    dry_run: bool,
+) -> Result<Vec<IdMapping>> {
+    let store = MappingStore::new(path.to_path_buf());
+    let (kept, mut removed): (Vec<_>, Vec<_>) =
+        store.read_all()?.into_values().partition(|m| keep(m));
+    removed.sort_by(|a, b| a.local_id.cmp(&b.local_id));
+    if !dry_run && !removed.is_empty() {
+        store.write_all(&kept)?;
+    }
+    Ok(removed)
+}
+
 /// Storage for ID mappings
 struct MappingStore {
     path: PathBuf,
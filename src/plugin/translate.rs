@@ -0,0 +1,153 @@
+//! Translate plugin interface
+//!
+//! Translate plugins localize brief text for `shape brief export --translate`.
+//! Operations: translate
+//!
+//! The brief body is sent as sections, split at its `## ` headings, so a
+//! plugin can translate them one by one and the structure survives.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::loader::{PluginError, PluginLoader};
+use super::protocol::PluginRequest;
+use crate::domain::Brief;
+
+/// A part of a brief body: a `## ` heading and the text under it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Section {
+    /// Heading text without the `## `, empty for the text before the first
+    /// heading
+    pub heading: String,
+
+    /// Text under the heading, up to the next one
+    pub body: String,
+}
+
+/// Splits a brief body at its `## ` headings, outside code blocks
+pub fn split_sections(body: &str) -> Vec<Section> {
+    let mut sections = vec![Section {
+        heading: String::new(),
+        body: String::new(),
+    }];
+    let mut in_code = false;
+    for line in body.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        match line.strip_prefix("## ").filter(|_| !in_code) {
+            Some(heading) => sections.push(Section {
+                heading: heading.trim_end().to_string(),
+                body: String::new(),
+            }),
+            None => sections
+                .last_mut()
+                .expect("one section")
+                .body
+                .push_str(line),
+        }
+    }
+    if sections[0].body.is_empty() && sections.len() > 1 {
+        sections.remove(0);
+    }
+    sections
+}
+
+/// Reassembles a body split by [`split_sections`]
+pub fn join_sections(sections: &[Section]) -> String {
+    let mut body = String::new();
+    for section in sections {
+        if !section.heading.is_empty() {
+            body.push_str("## ");
+            body.push_str(&section.heading);
+            body.push('\n');
+        }
+        body.push_str(&section.body);
+    }
+    body
+}
+
+/// A brief's title and body in another language
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranslatedText {
+    /// Translated title
+    pub title: String,
+
+    /// Translated sections, in the order they were sent
+    pub sections: Vec<Section>,
+}
+
+/// Translate plugin wrapper
+pub struct TranslatePlugin<'a> {
+    loader: &'a PluginLoader,
+    plugin_name: String,
+}
+
+impl<'a> TranslatePlugin<'a> {
+    /// Creates a new translate plugin wrapper
+    pub fn new(loader: &'a PluginLoader, plugin_name: impl Into<String>) -> Self {
+        Self {
+            loader,
+            plugin_name: plugin_name.into(),
+        }
+    }
+
+    /// Translates a brief's title and sections into `lang`
+    pub fn translate(&self, brief: &Brief, lang: &str) -> Result<TranslatedText> {
+        let sections = split_sections(&brief.body);
+        let request = PluginRequest::new(
+            "translate",
+            serde_json::json!({
+                "brief": brief.id.to_string(),
+                "lang": lang,
+                "title": brief.title,
+                "sections": sections,
+            }),
+        );
+
+        let response = self.loader.execute(&self.plugin_name, &request)?;
+        if !response.success {
+            return Err(PluginError::Failed(format!(
+                "Translation failed: {}",
+                response
+                    .error
+                    .unwrap_or_else(|| "Unknown error".to_string())
+            ))
+            .into());
+        }
+
+        let data = response
+            .data
+            .ok_or_else(|| PluginError::Failed("No translation returned".to_string()))?;
+        let translated: TranslatedText =
+            serde_json::from_value(data).context("Failed to parse translation")?;
+        if translated.sections.len() != sections.len() {
+            return Err(PluginError::Failed(format!(
+                "Translation returned {} sections for {} sent",
+                translated.sections.len(),
+                sections.len()
+            ))
+            .into());
+        }
+        Ok(translated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_round_trip() {
+        let body = "# Search\n\n## Problem\n\nSlow.\n\n```md\n## not a heading\n```\n## Solution\n\nIndex it.\n";
+        let sections = split_sections(body);
+        let headings: Vec<&str> = sections.iter().map(|s| s.heading.as_str()).collect();
+        assert_eq!(headings, vec!["", "Problem", "Solution"]);
+        assert!(sections[1].body.contains("## not a heading"));
+        assert_eq!(join_sections(&sections), body);
+
+        let no_preamble = "## Only\nText";
+        assert_eq!(split_sections(no_preamble).len(), 1);
+        assert_eq!(join_sections(&split_sections(no_preamble)), no_preamble);
+    }
+}
@@ -0,0 +1,173 @@
+//! Python bindings (feature `python`)
+//!
+//! Exposes [`ShapeApi`] to Python as the `shape_api` extension module, built
+//! with maturin from `packages/python-api`. Briefs and tasks are returned as
+//! plain dicts with the same shape as the CLI's JSON output.
+//!
+//! ```python
+//! import shape_api
+//!
+//! project = shape_api.Project.open(".")
+//! for task in project.ready():
+//!     print(task["id"], task["title"])
+//! ```
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use serde::Serialize;
+
+use crate::api::ShapeApi;
+use crate::domain::{BriefId, TaskId};
+
+create_exception!(shape_api, ShapeError, PyException);
+
+fn to_py_err(e: anyhow::Error) -> PyErr {
+    ShapeError::new_err(format!("{:#}", e))
+}
+
+/// Converts a serializable value into Python objects via the json module
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<Py<PyAny>> {
+    let json = serde_json::to_string(value).map_err(|e| ShapeError::new_err(e.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+fn parse_task_id(id: &str) -> PyResult<TaskId> {
+    id.parse()
+        .map_err(|e: crate::domain::IdError| ShapeError::new_err(e.to_string()))
+}
+
+/// A shape project
+#[pyclass(name = "Project", module = "shape_api")]
+struct PyProject {
+    api: ShapeApi,
+}
+
+#[pymethods]
+impl PyProject {
+    /// Opens an existing project at `path`
+    #[staticmethod]
+    fn open(path: &str) -> PyResult<Self> {
+        let api = ShapeApi::open(path).map_err(to_py_err)?;
+        Ok(Self { api })
+    }
+
+    /// Opens the project containing the current directory
+    #[staticmethod]
+    fn open_current() -> PyResult<Self> {
+        let api = ShapeApi::open_current().map_err(to_py_err)?;
+        Ok(Self { api })
+    }
+
+    /// Initializes (or re-opens) a project at `path`
+    #[staticmethod]
+    fn init(path: &str) -> PyResult<Self> {
+        let api = ShapeApi::init(path).map_err(to_py_err)?;
+        Ok(Self { api })
+    }
+
+    /// Creates a throwaway project, deleted when the object is collected
+    #[staticmethod]
+    fn temporary() -> PyResult<Self> {
+        let api = ShapeApi::temporary().map_err(to_py_err)?;
+        Ok(Self { api })
+    }
+
+    /// Project root directory
+    #[getter]
+    fn root(&self) -> String {
+        self.api.root().display().to_string()
+    }
+
+    fn briefs(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        to_py(py, &self.api.briefs().map_err(to_py_err)?)
+    }
+
+    fn brief(&self, py: Python<'_>, id: &str) -> PyResult<Py<PyAny>> {
+        let id: BriefId = id
+            .parse()
+            .map_err(|e: crate::domain::IdError| ShapeError::new_err(e.to_string()))?;
+        to_py(py, &self.api.brief(&id).map_err(to_py_err)?)
+    }
+
+    #[pyo3(signature = (title, brief_type = "minimal"))]
+    fn create_brief(&self, py: Python<'_>, title: &str, brief_type: &str) -> PyResult<Py<PyAny>> {
+        to_py(
+            py,
+            &self
+                .api
+                .create_brief(title, brief_type)
+                .map_err(to_py_err)?,
+        )
+    }
+
+    fn tasks(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        to_py(py, &self.api.tasks().map_err(to_py_err)?)
+    }
+
+    fn task(&self, py: Python<'_>, id: &str) -> PyResult<Py<PyAny>> {
+        let id = parse_task_id(id)?;
+        to_py(py, &self.api.task(&id).map_err(to_py_err)?)
+    }
+
+    /// Tasks whose blocking dependencies are complete
+    fn ready(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        to_py(py, &self.api.ready().map_err(to_py_err)?)
+    }
+
+    /// Adds a task under a brief or task ID, or standalone when `parent` is None
+    #[pyo3(signature = (title, parent = None))]
+    fn add_task(&self, py: Python<'_>, title: &str, parent: Option<&str>) -> PyResult<Py<PyAny>> {
+        to_py(py, &self.api.add_task(parent, title).map_err(to_py_err)?)
+    }
+
+    fn add_dependency(&self, py: Python<'_>, task: &str, depends_on: &str) -> PyResult<Py<PyAny>> {
+        let task = parse_task_id(task)?;
+        let depends_on = parse_task_id(depends_on)?;
+        to_py(
+            py,
+            &self
+                .api
+                .add_dependency(&task, &depends_on)
+                .map_err(to_py_err)?,
+        )
+    }
+
+    fn claim(&self, py: Python<'_>, id: &str, agent: &str) -> PyResult<Py<PyAny>> {
+        let id = parse_task_id(id)?;
+        to_py(py, &self.api.claim(&id, agent).map_err(to_py_err)?)
+    }
+
+    fn unclaim(&self, py: Python<'_>, id: &str, agent: &str) -> PyResult<Py<PyAny>> {
+        let id = parse_task_id(id)?;
+        to_py(py, &self.api.unclaim(&id, agent).map_err(to_py_err)?)
+    }
+
+    fn start(&self, py: Python<'_>, id: &str) -> PyResult<Py<PyAny>> {
+        let id = parse_task_id(id)?;
+        to_py(py, &self.api.start(&id).map_err(to_py_err)?)
+    }
+
+    fn complete(&self, py: Python<'_>, id: &str) -> PyResult<Py<PyAny>> {
+        let id = parse_task_id(id)?;
+        to_py(py, &self.api.complete(&id).map_err(to_py_err)?)
+    }
+
+    fn add_note(&self, py: Python<'_>, id: &str, agent: &str, text: &str) -> PyResult<Py<PyAny>> {
+        let id = parse_task_id(id)?;
+        to_py(py, &self.api.add_note(&id, agent, text).map_err(to_py_err)?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Project({:?})", self.root())
+    }
+}
+
+/// The `shape_api` Python module
+#[pymodule]
+fn shape_api(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyProject>()?;
+    m.add("ShapeError", m.py().get_type::<ShapeError>())?;
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    Ok(())
+}
@@ -7,9 +7,12 @@ use predicates::prelude::*;
 use std::fs;
 use tempfile::TempDir;
 
-/// Get a command instance for the shape binary
+/// Get a command instance for the shape binary. `shape status` stays off the
+/// network unless a test opts back in.
 fn shape_cmd() -> assert_cmd::Command {
-    assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("shape"))
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("shape"));
+    cmd.env("SHAPE_NO_UPDATE_CHECK", "1");
+    cmd
 }
 
 /// Create a temporary directory and initialize a shape project
@@ -54,6 +57,45 @@ fn test_init_is_idempotent() {
     shape_cmd().arg("init").arg(dir.path()).assert().success();
 }
 
+#[test]
+fn test_init_adopts_markdown_docs() {
+    let dir = TempDir::new().unwrap();
+    let rfcs = dir.path().join("docs/rfcs");
+    std::fs::create_dir_all(&rfcs).unwrap();
+    std::fs::write(
+        rfcs.join("0001-auth.md"),
+        "# Auth Flow\n\n- Status: Accepted\n- Date: 2024-03-01\n\nBody\n",
+    )
+    .unwrap();
+    std::fs::write(rfcs.join("0002-caching-layer.md"), "No heading here\n").unwrap();
+    std::fs::write(rfcs.join("README.md"), "# Index\n").unwrap();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["init", "--adopt", "docs/rfcs", "--move", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    let adopted = json["adopted"].as_array().unwrap();
+    assert_eq!(adopted.len(), 2);
+    assert_eq!(adopted[0]["title"], "Auth Flow");
+    assert_eq!(adopted[0]["status"], "in_progress");
+    assert_eq!(adopted[0]["source"], "docs/rfcs/0001-auth.md");
+    assert_eq!(adopted[1]["title"], "Caching layer");
+
+    assert!(!rfcs.join("0001-auth.md").exists());
+    assert!(rfcs.join("README.md").exists());
+
+    let id = adopted[0]["id"].as_str().unwrap();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "show", id])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Body"));
+}
+
 // =============================================================================
 // Anchor Tests
 // =============================================================================
@@ -79,6 +121,58 @@ fn test_brief_new_creates_brief() {
     assert_eq!(briefs.len(), 1);
 }
 
+#[test]
+fn test_rfc_brief_has_lifecycle_and_required_sections() {
+    let dir = setup_project();
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "brief", "new", "Async IO", "--type", "rfc", "--format", "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+    assert_eq!(json["type"], "rfc");
+
+    // Stage names map onto core statuses
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "status", &id, "review", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["status"], "betting");
+    assert_eq!(json["stage"], "review");
+
+    // A fresh template is missing its required sections
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["doctor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "missing required section: ## Detailed Design",
+        ));
+
+    let path = dir.path().join(format!(".shape/briefs/{}.md", id));
+    let filled = fs::read_to_string(&path)
+        .unwrap()
+        .replace("## Summary\n", "## Summary\n\nUse tokio.\n")
+        .replace("## Motivation\n", "## Motivation\n\nBlocking IO stalls.\n")
+        .replace(
+            "## Detailed Design\n",
+            "## Detailed Design\n\nA runtime per worker.\n",
+        );
+    fs::write(&path, filled).unwrap();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["doctor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("missing required section").not());
+}
+
 #[test]
 fn test_brief_list_shows_briefs() {
     let dir = setup_project();
@@ -231,6 +325,54 @@ fn test_task_start_and_done() {
         .stdout(predicate::str::contains("Completed task"));
 }
 
+#[test]
+fn test_task_show_card_and_short() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+    for (task, dep) in [(&ids[1], &ids[0]), (&ids[2], &ids[1])] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "dep", task, dep])
+            .assert()
+            .success();
+    }
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["claim", &ids[0], "--agent", "ada"])
+        .assert()
+        .success();
+
+    // Dependency tree reaches transitive dependencies
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[2]])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[todo]"))
+        .stdout(predicate::str::contains("Depends on"))
+        .stdout(predicate::str::contains(format!("└─ ○ {}", ids[1])))
+        .stdout(predicate::str::contains(format!("└─ ◐ {}", ids[0])));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[0]])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[in progress]"))
+        .stdout(predicate::str::contains("Claimed: ada"))
+        .stdout(predicate::str::contains("lease"))
+        .stdout(predicate::str::contains("Blocks"))
+        .stdout(predicate::str::contains("claimed by ada"));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[0], "--short"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Title: "))
+        .stdout(predicate::str::contains("Depends on").not());
+}
+
 // =============================================================================
 // Dependency Tests
 // =============================================================================
@@ -484,6 +626,223 @@ fn test_context_compact() {
     assert!(json["recently_done"].is_array());
 }
 
+#[test]
+fn test_context_etag_not_modified() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+
+    let context = |etag: Option<&str>| -> serde_json::Value {
+        let mut args = vec!["context", "--compact", "--format", "json"];
+        if let Some(etag) = etag {
+            args.extend(["--etag", etag]);
+        }
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(&args)
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+
+    let first = context(None);
+    let etag = first["etag"].as_str().unwrap().to_string();
+    assert!(first["revision"].as_u64().unwrap() > 0);
+
+    // Same state serializes identically
+    assert_eq!(context(None), first);
+
+    let unchanged = context(Some(&etag));
+    assert_eq!(unchanged["not_modified"], true);
+    assert_eq!(unchanged["etag"], etag.as_str());
+    assert!(unchanged.get("ready").is_none());
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "start", &ids[0]])
+        .assert()
+        .success();
+
+    let changed = context(Some(&etag));
+    assert!(changed.get("not_modified").is_none());
+    assert_ne!(changed["etag"], etag.as_str());
+    assert!(changed["revision"].as_u64() > first["revision"].as_u64());
+}
+
+#[test]
+fn test_context_split_out_per_brief() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let brief_id = ids[0].split('.').next().unwrap().to_string();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "add", "Standalone chore"])
+        .assert()
+        .success();
+
+    let out_dir = dir.path().join("ctx");
+    let split = || {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(["context", "--split-out", "ctx", "--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_slice::<serde_json::Value>(&output.get_output().stdout).unwrap()
+    };
+
+    let result = split();
+    let shard_file = format!("{}.json", brief_id);
+    assert_eq!(
+        result["files"],
+        serde_json::json!([shard_file, "standalone.json"])
+    );
+
+    let index: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out_dir.join("index.json")).unwrap())
+            .unwrap();
+    assert_eq!(index["briefs"][0]["id"], brief_id.as_str());
+    assert_eq!(index["briefs"][0]["tasks"], 2);
+    assert_eq!(index["standalone"]["tasks"], 1);
+
+    let shard: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out_dir.join(&shard_file)).unwrap()).unwrap();
+    assert_eq!(shard["ready"].as_array().unwrap().len(), 2);
+    assert!(!shard.to_string().contains("Standalone chore"));
+
+    // Shipped briefs drop out and their shard is removed
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "status", &brief_id, "shipped"])
+        .assert()
+        .success();
+    split();
+    assert!(!out_dir.join(&shard_file).exists());
+    assert!(out_dir.join("standalone.json").exists());
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--split-out", "ctx", "--brief", &brief_id])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_context_selective_bodies() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+    let active_id = ids[0].split('.').next().unwrap().to_string();
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "new", "Parked idea", "--format", "json"])
+        .assert()
+        .success();
+    let parked: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let parked_id = parked["id"].as_str().unwrap().to_string();
+
+    // A body longer than the default 500-character cut
+    let brief_path = dir.path().join(format!(".shape/briefs/{}.md", active_id));
+    let mut content = fs::read_to_string(&brief_path).unwrap();
+    content.push_str(&format!("\n{}\n", "x".repeat(600)));
+    fs::write(&brief_path, content).unwrap();
+
+    let context = || -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args([
+                "context",
+                "--include-bodies",
+                "selective",
+                "--format",
+                "json",
+            ])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+    let brief = |json: &serde_json::Value, id: &str| -> serde_json::Value {
+        json["briefs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|b| b["id"] == id)
+            .unwrap()
+            .clone()
+    };
+
+    let json = context();
+    let body = brief(&json, &active_id)["body"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(body.contains(&"x".repeat(600)));
+    assert!(brief(&json, &parked_id).get("body").is_none());
+    let elided = json["elided_bodies"].as_array().unwrap();
+    assert_eq!(elided.len(), 1);
+    assert_eq!(elided[0]["id"], parked_id.as_str());
+    assert_eq!(elided[0]["reason"], "no active tasks");
+
+    // Bodies over the configured limit are cut and reported
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[context]\nselective_max_chars = 100\n");
+    fs::write(&config_path, config).unwrap();
+
+    let json = context();
+    let body = brief(&json, &active_id)["body"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(body.chars().count(), 103);
+    let reasons: Vec<_> = json["elided_bodies"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["reason"].as_str().unwrap().to_string())
+        .collect();
+    assert!(reasons.contains(&"truncated".to_string()));
+}
+
+#[test]
+fn test_context_bundle_verify() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let bundle = dir.path().join("run.ctx");
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--compact", "--bundle", "run.ctx"])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&bundle).unwrap()).unwrap();
+    assert_eq!(json["format"], "shape-context-bundle");
+    assert_eq!(json["options"]["compact"], true);
+    assert!(json["sources"][".shape/tasks.jsonl"].is_string());
+    assert!(json["context"]["ready"].is_array());
+
+    let verify = || {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["context", "verify", "run.ctx", "--format", "json"])
+            .assert()
+    };
+    let output = verify().success();
+    let report: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(report["reproducible"], true);
+    assert_eq!(report["changed_sources"].as_array().unwrap().len(), 0);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "start", &ids[0]])
+        .assert()
+        .success();
+
+    let output = verify().failure();
+    let report: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(report["reproducible"], false);
+    assert_eq!(report["changed_sources"][0], ".shape/tasks.jsonl");
+}
+
 // =============================================================================
 // Verbose Flag Tests
 // =============================================================================
@@ -503,6 +862,64 @@ fn test_verbose_flag() {
     assert!(stderr.contains("[verbose]"));
 }
 
+#[test]
+fn test_trace_flag_and_log_dir() {
+    let dir = setup_project();
+    create_brief_with_tasks(&dir, 1);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["--trace", "task", "list"])
+        .assert()
+        .success();
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr);
+    assert!(stderr.contains("[verbose] Shape CLI starting"));
+    assert!(stderr.contains("[trace] tasks.read_all: close time.busy="));
+
+    // The log file gets debug spans even when stderr stays quiet
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list"])
+        .env("SHAPE_LOG_DIR", dir.path().join("logs"))
+        .assert()
+        .success();
+    assert!(output.get_output().stderr.is_empty());
+    let log = std::fs::read_dir(dir.path().join("logs"))
+        .unwrap()
+        .map(|e| std::fs::read_to_string(e.unwrap().path()).unwrap())
+        .collect::<String>();
+    assert!(log.contains("tasks.read_all"));
+}
+
+#[test]
+fn test_profile_flag_breaks_down_command_time() {
+    let dir = setup_project();
+    create_brief_with_tasks(&dir, 1);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", "--profile"])
+        .assert()
+        .success();
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr);
+    assert!(stderr.contains("Profile: "));
+    assert!(stderr.contains("project.open"));
+    assert!(stderr.contains("tasks.read_all"));
+    assert!(stderr.contains("(unattributed)"));
+
+    // JSON output stays on stdout; the profile is a JSON line on stderr
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", "--profile", "--format", "json"])
+        .assert()
+        .success();
+    let _: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let profile: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    let spans = profile["profile"]["spans"].as_array().unwrap();
+    assert!(spans.iter().any(|s| s["span"] == "output.serialize"));
+    assert!(profile["profile"]["total_ms"].as_f64().is_some());
+}
+
 // =============================================================================
 // Error Handling Tests
 // =============================================================================
@@ -520,6 +937,42 @@ fn test_not_in_project_error() {
         .stderr(predicate::str::contains("Not in a shape project"));
 }
 
+#[test]
+fn test_project_flag_and_env() {
+    let outside = TempDir::new().unwrap();
+    let repo = outside.path().join("repo");
+    shape_cmd().arg("init").arg(&repo).assert().success();
+
+    shape_cmd()
+        .current_dir(outside.path())
+        .args([
+            "--project",
+            repo.to_str().unwrap(),
+            "brief",
+            "new",
+            "Remote",
+        ])
+        .assert()
+        .success();
+
+    shape_cmd()
+        .current_dir(outside.path())
+        .env("SHAPE_PROJECT", &repo)
+        .args(["brief", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Remote"));
+
+    // Not found: nearby projects are listed
+    shape_cmd()
+        .current_dir(outside.path())
+        .args(["brief", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Projects nearby"))
+        .stderr(predicate::str::contains("repo"));
+}
+
 #[test]
 fn test_brief_invalid_id_error() {
     let dir = setup_project();
@@ -949,6 +1402,57 @@ fn test_standalone_subtask() {
         .stdout(predicate::str::contains(".1")); // Sequence number
 }
 
+#[test]
+fn test_task_tree_rolls_up_subtask_progress() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let brief = ids[0].split('.').next().unwrap().to_string();
+    let json_of = |args: &[&str]| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(args)
+            .args(["--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+
+    let first = json_of(&["task", "add", &ids[0], "Step one"]);
+    assert_eq!(first["id"], format!("{}.1", ids[0]));
+    json_of(&["task", "external", &ids[0], "github:42"]);
+    let second = json_of(&["task", "add", "github:42", "--sub", "Step two"]);
+    assert_eq!(second["id"], format!("{}.2", ids[0]));
+    json_of(&["task", "done", first["id"].as_str().unwrap()]);
+
+    let tree = json_of(&["task", "tree", &ids[0]]);
+    assert_eq!(tree["status"], "todo");
+    assert_eq!(tree["rollup_status"], "in_progress");
+    assert_eq!(tree["progress"]["done"], 1);
+    assert_eq!(tree["progress"]["total"], 2);
+    assert_eq!(tree["subtasks"][1]["title"], "Step two");
+
+    // Leaf-weighted: Task 0 counts as its two subtasks, next to Task 1
+    let shown = json_of(&["brief", "show", &brief]);
+    assert_eq!(shown["progress"]["done"], 1);
+    assert_eq!(shown["progress"]["total"], 3);
+    assert_eq!(shown["progress"]["percent"], 33);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "tree", &brief])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1/3 (33%)"))
+        .stdout(predicate::str::contains("└─"));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "add", &format!("{}.9", brief), "Orphan"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not found"));
+}
+
 #[test]
 fn test_status_shows_standalone_count() {
     let dir = setup_project();
@@ -1427,6 +1931,36 @@ fn test_next_suggests_ready_task() {
     );
 }
 
+#[test]
+fn test_idempotency_key_replays_retries() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+
+    let note = |text: &str| {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["note", &ids[0], text, "--format", "json"])
+            .args(["--idempotency-key", "run-7-note"])
+            .assert()
+    };
+    let first = note("Retried after timeout").success();
+    let retry = note("Retried after timeout").success();
+    assert_eq!(first.get_output().stdout, retry.get_output().stdout);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["notes"].as_array().unwrap().len(), 1);
+
+    // Reusing a key for a different command is a conflict
+    note("Something else")
+        .code(4)
+        .stderr(predicate::str::contains("run-7-note"));
+}
+
 #[test]
 fn test_note_adds_to_task() {
     let dir = setup_project();
@@ -1696,3 +2230,5444 @@ fn test_handoff_task() {
         .stdout(predicate::str::contains("handoff"))
         .stdout(predicate::str::contains("human"));
 }
+
+#[test]
+fn test_claims_sweep_releases_expired_claims() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["claim", &ids[0], "--agent", "agent-1"])
+        .assert()
+        .success();
+
+    // Nothing has expired under the default timeout
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["claims", "sweep"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No expired claims"));
+
+    let config = dir.path().join(".shape/config.toml");
+    let mut content = std::fs::read_to_string(&config).unwrap_or_default();
+    content.push_str("\n[agent]\nclaim_timeout_hours = 0\n");
+    std::fs::write(&config, content).unwrap();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["claims", "sweep", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would release 1"));
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "claims",
+            "sweep",
+            "--assign-to",
+            "human",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["swept"][0]["id"], ids[0].as_str());
+    assert_eq!(json["swept"][0]["claimed_by"], "agent-1");
+    assert_eq!(json["swept"][0]["assigned_to"], "human");
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let task: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert!(task["claimed_by"].is_null());
+    assert_eq!(task["assigned_to"], "human");
+    assert!(task.to_string().contains("Claim by agent-1 expired"));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["claims", "sweep"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No expired claims"));
+}
+
+// =============================================================================
+// Bulk Guardrail Tests
+// =============================================================================
+
+/// Creates a brief with `count` tasks and returns the task IDs
+fn create_brief_with_tasks(dir: &TempDir, count: usize) -> Vec<String> {
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "new", "Bulk Brief", "--format", "json"])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    let brief_id = json["id"].as_str().unwrap().to_string();
+
+    (0..count)
+        .map(|i| {
+            let output = shape_cmd()
+                .current_dir(dir.path())
+                .args([
+                    "task",
+                    "add",
+                    &brief_id,
+                    &format!("Task {}", i),
+                    "--format",
+                    "json",
+                ])
+                .assert()
+                .success();
+            let json: serde_json::Value =
+                serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout))
+                    .unwrap();
+            json["id"].as_str().unwrap().to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn test_bulk_done_below_threshold_applies() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[0], &ids[1], "--format", "json"])
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 2);
+    assert_eq!(json[0]["status"], "done");
+}
+
+#[test]
+fn test_bulk_done_above_threshold_requires_yes_in_json() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 6);
+
+    let mut args = vec!["task", "done"];
+    args.extend(ids.iter().map(|s| s.as_str()));
+    args.extend(["--format", "json"]);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(&args)
+        .assert()
+        .success();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert_eq!(json["confirmation_required"], true);
+    assert_eq!(json["count"], 6);
+    assert_eq!(json["changes"][0]["to"], "done");
+
+    // Nothing was applied
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert_eq!(json["status"], "todo");
+
+    // --yes applies the change
+    args.push("--yes");
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(&args)
+        .assert()
+        .success();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[5], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert_eq!(json["status"], "done");
+}
+
+#[test]
+fn test_bulk_done_above_threshold_refuses_in_text_mode() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 6);
+
+    let mut args = vec!["task", "done"];
+    args.extend(ids.iter().map(|s| s.as_str()));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(&args)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("todo -> done"))
+        .stderr(predicate::str::contains("--yes"));
+}
+
+// =============================================================================
+// Pipeline Tests
+// =============================================================================
+
+#[test]
+fn test_task_pipeline_add_creates_chain() {
+    let dir = setup_project();
+
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str(
+        r#"
+[pipelines.release]
+steps = [
+    { title = "Design" },
+    { title = "Implement" },
+    { title = "Review" },
+]
+"#,
+    );
+    fs::write(&config_path, config).unwrap();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "new", "Release", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    let brief_id = json["id"].as_str().unwrap();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "task", "pipeline", "add", brief_id, "release", "--format", "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    let tasks = json["tasks"].as_array().unwrap();
+    assert_eq!(tasks.len(), 3);
+    assert_eq!(tasks[2]["depends_on"][0], tasks[1]["id"]);
+
+    // Only the first step is ready
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["ready"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Design"))
+        .stdout(predicate::str::contains("Review").not());
+
+    // Unknown pipelines fail without creating tasks
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "pipeline", "add", brief_id, "missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Pipeline not found"));
+}
+
+// =============================================================================
+// CI Diagnostics Tests
+// =============================================================================
+
+#[test]
+fn test_doctor_passes_on_clean_project() {
+    let dir = setup_project();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "add", "Standalone task"])
+        .assert()
+        .success();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["doctor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0 error(s)"));
+}
+
+#[test]
+fn test_doctor_junit_reports_invalid_brief() {
+    let dir = setup_project();
+    fs::write(dir.path().join(".shape/briefs/broken.md"), "not a brief").unwrap();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["doctor", "--format", "junit"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("<testsuite name=\"doctor\""))
+        .stdout(predicate::str::contains(
+            "<testcase classname=\"doctor\" name=\"frontmatter\">",
+        ))
+        .stdout(predicate::str::contains("<failure"))
+        .stdout(predicate::str::contains("broken.md"));
+}
+
+#[test]
+fn test_frontmatter_errors_name_line_and_key() {
+    let dir = setup_project();
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "new", "Hand edited", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let brief_id = json["id"].as_str().unwrap();
+    let path = dir.path().join(format!(".shape/briefs/{}.md", brief_id));
+    let original = fs::read_to_string(&path).unwrap();
+    let status_line = original
+        .lines()
+        .position(|l| l.starts_with("status:"))
+        .unwrap()
+        + 1;
+
+    // A misspelled optional key loads with a warning
+    fs::write(&path, original.replacen("---\n", "---\nownr: alice\n", 1)).unwrap();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "show", brief_id])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "line 2: Unknown key `ownr` is kept as metadata (did you mean `owner`?)",
+        ));
+
+    // A bad value fails with its location and the closest valid value
+    fs::write(
+        &path,
+        original.replace("status: proposed", "status: inprogress"),
+    )
+    .unwrap();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "show", brief_id])
+        .assert()
+        .failure()
+        .code(5)
+        .stderr(predicate::str::contains(format!("line {}", status_line)))
+        .stderr(predicate::str::contains("did you mean `in_progress`?"));
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["doctor", "--format", "json"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let finding = &json["diagnostics"][0];
+    assert_eq!(finding["check"], "frontmatter");
+    assert_eq!(finding["line"], status_line);
+    assert!(finding["message"]
+        .as_str()
+        .unwrap()
+        .contains("`status` must be one of"));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["cache", "rebuild"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Warning: skipped"));
+}
+
+#[test]
+fn test_affected_github_annotations() {
+    let dir = setup_project();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "add", "Auth work", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    let task_id = json["id"].as_str().unwrap();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["link", task_id, "--file", "src/auth.rs"])
+        .assert()
+        .success();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "affected",
+            "--files",
+            "src/auth.rs",
+            "src/other.rs",
+            "--format",
+            "github-annotations",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "::notice title=shape affected%3A linked,file=src/auth.rs::Affects {}",
+            task_id
+        )))
+        .stdout(predicate::str::contains(
+            "::notice title=shape affected%3A unlinked,file=src/other.rs::",
+        ));
+}
+
+// =============================================================================
+// Serve Tests
+// =============================================================================
+
+#[test]
+fn test_serve_jsonrpc_stdio_roundtrip() {
+    let dir = setup_project();
+
+    let requests = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"apply","params":{"op":"add_task","title":"From editor"}}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"tasks.ready"}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"tasks.get","params":{}}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"bogus"}"#,
+        r#"{"jsonrpc":"2.0","id":5,"method":"shutdown"}"#,
+        r#"{"jsonrpc":"2.0","id":6,"method":"tasks.ready"}"#,
+    ]
+    .join("\n");
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["serve", "--jsonrpc-stdio"])
+        .write_stdin(requests)
+        .assert()
+        .success();
+
+    let responses: Vec<serde_json::Value> = String::from_utf8_lossy(&output.get_output().stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+
+    // Nothing is processed after shutdown
+    assert_eq!(responses.len(), 5);
+
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[0]["result"]["title"], "From editor");
+    assert_eq!(
+        responses[1]["result"][0]["id"],
+        responses[0]["result"]["id"]
+    );
+    assert_eq!(responses[2]["error"]["code"], -32602);
+    assert_eq!(responses[3]["error"]["code"], -32601);
+    assert!(responses[4]["result"].is_null());
+}
+
+#[test]
+fn test_serve_mcp_tools() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+
+    let call = |id: u32, name: &str, arguments: serde_json::Value| {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments },
+        })
+        .to_string()
+    };
+    let requests = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"0"}}}"#.to_string(),
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#.to_string(),
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#.to_string(),
+        call(3, "shape_ready", serde_json::json!({})),
+        call(4, "shape_claim", serde_json::json!({ "task_id": ids[0], "agent": "mcp-agent" })),
+        call(5, "shape_claim", serde_json::json!({ "task_id": ids[0], "agent": "other" })),
+        call(6, "shape_nope", serde_json::json!({})),
+    ]
+    .join("\n");
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["serve", "--mcp"])
+        .write_stdin(requests)
+        .assert()
+        .success();
+
+    let responses: Vec<serde_json::Value> = String::from_utf8_lossy(&output.get_output().stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    let text = |r: &serde_json::Value| -> serde_json::Value {
+        serde_json::from_str(r["result"]["content"][0]["text"].as_str().unwrap()).unwrap()
+    };
+
+    // The notification gets no response
+    assert_eq!(responses.len(), 6);
+
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2025-03-26");
+    assert_eq!(responses[0]["result"]["serverInfo"]["name"], "shape");
+
+    let tools: Vec<&str> = responses[1]["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    assert!(tools.contains(&"shape_claim"));
+    assert!(tools.contains(&"shape_context"));
+
+    assert_eq!(text(&responses[2])[0]["id"], ids[0].as_str());
+
+    assert_eq!(responses[3]["result"]["isError"], false);
+    assert_eq!(text(&responses[3])["claimed_by"], "mcp-agent");
+
+    // Tool failures are results the model can read, not protocol errors
+    assert_eq!(responses[4]["result"]["isError"], true);
+
+    assert_eq!(responses[5]["error"]["code"], -32602);
+}
+
+#[test]
+fn test_serve_keeps_hook_output_off_stdout() {
+    let dir = setup_project();
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[hooks]\nmention = \"echo HOOK-OUTPUT\"\n");
+    fs::write(&config_path, config).unwrap();
+    let ids = create_brief_with_tasks(&dir, 1);
+
+    let mcp = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#.to_string(),
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "shape_note",
+                "arguments": { "task_id": ids[0], "text": "@alice please look" },
+            },
+        })
+        .to_string(),
+        r#"{"jsonrpc":"2.0","id":3,"method":"ping"}"#.to_string(),
+    ]
+    .join("\n");
+    let jsonrpc = [
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "apply",
+            "params": { "op": "add_note", "id": ids[0], "agent": "ada", "text": "@alice again" },
+        })
+        .to_string(),
+        r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#.to_string(),
+    ]
+    .join("\n");
+
+    for (transport, requests, responses) in [("--mcp", mcp, 3), ("--jsonrpc-stdio", jsonrpc, 2)] {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(["serve", transport])
+            .write_stdin(requests)
+            .assert()
+            .success();
+        let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), responses, "{}", stdout);
+        for line in lines {
+            let message: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(message["jsonrpc"], "2.0");
+            assert!(message.get("error").is_none(), "{}", line);
+        }
+        assert!(String::from_utf8_lossy(&output.get_output().stderr).contains("HOOK-OUTPUT"));
+    }
+}
+
+#[test]
+fn test_serve_mcp_writes_pass_cli_guards() {
+    let dir = setup_project();
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[gates]\ndefault = [\"pr_linked\"]\n");
+    fs::write(&config_path, config).unwrap();
+    let ids = create_brief_with_tasks(&dir, 2);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["freeze", &ids[0], "--reason", "release"])
+        .assert()
+        .success();
+
+    let call = |id: u32, name: &str, task_id: &str| {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": { "task_id": task_id } },
+        })
+        .to_string()
+    };
+    let requests = [
+        call(1, "shape_task_done", &ids[0]),
+        call(2, "shape_task_done", &ids[1]),
+        call(3, "shape_task_start", &ids[1]),
+    ]
+    .join("\n");
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["serve", "--mcp"])
+        .write_stdin(requests)
+        .assert()
+        .success();
+    let responses: Vec<serde_json::Value> = String::from_utf8_lossy(&output.get_output().stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    let text = |r: &serde_json::Value| {
+        r["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+
+    // Frozen and gated tasks are refused as `shape task done` refuses them
+    assert_eq!(responses[0]["result"]["isError"], true);
+    assert!(text(&responses[0]).contains("frozen"));
+    assert_eq!(responses[1]["result"]["isError"], true);
+    assert!(text(&responses[1]).contains("completion gates"));
+    assert_eq!(responses[2]["result"]["isError"], false);
+
+    let status = |id: &str| {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "show", id, "--format", "json"])
+            .assert()
+            .success();
+        let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+        json["status"].as_str().unwrap().to_string()
+    };
+    assert_eq!(status(&ids[0]), "todo");
+    assert_eq!(status(&ids[1]), "in_progress");
+
+    // The start was journaled, so `shape undo` rolls it back
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["undo"])
+        .assert()
+        .success();
+    assert_eq!(status(&ids[1]), "todo");
+}
+
+// =============================================================================
+// Self-update Tests
+// =============================================================================
+
+/// A release feed offering version 99.0.0, whose archive for every platform
+/// holds a stand-in `shape` script. Returns the feed path and the script.
+#[cfg(all(feature = "self-update", target_os = "linux"))]
+fn write_release_feed(dir: &std::path::Path, good_checksum: bool) -> (std::path::PathBuf, String) {
+    let script = "#!/bin/sh\necho updated shape\n".to_string();
+    let build = dir.join("build");
+    fs::create_dir_all(&build).unwrap();
+    fs::write(build.join("shape"), &script).unwrap();
+
+    let archive = dir.join("shape.tar.gz");
+    let status = std::process::Command::new("tar")
+        .arg("-czf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(&build)
+        .arg("shape")
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let sum = if good_checksum {
+        let out = std::process::Command::new("sha256sum")
+            .arg(&archive)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&out.stdout).to_string()
+    } else {
+        format!("{}  shape.tar.gz\n", "0".repeat(64))
+    };
+    let sums = dir.join("shape.tar.gz.sha256");
+    fs::write(&sums, sum).unwrap();
+
+    let assets: Vec<serde_json::Value> = [
+        "linux-x64",
+        "linux-arm64",
+        "linux-x64-musl",
+        "linux-arm64-musl",
+    ]
+    .iter()
+    .flat_map(|p| {
+        [
+            serde_json::json!({
+                "name": format!("shape-{}.tar.gz", p),
+                "browser_download_url": archive,
+            }),
+            serde_json::json!({
+                "name": format!("shape-{}.tar.gz.sha256", p),
+                "browser_download_url": sums,
+            }),
+        ]
+    })
+    .collect();
+    let feed = dir.join("release.json");
+    fs::write(
+        &feed,
+        serde_json::json!({ "tag_name": "v99.0.0", "assets": assets }).to_string(),
+    )
+    .unwrap();
+    (feed, script)
+}
+
+/// A copy of the shape binary the test may replace
+#[cfg(all(feature = "self-update", target_os = "linux"))]
+fn copy_shape_binary(dir: &std::path::Path) -> std::path::PathBuf {
+    let bin = dir.join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let exe = bin.join("shape");
+    fs::copy(assert_cmd::cargo::cargo_bin!("shape"), &exe).unwrap();
+    exe
+}
+
+#[test]
+#[cfg(all(feature = "self-update", target_os = "linux"))]
+fn test_self_update_replaces_binary() {
+    let dir = TempDir::new().unwrap();
+    let (feed, script) = write_release_feed(dir.path(), true);
+    let exe = copy_shape_binary(dir.path());
+
+    let output = assert_cmd::Command::new(&exe)
+        .env("SHAPE_UPDATE_FEED", &feed)
+        .env("XDG_CACHE_HOME", dir.path().join("cache"))
+        .args(["self-update", "--check", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert_eq!(json["latest"], "99.0.0");
+    assert_eq!(json["update_available"], true);
+    assert_eq!(json["updated"], false);
+
+    let output = assert_cmd::Command::new(&exe)
+        .env("SHAPE_UPDATE_FEED", &feed)
+        .env("XDG_CACHE_HOME", dir.path().join("cache"))
+        .args(["self-update", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert_eq!(json["updated"], true);
+    assert_eq!(fs::read_to_string(&exe).unwrap(), script);
+}
+
+#[test]
+#[cfg(all(feature = "self-update", target_os = "linux"))]
+fn test_self_update_rejects_bad_checksum() {
+    let dir = TempDir::new().unwrap();
+    let (feed, _) = write_release_feed(dir.path(), false);
+    let exe = copy_shape_binary(dir.path());
+    let before = fs::read(&exe).unwrap();
+
+    assert_cmd::Command::new(&exe)
+        .env("SHAPE_UPDATE_FEED", &feed)
+        .env("XDG_CACHE_HOME", dir.path().join("cache"))
+        .args(["self-update", "--format", "json"])
+        .assert()
+        .code(13)
+        .stderr(predicate::str::contains("E_UPDATE_FAILED"));
+    assert_eq!(fs::read(&exe).unwrap(), before);
+}
+
+#[test]
+#[cfg(all(feature = "self-update", target_os = "linux"))]
+fn test_status_mentions_new_version_once_checked() {
+    let dir = setup_project();
+    let (feed, _) = write_release_feed(dir.path(), true);
+    let cache = dir.path().join("cache");
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .env_remove("SHAPE_NO_UPDATE_CHECK")
+        .env("SHAPE_UPDATE_FEED", &feed)
+        .env("XDG_CACHE_HOME", &cache)
+        .arg("status")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("99.0.0"));
+
+    // Within the interval the recorded check is used, even with no feed
+    fs::remove_file(&feed).unwrap();
+    shape_cmd()
+        .current_dir(dir.path())
+        .env_remove("SHAPE_NO_UPDATE_CHECK")
+        .env("SHAPE_UPDATE_FEED", &feed)
+        .env("XDG_CACHE_HOME", &cache)
+        .arg("status")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("99.0.0"));
+}
+
+// =============================================================================
+// Field Projection Tests
+// =============================================================================
+
+#[test]
+fn test_fields_and_exclude_project_json_records() {
+    let dir = setup_project();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "add", "Projected task"])
+        .assert()
+        .success();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", "--format", "json", "--fields", "id,title"])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    let task = json[0].as_object().unwrap();
+    assert_eq!(task.len(), 2);
+    assert_eq!(task["title"], "Projected task");
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["ready", "--format", "json", "--exclude", "history,notes"])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert!(json[0].get("title").is_some());
+    assert!(json[0].get("history").is_none());
+}
+
+// =============================================================================
+// Pagination Tests
+// =============================================================================
+
+#[test]
+fn test_task_list_pagination_with_page_token() {
+    let dir = setup_project();
+    create_brief_with_tasks(&dir, 3);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", "--limit", "2", "--format", "json"])
+        .assert()
+        .success();
+    let first: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert_eq!(first["items"].as_array().unwrap().len(), 2);
+    let token = first["next_page_token"].as_str().unwrap();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", "--limit", "2", "--page-token", token])
+        .args(["--format", "json"])
+        .assert()
+        .success();
+    let second: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert_eq!(second["items"].as_array().unwrap().len(), 1);
+    assert!(second["next_page_token"].is_null());
+    assert_ne!(second["items"][0]["id"], first["items"][0]["id"]);
+}
+
+// =============================================================================
+// History Aggregation Tests
+// =============================================================================
+
+#[test]
+fn test_history_all_filters_by_agent_and_event() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["claim", &ids[0], "--agent", "agent-1"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["claim", &ids[1], "--agent", "agent-2"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[1]])
+        .assert()
+        .success();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["history", "--all", "--since", "24h", "--agent", "agent-2"])
+        .args(["--event", "completed", "--format", "json"])
+        .assert()
+        .success();
+    let events: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+
+    let events = events.as_array().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["task"], ids[1].as_str());
+    assert_eq!(events[0]["event"], "completed");
+    assert_eq!(events[0]["by"], "agent-2");
+}
+
+#[test]
+fn test_history_requires_id_or_all() {
+    let dir = setup_project();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["history"])
+        .assert()
+        .failure();
+}
+
+// =============================================================================
+// Milestone Tests
+// =============================================================================
+
+#[test]
+fn test_milestone_progress_from_brief_and_task_targets() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+    let brief_id = ids[0].split('.').next().unwrap().to_string();
+
+    let json_of = |args: &[&str]| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(args)
+            .args(["--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap()
+    };
+
+    let demo = json_of(&[
+        "milestone",
+        "add",
+        "Conference demo",
+        "--date",
+        "2020-03-03",
+    ]);
+    let demo_id = demo["id"].as_str().unwrap().to_string();
+    let launch = json_of(&["milestone", "add", "Launch", "--date", "2999-01-01"]);
+    let launch_id = launch["id"].as_str().unwrap().to_string();
+
+    // The brief's tasks inherit its milestone; one task overrides it
+    json_of(&["milestone", "target", &demo_id, &brief_id]);
+    json_of(&["milestone", "target", &launch_id, &ids[2]]);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[0]])
+        .assert()
+        .success();
+
+    let list = json_of(&["milestone", "list"]);
+    let list = list.as_array().unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0]["id"], demo_id.as_str());
+    assert_eq!(list[0]["tasks"]["total"], 2);
+    assert_eq!(list[0]["tasks"]["done"], 1);
+    assert_eq!(list[0]["state"], "overdue");
+    assert_eq!(list[1]["tasks"]["total"], 1);
+    assert_eq!(list[1]["state"], "upcoming");
+
+    let overdue = json_of(&["milestone", "list", "--overdue"]);
+    assert_eq!(overdue.as_array().unwrap().len(), 1);
+
+    json_of(&["milestone", "remove", &demo_id]);
+    let show = json_of(&["brief", "show", &brief_id]);
+    assert!(show["meta"].get("milestone").is_none());
+}
+
+#[test]
+fn test_milestone_rejects_bad_date_and_unknown_target() {
+    let dir = setup_project();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["milestone", "add", "Demo", "--date", "March 3"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("YYYY-MM-DD"));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["milestone", "target", "m-0000000", "b-1234567"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Milestone not found"));
+}
+
+// =============================================================================
+// Betting Table Tests
+// =============================================================================
+
+#[test]
+fn test_betting_table_moves_winners_and_records_history() {
+    let dir = setup_project();
+    let json_of = |args: &[&str]| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(args)
+            .args(["--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap()
+    };
+    let search = json_of(&["brief", "new", "Search"])["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let billing = json_of(&["brief", "new", "Billing"])["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    json_of(&["bet", "open", "--cycle", "c-1"]);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["bet", "open", "--cycle", "c-2"])
+        .assert()
+        .code(4);
+    json_of(&["bet", "cast", &search, "--by", "alice", "--appetite", "2w"]);
+    json_of(&["bet", "cast", &search, "--by", "bob", "--appetite", "6w"]);
+    json_of(&[
+        "bet",
+        "cast",
+        &billing,
+        "--by",
+        "carol",
+        "--appetite",
+        "1 week",
+    ]);
+
+    let report = json_of(&["bet", "close", "--min-backers", "2"]);
+    assert_eq!(report["open"], false);
+    assert_eq!(report["bets"], 3);
+    assert_eq!(report["briefs"][0]["brief"], search.as_str());
+    assert_eq!(report["briefs"][0]["outcome"], "bet");
+    assert_eq!(report["briefs"][0]["appetite"], "2-weeks");
+    assert_eq!(report["briefs"][1]["outcome"], "passed");
+
+    let shown = json_of(&["brief", "show", &search]);
+    assert_eq!(shown["status"], "betting");
+    assert_eq!(shown["meta"]["appetite"], "2-weeks");
+    assert_eq!(shown["history"][0]["event"], "bet");
+    assert_eq!(shown["history"][0]["data"]["cycle"], "c-1");
+    let shown = json_of(&["brief", "show", &billing]);
+    assert_eq!(shown["status"], "proposed");
+    assert_eq!(shown["history"][0]["event"], "passed");
+
+    // Closed tables stay reportable and take no more bets
+    assert_eq!(
+        json_of(&["bet", "report", "c-1"])["briefs"][0]["outcome"],
+        "bet"
+    );
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["bet", "cast", &billing, "--appetite", "1w"])
+        .assert()
+        .code(5);
+}
+
+// =============================================================================
+// Task Template Tests
+// =============================================================================
+
+#[test]
+fn test_task_from_template_fills_params() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+    let brief_id = ids[0].split('.').next().unwrap().to_string();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "template", "add", "code-review"])
+        .args([
+            "--title",
+            "Review {{pr}}",
+            "--label",
+            "review",
+            "--estimate",
+            "1",
+        ])
+        .assert()
+        .success();
+    assert!(dir
+        .path()
+        .join(".shape/templates/tasks/code-review.toml")
+        .exists());
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "from-template", "code-review", "--brief", &brief_id])
+        .args(["--set", "pr=412", "--format", "json"])
+        .assert()
+        .success();
+    let task: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+
+    assert_eq!(task["id"], format!("{}.2", brief_id));
+    assert_eq!(task["title"], "Review 412");
+    assert_eq!(task["meta"]["labels"], serde_json::json!(["review"]));
+    assert_eq!(task["meta"]["estimate"], 1);
+}
+
+#[test]
+fn test_task_from_template_requires_params() {
+    let dir = setup_project();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "task",
+            "template",
+            "add",
+            "code-review",
+            "--title",
+            "Review {{pr}}",
+        ])
+        .assert()
+        .success();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "from-template", "code-review"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Missing template parameter(s): pr",
+        ));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "from-template", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Task template not found"));
+}
+
+#[test]
+fn test_task_add_uses_label_description_template() {
+    let dir = setup_project();
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str(
+        "\n[description_templates]\nbug = \"## Steps to reproduce\\n\\n## Expected\\n\\n## Actual\\n\"\n",
+    );
+    fs::write(&config_path, config).unwrap();
+
+    let add = |args: &[&str]| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .env("EDITOR", "sed -i s/Expected/Wanted/")
+            .args(["task", "add"])
+            .args(args)
+            .args(["--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+
+    let json = add(&["Crash on login", "--label", "ui", "-l", "bug"]);
+    assert_eq!(
+        json["description"],
+        "## Steps to reproduce\n\n## Expected\n\n## Actual\n"
+    );
+
+    let json = add(&["No scaffold", "--label", "ui"]);
+    assert!(json["description"].is_null());
+
+    let json = add(&["Given", "--label", "bug", "-d", "Already known"]);
+    assert_eq!(json["description"], "Already known");
+
+    // --edit starts from the scaffold and keeps what the editor saved
+    let json = add(&["Edited", "--label", "bug", "--edit"]);
+    assert_eq!(
+        json["description"],
+        "## Steps to reproduce\n\n## Wanted\n\n## Actual"
+    );
+}
+
+// =============================================================================
+// Auto Provenance Tests
+// =============================================================================
+
+#[test]
+fn test_tasks_created_under_claim_link_provenance() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+    let brief_id = ids[0].split('.').next().unwrap().to_string();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "worker")
+        .args(["claim", &ids[0]])
+        .assert()
+        .success();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "worker")
+        .args(["task", "add", &brief_id, "Follow-up", "--format", "json"])
+        .assert()
+        .success();
+    let created: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert_eq!(created["spawned_from"], ids[0].as_str());
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "task",
+            "show",
+            created["id"].as_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success();
+    let task: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert_eq!(task["depends_on"][0]["task"], ids[0].as_str());
+    assert_eq!(task["depends_on"][0]["type"], "provenance");
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let parent: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    let note = parent["notes"][0]["text"].as_str().unwrap();
+    assert!(note.contains(created["id"].as_str().unwrap()));
+
+    // Another agent without a claim creates unlinked tasks
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "someone-else")
+        .args(["task", "add", &brief_id, "Unrelated", "--format", "json"])
+        .assert()
+        .success();
+    let other: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert!(other["spawned_from"].is_null());
+}
+
+// =============================================================================
+// Brief Ownership Tests
+// =============================================================================
+
+#[test]
+fn test_brief_owner_filter_and_ship_enforcement() {
+    let dir = setup_project();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "new", "Owned", "--format", "json"])
+        .assert()
+        .success();
+    let brief: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    let brief_id = brief["id"].as_str().unwrap();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "own", brief_id, "alice", "--co-owner", "bob"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("alice (+bob)"));
+
+    let list = |owner: &str| -> usize {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(["brief", "list", "--owner", owner, "--format", "json"])
+            .assert()
+            .success();
+        let items: serde_json::Value =
+            serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+        items.as_array().unwrap().len()
+    };
+    assert_eq!(list("bob"), 1);
+    assert_eq!(list("carol"), 0);
+
+    let config = dir.path().join(".shape/config.toml");
+    let mut content = std::fs::read_to_string(&config).unwrap_or_default();
+    content.push_str("\n[briefs]\nowner_only_ship = true\n");
+    std::fs::write(&config, content).unwrap();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "carol")
+        .args(["brief", "status", brief_id, "shipped"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Only the owner"));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "bob")
+        .args(["brief", "status", brief_id, "shipped"])
+        .assert()
+        .success();
+}
+
+// =============================================================================
+// Epic Tests
+// =============================================================================
+
+#[test]
+fn test_epics_group_briefs_and_roll_up_progress() {
+    let dir = setup_project();
+    let new_brief = |args: &[&str]| -> String {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(["brief", "new", "--format", "json"])
+            .args(args)
+            .assert()
+            .success();
+        let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+
+    let epic = new_brief(&["Platform revamp"]);
+    let child = new_brief(&["Auth rewrite", "--parent", &epic]);
+    let other = new_brief(&["Billing"]);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "parent", &other, &epic])
+        .assert()
+        .success();
+
+    // One level deep: no epics under briefs that have a parent or children
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "parent", &epic, &child])
+        .assert()
+        .failure();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "new", "Too deep", "--parent", &child])
+        .assert()
+        .failure();
+
+    for (brief, title) in [(&epic, "Plan"), (&child, "Login"), (&other, "Invoices")] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "add", brief, title])
+            .assert()
+            .success();
+    }
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &format!("{}.1", child)])
+        .assert()
+        .success();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "list", "--tree", "--format", "json"])
+        .assert()
+        .success();
+    let tree: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let roots = tree.as_array().unwrap();
+    assert_eq!(roots.len(), 1);
+    assert_eq!(roots[0]["id"], epic.as_str());
+    assert_eq!(
+        roots[0]["progress"],
+        serde_json::json!({"done": 1, "total": 3})
+    );
+    assert_eq!(roots[0]["children"].as_array().unwrap().len(), 2);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["summary", &epic, "--format", "json"])
+        .assert()
+        .success();
+    let summary: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(summary["rollup"]["done"], 1);
+    assert_eq!(summary["rollup"]["total"], 3);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--format", "json"])
+        .assert()
+        .success();
+    let context: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(context["epics"][0]["id"], epic.as_str());
+    let child_entry = context["briefs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|b| b["id"] == child.as_str())
+        .unwrap();
+    assert_eq!(child_entry["parent"], epic.as_str());
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "parent", &other, "--clear"])
+        .assert()
+        .success();
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "show", &other, "--format", "json"])
+        .assert()
+        .success();
+    let shown: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert!(shown["parent"].is_null());
+}
+
+// =============================================================================
+// My Work Tests
+// =============================================================================
+
+#[test]
+fn test_mine_collects_claimed_assigned_watched_and_completed() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 4);
+
+    let run_as = |agent: &str, args: &[&str]| {
+        shape_cmd()
+            .current_dir(dir.path())
+            .env("SHAPE_AGENT", agent)
+            .args(args)
+            .assert()
+            .success();
+    };
+
+    run_as("me", &["claim", &ids[0]]);
+    run_as("other", &["claim", &ids[1]]);
+    run_as("other", &["handoff", &ids[1], "Needs review", "--to", "me"]);
+    run_as("me", &["note", &ids[2], "Looked into this"]);
+    run_as("other", &["note", &ids[2], "Picked it up"]);
+    run_as("me", &["claim", &ids[3]]);
+    run_as("me", &["task", "done", &ids[3]]);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "me")
+        .args(["mine", "--format", "json"])
+        .assert()
+        .success();
+    let mine: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+
+    assert_eq!(mine["agent"], "me");
+    assert_eq!(mine["claimed"][0]["id"], ids[0].as_str());
+    assert_eq!(mine["assigned"][0]["id"], ids[1].as_str());
+    assert_eq!(mine["watching"][0]["id"], ids[2].as_str());
+    assert_eq!(mine["watching"][0]["last_activity"]["by"], "other");
+    assert_eq!(mine["completed"][0]["id"], ids[3].as_str());
+    for section in ["claimed", "assigned", "watching", "completed"] {
+        assert_eq!(mine[section].as_array().unwrap().len(), 1, "{}", section);
+    }
+}
+
+// =============================================================================
+// ID Scheme Tests
+// =============================================================================
+
+#[test]
+fn test_configured_id_scheme_and_migration() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let old_brief = ids[0].split('.').next().unwrap().to_string();
+
+    let config = dir.path().join(".shape/config.toml");
+    let mut content = std::fs::read_to_string(&config).unwrap_or_default();
+    content
+        .push_str("\n[ids]\nhash_length = 10\nslug = true\n\n[ids.prefixes]\nminimal = \"rfc\"\n");
+    std::fs::write(&config, content).unwrap();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "new", "Auth Flow", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    let new_brief = json["id"].as_str().unwrap();
+    assert!(new_brief.starts_with("rfc-"), "{}", new_brief);
+    assert!(new_brief.ends_with("-auth-flow"), "{}", new_brief);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "add", new_brief, "Write spec"])
+        .assert()
+        .success();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["ids", "migrate", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(old_brief.as_str()));
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "show", &old_brief])
+        .assert()
+        .success();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["ids", "migrate"])
+        .assert()
+        .success();
+
+    let migrated_brief = format!("rfc-{}", &old_brief[2..]);
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "list", "--format", "json"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    assert!(!stdout.contains(&format!("\"{}\"", old_brief)));
+    assert!(stdout.contains(&migrated_brief));
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["ready", "--format", "json"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    assert!(stdout.contains(&migrated_brief));
+    assert!(stdout.contains(&format!("{}.1", new_brief)));
+
+    // Already migrated: nothing left to rewrite
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["ids", "migrate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("already match"));
+}
+
+#[test]
+fn test_id_migration_keeps_old_ids_resolving() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["claim", &ids[0], "--agent", "agent-a"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["advanced", "sync", "map", "add", "github", "task"])
+        .args([&ids[1], "77"])
+        .assert()
+        .success();
+
+    let config = dir.path().join(".shape/config.toml");
+    let mut content = std::fs::read_to_string(&config).unwrap_or_default();
+    content.push_str("\n[ids.prefixes]\nminimal = \"rfc\"\n");
+    std::fs::write(&config, content).unwrap();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["ids", "migrate", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let new_id = format!("rfc-{}", &ids[0][2..]);
+    assert_eq!(json["claimed"][0]["old"], ids[0].as_str());
+    assert_eq!(json["claimed"][0]["new"], new_id.as_str());
+    assert_eq!(json["claimed"][0]["claimed_by"], "agent-a");
+
+    // The agent's old ID resolves to the new one
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["id"], new_id.as_str());
+    assert_eq!(json["claimed_by"], "agent-a");
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["unclaim", &ids[0]])
+        .assert()
+        .success();
+
+    // External mappings follow the new ID
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["advanced", "sync", "map", "list", "github", "--broken"])
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(&ids[1]).not());
+    shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "advanced", "sync", "map", "list", "github", "--format", "json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("rfc-{}", &ids[1][2..])));
+}
+
+#[test]
+fn test_id_collisions_are_reported_and_widened() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+    let brief = ids[0].split('.').next().unwrap().to_string();
+    let hash = &brief[2..];
+
+    // A second brief sharing the hash, as if two had collided
+    let briefs = dir.path().join(".shape/briefs");
+    let content = std::fs::read_to_string(briefs.join(format!("{}.md", brief))).unwrap();
+    let twin = format!("rfc-{}", hash);
+    std::fs::write(
+        briefs.join(format!("{}.md", twin)),
+        content.replace(&brief, &twin),
+    )
+    .unwrap();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["doctor"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(format!(
+            "IDs share the hash {}",
+            hash
+        )));
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["ids", "migrate", "--collisions", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let changed = json["changed"].as_array().unwrap();
+    // Both briefs are widened; tasks follow their brief
+    assert_eq!(changed.len(), 2);
+    for change in changed {
+        let new = change["new"].as_str().unwrap();
+        assert!(new.len() > change["old"].as_str().unwrap().len());
+        assert!(new.contains(hash));
+    }
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["doctor"])
+        .assert()
+        .success();
+
+    // The old task ID still resolves to its widened one
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let widened = json["id"].as_str().unwrap();
+    assert_ne!(widened, ids[0]);
+    assert!(widened.starts_with(&brief));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["ids", "migrate", "--collisions"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No ID collisions"));
+}
+
+// =============================================================================
+// External ID Tests
+// =============================================================================
+
+#[test]
+fn test_external_ids_work_as_identifiers() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+    let brief_id = ids[0].split('.').next().unwrap();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "external", &ids[0], "jira:ABC-123"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "external", brief_id, "github:456"])
+        .assert()
+        .success();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", "jira:ABC-123"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(ids[0].as_str()))
+        .stdout(predicate::str::contains("External: jira:ABC-123"));
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "show", "github:456"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(brief_id));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["search", "jira:ABC-123"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(ids[0].as_str()));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", "jira:ABC-123"])
+        .assert()
+        .success();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", "jira:NOPE-1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No brief or task has external ID"));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "external", &ids[0], "jira", "--remove"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", "jira:ABC-123"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_sync_map_manages_mappings() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "advanced", "sync", "map", "add", "github", "task", &ids[0], "1234",
+        ])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", "github:1234", "--short"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(ids[0].as_str()));
+
+    // A remote ID maps to one local ID at a time
+    shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "advanced", "sync", "map", "add", "github", "task", &ids[1], "1234",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already mapped"));
+    shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "advanced",
+            "sync",
+            "map",
+            "add",
+            "github",
+            "task",
+            "b-0000000.9",
+            "9",
+        ])
+        .assert()
+        .failure();
+
+    // Import merges by local ID, including mappings for deleted tasks
+    let import = dir.path().join("mappings.json");
+    fs::write(
+        &import,
+        format!(
+            r#"[{{"local_id":"{}","remote_id":"1235","entity_type":"task","last_sync":"2026-01-01T00:00:00Z"}},
+               {{"local_id":"b-0000000.1","remote_id":"77","entity_type":"task","last_sync":"2026-01-01T00:00:00Z"}}]"#,
+            ids[1]
+        ),
+    )
+    .unwrap();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["advanced", "sync", "map", "import", "github"])
+        .arg(&import)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 total"));
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "advanced", "sync", "map", "list", "--broken", "--format", "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["plugin"], "shape-sync-github");
+    assert_eq!(json[0]["local_id"], "b-0000000.1");
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["advanced", "sync", "map", "remove", "github", "--broken"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("b-0000000.1"));
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["advanced", "sync", "map", "remove", "github", &ids[0]])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", "github:1234"])
+        .assert()
+        .failure();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["advanced", "sync", "map", "export", "github"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["local_id"], ids[1].as_str());
+    assert_eq!(json[0]["remote_id"], "1235");
+}
+
+#[test]
+fn test_config_show_transcodes_to_yaml_and_toml() {
+    let dir = setup_project();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["config", "show", "project"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[context]\n"))
+        .stdout(predicate::str::contains("null").not());
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["config", "show", "project", "--format", "yaml"])
+        .assert()
+        .success();
+    let yaml: serde_yaml::Value = serde_yaml::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(yaml["context_days"], serde_yaml::Value::from(7));
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["config", "show", "--format", "toml"])
+        .assert()
+        .success();
+    let text = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let config: toml::Value = toml::from_str(&text).unwrap();
+    assert_eq!(config["project"]["context_days"].as_integer(), Some(7));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["doctor", "--format", "yaml"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("suite: doctor"));
+}
+
+#[test]
+fn test_unsupported_format_fails() {
+    let dir = setup_project();
+    create_brief_with_tasks(&dir, 1);
+
+    for format in ["yaml", "toml", "toon", "junit", "json-graph", "markdown"] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "list", "--format", format])
+            .assert()
+            .code(5)
+            .stdout("")
+            .stderr(predicate::str::contains(format!(
+                "--format {} is not supported by `shape task list`",
+                format
+            )));
+    }
+
+    // Still accepted where declared
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", "--format", "gfm"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["graph", "export", "--format", "gexf"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_open_resolves_files_and_links() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let brief_id = ids[0].split('.').next().unwrap();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["open", brief_id, "--print"])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(format!(
+            ".shape/briefs/{}.md\n",
+            brief_id
+        )));
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("EDITOR", "true")
+        .args(["open", brief_id])
+        .assert()
+        .success();
+
+    // PR numbers become URLs on the origin remote; PRs beat plain URLs
+    std::process::Command::new("git")
+        .current_dir(dir.path())
+        .args(["init", "-q"])
+        .status()
+        .unwrap();
+    std::process::Command::new("git")
+        .current_dir(dir.path())
+        .args(["remote", "add", "origin", "git@github.com:acme/app.git"])
+        .status()
+        .unwrap();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["link", &ids[0], "--url", "https://example.com/spec"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["link", &ids[0], "--pr", "42"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["open", &ids[0], "--print"])
+        .assert()
+        .success()
+        .stdout("https://github.com/acme/app/pull/42\n");
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("BROWSER", "true")
+        .args(["open", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["type"], "task");
+    assert_eq!(json["target"], "url");
+    assert_eq!(json["opened"], true);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["open", &ids[1]])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("has no pr, url or file link"));
+}
+
+#[test]
+fn test_prompt_renders_preset_with_task_context() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["prompt", "coder", "--task", &ids[1]])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "You are implementing task {}",
+            ids[1]
+        )))
+        .stdout(predicate::str::contains("\"ready\""));
+
+    // Project presets override the built-ins and can use any field
+    let prompts = dir.path().join(".shape/prompts");
+    std::fs::create_dir_all(&prompts).unwrap();
+    std::fs::write(
+        prompts.join("coder.md"),
+        "Do {{task.id}} for {{brief.title}}\n",
+    )
+    .unwrap();
+    std::fs::write(prompts.join("bad.md"), "Do {{task.owner}}\n").unwrap();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["prompt", "coder", "--task", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["preset"], "coder");
+    assert_eq!(json["task"], ids[0].as_str());
+    assert_eq!(
+        json["prompt"],
+        format!("Do {} for Bulk Brief\n", ids[0]).as_str()
+    );
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["prompt", "bad", "--task", &ids[0]])
+        .assert()
+        .code(5)
+        .stderr(predicate::str::contains("{{task.owner}}"))
+        .stderr(predicate::str::contains("task.title"));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["prompt", "--list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bad"))
+        .stdout(predicate::str::contains("reviewer"));
+}
+
+// =============================================================================
+// Ingest Tests
+// =============================================================================
+
+#[test]
+fn test_ingest_email_creates_triage_task() {
+    let dir = setup_project();
+
+    let message = "From: Dana <dana@example.com>\r\n\
+Subject: Export fails on large files\r\n\
+Message-ID: <req-42@example.com>\r\n\
+Content-Type: multipart/mixed; boundary=\"b1\"\r\n\
+\r\n\
+--b1\r\n\
+Content-Type: text/plain\r\n\
+\r\n\
+The CSV export times out above 10MB.\r\n\
+--b1\r\n\
+Content-Type: text/plain\r\n\
+Content-Disposition: attachment; filename=\"trace.log\"\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+dGltZW91dA==\r\n\
+--b1--\r\n";
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["ingest", "email", "--format", "json"])
+        .write_stdin(message)
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+    assert_eq!(json["title"], "Export fails on large files");
+    assert_eq!(json["duplicate"], false);
+
+    let attachment = dir
+        .path()
+        .join(format!(".shape/attachments/{}/trace.log", id));
+    assert_eq!(std::fs::read_to_string(attachment).unwrap(), "timeout");
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &id])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("CSV export times out"))
+        .stdout(predicate::str::contains("dana@example.com"))
+        .stdout(predicate::str::contains("triage"));
+
+    // Re-delivery of the same message doesn't create a second task
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["ingest", "email"])
+        .write_stdin(message)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "Already ingested as {}",
+            id
+        )));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["ingest", "email"])
+        .write_stdin("not an email")
+        .assert()
+        .failure();
+}
+
+// =============================================================================
+// Import Tests
+// =============================================================================
+
+#[test]
+fn test_import_trello_and_linear_map_ids_and_skip_reimports() {
+    let dir = setup_project();
+    let json_of = |args: &[&str]| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(args)
+            .args(["--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+
+    let board = serde_json::json!({
+        "lists": [{ "id": "l1", "name": "Search" }, { "id": "l2", "name": "Done" }],
+        "cards": [
+            { "id": "c1", "name": "Index docs", "desc": "Use tantivy", "idList": "l1" },
+            { "id": "c2", "name": "Ship it", "idList": "l2" }
+        ],
+        "checklists": [{ "idCard": "c1", "checkItems": [
+            { "id": "i1", "name": "Pick analyzer", "state": "complete", "pos": 1 }
+        ] }],
+        "actions": [{ "type": "commentCard", "date": "2024-01-01T00:00:00Z",
+            "data": { "text": "Start with English", "card": { "id": "c1" } },
+            "memberCreator": { "username": "alice" } }]
+    });
+    std::fs::write(dir.path().join("board.json"), board.to_string()).unwrap();
+
+    let preview = json_of(&["import", "trello", "board.json", "--dry-run"]);
+    assert_eq!(preview["created"], 5);
+    assert!(json_of(&["brief", "list"]).as_array().unwrap().is_empty());
+
+    let imported = json_of(&["import", "trello", "board.json"]);
+    let mapping = imported["mapping"].as_array().unwrap();
+    let id_of = |source: &str| {
+        mapping.iter().find(|m| m["source"] == source).unwrap()["id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    let card = id_of("c1");
+    assert_eq!(id_of("i1"), format!("{}.1", card));
+    let task = json_of(&["task", "show", &card]);
+    assert_eq!(task["external_ids"]["trello"], "c1");
+    assert_eq!(task["notes"][0]["by"], "alice");
+    assert_eq!(json_of(&["task", "show", &id_of("c2")])["status"], "done");
+
+    let again = json_of(&["import", "trello", "board.json"]);
+    assert_eq!(again["created"], 0);
+    assert_eq!(again["existing"], 5);
+
+    std::fs::write(
+        dir.path().join("issues.csv"),
+        "ID,Title,Description,Status,Project,Labels,Parent issue\n\
+         ENG-2,Child,,In Progress,Billing,,ENG-1\n\
+         ENG-1,Parent,\"Line one, two\",Todo,Billing,\"bug, p1\",\n",
+    )
+    .unwrap();
+    let brief = card.split('.').next().unwrap();
+    let linear = json_of(&["import", "linear", "issues.csv", "--brief", brief]);
+    assert_eq!(linear["created"], 2);
+    let parent = &linear["mapping"][0];
+    assert_eq!(parent["source"], "ENG-1");
+    assert_eq!(parent["id"], format!("{}.2", brief));
+    assert_eq!(linear["mapping"][1]["id"], format!("{}.2.1", brief));
+    let parent = json_of(&["task", "show", parent["id"].as_str().unwrap()]);
+    assert_eq!(parent["description"], "Line one, two");
+    assert_eq!(parent["meta"]["labels"], serde_json::json!(["bug", "p1"]));
+    assert_eq!(parent["meta"]["linear_project"], "Billing");
+}
+
+/// Serves the issues API from files: curl drops the query string of a
+/// `file://` URL, so every page request reads the same file
+#[cfg(unix)]
+#[test]
+fn test_import_github_issues_fetches_once_and_skips_reimports() {
+    let dir = setup_project();
+    let api = TempDir::new().unwrap();
+    let issues_dir = api.path().join("repos/acme/app");
+    std::fs::create_dir_all(&issues_dir).unwrap();
+    let issues = serde_json::json!([
+        { "number": 12, "title": "Search", "body": "Index docs", "state": "open",
+          "labels": [{ "name": "roadmap" }], "milestone": { "number": 1, "title": "v1" },
+          "html_url": "https://github.com/acme/app/issues/12" },
+        { "number": 13, "title": "Bump deps", "state": "open", "pull_request": {} },
+        { "number": 4, "title": "Login", "state": "closed", "labels": [{ "name": "roadmap" }] }
+    ]);
+    std::fs::write(issues_dir.join("issues"), issues.to_string()).unwrap();
+    let api_url = format!("file://{}", api.path().display());
+
+    let import = |args: &[&str]| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .env("GITHUB_API_URL", &api_url)
+            .env("GITHUB_TOKEN", "test-token")
+            .args(["import", "github-issues", "acme/app", "--label", "roadmap"])
+            .args(args)
+            .args(["--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+
+    let imported = import(&[]);
+    assert_eq!(imported["source"], "github");
+    assert_eq!(imported["created"], 3);
+    let mapping = imported["mapping"].as_array().unwrap();
+    assert_eq!(mapping[0]["source"], "milestone-1");
+    assert_eq!(mapping[1]["source"], "4");
+    assert_eq!(mapping[2]["source"], "12");
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", "github:12", "--format", "json"])
+        .assert()
+        .success();
+    let task: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(task["brief_id"], mapping[0]["id"]);
+    assert_eq!(task["description"], "Index docs");
+    assert_eq!(task["meta"]["github_repo"], "acme/app");
+
+    let again = import(&[]);
+    assert_eq!(again["created"], 0);
+    assert_eq!(again["existing"], 3);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["import", "github-issues", "acme"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("owner/repo"));
+}
+
+// =============================================================================
+// Error Code Tests
+// =============================================================================
+
+#[test]
+fn test_errors_have_codes_and_exit_codes() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+    let missing = format!("{}9", ids[0]);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &missing, "--format", "json"])
+        .assert()
+        .code(3);
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["success"], false);
+    assert_eq!(json["error"]["code"], "E_NOT_FOUND");
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains(&missing));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["claim", &ids[0], "--agent", "agent-a"])
+        .assert()
+        .success();
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["claim", &ids[0], "--agent", "agent-b", "--format", "json"])
+        .assert()
+        .code(4);
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_CLAIM_CONFLICT");
+
+    // Text mode keeps the plain message but the same exit code
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["claim", &ids[0], "--agent", "agent-b"])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::starts_with("Error: Task"));
+
+    let empty = TempDir::new().unwrap();
+    shape_cmd()
+        .current_dir(empty.path())
+        .args(["ready", "--format", "json"])
+        .assert()
+        .code(8)
+        .stderr(predicate::str::contains("E_NOT_IN_PROJECT"));
+}
+
+#[test]
+fn test_lock_timeout_names_holder() {
+    use fs2::FileExt;
+
+    let dir = setup_project();
+    create_brief_with_tasks(&dir, 1);
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[storage]\nlock_timeout_secs = 1\n");
+    fs::write(&config_path, config).unwrap();
+
+    // Another process holds the write lock and has recorded itself
+    let held = fs::File::open(dir.path().join(".shape/tasks.jsonl")).unwrap();
+    held.lock_exclusive().unwrap();
+    fs::write(
+        dir.path().join(".shape/.cache/tasks.lock"),
+        r#"{"pid":4242,"command":"shape sync run","acquired_at":"2026-01-01T00:00:00Z"}"#,
+    )
+    .unwrap();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", "--format", "json"])
+        .assert()
+        .code(6);
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_LOCK_TIMEOUT");
+    let message = json["error"]["message"].as_str().unwrap();
+    assert!(message.contains("PID 4242"));
+    assert!(message.contains("shape sync run"));
+    assert!(message.contains("--wait"));
+
+    // --wait blocks until the lock is released
+    let releaser = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(1500));
+        drop(held);
+    });
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", "--wait"])
+        .assert()
+        .success();
+    releaser.join().unwrap();
+}
+
+// =============================================================================
+// Freeze Tests
+// =============================================================================
+
+#[test]
+fn test_frozen_entities_need_audited_override() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let brief_id = ids[0].split('.').next().unwrap().to_string();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["freeze", &ids[0], "--reason", "security sign-off pending"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Froze task"));
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "start", &ids[0], "--format", "json"])
+        .assert()
+        .code(4);
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_FROZEN");
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("security sign-off pending"));
+
+    // Other tasks are unaffected until the brief itself is frozen
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["note", &ids[1], "still editable"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["freeze", &brief_id, "--reason", "legal review"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["note", &ids[1], "blocked now"])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("legal review"));
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "add", &brief_id, "New task"])
+        .assert()
+        .code(4);
+
+    // Overrides go through and are audited
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "start", &ids[0], "--override", "hotfix approved"])
+        .assert()
+        .success();
+    let audit = fs::read_to_string(dir.path().join(".shape/audit.jsonl")).unwrap();
+    let entry: serde_json::Value = serde_json::from_str(audit.lines().next().unwrap()).unwrap();
+    assert_eq!(entry["id"], ids[0]);
+    assert_eq!(entry["action"], "task start");
+    assert_eq!(entry["reason"], "hotfix approved");
+
+    // Unfreezing lifts the guard
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["unfreeze", &brief_id])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["note", &ids[1], "editable again"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["unfreeze", &brief_id])
+        .assert()
+        .code(4);
+}
+
+// =============================================================================
+// Review Tests
+// =============================================================================
+
+#[test]
+fn test_review_gates_completion_of_labeled_tasks() {
+    let dir = setup_project();
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[reviews]\nrequire_approval = true\n");
+    fs::write(&config_path, config).unwrap();
+
+    let ids = create_brief_with_tasks(&dir, 2);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "meta", &ids[0], "labels", r#"["needs-review"]"#])
+        .assert()
+        .success();
+
+    // Unlabeled tasks complete as usual
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[1]])
+        .assert()
+        .success();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[0], "--format", "json"])
+        .assert()
+        .code(4);
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_REVIEW_REQUIRED");
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["review", "request", &ids[0], "--from", "bob"])
+        .assert()
+        .success();
+
+    // Pending reviews show up for the reviewer and in context
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["mine", "--agent", "bob", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["reviews"][0]["id"], ids[0]);
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["pending_reviews"][0]["reviewer"], "bob");
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "review",
+            "reject",
+            &ids[0],
+            "--by",
+            "bob",
+            "--comment",
+            "Missing tests",
+        ])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[0]])
+        .assert()
+        .code(4);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "review", "approve", &ids[0], "--by", "bob", "--format", "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["approved"], true);
+    assert_eq!(json["reviews"][0]["verdict"], "rejected");
+    assert_eq!(json["reviews"][0]["comment"], "Missing tests");
+    assert_eq!(json["reviews"][1]["verdict"], "approved");
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[0]])
+        .assert()
+        .success();
+}
+
+// =============================================================================
+// Garbage Collection Tests
+// =============================================================================
+
+#[test]
+fn test_gc_finds_and_prunes_orphans() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+    let brief_id = ids[0].split('.').next().unwrap().to_string();
+    let shape_dir = dir.path().join(".shape");
+
+    // Attachments: one for a deleted task, one unlinked file of a live task
+    fs::create_dir_all(shape_dir.join("attachments/b-0000000.1")).unwrap();
+    fs::write(shape_dir.join("attachments/b-0000000.1/log.txt"), "12345").unwrap();
+    fs::create_dir_all(shape_dir.join(format!("attachments/{}", ids[0]))).unwrap();
+    fs::write(
+        shape_dir.join(format!("attachments/{}/stray.txt", ids[0])),
+        "abc",
+    )
+    .unwrap();
+
+    // A sync mapping for a live task and one for a deleted brief
+    fs::create_dir_all(shape_dir.join("sync")).unwrap();
+    fs::write(
+        shape_dir.join("sync/github.jsonl"),
+        format!(
+            "{{\"local_id\":\"{}\",\"remote_id\":\"1\",\"entity_type\":\"task\",\"last_sync\":\"2026-01-01T00:00:00Z\"}}\n\
+             {{\"local_id\":\"b-0000000\",\"remote_id\":\"2\",\"entity_type\":\"brief\",\"last_sync\":\"2026-01-01T00:00:00Z\"}}\n",
+            ids[0]
+        ),
+    )
+    .unwrap();
+
+    // A second brief that is cached, then deleted
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "new", "Doomed"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["cache", "rebuild"])
+        .assert()
+        .success();
+    for entry in fs::read_dir(shape_dir.join("briefs")).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        if name.ends_with(".md") && !name.starts_with(&brief_id) {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["gc", "--scan", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let kinds: Vec<&str> = json["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec!["attachment", "attachment", "sync_mapping", "cache"]
+    );
+    assert_eq!(json["items"][0]["bytes"], 5);
+    assert!(shape_dir.join("attachments/b-0000000.1").exists());
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["gc", "--prune"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pruned 4 item(s)"));
+    assert!(!shape_dir.join("attachments/b-0000000.1").exists());
+    let mappings = fs::read_to_string(shape_dir.join("sync/github.jsonl")).unwrap();
+    assert_eq!(mappings.lines().count(), 1);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["gc"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No garbage found."));
+}
+
+// =============================================================================
+// Ranking Tests
+// =============================================================================
+
+#[test]
+fn test_task_rank_orders_list_and_breaks_next_ties() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+
+    let list_ids = || -> Vec<String> {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "list", "--format", "json"])
+            .assert()
+            .success();
+        let json: serde_json::Value =
+            serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+        json.as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(list_ids(), ids);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "rank", &ids[2], "--before", &ids[0]])
+        .assert()
+        .success();
+    assert_eq!(
+        list_ids(),
+        vec![ids[2].clone(), ids[0].clone(), ids[1].clone()]
+    );
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "rank", &ids[0], "--after", &ids[1]])
+        .assert()
+        .success();
+    assert_eq!(
+        list_ids(),
+        vec![ids[2].clone(), ids[1].clone(), ids[0].clone()]
+    );
+
+    // Equal scores: the top-ranked task is recommended
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["next", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert_eq!(json["recommended"]["id"], ids[2].as_str());
+
+    // Tasks from different briefs cannot be ranked against each other
+    let other = create_brief_with_tasks(&dir, 1);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "rank", &other[0], "--before", &ids[0]])
+        .assert()
+        .failure();
+}
+
+// =============================================================================
+// Graph Export Tests
+// =============================================================================
+
+#[test]
+fn test_graph_export_json_graph_and_gexf() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "dep", &ids[1], &ids[0]])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "meta", &ids[1], "labels", "[\"api\"]"])
+        .assert()
+        .success();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["graph", "export", "--format", "json-graph"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let graph = &json["graph"];
+    assert_eq!(graph["directed"], true);
+    assert_eq!(graph["nodes"].as_object().unwrap().len(), 2);
+    assert_eq!(graph["nodes"][&ids[1]]["metadata"]["labels"][0], "api");
+    assert_eq!(graph["nodes"][&ids[0]]["metadata"]["status"], "todo");
+    assert_eq!(
+        graph["edges"],
+        serde_json::json!([{
+            "source": ids[0],
+            "target": ids[1],
+            "relation": "blocks",
+            "directed": true,
+        }])
+    );
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["graph", "export", "--format", "gexf"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "<gexf xmlns=\"http://gexf.net/1.3\"",
+        ))
+        .stdout(predicate::str::contains(format!(
+            "source=\"{}\" target=\"{}\" label=\"blocks\"",
+            ids[0], ids[1]
+        )));
+}
+
+// =============================================================================
+// Report Tests
+// =============================================================================
+
+#[test]
+fn test_report_wip_orders_by_age_and_flags_stale() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+    for id in [&ids[1], &ids[0]] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["claim", id, "--agent", "alice"])
+            .assert()
+            .success();
+    }
+
+    let report = |args: &[&str]| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(["report", "wip", "--format", "json"])
+            .args(args)
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+
+    let json = report(&[]);
+    assert_eq!(json["stale_after_hours"], 72);
+    let tasks = json["tasks"].as_array().unwrap();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0]["id"], ids[1].as_str());
+    assert_eq!(tasks[0]["claimed_by"], "alice");
+    assert_eq!(tasks[0]["stale"], false);
+
+    assert!(report(&["--stale"])["tasks"].as_array().unwrap().is_empty());
+    let stale = report(&["--stale-after", "0m", "--stale"]);
+    assert_eq!(stale["tasks"].as_array().unwrap().len(), 2);
+    assert_eq!(stale["tasks"][1]["stale"], true);
+}
+
+#[test]
+fn test_report_digest_summarizes_week_and_delivers_via_hook() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+    let json_of = |args: &[&str]| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(args)
+            .args(["--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+
+    let brief = ids[0].split('.').next().unwrap().to_string();
+    json_of(&["brief", "status", &brief, "in_progress"]);
+    json_of(&["task", "done", &ids[0]]);
+    json_of(&["block", &ids[1], "Waiting for API spec"]);
+    let soon = (chrono::Utc::now() + chrono::Duration::days(3))
+        .format("%Y-%m-%d")
+        .to_string();
+    json_of(&["milestone", "add", "Beta", "--date", &soon]);
+
+    let digest = json_of(&["report", "digest", "--weekly"]);
+    assert_eq!(digest["period"]["days"], 7);
+    assert_eq!(digest["completed_tasks"], 1);
+    assert_eq!(digest["progress"][0]["id"], brief.as_str());
+    assert_eq!(digest["progress"][0]["percent"], 33);
+    assert_eq!(digest["newly_blocked"][0]["id"], ids[1].as_str());
+    assert_eq!(digest["newly_blocked"][0]["reason"], "Waiting for API spec");
+    assert_eq!(digest["upcoming"][0]["title"], "Beta");
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["report", "digest"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# Weekly digest"))
+        .stdout(predicate::str::contains("## Newly blocked"));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["report", "digest", "--deliver"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No digest hook"));
+
+    let config = dir.path().join(".shape/config.toml");
+    let mut content = std::fs::read_to_string(&config).unwrap_or_default();
+    content.push_str("\n[hooks]\ndigest = \"cat > digest.json\"\n");
+    std::fs::write(&config, content).unwrap();
+    json_of(&["report", "digest", "--deliver"]);
+
+    let delivered: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(dir.path().join("digest.json")).unwrap()).unwrap();
+    assert_eq!(delivered["event"], "digest");
+    assert_eq!(delivered["digest"]["completed_tasks"], 1);
+    assert!(delivered["markdown"]
+        .as_str()
+        .unwrap()
+        .starts_with("# Weekly digest"));
+}
+
+#[test]
+fn test_report_heatmap_counts_daily_activity() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let json_of = |args: &[&str]| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(args)
+            .args(["--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+
+    json_of(&["claim", &ids[0], "--agent", "alice"]);
+    json_of(&["task", "done", &ids[0]]);
+    json_of(&["task", "done", &ids[1]]);
+
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let all = json_of(&["report", "heatmap"]);
+    assert_eq!(all["metric"], "events");
+    assert_eq!(all["active_days"], 1);
+    assert_eq!(all["days"][0]["date"], today.as_str());
+    assert_eq!(all["busiest"]["date"], today.as_str());
+    assert_eq!(all["longest_streak"], 1);
+    assert!(all["total"].as_u64().unwrap() >= 5);
+
+    let completed = json_of(&["report", "heatmap", "--completed"]);
+    assert_eq!(completed["total"], 2);
+
+    let alice = json_of(&["report", "heatmap", "--agent", "alice"]);
+    assert!(alice["total"].as_u64().unwrap() >= 1);
+    assert!(alice["total"].as_u64().unwrap() < all["total"].as_u64().unwrap());
+
+    let long_ago = json_of(&["report", "heatmap", "--year", "2001"]);
+    assert_eq!(long_ago["total"], 0);
+    assert_eq!(long_ago["busiest"], serde_json::Value::Null);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["report", "heatmap", "--completed"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 task(s) completed in"))
+        .stdout(predicate::str::contains("Mon "))
+        .stdout(predicate::str::contains("Less"));
+}
+
+// =============================================================================
+// Simulation Tests
+// =============================================================================
+
+#[test]
+fn test_simulate_done_reports_without_mutating() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+    // 0 -> 1 -> 2, with a three-day middle task
+    for (task, dep) in [(1, 0), (2, 1)] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "dep", &ids[task], &ids[dep]])
+            .assert()
+            .success();
+    }
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "meta", &ids[1], "estimate", "3"])
+        .assert()
+        .success();
+    let before = fs::read_to_string(dir.path().join(".shape/tasks.jsonl")).unwrap();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["simulate", "done", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+
+    assert_eq!(json["newly_ready"][0]["id"], ids[1].as_str());
+    assert_eq!(json["critical_path"]["before"]["days"], 5);
+    assert_eq!(json["critical_path"]["after"]["days"], 4);
+    assert_eq!(
+        json["critical_path"]["after"]["tasks"],
+        serde_json::json!([ids[1], ids[2]])
+    );
+    let today = chrono::Utc::now().date_naive();
+    assert_eq!(
+        json["finish"]["after"],
+        (today + chrono::Duration::days(4)).to_string()
+    );
+    assert_eq!(json["briefs"].as_array().unwrap().len(), 1);
+
+    // Nothing was written
+    assert_eq!(
+        fs::read_to_string(dir.path().join(".shape/tasks.jsonl")).unwrap(),
+        before
+    );
+}
+
+#[test]
+fn test_dependency_lag_shifts_simulated_schedule() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+    // 1 starts two days after 0 finishes; 2 overlaps 1 by a day
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "dep", &ids[1], &ids[0], "--lag", "2d"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "dep", &ids[2], &ids[1], "--lead", "1d"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "meta", &ids[2], "estimate", "2"])
+        .assert()
+        .success();
+
+    let json_of = |args: &[&str]| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(args)
+            .args(["--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+
+    let show = json_of(&["task", "show", &ids[2]]);
+    assert_eq!(show["depends_on"][0]["lag_days"], -1);
+
+    let json = json_of(&["simulate", "done", &ids[0]]);
+    assert_eq!(json["critical_path"]["before"]["days"], 5);
+    assert_eq!(json["critical_path"]["after"]["days"], 4);
+    assert_eq!(
+        json["critical_path"]["after"]["tasks"],
+        serde_json::json!([ids[1], ids[2]])
+    );
+    // Lags shape the schedule, not readiness
+    assert_eq!(json["newly_ready"][0]["id"], ids[1].as_str());
+
+    // Re-adding the dependency replaces its lag
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "dep", &ids[1], &ids[0]])
+        .assert()
+        .success();
+    let show = json_of(&["task", "show", &ids[1]]);
+    assert_eq!(show["depends_on"][0]["lag_days"], 0);
+}
+
+// =============================================================================
+// Metrics Tests
+// =============================================================================
+
+#[test]
+fn test_metrics_are_opt_in_and_local() {
+    let dir = setup_project();
+    let metrics_path = dir.path().join(".shape/.cache/metrics.jsonl");
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .arg("ready")
+        .assert()
+        .success();
+    assert!(!metrics_path.exists());
+
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = std::fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[metrics]\nenabled = true\n");
+    std::fs::write(&config_path, config).unwrap();
+
+    create_brief_with_tasks(&dir, 1);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", "b-0000000.1"])
+        .assert()
+        .failure();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["metrics", "show", "--command", "task", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["enabled"], true);
+    let commands = json["commands"].as_array().unwrap();
+    let names: Vec<&str> = commands
+        .iter()
+        .map(|c| c["command"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["task add", "task show"]);
+    assert_eq!(commands[1]["failures"], 1);
+    assert!(json["sizes"]["tasks_bytes"].as_u64().unwrap() > 0);
+    assert_eq!(json["sizes"]["briefs"], 1);
+}
+
+#[test]
+fn test_bench_records_and_compares_baseline() {
+    let dir = setup_project();
+    create_brief_with_tasks(&dir, 3);
+    let bench = |args: &[&str]| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(["bench", "--runs", "2", "--format", "json"])
+            .args(args)
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+
+    let first = bench(&[]);
+    assert_eq!(first["tasks"], 3);
+    assert_eq!(first["baseline_saved"], true);
+    let names: Vec<&str> = first["operations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|o| o["operation"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        ["read_all", "cache_rebuild", "ready", "context_export"]
+    );
+    assert!(first["operations"][0].get("baseline_us").is_none());
+    assert!(first["suggestions"].as_array().unwrap().is_empty());
+    assert!(dir.path().join(".shape/.cache/bench.json").is_file());
+
+    let second = bench(&[]);
+    assert_eq!(second["baseline_saved"], false);
+    assert!(second["operations"][0]["baseline_us"].is_u64());
+    assert!(second["operations"][0]["change_pct"].is_i64());
+
+    assert_eq!(bench(&["--save-baseline"])["baseline_saved"], true);
+}
+
+// =============================================================================
+// Format Version Tests
+// =============================================================================
+
+#[test]
+fn test_migrate_upgrades_legacy_project() {
+    let dir = setup_project();
+    create_brief_with_tasks(&dir, 2);
+
+    // Strip the version markers to simulate a project from before versioning
+    let tasks_path = dir.path().join(".shape/tasks.jsonl");
+    let config_path = dir.path().join(".shape/config.toml");
+    let tasks = std::fs::read_to_string(&tasks_path).unwrap();
+    assert!(tasks.starts_with("{\"format_version\":1}\n"));
+    let legacy: String = tasks.lines().skip(1).map(|l| format!("{}\n", l)).collect();
+    std::fs::write(&tasks_path, &legacy).unwrap();
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    std::fs::write(&config_path, config.replace("format_version = 1", "")).unwrap();
+
+    // Legacy projects keep working and stay headerless until migrated
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list"])
+        .assert()
+        .success();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["migrate", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Would migrate from format version 0 to 1",
+        ));
+    assert_eq!(std::fs::read_to_string(&tasks_path).unwrap(), legacy);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .arg("migrate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("v0 -> v1"));
+    let tasks = std::fs::read_to_string(&tasks_path).unwrap();
+    assert!(tasks.starts_with("{\"format_version\":1}\n"));
+    assert_eq!(tasks.lines().count(), 3);
+    assert!(std::fs::read_to_string(&config_path)
+        .unwrap()
+        .contains("format_version = 1"));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .arg("migrate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Already at format version 1"));
+}
+
+#[test]
+fn test_newer_format_version_is_refused() {
+    let dir = setup_project();
+    create_brief_with_tasks(&dir, 1);
+
+    let tasks_path = dir.path().join(".shape/tasks.jsonl");
+    let tasks = std::fs::read_to_string(&tasks_path).unwrap();
+    std::fs::write(
+        &tasks_path,
+        tasks.replace("{\"format_version\":1}", "{\"format_version\":99}"),
+    )
+    .unwrap();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .arg("ready")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "tasks.jsonl uses format version 99",
+        ))
+        .stderr(predicate::str::contains("Upgrade shape"));
+
+    let config_path = dir.path().join(".shape/config.toml");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    std::fs::write(
+        &config_path,
+        config.replace("format_version = 1", "format_version = 99"),
+    )
+    .unwrap();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .arg("status")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "config.toml uses format version 99",
+        ));
+}
+
+// =============================================================================
+// Deterministic Ordering Tests
+// =============================================================================
+
+/// IDs of a JSON array of `{id: ...}` objects (or `"id: title"` strings)
+fn listed_ids(items: &serde_json::Value) -> Vec<String> {
+    items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| match item.as_str() {
+            Some(line) => line.split(':').next().unwrap().to_string(),
+            None => item["id"].as_str().unwrap().to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn test_listings_are_in_canonical_order() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 12);
+    let brief_id = ids[0].split('.').next().unwrap().to_string();
+
+    for title in ["Standalone one", "Standalone two"] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "add", title])
+            .assert()
+            .success();
+    }
+    for blocked in [&ids[11], &ids[3]] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "dep", blocked, &ids[0]])
+            .assert()
+            .success();
+    }
+    for id in [&ids[10], &ids[2]] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["claim", id, "--agent", "agent-1"])
+            .assert()
+            .success();
+    }
+
+    let run = |args: &[&str]| -> serde_json::Value {
+        let mut args = args.to_vec();
+        args.extend(["--format", "json"]);
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(&args)
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+
+    let sorted = |ids: &[String]| {
+        let mut sorted = ids.to_vec();
+        sorted.sort_by_key(|id| id.parse::<shape_cli::TaskId>().unwrap());
+        sorted
+    };
+
+    let ready = run(&["ready"]);
+    let ready_ids = listed_ids(&ready);
+    assert_eq!(ready_ids, sorted(&ready_ids));
+    // Numeric order: .2 sorts before .10 and .11
+    let pos = |id: &str| ready_ids.iter().position(|r| r == id).unwrap();
+    assert!(pos(&ids[1]) < pos(&ids[9]));
+
+    let task_list = run(&["task", "list"]);
+    let listed = listed_ids(&task_list);
+    assert_eq!(listed.len(), 14);
+    assert_eq!(listed, sorted(&listed));
+
+    let blocked = listed_ids(&run(&["blocked"]));
+    assert_eq!(blocked, vec![ids[3].clone(), ids[11].clone()]);
+
+    let brief = run(&["brief", "show", &brief_id]);
+    let brief_tasks = listed_ids(&brief["tasks"]);
+    assert_eq!(brief_tasks, sorted(&ids));
+
+    let context = run(&["context", "--compact"]);
+    assert_eq!(
+        listed_ids(&context["in_progress"]),
+        vec![ids[2].clone(), ids[10].clone()]
+    );
+    for key in ["ready", "in_progress", "blocked"] {
+        let listed = listed_ids(&context[key]);
+        assert_eq!(listed, sorted(&listed), "context {} out of order", key);
+    }
+
+    // Repeated runs produce byte-identical output
+    for args in [
+        &["ready"][..],
+        &["task", "list"],
+        &["blocked"],
+        &["context"],
+        &["brief", "show", &brief_id],
+    ] {
+        assert_eq!(run(args), run(args), "{:?} is not stable", args);
+    }
+}
+
+// =============================================================================
+// Display Tests
+// =============================================================================
+
+#[test]
+fn test_display_icons_and_label_colors() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "meta", &ids[0], "labels", r#"["bug"]"#])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "start", &ids[1]])
+        .assert()
+        .success();
+
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str(
+        "\n[display]\nicons = \"ascii\"\n\n[display.status_icons]\nin_progress = \">>\"\n\n[display.label_colors]\nbug = \"red\"\n",
+    );
+    fs::write(&config_path, config).unwrap();
+
+    let list = |envs: &[(&str, &str)]| -> String {
+        let mut cmd = shape_cmd();
+        cmd.current_dir(dir.path()).args(["task", "list"]);
+        for (key, value) in envs {
+            cmd.env(key, value);
+        }
+        String::from_utf8(cmd.assert().success().get_output().stdout.clone()).unwrap()
+    };
+
+    let text = list(&[]);
+    let row = |text: &str, id: &str| -> String {
+        text.lines()
+            .find(|l| l.contains(id))
+            .unwrap_or_else(|| panic!("{} not listed:\n{}", id, text))
+            .to_string()
+    };
+    assert!(row(&text, &ids[0]).starts_with("[ ] b-"));
+    assert!(row(&text, &ids[0]).ends_with("[bug]"));
+    assert!(row(&text, &ids[1]).starts_with(">>  b-"));
+
+    // SHAPE_ICONS overrides the project setting per user
+    let text = list(&[("SHAPE_ICONS", "none")]);
+    assert!(row(&text, &ids[0]).starts_with(&ids[0]));
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "list"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout);
+    assert!(
+        stdout.lines().any(|l| l.starts_with("[?] b-")),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_plain_output_and_interactive_prompts() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let brief = ids[0].split('.').next().unwrap().to_string();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", "--plain"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout);
+    assert!(stdout.starts_with("2 items\nItem 1 of 2\n"), "{}", stdout);
+    assert!(stdout.contains(&format!("id: {}\n", ids[0])));
+    assert!(!stdout.contains('{') && !stdout.contains("---"));
+
+    // --plain and --format don't mix
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["ready", "--plain", "--format", "json"])
+        .assert()
+        .failure();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .arg("interactive")
+        .write_stdin(format!(
+            "7\n{}\nWrite docs\n6\n{}\ntask show {}\nbogus\nq\n",
+            brief, ids[0], ids[0]
+        ))
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&output.get_output().stdout);
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr);
+    assert!(stdout.contains("7. Add a task"), "{}", stdout);
+    assert!(stdout.contains("title: Write docs"), "{}", stdout);
+    assert!(stdout.contains("status: done"), "{}", stdout);
+    assert!(stdout.ends_with("Goodbye.\n"), "{}", stdout);
+    assert!(
+        stderr.contains("unrecognized subcommand 'bogus'"),
+        "{}",
+        stderr
+    );
+
+    let json = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", "--format", "json"])
+        .assert()
+        .success();
+    let tasks: serde_json::Value = serde_json::from_slice(&json.get_output().stdout).unwrap();
+    assert_eq!(tasks.as_array().unwrap().len(), 3);
+}
+
+// =============================================================================
+// Task Import Tests
+// =============================================================================
+
+#[test]
+fn test_task_import_checklist_resolves_refs() {
+    let dir = setup_project();
+    let existing = create_brief_with_tasks(&dir, 1);
+    let brief = existing[0].split('.').next().unwrap().to_string();
+
+    let plan = format!(
+        "## Plan\n\
+         - [ ] Schema (after: {})\n\
+         - [ ] API (after: schema)\n\
+         \x20 - [ ] Endpoints\n\
+         - [ ] Ship (after: 2)\n",
+        existing[0]
+    );
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "import", "--brief", &brief, "--format", "json"])
+        .write_stdin(plan)
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    let tasks = json["tasks"].as_array().unwrap();
+    let id = |i: usize| tasks[i]["id"].as_str().unwrap().to_string();
+
+    assert_eq!(json["created"], 4);
+    assert_eq!(id(0), format!("{}.2", brief));
+    assert_eq!(id(1), format!("{}.3", brief));
+    assert_eq!(id(2), format!("{}.3.1", brief));
+    assert_eq!(id(3), format!("{}.4", brief));
+    assert_eq!(tasks[0]["depends_on"][0], existing[0].as_str());
+    assert_eq!(tasks[1]["depends_on"][0], id(0).as_str());
+    assert_eq!(tasks[3]["depends_on"][0], id(1).as_str());
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", &brief, "--format", "json"])
+        .assert()
+        .success();
+    let listed: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert_eq!(listed.as_array().unwrap().len(), 5);
+}
+
+#[test]
+fn test_task_import_rejects_cycles_without_writing() {
+    let dir = setup_project();
+    let plan = "{\"title\": \"A\", \"depends_on\": [\"B\"]}\n\
+                {\"title\": \"B\", \"depends_on\": [1]}\n";
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "import", "--format", "json"])
+        .write_stdin(plan)
+        .assert()
+        .code(5)
+        .stderr(predicate::str::contains("E_DEPENDENCY_CYCLE"));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[]"));
+}
+
+// =============================================================================
+// Compliance Tests
+// =============================================================================
+
+#[test]
+fn test_compliance_export_and_verify() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let brief = ids[0].split('.').next().unwrap().to_string();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["review", "approve", &ids[0], "--by", "bob"])
+        .assert()
+        .success();
+
+    let archive = dir.path().join("archive.json");
+    let archive_arg = archive.to_str().unwrap();
+
+    // Signing needs a key unless asked not to
+    shape_cmd()
+        .current_dir(dir.path())
+        .env_remove("SHAPE_COMPLIANCE_KEY")
+        .args(["compliance", "export", "--brief", &brief, "-o", archive_arg])
+        .assert()
+        .code(5);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_COMPLIANCE_KEY", "audit-secret")
+        .args(["compliance", "export", "--brief", &brief, "-o", archive_arg])
+        .args(["--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    assert_eq!(json["tasks"], 2);
+    assert_eq!(json["reviews"], 1);
+    assert_eq!(json["signed"], true);
+
+    let verify = |key: &str| {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .env("SHAPE_COMPLIANCE_KEY", key)
+            .args(["compliance", "verify", archive_arg, "--format", "json"])
+            .output()
+            .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&String::from_utf8_lossy(&output.stdout)).unwrap();
+        (output.status.success(), json)
+    };
+
+    let (ok, json) = verify("audit-secret");
+    assert!(ok);
+    assert_eq!(json["verified"], true);
+
+    let (ok, json) = verify("someone-else");
+    assert!(!ok);
+    assert_eq!(json["intact"], true);
+    assert_eq!(json["signature_valid"], false);
+
+    let content = fs::read_to_string(&archive).unwrap();
+
+    // Stripping the signature and recomputing the digest doesn't pass
+    let mut stripped: serde_json::Value = serde_json::from_str(&content).unwrap();
+    let fields = stripped.as_object_mut().unwrap();
+    fields.remove("signature");
+    fields.remove("digest");
+    let digest = blake3::hash(stripped.to_string().as_bytes());
+    stripped["digest"] = serde_json::json!(digest.to_hex().to_string());
+    stripped["signature"] = serde_json::Value::Null;
+    fs::write(&archive, serde_json::to_string_pretty(&stripped).unwrap()).unwrap();
+    let (ok, json) = verify("audit-secret");
+    assert!(!ok);
+    assert_eq!(json["intact"], true);
+    assert_eq!(json["signed"], false);
+    assert_eq!(json["verified"], false);
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_COMPLIANCE_KEY", "audit-secret")
+        .args(["compliance", "verify", archive_arg, "--allow-unsigned"])
+        .assert()
+        .success();
+
+    fs::write(&archive, content.replacen("Task 1", "Task one", 1)).unwrap();
+    let (ok, json) = verify("audit-secret");
+    assert!(!ok);
+    assert_eq!(json["intact"], false);
+}
+
+// =============================================================================
+// Split Tests
+// =============================================================================
+
+#[test]
+fn test_task_split_rewires_dependencies() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+    let json_of = |args: &[&str]| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(args)
+            .args(["--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+    // 1 <- 2 <- 3
+    json_of(&["task", "dep", &ids[1], &ids[0]]);
+    json_of(&["task", "dep", &ids[2], &ids[1]]);
+    json_of(&["note", &ids[1], "Keep the old endpoint"]);
+
+    let split = json_of(&[
+        "task",
+        "split",
+        &ids[1],
+        "--title",
+        "Schema",
+        "--title",
+        "API",
+        "--sequential",
+    ]);
+    let first = format!("{}.1", ids[1]);
+    let second = format!("{}.2", ids[1]);
+    assert_eq!(split["subtasks"][0]["id"], first.as_str());
+    assert_eq!(split["subtasks"][0]["depends_on"][0], ids[0].as_str());
+    assert_eq!(split["subtasks"][1]["depends_on"][0], first.as_str());
+    assert_eq!(split["rewired"][0], ids[2].as_str());
+
+    let parent = json_of(&["task", "show", &ids[1]]);
+    assert_eq!(parent["meta"]["container"], true);
+    assert_eq!(parent["notes"][0]["text"], "Keep the old endpoint");
+    let dependent = json_of(&["task", "show", &ids[2]]);
+    assert_eq!(dependent["depends_on"][0]["task"], second.as_str());
+
+    // The container waits for its subtasks
+    let blocked = json_of(&["blocked"]);
+    assert!(blocked.to_string().contains(&format!("\"{}\"", ids[1])));
+
+    // Splitting needs two titles
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "split", &ids[0], "--title", "Only one"])
+        .assert()
+        .failure();
+}
+
+// =============================================================================
+// Completion Gate Tests
+// =============================================================================
+
+#[test]
+fn test_task_done_checks_completion_gates() {
+    let dir = setup_project();
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[gates]\ndefault = [\"pr_linked\"]\n\n[gates.labels]\nbug = [\"note\"]\n");
+    fs::write(&config_path, config).unwrap();
+
+    let ids = create_brief_with_tasks(&dir, 2);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "meta", &ids[0], "labels", r#"["bug"]"#])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["link", &ids[0], "--pr", "42"])
+        .assert()
+        .success();
+
+    // The report lists every gate with a hint for the unmet ones
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[0], "--format", "json"])
+        .assert()
+        .code(4);
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_GATES_FAILED");
+    let message = json["error"]["message"].as_str().unwrap();
+    assert!(message.contains("[x] pr_linked"));
+    assert!(message.contains(&format!("[ ] note: shape note {} <TEXT>", ids[0])));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["note", &ids[0], "Fixed the off-by-one"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[0]])
+        .assert()
+        .success();
+
+    // Skipping completes the task and is audited
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[1]])
+        .assert()
+        .code(4);
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "task",
+            "done",
+            &ids[1],
+            "--skip-gates",
+            "hotfix",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["skipped_gates"][0], "pr_linked");
+    let audit = fs::read_to_string(dir.path().join(".shape/audit.jsonl")).unwrap();
+    let entry: serde_json::Value = serde_json::from_str(audit.lines().last().unwrap()).unwrap();
+    assert_eq!(entry["id"], ids[1]);
+    assert_eq!(entry["action"], "task done --skip-gates (pr_linked)");
+    assert_eq!(entry["reason"], "hotfix");
+}
+
+// =============================================================================
+// Translation Tests
+// =============================================================================
+
+#[cfg(unix)]
+#[test]
+fn test_brief_export_translation() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = setup_project();
+    let brief = {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(["brief", "new", "Bulk Brief", "--format", "json"])
+            .assert()
+            .success();
+        let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+
+    // Echoes the request back as the translation, with the title translated
+    let plugin = dir.path().join(".shape/plugins/shape-translate-echo");
+    fs::write(
+        &plugin,
+        "#!/bin/sh\nread -r request\nprintf '%s\\n' \"$request\" | sed -e 's/^{\"operation\":\"translate\",\"params\":\\(.*\\)}$/{\"success\":true,\"data\":\\1}/' -e 's/Bulk Brief/Dossier groupé/g'\n",
+    )
+    .unwrap();
+    fs::set_permissions(&plugin, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let json_of = |args: &[&str]| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(args)
+            .args(["--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+    let exported = json_of(&[
+        "brief",
+        "export",
+        &brief,
+        "--translate",
+        "fr",
+        "--via",
+        "echo",
+    ]);
+    assert_eq!(exported["title"], "Dossier groupé");
+    let path = dir
+        .path()
+        .join(format!(".shape/briefs/i18n/{}.fr.md", brief));
+    assert!(fs::read_to_string(path)
+        .unwrap()
+        .contains("# Dossier groupé"));
+
+    // The canonical brief is untouched; --lang prefers the translation
+    assert_eq!(json_of(&["brief", "show", &brief])["title"], "Bulk Brief");
+    let shown = json_of(&["brief", "show", &brief, "--lang", "fr"]);
+    assert_eq!(shown["title"], "Dossier groupé");
+    assert!(shown["body"].as_str().unwrap().contains("# Dossier groupé"));
+    assert_eq!(shown["translation"]["stale"], false);
+    assert_eq!(shown["translations"][0], "fr");
+
+    // Without a translation the canonical brief is shown
+    let shown = json_of(&["brief", "show", &brief, "--lang", "de"]);
+    assert_eq!(shown["title"], "Bulk Brief");
+    assert!(shown["translation"].is_null());
+
+    // Changing the brief marks the translation stale
+    json_of(&["brief", "status", &brief, "betting"]);
+    let shown = json_of(&["brief", "show", &brief, "--lang", "fr"]);
+    assert_eq!(shown["translation"]["stale"], true);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "brief",
+            "export",
+            &brief,
+            "--translate",
+            "fr",
+            "--via",
+            "missing",
+        ])
+        .assert()
+        .failure();
+}
+
+// =============================================================================
+// Grouped Status Tests
+// =============================================================================
+
+#[test]
+fn test_status_grouped_by_owner_and_label() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let brief = ids[0].split('.').next().unwrap().to_string();
+    let run = |args: &[&str]| {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(args)
+            .assert()
+            .success()
+    };
+    run(&["brief", "own", &brief, "alice"]);
+    run(&["task", "meta", &ids[0], "labels", r#"["ui"]"#]);
+    run(&["task", "done", &ids[0]]);
+
+    let output = run(&["status", "--by", "owner", "--format", "json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["by"], "owner");
+    let alice = &json["groups"][0];
+    assert_eq!(alice["name"], "alice");
+    assert_eq!(alice["tasks"]["total"], 2);
+    assert_eq!(alice["percent_done"], 50);
+    assert_eq!(alice["week_delta"]["done"], 1);
+    assert_eq!(alice["children"][0]["id"], brief.as_str());
+
+    let output = run(&["status", "--by", "label"]);
+    let text = String::from_utf8_lossy(&output.get_output().stdout);
+    assert!(text.contains("Project Status by label"));
+    assert!(text.contains(&format!("└─ {} Bulk Brief (1/1 done)", brief)));
+    assert!(text.contains("(unlabeled)"));
+}
+
+#[test]
+fn test_brief_definition_of_done_gates_its_tasks() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let brief = ids[0].split('.').next().unwrap();
+    let path = dir.path().join(format!(".shape/briefs/{}.md", brief));
+    let content = fs::read_to_string(&path).unwrap().replacen(
+        "\ntitle:",
+        "\ndefinition_of_done:\n- note\n- Docs updated\ntitle:",
+        1,
+    );
+    fs::write(&path, content).unwrap();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[0], "--format", "json"])
+        .assert()
+        .code(4);
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    let message = json["error"]["message"].as_str().unwrap();
+    assert!(message.contains(&format!(
+        "[ ] check:Docs updated: shape task check {} \"Docs updated\"",
+        ids[0]
+    )));
+    assert!(message.contains("[ ] note"));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "check", &ids[0], "docs UPDATED"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Checked \"Docs updated\""));
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "check", &ids[0], "Tests pass"])
+        .assert()
+        .code(5);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["note", &ids[0], "Wrote the docs"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "check", &ids[0]])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[x] note\n[x] check:Docs updated"));
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[0]])
+        .assert()
+        .success();
+
+    // A task can opt out
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "meta", &ids[1], "skip_dod", "true"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[1]])
+        .assert()
+        .success();
+}
+
+// =============================================================================
+// Human-Only Task Tests
+// =============================================================================
+
+#[test]
+fn test_tasks_requiring_a_human_are_kept_from_agents() {
+    let dir = setup_project();
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str(
+        "\n[agent]\nhumans = [\"alice\"]\noperators = [\"lead\"]\nhuman_labels = [\"legal\"]\n",
+    );
+    fs::write(&config_path, config).unwrap();
+
+    let ids = create_brief_with_tasks(&dir, 2);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "meta", &ids[0], "labels", r#"["legal"]"#])
+        .assert()
+        .success();
+
+    // Agents are steered to the other task and can't claim this one
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "bot")
+        .args(["next", "-n", "5", "--format", "json"])
+        .assert()
+        .success();
+    let next = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    assert!(!next.contains(&format!("\"{}\"", ids[0])));
+    assert!(next.contains(&format!("\"{}\"", ids[1])));
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "bot")
+        .args(["claim", &ids[0], "--format", "json"])
+        .assert()
+        .code(4);
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_HUMAN_REQUIRED");
+
+    // Only operators may let an agent take it
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "bot")
+        .args(["claim", &ids[0], "--allow-agent", "routine filing"])
+        .assert()
+        .code(4);
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "lead")
+        .args([
+            "claim",
+            &ids[0],
+            "--agent",
+            "bot",
+            "--allow-agent",
+            "routine filing",
+        ])
+        .assert()
+        .success();
+    let audit = fs::read_to_string(dir.path().join(".shape/audit.jsonl")).unwrap();
+    let entry: serde_json::Value = serde_json::from_str(audit.lines().last().unwrap()).unwrap();
+    assert_eq!(entry["by"], "lead");
+    assert_eq!(entry["action"], "claim --allow-agent (for bot)");
+
+    // Humans claim as usual
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "alice")
+        .args(["claim", &ids[0], "--force", "--reason", "taking it back"])
+        .assert()
+        .success();
+}
+
+// =============================================================================
+// Resource Lock Tests
+// =============================================================================
+
+#[test]
+fn test_resource_locks_steer_next_away_from_locked_scopes() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "meta", &ids[0], "scope", r#"["src/auth"]"#])
+        .assert()
+        .success();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "agent-b")
+        .args(["lock", "acquire", "./src/auth/", "--ttl", "2h"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Locked src/auth as agent-b for 2h",
+        ));
+
+    // The locked task comes last for everyone but the holder
+    let next = |agent: &str| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .env("SHAPE_AGENT", agent)
+            .args(["next", "-n", "5", "--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+    let others = next("agent-a");
+    assert_eq!(others[0]["id"], ids[1].as_str());
+    assert_eq!(others[1]["id"], ids[0].as_str());
+    assert_eq!(others[1]["locked_by"][0]["agent"], "agent-b");
+    assert_eq!(next("agent-b")[0]["id"], ids[0].as_str());
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "agent-a")
+        .args(["lock", "acquire", "src", "--format", "json"])
+        .assert()
+        .code(4);
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_RESOURCE_LOCKED");
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["lock", "list", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json[0]["resource"], "src/auth");
+    assert_eq!(json[0]["agent"], "agent-b");
+    assert_eq!(json[0]["active"], true);
+
+    // With filtering configured the locked task is left out
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[agent]\nlock_conflicts = \"filter\"\n");
+    fs::write(&config_path, config).unwrap();
+    assert_eq!(next("agent-a").as_array().unwrap().len(), 1);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "agent-a")
+        .args(["lock", "release", "src/auth"])
+        .assert()
+        .code(4);
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "agent-b")
+        .args(["lock", "release", "src/auth"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["lock", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No resource locks held."));
+}
+
+// =============================================================================
+// Watch-run
+// =============================================================================
+
+#[cfg(unix)]
+#[test]
+fn test_watch_run_records_command_result_on_task() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["watch-run", &ids[0], "--", "sh", "-c", "echo all good"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("all good"))
+        .stdout(predicate::str::contains("passed; recorded on"));
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "ci")
+        .args([
+            "watch-run",
+            &ids[0],
+            "--format",
+            "json",
+            "--",
+            "sh",
+            "-c",
+            "echo first; echo boom >&2; exit 3",
+        ])
+        .assert()
+        .code(3);
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["status"], "failed");
+    assert_eq!(json["exit_code"], 3);
+    let log = json["log"].as_str().unwrap();
+    let contents = fs::read_to_string(dir.path().join(log)).unwrap();
+    assert!(contents.contains("first") && contents.contains("boom"));
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let task: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(task["meta"]["run_status"], "failed");
+    let links = task["links"].as_array().unwrap();
+    assert_eq!(links.len(), 2);
+    assert_eq!(links[1]["ref"], log);
+    let notes = task["notes"].as_array().unwrap();
+    let last = notes.last().unwrap();
+    assert_eq!(last["by"], "ci");
+    assert!(last["text"].as_str().unwrap().contains("failed (exit 3)"));
+}
+
+// =============================================================================
+// Priority inheritance
+// =============================================================================
+
+#[test]
+fn test_blockers_inherit_priority_in_ready_and_next() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+    // The high-priority first task waits on the unprioritized third
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "meta", &ids[0], "priority", "high"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "dep", &ids[0], &ids[2]])
+        .assert()
+        .success();
+
+    let ready = |dir: &TempDir| -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(["ready", "--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+    let json = ready(&dir);
+    assert_eq!(json[0]["id"], ids[2].as_str());
+    assert_eq!(json[0]["priority"], "high");
+    assert_eq!(json[0]["inherited_from"], ids[0].as_str());
+    assert_eq!(json[1]["id"], ids[1].as_str());
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["next", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["recommended"]["id"], ids[2].as_str());
+    assert_eq!(json["recommended"]["priority"], "high");
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["ready"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "(high priority, blocks {})",
+            ids[0]
+        )));
+
+    // Switched off, ready goes back to ID order
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[agent]\npriority_inheritance = false\n");
+    fs::write(&config_path, config).unwrap();
+    let json = ready(&dir);
+    assert_eq!(json[0]["id"], ids[1].as_str());
+    assert!(json[0].get("priority").is_none());
+}
+
+// =============================================================================
+// GitHub-flavored markdown task lists
+// =============================================================================
+
+#[test]
+fn test_gfm_task_list_round_trips_checkbox_state() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+    let brief_id = ids[0].rsplit_once('.').unwrap().0.to_string();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[0]])
+        .assert()
+        .success();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", &brief_id, "--format", "gfm"])
+        .assert()
+        .success();
+    let list = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+    assert_eq!(
+        list,
+        format!(
+            "- [x] Task 0 <!-- {} -->\n- [ ] Task 1 <!-- {} -->\n- [ ] Task 2 <!-- {} -->\n",
+            ids[0], ids[1], ids[2]
+        )
+    );
+
+    // Ticked and unticked on GitHub, with an item added there
+    let edited = list
+        .replace("- [x] Task 0", "- [ ] Task 0")
+        .replace("- [ ] Task 1", "- [x] Task 1")
+        + "- [ ] Added on GitHub\n";
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["import", "gfm", "-", "--format", "json"])
+        .write_stdin(edited)
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["changed"].as_array().unwrap().len(), 2);
+    assert_eq!(json["unmatched"][0], "Added on GitHub");
+
+    let status = |id: &str| -> String {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "show", id, "--format", "json"])
+            .assert()
+            .success();
+        let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+        json["status"].as_str().unwrap().to_string()
+    };
+    assert_eq!(status(&ids[0]), "todo");
+    assert_eq!(status(&ids[1]), "done");
+    assert_eq!(status(&ids[2]), "todo");
+}
+
+// =============================================================================
+// Encryption at rest
+// =============================================================================
+
+#[test]
+fn test_lock_store_encrypts_tasks_and_briefs() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let shape_dir = dir.path().join(".shape");
+    let brief_file = || {
+        fs::read_dir(shape_dir.join("briefs"))
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .find(|p| p.extension().is_some_and(|e| e == "md"))
+            .unwrap()
+    };
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_PASSPHRASE", "correct horse")
+        .args(["lock-store", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["tasks"], 2);
+    assert_eq!(json["briefs"], 1);
+    assert!(shape_dir.join("encryption.json").is_file());
+    let tasks = fs::read_to_string(shape_dir.join("tasks.jsonl")).unwrap();
+    assert!(!tasks.contains("Task 0"));
+    assert!(tasks.contains("format_version"));
+    assert!(!fs::read_to_string(brief_file())
+        .unwrap()
+        .contains("Bulk Brief"));
+
+    // Transparent with the passphrase, including writes
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_PASSPHRASE", "correct horse")
+        .args(["task", "done", &ids[0]])
+        .assert()
+        .success();
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_PASSPHRASE", "correct horse")
+        .args(["task", "show", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["title"], "Task 0");
+    assert_eq!(json["status"], "done");
+
+    // Locked without it, refused with a wrong one
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env_remove("SHAPE_PASSPHRASE")
+        .args(["task", "show", &ids[1], "--format", "json"])
+        .assert()
+        .code(12);
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr);
+    assert!(stderr.contains("E_STORE_LOCKED"), "{}", stderr);
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_PASSPHRASE", "wrong")
+        .args(["ready", "--format", "json"])
+        .assert()
+        .code(12);
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr);
+    assert!(stderr.contains("E_BAD_PASSPHRASE"), "{}", stderr);
+
+    // Unlocking with the passphrase on stdin restores plaintext
+    shape_cmd()
+        .current_dir(dir.path())
+        .env_remove("SHAPE_PASSPHRASE")
+        .args(["unlock-store"])
+        .write_stdin("correct horse\n")
+        .assert()
+        .success();
+    assert!(!shape_dir.join("encryption.json").exists());
+    assert!(fs::read_to_string(shape_dir.join("tasks.jsonl"))
+        .unwrap()
+        .contains("Task 0"));
+    assert!(fs::read_to_string(brief_file())
+        .unwrap()
+        .contains("Bulk Brief"));
+    shape_cmd()
+        .current_dir(dir.path())
+        .env_remove("SHAPE_PASSPHRASE")
+        .args(["task", "show", &ids[1]])
+        .assert()
+        .success();
+}
+
+// =============================================================================
+// Team directory and @mentions
+// =============================================================================
+
+#[test]
+fn test_team_mentions_are_validated_listed_and_hooked() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+    for args in [
+        vec![
+            "team",
+            "add",
+            "alice",
+            "--name",
+            "Alice Chen",
+            "--alias",
+            "achen",
+        ],
+        vec!["team", "add", "reviewer", "--agent"],
+    ] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(&args)
+            .assert()
+            .success();
+    }
+    let mut config = fs::read_to_string(dir.path().join(".shape/config.toml")).unwrap();
+    config.push_str("\n[hooks]\nmention = \"cat > mention.json\"\n");
+    fs::write(dir.path().join(".shape/config.toml"), config).unwrap();
+
+    // Unknown handles are refused
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["note", &ids[0], "ping @mallory", "--format", "json"])
+        .assert()
+        .code(5);
+    let stderr = String::from_utf8_lossy(&output.get_output().stderr);
+    assert!(stderr.contains("@mallory"), "{}", stderr);
+
+    // Aliases resolve to handles, emails aren't mentions
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "note",
+            &ids[0],
+            "@achen please review, cc ops@example.com",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["mentions"], serde_json::json!(["alice"]));
+    let hook: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("mention.json")).unwrap())
+            .unwrap();
+    assert_eq!(hook["mentions"][0], "alice");
+    assert_eq!(hook["task"]["id"], ids[0].as_str());
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["mine", "--mentions", "--agent", "achen", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["notes"].as_array().unwrap().len(), 1);
+    assert_eq!(json["notes"][0]["task"], ids[0].as_str());
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "mine",
+            "--mentions",
+            "--agent",
+            "reviewer",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert!(json["notes"].as_array().unwrap().is_empty());
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["team", "list", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json[0]["handle"], "alice");
+    assert_eq!(json[1]["kind"], "agent");
+}
+
+// =============================================================================
+// TOON Context Tests
+// =============================================================================
+
+#[test]
+fn test_context_toon_round_trips_to_compact_json() {
+    let dir = setup_project();
+    create_brief_with_tasks(&dir, 40);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--compact", "--format", "json"])
+        .assert()
+        .success();
+    let compact: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--format", "json"])
+        .assert()
+        .success();
+    let full_len = output.get_output().stdout.len();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--format", "toon"])
+        .assert()
+        .success();
+    let toon = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+
+    assert!(toon.starts_with("# toon: "));
+    assert!(toon.contains("\nready[40]: "));
+    assert_eq!(shape_cli::cli::from_toon(&toon).unwrap(), compact);
+    assert!(
+        toon.len() * 10 < full_len * 6,
+        "{} vs {}",
+        toon.len(),
+        full_len
+    );
+}
+
+// =============================================================================
+// Task Edit Tests
+// =============================================================================
+
+#[test]
+fn test_task_edit_with_flags_and_editor() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "task",
+            "edit",
+            &ids[0],
+            "--title",
+            "Build REST API",
+            "--description",
+            "Endpoints for users",
+            "--priority",
+            "high",
+            "--meta",
+            "estimate=3",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["title"], "Build REST API");
+    assert_eq!(json["meta"]["priority"], "high");
+    assert_eq!(json["meta"]["estimate"], 3);
+    assert_eq!(
+        json["changed"],
+        serde_json::json!(["title", "description", "priority", "estimate"])
+    );
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "edit", &ids[0], "--meta", "estimate"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("expected key=value"));
+
+    // The editor sees the task as TOML; drop the estimate and lower the priority
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env(
+            "EDITOR",
+            "sed -i -e /^estimate/d -e s/high/low/ -e s/users/accounts/",
+        )
+        .args(["task", "edit", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["title"], "Build REST API");
+    assert_eq!(json["description"], "Endpoints for accounts");
+    assert_eq!(json["meta"]["priority"], "low");
+    assert!(json["meta"].get("estimate").is_none());
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("EDITOR", "true")
+        .args(["task", "edit", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["changed"], serde_json::json!([]));
+}
+
+// =============================================================================
+// Appetite Budget Tests
+// =============================================================================
+
+#[test]
+fn test_appetite_budget_warns_once_when_exceeded() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+    let brief_id = ids[0].split('.').next().unwrap().to_string();
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[hooks]\nappetite_warning = \"cat >> appetite.log\"\n");
+    fs::write(&config_path, config).unwrap();
+
+    for args in [
+        vec!["bet", "open", "--cycle", "c-1"],
+        vec![
+            "bet",
+            "cast",
+            &brief_id,
+            "--by",
+            "alice",
+            "--appetite",
+            "1w",
+        ],
+        vec!["bet", "close", "--start"],
+    ] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(&args)
+            .assert()
+            .success();
+    }
+
+    let summary = || -> serde_json::Value {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(["summary", &brief_id, "--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_slice(&output.get_output().stdout).unwrap()
+    };
+    let json = summary();
+    assert_eq!(json["appetite"]["appetite"], "1-week");
+    assert_eq!(json["appetite"]["budget_days"], 7);
+    assert_eq!(json["appetite"]["exceeded"], false);
+    assert!(!dir.path().join("appetite.log").exists());
+
+    // Pretend work started ten days ago
+    let brief_path = dir.path().join(format!(".shape/briefs/{}.md", brief_id));
+    let content = fs::read_to_string(&brief_path).unwrap();
+    let started = (chrono::Utc::now() - chrono::Duration::days(10)).to_rfc3339();
+    let content: String = content
+        .lines()
+        .map(|l| {
+            if l.starts_with("started_at:") {
+                format!("started_at: {}\n", started)
+            } else {
+                format!("{}\n", l)
+            }
+        })
+        .collect();
+    fs::write(&brief_path, content).unwrap();
+
+    let json = summary();
+    assert_eq!(json["appetite"]["exceeded"], true);
+    assert_eq!(json["appetite"]["remaining_days"], -3);
+    summary();
+    let log = fs::read_to_string(dir.path().join("appetite.log")).unwrap();
+    assert_eq!(log.matches("\"warn_percent\":100").count(), 1);
+    assert!(log.contains(&brief_id));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["doctor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 days over its 1-week appetite"));
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "show", &brief_id, "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let events: Vec<_> = json["history"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["event"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(events, vec!["bet", "appetite_warning"]);
+}
+
+// =============================================================================
+// Transaction Tests
+// =============================================================================
+
+/// Runs `shape tx begin` and returns the session token
+fn begin_tx(dir: &TempDir) -> String {
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["tx", "begin", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    json["token"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_tx_stages_changes_until_commit() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+    let brief_id = ids[0].split('.').next().unwrap().to_string();
+    let tasks_path = dir.path().join(".shape/tasks.jsonl");
+    let before = fs::read_to_string(&tasks_path).unwrap();
+
+    let token = begin_tx(&dir);
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_TX", &token)
+        .args(["task", "add", &brief_id, "Second"])
+        .assert()
+        .success();
+    let second = format!("{}.2", brief_id);
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_TX", &token)
+        .args(["task", "dep", &second, &ids[0]])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_TX", &token)
+        .args(["claim", &ids[0], "--agent", "ada"])
+        .assert()
+        .success();
+
+    // Later commands see the staged state; the live store is untouched
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_TX", &token)
+        .args(["task", "show", &second])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&tasks_path).unwrap(), before);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_TX", &token)
+        .args(["tx", "commit", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["tasks_changed"], true);
+
+    let after = fs::read_to_string(&tasks_path).unwrap();
+    assert!(after.contains("Second"));
+    assert!(after.contains("ada"));
+    assert!(!dir.path().join(".shape/.cache/tx").exists());
+}
+
+#[test]
+fn test_tx_abort_and_conflict() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+    let brief_id = ids[0].split('.').next().unwrap().to_string();
+    let tasks_path = dir.path().join(".shape/tasks.jsonl");
+    let before = fs::read_to_string(&tasks_path).unwrap();
+
+    let token = begin_tx(&dir);
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_TX", &token)
+        .args(["task", "add", &brief_id, "Discarded"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_TX", &token)
+        .args(["tx", "abort"])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&tasks_path).unwrap(), before);
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_TX", &token)
+        .args(["task", "show", &format!("{}.2", brief_id)])
+        .assert()
+        .failure();
+
+    // A write outside the transaction blocks its commit
+    let token = begin_tx(&dir);
+    fs::write(&tasks_path, before.replacen("Task ", "Renamed task ", 1)).unwrap();
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_TX", &token)
+        .args(["tx", "commit", "--format", "json"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_CONFLICT");
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_TX", &token)
+        .args(["tx", "status", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["open"], true);
+}
+
+#[test]
+fn test_tx_belongs_to_its_owner() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let tasks_path = dir.path().join(".shape/tasks.jsonl");
+
+    let token = begin_tx(&dir);
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_TX", &token)
+        .args(["claim", &ids[0], "--agent", "ada"])
+        .assert()
+        .success();
+
+    // Another agent reads the live store and can't write while the session
+    // is open, so it neither claims into staging nor claims twice
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "other")
+        .args(["claim", &ids[1], "--format", "json"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_CONFLICT");
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "other")
+        .args(["claim", &ids[0]])
+        .assert()
+        .failure();
+    assert!(!fs::read_to_string(&tasks_path).unwrap().contains("ada"));
+
+    // Only the owner closes the session
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["tx", "abort", "--format", "json"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_CONFLICT");
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["tx", "status", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["open"], true);
+    assert_eq!(json["owned"], false);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_TX", &token)
+        .args(["tx", "commit"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(&tasks_path).unwrap().contains("ada"));
+    shape_cmd()
+        .current_dir(dir.path())
+        .env("SHAPE_AGENT", "other")
+        .args(["claim", &ids[1]])
+        .assert()
+        .success();
+
+    // A session whose token was lost can still be discarded
+    begin_tx(&dir);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["tx", "abort", "--force"])
+        .assert()
+        .success();
+    assert!(!dir.path().join(".shape/.cache/tx").exists());
+}
+
+// =============================================================================
+// Due Date Tests
+// =============================================================================
+
+#[test]
+fn test_due_dates_surface_in_overdue_next_and_context() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "due", &ids[1], "2020-01-01"])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "due", &ids[2], "2d"])
+        .assert()
+        .success();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["overdue", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let items = json.as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["id"], ids[1].as_str());
+    assert_eq!(items[0]["due_at"], "2020-01-01T23:59:59Z");
+    assert_eq!(items[0]["overdue"], true);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["overdue", "--within", "3d", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 2);
+    assert_eq!(json[1]["overdue"], false);
+
+    // The overdue task outranks otherwise equal ones
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["next", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["recommended"]["id"], ids[1].as_str());
+    assert_eq!(json["recommended"]["overdue"], true);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--compact"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let due = json["due"].as_array().unwrap();
+    assert_eq!(due.len(), 2);
+    assert!(due[0]
+        .as_str()
+        .unwrap()
+        .ends_with("(overdue since 2020-01-01)"));
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "due", &ids[1], "--clear"])
+        .assert()
+        .success();
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["overdue", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert!(json.as_array().unwrap().is_empty());
+}
+
+// =============================================================================
+// Brief Archive Tests
+// =============================================================================
+
+#[test]
+fn test_brief_archive_requires_cascade_for_open_tasks() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let brief_id = ids[0].split('.').next().unwrap().to_string();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "archive", &brief_id, "--format", "json"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_VALIDATION");
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "brief",
+            "archive",
+            &brief_id,
+            "--cascade",
+            "block",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["status"], "archived");
+    assert_eq!(json["tasks"].as_array().unwrap().len(), 2);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert!(json["blocked"]["reason"]
+        .as_str()
+        .unwrap()
+        .contains(&brief_id));
+    let last = json["history"].as_array().unwrap().last().unwrap().clone();
+    assert_eq!(last["event"], "brief_archived");
+    assert_eq!(last["data"]["cascade"], "block");
+}
+
+#[test]
+fn test_brief_archive_orphans_open_tasks() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let brief_id = ids[0].split('.').next().unwrap().to_string();
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[0]])
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "add", "Follow-up"])
+        .assert()
+        .success();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "brief",
+            "archive",
+            &brief_id,
+            "--cascade",
+            "orphan",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let moved = json["tasks"].as_array().unwrap();
+    assert_eq!(moved.len(), 1);
+    assert_eq!(moved[0]["was"], ids[1].as_str());
+    let new_id = moved[0]["id"].as_str().unwrap().to_string();
+    assert!(!new_id.starts_with(&brief_id));
+
+    // The new ID is standalone, and the old one still resolves to it
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[1], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["id"], new_id.as_str());
+    assert_eq!(json["standalone"], true);
+    assert_eq!(json["status"], "todo");
+    let last = json["history"].as_array().unwrap().last().unwrap().clone();
+    assert_eq!(last["data"]["from"], ids[1].as_str());
+
+    // Done tasks stay with the brief
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[0], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["id"], ids[0].as_str());
+}
+
+// =============================================================================
+// Guarded reopen
+// =============================================================================
+
+#[test]
+fn test_task_reopen_requires_reason_and_done_task() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "reopen", &ids[0]])
+        .assert()
+        .failure();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "task", "reopen", &ids[0], "--reason", "flaky", "--format", "json",
+        ])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_CONFLICT");
+}
+
+#[test]
+fn test_task_reopen_cascades_to_selected_dependents() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+    for dependent in [&ids[1], &ids[2]] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "dep", dependent, &ids[0]])
+            .assert()
+            .success();
+    }
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "done", &ids[0], &ids[1], &ids[2]])
+        .assert()
+        .success();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "task",
+            "reopen",
+            &ids[0],
+            "--reason",
+            "Breaks on empty input",
+            "--dependents",
+            &ids[1],
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["status"], "todo");
+    assert_eq!(json["reopened"], serde_json::json!([ids[1]]));
+    assert_eq!(json["stale"], serde_json::json!([ids[2]]));
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "show", &ids[1], "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["status"], "todo");
+    let last = json["history"].as_array().unwrap().last().unwrap().clone();
+    assert_eq!(last["event"], "reopened");
+    assert_eq!(last["data"]["cause"], ids[0].as_str());
+
+    // Not downstream of the reopened task
+    shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "task",
+            "reopen",
+            &ids[2],
+            "--reason",
+            "x",
+            "--dependents",
+            &ids[0],
+        ])
+        .assert()
+        .failure();
+}
+
+// =============================================================================
+// Archiving shipped briefs
+// =============================================================================
+
+fn ship_brief(dir: &TempDir, ids: &[String]) -> String {
+    let brief_id = ids[0].split('.').next().unwrap().to_string();
+    let mut args = vec!["task", "done"];
+    args.extend(ids.iter().map(String::as_str));
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(&args)
+        .assert()
+        .success();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "status", &brief_id, "shipped"])
+        .assert()
+        .success();
+    brief_id
+}
+
+#[test]
+fn test_maintenance_archive_shipped_moves_brief_and_tasks() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let brief_id = ship_brief(&dir, &ids);
+
+    // Needs an age from the flag or the config
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["maintenance", "archive-shipped"])
+        .assert()
+        .failure();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "maintenance",
+            "archive-shipped",
+            "--older-than",
+            "0d",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["archived"][0]["id"], brief_id.as_str());
+    assert_eq!(json["archived"][0]["tasks"], 2);
+    assert!(dir
+        .path()
+        .join(format!(".shape/archive/briefs/{}.md", brief_id))
+        .is_file());
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "list", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert!(json.as_array().unwrap().is_empty());
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "list", "--include-archived", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json[0]["id"], brief_id.as_str());
+    assert_eq!(json[0]["archived"], true);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "list", "--include-archived", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 2);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["search", "Task", "--include-archived", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let items = json.as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert!(items
+        .iter()
+        .all(|i| i["type"] == "task" && i["archived"] == true));
+}
+
+#[test]
+fn test_maintenance_archive_shipped_keeps_index_out_of_git_in_older_projects() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+    let brief_id = ship_brief(&dir, &ids);
+    let shape_dir = dir.path().join(".shape");
+    let title = {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(["brief", "show", &brief_id, "--format", "json"])
+            .assert()
+            .success();
+        let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+        json["title"].as_str().unwrap().to_string()
+    };
+
+    // A project from before the archive: no ignore line, and an index left
+    // next to the archived briefs by an older version
+    let gitignore = shape_dir.join(".gitignore");
+    let old = fs::read_to_string(&gitignore)
+        .unwrap()
+        .replace("archive/briefs/index.jsonl\n", "");
+    fs::write(&gitignore, old).unwrap();
+    let legacy_index = shape_dir.join("archive/briefs/index.jsonl");
+    fs::create_dir_all(legacy_index.parent().unwrap()).unwrap();
+    fs::write(&legacy_index, format!("{{\"title\":\"{}\"}}\n", title)).unwrap();
+
+    for args in [
+        &["lock-store"][..],
+        &["maintenance", "archive-shipped", "--older-than", "0d"],
+        &["brief", "list", "--include-archived"],
+    ] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .env("SHAPE_PASSPHRASE", "correct horse")
+            .args(args)
+            .assert()
+            .success();
+    }
+
+    assert!(!legacy_index.exists());
+    assert!(fs::read_to_string(&gitignore)
+        .unwrap()
+        .lines()
+        .any(|l| l == "archive/briefs/index.jsonl"));
+    // Nothing committed from the archive shows the title in plaintext
+    for entry in fs::read_dir(shape_dir.join("archive/briefs")).unwrap() {
+        let content = fs::read_to_string(entry.unwrap().path()).unwrap();
+        assert!(!content.contains(&title));
+    }
+}
+
+#[test]
+fn test_maintenance_archive_shipped_keeps_depended_on_briefs() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 1);
+    let brief_id = ship_brief(&dir, &ids);
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "add", "Follow-up", "--format", "json"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let follow_up = json["id"].as_str().unwrap().to_string();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "dep", &follow_up, &ids[0]])
+        .assert()
+        .success();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "maintenance",
+            "archive-shipped",
+            "--older-than",
+            "0d",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert!(json["archived"].as_array().unwrap().is_empty());
+    assert_eq!(json["skipped"][0]["id"], brief_id.as_str());
+    assert!(json["skipped"][0]["reason"]
+        .as_str()
+        .unwrap()
+        .contains(&follow_up));
+}
+
+// =============================================================================
+// Dependency cycles
+// =============================================================================
+
+#[test]
+fn test_dep_cycle_shows_path_and_suggests_links() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+    for (task, dep) in [(&ids[1], &ids[0]), (&ids[2], &ids[1])] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "dep", task, dep])
+            .assert()
+            .success();
+    }
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "dep", &ids[0], &ids[2], "--format", "json"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_DEPENDENCY_CYCLE");
+    let message = json["error"]["message"].as_str().unwrap();
+    let cycle = format!("{0} → {2} → {1} → {0}", ids[0], ids[1], ids[2]);
+    assert!(message.contains(&cycle), "{}", message);
+    assert!(message.contains("--related"));
+
+    // The suggested link goes through
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "dep", &ids[0], &ids[2], "--related"])
+        .assert()
+        .success();
+}
+
+// =============================================================================
+// Interest-scoped ready queues
+// =============================================================================
+
+#[test]
+fn test_next_prefers_interests_and_skips_disclaimed_labels() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 3);
+    for (id, label) in [(&ids[1], "frontend"), (&ids[2], "db")] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "meta", id, "labels", &format!("[\"{}\"]", label)])
+            .assert()
+            .success();
+    }
+
+    let next = |args: &[&str]| -> Vec<String> {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .env("SHAPE_AGENT", "db-bot")
+            .args(["next", "-n", "5", "--format", "json"])
+            .args(args)
+            .assert()
+            .success();
+        let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+        json.as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let picked = next(&["--interests", "db,-frontend"]);
+    assert_eq!(picked, vec![ids[2].clone(), ids[0].clone()]);
+
+    // Without flags the profile comes from the team directory
+    shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "team",
+            "add",
+            "db-bot",
+            "--agent",
+            "--interests",
+            "-frontend,db",
+        ])
+        .assert()
+        .success();
+    assert_eq!(next(&[]), picked);
+    assert_eq!(next(&["--interests", "frontend"])[0], ids[1]);
+}
+
+// =============================================================================
+// Ready watch
+// =============================================================================
+
+#[test]
+fn test_ready_watch_streams_changes() {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["task", "dep", &ids[1], &ids[0]])
+        .assert()
+        .success();
+
+    let mut child = Command::new(assert_cmd::cargo::cargo_bin!("shape"))
+        .current_dir(dir.path())
+        .args(["ready", "--watch", "--format", "json"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let (tx, rx) = mpsc::channel();
+    let stdout = child.stdout.take().unwrap();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let json: serde_json::Value = serde_json::from_str(&line.unwrap()).unwrap();
+            if tx.send(json).is_err() {
+                break;
+            }
+        }
+    });
+    let next_event = || rx.recv_timeout(Duration::from_secs(10));
+
+    let snapshot = next_event();
+    let changed = snapshot.as_ref().ok().map(|_| {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "done", &ids[0]])
+            .assert()
+            .success();
+        next_event()
+    });
+    child.kill().unwrap();
+    let _ = child.wait();
+
+    let snapshot = snapshot.expect("snapshot event");
+    assert_eq!(snapshot["event"], "snapshot");
+    assert_eq!(snapshot["added"], serde_json::json!([ids[0]]));
+
+    let changed = changed.unwrap().expect("change event");
+    assert_eq!(changed["event"], "changed");
+    assert_eq!(changed["added"], serde_json::json!([ids[1]]));
+    assert_eq!(changed["removed"], serde_json::json!([ids[0]]));
+    assert_eq!(changed["ready"][0]["id"], ids[1].as_str());
+}
+
+// =============================================================================
+// Undo Tests
+// =============================================================================
+
+#[test]
+fn test_undo_and_redo_replay_the_journal() {
+    let dir = setup_project();
+    let ids = create_brief_with_tasks(&dir, 2);
+    let run = |args: &[&str]| {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(args)
+            .assert()
+            .success()
+    };
+    let status = |id: &str| -> serde_json::Value {
+        let output = run(&["task", "show", id, "--format", "json"]);
+        let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+        json["status"].clone()
+    };
+
+    run(&["task", "done", &ids[0]]);
+    run(&["task", "start", &ids[1]]);
+    // Read-only commands aren't journaled
+    run(&["task", "list"]);
+
+    let output = run(&["undo", "--format", "json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["undone"][0]["command"], "task start");
+    assert_eq!(json["undone"][0]["tasks"], serde_json::json!([ids[1]]));
+    assert_eq!(status(&ids[1]), "todo");
+
+    run(&["redo"]).stdout(predicate::str::contains("Redid `shape task start`"));
+    assert_eq!(status(&ids[1]), "in_progress");
+
+    // A task changed outside the journal can't be rolled back over
+    run(&["freeze", &ids[0], "--reason", "release"]);
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["undo", "--last", "2", "--format", "json"])
+        .assert()
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&output.get_output().stderr).unwrap();
+    assert_eq!(json["error"]["code"], "E_CONFLICT");
+    assert_eq!(status(&ids[1]), "todo");
+    assert_eq!(status(&ids[0]), "done");
+
+    // A new change drops what was undone
+    run(&["note", &ids[1], "picking this up later"]);
+    shape_cmd()
+        .current_dir(dir.path())
+        .arg("redo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Nothing to redo"));
+}
@@ -555,3 +555,352 @@ fn test_empty_project_context() {
     assert_eq!(json["in_progress"].as_array().unwrap().len(), 0);
     assert_eq!(json["recently_done"].as_array().unwrap().len(), 0);
 }
+
+#[test]
+fn test_compact_format_uses_brief_summary_not_body() {
+    let dir = TempDir::new().unwrap();
+    shape_cmd().arg("init").arg(dir.path()).assert().success();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "new", "Summarized", "--format", "json"])
+        .assert()
+        .success();
+    let json: Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+    let brief_id = json["id"].as_str().unwrap().to_string();
+
+    // Hand-edit the body; the summary must follow the new body
+    let path = dir.path().join(format!(".shape/briefs/{}.md", brief_id));
+    let content = std::fs::read_to_string(&path).unwrap();
+    let content = format!(
+        "{}\nSearch is slow for large projects. Results arrive after seconds. We index nothing. Long detail follows.\n",
+        content
+    );
+    std::fs::write(&path, content).unwrap();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--compact"])
+        .assert()
+        .success();
+    let json: Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+
+    let brief = &json["briefs"][0];
+    assert_eq!(
+        brief["summary"],
+        "Search is slow for large projects. Results arrive after seconds. We index nothing."
+    );
+    assert!(
+        brief.get("body").is_none(),
+        "Compact format must not include bodies"
+    );
+}
+
+#[test]
+fn test_context_for_task_selects_dependency_closure() {
+    let dir = TempDir::new().unwrap();
+    shape_cmd().arg("init").arg(dir.path()).assert().success();
+
+    let new_id = |args: &[&str]| -> String {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .args(args)
+            .args(["--format", "json"])
+            .assert()
+            .success();
+        serde_json::from_str::<Value>(&String::from_utf8_lossy(&output.get_output().stdout))
+            .unwrap()["id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+
+    let brief = new_id(&["brief", "new", "Focus Brief"]);
+    let other_brief = new_id(&["brief", "new", "Other Brief"]);
+    let upstream = new_id(&["task", "add", "Upstream"]);
+    let design = new_id(&["task", "add", &brief, "Design"]);
+    let build = new_id(&["task", "add", &brief, "Build"]);
+    let unrelated = new_id(&["task", "add", &other_brief, "Unrelated"]);
+
+    for (task, dep) in [(&build, &design), (&design, &upstream)] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["task", "dep", task, dep])
+            .assert()
+            .success();
+    }
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--for-task", &build])
+        .assert()
+        .success();
+    let json: Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+
+    let briefs = json["briefs"].as_array().unwrap();
+    assert_eq!(briefs.len(), 1);
+    assert_eq!(briefs[0]["id"], brief.as_str());
+
+    let text = json.to_string();
+    assert!(text.contains(&build));
+    assert!(text.contains(&design));
+    assert!(text.contains(&upstream), "blocking ancestors are included");
+    assert!(!text.contains(&unrelated), "other briefs are excluded");
+
+    // The upstream standalone task is the only ready one
+    let ready = json["tasks"]["ready"].as_array().unwrap();
+    assert_eq!(ready.len(), 1);
+    assert_eq!(ready[0]["id"], upstream.as_str());
+}
+
+// =============================================================================
+// Schema Version Compatibility Tests
+// =============================================================================
+
+/// Sorted top-level keys and keys of every brief for a context export
+fn context_keys(dir: &TempDir, args: &[&str]) -> (Vec<String>, Vec<Vec<String>>) {
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .arg("context")
+        .args(args)
+        .assert()
+        .success();
+    let json: Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+
+    let keys = |value: &Value| -> Vec<String> {
+        let mut keys: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    };
+    let briefs = json["briefs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(keys)
+        .collect();
+    (keys(&json), briefs)
+}
+
+/// The test project plus an epic, so every version-2 field is present
+fn setup_schema_test_project() -> TempDir {
+    let dir = setup_context_test_project();
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "new", "Auth Epic", "--format", "json"])
+        .assert()
+        .success();
+    let epic = serde_json::from_str::<Value>(&String::from_utf8_lossy(&output.get_output().stdout))
+        .unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["brief", "new", "Login", "--parent", &epic])
+        .assert()
+        .success();
+    dir
+}
+
+#[test]
+fn test_context_reports_schema_version() {
+    let dir = setup_context_test_project();
+
+    for (args, version) in [
+        (&["--compact"][..], 3),
+        (&[][..], 3),
+        (&["--schema-version", "2"][..], 2),
+    ] {
+        let output = shape_cmd()
+            .current_dir(dir.path())
+            .arg("context")
+            .args(args)
+            .assert()
+            .success();
+        let json: Value =
+            serde_json::from_str(&String::from_utf8_lossy(&output.get_output().stdout)).unwrap();
+        assert_eq!(json["context_schema_version"], version, "args: {:?}", args);
+    }
+}
+
+#[test]
+fn test_schema_version_1_full_structure() {
+    let dir = setup_schema_test_project();
+
+    let (top, briefs) = context_keys(&dir, &["--schema-version", "1"]);
+    assert_eq!(top, ["briefs", "standalone_tasks", "summary", "tasks"]);
+    for brief in briefs {
+        assert_eq!(brief, ["body", "id", "meta", "status", "title", "type"]);
+    }
+}
+
+#[test]
+fn test_schema_version_1_compact_structure() {
+    let dir = setup_schema_test_project();
+
+    let (top, briefs) = context_keys(&dir, &["--compact", "--schema-version", "1"]);
+    assert_eq!(
+        top,
+        [
+            "blocked",
+            "briefs",
+            "compacted",
+            "in_progress",
+            "ready",
+            "recently_done",
+            "standalone_tasks"
+        ]
+    );
+    for brief in briefs {
+        assert_eq!(brief, ["id", "status", "title"]);
+    }
+}
+
+#[test]
+fn test_schema_version_2_structure() {
+    let dir = setup_schema_test_project();
+
+    let (top, briefs) = context_keys(&dir, &["--compact"]);
+    for key in ["context_schema_version", "epics", "etag", "revision"] {
+        assert!(top.iter().any(|k| k == key), "Missing '{}' key", key);
+    }
+    assert!(briefs.iter().any(|b| b.iter().any(|k| k == "parent")));
+}
+
+#[test]
+fn test_schema_version_2_has_no_due_dates() {
+    let dir = setup_schema_test_project();
+
+    let (top, _) = context_keys(&dir, &["--compact"]);
+    assert!(top.iter().any(|k| k == "due"));
+    let (top, _) = context_keys(&dir, &["--compact", "--schema-version", "2"]);
+    assert!(!top.iter().any(|k| k == "due"));
+}
+
+#[test]
+fn test_unsupported_schema_version_is_rejected() {
+    let dir = setup_context_test_project();
+
+    for version in ["0", "4"] {
+        shape_cmd()
+            .current_dir(dir.path())
+            .args(["context", "--schema-version", version])
+            .assert()
+            .code(5);
+    }
+
+    // Version 1 predates etags
+    shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--schema-version", "1", "--etag", "abc"])
+        .assert()
+        .code(5);
+}
+
+// =============================================================================
+// Markdown Format
+// =============================================================================
+
+#[test]
+fn test_markdown_format_sections() {
+    let dir = setup_context_test_project();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--format", "markdown"])
+        .assert()
+        .success();
+    let md = String::from_utf8_lossy(&output.get_output().stdout).to_string();
+
+    assert!(md.starts_with("# Project context\n\n1 ready, 1 blocked, 1 in progress\n"));
+    assert!(md.contains("\n## Briefs\n\n### b-"));
+    assert!(md.contains(": Test Anchor (proposed)"));
+    for (heading, title) in [
+        ("## In progress", "In Progress Task"),
+        ("## Ready", "Ready Task"),
+        ("## Blocked", "Blocked Task"),
+        ("## Recently completed", "Completed Task"),
+    ] {
+        let section = md
+            .split(heading)
+            .nth(1)
+            .unwrap_or_else(|| panic!("{heading}"));
+        let first = section.split("\n## ").next().unwrap();
+        assert!(first.contains(title), "{heading} should list {title}");
+    }
+    assert!(md.contains(", waiting on `b-"));
+
+    // The body's own title heading isn't repeated
+    assert!(!md.contains("# Test Anchor\n"));
+    assert!(!md.starts_with('{'));
+}
+
+#[test]
+fn test_max_tokens_drops_old_work_first() {
+    let dir = setup_context_test_project();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--format", "json"])
+        .assert()
+        .success();
+    let full: Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let size = serde_json::to_string(&full).unwrap().len() / 4;
+
+    // Room for everything: nothing dropped
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--format", "json", "--max-tokens", "100000"])
+        .assert()
+        .success();
+    let json: Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["budget"]["fits"], true);
+    assert_eq!(json["budget"]["truncated"], serde_json::json!([]));
+    assert_eq!(json["tasks"], full["tasks"]);
+
+    // Slightly too small: only the completed work goes
+    let max = (size - 5).to_string();
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args(["context", "--format", "json", "--max-tokens", &max])
+        .assert()
+        .success();
+    let json: Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    let truncated = json["budget"]["truncated"].as_array().unwrap();
+    assert_eq!(truncated[0]["section"], "recently_completed");
+    assert_eq!(truncated[0]["dropped"], 1);
+    assert_eq!(json["tasks"]["recently_completed"], serde_json::json!([]));
+    assert_eq!(json["tasks"]["ready"][0]["title"], "Ready Task");
+    assert!(json["budget"]["estimated_tokens"].as_u64().unwrap() <= size as u64);
+}
+
+#[test]
+fn test_max_tokens_reports_when_it_cannot_fit() {
+    let dir = setup_context_test_project();
+
+    let output = shape_cmd()
+        .current_dir(dir.path())
+        .args([
+            "context",
+            "--compact",
+            "--format",
+            "json",
+            "--max-tokens",
+            "10",
+        ])
+        .assert()
+        .success();
+    let json: Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+    assert_eq!(json["budget"]["fits"], false);
+    // Open work is never dropped
+    assert!(json["ready"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|t| t.as_str().unwrap().ends_with("Ready Task")));
+    assert!(String::from_utf8_lossy(&output.get_output().stderr).contains("--max-tokens 10"));
+}
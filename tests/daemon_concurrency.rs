@@ -0,0 +1,283 @@
+//! Daemon and CLI interplay under concurrent writes
+//!
+//! Spawns the auto-commit daemon against a real git repository while several
+//! CLI processes write at once, then checks that no write was lost and that
+//! every commit the daemon made contains a well-formed task store.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+/// Concurrent CLI writers, each with its own brief
+const WRITERS: usize = 4;
+
+/// Tasks each writer adds
+const TASKS_PER_WRITER: usize = 5;
+
+fn shape_bin() -> std::path::PathBuf {
+    assert_cmd::cargo::cargo_bin!("shape").to_path_buf()
+}
+
+fn shape(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(shape_bin())
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "shape {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {:?} failed", args);
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// A git repository with a shape project whose daemon commits quickly
+fn setup_repo() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["config", "user.email", "test@example.com"]);
+    git(dir.path(), &["config", "user.name", "Test"]);
+    shape(dir.path(), &["init"]);
+
+    let config_path = dir.path().join(".shape/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[daemon]\ndebounce_seconds = 1\n");
+    fs::write(&config_path, config).unwrap();
+
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "init"]);
+    dir
+}
+
+/// Stops the daemon when the test ends, even on failure
+struct Daemon(Child);
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn start_daemon(dir: &Path) -> Daemon {
+    let child = Command::new(shape_bin())
+        .current_dir(dir)
+        .args(["daemon", "start", "--foreground", "--quiet"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let daemon = Daemon(child);
+
+    let log = dir.join(".shape/daemon.log");
+    wait_until(Duration::from_secs(10), || {
+        fs::read_to_string(&log).is_ok_and(|l| l.contains("Daemon ready"))
+    });
+    daemon
+}
+
+fn wait_until(limit: Duration, mut done: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !done() {
+        assert!(start.elapsed() < limit, "timed out after {:?}", limit);
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Task IDs in a tasks.jsonl snapshot, failing on any malformed line
+fn task_ids(content: &str) -> Vec<String> {
+    assert!(
+        content.is_empty() || content.ends_with('\n'),
+        "task store ends mid-line"
+    );
+    content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| serde_json::from_str::<Value>(l).expect("malformed task line"))
+        .filter_map(|v| v["id"].as_str().map(str::to_string))
+        .collect()
+}
+
+#[test]
+fn test_daemon_commits_are_consistent_under_concurrent_writers() {
+    let dir = setup_repo();
+    let briefs: Vec<String> = (0..WRITERS)
+        .map(|i| {
+            let out = shape(
+                dir.path(),
+                &["brief", "new", &format!("Stream {}", i), "--format", "json"],
+            );
+            serde_json::from_str::<Value>(&out).unwrap()["id"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+
+    let _daemon = start_daemon(dir.path());
+
+    let writers: Vec<_> = briefs
+        .iter()
+        .cloned()
+        .map(|brief| {
+            let root = dir.path().to_path_buf();
+            thread::spawn(move || {
+                for i in 0..TASKS_PER_WRITER {
+                    shape(
+                        &root,
+                        &["task", "add", &brief, &format!("Task {}", i), "--wait"],
+                    );
+                    // Readers contend for the same lock
+                    shape(&root, &["ready", "--format", "json", "--wait"]);
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    // No write was lost on disk
+    let expected = WRITERS * TASKS_PER_WRITER;
+    let on_disk = task_ids(&fs::read_to_string(dir.path().join(".shape/tasks.jsonl")).unwrap());
+    assert_eq!(on_disk.len(), expected);
+    assert_eq!(on_disk.iter().collect::<HashSet<_>>().len(), expected);
+
+    // The daemon eventually commits the final state
+    wait_until(Duration::from_secs(30), || {
+        let committed = Command::new("git")
+            .current_dir(dir.path())
+            .args(["show", "HEAD:.shape/tasks.jsonl"])
+            .output()
+            .unwrap();
+        committed.status.success()
+            && task_ids(&String::from_utf8_lossy(&committed.stdout)).len() == expected
+    });
+
+    // Every intermediate commit holds a complete, parseable store
+    let commits = git(
+        dir.path(),
+        &["log", "--format=%H", "--", ".shape/tasks.jsonl"],
+    );
+    for commit in commits.lines() {
+        let content = git(
+            dir.path(),
+            &["show", &format!("{}:.shape/tasks.jsonl", commit)],
+        );
+        let ids = task_ids(&content);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+    }
+
+    let log = fs::read_to_string(dir.path().join(".shape/daemon.log")).unwrap();
+    assert!(!log.contains("Commit failed"), "daemon log:\n{}", log);
+}
+
+#[test]
+fn test_daemon_defers_commit_while_store_is_locked() {
+    use fs2::FileExt;
+
+    let dir = setup_repo();
+    let out = shape(dir.path(), &["brief", "new", "Locked", "--format", "json"]);
+    let brief = serde_json::from_str::<Value>(&out).unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    shape(dir.path(), &["task", "add", &brief, "First"]);
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "seed"]);
+    let head = git(dir.path(), &["rev-parse", "HEAD"]);
+
+    let _daemon = start_daemon(dir.path());
+
+    // A writer holds the store lock in the middle of an operation
+    let held = fs::File::open(dir.path().join(".shape/tasks.jsonl")).unwrap();
+    held.lock_exclusive().unwrap();
+    let brief_path = dir.path().join(format!(".shape/briefs/{}.md", brief));
+    let mut content = fs::read_to_string(&brief_path).unwrap();
+    content.push_str("\nMore detail.\n");
+    fs::write(&brief_path, content).unwrap();
+
+    let log_path = dir.path().join(".shape/daemon.log");
+    wait_until(Duration::from_secs(10), || {
+        fs::read_to_string(&log_path).is_ok_and(|l| l.contains("retrying commit"))
+    });
+    assert_eq!(git(dir.path(), &["rev-parse", "HEAD"]), head);
+
+    // Once the writer finishes the daemon commits
+    drop(held);
+    wait_until(Duration::from_secs(10), || {
+        git(dir.path(), &["rev-parse", "HEAD"]) != head
+    });
+}
+
+#[test]
+fn test_daemon_commits_fenced_operation_once_with_journal_message() {
+    let dir = setup_repo();
+    let out = shape(dir.path(), &["brief", "new", "Fenced", "--format", "json"]);
+    let brief = serde_json::from_str::<Value>(&out).unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "seed"]);
+    let head = git(dir.path(), &["rev-parse", "HEAD"]);
+
+    let _daemon = start_daemon(dir.path());
+
+    // A running operation (this test process) holds the fence
+    let fences = dir.path().join(".shape/.cache/fences");
+    fs::create_dir_all(&fences).unwrap();
+    let fence = fences.join(format!("{}.json", std::process::id()));
+    let holder = serde_json::json!({
+        "pid": std::process::id(),
+        "operation": "task import",
+        "started_at": "2026-01-01T00:00:00Z",
+    });
+    fs::write(&fence, holder.to_string()).unwrap();
+
+    shape(dir.path(), &["task", "add", &brief, "First"]);
+    shape(dir.path(), &["task", "add", &brief, "Second"]);
+    let log_path = dir.path().join(".shape/daemon.log");
+    wait_until(Duration::from_secs(10), || {
+        fs::read_to_string(&log_path).is_ok_and(|l| l.contains("Holding commit until"))
+    });
+    thread::sleep(Duration::from_millis(1500));
+    assert_eq!(git(dir.path(), &["rev-parse", "HEAD"]), head);
+
+    // Lowering the fence lets both writes land in one commit, described by
+    // the journal
+    fs::remove_file(&fence).unwrap();
+    wait_until(Duration::from_secs(10), || {
+        git(dir.path(), &["rev-parse", "HEAD"]) != head
+    });
+    let commits = git(
+        dir.path(),
+        &["log", "--format=%s", &format!("{}..HEAD", head.trim())],
+    );
+    assert_eq!(commits.lines().count(), 1, "commits:\n{}", commits);
+    assert!(
+        commits.starts_with(&format!("shape: task add {}.1, {}.2", brief, brief)),
+        "commits:\n{}",
+        commits
+    );
+    let committed = git(dir.path(), &["show", "--name-only", "--format=", "HEAD"]);
+    assert!(!committed.contains("journal.jsonl"));
+}